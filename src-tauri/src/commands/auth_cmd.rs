@@ -1,23 +1,28 @@
 // src-tauri/src/commands/auth_cmd.rs
-use crate::domain::auth::{Account, DeviceCodeResponse, McProfile, WardrobeSkinLibrary};
+use crate::domain::auth::{
+    Account, AccountStoreStatus, AccountSummary, AddAccountRequest, AuthlibLoginChallenge,
+    DeviceCodeResponse, McProfile, MinecraftLoginError, ProfileNameStatus, WardrobeSkinLibrary,
+};
 use crate::services::auth as auth_service;
+use crate::services::auth::account_store::{self, AccountStore};
 
 // 核心修复 1：引入 tauri 的 AppHandle 和 Runtime
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Manager, Runtime};
 
 #[tauri::command]
 pub async fn request_microsoft_device_code() -> Result<DeviceCodeResponse, String> {
     auth_service::request_device_code().await
 }
 
-// 核心修复 2：统一使用 <R: Runtime> 泛型，并接收 app: AppHandle<R>
+/// 轮询设备码完成登录，账号直接写入后端账号库，前端只拿到摘要
 #[tauri::command]
 pub async fn poll_and_exchange_microsoft_token<R: Runtime>(
     app: AppHandle<R>,
     device_code: String,
     interval: u64,
-) -> Result<Account, String> {
-    auth_service::poll_and_exchange_token(&app, &device_code, interval).await
+) -> Result<AccountSummary, MinecraftLoginError> {
+    let account = auth_service::poll_and_exchange_token(&app, &device_code, interval).await?;
+    Ok(app.state::<AccountStore>().upsert(&app, account)?)
 }
 
 #[tauri::command]
@@ -51,22 +56,34 @@ pub fn delete_offline_account_dir<R: Runtime>(
     auth_service::delete_offline_account_dir(&app, &uuid)
 }
 
-// 新增：为前端提供刷新 Token 的命令
+/// 刷新账号库中微软账号的令牌，刷新令牌只在后端使用
 #[tauri::command]
 pub async fn refresh_microsoft_token<R: Runtime>(
     app: AppHandle<R>,
-    refresh_token: String,
-) -> Result<Account, String> {
-    auth_service::refresh_microsoft_token(&app, &refresh_token).await
+    id: String,
+) -> Result<AccountSummary, String> {
+    account_store::refresh_account(&app, &id).await
 }
 
+/// 皮肤站两步登录：先认证并返回角色列表，令牌暂存在后端
 #[tauri::command]
-pub async fn login_authlib(
+pub async fn start_authlib_login(
     api_root: String,
     username: String,
     password: String,
-) -> Result<Account, String> {
-    auth_service::login_authlib(&api_root, &username, &password).await
+) -> Result<AuthlibLoginChallenge, String> {
+    auth_service::authlib::start_authlib_login(&api_root, &username, &password).await
+}
+
+/// 用户选定角色后完成登录，账号直接写入后端账号库
+#[tauri::command]
+pub async fn finish_authlib_login<R: Runtime>(
+    app: AppHandle<R>,
+    ticket: String,
+    profile_id: String,
+) -> Result<AccountSummary, String> {
+    let account = auth_service::authlib::finish_authlib_login(&ticket, &profile_id).await?;
+    app.state::<AccountStore>().upsert(&app, account)
 }
 
 // =======================================================
@@ -94,44 +111,51 @@ pub async fn ensure_account_skin<R: tauri::Runtime>(
         .map(|p| p.to_string_lossy().to_string())
 }
 
+// 衣柜的在线操作按账号 id 在后端取令牌，令牌不经过前端
 #[tauri::command]
 pub async fn get_wardrobe_profile<R: Runtime>(
     app: AppHandle<R>,
-    access_token: String,
-    account_uuid: String,
+    account_id: String,
 ) -> Result<McProfile, String> {
-    auth_service::get_wardrobe_profile(&app, &access_token, &account_uuid).await
+    let account = account_store::resolve_account(&app, Some(&account_id)).await?;
+    auth_service::get_wardrobe_profile(&app, &account.access_token, &account.uuid).await
 }
 
 #[tauri::command]
 pub async fn apply_wardrobe_skin<R: Runtime>(
     app: AppHandle<R>,
-    access_token: String,
-    account_uuid: String,
+    account_id: String,
     source_path: String,
     variant: String,
 ) -> Result<McProfile, String> {
-    auth_service::apply_wardrobe_skin(&app, &access_token, &account_uuid, &source_path, &variant)
-        .await
+    let account = account_store::resolve_account(&app, Some(&account_id)).await?;
+    auth_service::apply_wardrobe_skin(
+        &app,
+        &account.access_token,
+        &account.uuid,
+        &source_path,
+        &variant,
+    )
+    .await
 }
 
 #[tauri::command]
 pub async fn set_active_cape<R: Runtime>(
     app: AppHandle<R>,
-    access_token: String,
-    account_uuid: String,
+    account_id: String,
     cape_id: String,
 ) -> Result<McProfile, String> {
-    auth_service::set_active_cape(&app, &access_token, &account_uuid, &cape_id).await
+    let account = account_store::resolve_account(&app, Some(&account_id)).await?;
+    auth_service::set_active_cape(&app, &account.access_token, &account.uuid, &cape_id).await
 }
 
 #[tauri::command]
 pub async fn clear_active_cape<R: Runtime>(
     app: AppHandle<R>,
-    access_token: String,
-    account_uuid: String,
+    account_id: String,
 ) -> Result<McProfile, String> {
-    auth_service::clear_active_cape(&app, &access_token, &account_uuid).await
+    let account = account_store::resolve_account(&app, Some(&account_id)).await?;
+    auth_service::clear_active_cape(&app, &account.access_token, &account.uuid).await
 }
 
 #[tauri::command]
@@ -183,10 +207,88 @@ pub fn set_active_wardrobe_skin_offline<R: Runtime>(
 #[tauri::command]
 pub async fn update_active_wardrobe_skin_variant<R: Runtime>(
     app: AppHandle<R>,
-    access_token: String,
-    account_uuid: String,
+    account_id: String,
     variant: String,
 ) -> Result<McProfile, String> {
-    auth_service::update_active_wardrobe_skin_variant(&app, &access_token, &account_uuid, &variant)
-        .await
+    let account = account_store::resolve_account(&app, Some(&account_id)).await?;
+    auth_service::update_active_wardrobe_skin_variant(
+        &app,
+        &account.access_token,
+        &account.uuid,
+        &variant,
+    )
+    .await
+}
+
+// =======================================================
+// 后端加密账号库：令牌只保存在 Rust 侧，前端只拿账号摘要
+// =======================================================
+#[tauri::command]
+pub fn get_account_store_status<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<AccountStoreStatus, String> {
+    app.state::<AccountStore>().status(&app)
+}
+
+#[tauri::command]
+pub fn unlock_account_store<R: Runtime>(
+    app: AppHandle<R>,
+    passphrase: String,
+) -> Result<AccountStoreStatus, String> {
+    app.state::<AccountStore>()
+        .unlock_with_passphrase(&app, &passphrase)
+}
+
+#[tauri::command]
+pub fn list_accounts<R: Runtime>(app: AppHandle<R>) -> Result<Vec<AccountSummary>, String> {
+    app.state::<AccountStore>().list(&app)
+}
+
+#[tauri::command]
+pub async fn add_account<R: Runtime>(
+    app: AppHandle<R>,
+    request: AddAccountRequest,
+) -> Result<AccountSummary, MinecraftLoginError> {
+    account_store::add_account(&app, request).await
+}
+
+#[tauri::command]
+pub fn remove_account<R: Runtime>(app: AppHandle<R>, id: String) -> Result<(), String> {
+    app.state::<AccountStore>().remove(&app, &id)
+}
+
+#[tauri::command]
+pub fn select_account<R: Runtime>(app: AppHandle<R>, id: String) -> Result<AccountSummary, String> {
+    app.state::<AccountStore>().select(&app, &id)
+}
+
+/// 一次性迁移前端旧版持久化的账号，迁移后前端应清除本地令牌
+#[tauri::command]
+pub fn import_frontend_accounts<R: Runtime>(
+    app: AppHandle<R>,
+    accounts: Vec<Account>,
+    selected_id: Option<String>,
+) -> Result<Vec<AccountSummary>, String> {
+    app.state::<AccountStore>()
+        .import(&app, accounts, selected_id)
+}
+
+/// 登录返回 noProfile 时，凭 ticket 查询角色名是否可用
+#[tauri::command]
+pub async fn check_minecraft_profile_name(
+    ticket: String,
+    name: String,
+) -> Result<ProfileNameStatus, String> {
+    auth_service::ownership::check_profile_name(&ticket, &name).await
+}
+
+/// 创建 Minecraft 角色并把账号写入后端账号库
+#[tauri::command]
+pub async fn create_minecraft_profile<R: Runtime>(
+    app: AppHandle<R>,
+    ticket: String,
+    name: String,
+) -> Result<AccountSummary, String> {
+    let account = auth_service::create_minecraft_profile(&app, &ticket, &name).await?;
+    app.state::<AccountStore>().upsert(&app, account)
 }
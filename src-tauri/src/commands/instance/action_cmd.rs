@@ -173,6 +173,7 @@ pub async fn get_instance_detail<R: Runtime>(
                     modpack_uuid: None,
                     modpack_version: None,
                     modpack_source: None,
+                    modpack_version_id: None,
                 };
 
                 if let Err(e) = InstanceBindingService::write_instance_config(&app, &id, &config) {
@@ -1,7 +1,13 @@
 // src-tauri/src/commands/instance/save_cmd.rs
 use crate::services::instance::save_manager::{
-    SaveBackupMetadata, SaveItem, SaveManagerService, SaveRestoreCheckResult, SaveRestoreResult,
+    SaveBackupMetadata, SaveItem, SaveManagerService, SavePartialRestoreCheckResult,
+    SavePartialRestoreResult, SavePeriodicBackupSettings, SaveRestoreCheckResult,
+    SaveRestoreResult,
 };
+use crate::services::instance::partial_restore::SaveRestoreSelection;
+use crate::services::instance::save_diff::SaveBackupDiff;
+use crate::services::instance::save_retention::SaveBackupRetention;
+use crate::services::instance::world_optimizer::{WorldOptimizeOptions, WorldOptimizeReport};
 use tauri::{AppHandle, Runtime};
 
 #[tauri::command]
@@ -68,6 +74,33 @@ pub async fn restore_save_backup<R: Runtime>(
     SaveManagerService::restore_backup(&app, &id, &backup_id, restore_configs, auto_backup_current)
 }
 
+#[tauri::command]
+pub async fn verify_partial_save_restore<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    backup_id: String,
+    selection: SaveRestoreSelection,
+) -> Result<SavePartialRestoreCheckResult, String> {
+    SaveManagerService::verify_partial_restore(&app, &id, &backup_id, &selection)
+}
+
+#[tauri::command]
+pub async fn restore_save_backup_partial<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    backup_id: String,
+    selection: SaveRestoreSelection,
+    auto_backup_current: bool,
+) -> Result<SavePartialRestoreResult, String> {
+    SaveManagerService::restore_backup_partial(
+        &app,
+        &id,
+        &backup_id,
+        &selection,
+        auto_backup_current,
+    )
+}
+
 #[tauri::command]
 pub async fn get_save_backups<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
@@ -115,3 +148,71 @@ pub async fn set_backup_all_worlds_on_exit_enabled<R: Runtime>(
     SaveManagerService::set_backup_all_worlds_on_exit_enabled(&app, &id, enabled)
 }
 
+
+#[tauri::command]
+pub async fn get_save_backup_retention<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    folder_name: Option<String>,
+) -> Result<SaveBackupRetention, String> {
+    SaveManagerService::get_backup_retention(&app, &id, folder_name.as_deref())
+}
+
+#[tauri::command]
+pub async fn set_save_backup_retention<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    folder_name: Option<String>,
+    retention: SaveBackupRetention,
+) -> Result<(), String> {
+    SaveManagerService::set_backup_retention(&app, &id, folder_name.as_deref(), retention)
+}
+
+#[tauri::command]
+pub async fn prune_save_backups<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+) -> Result<Vec<String>, String> {
+    SaveManagerService::prune_backups(&app, &id)
+}
+
+#[tauri::command]
+pub async fn get_periodic_save_backup<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+) -> Result<SavePeriodicBackupSettings, String> {
+    SaveManagerService::get_periodic_backup_settings(&app, &id)
+}
+
+#[tauri::command]
+pub async fn set_periodic_save_backup<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    settings: SavePeriodicBackupSettings,
+) -> Result<(), String> {
+    SaveManagerService::set_periodic_backup_settings(&app, &id, settings)
+}
+
+#[tauri::command]
+pub async fn optimize_save_world<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    folder_name: String,
+    options: WorldOptimizeOptions,
+) -> Result<WorldOptimizeReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        SaveManagerService::optimize_world(&app, &id, &folder_name, options)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn diff_save_backups<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    from_backup_id: String,
+    to_backup_id: Option<String>,
+) -> Result<SaveBackupDiff, String> {
+    SaveManagerService::diff_backups(&app, &id, &from_backup_id, to_backup_id.as_deref())
+}
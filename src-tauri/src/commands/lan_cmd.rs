@@ -1,24 +1,33 @@
 use crate::domain::lan::{
-    DeviceInitInfo, DiscoveredDevice, OnlineDeviceCheck, TransferProgressEvent, TransferRecord,
-    TrustRequest, TrustedDevice,
+    DeltaOffer, DeviceIdentity, DeviceInitInfo, DiscoveredDevice, KeyRotationReport, LanKnownPeer,
+    LanPairingInfo, LanPullRequest, LanSharedInstance, LanSkinServerConfig, OnlineDeviceCheck,
+    TransferProgressEvent, TransferRecord, TrustAuditEntry, TrustRequest, TrustedDevice,
 };
 use crate::services::config_service::ConfigService;
 use crate::services::db_service::AppDatabase;
-use crate::services::lan::http_api::SharedLanState;
+use crate::services::lan::catalog;
+use crate::services::lan::delta_transfer::{self, DeltaPeer, DeltaSendOutcome};
+use crate::services::lan::http_api::{SharedLanState, DEFAULT_HTTP_PORT};
+use crate::services::lan::key_rotation;
 use crate::services::lan::mdns_service::MdnsScanner;
+use crate::services::lan::pairing;
+use crate::services::lan::secure_channel::{self, FrameOpener};
+use crate::services::lan::skin_server;
 use crate::services::lan::transfer_records::{
     emit_transfer_progress, fetch_transfer_history as fetch_transfer_history_records,
     upsert_transfer_record, TransferRecordUpsert,
 };
 use crate::services::lan::transfer_service;
-use crate::services::lan::trust_store::TrustStore;
-use futures::stream;
+use crate::services::lan::trust_store::{TrustStore, AUDIT_DOWNGRADED};
+use crate::services::lan::udp_discovery;
+use crate::services::qrcode_service;
+use futures::StreamExt;
 use sqlx::Row;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager, Runtime, State};
-use tokio::io::AsyncReadExt;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tokio::io::AsyncWriteExt;
 
 const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
 
@@ -30,6 +39,16 @@ fn is_safe_filename(name: &str) -> bool {
     !name.is_empty() && !name.contains('/') && !name.contains('\\') && name != "." && name != ".."
 }
 
+/// 校验前端回传的接收路径，只允许操作本机传输缓存目录中的内容
+fn received_temp_path<R: Runtime>(app: &AppHandle<R>, temp_path: &str) -> Result<PathBuf, String> {
+    let temp_root = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+        .join("temp_transfers");
+    delta_transfer::resolve_received_path(&temp_root, temp_path)
+}
+
 fn is_valid_png(bytes: &[u8]) -> bool {
     bytes.len() > PNG_SIGNATURE.len() && bytes.starts_with(&PNG_SIGNATURE)
 }
@@ -91,7 +110,28 @@ fn build_progress_event(
 
 #[tauri::command]
 pub async fn scan_lan_devices() -> Result<Vec<DiscoveredDevice>, String> {
-    MdnsScanner::scan_for_seconds(3).await
+    // mDNS 与 UDP 广播同时扫描，任一方式可用即可
+    let (mdns_result, udp_result) = tokio::join!(
+        MdnsScanner::scan_for_seconds(3),
+        udp_discovery::scan_for_seconds(3)
+    );
+    if let (Err(mdns_err), Err(udp_err)) = (&mdns_result, &udp_result) {
+        return Err(format!(
+            "mDNS 扫描失败: {}；UDP 广播扫描失败: {}",
+            mdns_err, udp_err
+        ));
+    }
+
+    let mut devices = mdns_result.unwrap_or_default();
+    for device in udp_result.unwrap_or_default() {
+        if !devices
+            .iter()
+            .any(|item| item.device_id == device.device_id)
+        {
+            devices.push(device);
+        }
+    }
+    Ok(devices)
 }
 
 #[tauri::command]
@@ -171,6 +211,18 @@ pub async fn send_trust_request<R: Runtime>(
         .json::<TrustRequest>()
         .await
         .map_err(|_| "对方数据格式异常".to_string())?;
+    // 通过配对码或二维码添加的设备，公钥必须与添加时记录的一致
+    if let Some(pinned) =
+        TrustStore::pinned_public_key(&db.pool, &target_identity.device_id).await?
+    {
+        if pinned != target_identity.public_key {
+            return Err(
+                "对方公钥与添加设备时记录的公钥不一致，可能连接到了其他设备，已中止配对。"
+                    .to_string(),
+            );
+        }
+    }
+    let remote_device_id = target_identity.device_id.clone();
     let remote_info = fetch_remote_device_info(&target_ip, target_port).await;
     let remote_username = remote_info
         .as_ref()
@@ -203,6 +255,12 @@ pub async fn send_trust_request<R: Runtime>(
         )
         .await?;
     }
+    TrustStore::touch_peer_address(
+        &db.pool,
+        &remote_device_id,
+        &pairing::format_address(&target_ip, target_port),
+    )
+    .await?;
 
     Ok(())
 }
@@ -388,7 +446,7 @@ pub async fn sync_lan_avatar<R: Runtime>(
 pub async fn get_trusted_devices(db: State<'_, AppDatabase>) -> Result<Vec<TrustedDevice>, String> {
     let rows = sqlx::query(
         "SELECT device_uuid, device_name, user_uuid, username, public_key_b64, trust_level,
-                strftime('%s', trusted_at) AS ts
+                strftime('%s', trusted_at) AS ts, expires_at
          FROM trusted_devices
          WHERE trust_level = 'trusted'
          ORDER BY trusted_at DESC",
@@ -415,6 +473,7 @@ pub async fn get_trusted_devices(db: State<'_, AppDatabase>) -> Result<Vec<Trust
                 trust_level: row
                     .try_get("trust_level")
                     .unwrap_or_else(|_| "trusted".to_string()),
+                expires_at: row.try_get("expires_at").unwrap_or_default(),
             })
         })
         .collect()
@@ -424,7 +483,7 @@ pub async fn get_trusted_devices(db: State<'_, AppDatabase>) -> Result<Vec<Trust
 pub async fn get_friend_devices(db: State<'_, AppDatabase>) -> Result<Vec<TrustedDevice>, String> {
     let rows = sqlx::query(
         "SELECT device_uuid, device_name, user_uuid, username, public_key_b64, trust_level,
-                strftime('%s', trusted_at) AS ts
+                strftime('%s', trusted_at) AS ts, expires_at
          FROM trusted_devices
          WHERE trust_level IN ('friend', 'trusted')
          ORDER BY CASE trust_level WHEN 'trusted' THEN 0 ELSE 1 END, trusted_at DESC",
@@ -451,6 +510,7 @@ pub async fn get_friend_devices(db: State<'_, AppDatabase>) -> Result<Vec<Truste
                 trust_level: row
                     .try_get("trust_level")
                     .unwrap_or_else(|_| "friend".to_string()),
+                expires_at: row.try_get("expires_at").unwrap_or_default(),
             })
         })
         .collect()
@@ -481,7 +541,7 @@ pub async fn remove_trusted_device(
     db: State<'_, AppDatabase>,
     device_id: String,
 ) -> Result<(), String> {
-    sqlx::query(
+    let result = sqlx::query(
         "UPDATE trusted_devices
          SET trust_level = 'friend'
          WHERE device_uuid = $1 AND trust_level = 'trusted'",
@@ -491,9 +551,111 @@ pub async fn remove_trusted_device(
     .await
     .map_err(|e| format!("取消设备信任失败: {}", e))?;
 
+    if result.rows_affected() > 0 {
+        TrustStore::record_audit(&db.pool, &device_id, AUDIT_DOWNGRADED, "").await?;
+    }
+    Ok(())
+}
+
+/// 吊销设备：数据库标记立即生效，并作废该设备已建立的加密会话
+#[tauri::command]
+pub async fn revoke_trusted_device(
+    state: State<'_, Arc<SharedLanState>>,
+    db: State<'_, AppDatabase>,
+    device_id: String,
+) -> Result<(), String> {
+    TrustStore::revoke_device(&db.pool, &device_id).await?;
+    state.drop_secure_sessions(&device_id);
     Ok(())
 }
 
+/// 设置信任到期时间（Unix 秒），传 None 取消到期
+#[tauri::command]
+pub async fn set_device_trust_expiry(
+    db: State<'_, AppDatabase>,
+    device_id: String,
+    expires_at: Option<i64>,
+) -> Result<(), String> {
+    if expires_at.is_some_and(|value| value <= chrono::Utc::now().timestamp()) {
+        return Err("到期时间必须晚于当前时间".to_string());
+    }
+    TrustStore::set_trust_expiry(&db.pool, &device_id, expires_at).await
+}
+
+#[tauri::command]
+pub async fn get_trust_audit_log(
+    db: State<'_, AppDatabase>,
+    device_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<TrustAuditEntry>, String> {
+    TrustStore::list_audit_log(
+        &db.pool,
+        device_id.as_deref(),
+        limit.unwrap_or(200).min(1000) as i64,
+    )
+    .await
+}
+
+/// 轮换本机设备密钥，并把新旧密钥共同签名的交接声明发给已配对设备
+#[tauri::command]
+pub async fn rotate_device_key<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<SharedLanState>>,
+    db: State<'_, AppDatabase>,
+) -> Result<KeyRotationReport, String> {
+    let base_path = ConfigService::get_base_path(&app)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "尚未配置启动器数据目录".to_string())?;
+    let config_dir = PathBuf::from(base_path).join("config");
+    let identity = TrustStore::get_or_create_identity(&config_dir);
+    let current_info = state.current_device_info.lock().unwrap().clone();
+    let device_id = if !current_info.device_id.trim().is_empty() {
+        current_info.device_id
+    } else {
+        identity.device_id
+    };
+    let device_name = if !current_info.device_name.trim().is_empty() {
+        current_info.device_name
+    } else {
+        identity.device_name
+    };
+
+    let handover = key_rotation::rotate_identity(&db.pool, &config_dir, &device_id).await?;
+    MdnsScanner::restart_broadcast(
+        &device_id,
+        &device_name,
+        &device_id,
+        &device_name,
+        &handover.new_public_key,
+        DEFAULT_HTTP_PORT,
+    );
+
+    let (delivered, pending) = key_rotation::deliver_pending(&db.pool, &config_dir).await?;
+    Ok(KeyRotationReport {
+        public_key: handover.new_public_key,
+        delivered,
+        pending,
+    })
+}
+
+/// 向轮换密钥时未能联系上的设备重新发送交接声明
+#[tauri::command]
+pub async fn retry_key_handover<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, AppDatabase>,
+) -> Result<KeyRotationReport, String> {
+    let base_path = ConfigService::get_base_path(&app)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "尚未配置启动器数据目录".to_string())?;
+    let config_dir = PathBuf::from(base_path).join("config");
+    let (delivered, pending) = key_rotation::deliver_pending(&db.pool, &config_dir).await?;
+    Ok(KeyRotationReport {
+        public_key: TrustStore::get_or_create_identity(&config_dir).public_key_b64,
+        delivered,
+        pending,
+    })
+}
+
 #[tauri::command]
 pub async fn verify_trusted_devices(
     db: State<'_, AppDatabase>,
@@ -529,6 +691,13 @@ pub async fn verify_trusted_devices(
                 .execute(&db.pool)
                 .await
                 .map_err(|e| format!("更新设备信任状态失败: {}", e))?;
+                TrustStore::record_audit(
+                    &db.pool,
+                    &db_device_id,
+                    AUDIT_DOWNGRADED,
+                    "identity_changed",
+                )
+                .await?;
                 downgraded.push(db_device_id);
             }
         }
@@ -537,6 +706,158 @@ pub async fn verify_trusted_devices(
     Ok(downgraded)
 }
 
+/// 手动添加设备：支持 IP[:端口]、配对码与配对二维码内容，添加前先探测对方是否在线
+#[tauri::command]
+pub async fn add_manual_peer(
+    db: State<'_, AppDatabase>,
+    input: String,
+) -> Result<DiscoveredDevice, String> {
+    let target = pairing::parse_peer_input(&input)?;
+    let info = fetch_remote_device_info(&target.host, target.port)
+        .await
+        .ok_or_else(|| {
+            format!(
+                "无法连接到 {}，请确认对方已打开启动器且防火墙放行了该端口",
+                target.address()
+            )
+        })?;
+    if info.device_id.trim().is_empty() {
+        return Err("对方设备信息尚未就绪，请稍后重试".to_string());
+    }
+    if target
+        .device_id
+        .as_deref()
+        .is_some_and(|expected| expected != info.device_id)
+    {
+        return Err("该地址上的设备与配对二维码中的设备不一致".to_string());
+    }
+    if let Some(expected) = target.public_key.as_deref() {
+        if !info.public_key.is_empty() && expected != info.public_key {
+            return Err("该地址上的设备公钥与配对二维码不一致".to_string());
+        }
+    }
+
+    let public_key = target.public_key.clone().unwrap_or(info.public_key);
+    TrustStore::remember_peer_address(
+        &db.pool,
+        &info.device_id,
+        &info.device_name,
+        &info.username,
+        &public_key,
+        &target.address(),
+    )
+    .await?;
+
+    Ok(DiscoveredDevice {
+        device_id: info.device_id,
+        device_name: info.device_name,
+        ip: target.host,
+        port: target.port,
+        public_key,
+    })
+}
+
+/// 本机的配对信息，供对方输入配对码或扫描二维码
+#[tauri::command]
+pub fn get_pairing_info<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<SharedLanState>>,
+) -> Result<LanPairingInfo, String> {
+    let ip = match local_ip_address::local_ip() {
+        Ok(std::net::IpAddr::V4(ip)) => ip,
+        _ => return Err("无法获取本机局域网 IPv4 地址".to_string()),
+    };
+    let base_path = ConfigService::get_base_path(&app)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    let identity = TrustStore::get_or_create_identity(&PathBuf::from(base_path).join("config"));
+    let current_info = state.current_device_info.lock().unwrap().clone();
+    let device_id = if !current_info.device_id.trim().is_empty() {
+        current_info.device_id
+    } else {
+        identity.device_id
+    };
+
+    let uri = pairing::pairing_uri(ip, DEFAULT_HTTP_PORT, &device_id, &identity.public_key_b64);
+    let qr_data_uri = qrcode_service::generate_qr_data_uri(&uri)?;
+    Ok(LanPairingInfo {
+        address: format!("{}:{}", ip, DEFAULT_HTTP_PORT),
+        code: pairing::encode_pairing_code(ip, DEFAULT_HTTP_PORT),
+        uri,
+        qr_data_uri,
+    })
+}
+
+/// 记住了地址的设备，并发请求 /device/init 探测在线状态
+#[tauri::command]
+pub async fn get_known_peers(db: State<'_, AppDatabase>) -> Result<Vec<LanKnownPeer>, String> {
+    let rows = sqlx::query(
+        "SELECT device_uuid, device_name, username, public_key_b64, trust_level, address,
+                strftime('%s', last_seen) AS seen
+         FROM trusted_devices
+         WHERE address != ''
+         ORDER BY last_seen DESC",
+    )
+    .fetch_all(&db.pool)
+    .await
+    .map_err(|e| format!("查询已记住的设备失败: {}", e))?;
+
+    let mut peers = Vec::with_capacity(rows.len());
+    let mut public_keys = Vec::with_capacity(rows.len());
+    for row in rows {
+        public_keys.push(
+            row.try_get::<String, _>("public_key_b64")
+                .unwrap_or_default(),
+        );
+        peers.push(LanKnownPeer {
+            device_id: row.try_get("device_uuid").map_err(|e| e.to_string())?,
+            device_name: row.try_get("device_name").unwrap_or_default(),
+            username: row.try_get("username").unwrap_or_default(),
+            trust_level: row.try_get("trust_level").unwrap_or_default(),
+            address: row.try_get("address").unwrap_or_default(),
+            online: false,
+            last_seen: row
+                .try_get::<Option<String>, _>("seen")
+                .ok()
+                .flatten()
+                .and_then(|value| value.parse::<i64>().ok()),
+            info: None,
+        });
+    }
+
+    let probes = peers.iter().map(|peer| async move {
+        let target = pairing::parse_peer_input(&peer.address).ok()?;
+        fetch_remote_device_info(&target.host, target.port).await
+    });
+    let infos = futures::future::join_all(probes).await;
+
+    for ((peer, public_key), info) in peers.iter_mut().zip(public_keys).zip(infos) {
+        // 地址上换成了别的设备（例如 DHCP 重新分配）时不视为在线
+        let Some(info) = info.filter(|info| {
+            info.device_id == peer.device_id
+                && (info.public_key.is_empty()
+                    || public_key.is_empty()
+                    || info.public_key == public_key)
+        }) else {
+            continue;
+        };
+        TrustStore::touch_peer_address(&db.pool, &peer.device_id, &peer.address).await?;
+        peer.online = true;
+        peer.last_seen = Some(chrono::Utc::now().timestamp());
+        peer.info = Some(info);
+    }
+
+    Ok(peers)
+}
+
+#[tauri::command]
+pub async fn forget_known_peer(
+    db: State<'_, AppDatabase>,
+    device_id: String,
+) -> Result<(), String> {
+    TrustStore::forget_peer_address(&db.pool, &device_id).await
+}
+
 #[tauri::command]
 pub async fn get_local_instances<R: Runtime>(
     app: AppHandle<R>,
@@ -608,49 +929,25 @@ pub async fn push_to_device<R: Runtime>(
     remote_device_id: Option<String>,
     remote_device_name: Option<String>,
     remote_username: Option<String>,
+    resume_transfer_id: Option<String>,
 ) -> Result<String, String> {
-    if !is_safe_filename(&target_id) {
-        return Err("非法的实例 ID".to_string());
-    }
-    if transfer_type != "instance" {
-        if let Some(ref save) = save_name {
-            if !is_safe_filename(save) {
-                return Err("非法的存档名称".to_string());
-            }
-        }
-    }
-
     let base_path = ConfigService::get_base_path(&app)
         .map_err(|e| e.to_string())?
         .unwrap_or_default();
     let instances_dir = PathBuf::from(&base_path).join("instances");
-
-    let (src_dir, item_name) = if transfer_type == "instance" {
-        (instances_dir.join(&target_id), target_id.clone())
-    } else {
-        let selected_save = save_name.clone().unwrap_or_default();
-        (
-            instances_dir
-                .join(&target_id)
-                .join("saves")
-                .join(&selected_save),
-            selected_save,
-        )
+    let (src_dir, item_name) = catalog::resolve_transfer_source(
+        &instances_dir,
+        &transfer_type,
+        &target_id,
+        save_name.as_deref(),
+    )?;
+
+    // 传入上次中断的传输 ID 时沿用它，对方会保留已接收的部分
+    let transfer_id = match resume_transfer_id {
+        Some(id) if delta_transfer::is_valid_transfer_id(&id) => id,
+        Some(_) => return Err("非法的传输 ID".to_string()),
+        None => uuid::Uuid::new_v4().to_string(),
     };
-
-    if !src_dir.exists() {
-        return Err("目标内容不存在，无法发送".to_string());
-    }
-
-    let transfer_id = uuid::Uuid::new_v4().to_string();
-    let temp_root = app
-        .path()
-        .app_data_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join("temp_transfers");
-    fs::create_dir_all(&temp_root).map_err(|e| format!("创建传输缓存目录失败: {}", e))?;
-    let temp_zip = temp_root.join(format!("{}.zip", transfer_id));
-
     let current_info = state.current_device_info.lock().unwrap().clone();
     let config_dir = PathBuf::from(&base_path).join("config");
     let identity = TrustStore::get_or_create_identity(&config_dir);
@@ -729,178 +1026,336 @@ pub async fn push_to_device<R: Runtime>(
     );
 
     let result: Result<String, String> = async {
-        if let Err(error) = transfer_service::zip_dir_with_progress(
-            &src_dir,
-            &temp_zip,
-            |current, total, message| {
+        // 增量传输只发送对方缺少的文件，内容全部经加密会话发送，对方不支持加密通道时直接报错
+        let (directories, files) =
+            match delta_transfer::build_file_list(&src_dir, |current, total, message| {
                 emit_stage("packing", "PACKING", current, total, message);
-            },
-        ) {
-            let message = format!("打包失败: {}", error);
-            let _ =
-                upsert_transfer_record(&app, &db, make_upsert("failed", 0, Some(&message), true))
+            }) {
+                Ok(list) => list,
+                Err(message) => {
+                    let _ = upsert_transfer_record(
+                        &app,
+                        &db,
+                        make_upsert("failed", 0, Some(&message), true),
+                    )
                     .await;
-            emit_stage("failed", "FAILED", 0, 0, message.clone());
-            return Err(message);
-        }
-
-        let total_size = fs::metadata(&temp_zip)
-            .map_err(|e| format!("读取临时压缩包信息失败: {}", e))?
-            .len();
-
+                    emit_stage("failed", "FAILED", 0, 0, message.clone());
+                    return Err(message);
+                }
+            };
+        let offer = DeltaOffer {
+            transfer_id: transfer_id.clone(),
+            transfer_type: transfer_type.clone(),
+            name: item_name.clone(),
+            directories,
+            files,
+        };
+        let offer_total = delta_transfer::offer_size(&offer);
         upsert_transfer_record(
             &app,
             &db,
-            make_upsert("sending", total_size as i64, None, false),
+            make_upsert("sending", offer_total as i64, None, false),
         )
         .await?;
-        emit_stage(
-            "sending",
-            "SENDING",
-            0,
-            total_size,
-            "正在发送压缩包到目标设备".to_string(),
-        );
 
-        let client = match reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(900))
+        let delta_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
             .build()
-        {
-            Ok(client) => client,
-            Err(error) => {
-                let message = format!("创建网络客户端失败: {}", error);
-                let _ = upsert_transfer_record(
+            .map_err(|e| format!("创建网络客户端失败: {}", e))?;
+        let base_url = format!("http://{}:{}", target_ip, target_port);
+        let source_root = src_dir.parent().unwrap_or(&src_dir);
+        let delta_result = async {
+            // 用配对时记录的对方公钥握手，后续内容全部经会话密钥加密
+            let peer_public_key = TrustStore::get_public_key(&db.pool, &resolved_remote_device_id)
+                .await
+                .map_err(|e| (e, "failed"))?
+                .ok_or_else(|| {
+                    (
+                        "未找到目标设备的配对公钥，请重新配对后再发送".to_string(),
+                        "failed",
+                    )
+                })?;
+            let (session_id, session) = secure_channel::handshake(
+                &delta_client,
+                &base_url,
+                &identity,
+                &sender_device_id,
+                &peer_public_key,
+            )
+            .await?;
+            let peer = DeltaPeer {
+                client: &delta_client,
+                base_url: base_url.clone(),
+                identity: &identity,
+                device_id: &sender_device_id,
+                device_name: &sender_device_name,
+                username: &sender_username,
+                session_id: &session_id,
+                session: &session,
+            };
+            peer.send(&offer, source_root, |current, total, message| {
+                emit_stage("sending", "SENDING", current, total, message);
+            })
+            .await
+        }
+        .await;
+        let delta_result = delta_result.and_then(|outcome| match outcome {
+            DeltaSendOutcome::Sent { total, transferred } => Ok((total, transferred)),
+            DeltaSendOutcome::Unsupported => Err((
+                "目标设备版本过旧，不支持加密传输，请升级后再发送".to_string(),
+                "failed",
+            )),
+        });
+        match delta_result {
+            Ok((total, transferred)) => {
+                upsert_transfer_record(
                     &app,
                     &db,
-                    make_upsert("failed", total_size as i64, Some(&message), true),
+                    make_upsert("received", total as i64, None, true),
                 )
-                .await;
-                emit_stage("failed", "FAILED", 0, total_size, message.clone());
-                return Err(message);
+                .await?;
+                emit_stage(
+                    "received",
+                    "RECEIVED",
+                    total,
+                    total,
+                    format!(
+                        "目标设备已接收传输内容，实际发送 {} / {} 字节",
+                        transferred, total
+                    ),
+                );
+                Ok(transfer_id.clone())
             }
-        };
-
-        let file = match tokio::fs::File::open(&temp_zip).await {
-            Ok(file) => file,
-            Err(error) => {
-                let message = format!("读取临时压缩包失败: {}", error);
+            Err((message, status)) => {
                 let _ = upsert_transfer_record(
                     &app,
                     &db,
-                    make_upsert("failed", total_size as i64, Some(&message), true),
+                    make_upsert(status, offer_total as i64, Some(&message), true),
                 )
                 .await;
-                emit_stage("failed", "FAILED", 0, total_size, message.clone());
-                return Err(message);
+                emit_stage(status, "FAILED", 0, offer_total, message.clone());
+                Err(message)
             }
-        };
+        }
+    }
+    .await;
 
-        let app_for_stream = app.clone();
-        let transfer_id_for_stream = transfer_id.clone();
-        let remote_device_id_for_stream = resolved_remote_device_id.clone();
-        let remote_device_name_for_stream = resolved_remote_device_name.clone();
-        let remote_username_for_stream = resolved_remote_username.clone();
-        let transfer_type_for_stream = transfer_type.clone();
-        let item_name_for_stream = item_name.clone();
-
-        let body_stream = stream::try_unfold((file, 0_u64), move |(mut file, sent)| {
-            let app = app_for_stream.clone();
-            let transfer_id = transfer_id_for_stream.clone();
-            let remote_device_id = remote_device_id_for_stream.clone();
-            let remote_device_name = remote_device_name_for_stream.clone();
-            let remote_username = remote_username_for_stream.clone();
-            let transfer_type = transfer_type_for_stream.clone();
-            let item_name = item_name_for_stream.clone();
-
-            async move {
-                let mut buffer = vec![0_u8; 128 * 1024];
-                let read = file.read(&mut buffer).await?;
-                if read == 0 {
-                    return Ok::<_, std::io::Error>(None);
-                }
+    result
+}
 
-                buffer.truncate(read);
-                let current = sent + read as u64;
-                emit_transfer_progress(
-                    &app,
-                    &build_progress_event(
-                        &transfer_id,
-                        "outgoing",
-                        &remote_device_id,
-                        &remote_device_name,
-                        &remote_username,
-                        &transfer_type,
-                        &item_name,
-                        "sending",
-                        "SENDING",
-                        current,
-                        total_size,
-                        "正在通过局域网传输压缩包".to_string(),
-                    ),
-                );
+/// 本机作为请求方的身份信息，优先使用前端同步的设备信息
+fn local_sender<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &SharedLanState,
+) -> Result<(DeviceIdentity, DeviceInitInfo), String> {
+    let base_path = ConfigService::get_base_path(app)
+        .map_err(|e| e.to_string())?
+        .unwrap_or_default();
+    let identity = TrustStore::get_or_create_identity(&PathBuf::from(base_path).join("config"));
+    let mut current_info = state.current_device_info.lock().unwrap().clone();
+    if current_info.device_id.trim().is_empty() {
+        current_info.device_id = identity.device_id.clone();
+    }
+    if current_info.device_name.trim().is_empty() {
+        current_info.device_name = identity.device_name.clone();
+    }
+    Ok((identity, current_info))
+}
 
-                Ok(Some((buffer, (file, current))))
-            }
-        });
+fn signed_peer_request(
+    request: reqwest::RequestBuilder,
+    identity: &DeviceIdentity,
+    sender: &DeviceInitInfo,
+    method: &str,
+    path: &str,
+) -> Result<reqwest::RequestBuilder, String> {
+    let (timestamp, signature_b64) =
+        TrustStore::sign_request(identity, &sender.device_id, method, path)?;
+    Ok(request
+        .header("X-Device-Id", sender.device_id.clone())
+        .header(
+            "X-Device-Name",
+            urlencoding::encode(&sender.device_name).into_owned(),
+        )
+        .header(
+            "X-Username",
+            urlencoding::encode(&sender.username).into_owned(),
+        )
+        .header("X-Signature", signature_b64)
+        .header("X-Timestamp", timestamp))
+}
 
-        let timestamp = chrono::Utc::now().timestamp().to_string();
-        let method = "POST";
-        let path = "/api/transfer/receive";
-        let sign_message = format!("{}:{}:{}:{}", method, path, timestamp, sender_device_id);
-
-        use base64::{engine::general_purpose, Engine as _};
-        use ed25519_dalek::{SigningKey, Signer};
-
-        let private_bytes = general_purpose::STANDARD
-            .decode(&identity.private_key_b64)
-            .map_err(|e| format!("解码私钥失败: {}", e))?;
-        let private_array: [u8; 32] = private_bytes
-            .try_into()
-            .map_err(|_| "私钥长度错误".to_string())?;
-        let signing_key = SigningKey::from_bytes(&private_array);
-        let signature = signing_key.sign(sign_message.as_bytes());
-        let signature_b64 = general_purpose::STANDARD.encode(signature.to_bytes());
-
-        let response = match client
-            .post(format!(
-                "http://{}:{}/api/transfer/receive",
-                target_ip, target_port
-            ))
-            .header("X-Transfer-Id", transfer_id.clone())
-            .header("X-Transfer-Type", transfer_type.clone())
-            .header(
-                "X-Transfer-Name",
-                urlencoding::encode(&item_name).into_owned(),
-            )
-            .header(
-                "X-Device-Name",
-                urlencoding::encode(&sender_device_name).into_owned(),
-            )
-            .header("X-Device-Id", sender_device_id.clone())
-            .header(
-                "X-Username",
-                urlencoding::encode(&sender_username).into_owned(),
-            )
-            .header("X-Signature", signature_b64)
-            .header("X-Timestamp", timestamp)
-            .header(reqwest::header::CONTENT_LENGTH, total_size.to_string())
-            .body(reqwest::Body::wrap_stream(body_stream))
-            .send()
+#[tauri::command]
+pub async fn get_remote_catalog<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<SharedLanState>>,
+    target_ip: String,
+    target_port: u16,
+) -> Result<Vec<LanSharedInstance>, String> {
+    let (identity, sender) = local_sender(&app, &state)?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("创建网络客户端失败: {}", e))?;
+    let path = "/api/instances/list";
+    let response = signed_peer_request(
+        client.get(format!("http://{}:{}{}", target_ip, target_port, path)),
+        &identity,
+        &sender,
+        "GET",
+        path,
+    )?
+    .send()
+    .await
+    .map_err(|e| format!("获取共享列表失败: {}", e))?;
+
+    match response.status() {
+        status if status.is_success() => response
+            .json::<Vec<LanSharedInstance>>()
             .await
-        {
-            Ok(response) => response,
-            Err(error) => {
-                let message = format!("发送失败: {}", error);
-                let _ = upsert_transfer_record(
-                    &app,
-                    &db,
-                    make_upsert("failed", total_size as i64, Some(&message), true),
-                )
-                .await;
-                emit_stage("failed", "FAILED", 0, total_size, message.clone());
-                return Err(message);
-            }
-        };
+            .map_err(|e| format!("解析共享列表失败: {}", e)),
+        reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::UNAUTHORIZED => {
+            Err("对方尚未与本机配对，无法浏览共享内容".to_string())
+        }
+        status => Err(format!("目标设备返回状态码 {}", status)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn pull_from_device<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, AppDatabase>,
+    state: State<'_, Arc<SharedLanState>>,
+    target_ip: String,
+    target_port: u16,
+    transfer_type: String,
+    target_id: String,
+    save_name: Option<String>,
+    remote_device_id: Option<String>,
+    remote_device_name: Option<String>,
+    remote_username: Option<String>,
+) -> Result<String, String> {
+    if !is_safe_filename(&target_id) {
+        return Err("非法的实例 ID".to_string());
+    }
+    if transfer_type != "instance" && !save_name.as_deref().is_some_and(is_safe_filename) {
+        return Err("非法的存档名称".to_string());
+    }
+
+    let (identity, sender) = local_sender(&app, &state)?;
+    let remote_info = fetch_remote_device_info(&target_ip, target_port).await;
+    let resolved_remote_device_id = remote_device_id
+        .or_else(|| remote_info.as_ref().map(|item| item.device_id.clone()))
+        .unwrap_or_else(|| format!("{}:{}", target_ip, target_port));
+    let resolved_remote_device_name = remote_device_name
+        .or_else(|| remote_info.as_ref().map(|item| item.device_name.clone()))
+        .unwrap_or_else(|| "局域网设备".to_string());
+    let resolved_remote_username = remote_username
+        .or_else(|| remote_info.as_ref().map(|item| item.username.clone()))
+        .unwrap_or_default();
+    let item_name = if transfer_type == "instance" {
+        target_id.clone()
+    } else {
+        save_name.clone().unwrap_or_default()
+    };
+
+    let transfer_id = uuid::Uuid::new_v4().to_string();
+    let temp_root = app
+        .path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("temp_transfers");
+    fs::create_dir_all(&temp_root).map_err(|e| format!("创建传输缓存目录失败: {}", e))?;
+    let temp_zip = temp_root.join(format!("{}.zip", transfer_id));
+
+    let make_upsert = |status: &str,
+                       size: i64,
+                       error_message: Option<&str>,
+                       mark_completed: bool| TransferRecordUpsert {
+        transfer_id: &transfer_id,
+        direction: "incoming",
+        sender_device_id: &resolved_remote_device_id,
+        sender_device: &resolved_remote_device_name,
+        receiver_device_id: &sender.device_id,
+        receiver_device: &sender.device_name,
+        remote_device_id: &resolved_remote_device_id,
+        remote_device_name: &resolved_remote_device_name,
+        remote_username: &resolved_remote_username,
+        transfer_type: &transfer_type,
+        name: &item_name,
+        size,
+        status: status.to_string(),
+        error_message: error_message.map(str::to_string),
+        mark_completed,
+    };
+
+    let emit_stage = |status: &str, stage: &str, current: u64, total: u64, message: String| {
+        emit_transfer_progress(
+            &app,
+            &build_progress_event(
+                &transfer_id,
+                "incoming",
+                &resolved_remote_device_id,
+                &resolved_remote_device_name,
+                &resolved_remote_username,
+                &transfer_type,
+                &item_name,
+                status,
+                stage,
+                current,
+                total,
+                message,
+            ),
+        );
+    };
+
+    upsert_transfer_record(&app, &db, make_upsert("requesting", 0, None, false)).await?;
+    emit_stage(
+        "requesting",
+        "REQUESTING",
+        0,
+        0,
+        "正在等待对方确认拉取请求".to_string(),
+    );
+
+    let result: Result<u64, (String, &str)> = async {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(900))
+            .build()
+            .map_err(|e| (format!("创建网络客户端失败: {}", e), "failed"))?;
+        let base_url = format!("http://{}:{}", target_ip, target_port);
+        let peer_public_key = TrustStore::get_public_key(&db.pool, &resolved_remote_device_id)
+            .await
+            .map_err(|e| (e, "failed"))?
+            .ok_or_else(|| ("未找到目标设备的配对公钥，请重新配对".to_string(), "failed"))?;
+        let (session_id, session) = secure_channel::handshake(
+            &client,
+            &base_url,
+            &identity,
+            &sender.device_id,
+            &peer_public_key,
+        )
+        .await?;
+
+        let path = "/api/transfer/pull";
+        let response = signed_peer_request(
+            client.post(format!("http://{}:{}{}", target_ip, target_port, path)),
+            &identity,
+            &sender,
+            "POST",
+            path,
+        )
+        .map_err(|e| (e, "failed"))?
+        .header("X-Session-Id", session_id)
+        .json(&LanPullRequest {
+            transfer_type: transfer_type.clone(),
+            target_id: target_id.clone(),
+            save_name: save_name.clone(),
+        })
+        .send()
+        .await
+        .map_err(|e| (format!("拉取失败: {}", e), "failed"))?;
 
         if !response.status().is_success() {
             let status = if response.status() == reqwest::StatusCode::FORBIDDEN {
@@ -908,37 +1363,96 @@ pub async fn push_to_device<R: Runtime>(
             } else {
                 "failed"
             };
-            let message = format!("目标设备返回状态码 {}", response.status());
-            let _ = upsert_transfer_record(
-                &app,
-                &db,
-                make_upsert(status, total_size as i64, Some(&message), true),
-            )
-            .await;
-            emit_stage(status, "FAILED", total_size, total_size, message.clone());
-            return Err(message);
+            return Err((format!("目标设备返回状态码 {}", response.status()), status));
         }
 
-        upsert_transfer_record(
-            &app,
-            &db,
-            make_upsert("received", total_size as i64, None, true),
-        )
-        .await?;
-        emit_stage(
-            "received",
-            "RECEIVED",
-            total_size,
-            total_size,
-            "目标设备已接收传输内容".to_string(),
+        let header_text = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let total = header_text("X-Content-Size").parse::<u64>().unwrap_or(0);
+        let content_sha256 = header_text("X-Content-Sha256");
+        // 帧的 aad 绑定发送方生成的传输 ID，本地记录仍使用自己的 ID
+        let remote_transfer_id = header_text("X-Transfer-Id");
+        let mut opener = FrameOpener::new(
+            session,
+            &format!("{}:{}", remote_transfer_id, content_sha256),
         );
-
-        Ok(transfer_id.clone())
+        let mut file = tokio::fs::File::create(&temp_zip)
+            .await
+            .map_err(|e| (format!("创建临时文件失败: {}", e), "failed"))?;
+        let mut received = 0_u64;
+        let mut body = response.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(|e| (format!("接收数据失败: {}", e), "failed"))?;
+            for plaintext in opener.push(&chunk).map_err(|e| (e, "failed"))? {
+                file.write_all(&plaintext)
+                    .await
+                    .map_err(|e| (format!("写入临时文件失败: {}", e), "failed"))?;
+                received += plaintext.len() as u64;
+            }
+            emit_stage(
+                "receiving",
+                "RECEIVING",
+                received,
+                total,
+                "正在接收压缩包数据".to_string(),
+            );
+        }
+        opener.finish().map_err(|e| (e, "failed"))?;
+        file.flush()
+            .await
+            .map_err(|e| (format!("写入临时文件失败: {}", e), "failed"))?;
+        secure_channel::save_expected_digest(&temp_zip, &content_sha256)
+            .map_err(|e| (e, "failed"))?;
+        Ok(received)
     }
     .await;
 
-    let _ = fs::remove_file(&temp_zip);
-    result
+    let received = match result {
+        Ok(received) => received,
+        Err((message, status)) => {
+            let _ = fs::remove_file(&temp_zip);
+            let _ = upsert_transfer_record(&app, &db, make_upsert(status, 0, Some(&message), true))
+                .await;
+            emit_stage(status, "FAILED", 0, 0, message.clone());
+            return Err(message);
+        }
+    };
+
+    upsert_transfer_record(
+        &app,
+        &db,
+        make_upsert("received", received as i64, None, false),
+    )
+    .await?;
+    emit_stage(
+        "received",
+        "RECEIVED",
+        received,
+        received,
+        "文件已接收，等待用户确认".to_string(),
+    );
+
+    // 与对方推送的内容走同一套确认与部署流程
+    let _ = app.emit(
+        "transfer_received",
+        serde_json::json!({
+            "id": transfer_id,
+            "type": transfer_type,
+            "name": item_name,
+            "from": resolved_remote_device_name,
+            "fromDeviceId": resolved_remote_device_id,
+            "fromUsername": resolved_remote_username,
+            "tempPath": temp_zip.to_string_lossy().to_string(),
+        }),
+    );
+
+    Ok(transfer_id)
 }
 
 #[tauri::command]
@@ -953,13 +1467,21 @@ pub async fn reject_received_transfer<R: Runtime>(
     remote_device_name: String,
     remote_username: String,
 ) -> Result<(), String> {
+    let temp = received_temp_path(&app, &temp_path)?;
     let state = app.state::<Arc<SharedLanState>>();
     let current_info = state.current_device_info.lock().unwrap().clone();
-    let size = fs::metadata(&temp_path)
+    let size = fs::metadata(&temp)
         .map(|meta| meta.len() as i64)
         .unwrap_or(0);
 
-    fs::remove_file(&temp_path).map_err(|e| format!("清理临时文件失败: {}", e))?;
+    // 增量传输接收的是暂存目录，拉取得到的是压缩包
+    if temp.is_dir() {
+        fs::remove_dir_all(&temp)
+    } else {
+        fs::remove_file(&temp)
+    }
+    .map_err(|e| format!("清理临时文件失败: {}", e))?;
+    let _ = fs::remove_file(secure_channel::digest_path(&temp));
 
     upsert_transfer_record(
         &app,
@@ -1029,7 +1551,7 @@ pub async fn apply_received_transfer<R: Runtime>(
     let base_path = ConfigService::get_base_path(&app)
         .map_err(|e| e.to_string())?
         .unwrap_or_default();
-    let zip_file = PathBuf::from(&temp_path);
+    let zip_file = received_temp_path(&app, &temp_path)?;
     let archive_size = fs::metadata(&zip_file)
         .map(|meta| meta.len() as i64)
         .unwrap_or(0);
@@ -1086,15 +1608,31 @@ pub async fn apply_received_transfer<R: Runtime>(
         "正在校验并解压接收到的内容".to_string(),
     );
 
-    let temp_extract_dir = app
-        .path()
-        .app_data_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join(format!("ext_{}", uuid::Uuid::new_v4()));
+    // 增量传输已在暂存目录中还原出文件结构，无需再解压
+    let is_staged_dir = zip_file.is_dir();
+    let temp_extract_dir = if is_staged_dir {
+        zip_file.clone()
+    } else {
+        app.path()
+            .app_data_dir()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(format!("ext_{}", uuid::Uuid::new_v4()))
+    };
     fs::create_dir_all(&temp_extract_dir).map_err(|e| e.to_string())?;
 
     let result: Result<String, String> = (|| {
-        transfer_service::unzip_file(&zip_file, &temp_extract_dir)?;
+        // 端到端校验必须在写入实例目录之前完成
+        secure_channel::verify_content(&zip_file)?;
+        emit_stage(
+            "applying",
+            "APPLYING",
+            20,
+            100,
+            "内容摘要校验通过".to_string(),
+        );
+        if !is_staged_dir {
+            transfer_service::unzip_file(&zip_file, &temp_extract_dir)?;
+        }
         emit_stage(
             "applying",
             "APPLYING",
@@ -1153,7 +1691,10 @@ pub async fn apply_received_transfer<R: Runtime>(
     })();
 
     let _ = fs::remove_dir_all(&temp_extract_dir);
-    let _ = fs::remove_file(&zip_file);
+    let _ = fs::remove_file(secure_channel::digest_path(&zip_file));
+    if !is_staged_dir {
+        let _ = fs::remove_file(&zip_file);
+    }
 
     match result {
         Ok(final_name) => {
@@ -1175,3 +1716,16 @@ pub async fn apply_received_transfer<R: Runtime>(
         }
     }
 }
+
+#[tauri::command]
+pub fn get_lan_skin_server_config<R: Runtime>(app: AppHandle<R>) -> LanSkinServerConfig {
+    skin_server::load_config(&app)
+}
+
+#[tauri::command]
+pub fn set_lan_skin_server_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: LanSkinServerConfig,
+) -> Result<(), String> {
+    skin_server::save_config(&app, &config)
+}
@@ -1,18 +1,20 @@
 // src-tauri/src/commands/launcher_cmd.rs
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::services::launcher::LauncherService;
-// ✅ 核心修改 1：引入新的统一账号模型
-use crate::domain::launcher::Account;
 use tauri::{AppHandle, Runtime};
 
+/// 使用后端账号库中的账号启动，account_id 为空时使用当前选中账号；令牌不经过前端
 #[tauri::command]
 pub async fn launch_game<R: Runtime>(
     app: AppHandle<R>,
     instance_id: String,
-    account: Account, // 核心修改 2：将 AccountPayload 替换为 Account
+    account_id: Option<String>,
     pre_launch_check_enabled: Option<bool>,
 ) -> AppResult<()> {
-    // 异步交由 Service 调度
+    let account =
+        crate::services::auth::account_store::resolve_launch_account(&app, account_id.as_deref())
+            .await
+            .map_err(AppError::Generic)?;
     LauncherService::launch_instance(&app, &instance_id, account, pre_launch_check_enabled).await
 }
 
@@ -6,8 +6,9 @@ use crate::domain::library::{
 use crate::services::db_service::AppDatabase;
 use crate::services::library_service::LibraryService;
 use crate::services::webdav_sync_service::{
+    InstanceConflictChoice, InstanceSyncOutcome, InstanceSyncState, WebDavInstanceSyncResult,
     WebDavRemoteSaveBackup, WebDavSaveBackupDeleteResult, WebDavSaveBackupDownloadResult,
-    WebDavSyncService,
+    WebDavSaveBackupPruneResult, WebDavSyncPlan, WebDavSyncScope, WebDavSyncService,
 };
 use tauri::{AppHandle, Runtime, State};
 
@@ -187,24 +188,37 @@ pub async fn sync_webdav_favorites<R: Runtime>(
     app: AppHandle<R>,
     db: State<'_, AppDatabase>,
     config: WebDavSyncConfig,
+    confirmed_plan: Option<String>,
 ) -> Result<WebDavFavoriteSyncResult, String> {
-    WebDavSyncService::sync_favorites(&app, &db.pool, &config).await
+    WebDavSyncService::sync_favorites(&app, &db.pool, &config, confirmed_plan.as_deref()).await
 }
 
 #[tauri::command]
 pub async fn sync_webdav_skin_assets<R: Runtime>(
     app: AppHandle<R>,
     config: WebDavSyncConfig,
+    confirmed_plan: Option<String>,
 ) -> Result<WebDavSkinSyncResult, String> {
-    WebDavSyncService::sync_skin_assets(&app, &config).await
+    WebDavSyncService::sync_skin_assets(&app, &config, confirmed_plan.as_deref()).await
 }
 
 #[tauri::command]
 pub async fn sync_webdav_save_backups<R: Runtime>(
     app: AppHandle<R>,
     config: WebDavSyncConfig,
+    confirmed_plan: Option<String>,
 ) -> Result<WebDavSaveBackupSyncResult, String> {
-    WebDavSyncService::sync_save_backups(&app, &config).await
+    WebDavSyncService::sync_save_backups(&app, &config, confirmed_plan.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn plan_webdav_sync<R: Runtime>(
+    app: AppHandle<R>,
+    db: State<'_, AppDatabase>,
+    config: WebDavSyncConfig,
+    scope: WebDavSyncScope,
+) -> Result<WebDavSyncPlan, String> {
+    WebDavSyncService::plan_sync(&app, &db.pool, &config, scope).await
 }
 
 #[tauri::command]
@@ -244,6 +258,14 @@ pub async fn delete_webdav_save_backup(
     WebDavSyncService::delete_remote_save_backup(&config, &backup_id).await
 }
 
+#[tauri::command]
+pub async fn prune_webdav_save_backups<R: Runtime>(
+    app: AppHandle<R>,
+    config: WebDavSyncConfig,
+) -> Result<WebDavSaveBackupPruneResult, String> {
+    WebDavSyncService::prune_remote_save_backups(&app, &config).await
+}
+
 #[tauri::command]
 pub async fn delete_webdav_keymap(
     config: WebDavSyncConfig,
@@ -252,3 +274,38 @@ pub async fn delete_webdav_keymap(
     WebDavSyncService::delete_webdav_keymap(&config, &filename).await
 }
 
+#[tauri::command]
+pub async fn sync_webdav_instances<R: Runtime>(
+    app: AppHandle<R>,
+    config: WebDavSyncConfig,
+    confirmed_plan: Option<String>,
+) -> Result<WebDavInstanceSyncResult, String> {
+    WebDavSyncService::sync_instances(&app, &config, confirmed_plan.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn resolve_webdav_instance_conflict<R: Runtime>(
+    app: AppHandle<R>,
+    config: WebDavSyncConfig,
+    instance_id: String,
+    keep: String,
+) -> Result<InstanceSyncOutcome, String> {
+    let choice = InstanceConflictChoice::from_input(&keep)?;
+    WebDavSyncService::resolve_instance_conflict(&app, &config, &instance_id, choice).await
+}
+
+#[tauri::command]
+pub fn set_webdav_instance_sync_enabled<R: Runtime>(
+    app: AppHandle<R>,
+    instance_id: String,
+    enabled: bool,
+) -> Result<InstanceSyncState, String> {
+    WebDavSyncService::set_instance_sync_enabled(&app, &instance_id, enabled)
+}
+
+#[tauri::command]
+pub fn list_webdav_instance_sync_states<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<InstanceSyncState>, String> {
+    WebDavSyncService::list_instance_sync_states(&app)
+}
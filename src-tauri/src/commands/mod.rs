@@ -128,6 +128,8 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         instance::save_cmd::delete_save_backup,
         instance::save_cmd::verify_save_restore,
         instance::save_cmd::restore_save_backup,
+        instance::save_cmd::verify_partial_save_restore,
+        instance::save_cmd::restore_save_backup_partial,
         instance::save_cmd::get_save_backups,
         instance::resource_cmd::open_resource_folder,
         instance::resource_cmd::extract_resourcepack_icon,
@@ -144,6 +146,13 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         instance::save_cmd::set_exit_backup_enabled,
         instance::save_cmd::get_backup_all_worlds_on_exit_enabled,
         instance::save_cmd::set_backup_all_worlds_on_exit_enabled,
+        instance::save_cmd::get_save_backup_retention,
+        instance::save_cmd::set_save_backup_retention,
+        instance::save_cmd::prune_save_backups,
+        instance::save_cmd::get_periodic_save_backup,
+        instance::save_cmd::set_periodic_save_backup,
+        instance::save_cmd::optimize_save_world,
+        instance::save_cmd::diff_save_backups,
         resource_cmd::get_ore_project_detail,
         resource_cmd::get_ore_project_versions,
         resource_cmd::download_resource,
@@ -168,6 +177,11 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         modpack_cmd::check_modpack_update,
         modpack_cmd::execute_modpack_upgrade,
         modpack_cmd::rollback_modpack_upgrade,
+        modpack_cmd::get_modpack_merge_conflicts,
+        modpack_cmd::resolve_modpack_merge_conflict,
+        modpack_cmd::list_remote_modpack_updates,
+        modpack_cmd::prepare_remote_modpack_update,
+        modpack_cmd::verify_pipack,
         modpack_cmd::detect_third_party_launcher_sources,
         modpack_cmd::import_third_party_launcher_source,
         modpack_cmd::scan_instances_in_dir,
@@ -184,7 +198,8 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         fs_cmd::open_path_in_file_manager,
         fs_cmd::get_default_data_directory,
         auth_cmd::refresh_microsoft_token,
-        auth_cmd::login_authlib,
+        auth_cmd::start_authlib_login,
+        auth_cmd::finish_authlib_login,
         auth_cmd::get_or_fetch_account_avatar,
         auth_cmd::ensure_account_skin,
         auth_cmd::get_wardrobe_profile,
@@ -197,6 +212,15 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         auth_cmd::set_wardrobe_skin_asset_variant,
         auth_cmd::set_active_wardrobe_skin_offline,
         auth_cmd::update_active_wardrobe_skin_variant,
+        auth_cmd::get_account_store_status,
+        auth_cmd::unlock_account_store,
+        auth_cmd::list_accounts,
+        auth_cmd::add_account,
+        auth_cmd::remove_account,
+        auth_cmd::select_account,
+        auth_cmd::import_frontend_accounts,
+        auth_cmd::check_minecraft_profile_name,
+        auth_cmd::create_minecraft_profile,
         lan_cmd::scan_lan_devices,
         lan_cmd::send_trust_request,
         lan_cmd::get_trusted_devices,
@@ -208,11 +232,24 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         lan_cmd::get_instance_saves,
         lan_cmd::get_transfer_history,
         lan_cmd::push_to_device,
+        lan_cmd::get_remote_catalog,
+        lan_cmd::pull_from_device,
         lan_cmd::reject_received_transfer,
         lan_cmd::apply_received_transfer,
         lan_cmd::update_lan_device_info,
         lan_cmd::remove_trusted_device,
         lan_cmd::verify_trusted_devices,
+        lan_cmd::add_manual_peer,
+        lan_cmd::get_pairing_info,
+        lan_cmd::get_known_peers,
+        lan_cmd::forget_known_peer,
+        lan_cmd::revoke_trusted_device,
+        lan_cmd::set_device_trust_expiry,
+        lan_cmd::get_trust_audit_log,
+        lan_cmd::rotate_device_key,
+        lan_cmd::retry_key_handover,
+        lan_cmd::get_lan_skin_server_config,
+        lan_cmd::set_lan_skin_server_config,
         network_cmd::run_network_test,
         network_cmd::fetch_donors,
         network_cmd::proxy_fetch,
@@ -249,10 +286,16 @@ pub fn register<R: Runtime>(builder: Builder<R>) -> Builder<R> {
         library_cmd::sync_webdav_favorites,
         library_cmd::sync_webdav_skin_assets,
         library_cmd::sync_webdav_save_backups,
+        library_cmd::plan_webdav_sync,
         library_cmd::list_webdav_save_backups,
         library_cmd::download_webdav_save_backup,
         library_cmd::delete_webdav_save_backup,
+        library_cmd::prune_webdav_save_backups,
         library_cmd::delete_webdav_keymap,
+        library_cmd::sync_webdav_instances,
+        library_cmd::resolve_webdav_instance_conflict,
+        library_cmd::set_webdav_instance_sync_enabled,
+        library_cmd::list_webdav_instance_sync_states,
         wiki_cmd::get_wiki_url,
         logshare_cmd::share_minecraft_log,
         logshare_cmd::analyse_minecraft_log,
@@ -1,13 +1,15 @@
 use crate::domain::instance::ServerBinding;
 use crate::domain::modpack::{
-    ImportResult, MissingRuntime, ModpackMetadata, ThirdPartyImportResult, ThirdPartyImportSource,
-    VerifyInstanceRuntimeResult,
+    ImportResult, MissingRuntime, ModpackMergeReport, ModpackMergeTarget, ModpackMetadata,
+    PiPackVerification, RemoteModpackUpdatePreview, RemoteModpackUpdates, ThirdPartyImportResult, ThirdPartyImportSource, VerifyInstanceRuntimeResult,
 };
 use crate::services::import_service::{local_instance_service, third_party_service};
 use crate::services::instance::verify_service;
 use crate::services::modpack_service;
+use crate::services::db_service::AppDatabase;
 use crate::services::modpack_service::export::ExportConfig;
-use tauri::{AppHandle, Runtime};
+use crate::services::modpack_service::pipack_signing;
+use tauri::{AppHandle, Runtime, State};
 
 #[tauri::command]
 pub fn import_local_instances_folders<R: Runtime>(
@@ -42,8 +44,8 @@ pub async fn download_missing_runtimes<R: Runtime>(
 }
 
 #[tauri::command]
-pub fn parse_modpack_metadata(path: String) -> Result<ModpackMetadata, String> {
-    modpack_service::parse_modpack(&path)
+pub async fn parse_modpack_metadata(path: String) -> Result<ModpackMetadata, String> {
+    modpack_service::parse_modpack_location(&path).await
 }
 
 #[tauri::command]
@@ -84,6 +86,17 @@ pub async fn export_modpack<R: Runtime>(
     modpack_service::export::execute_export(&app, config).await
 }
 
+/// 校验 PiPack 的签名与文件完整性，并给出签名设备的信任状态
+#[tauri::command]
+pub async fn verify_pipack(
+    db: State<'_, AppDatabase>,
+    path: String,
+) -> Result<PiPackVerification, String> {
+    let mut verification = pipack_signing::verify_pipack_archive(&path)?;
+    pipack_signing::resolve_signer_trust(&db.pool, &mut verification).await?;
+    Ok(verification)
+}
+
 #[tauri::command]
 pub fn detect_third_party_launcher_sources<R: Runtime>(
     app: AppHandle<R>,
@@ -115,8 +128,52 @@ pub async fn execute_modpack_upgrade<R: Runtime>(
     instance_id: String,
     new_pack_path: String,
     skip_backup: Option<bool>,
-) -> Result<(), String> {
-    modpack_service::execute_modpack_upgrade(&app, &instance_id, &new_pack_path, skip_backup).await
+    remote_version_id: Option<String>,
+) -> Result<ModpackMergeReport, String> {
+    modpack_service::execute_modpack_upgrade(
+        &app,
+        &instance_id,
+        &new_pack_path,
+        skip_backup,
+        remote_version_id,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn list_remote_modpack_updates<R: Runtime>(
+    app: AppHandle<R>,
+    instance_id: String,
+) -> Result<RemoteModpackUpdates, String> {
+    modpack_service::check_remote_updates(&app, &instance_id).await
+}
+
+#[tauri::command]
+pub async fn prepare_remote_modpack_update<R: Runtime>(
+    app: AppHandle<R>,
+    instance_id: String,
+    version_id: String,
+) -> Result<RemoteModpackUpdatePreview, String> {
+    modpack_service::prepare_remote_update(&app, &instance_id, &version_id).await
+}
+
+#[tauri::command]
+pub fn get_modpack_merge_conflicts<R: Runtime>(
+    app: AppHandle<R>,
+    instance_id: String,
+) -> Result<Option<ModpackMergeReport>, String> {
+    modpack_service::get_modpack_merge_conflicts(&app, &instance_id)
+}
+
+#[tauri::command]
+pub fn resolve_modpack_merge_conflict<R: Runtime>(
+    app: AppHandle<R>,
+    instance_id: String,
+    target: ModpackMergeTarget,
+    path: String,
+    use_theirs: bool,
+) -> Result<ModpackMergeReport, String> {
+    modpack_service::resolve_modpack_merge_conflict(&app, &instance_id, target, &path, use_theirs)
 }
 
 #[tauri::command]
@@ -1,30 +1,32 @@
 use serde::Serialize;
 
+use crate::domain::auth::AccountType;
+use crate::services::auth::account_store::AccountStore;
 use crate::services::auth::{microsoft, minecraft, xbox};
 use crate::services::social_service::{self, JavaFriendStatus};
-
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct JavaFriendsAuthUpdate {
-    pub access_token: String,
-    pub refresh_token: String,
-    pub expires_at: i64,
-}
+use tauri::{AppHandle, Manager, Runtime};
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JavaFriendsStatusPayload {
     pub friends: Vec<JavaFriendStatus>,
-    pub auth_update: JavaFriendsAuthUpdate,
 }
 
+/// 刷新令牌从账号库按 id 取用，续期后的令牌写回账号库，不经过前端
 #[tauri::command]
-pub async fn get_java_friends_status(
-    refresh_token: String,
+pub async fn get_java_friends_status<R: Runtime>(
+    app: AppHandle<R>,
+    account_id: String,
 ) -> Result<JavaFriendsStatusPayload, String> {
-    if refresh_token.trim().is_empty() {
-        return Err("Java 好友在线状态需要 Microsoft 账号，请重新登录正版账号后重试".to_string());
-    }
+    let store = app.state::<AccountStore>();
+    let mut account = store.get(&app, Some(&account_id))?;
+    let refresh_token = account
+        .refresh_token
+        .clone()
+        .filter(|token| account.account_type == AccountType::Microsoft && !token.trim().is_empty())
+        .ok_or_else(|| {
+            "Java 好友在线状态需要 Microsoft 账号，请重新登录正版账号后重试".to_string()
+        })?;
 
     let (ms_access_token, new_refresh_token) = microsoft::refresh_token(&refresh_token).await?;
     let xbl_token = xbox::auth_xbl(&ms_access_token).await?;
@@ -50,12 +52,10 @@ pub async fn get_java_friends_status(
         }
     };
 
-    Ok(JavaFriendsStatusPayload {
-        friends,
-        auth_update: JavaFriendsAuthUpdate {
-            access_token: mc_access_token,
-            refresh_token: new_refresh_token,
-            expires_at: chrono::Utc::now().timestamp() + 86400,
-        },
-    })
+    account.access_token = mc_access_token;
+    account.refresh_token = Some(new_refresh_token);
+    account.expires_at = Some(chrono::Utc::now().timestamp() + 86400);
+    store.upsert(&app, account)?;
+
+    Ok(JavaFriendsStatusPayload { friends })
 }
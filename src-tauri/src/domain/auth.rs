@@ -42,6 +42,108 @@ pub struct Account {
     pub skin_url: Option<String>,
     pub cape_url: Option<String>,
     pub authlib_api_root: Option<String>,
+    /// 登录时预取的皮肤站元数据，启动时作为 authlib-injector 的 prefetched 参数
+    #[serde(default)]
+    pub authlib_server: Option<AuthlibServerMeta>,
+    /// 微软账号的 Java 版授权来源
+    #[serde(default)]
+    pub minecraft_ownership: Option<MinecraftOwnership>,
+}
+
+/// 由 entitlements/mcstore 判定的 Java 版授权状态
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MinecraftOwnership {
+    GamePass,
+    Owned,
+    NoLicense,
+    /// 授权条目为空但能取到档案（例如授权接口暂时缺项），来源无法确定
+    Unknown,
+}
+
+/// 微软登录链在 Minecraft 阶段的类型化错误，前端按 kind 分支处理
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum MinecraftLoginError {
+    /// 账号没有 Java 版授权
+    NoLicense {
+        message: String,
+    },
+    /// 已有授权但尚未创建角色，凭 ticket 检查并创建角色名
+    #[serde(rename_all = "camelCase")]
+    NoProfile {
+        ticket: String,
+        ownership: MinecraftOwnership,
+        message: String,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for MinecraftLoginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MinecraftLoginError::NoLicense { message }
+            | MinecraftLoginError::NoProfile { message, .. }
+            | MinecraftLoginError::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for MinecraftLoginError {
+    fn from(message: String) -> Self {
+        MinecraftLoginError::Failed { message }
+    }
+}
+
+impl From<MinecraftLoginError> for String {
+    fn from(error: MinecraftLoginError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 角色名可用性，对应 profile/name/{name}/available 的 status
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProfileNameStatus {
+    Available,
+    Duplicate,
+    NotAllowed,
+}
+
+/// Yggdrasil API 根地址返回的皮肤站元数据
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthlibServerMeta {
+    pub server_name: Option<String>,
+    pub implementation_name: Option<String>,
+    pub implementation_version: Option<String>,
+    pub homepage: Option<String>,
+    pub register: Option<String>,
+    #[serde(default)]
+    pub skin_domains: Vec<String>,
+    pub signature_public_key: Option<String>,
+    /// 元数据原文的 base64，对应 -Dauthlibinjector.yggdrasil.prefetched
+    pub prefetched: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthlibProfileOption {
+    pub id: String,
+    pub name: String,
+}
+
+/// 皮肤站账号拥有多个角色时返回给前端的选择信息，令牌留在后端
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthlibLoginChallenge {
+    pub ticket: String,
+    pub api_root: String,
+    pub server: AuthlibServerMeta,
+    pub profiles: Vec<AuthlibProfileOption>,
+    pub selected_profile_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -89,3 +191,59 @@ pub struct WardrobeSkinLibrary {
     #[serde(default)]
     pub assets: Vec<WardrobeSkinAsset>,
 }
+
+/// 后端账号库对外暴露的账号摘要，不含任何令牌
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    pub id: String,
+    pub account_type: AccountType,
+    pub username: String,
+    pub uuid: String,
+    pub expires_at: Option<i64>,
+    pub skin_url: Option<String>,
+    pub cape_url: Option<String>,
+    pub authlib_api_root: Option<String>,
+    pub authlib_server: Option<AuthlibServerMeta>,
+    pub minecraft_ownership: Option<MinecraftOwnership>,
+    pub has_refresh_token: bool,
+    pub selected: bool,
+}
+
+/// 账号库的密钥来源：系统密钥环或用户口令
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountKeySource {
+    Keyring,
+    Passphrase,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStoreStatus {
+    pub exists: bool,
+    pub unlocked: bool,
+    pub key_source: Option<AccountKeySource>,
+    /// 密钥环不可用或账号库使用口令加密，需要先调用 unlock_account_store
+    pub needs_passphrase: bool,
+}
+
+/// 新增账号的请求，登录流程在后端完成，令牌不经过前端
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AddAccountRequest {
+    #[serde(rename_all = "camelCase")]
+    Offline { username: String },
+    #[serde(rename_all = "camelCase")]
+    Microsoft { device_code: String, interval: u64 },
+    /// 系统浏览器 + 本地回环回调的授权码流
+    MicrosoftBrowser,
+    #[serde(rename_all = "camelCase")]
+    Authlib {
+        api_root: String,
+        username: String,
+        password: String,
+        #[serde(default)]
+        profile_id: Option<String>,
+    },
+}
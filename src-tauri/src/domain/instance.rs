@@ -87,6 +87,9 @@ pub struct InstanceConfig {
     pub modpack_version: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub modpack_source: Option<String>,
+    /// 远程平台上当前安装的版本 ID（Modrinth version id / CurseForge file id）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modpack_version_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,6 +22,9 @@ pub struct TrustedDevice {
     pub public_key_b64: String,
     pub trusted_at: i64,
     pub trust_level: String,
+    /// 信任到期时间（Unix 秒），None 表示永不过期
+    #[serde(default)]
+    pub expires_at: Option<i64>,
 }
 
 // 3. 局域网扫描到的设备模型 (用于前端雷达大盘展示)
@@ -71,6 +74,8 @@ pub struct DeviceInitInfo {
     pub instance_name: Option<String>, // 当前正在玩的整合包名字
     pub instance_id: Option<String>,   // 整合包ID
     pub bg_url: String,                // 个人中心背景图的拉取接口 (例如 "/device/bg")
+    #[serde(default)]
+    pub public_key: String,            // 设备公钥，手动添加设备时用于核对身份
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -106,3 +111,143 @@ pub struct TransferProgressEvent {
     pub total: u64,
     pub message: String,
 }
+
+/// 局域网离线皮肤服务设置；host_address 为空时使用本机作为皮肤服务
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSkinServerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub host_address: Option<String>,
+}
+
+// 局域网共享目录：对已配对设备公开的实例与存档
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSharedSave {
+    pub name: String,
+    pub size: u64,
+    pub last_modified: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LanSharedInstance {
+    pub id: String,
+    pub name: String,
+    pub mc_version: String,
+    pub loader_type: String,
+    pub loader_version: String,
+    pub mod_count: u32,
+    pub size: u64,
+    pub saves: Vec<LanSharedSave>,
+}
+
+// 接收方主动拉取实例或存档的请求体
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LanPullRequest {
+    pub transfer_type: String,
+    pub target_id: String,
+    pub save_name: Option<String>,
+}
+
+// 增量传输：发送方提供文件清单，接收方只索取本地缺失的部分
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaFileEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha1: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaOffer {
+    pub transfer_id: String,
+    pub transfer_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub directories: Vec<String>,
+    pub files: Vec<DeltaFileEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaNeededFile {
+    pub path: String,
+    /// 已接收的字节数，断点续传时从这里继续
+    pub offset: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaOfferResponse {
+    pub needed: Vec<DeltaNeededFile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaCompleteRequest {
+    pub transfer_id: String,
+    /// 发送方按文件清单计算的端到端摘要，接收方部署前重新校验
+    pub content_sha256: String,
+}
+
+// 手动添加设备：本机的地址、配对码与配对二维码
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LanPairingInfo {
+    pub address: String,
+    pub code: String,
+    pub uri: String,
+    pub qr_data_uri: String,
+}
+
+// 记住了地址的设备，启动器通过 /device/init 探测在线状态
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LanKnownPeer {
+    pub device_id: String,
+    pub device_name: String,
+    pub username: String,
+    pub trust_level: String,
+    pub address: String,
+    pub online: bool,
+    pub last_seen: Option<i64>,
+    pub info: Option<DeviceInitInfo>,
+}
+
+// 信任审计日志：授予、降级、吊销、到期设置、密钥轮换与每次访问
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustAuditEntry {
+    pub id: i64,
+    pub device_id: String,
+    pub event: String,
+    pub detail: String,
+    pub created_at: i64,
+}
+
+// 密钥轮换交接：旧密钥为新密钥背书，新密钥证明持有，对方据此更新记录的公钥
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyHandover {
+    pub device_id: String,
+    pub old_public_key: String,
+    pub new_public_key: String,
+    pub timestamp: i64,
+    pub old_signature: String,
+    pub new_signature: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationReport {
+    pub public_key: String,
+    /// 已确认更新公钥的设备
+    pub delivered: Vec<String>,
+    /// 暂时无法联系的设备，稍后可重试交接
+    pub pending: Vec<String>,
+}
@@ -21,6 +21,7 @@ pub struct AuthSession {
     pub user_type: String,
     pub authlib_api_root: Option<String>,
     pub authlib_injector_jar: Option<String>,
+    pub authlib_prefetched: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,4 +55,6 @@ pub struct Account {
     pub expires_at: Option<i64>,
     pub skin_url: Option<String>,
     pub authlib_api_root: Option<String>,
+    #[serde(default)]
+    pub authlib_prefetched: Option<String>,
 }
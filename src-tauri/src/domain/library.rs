@@ -166,6 +166,65 @@ pub struct WebDavSyncConfig {
     pub device_id: String,
     #[serde(default = "default_save_backup_mode")]
     pub save_backup_mode: String,
+    /// 远端存储后端；缺省为 WebDAV，此时使用上面的 baseUrl / username / password
+    #[serde(default)]
+    pub store: RemoteStoreSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteStoreKind {
+    #[default]
+    Webdav,
+    S3,
+    Local,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct S3StoreSettings {
+    /// 例如 `https://s3.amazonaws.com`、`http://nas.local:9000`
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub bucket: String,
+    #[serde(default)]
+    pub access_key_id: String,
+    #[serde(default)]
+    pub secret_access_key: String,
+    /// MinIO 等自建服务通常需要 path-style 访问
+    #[serde(default)]
+    pub path_style: bool,
+    /// 桶内的根前缀，所有同步数据放在该前缀下
+    #[serde(default)]
+    pub prefix: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStoreSettings {
+    #[serde(default)]
+    pub kind: RemoteStoreKind,
+    #[serde(default)]
+    pub s3: S3StoreSettings,
+    /// 本地文件夹 / SMB 挂载 / Syncthing 目录的绝对路径
+    #[serde(default)]
+    pub local_path: String,
+    /// 客户端端到端加密；开启后远端只保存密文内容与密文文件名
+    #[serde(default)]
+    pub encryption: RemoteEncryptionSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteEncryptionSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 加密口令只保存在本机，丢失后远端数据无法恢复
+    #[serde(default)]
+    pub passphrase: String,
 }
 
 fn default_save_backup_mode() -> String {
@@ -2,8 +2,10 @@ use crate::domain::instance::ServerBinding;
 use crate::domain::mod_manifest::{ModFileHash, ModFileState, ModManifestSource};
 use serde::{Deserialize, Serialize};
 
-pub const PIPACK_FORMAT_VERSION: u32 = 1;
+/// v2 新增 `integrity` 文件哈希清单与可选的 `pi_signature.json`；v1 包仍可导入
+pub const PIPACK_FORMAT_VERSION: u32 = 2;
 pub const PIPACK_MANIFEST_FILE: &str = "pi_manifest.json";
+pub const PIPACK_SIGNATURE_FILE: &str = "pi_signature.json";
 pub const PIPACK_OVERRIDES_DIR: &str = "overrides";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,6 +38,57 @@ pub struct PiPackManifest {
     pub mods: Vec<PiPackModEntry>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub server: Option<ServerBinding>,
+    /// 包内每个 overrides 文件的 sha256，签名覆盖整个清单因此也覆盖这些哈希
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub integrity: Vec<PiPackFileHash>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PiPackFileHash {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// `pi_signature.json`：对 `pi_manifest.json` 原始字节的 ed25519 签名
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PiPackSignature {
+    pub algorithm: String,
+    pub device_id: String,
+    pub device_name: String,
+    pub public_key_b64: String,
+    pub signed_at: String,
+    pub signature_b64: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PiPackVerification {
+    pub signed: bool,
+    pub signature_valid: bool,
+    pub signer_device_id: Option<String>,
+    pub signer_device_name: Option<String>,
+    pub signer_public_key_b64: Option<String>,
+    /// 签名者在 trusted_devices 中且公钥一致
+    pub trusted: bool,
+    pub trust_level: Option<String>,
+    pub integrity_checked: bool,
+    pub verified_files: usize,
+    pub mismatched_files: Vec<String>,
+    pub missing_files: Vec<String>,
+    pub unlisted_files: Vec<String>,
+}
+
+impl PiPackVerification {
+    /// 签名无效或文件与清单不符时视为被篡改
+    pub fn is_tampered(&self) -> bool {
+        (self.signed && !self.signature_valid)
+            || !self.mismatched_files.is_empty()
+            || !self.missing_files.is_empty()
+            || (self.signed && !self.unlisted_files.is_empty())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -191,3 +244,120 @@ pub struct ModpackUpgradeInfo {
     pub backup_original_loader_version: Option<String>,
 }
 
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModpackMergeTarget {
+    Mod,
+    Config,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModpackMergeAction {
+    Added,
+    Updated,
+    Removed,
+    KeptUserChange,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackMergeChange {
+    pub target: ModpackMergeTarget,
+    /// 模组为身份标识（platform:projectId 或文件名），配置为实例内相对路径
+    pub path: String,
+    pub action: ModpackMergeAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackMergeConflict {
+    pub target: ModpackMergeTarget,
+    pub path: String,
+    /// 当前实例中的文件名（模组）或路径（配置），None 表示用户已删除
+    pub ours: Option<String>,
+    /// 新版整合包中的文件名（模组）或路径（配置），None 表示新版已移除
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackMergeReport {
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    pub has_baseline: bool,
+    pub applied: Vec<ModpackMergeChange>,
+    pub conflicts: Vec<ModpackMergeConflict>,
+}
+
+/// 整合包在远程平台上的来源（platform 为 "modrinth" 或 "curseforge"）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackRemoteRef {
+    pub platform: String,
+    pub project_id: String,
+    pub version_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteModpackVersion {
+    pub version_id: String,
+    pub name: String,
+    pub version_number: String,
+    pub changelog: Option<String>,
+    pub published_at: String,
+    pub file_name: String,
+    pub download_url: String,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteModpackUpdates {
+    pub instance_id: String,
+    pub platform: String,
+    pub project_id: String,
+    pub current_version: Option<String>,
+    pub current_version_id: Option<String>,
+    /// 比当前版本更新且与实例游戏版本/加载器兼容的版本，最新的在前
+    pub versions: Vec<RemoteModpackVersion>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackModDiffEntry {
+    pub identity: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackModDiff {
+    pub added: Vec<ModpackModDiffEntry>,
+    pub removed: Vec<ModpackModDiffEntry>,
+    pub updated: Vec<ModpackModDiffEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteModpackUpdatePreview {
+    pub instance_id: String,
+    pub version: RemoteModpackVersion,
+    /// 已下载到本地的整合包文件，作为 `execute_modpack_upgrade` 的 newPackPath
+    pub pack_path: String,
+    pub diff: ModpackModDiff,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackUpdateAvailableEvent {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub current_version: Option<String>,
+    pub latest_version: String,
+    pub latest_version_id: String,
+}
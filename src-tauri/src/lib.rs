@@ -87,7 +87,8 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_opener::init())
-        .manage(lan_state.clone());
+        .manage(lan_state.clone())
+        .manage(services::auth::account_store::AccountStore::default());
 
     builder = commands::register(builder);
 
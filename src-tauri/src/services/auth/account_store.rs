@@ -0,0 +1,679 @@
+// src-tauri/src/services/auth/account_store.rs
+//
+// 后端持有的加密账号库。
+// 账号（含访问令牌与刷新令牌）序列化后用 XChaCha20-Poly1305 加密，保存在 `{config}/accounts.vault`。
+// 密钥优先保存在系统密钥环；密钥环不可用时退回到用户口令经 Argon2id 派生的密钥。
+// 前端只拿到不含令牌的 AccountSummary，登录、刷新、启动都在后端按账号 id 取用令牌。
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::domain::auth::{
+    Account, AccountKeySource, AccountStoreStatus, AccountSummary, AccountType, AddAccountRequest,
+    MinecraftLoginError,
+};
+use crate::domain::launcher;
+use crate::services::remote_store::crypto::{
+    self, DEFAULT_ITERATIONS, DEFAULT_MEMORY_KIB, DEFAULT_PARALLELISM, KEY_LEN, SALT_LEN,
+};
+
+const VAULT_FILE: &str = "accounts.vault";
+const VAULT_AAD: &[u8] = b"pilauncher-account-vault-v1";
+const KEYRING_SERVICE: &str = "pilauncher";
+const KEYRING_USER: &str = "account-vault-key";
+
+/// 离线账号沿用前端的占位令牌
+const OFFLINE_ACCESS_TOKEN: &str = "offline_local_token";
+/// 微软令牌剩余有效期不足 5 分钟时，启动前先刷新
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct VaultData {
+    #[serde(default)]
+    accounts: Vec<Account>,
+    selected_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    key_source: AccountKeySource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kdf: Option<KdfParams>,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct VaultKey {
+    key: [u8; KEY_LEN],
+    source: AccountKeySource,
+    kdf: Option<KdfParams>,
+}
+
+/// 账号库状态，解锁后的密钥只保存在内存中
+#[derive(Default)]
+pub struct AccountStore {
+    key: Mutex<Option<VaultKey>>,
+}
+
+fn vault_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(VAULT_FILE))
+        .map_err(|e| format!("无法获取系统配置目录: {}", e))
+}
+
+fn read_vault_file(path: &Path) -> Result<Option<VaultFile>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取账号库失败: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("账号库文件已损坏: {}", e))
+}
+
+fn write_vault_file(path: &Path, file: &VaultFile) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+    // 先写临时文件再替换，避免写到一半断电导致账号库损坏
+    let tmp = path.with_extension("vault.tmp");
+    fs::write(&tmp, content).map_err(|e| format!("写入账号库失败: {}", e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("写入账号库失败: {}", e))
+}
+
+fn new_kdf_params() -> KdfParams {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    KdfParams {
+        salt: STANDARD.encode(salt),
+        memory_kib: DEFAULT_MEMORY_KIB,
+        iterations: DEFAULT_ITERATIONS,
+        parallelism: DEFAULT_PARALLELISM,
+    }
+}
+
+fn derive_passphrase_key(passphrase: &str, kdf: &KdfParams) -> Result<[u8; KEY_LEN], String> {
+    if passphrase.is_empty() {
+        return Err("账号库口令不能为空".to_string());
+    }
+    let salt = STANDARD
+        .decode(&kdf.salt)
+        .map_err(|_| "账号库密钥参数已损坏".to_string())?;
+    let mut key = [0u8; KEY_LEN];
+    crypto::derive_argon2id(
+        passphrase,
+        &salt,
+        kdf.memory_kib,
+        kdf.iterations,
+        kdf.parallelism,
+        &mut key,
+    )
+    .map_err(|e| format!("派生账号库密钥失败: {}", e))?;
+    Ok(key)
+}
+
+fn seal_vault(key: &VaultKey, data: &VaultData) -> Result<VaultFile, String> {
+    let plaintext = serde_json::to_vec(data).map_err(|e| e.to_string())?;
+    let (nonce, ciphertext) = crypto::seal_detached(&key.key, &plaintext, VAULT_AAD)
+        .map_err(|_| "加密账号库失败".to_string())?;
+    Ok(VaultFile {
+        version: 1,
+        key_source: key.source,
+        kdf: key.kdf.clone(),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+fn open_vault(key: &[u8; KEY_LEN], file: &VaultFile) -> Result<VaultData, String> {
+    if file.version != 1 {
+        return Err(format!("不支持的账号库版本: v{}", file.version));
+    }
+    let nonce = STANDARD
+        .decode(&file.nonce)
+        .ok()
+        .filter(|nonce| nonce.len() == crypto::NONCE_LEN)
+        .ok_or_else(|| "账号库文件已损坏".to_string())?;
+    let ciphertext = STANDARD
+        .decode(&file.ciphertext)
+        .map_err(|_| "账号库文件已损坏".to_string())?;
+    let plaintext = crypto::open_detached(key, &nonce, &ciphertext, VAULT_AAD)
+        .map_err(|_| "账号库解密失败，口令或密钥不正确".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("账号库内容已损坏: {}", e))
+}
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("系统密钥环不可用: {}", e))
+}
+
+/// 从系统密钥环读取账号库密钥，条目不存在时返回 None
+fn keyring_load() -> Result<Option<[u8; KEY_LEN]>, String> {
+    match keyring_entry()?.get_password() {
+        Ok(encoded) => {
+            let bytes = STANDARD
+                .decode(encoded.trim())
+                .map_err(|_| "系统密钥环中的账号库密钥已损坏".to_string())?;
+            let key: [u8; KEY_LEN] = bytes
+                .try_into()
+                .map_err(|_| "系统密钥环中的账号库密钥已损坏".to_string())?;
+            Ok(Some(key))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("系统密钥环不可用: {}", e)),
+    }
+}
+
+fn keyring_create() -> Result<[u8; KEY_LEN], String> {
+    let mut key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    keyring_entry()?
+        .set_password(&STANDARD.encode(key))
+        .map_err(|e| format!("系统密钥环不可用: {}", e))?;
+    Ok(key)
+}
+
+fn summarize(account: &Account, selected_id: Option<&str>) -> AccountSummary {
+    AccountSummary {
+        id: account.id.clone(),
+        account_type: account.account_type.clone(),
+        username: account.username.clone(),
+        uuid: account.uuid.clone(),
+        expires_at: account.expires_at,
+        skin_url: account.skin_url.clone(),
+        cape_url: account.cape_url.clone(),
+        authlib_api_root: account.authlib_api_root.clone(),
+        authlib_server: account.authlib_server.clone(),
+        minecraft_ownership: account.minecraft_ownership,
+        has_refresh_token: account.refresh_token.is_some(),
+        selected: selected_id == Some(account.id.as_str()),
+    }
+}
+
+fn summaries(data: &VaultData) -> Vec<AccountSummary> {
+    data.accounts
+        .iter()
+        .map(|account| summarize(account, data.selected_id.as_deref()))
+        .collect()
+}
+
+/// 插入或替换同 id 账号；账号库为空或尚未选中账号时自动选中它
+fn upsert_account(data: &mut VaultData, account: Account) {
+    if data.selected_id.is_none() {
+        data.selected_id = Some(account.id.clone());
+    }
+    match data.accounts.iter_mut().find(|a| a.id == account.id) {
+        Some(existing) => *existing = account,
+        None => data.accounts.push(account),
+    }
+}
+
+/// 合并前端旧版持久化的账号：已存在的账号只在导入数据的令牌更新时才覆盖
+fn merge_imported(data: &mut VaultData, accounts: Vec<Account>, selected_id: Option<String>) {
+    for account in accounts {
+        if account.id.trim().is_empty() {
+            continue;
+        }
+        match data.accounts.iter_mut().find(|a| a.id == account.id) {
+            Some(existing) => {
+                if account.expires_at.unwrap_or(0) > existing.expires_at.unwrap_or(0) {
+                    *existing = account;
+                }
+            }
+            None => data.accounts.push(account),
+        }
+    }
+
+    let selected_valid = |id: &str| data.accounts.iter().any(|a| a.id == id);
+    if !data.selected_id.as_deref().is_some_and(selected_valid) {
+        data.selected_id = selected_id
+            .filter(|id| selected_valid(id))
+            .or_else(|| data.accounts.first().map(|a| a.id.clone()));
+    }
+}
+
+fn needs_refresh(account: &Account, now: i64) -> bool {
+    account.account_type == AccountType::Microsoft
+        && account.refresh_token.is_some()
+        && account
+            .expires_at
+            .map_or(true, |expires_at| expires_at - now < REFRESH_MARGIN_SECS)
+}
+
+fn to_launch_account(account: Account) -> launcher::Account {
+    let account_type = match account.account_type {
+        AccountType::Offline => launcher::AccountType::Offline,
+        AccountType::Microsoft => launcher::AccountType::Microsoft,
+        AccountType::Authlib => launcher::AccountType::Authlib,
+    };
+    launcher::Account {
+        id: account.id,
+        account_type,
+        username: account.username,
+        uuid: account.uuid,
+        access_token: account.access_token,
+        refresh_token: account.refresh_token,
+        expires_at: account.expires_at,
+        skin_url: account.skin_url,
+        authlib_api_root: account.authlib_api_root,
+        authlib_prefetched: account.authlib_server.map(|server| server.prefetched),
+    }
+}
+
+impl AccountStore {
+    /// 确保内存中已有密钥：已有账号库按其密钥来源取密钥，首次使用时尝试在密钥环中生成
+    fn ensure_key(&self, slot: &mut Option<VaultKey>, path: &Path) -> Result<(), String> {
+        if slot.is_some() {
+            return Ok(());
+        }
+        match read_vault_file(path)? {
+            Some(file) if file.key_source == AccountKeySource::Passphrase => {
+                Err("账号库已使用口令加密，请先输入口令解锁".to_string())
+            }
+            Some(file) => {
+                let key = keyring_load()?
+                    .ok_or_else(|| "系统密钥环中找不到账号库密钥，无法解密账号库".to_string())?;
+                open_vault(&key, &file)?;
+                *slot = Some(VaultKey {
+                    key,
+                    source: AccountKeySource::Keyring,
+                    kdf: None,
+                });
+                Ok(())
+            }
+            None => {
+                let key = match keyring_load()? {
+                    Some(key) => key,
+                    None => keyring_create()?,
+                };
+                *slot = Some(VaultKey {
+                    key,
+                    source: AccountKeySource::Keyring,
+                    kdf: None,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    fn with_vault<R: Runtime, T>(
+        &self,
+        app: &AppHandle<R>,
+        persist: bool,
+        f: impl FnOnce(&mut VaultData) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let path = vault_path(app)?;
+        let mut slot = self
+            .key
+            .lock()
+            .map_err(|_| "账号库状态已损坏".to_string())?;
+        self.ensure_key(&mut slot, &path)?;
+        let key = slot.as_ref().ok_or_else(|| "账号库尚未解锁".to_string())?;
+
+        let mut data = match read_vault_file(&path)? {
+            Some(file) => open_vault(&key.key, &file)?,
+            None => VaultData::default(),
+        };
+        let result = f(&mut data)?;
+        if persist {
+            write_vault_file(&path, &seal_vault(key, &data)?)?;
+        }
+        Ok(result)
+    }
+
+    /// 只读查询账号库状态：不会创建密钥环条目，也不会把密钥载入内存
+    pub fn status<R: Runtime>(&self, app: &AppHandle<R>) -> Result<AccountStoreStatus, String> {
+        let path = vault_path(app)?;
+        let file = read_vault_file(&path)?;
+        let slot = self
+            .key
+            .lock()
+            .map_err(|_| "账号库状态已损坏".to_string())?;
+        let key_source = file
+            .as_ref()
+            .map(|f| f.key_source)
+            .or_else(|| slot.as_ref().map(|k| k.source));
+        // 密钥环模式只要能读到密钥即可使用；尚无账号库时密钥环可用即可在首次写入时创建
+        let keyring_usable = match file.as_ref().map(|f| f.key_source) {
+            Some(AccountKeySource::Passphrase) => false,
+            Some(AccountKeySource::Keyring) => matches!(keyring_load(), Ok(Some(_))),
+            None => keyring_load().is_ok(),
+        };
+        let unlocked = slot.is_some() || keyring_usable;
+        let needs_passphrase = !unlocked
+            && file
+                .as_ref()
+                .map_or(true, |f| f.key_source == AccountKeySource::Passphrase);
+
+        Ok(AccountStoreStatus {
+            exists: file.is_some(),
+            unlocked,
+            key_source,
+            needs_passphrase,
+        })
+    }
+
+    /// 用口令解锁账号库；账号库尚不存在时以该口令创建
+    pub fn unlock_with_passphrase<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        passphrase: &str,
+    ) -> Result<AccountStoreStatus, String> {
+        let path = vault_path(app)?;
+        {
+            let mut slot = self
+                .key
+                .lock()
+                .map_err(|_| "账号库状态已损坏".to_string())?;
+            match read_vault_file(&path)? {
+                Some(file) if file.key_source == AccountKeySource::Keyring => {
+                    return Err("账号库由系统密钥环保护，无需口令".to_string());
+                }
+                Some(file) => {
+                    let kdf = file
+                        .kdf
+                        .clone()
+                        .ok_or_else(|| "账号库密钥参数缺失".to_string())?;
+                    let key = derive_passphrase_key(passphrase, &kdf)?;
+                    open_vault(&key, &file)?;
+                    *slot = Some(VaultKey {
+                        key,
+                        source: AccountKeySource::Passphrase,
+                        kdf: Some(kdf),
+                    });
+                }
+                None => {
+                    let kdf = new_kdf_params();
+                    let key = VaultKey {
+                        key: derive_passphrase_key(passphrase, &kdf)?,
+                        source: AccountKeySource::Passphrase,
+                        kdf: Some(kdf),
+                    };
+                    write_vault_file(&path, &seal_vault(&key, &VaultData::default())?)?;
+                    *slot = Some(key);
+                }
+            }
+        }
+        self.status(app)
+    }
+
+    pub fn list<R: Runtime>(&self, app: &AppHandle<R>) -> Result<Vec<AccountSummary>, String> {
+        self.with_vault(app, false, |data| Ok(summaries(data)))
+    }
+
+    pub fn upsert<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        account: Account,
+    ) -> Result<AccountSummary, String> {
+        self.with_vault(app, true, |data| {
+            let id = account.id.clone();
+            upsert_account(data, account);
+            data.accounts
+                .iter()
+                .find(|a| a.id == id)
+                .map(|a| summarize(a, data.selected_id.as_deref()))
+                .ok_or_else(|| "保存账号失败".to_string())
+        })
+    }
+
+    pub fn remove<R: Runtime>(&self, app: &AppHandle<R>, id: &str) -> Result<(), String> {
+        self.with_vault(app, true, |data| {
+            data.accounts.retain(|a| a.id != id);
+            if data.selected_id.as_deref() == Some(id) {
+                data.selected_id = data.accounts.first().map(|a| a.id.clone());
+            }
+            Ok(())
+        })
+    }
+
+    pub fn select<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: &str,
+    ) -> Result<AccountSummary, String> {
+        self.with_vault(app, true, |data| {
+            let account = data
+                .accounts
+                .iter()
+                .find(|a| a.id == id)
+                .ok_or_else(|| format!("账号不存在: {}", id))?;
+            data.selected_id = Some(id.to_string());
+            Ok(summarize(account, Some(id)))
+        })
+    }
+
+    pub fn import<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        accounts: Vec<Account>,
+        selected_id: Option<String>,
+    ) -> Result<Vec<AccountSummary>, String> {
+        self.with_vault(app, true, |data| {
+            merge_imported(data, accounts, selected_id);
+            Ok(summaries(data))
+        })
+    }
+
+    /// 取出完整账号（含令牌），仅供后端内部使用；id 为空时取当前选中账号
+    pub(crate) fn get<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        id: Option<&str>,
+    ) -> Result<Account, String> {
+        self.with_vault(app, false, |data| {
+            let id = id
+                .or(data.selected_id.as_deref())
+                .ok_or_else(|| "尚未选择账号".to_string())?;
+            data.accounts
+                .iter()
+                .find(|a| a.id == id)
+                .cloned()
+                .ok_or_else(|| format!("账号不存在: {}", id))
+        })
+    }
+
+    /// 用刷新后的账号替换原账号，微软 Profile id 变化时保持原有的选中状态
+    fn replace<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        old_id: &str,
+        account: Account,
+    ) -> Result<AccountSummary, String> {
+        self.with_vault(app, true, |data| {
+            let id = account.id.clone();
+            if old_id != id {
+                data.accounts.retain(|a| a.id != old_id);
+                if data.selected_id.as_deref() == Some(old_id) {
+                    data.selected_id = Some(id.clone());
+                }
+            }
+            upsert_account(data, account);
+            data.accounts
+                .iter()
+                .find(|a| a.id == id)
+                .map(|a| summarize(a, data.selected_id.as_deref()))
+                .ok_or_else(|| "保存账号失败".to_string())
+        })
+    }
+}
+
+/// 在后端完成登录并把账号写入账号库；微软账号无授权或无角色时返回类型化错误
+pub async fn add_account<R: Runtime>(
+    app: &AppHandle<R>,
+    request: AddAccountRequest,
+) -> Result<AccountSummary, MinecraftLoginError> {
+    let account = match request {
+        AddAccountRequest::Offline { username } => {
+            let username = username.trim().to_string();
+            if username.is_empty() {
+                return Err("离线账号名不能为空".to_string().into());
+            }
+            let uuid = super::generate_offline_uuid(&username);
+            Account {
+                id: uuid.clone(),
+                account_type: AccountType::Offline,
+                username,
+                uuid,
+                access_token: OFFLINE_ACCESS_TOKEN.to_string(),
+                refresh_token: None,
+                expires_at: None,
+                skin_url: None,
+                cape_url: None,
+                authlib_api_root: None,
+                authlib_server: None,
+                minecraft_ownership: None,
+            }
+        }
+        AddAccountRequest::Microsoft {
+            device_code,
+            interval,
+        } => super::poll_and_exchange_token(app, &device_code, interval).await?,
+        AddAccountRequest::MicrosoftBrowser => super::login_microsoft_with_browser(app).await?,
+        AddAccountRequest::Authlib {
+            api_root,
+            username,
+            password,
+            profile_id,
+        } => super::login_authlib(&api_root, &username, &password, profile_id.as_deref()).await?,
+    };
+    Ok(app.state::<AccountStore>().upsert(app, account)?)
+}
+
+/// 刷新账号库中的微软账号令牌
+pub async fn refresh_account<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+) -> Result<AccountSummary, String> {
+    let store = app.state::<AccountStore>();
+    let account = store.get(app, Some(id))?;
+    if account.account_type != AccountType::Microsoft {
+        return Err("只有微软账号支持刷新令牌".to_string());
+    }
+    let refresh_token = account
+        .refresh_token
+        .ok_or_else(|| "该账号没有刷新令牌，请重新登录".to_string())?;
+    let refreshed = super::refresh_microsoft_token(app, &refresh_token).await?;
+    store.replace(app, id, refreshed)
+}
+
+/// 取出带可用令牌的完整账号，仅供后端内部使用；微软令牌即将过期时先刷新
+pub(crate) async fn resolve_account<R: Runtime>(
+    app: &AppHandle<R>,
+    id: Option<&str>,
+) -> Result<Account, String> {
+    let store = app.state::<AccountStore>();
+    let account = store.get(app, id)?;
+    if !needs_refresh(&account, chrono::Utc::now().timestamp()) {
+        return Ok(account);
+    }
+    let refreshed = refresh_account(app, &account.id).await?;
+    store.get(app, Some(&refreshed.id))
+}
+
+/// 取出用于启动游戏的账号
+pub async fn resolve_launch_account<R: Runtime>(
+    app: &AppHandle<R>,
+    id: Option<&str>,
+) -> Result<launcher::Account, String> {
+    resolve_account(app, id).await.map(to_launch_account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(id: &str, expires_at: Option<i64>) -> Account {
+        Account {
+            id: id.to_string(),
+            account_type: AccountType::Microsoft,
+            username: format!("user-{id}"),
+            uuid: id.to_string(),
+            access_token: format!("token-{id}-{}", expires_at.unwrap_or(0)),
+            refresh_token: Some("refresh".to_string()),
+            expires_at,
+            skin_url: None,
+            cape_url: None,
+            authlib_api_root: None,
+            authlib_server: None,
+            minecraft_ownership: None,
+        }
+    }
+
+    #[test]
+    fn vault_round_trips_and_rejects_wrong_key() {
+        let key = VaultKey {
+            key: [7u8; KEY_LEN],
+            source: AccountKeySource::Keyring,
+            kdf: None,
+        };
+        let mut data = VaultData::default();
+        upsert_account(&mut data, account("a", Some(100)));
+
+        let file = seal_vault(&key, &data).unwrap();
+        assert!(!file.ciphertext.contains("token-a"));
+        let opened = open_vault(&key.key, &file).unwrap();
+        assert_eq!(opened.accounts[0].access_token, "token-a-100");
+        assert_eq!(opened.selected_id.as_deref(), Some("a"));
+        assert!(open_vault(&[8u8; KEY_LEN], &file).is_err());
+    }
+
+    #[test]
+    fn passphrase_key_is_deterministic_per_salt() {
+        let kdf = new_kdf_params();
+        let first = derive_passphrase_key("hunter2", &kdf).unwrap();
+        assert_eq!(first, derive_passphrase_key("hunter2", &kdf).unwrap());
+        assert_ne!(first, derive_passphrase_key("hunter3", &kdf).unwrap());
+        assert!(derive_passphrase_key("", &kdf).is_err());
+    }
+
+    #[test]
+    fn import_keeps_newer_tokens_and_valid_selection() {
+        let mut data = VaultData::default();
+        upsert_account(&mut data, account("a", Some(200)));
+
+        merge_imported(
+            &mut data,
+            vec![account("a", Some(100)), account("b", Some(300))],
+            Some("missing".to_string()),
+        );
+        assert_eq!(data.accounts.len(), 2);
+        assert_eq!(data.accounts[0].access_token, "token-a-200");
+        assert_eq!(data.selected_id.as_deref(), Some("a"));
+
+        merge_imported(&mut data, vec![account("a", Some(400))], None);
+        assert_eq!(data.accounts[0].access_token, "token-a-400");
+    }
+
+    #[test]
+    fn refresh_only_when_microsoft_token_expiring() {
+        let now = 1_000_000;
+        assert!(needs_refresh(&account("a", Some(now + 60)), now));
+        assert!(needs_refresh(&account("a", None), now));
+        assert!(!needs_refresh(&account("a", Some(now + 3600)), now));
+
+        let mut offline = account("b", None);
+        offline.account_type = AccountType::Offline;
+        assert!(!needs_refresh(&offline, now));
+    }
+}
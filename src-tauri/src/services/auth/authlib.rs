@@ -1,8 +1,33 @@
 use super::http::{format_reqwest_error, get_client};
-use crate::domain::auth::{Account, AccountType};
+use crate::domain::auth::{
+    Account, AccountType, AuthlibLoginChallenge, AuthlibProfileOption, AuthlibServerMeta,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// authlib-injector 规范中的 API 地址指示 (ALI) 响应头
+const API_LOCATION_HEADER: &str = "X-Authlib-Injector-API-Location";
+/// 多角色登录等待用户选择的最长时间
+const PENDING_LOGIN_TTL: Duration = Duration::from_secs(600);
+
+struct PendingLogin {
+    api_root: String,
+    server: AuthlibServerMeta,
+    access_token: String,
+    client_token: String,
+    selected_profile: Option<AuthlibProfile>,
+    available_profiles: Vec<AuthlibProfile>,
+    created_at: Instant,
+}
+
+static PENDING_LOGINS: Lazy<Mutex<HashMap<String, PendingLogin>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct AuthlibAgent {
@@ -20,13 +45,64 @@ struct AuthlibAuthenticateRequest<'a> {
     agent: AuthlibAgent,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct AuthlibProfile {
     id: String,
     name: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthlibRefreshRequest<'a> {
+    access_token: &'a str,
+    client_token: &'a str,
+    request_user: bool,
+    selected_profile: &'a AuthlibProfile,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthlibRefreshResponse {
+    access_token: String,
+    client_token: Option<String>,
+    selected_profile: Option<AuthlibProfile>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AuthlibMetaLinks {
+    homepage: Option<String>,
+    register: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AuthlibMetaInfo {
+    server_name: Option<String>,
+    implementation_name: Option<String>,
+    implementation_version: Option<String>,
+    #[serde(default)]
+    links: AuthlibMetaLinks,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthlibMetadataResponse {
+    meta: Option<AuthlibMetaInfo>,
+    #[serde(default)]
+    skin_domains: Vec<String>,
+    // 规范中的字段名就是小写的 signaturePublickey
+    #[serde(rename = "signaturePublickey")]
+    signature_public_key: Option<String>,
+}
+
+/// 角色选择结果：令牌已绑定角色，或需要通过 /refresh 绑定
+enum ProfilePick {
+    Bound(AuthlibProfile),
+    NeedsBinding(AuthlibProfile),
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AuthlibAuthenticateResponse {
@@ -86,12 +162,102 @@ fn authlib_error_message(status: reqwest::StatusCode, body: &str) -> String {
     }
 }
 
-pub async fn login_authlib(
+/// 解析 ALI 响应头，相对地址按当前地址补全；指向自身时返回 None
+fn resolve_api_location(current: &str, header: Option<&str>) -> Option<String> {
+    let header = header?.trim();
+    if header.is_empty() {
+        return None;
+    }
+    let resolved = reqwest::Url::parse(current).ok()?.join(header).ok()?;
+    let resolved = resolved.as_str().trim_end_matches('/').to_string();
+    (resolved != current.trim_end_matches('/')).then_some(resolved)
+}
+
+fn parse_server_meta(body: &str) -> Result<AuthlibServerMeta, String> {
+    let parsed: AuthlibMetadataResponse = serde_json::from_str(body)
+        .map_err(|_| "该地址不是有效的 Yggdrasil API，请检查皮肤站地址".to_string())?;
+    if parsed.meta.is_none() && parsed.signature_public_key.is_none() {
+        return Err("该地址不是有效的 Yggdrasil API，请检查皮肤站地址".to_string());
+    }
+    let meta = parsed.meta.unwrap_or_default();
+    Ok(AuthlibServerMeta {
+        server_name: meta.server_name,
+        implementation_name: meta.implementation_name,
+        implementation_version: meta.implementation_version,
+        homepage: meta.links.homepage,
+        register: meta.links.register,
+        skin_domains: parsed.skin_domains,
+        signature_public_key: parsed.signature_public_key,
+        prefetched: STANDARD.encode(body.trim()),
+    })
+}
+
+async fn fetch_api_root(url: &str) -> Result<(Option<String>, String), String> {
+    let response = get_client()
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(|error| format_reqwest_error("连接第三方皮肤站失败", error))?;
+    let location = response
+        .headers()
+        .get(API_LOCATION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|error| format_reqwest_error("读取第三方皮肤站响应失败", error))?;
+    if location.is_none() && !status.is_success() {
+        return Err(format!("获取皮肤站元数据失败，HTTP {}", status));
+    }
+    Ok((location, body))
+}
+
+/// 按 ALI 规范从皮肤站首页地址发现 API 根地址，并预取服务器元数据
+pub async fn discover_api_root(input: &str) -> Result<(String, AuthlibServerMeta), String> {
+    let api_root = normalize_api_root(input)?;
+    let (location, body) = fetch_api_root(&api_root).await?;
+    match resolve_api_location(&api_root, location.as_deref()) {
+        Some(resolved) => {
+            let resolved = normalize_api_root(&resolved)?;
+            let (_, body) = fetch_api_root(&resolved).await?;
+            Ok((resolved, parse_server_meta(&body)?))
+        }
+        None => Ok((api_root, parse_server_meta(&body)?)),
+    }
+}
+
+async fn post_authserver<T: Serialize>(
+    api_root: &str,
+    action: &str,
+    request: &T,
+) -> Result<String, String> {
+    let response = get_client()
+        .post(format!("{}/authserver/{}", api_root, action))
+        .json(request)
+        .send()
+        .await
+        .map_err(|error| format_reqwest_error("连接第三方皮肤站失败", error))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|error| format_reqwest_error("读取第三方皮肤站响应失败", error))?;
+
+    if !status.is_success() {
+        return Err(authlib_error_message(status, &body));
+    }
+    Ok(body)
+}
+
+async fn authenticate(
     api_root: &str,
     username: &str,
     password: &str,
-) -> Result<Account, String> {
-    let normalized_api_root = normalize_api_root(api_root)?;
+) -> Result<(AuthlibAuthenticateResponse, String), String> {
     let trimmed_username = username.trim();
     if trimmed_username.is_empty() {
         return Err("账号不能为空".to_string());
@@ -101,7 +267,6 @@ pub async fn login_authlib(
     }
 
     let client_token = Uuid::new_v4().to_string();
-    let endpoint = format!("{}/authserver/authenticate", normalized_api_root);
     let request = AuthlibAuthenticateRequest {
         username: trimmed_username,
         password,
@@ -112,41 +277,233 @@ pub async fn login_authlib(
             version: 1,
         },
     };
+    let body = post_authserver(api_root, "authenticate", &request).await?;
+    let response: AuthlibAuthenticateResponse = serde_json::from_str(&body)
+        .map_err(|error| format!("解析第三方皮肤站登录响应失败: {}", error))?;
+    let client_token = response.client_token.clone().unwrap_or(client_token);
+    Ok((response, client_token))
+}
 
-    let response = get_client()
-        .post(&endpoint)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|error| format_reqwest_error("连接第三方皮肤站失败", error))?;
-
-    let status = response.status();
-    let body = response
-        .text()
-        .await
-        .map_err(|error| format_reqwest_error("读取第三方皮肤站响应失败", error))?;
+/// 令牌未绑定角色时，用 /refresh + selectedProfile 绑定到用户选中的角色
+async fn bind_profile(
+    api_root: &str,
+    access_token: &str,
+    client_token: &str,
+    profile: &AuthlibProfile,
+) -> Result<(String, String, AuthlibProfile), String> {
+    let request = AuthlibRefreshRequest {
+        access_token,
+        client_token,
+        request_user: false,
+        selected_profile: profile,
+    };
+    let body = post_authserver(api_root, "refresh", &request).await?;
+    let response: AuthlibRefreshResponse = serde_json::from_str(&body)
+        .map_err(|error| format!("解析第三方皮肤站刷新响应失败: {}", error))?;
+    Ok((
+        response.access_token,
+        response
+            .client_token
+            .unwrap_or_else(|| client_token.to_string()),
+        response.selected_profile.unwrap_or_else(|| profile.clone()),
+    ))
+}
 
-    if !status.is_success() {
-        return Err(authlib_error_message(status, &body));
+fn pick_profile(
+    selected: Option<&AuthlibProfile>,
+    available: &[AuthlibProfile],
+    profile_id: Option<&str>,
+) -> Result<ProfilePick, String> {
+    if let Some(selected) = selected {
+        return match profile_id {
+            Some(id) if id != selected.id => Err(format!(
+                "该登录已绑定角色 {}，无法切换到其他角色",
+                selected.name
+            )),
+            _ => Ok(ProfilePick::Bound(selected.clone())),
+        };
     }
+    match profile_id {
+        Some(id) => available
+            .iter()
+            .find(|profile| profile.id == id)
+            .cloned()
+            .map(ProfilePick::NeedsBinding)
+            .ok_or_else(|| format!("皮肤站账号下不存在角色: {}", id)),
+        None => match available {
+            [] => Err("第三方皮肤站未返回可用角色".to_string()),
+            [only] => Ok(ProfilePick::NeedsBinding(only.clone())),
+            _ => Err("该皮肤站账号有多个角色，请先选择角色".to_string()),
+        },
+    }
+}
 
-    let auth_response: AuthlibAuthenticateResponse = serde_json::from_str(&body)
-        .map_err(|error| format!("解析第三方皮肤站登录响应失败: {}", error))?;
-    let profile = auth_response
-        .selected_profile
-        .or_else(|| auth_response.available_profiles.into_iter().next())
-        .ok_or_else(|| "第三方皮肤站未返回可用角色".to_string())?;
+async fn complete_login(
+    pending: PendingLogin,
+    profile_id: Option<&str>,
+) -> Result<Account, String> {
+    let pick = pick_profile(
+        pending.selected_profile.as_ref(),
+        &pending.available_profiles,
+        profile_id,
+    )?;
+    let (access_token, client_token, profile) = match pick {
+        ProfilePick::Bound(profile) => (pending.access_token, pending.client_token, profile),
+        ProfilePick::NeedsBinding(profile) => {
+            bind_profile(
+                &pending.api_root,
+                &pending.access_token,
+                &pending.client_token,
+                &profile,
+            )
+            .await?
+        }
+    };
 
     Ok(Account {
         id: profile.id.clone(),
         account_type: AccountType::Authlib,
         username: profile.name,
         uuid: profile.id,
-        access_token: auth_response.access_token,
-        refresh_token: auth_response.client_token.or(Some(client_token)),
+        access_token,
+        refresh_token: Some(client_token),
         expires_at: None,
         skin_url: None,
         cape_url: None,
-        authlib_api_root: Some(normalized_api_root),
+        authlib_api_root: Some(pending.api_root),
+        authlib_server: Some(pending.server),
+        minecraft_ownership: None,
+    })
+}
+
+async fn begin_login(
+    api_root: &str,
+    username: &str,
+    password: &str,
+) -> Result<PendingLogin, String> {
+    let (api_root, server) = discover_api_root(api_root).await?;
+    let (response, client_token) = authenticate(&api_root, username, password).await?;
+    Ok(PendingLogin {
+        api_root,
+        server,
+        access_token: response.access_token,
+        client_token,
+        selected_profile: response.selected_profile,
+        available_profiles: response.available_profiles,
+        created_at: Instant::now(),
     })
 }
+
+/// 一步完成登录；账号有多个角色时必须传入 profile_id
+pub async fn login_authlib(
+    api_root: &str,
+    username: &str,
+    password: &str,
+    profile_id: Option<&str>,
+) -> Result<Account, String> {
+    let pending = begin_login(api_root, username, password).await?;
+    complete_login(pending, profile_id).await
+}
+
+/// 两步登录的第一步：认证并返回可选角色，令牌暂存在后端等待 finish_authlib_login
+pub async fn start_authlib_login(
+    api_root: &str,
+    username: &str,
+    password: &str,
+) -> Result<AuthlibLoginChallenge, String> {
+    let pending = begin_login(api_root, username, password).await?;
+    let ticket = Uuid::new_v4().to_string();
+    let to_option = |profile: &AuthlibProfile| AuthlibProfileOption {
+        id: profile.id.clone(),
+        name: profile.name.clone(),
+    };
+    let challenge = AuthlibLoginChallenge {
+        ticket: ticket.clone(),
+        api_root: pending.api_root.clone(),
+        server: pending.server.clone(),
+        profiles: pending.available_profiles.iter().map(to_option).collect(),
+        selected_profile_id: pending.selected_profile.as_ref().map(|p| p.id.clone()),
+    };
+
+    let mut pending_logins = PENDING_LOGINS
+        .lock()
+        .map_err(|_| "登录状态已损坏".to_string())?;
+    pending_logins.retain(|_, login| login.created_at.elapsed() < PENDING_LOGIN_TTL);
+    pending_logins.insert(ticket, pending);
+    Ok(challenge)
+}
+
+/// 两步登录的第二步：用户选定角色后绑定令牌
+pub async fn finish_authlib_login(ticket: &str, profile_id: &str) -> Result<Account, String> {
+    let pending = PENDING_LOGINS
+        .lock()
+        .map_err(|_| "登录状态已损坏".to_string())?
+        .remove(ticket)
+        .filter(|login| login.created_at.elapsed() < PENDING_LOGIN_TTL)
+        .ok_or_else(|| "登录已过期，请重新输入账号密码".to_string())?;
+    complete_login(pending, Some(profile_id)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(id: &str) -> AuthlibProfile {
+        AuthlibProfile {
+            id: id.to_string(),
+            name: format!("name-{id}"),
+        }
+    }
+
+    #[test]
+    fn api_location_resolves_relative_and_ignores_self() {
+        assert_eq!(
+            resolve_api_location("https://skin.example.com", Some("/api/yggdrasil/")).as_deref(),
+            Some("https://skin.example.com/api/yggdrasil")
+        );
+        assert_eq!(
+            resolve_api_location(
+                "https://skin.example.com/api/yggdrasil",
+                Some("https://skin.example.com/api/yggdrasil/")
+            ),
+            None
+        );
+        assert_eq!(resolve_api_location("https://skin.example.com", None), None);
+    }
+
+    #[test]
+    fn server_meta_parses_spec_fields() {
+        let body = r#"{"meta":{"serverName":"Example","links":{"homepage":"https://e.com"}},
+            "skinDomains":[".e.com"],"signaturePublickey":"-----BEGIN PUBLIC KEY-----"}"#;
+        let meta = parse_server_meta(body).unwrap();
+        assert_eq!(meta.server_name.as_deref(), Some("Example"));
+        assert_eq!(meta.homepage.as_deref(), Some("https://e.com"));
+        assert_eq!(meta.skin_domains, vec![".e.com".to_string()]);
+        assert!(meta.signature_public_key.is_some());
+        assert_eq!(
+            STANDARD.decode(&meta.prefetched).unwrap(),
+            body.trim().as_bytes()
+        );
+        assert!(parse_server_meta("<html></html>").is_err());
+        assert!(parse_server_meta("{}").is_err());
+    }
+
+    #[test]
+    fn profile_pick_requires_choice_for_multiple_profiles() {
+        let available = vec![profile("a"), profile("b")];
+        assert!(pick_profile(None, &available, None).is_err());
+        assert!(matches!(
+            pick_profile(None, &available, Some("b")),
+            Ok(ProfilePick::NeedsBinding(p)) if p.id == "b"
+        ));
+        assert!(matches!(
+            pick_profile(None, &available[..1], None),
+            Ok(ProfilePick::NeedsBinding(p)) if p.id == "a"
+        ));
+        assert!(matches!(
+            pick_profile(Some(&profile("a")), &available, None),
+            Ok(ProfilePick::Bound(p)) if p.id == "a"
+        ));
+        assert!(pick_profile(Some(&profile("a")), &available, Some("b")).is_err());
+    }
+}
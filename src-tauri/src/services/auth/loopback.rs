@@ -0,0 +1,216 @@
+// src-tauri/src/services/auth/loopback.rs
+//
+// 授权码流的本地回环监听器。
+// 在 127.0.0.1 的随机端口上临时监听，接住浏览器带回的 `code` 与 `state`，校验后立即关闭。
+
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{timeout_at, Instant};
+
+const CALLBACK_PATH: &str = "/callback";
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+/// 单个连接读取请求头的最长时间，避免空闲连接占住监听器直到整体超时
+const CONNECTION_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SUCCESS_PAGE: &str =
+    "<!doctype html><html><head><meta charset=\"utf-8\"><title>PiLauncher</title></head>\
+<body style=\"font-family:sans-serif;text-align:center;padding-top:80px\">\
+<h2>登录完成</h2><p>现在可以关闭此页面并返回启动器。</p></body></html>";
+const FAILURE_PAGE: &str =
+    "<!doctype html><html><head><meta charset=\"utf-8\"><title>PiLauncher</title></head>\
+<body style=\"font-family:sans-serif;text-align:center;padding-top:80px\">\
+<h2>登录失败</h2><p>请返回启动器查看详情并重试。</p></body></html>";
+
+pub struct LoopbackListener {
+    listener: TcpListener,
+    redirect_uri: String,
+}
+
+enum CallbackResult {
+    Code(String),
+    Failed(String),
+    /// state 不匹配的回调可能来自其他页面或过期的登录，拒绝后继续等待
+    Rejected,
+    /// 与回调无关的请求（例如 favicon），忽略后继续等待
+    Ignored,
+}
+
+impl LoopbackListener {
+    /// 绑定 127.0.0.1 上由系统分配的空闲端口
+    pub async fn bind() -> Result<Self, String> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| format!("无法启动本地登录回调监听: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("无法启动本地登录回调监听: {}", e))?
+            .port();
+        Ok(Self {
+            listener,
+            redirect_uri: format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH),
+        })
+    }
+
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// 等待浏览器回调并返回授权码；state 不匹配的请求会被忽略，直到超时
+    pub async fn wait_for_code(
+        self,
+        expected_state: &str,
+        wait: Duration,
+    ) -> Result<String, String> {
+        let deadline = Instant::now() + wait;
+        loop {
+            let (mut stream, _) = timeout_at(deadline, self.listener.accept())
+                .await
+                .map_err(|_| "等待浏览器登录超时，请重新发起登录".to_string())?
+                .map_err(|e| format!("接收登录回调失败: {}", e))?;
+
+            let read_deadline = deadline.min(Instant::now() + CONNECTION_READ_TIMEOUT);
+            let request = match timeout_at(read_deadline, read_request_target(&mut stream)).await {
+                Ok(Ok(target)) => target,
+                Ok(Err(_)) => continue,
+                Err(_) if Instant::now() >= deadline => {
+                    return Err("等待浏览器登录超时，请重新发起登录".to_string());
+                }
+                Err(_) => continue,
+            };
+
+            match parse_callback(&request, expected_state) {
+                CallbackResult::Ignored => {
+                    let _ = respond(&mut stream, "404 Not Found", "").await;
+                }
+                CallbackResult::Rejected => {
+                    let _ = respond(&mut stream, "400 Bad Request", FAILURE_PAGE).await;
+                }
+                CallbackResult::Code(code) => {
+                    let _ = respond(&mut stream, "200 OK", SUCCESS_PAGE).await;
+                    return Ok(code);
+                }
+                CallbackResult::Failed(message) => {
+                    let _ = respond(&mut stream, "400 Bad Request", FAILURE_PAGE).await;
+                    return Err(message);
+                }
+            }
+        }
+    }
+}
+
+/// 读取 HTTP 请求行中的请求目标，例如 `/callback?code=...&state=...`
+async fn read_request_target(stream: &mut TcpStream) -> Result<String, String> {
+    let mut buffer = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Err("request too large".to_string());
+        }
+    }
+    let text = String::from_utf8_lossy(&buffer);
+    let mut parts = text.lines().next().unwrap_or_default().split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Ok(target.to_string()),
+        _ => Err("unexpected request".to_string()),
+    }
+}
+
+fn parse_callback(target: &str, expected_state: &str) -> CallbackResult {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != CALLBACK_PATH {
+        return CallbackResult::Ignored;
+    }
+    let params: HashMap<String, String> =
+        reqwest::Url::parse(&format!("http://localhost/?{}", query))
+            .map(|url| url.query_pairs().into_owned().collect())
+            .unwrap_or_default();
+
+    if params.get("state").map(String::as_str) != Some(expected_state) {
+        return CallbackResult::Rejected;
+    }
+    if let Some(error) = params.get("error") {
+        let description = params.get("error_description").cloned().unwrap_or_default();
+        return CallbackResult::Failed(format!("授权异常中止: {} ({})", error, description));
+    }
+    match params.get("code").filter(|code| !code.is_empty()) {
+        Some(code) => CallbackResult::Code(code.clone()),
+        None => CallbackResult::Failed("登录回调中缺少授权码".to_string()),
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callback_requires_matching_state() {
+        assert!(matches!(
+            parse_callback("/callback?code=abc&state=s1", "s1"),
+            CallbackResult::Code(code) if code == "abc"
+        ));
+        assert!(matches!(
+            parse_callback("/callback?code=abc&state=s2", "s1"),
+            CallbackResult::Rejected
+        ));
+        assert!(matches!(
+            parse_callback("/callback?error=access_denied&state=s2", "s1"),
+            CallbackResult::Rejected
+        ));
+        assert!(matches!(
+            parse_callback("/callback?error=access_denied&state=s1", "s1"),
+            CallbackResult::Failed(message) if message.contains("access_denied")
+        ));
+        assert!(matches!(
+            parse_callback("/favicon.ico", "s1"),
+            CallbackResult::Ignored
+        ));
+    }
+
+    #[tokio::test]
+    async fn listener_returns_code_from_browser_redirect() {
+        let listener = LoopbackListener::bind().await.unwrap();
+        let redirect_uri = listener.redirect_uri().to_string();
+        let waiter = tokio::spawn(listener.wait_for_code("xyz", Duration::from_secs(10)));
+
+        let client = reqwest::Client::new();
+        let base = redirect_uri.trim_end_matches(CALLBACK_PATH);
+        let favicon = client
+            .get(format!("{}/favicon.ico", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(favicon.status(), 404);
+        let forged = client
+            .get(format!("{}?code=forged&state=other", redirect_uri))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(forged.status(), 400);
+        let page = client
+            .get(format!("{}?code=the-code&state=xyz", redirect_uri))
+            .send()
+            .await
+            .unwrap();
+        assert!(page.status().is_success());
+
+        assert_eq!(waiter.await.unwrap().unwrap(), "the-code");
+    }
+}
@@ -1,9 +1,11 @@
 // src-tauri/src/services/auth/microsoft.rs
 //
-// 微软 OAuth 设备码流与 Token 刷新。
+// 微软 OAuth 设备码流、授权码 + PKCE 流与 Token 刷新。
 // 负责与 https://login.microsoftonline.com 的所有交互。
 
 use crate::domain::auth::{DeviceCodeResponse, MicrosoftTokenResponse};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use sha2::{Digest, Sha256};
 use std::time::Duration;
 use tokio::time::sleep;
 
@@ -11,6 +13,91 @@ use super::http::{format_reqwest_error, get_client};
 
 const CLIENT_ID: &str = env!("MICROSOFT_CLIENT_ID");
 const SCOPE: &str = "XboxLive.signin offline_access";
+const AUTHORIZE_ENDPOINT: &str =
+    "https://login.microsoftonline.com/consumers/oauth2/v2.0/authorize";
+pub const TOKEN_ENDPOINT: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+
+/// PKCE 校验对：verifier 留在本地，challenge 随授权请求发出
+pub struct PkcePair {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl PkcePair {
+    pub fn generate() -> Self {
+        // 64 字节随机数编码后为 86 个字符，落在 RFC 7636 要求的 43~128 之间
+        let bytes: [u8; 64] = rand::random();
+        let verifier = URL_SAFE_NO_PAD.encode(bytes);
+        Self {
+            challenge: pkce_challenge(&verifier),
+            verifier,
+        }
+    }
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// 拼接浏览器授权地址
+pub fn authorize_url(
+    redirect_uri: &str,
+    state: &str,
+    code_challenge: &str,
+) -> Result<String, String> {
+    let mut url =
+        reqwest::Url::parse(AUTHORIZE_ENDPOINT).map_err(|e| format!("微软授权地址无效: {}", e))?;
+    url.query_pairs_mut()
+        .append_pair("client_id", CLIENT_ID)
+        .append_pair("response_type", "code")
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("response_mode", "query")
+        .append_pair("scope", SCOPE)
+        .append_pair("state", state)
+        .append_pair("code_challenge", code_challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("prompt", "select_account");
+    Ok(url.into())
+}
+
+/// 用授权码和 PKCE verifier 换取 MS Token
+/// 返回 (ms_access_token, ms_refresh_token)
+pub async fn exchange_code(
+    token_endpoint: &str,
+    code: &str,
+    redirect_uri: &str,
+    code_verifier: &str,
+) -> Result<(String, String), String> {
+    let res = get_client()
+        .post(token_endpoint)
+        .form(&[
+            ("client_id", CLIENT_ID),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", code_verifier),
+            ("scope", SCOPE),
+        ])
+        .send()
+        .await
+        .map_err(|e| format_reqwest_error("兑换微软授权码失败", e))?;
+
+    let status = res.status();
+    let token_data: MicrosoftTokenResponse = res
+        .json()
+        .await
+        .map_err(|e| format_reqwest_error("解析授权码兑换响应失败", e))?;
+
+    match token_data.access_token {
+        Some(token) => Ok((token, token_data.refresh_token.unwrap_or_default())),
+        None => Err(format!(
+            "微软拒绝兑换授权码 (HTTP {}): {} ({})",
+            status,
+            token_data.error.unwrap_or_default(),
+            token_data.error_description.unwrap_or_default()
+        )),
+    }
+}
 
 /// 获取微软设备码，用于引导用户在浏览器登录
 pub async fn request_device_code() -> Result<DeviceCodeResponse, String> {
@@ -120,3 +207,63 @@ pub async fn refresh_token(refresh_token: &str) -> Result<(String, String), Stri
 
     Ok((ms_access_token, new_refresh_token))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn pkce_challenge_matches_rfc_example() {
+        // RFC 7636 附录 B 的示例
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let pair = PkcePair::generate();
+        assert_eq!(pair.verifier.len(), 86);
+        assert_eq!(pair.challenge, pkce_challenge(&pair.verifier));
+    }
+
+    #[tokio::test]
+    async fn exchange_code_posts_verifier_to_token_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/token", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 4096];
+            while !String::from_utf8_lossy(&request).contains("code_verifier=") {
+                let read = stream.read(&mut chunk).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&chunk[..read]);
+            }
+            let body = r#"{"access_token":"ms-access","refresh_token":"ms-refresh"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let tokens = exchange_code(
+            &endpoint,
+            "auth-code",
+            "http://127.0.0.1:1/callback",
+            "verifier",
+        )
+        .await
+        .unwrap();
+        assert_eq!(tokens, ("ms-access".to_string(), "ms-refresh".to_string()));
+
+        let request = server.await.unwrap();
+        assert!(request.contains("grant_type=authorization_code"));
+        assert!(request.contains("code=auth-code"));
+        assert!(request.contains("code_verifier=verifier"));
+    }
+}
@@ -3,7 +3,7 @@
 // Mojang / Minecraft Services API 客户端。
 // 负责：MC Token 交换、Profile 获取、皮肤上传、披风切换、头像获取、Mojang 皮肤获取。
 
-use crate::domain::auth::McProfile;
+use crate::domain::auth::{McProfile, ProfileNameStatus};
 use base64::{engine::general_purpose, Engine as _};
 use std::fs;
 use std::path::PathBuf;
@@ -77,6 +77,122 @@ pub async fn get_minecraft_profile(mc_token: &str) -> Result<McProfile, String>
     }
 }
 
+/// 获取账号在 Minecraft 商店中的授权条目名（entitlements/mcstore）
+pub async fn fetch_entitlements(mc_token: &str) -> Result<Vec<String>, String> {
+    let res = get_client()
+        .get("https://api.minecraftservices.com/entitlements/mcstore")
+        .bearer_auth(mc_token)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(|e| format_reqwest_error("获取 Minecraft 授权信息网络错误", e))?;
+
+    let status = res.status();
+    let text = res.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(minecraft_api_error("获取 Minecraft 授权信息", status, text));
+    }
+
+    let data: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("解析授权信息失败: {}", e))?;
+    Ok(data["items"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item["name"].as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// 获取游戏档案；账号尚未创建角色 (HTTP 404) 时返回 None
+pub async fn fetch_minecraft_profile(mc_token: &str) -> Result<Option<McProfile>, String> {
+    let res = get_client()
+        .get("https://api.minecraftservices.com/minecraft/profile")
+        .bearer_auth(mc_token)
+        .send()
+        .await
+        .map_err(|e| format_reqwest_error("获取 MC 游戏档案网络错误", e))?;
+
+    let status = res.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let text = res.text().await.unwrap_or_default();
+        return Err(minecraft_api_error("获取游戏档案", status, text));
+    }
+    res.json()
+        .await
+        .map(Some)
+        .map_err(|e| format!("解析游戏档案失败: {}", e))
+}
+
+/// 本地预检角色名：3~16 位字母、数字或下划线
+pub fn is_valid_profile_name(name: &str) -> bool {
+    (3..=16).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 查询角色名是否可用
+pub async fn check_profile_name(mc_token: &str, name: &str) -> Result<ProfileNameStatus, String> {
+    if !is_valid_profile_name(name) {
+        return Ok(ProfileNameStatus::NotAllowed);
+    }
+
+    let res = get_client()
+        .get(format!(
+            "https://api.minecraftservices.com/minecraft/profile/name/{}/available",
+            name
+        ))
+        .bearer_auth(mc_token)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await
+        .map_err(|e| format_reqwest_error("查询角色名网络错误", e))?;
+
+    let status = res.status();
+    let text = res.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(minecraft_api_error("查询角色名", status, text));
+    }
+
+    let data: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("解析角色名查询结果失败: {}", e))?;
+    serde_json::from_value(data["status"].clone())
+        .map_err(|_| format!("未知的角色名状态: {}", data["status"]))
+}
+
+/// 为尚未创建角色的账号创建 Java 版角色
+pub async fn create_profile(mc_token: &str, name: &str) -> Result<McProfile, String> {
+    if !is_valid_profile_name(name) {
+        return Err("角色名需为 3~16 位字母、数字或下划线".to_string());
+    }
+
+    let res = get_client()
+        .post("https://api.minecraftservices.com/minecraft/profile")
+        .bearer_auth(mc_token)
+        .header(reqwest::header::ACCEPT, "application/json")
+        .json(&serde_json::json!({ "profileName": name }))
+        .send()
+        .await
+        .map_err(|e| format_reqwest_error("创建角色网络错误", e))?;
+
+    let status = res.status();
+    let text = res.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let detail = serde_json::from_str::<serde_json::Value>(&text)
+            .ok()
+            .and_then(|data| data["details"]["status"].as_str().map(str::to_string));
+        return Err(match detail.as_deref() {
+            Some("DUPLICATE") => "该角色名已被占用".to_string(),
+            Some("NOT_ALLOWED") => "该角色名不允许使用".to_string(),
+            _ => minecraft_api_error("创建角色", status, text),
+        });
+    }
+    serde_json::from_str(&text).map_err(|e| format!("解析游戏档案失败: {}", e))
+}
+
 /// 获取 Profile 中当前激活皮肤的 URL
 pub fn active_skin_url(profile: &McProfile) -> Option<&str> {
     profile
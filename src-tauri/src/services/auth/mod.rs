@@ -4,18 +4,24 @@
 // 将微软认证、Xbox 认证、Minecraft API、皮肤库、离线账号等子模块组合起来，
 // 对外暴露与原 auth_service.rs 完全一致的公共 API，保持调用层零改动。
 
+pub mod account_store;
 pub mod authlib;
 pub(crate) mod http;
+pub mod loopback;
 pub mod microsoft;
 pub mod minecraft;
 pub mod offline;
+pub mod ownership;
 pub mod paths;
 pub mod skin_png;
 pub mod wardrobe;
 pub mod xbox;
 
-use crate::domain::auth::{Account, AccountType, McProfile};
+use crate::domain::auth::{
+    Account, AccountType, McProfile, MinecraftLoginError, MinecraftOwnership,
+};
 use std::path::Path;
+use std::time::Duration;
 use tauri::{AppHandle, Runtime};
 
 // ==========================================
@@ -25,19 +31,68 @@ use tauri::{AppHandle, Runtime};
 pub use authlib::login_authlib;
 pub use microsoft::request_device_code;
 
+/// 浏览器登录等待用户完成授权的最长时间
+const BROWSER_LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// 轮询设备码授权并完成完整的 MS -> Xbox -> MC 认证链
 pub async fn poll_and_exchange_token<R: Runtime>(
     app: &AppHandle<R>,
     device_code: &str,
     interval: u64,
-) -> Result<Account, String> {
+) -> Result<Account, MinecraftLoginError> {
     let (ms_access_token, ms_refresh_token) =
         microsoft::poll_for_token(device_code, interval).await?;
+    complete_microsoft_login(app, &ms_access_token, ms_refresh_token).await
+}
+
+/// 打开系统浏览器走授权码 + PKCE 流，由本地回环地址接收回调后完成认证链
+pub async fn login_microsoft_with_browser<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<Account, MinecraftLoginError> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let pkce = microsoft::PkcePair::generate();
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let listener = loopback::LoopbackListener::bind().await?;
+    let redirect_uri = listener.redirect_uri().to_string();
 
-    let (xsts_token, uhs) = xbox::authenticate(&ms_access_token).await?;
+    let authorize_url = microsoft::authorize_url(&redirect_uri, &state, &pkce.challenge)?;
+    app.opener()
+        .open_url(authorize_url, None::<&str>)
+        .map_err(|e| format!("无法打开系统浏览器: {}", e))?;
+
+    let code = listener
+        .wait_for_code(&state, BROWSER_LOGIN_TIMEOUT)
+        .await?;
+    let (ms_access_token, ms_refresh_token) = microsoft::exchange_code(
+        microsoft::TOKEN_ENDPOINT,
+        &code,
+        &redirect_uri,
+        &pkce.verifier,
+    )
+    .await?;
+    complete_microsoft_login(app, &ms_access_token, ms_refresh_token).await
+}
+
+/// 用 MS Token 走完 Xbox -> XSTS -> Minecraft 认证链，检查授权与角色后缓存皮肤
+async fn complete_microsoft_login<R: Runtime>(
+    app: &AppHandle<R>,
+    ms_access_token: &str,
+    ms_refresh_token: String,
+) -> Result<Account, MinecraftLoginError> {
+    let (xsts_token, uhs) = xbox::authenticate(ms_access_token).await?;
     let mc_token = minecraft::auth_minecraft(&xsts_token, &uhs).await?;
-    let profile = minecraft::get_minecraft_profile(&mc_token).await?;
+    let (profile, ownership) = ownership::resolve_profile(&mc_token, &ms_refresh_token).await?;
+    Ok(build_microsoft_account(app, mc_token, ms_refresh_token, profile, ownership).await)
+}
 
+async fn build_microsoft_account<R: Runtime>(
+    app: &AppHandle<R>,
+    mc_token: String,
+    ms_refresh_token: String,
+    profile: McProfile,
+    ownership: MinecraftOwnership,
+) -> Account {
     let skin_url = minecraft::active_skin_url(&profile).map(str::to_string);
     let cape_url = minecraft::active_cape_url(&profile).map(str::to_string);
     let _ = minecraft::cache_account_assets(
@@ -49,7 +104,7 @@ pub async fn poll_and_exchange_token<R: Runtime>(
     )
     .await;
 
-    Ok(Account {
+    Account {
         id: profile.id.clone(),
         account_type: AccountType::Microsoft,
         username: profile.name,
@@ -60,43 +115,35 @@ pub async fn poll_and_exchange_token<R: Runtime>(
         skin_url,
         cape_url,
         authlib_api_root: None,
-    })
+        authlib_server: None,
+        minecraft_ownership: Some(ownership),
+    }
 }
 
-/// 使用 Refresh Token 刷新完整的 MS -> Xbox -> MC 认证链
-pub async fn refresh_microsoft_token<R: Runtime>(
+/// 为登录时提示“尚未创建角色”的账号创建角色并完成登录
+pub async fn create_minecraft_profile<R: Runtime>(
     app: &AppHandle<R>,
-    refresh_token: &str,
+    ticket: &str,
+    name: &str,
 ) -> Result<Account, String> {
-    let (ms_access_token, new_refresh_token) = microsoft::refresh_token(refresh_token).await?;
-
-    let (xsts_token, uhs) = xbox::authenticate(&ms_access_token).await?;
-    let mc_token = minecraft::auth_minecraft(&xsts_token, &uhs).await?;
-    let profile = minecraft::get_minecraft_profile(&mc_token).await?;
-
-    let skin_url = minecraft::active_skin_url(&profile).map(str::to_string);
-    let cape_url = minecraft::active_cape_url(&profile).map(str::to_string);
-    let _ = minecraft::cache_account_assets(
+    let (profile, pending) = ownership::create_profile(ticket, name).await?;
+    Ok(build_microsoft_account(
         app,
-        &profile.id,
-        &profile.id,
-        skin_url.as_deref(),
-        cape_url.as_deref(),
+        pending.mc_token,
+        pending.ms_refresh_token,
+        profile,
+        pending.ownership,
     )
-    .await;
+    .await)
+}
 
-    Ok(Account {
-        id: profile.id.clone(),
-        account_type: AccountType::Microsoft,
-        username: profile.name,
-        uuid: profile.id,
-        access_token: mc_token,
-        refresh_token: Some(new_refresh_token),
-        expires_at: Some(chrono::Utc::now().timestamp() + 86400),
-        skin_url,
-        cape_url,
-        authlib_api_root: None,
-    })
+/// 使用 Refresh Token 刷新完整的 MS -> Xbox -> MC 认证链
+pub async fn refresh_microsoft_token<R: Runtime>(
+    app: &AppHandle<R>,
+    refresh_token: &str,
+) -> Result<Account, MinecraftLoginError> {
+    let (ms_access_token, new_refresh_token) = microsoft::refresh_token(refresh_token).await?;
+    complete_microsoft_login(app, &ms_access_token, new_refresh_token).await
 }
 
 /// 获取衣柜 Profile（含 Profile 资产缓存）
@@ -0,0 +1,203 @@
+// src-tauri/src/services/auth/ownership.rs
+//
+// 微软登录后的 Java 版授权与角色检查。
+// 根据 entitlements/mcstore 区分 XGP、购买与无授权；已有授权但未创建角色的账号，
+// 令牌暂存在后端，前端凭 ticket 查询角色名并创建角色。
+
+use crate::domain::auth::{McProfile, MinecraftLoginError, MinecraftOwnership, ProfileNameStatus};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use super::minecraft;
+
+const GAME_PASS_PREFIX: &str = "product_game_pass";
+const OWNED_ITEMS: [&str; 2] = ["product_minecraft", "game_minecraft"];
+/// 等待用户创建角色的最长时间，超过后需要重新登录
+const PENDING_SETUP_TTL: Duration = Duration::from_secs(900);
+
+#[derive(Clone)]
+pub struct PendingProfileSetup {
+    pub mc_token: String,
+    pub ms_refresh_token: String,
+    pub ownership: MinecraftOwnership,
+    created_at: Instant,
+}
+
+static PENDING_SETUPS: Lazy<Mutex<HashMap<String, PendingProfileSetup>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 按授权条目判定授权来源；购买是永久授权，优先于可能过期的 XGP
+pub fn classify_entitlements(names: &[String]) -> MinecraftOwnership {
+    if names
+        .iter()
+        .any(|name| OWNED_ITEMS.contains(&name.as_str()))
+    {
+        MinecraftOwnership::Owned
+    } else if names.iter().any(|name| name.starts_with(GAME_PASS_PREFIX)) {
+        MinecraftOwnership::GamePass
+    } else {
+        MinecraftOwnership::NoLicense
+    }
+}
+
+/// 已取到档案时的授权状态：条目为空不能断定来源，记为未知而不是 XGP
+fn ownership_with_profile(ownership: MinecraftOwnership) -> MinecraftOwnership {
+    match ownership {
+        MinecraftOwnership::NoLicense => MinecraftOwnership::Unknown,
+        other => other,
+    }
+}
+
+/// mcstore 请求失败（如非 2xx）时无法判定来源，记为未知，继续以档案为准
+fn ownership_from_entitlements(entitlements: Result<Vec<String>, String>) -> MinecraftOwnership {
+    match entitlements {
+        Ok(names) => classify_entitlements(&names),
+        Err(err) => {
+            log::warn!("Failed to fetch Minecraft entitlements: {}", err);
+            MinecraftOwnership::Unknown
+        }
+    }
+}
+
+fn store_pending(setup: PendingProfileSetup) -> Result<String, String> {
+    let ticket = Uuid::new_v4().to_string();
+    let mut setups = PENDING_SETUPS
+        .lock()
+        .map_err(|_| "登录状态已损坏".to_string())?;
+    setups.retain(|_, pending| pending.created_at.elapsed() < PENDING_SETUP_TTL);
+    setups.insert(ticket.clone(), setup);
+    Ok(ticket)
+}
+
+fn pending_setup(ticket: &str) -> Result<PendingProfileSetup, String> {
+    PENDING_SETUPS
+        .lock()
+        .map_err(|_| "登录状态已损坏".to_string())?
+        .get(ticket)
+        .filter(|pending| pending.created_at.elapsed() < PENDING_SETUP_TTL)
+        .cloned()
+        .ok_or_else(|| "创建角色的会话已过期，请重新登录".to_string())
+}
+
+/// 检查授权并取得游戏档案；无授权或无角色时返回对应的类型化错误
+pub async fn resolve_profile(
+    mc_token: &str,
+    ms_refresh_token: &str,
+) -> Result<(McProfile, MinecraftOwnership), MinecraftLoginError> {
+    let ownership = ownership_from_entitlements(minecraft::fetch_entitlements(mc_token).await);
+    let profile = minecraft::fetch_minecraft_profile(mc_token).await?;
+
+    match (profile, ownership) {
+        // mcstore 条目偶尔为空，但能取到档案就说明可以游玩
+        (Some(profile), ownership) => Ok((profile, ownership_with_profile(ownership))),
+        (None, MinecraftOwnership::NoLicense) => Err(MinecraftLoginError::NoLicense {
+            message: "该微软账号未购买 Minecraft Java 版，也没有有效的 XGP 订阅".to_string(),
+        }),
+        (None, ownership) => {
+            let ticket = store_pending(PendingProfileSetup {
+                mc_token: mc_token.to_string(),
+                ms_refresh_token: ms_refresh_token.to_string(),
+                ownership,
+                created_at: Instant::now(),
+            })?;
+            Err(MinecraftLoginError::NoProfile {
+                ticket,
+                ownership,
+                message: "该账号尚未创建 Minecraft 角色，请先设置角色名".to_string(),
+            })
+        }
+    }
+}
+
+/// 查询待创建角色的角色名是否可用
+pub async fn check_profile_name(ticket: &str, name: &str) -> Result<ProfileNameStatus, String> {
+    let pending = pending_setup(ticket)?;
+    minecraft::check_profile_name(&pending.mc_token, name.trim()).await
+}
+
+/// 创建角色，成功后移除暂存的会话
+pub async fn create_profile(
+    ticket: &str,
+    name: &str,
+) -> Result<(McProfile, PendingProfileSetup), String> {
+    let pending = pending_setup(ticket)?;
+    let profile = minecraft::create_profile(&pending.mc_token, name.trim()).await?;
+    if let Ok(mut setups) = PENDING_SETUPS.lock() {
+        setups.remove(ticket);
+    }
+    Ok((profile, pending))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn entitlements_distinguish_game_pass_owned_and_no_license() {
+        assert_eq!(
+            classify_entitlements(&names(&["product_minecraft", "game_minecraft"])),
+            MinecraftOwnership::Owned
+        );
+        assert_eq!(
+            classify_entitlements(&names(&["game_minecraft", "product_game_pass_ultimate"])),
+            MinecraftOwnership::Owned
+        );
+        assert_eq!(
+            classify_entitlements(&names(&["product_game_pass_pc"])),
+            MinecraftOwnership::GamePass
+        );
+        assert_eq!(
+            classify_entitlements(&names(&["product_minecraft_bedrock"])),
+            MinecraftOwnership::NoLicense
+        );
+        assert_eq!(classify_entitlements(&[]), MinecraftOwnership::NoLicense);
+    }
+
+    #[test]
+    fn profile_without_entitlements_is_unknown_not_game_pass() {
+        assert_eq!(
+            ownership_with_profile(MinecraftOwnership::NoLicense),
+            MinecraftOwnership::Unknown
+        );
+        assert_eq!(
+            ownership_with_profile(MinecraftOwnership::Owned),
+            MinecraftOwnership::Owned
+        );
+        assert_eq!(
+            ownership_with_profile(MinecraftOwnership::GamePass),
+            MinecraftOwnership::GamePass
+        );
+    }
+
+    #[test]
+    fn failed_entitlement_request_is_unknown_ownership() {
+        assert_eq!(
+            ownership_from_entitlements(Err("获取 Minecraft 授权信息失败 (HTTP 503)".to_string())),
+            MinecraftOwnership::Unknown
+        );
+        assert_eq!(
+            ownership_from_entitlements(Ok(names(&["product_game_pass_pc"]))),
+            MinecraftOwnership::GamePass
+        );
+    }
+
+    #[test]
+    fn pending_setup_is_looked_up_by_ticket() {
+        let ticket = store_pending(PendingProfileSetup {
+            mc_token: "mc".to_string(),
+            ms_refresh_token: "refresh".to_string(),
+            ownership: MinecraftOwnership::Owned,
+            created_at: Instant::now(),
+        })
+        .unwrap();
+        assert_eq!(pending_setup(&ticket).unwrap().mc_token, "mc");
+        assert!(pending_setup("unknown").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 // src-tauri/src/services/config_service.rs
+use crate::domain::library::RemoteStoreSettings;
 use crate::domain::runtime::MemoryAllocationMode;
 use crate::error::AppResult;
 use serde::Deserialize;
@@ -75,6 +76,14 @@ fn default_playtime_auto_sync() -> bool {
     true
 }
 
+fn default_modpack_update_auto_check() -> bool {
+    true
+}
+
+fn default_modpack_update_interval_hours() -> u64 {
+    6
+}
+
 fn default_playtime_remote_path() -> String {
     "PiLauncher/playtime".to_string()
 }
@@ -129,6 +138,8 @@ pub struct PlaytimeSyncSettings {
     pub password: String,
     #[serde(default = "default_playtime_remote_path")]
     pub remote_path: String,
+    #[serde(default)]
+    pub store: RemoteStoreSettings,
 }
 
 impl Default for PlaytimeSyncSettings {
@@ -140,6 +151,25 @@ impl Default for PlaytimeSyncSettings {
             username: String::new(),
             password: String::new(),
             remote_path: default_playtime_remote_path(),
+            store: RemoteStoreSettings::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModpackUpdateSettings {
+    #[serde(default = "default_modpack_update_auto_check")]
+    pub auto_check: bool,
+    #[serde(default = "default_modpack_update_interval_hours")]
+    pub interval_hours: u64,
+}
+
+impl Default for ModpackUpdateSettings {
+    fn default() -> Self {
+        Self {
+            auto_check: default_modpack_update_auto_check(),
+            interval_hours: default_modpack_update_interval_hours(),
         }
     }
 }
@@ -325,6 +355,17 @@ impl ConfigService {
         PlaytimeSyncSettings::default()
     }
 
+    pub fn get_modpack_update_settings<R: Runtime>(app: &AppHandle<R>) -> ModpackUpdateSettings {
+        if let Some(json) = Self::get_settings_json(app) {
+            if let Some(val) = json.pointer("/state/settings/modpackUpdate") {
+                if let Ok(s) = serde_json::from_value(val.clone()) {
+                    return s;
+                }
+            }
+        }
+        ModpackUpdateSettings::default()
+    }
+
     pub fn get_game_settings<R: Runtime>(app: &AppHandle<R>) -> GameSettings {
         if let Some(json) = Self::get_settings_json(app) {
             if let Some(val) = json.pointer("/state/settings/game") {
@@ -12,7 +12,7 @@ pub struct AppDatabase {
 pub struct DbService;
 
 impl DbService {
-    const CURRENT_SCHEMA_VERSION: i64 = 5;
+    const CURRENT_SCHEMA_VERSION: i64 = 7;
 
     pub async fn init_db(config_dir: &Path) -> Result<SqlitePool, String> {
         if !config_dir.exists() {
@@ -85,9 +85,24 @@ impl DbService {
                 trust_level TEXT DEFAULT 'trusted',
                 trusted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
                 last_used TIMESTAMP,
+                address TEXT DEFAULT '',
+                last_seen TIMESTAMP,
+                expires_at INTEGER,
+                revoked_at INTEGER,
                 FOREIGN KEY (user_id) REFERENCES users(id)
             );
 
+            CREATE TABLE IF NOT EXISTS trust_audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                device_uuid TEXT NOT NULL,
+                event TEXT NOT NULL,
+                detail TEXT DEFAULT '',
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_trust_audit_log_device
+                ON trust_audit_log(device_uuid, created_at);
+
             CREATE TABLE IF NOT EXISTS transfers (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 transfer_uuid TEXT,
@@ -324,6 +339,16 @@ impl DbService {
             Self::record_migration(pool, 5, "library_resource_mappings").await?;
         }
 
+        if !Self::is_migration_applied(pool, 6).await? {
+            Self::migrate_trusted_device_addresses(pool).await?;
+            Self::record_migration(pool, 6, "trusted_device_addresses").await?;
+        }
+
+        if !Self::is_migration_applied(pool, 7).await? {
+            Self::migrate_trusted_device_expiry(pool).await?;
+            Self::record_migration(pool, 7, "trusted_device_expiry").await?;
+        }
+
         sqlx::query(
             "INSERT OR REPLACE INTO app_meta (key, value)
              VALUES ('schema_version', ?)",
@@ -650,6 +675,58 @@ impl DbService {
         Ok(())
     }
 
+    async fn migrate_trusted_device_addresses(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query("PRAGMA table_info(trusted_devices)")
+            .fetch_all(pool)
+            .await?;
+        let has_column = |name: &str| {
+            rows.iter().any(|row| {
+                let col_name: String = sqlx::Row::get(row, "name");
+                col_name == name
+            })
+        };
+
+        if !has_column("address") {
+            sqlx::query("ALTER TABLE trusted_devices ADD COLUMN address TEXT DEFAULT ''")
+                .execute(pool)
+                .await?;
+        }
+
+        if !has_column("last_seen") {
+            sqlx::query("ALTER TABLE trusted_devices ADD COLUMN last_seen TIMESTAMP")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn migrate_trusted_device_expiry(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+        let rows = sqlx::query("PRAGMA table_info(trusted_devices)")
+            .fetch_all(pool)
+            .await?;
+        let has_column = |name: &str| {
+            rows.iter().any(|row| {
+                let col_name: String = sqlx::Row::get(row, "name");
+                col_name == name
+            })
+        };
+
+        if !has_column("expires_at") {
+            sqlx::query("ALTER TABLE trusted_devices ADD COLUMN expires_at INTEGER")
+                .execute(pool)
+                .await?;
+        }
+
+        if !has_column("revoked_at") {
+            sqlx::query("ALTER TABLE trusted_devices ADD COLUMN revoked_at INTEGER")
+                .execute(pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     async fn replace_instance_tag_rows(
         pool: &SqlitePool,
         instance_id: &str,
@@ -1,7 +1,7 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
 use crate::services;
 
@@ -46,8 +46,14 @@ pub fn start(app: AppHandle, lan_state: Arc<services::lan::http_api::SharedLanSt
                     &identity.device_id,
                     &identity.device_name,
                     &identity.public_key_b64,
-                    9999,
+                    services::lan::http_api::DEFAULT_HTTP_PORT,
                 );
+                // mDNS 被拦截时的后备发现方式
+                tauri::async_runtime::spawn(services::lan::udp_discovery::start_responder(
+                    state_for_lan.clone(),
+                    config_dir,
+                    services::lan::http_api::DEFAULT_HTTP_PORT,
+                ));
             }
             _ => {
                 println!("[PiLauncher] Base path is not configured.");
@@ -55,11 +61,27 @@ pub fn start(app: AppHandle, lan_state: Arc<services::lan::http_api::SharedLanSt
             }
         }
 
+        let db = handle_for_lan.state::<services::db_service::AppDatabase>();
+        match services::lan::trust_store::TrustStore::prune_audit_log(&db.pool).await {
+            Ok(removed) if removed > 0 => {
+                println!("[PiLauncher] Pruned {} old trust audit entries.", removed)
+            }
+            Ok(_) => {}
+            Err(e) => println!("[PiLauncher] Failed to prune trust audit log: {}", e),
+        }
+
         println!("[PiLauncher] Starting LAN HTTP RPC server on port 9999...");
         println!("[PiLauncher] ========================================\n");
 
-        services::lan::http_api::start_http_server(handle_for_lan, state_for_lan, 9999).await;
+        services::lan::http_api::start_http_server(
+            handle_for_lan,
+            state_for_lan,
+            services::lan::http_api::DEFAULT_HTTP_PORT,
+        )
+        .await;
     });
 
+    services::modpack_service::remote::start_background_update_check(app.clone());
+
     services::gamepad_service::GamepadService::start_listener(app);
 }
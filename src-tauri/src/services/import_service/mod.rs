@@ -1,2 +1,3 @@
 pub mod local_instance_service;
+pub mod multimc_service;
 pub mod third_party_service;
@@ -0,0 +1,467 @@
+// MultiMC / Prism Launcher 实例格式：instance.cfg + mmc-pack.json + .minecraft/minecraft 子目录
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MMC_INSTANCE_CFG: &str = "instance.cfg";
+pub const MMC_PACK_FILE: &str = "mmc-pack.json";
+pub const MMC_GAME_DIR: &str = ".minecraft";
+
+const UID_MINECRAFT: &str = "net.minecraft";
+const UID_INTERMEDIARY: &str = "net.fabricmc.intermediary";
+const UID_FABRIC: &str = "net.fabricmc.fabric-loader";
+const UID_QUILT: &str = "org.quiltmc.quilt-loader";
+const UID_FORGE: &str = "net.minecraftforge";
+const UID_NEOFORGE: &str = "net.neoforged";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MmcPack {
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+    #[serde(default)]
+    pub components: Vec<MmcComponent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MmcComponent {
+    pub uid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_version: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub important: bool,
+}
+
+impl MmcComponent {
+    fn resolved_version(&self) -> Option<String> {
+        self.version
+            .as_deref()
+            .or(self.cached_version.as_deref())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+}
+
+fn default_format_version() -> u32 {
+    1
+}
+
+/// instance.cfg 中与启动相关、需要映射到 InstanceConfig 的设置。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MmcInstanceSettings {
+    pub min_memory: Option<u32>,
+    pub max_memory: Option<u32>,
+    pub jvm_args: Option<String>,
+    pub java_path: Option<String>,
+    pub window_width: Option<u32>,
+    pub window_height: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MmcInstance {
+    pub root: PathBuf,
+    pub game_dir: PathBuf,
+    pub name: String,
+    pub mc_version: String,
+    pub loader_type: String,
+    pub loader_version: String,
+    pub settings: MmcInstanceSettings,
+}
+
+pub fn is_multimc_instance_dir(dir: &Path) -> bool {
+    dir.join(MMC_INSTANCE_CFG).is_file() && dir.join(MMC_PACK_FILE).is_file()
+}
+
+/// 解析 instance.cfg（INI 风格，忽略分节头）。
+pub fn parse_instance_cfg(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('[') && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), unescape_cfg_value(value.trim())))
+        .collect()
+}
+
+fn unescape_cfg_value(value: &str) -> String {
+    let unquoted = value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(value);
+    let mut result = String::with_capacity(unquoted.len());
+    let mut chars = unquoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some(c @ ('\\' | '"' | '#')) => result.push(c),
+            // 未知转义原样保留，兼容手写的 Windows 路径
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 按 QSettings INI 规则转义：含特殊字符的值加引号，换行、引号与反斜杠写成转义序列，
+/// 避免 JvmArgs 或实例名中的换行、`=` 破坏 instance.cfg
+fn escape_cfg_value(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value
+            .chars()
+            .any(|c| matches!(c, '=' | '"' | ';' | ',' | '#' | '\\') || c.is_control());
+    if !needs_quotes {
+        return value.to_string();
+    }
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            other => escaped.push(other),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn cfg_flag(cfg: &BTreeMap<String, String>, key: &str) -> bool {
+    cfg.get(key)
+        .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
+fn cfg_u32(cfg: &BTreeMap<String, String>, key: &str) -> Option<u32> {
+    cfg.get(key).and_then(|value| value.trim().parse().ok())
+}
+
+fn cfg_string(cfg: &BTreeMap<String, String>, key: &str) -> Option<String> {
+    cfg.get(key)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub fn settings_from_cfg(cfg: &BTreeMap<String, String>) -> MmcInstanceSettings {
+    let mut settings = MmcInstanceSettings::default();
+
+    if cfg_flag(cfg, "OverrideMemory") {
+        settings.min_memory = cfg_u32(cfg, "MinMemAlloc");
+        settings.max_memory = cfg_u32(cfg, "MaxMemAlloc");
+    }
+    if cfg_flag(cfg, "OverrideJavaArgs") {
+        settings.jvm_args = cfg_string(cfg, "JvmArgs");
+    }
+    if cfg_flag(cfg, "OverrideJavaLocation") || cfg_flag(cfg, "OverrideJava") {
+        settings.java_path = cfg_string(cfg, "JavaPath");
+    }
+    if cfg_flag(cfg, "OverrideWindow") {
+        settings.window_width = cfg_u32(cfg, "MinecraftWinWidth");
+        settings.window_height = cfg_u32(cfg, "MinecraftWinHeight");
+    }
+
+    settings
+}
+
+/// 从组件列表中读取 (mc_version, loader_type, loader_version)。
+pub fn resolve_components(pack: &MmcPack) -> Option<(String, String, String)> {
+    let mc_version = pack
+        .components
+        .iter()
+        .find(|component| component.uid == UID_MINECRAFT)
+        .and_then(MmcComponent::resolved_version)?;
+
+    for (uid, loader) in [
+        (UID_FABRIC, "fabric"),
+        (UID_QUILT, "quilt"),
+        (UID_NEOFORGE, "neoforge"),
+        (UID_FORGE, "forge"),
+    ] {
+        if let Some(version) = pack
+            .components
+            .iter()
+            .find(|component| component.uid == uid)
+            .and_then(MmcComponent::resolved_version)
+        {
+            return Some((mc_version, loader.to_string(), version));
+        }
+    }
+
+    Some((mc_version, "vanilla".to_string(), String::new()))
+}
+
+/// 游戏目录优先使用 `.minecraft`，其次是旧版 MultiMC 的 `minecraft`。
+pub fn resolve_game_dir(instance_root: &Path) -> PathBuf {
+    let dot_minecraft = instance_root.join(MMC_GAME_DIR);
+    if dot_minecraft.is_dir() {
+        return dot_minecraft;
+    }
+    let legacy = instance_root.join("minecraft");
+    if legacy.is_dir() {
+        return legacy;
+    }
+    dot_minecraft
+}
+
+pub fn read_multimc_instance(dir: &Path) -> Result<Option<MmcInstance>, String> {
+    if !is_multimc_instance_dir(dir) {
+        return Ok(None);
+    }
+
+    let cfg_content =
+        fs::read_to_string(dir.join(MMC_INSTANCE_CFG)).map_err(|error| error.to_string())?;
+    let cfg = parse_instance_cfg(&cfg_content);
+    let pack_content =
+        fs::read_to_string(dir.join(MMC_PACK_FILE)).map_err(|error| error.to_string())?;
+    let pack: MmcPack = serde_json::from_str(&pack_content)
+        .map_err(|error| format!("Failed to parse mmc-pack.json: {}", error))?;
+
+    let Some((mc_version, loader_type, loader_version)) = resolve_components(&pack) else {
+        return Ok(None);
+    };
+
+    let folder_name = dir
+        .file_name()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = cfg_string(&cfg, "name").unwrap_or(folder_name);
+
+    Ok(Some(MmcInstance {
+        root: dir.to_path_buf(),
+        game_dir: resolve_game_dir(dir),
+        name,
+        mc_version,
+        loader_type,
+        loader_version,
+        settings: settings_from_cfg(&cfg),
+    }))
+}
+
+/// Prism 与 MultiMC 的数据根目录都包含 `instances/`，通过全局配置文件区分。
+pub fn detect_launcher_root(path: &Path) -> Option<(PathBuf, String)> {
+    let hint_for = |root: &Path| {
+        if root.join("prismlauncher.cfg").exists() {
+            "PrismLauncher".to_string()
+        } else {
+            "MultiMC".to_string()
+        }
+    };
+
+    if path.join("instances").is_dir() && has_instance_children(&path.join("instances")) {
+        return Some((path.join("instances"), hint_for(path)));
+    }
+
+    if has_instance_children(path) {
+        let hint = path
+            .parent()
+            .map(hint_for)
+            .unwrap_or_else(|| "MultiMC".to_string());
+        return Some((path.to_path_buf(), hint));
+    }
+
+    None
+}
+
+fn has_instance_children(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|entry| is_multimc_instance_dir(&entry.path()))
+        })
+        .unwrap_or(false)
+}
+
+pub fn build_mmc_pack(mc_version: &str, loader_type: &str, loader_version: &str) -> MmcPack {
+    let component = |uid: &str, version: &str, important: bool| MmcComponent {
+        uid: uid.to_string(),
+        version: Some(version.to_string()),
+        cached_version: None,
+        important,
+    };
+
+    let mut components = vec![component(UID_MINECRAFT, mc_version, true)];
+    match loader_type.trim().to_ascii_lowercase().as_str() {
+        "fabric" => {
+            components.push(component(UID_INTERMEDIARY, mc_version, false));
+            components.push(component(UID_FABRIC, loader_version, false));
+        }
+        "quilt" => {
+            components.push(component(UID_INTERMEDIARY, mc_version, false));
+            components.push(component(UID_QUILT, loader_version, false));
+        }
+        "forge" => components.push(component(UID_FORGE, loader_version, false)),
+        "neoforge" => components.push(component(UID_NEOFORGE, loader_version, false)),
+        _ => {}
+    }
+
+    MmcPack {
+        format_version: 1,
+        components,
+    }
+}
+
+pub fn render_instance_cfg(name: &str, settings: &MmcInstanceSettings) -> String {
+    let mut lines = vec![
+        "[General]".to_string(),
+        "ConfigVersion=1.2".to_string(),
+        "InstanceType=OneSix".to_string(),
+        format!("name={}", escape_cfg_value(name)),
+    ];
+
+    if settings.min_memory.is_some() || settings.max_memory.is_some() {
+        lines.push("OverrideMemory=true".to_string());
+        if let Some(min) = settings.min_memory {
+            lines.push(format!("MinMemAlloc={}", min));
+        }
+        if let Some(max) = settings.max_memory {
+            lines.push(format!("MaxMemAlloc={}", max));
+        }
+    }
+    if let Some(args) = &settings.jvm_args {
+        lines.push("OverrideJavaArgs=true".to_string());
+        lines.push(format!("JvmArgs={}", escape_cfg_value(args)));
+    }
+    if settings.window_width.is_some() || settings.window_height.is_some() {
+        lines.push("OverrideWindow=true".to_string());
+        if let Some(width) = settings.window_width {
+            lines.push(format!("MinecraftWinWidth={}", width));
+        }
+        if let Some(height) = settings.window_height {
+            lines.push(format!("MinecraftWinHeight={}", height));
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_mmc_pack, detect_launcher_root, parse_instance_cfg, read_multimc_instance,
+        render_instance_cfg, resolve_components, settings_from_cfg, MmcInstanceSettings,
+    };
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    #[test]
+    fn parses_instance_cfg_overrides() {
+        let cfg = parse_instance_cfg(
+            "[General]\nname=My Pack\nOverrideMemory=true\nMinMemAlloc=2048\nMaxMemAlloc=6144\nOverrideJavaArgs=true\nJvmArgs=\"-XX:+UseG1GC\"\nOverrideWindow=false\nMinecraftWinWidth=1920\n",
+        );
+        let settings = settings_from_cfg(&cfg);
+
+        assert_eq!(cfg.get("name").map(String::as_str), Some("My Pack"));
+        assert_eq!(settings.min_memory, Some(2048));
+        assert_eq!(settings.max_memory, Some(6144));
+        assert_eq!(settings.jvm_args.as_deref(), Some("-XX:+UseG1GC"));
+        assert_eq!(settings.window_width, None);
+    }
+
+    #[test]
+    fn resolves_loader_components() {
+        let pack = build_mmc_pack("1.20.1", "Fabric", "0.15.11");
+        assert_eq!(
+            resolve_components(&pack),
+            Some((
+                "1.20.1".to_string(),
+                "fabric".to_string(),
+                "0.15.11".to_string()
+            ))
+        );
+
+        let pack = build_mmc_pack("1.20.1", "neoforge", "47.1.0");
+        assert_eq!(
+            resolve_components(&pack).map(|(_, loader, _)| loader),
+            Some("neoforge".to_string())
+        );
+    }
+
+    #[test]
+    fn escapes_instance_cfg_values() {
+        let settings = MmcInstanceSettings {
+            jvm_args: Some("-Dfoo=bar\n-Dpath=\"C:\\Games\"".to_string()),
+            ..MmcInstanceSettings::default()
+        };
+        let rendered = render_instance_cfg("Pack = One\nEvil=true", &settings);
+        assert!(!rendered.contains("\nEvil=true"));
+
+        let cfg = parse_instance_cfg(&rendered);
+        assert_eq!(cfg.get("Evil"), None);
+        assert_eq!(
+            cfg.get("name").map(String::as_str),
+            Some("Pack = One\nEvil=true")
+        );
+        assert_eq!(settings_from_cfg(&cfg).jvm_args, settings.jvm_args);
+
+        let legacy = parse_instance_cfg("JavaPath=C:\\Java\\bin\\javaw.exe\n");
+        assert_eq!(
+            legacy.get("JavaPath").map(String::as_str),
+            Some("C:\\Java\\bin\\javaw.exe")
+        );
+    }
+
+    #[test]
+    fn reads_prism_instance_from_launcher_root() {
+        let root = create_temp_dir("prism_root");
+        let instance_dir = root.join("instances").join("survival");
+        fs::create_dir_all(instance_dir.join(".minecraft").join("mods")).unwrap();
+        fs::write(root.join("prismlauncher.cfg"), "").unwrap();
+        let settings = MmcInstanceSettings {
+            min_memory: Some(1024),
+            max_memory: Some(8192),
+            jvm_args: Some("-Dfoo=bar".to_string()),
+            java_path: None,
+            window_width: Some(1600),
+            window_height: Some(900),
+        };
+        fs::write(
+            instance_dir.join("instance.cfg"),
+            render_instance_cfg("Survival", &settings),
+        )
+        .unwrap();
+        fs::write(
+            instance_dir.join("mmc-pack.json"),
+            serde_json::to_string(&build_mmc_pack("1.21.1", "quilt", "0.26.0")).unwrap(),
+        )
+        .unwrap();
+
+        let (instances_dir, hint) = detect_launcher_root(&root).unwrap();
+        assert_eq!(instances_dir, root.join("instances"));
+        assert_eq!(hint, "PrismLauncher");
+
+        let instance = read_multimc_instance(&instance_dir).unwrap().unwrap();
+        assert_eq!(instance.name, "Survival");
+        assert_eq!(instance.mc_version, "1.21.1");
+        assert_eq!(instance.loader_type, "quilt");
+        assert_eq!(instance.game_dir, instance_dir.join(".minecraft"));
+        assert_eq!(instance.settings, settings);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    fn create_temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", prefix, Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
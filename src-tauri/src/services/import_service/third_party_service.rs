@@ -6,6 +6,7 @@ use crate::domain::modpack::{
     ThirdPartyImportProgressEvent, ThirdPartyImportResult, ThirdPartyImportSource,
 };
 use crate::services::config_service::ConfigService;
+use crate::services::import_service::multimc_service::{self, MmcInstanceSettings};
 use crate::services::minecraft_service::{detect_missing_runtime, parse_third_party_json};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -20,9 +21,13 @@ pub(crate) struct ThirdPartyInstanceCandidate {
     pub mc_version: String,
     pub loader_type: String,
     pub loader_version: String,
+    /// MultiMC/Prism 实例的游戏目录（`.minecraft` 或 `minecraft`），`path` 则是实例根目录。
+    pub game_dir: Option<String>,
+    pub launch_settings: MmcInstanceSettings,
 }
 
 fn build_third_party_instance_config(candidate: &ThirdPartyInstanceCandidate) -> InstanceConfig {
+    let settings = &candidate.launch_settings;
     InstanceConfig {
         id: candidate.id.clone(),
         name: candidate.name.clone(),
@@ -32,16 +37,19 @@ fn build_third_party_instance_config(candidate: &ThirdPartyInstanceCandidate) ->
             version: candidate.loader_version.clone(),
         },
         java: JavaConfig {
-            path: "auto".to_string(),
+            path: settings
+                .java_path
+                .clone()
+                .unwrap_or_else(|| "auto".to_string()),
             version: "auto".to_string(),
         },
         memory: MemoryConfig {
-            min: 1024,
-            max: 4096,
+            min: settings.min_memory.unwrap_or(1024),
+            max: settings.max_memory.unwrap_or(4096),
         },
         resolution: ResolutionConfig {
-            width: 854,
-            height: 480,
+            width: settings.window_width.unwrap_or(854),
+            height: settings.window_height.unwrap_or(480),
         },
         play_time: 0.0,
         last_played: String::new(),
@@ -50,19 +58,25 @@ fn build_third_party_instance_config(candidate: &ThirdPartyInstanceCandidate) ->
         hero_logo: None,
         gamepad: None,
         custom_buttons: None,
-        third_party_path: Some(candidate.path.clone()),
+        third_party_path: Some(
+            candidate
+                .game_dir
+                .clone()
+                .unwrap_or_else(|| candidate.path.clone()),
+        ),
         server_binding: None,
         auto_join_server: None,
         tags: None,
-        jvm_args: None,
-        window_width: None,
-        window_height: None,
+        jvm_args: settings.jvm_args.clone(),
+        window_width: settings.window_width,
+        window_height: settings.window_height,
         is_favorite: None,
         global_metadata_settings: None,
         modpack_id: None,
         modpack_uuid: None,
         modpack_version: None,
         modpack_source: None,
+        modpack_version_id: None,
     }
 }
 
@@ -111,6 +125,28 @@ fn resolve_third_party_source(
     label: String,
     kind: String,
 ) -> Option<ThirdPartyImportSource> {
+    if let Some((instances_path, launcher_hint)) = multimc_service::detect_launcher_root(path) {
+        let root_path = instances_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| instances_path.clone());
+        return Some(ThirdPartyImportSource {
+            source_path: path.to_string_lossy().to_string(),
+            root_path: root_path.to_string_lossy().to_string(),
+            versions_path: instances_path.to_string_lossy().to_string(),
+            source_kind: "multimc".to_string(),
+            source_label: label,
+            launcher_hint,
+            has_assets: root_path.join("assets").exists(),
+            has_libraries: root_path.join("libraries").exists(),
+            instance_count: 0,
+            importable_count: 0,
+            already_imported_count: 0,
+            conflict_count: 0,
+            instances: Vec::new(),
+        });
+    }
+
     let mut root_path = path.to_path_buf();
     let mut versions_path = path.join("versions");
 
@@ -210,6 +246,23 @@ pub(crate) fn read_candidate_from_dir(
         return Ok(None);
     }
 
+    if let Some(instance) = multimc_service::read_multimc_instance(dir)? {
+        return Ok(Some(ThirdPartyInstanceCandidate {
+            id: dir_name.to_string(),
+            name: instance.name,
+            path: dir.to_string_lossy().to_string(),
+            version_json_path: dir
+                .join(multimc_service::MMC_PACK_FILE)
+                .to_string_lossy()
+                .to_string(),
+            mc_version: instance.mc_version,
+            loader_type: instance.loader_type,
+            loader_version: instance.loader_version,
+            game_dir: Some(instance.game_dir.to_string_lossy().to_string()),
+            launch_settings: instance.settings,
+        }));
+    }
+
     let version_json_path = dir.join(format!("{}.json", dir_name));
     if !version_json_path.exists() {
         return Ok(None);
@@ -228,6 +281,8 @@ pub(crate) fn read_candidate_from_dir(
         mc_version,
         loader_type,
         loader_version,
+        game_dir: None,
+        launch_settings: MmcInstanceSettings::default(),
     }))
 }
 
@@ -244,11 +299,14 @@ pub(crate) fn register_candidate_instance(
 
     fs::write(dest_dir.join("instance.json"), &config_content)
         .map_err(|error| error.to_string())?;
-    fs::write(
-        PathBuf::from(&candidate.path).join("instance.json"),
-        &config_content,
-    )
-    .map_err(|error| error.to_string())?;
+    // MultiMC/Prism 实例目录由原启动器管理，不写入额外文件。
+    if candidate.game_dir.is_none() {
+        fs::write(
+            PathBuf::from(&candidate.path).join("instance.json"),
+            &config_content,
+        )
+        .map_err(|error| error.to_string())?;
+    }
 
     Ok(())
 }
@@ -268,7 +326,7 @@ pub fn import_single_instance<R: Runtime>(
             .and_then(|value| value.to_str())
             .unwrap_or("instance");
         format!(
-            "Could not find {}.json. Select a third-party launcher versions/<instance> directory.",
+            "Could not find {}.json or instance.cfg. Select a third-party launcher versions/<instance> directory or a MultiMC/Prism instance.",
             id
         )
     })?;
@@ -336,7 +394,8 @@ pub fn import_launcher_source<R: Runtime>(
         "manual".to_string(),
     )
     .ok_or_else(|| {
-        "Directory is not a recognized .minecraft root or versions folder.".to_string()
+        "Directory is not a recognized .minecraft root, versions folder or MultiMC/Prism instances folder."
+            .to_string()
     })?;
 
     let source_path = resolved.source_path.clone();
@@ -441,15 +500,21 @@ pub fn import_launcher_source<R: Runtime>(
             Some(&instance.id),
         );
 
-        let candidate = ThirdPartyInstanceCandidate {
-            id: instance.id.clone(),
-            name: instance.name.clone(),
-            path: instance.path.clone(),
-            version_json_path: instance.version_json_path.clone(),
-            mc_version: instance.mc_version.clone(),
-            loader_type: instance.loader_type.clone(),
-            loader_version: instance.loader_version.clone(),
-        };
+        // 重新读取候选实例，以带上扫描结果中未包含的启动设置（内存、JVM 参数等）。
+        let candidate = read_candidate_from_dir(Path::new(&instance.path))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| ThirdPartyInstanceCandidate {
+                id: instance.id.clone(),
+                name: instance.name.clone(),
+                path: instance.path.clone(),
+                version_json_path: instance.version_json_path.clone(),
+                mc_version: instance.mc_version.clone(),
+                loader_type: instance.loader_type.clone(),
+                loader_version: instance.loader_version.clone(),
+                game_dir: None,
+                launch_settings: MmcInstanceSettings::default(),
+            });
 
         match register_candidate_instance(&candidate, &instances_dir) {
             Ok(()) => {
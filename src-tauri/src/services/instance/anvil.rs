@@ -0,0 +1,261 @@
+// src-tauri/src/services/instance/anvil.rs
+//
+// Anvil 区域文件（.mca）读写。文件头为 1024 个位置项与 1024 个时间戳，
+// 区块数据按 4KiB 扇区对齐存放，可能是 gzip / zlib / 未压缩，
+// 超大区块会外置到同目录的 c.<x>.<z>.mcc 文件中。
+use crate::services::instance::nbt::{self, NbtTag};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const REGION_CHUNKS: usize = 1024;
+const SECTOR_BYTES: usize = 4096;
+const HEADER_BYTES: usize = SECTOR_BYTES * 2;
+const EXTERNAL_CHUNK_FLAG: u8 = 0x80;
+
+/// 从 `r.<x>.<z>.mca` 文件名中解析区域坐标。
+pub fn parse_region_coords(file_name: &str) -> Option<(i32, i32)> {
+    let mut parts = file_name
+        .strip_prefix("r.")?
+        .strip_suffix(".mca")?
+        .split('.');
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((x, z))
+}
+
+pub struct RegionFile {
+    pub path: PathBuf,
+    pub region_x: i32,
+    pub region_z: i32,
+    bytes: Vec<u8>,
+}
+
+impl RegionFile {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let (region_x, region_z) = parse_region_coords(file_name)
+            .ok_or_else(|| format!("无效的区域文件名: {}", path.display()))?;
+        let bytes = fs::read(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            region_x,
+            region_z,
+            bytes,
+        })
+    }
+
+    pub fn file_size(&self) -> u64 {
+        self.bytes.len() as u64
+    }
+
+    fn header_u32(&self, index: usize) -> u32 {
+        self.bytes
+            .get(index * 4..index * 4 + 4)
+            .map(|raw| u32::from_be_bytes([raw[0], raw[1], raw[2], raw[3]]))
+            .unwrap_or(0)
+    }
+
+    /// 返回区块数据在文件中的字节范围；位置项为空或越界时视为不存在。
+    fn chunk_range(&self, index: usize) -> Option<std::ops::Range<usize>> {
+        if self.bytes.len() < HEADER_BYTES {
+            return None;
+        }
+        let location = self.header_u32(index);
+        let offset = (location >> 8) as usize;
+        let sectors = (location & 0xff) as usize;
+        if offset < 2 || sectors == 0 {
+            return None;
+        }
+        let start = offset * SECTOR_BYTES;
+        let end = (offset + sectors) * SECTOR_BYTES;
+        (start < self.bytes.len()).then(|| start..end.min(self.bytes.len()))
+    }
+
+    pub fn chunk_indices(&self) -> Vec<usize> {
+        (0..REGION_CHUNKS)
+            .filter(|index| self.chunk_range(*index).is_some())
+            .collect()
+    }
+
+    /// 区块在世界中的坐标（以区块为单位）。
+    pub fn chunk_coords(&self, index: usize) -> (i32, i32) {
+        (
+            self.region_x * 32 + (index % 32) as i32,
+            self.region_z * 32 + (index / 32) as i32,
+        )
+    }
+
+    fn external_chunk_path(&self, index: usize) -> PathBuf {
+        let (x, z) = self.chunk_coords(index);
+        self.path.with_file_name(format!("c.{}.{}.mcc", x, z))
+    }
+
+    /// 读取并解析区块 NBT。区块不存在或使用了不支持的压缩（如 LZ4）时返回 `Ok(None)`。
+    pub fn read_chunk(&self, index: usize) -> Result<Option<NbtTag>, String> {
+        let Some(range) = self.chunk_range(index) else {
+            return Ok(None);
+        };
+        let data = &self.bytes[range];
+        if data.len() < 5 {
+            return Err(format!("区块 {} 数据过短", index));
+        }
+        let length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let compression = data[4];
+
+        let external;
+        let payload = if compression & EXTERNAL_CHUNK_FLAG != 0 {
+            external = fs::read(self.external_chunk_path(index))
+                .map_err(|e| format!("读取外置区块失败: {}", e))?;
+            &external[..]
+        } else {
+            let end = (5 + length.saturating_sub(1)).min(data.len());
+            &data[5..end]
+        };
+
+        let mut raw = Vec::new();
+        match compression & !EXTERNAL_CHUNK_FLAG {
+            1 => {
+                GzDecoder::new(payload)
+                    .read_to_end(&mut raw)
+                    .map_err(|e| format!("区块 gzip 解压失败: {}", e))?;
+            }
+            2 => {
+                ZlibDecoder::new(payload)
+                    .read_to_end(&mut raw)
+                    .map_err(|e| format!("区块 zlib 解压失败: {}", e))?;
+            }
+            3 => raw.extend_from_slice(payload),
+            _ => return Ok(None),
+        }
+        nbt::parse(&raw).map(Some)
+    }
+
+    /// 仅保留 `keep` 返回 true 的区块并紧凑地写到 `dest`，被移除的外置区块文件一并删除。
+    /// 没有任何区块保留时删除目标文件并返回 `Ok(None)`，否则返回新文件大小。
+    pub fn write_retaining<F>(&self, dest: &Path, keep: F) -> Result<Option<u64>, String>
+    where
+        F: Fn(usize) -> bool,
+    {
+        let mut output = vec![0u8; HEADER_BYTES];
+        for index in 0..REGION_CHUNKS {
+            let Some(range) = self.chunk_range(index) else {
+                continue;
+            };
+            if !keep(index) {
+                let external = self.external_chunk_path(index);
+                if external.exists() {
+                    fs::remove_file(&external).map_err(|e| e.to_string())?;
+                }
+                continue;
+            }
+
+            let sector_offset = output.len() / SECTOR_BYTES;
+            let mut chunk = self.bytes[range].to_vec();
+            chunk.resize(chunk.len().div_ceil(SECTOR_BYTES) * SECTOR_BYTES, 0);
+            let sectors = chunk.len() / SECTOR_BYTES;
+            if sectors > 0xff {
+                return Err(format!("区块 {} 超出单个位置项可表示的扇区数", index));
+            }
+            let location = ((sector_offset as u32) << 8) | sectors as u32;
+            output[index * 4..index * 4 + 4].copy_from_slice(&location.to_be_bytes());
+            let timestamp = SECTOR_BYTES + index * 4;
+            output[timestamp..timestamp + 4].copy_from_slice(&self.bytes[timestamp..timestamp + 4]);
+            output.extend_from_slice(&chunk);
+        }
+
+        if output.len() == HEADER_BYTES {
+            if dest.exists() {
+                fs::remove_file(dest).map_err(|e| e.to_string())?;
+            }
+            return Ok(None);
+        }
+
+        let temp = dest.with_extension("mca.tmp");
+        fs::write(&temp, &output).map_err(|e| e.to_string())?;
+        fs::rename(&temp, dest).map_err(|e| e.to_string())?;
+        Ok(Some(output.len() as u64))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::services::instance::nbt::tests::{compound, write_tag};
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// 按给定的区块索引与根标签构造一个 zlib 压缩的区域文件。
+    pub(crate) fn build_region(chunks: &[(usize, NbtTag)]) -> Vec<u8> {
+        let mut bytes = vec![0u8; HEADER_BYTES];
+        for (index, root) in chunks {
+            let mut raw = vec![10];
+            write_tag(&mut raw, &NbtTag::String(String::new()));
+            write_tag(&mut raw, root);
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&raw).unwrap();
+            let compressed = encoder.finish().unwrap();
+
+            let mut chunk = ((compressed.len() + 1) as u32).to_be_bytes().to_vec();
+            chunk.push(2);
+            chunk.extend_from_slice(&compressed);
+            chunk.resize(chunk.len().div_ceil(SECTOR_BYTES) * SECTOR_BYTES, 0);
+
+            let location =
+                (((bytes.len() / SECTOR_BYTES) as u32) << 8) | (chunk.len() / SECTOR_BYTES) as u32;
+            bytes[index * 4..index * 4 + 4].copy_from_slice(&location.to_be_bytes());
+            bytes.extend_from_slice(&chunk);
+        }
+        bytes
+    }
+
+    pub(crate) fn chunk_root(inhabited_time: i64) -> NbtTag {
+        compound(vec![
+            ("InhabitedTime", NbtTag::Long(inhabited_time)),
+            ("Status", NbtTag::String("minecraft:full".to_string())),
+        ])
+    }
+
+    #[test]
+    fn parses_region_file_names() {
+        assert_eq!(parse_region_coords("r.-1.2.mca"), Some((-1, 2)));
+        assert_eq!(parse_region_coords("r.0.0.mcc"), None);
+        assert_eq!(parse_region_coords("r.0.mca"), None);
+    }
+
+    #[test]
+    fn rewrites_region_with_only_kept_chunks() {
+        let dir = std::env::temp_dir().join(format!("pilauncher-anvil-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("r.-1.0.mca");
+        fs::write(
+            &path,
+            build_region(&[
+                (0, chunk_root(5)),
+                (33, chunk_root(7)),
+                (1023, chunk_root(9)),
+            ]),
+        )
+        .unwrap();
+
+        let region = RegionFile::open(&path).unwrap();
+        assert_eq!(region.chunk_indices(), vec![0, 33, 1023]);
+        assert_eq!(region.chunk_coords(33), (-31, 1));
+        let new_size = region.write_retaining(&path, |index| index != 33).unwrap();
+
+        let rewritten = RegionFile::open(&path).unwrap();
+        assert_eq!(new_size, Some(rewritten.file_size()));
+        assert_eq!(rewritten.chunk_indices(), vec![0, 1023]);
+        let chunk = rewritten.read_chunk(1023).unwrap().unwrap();
+        assert_eq!(chunk.get("InhabitedTime").and_then(NbtTag::as_i64), Some(9));
+
+        assert_eq!(rewritten.write_retaining(&path, |_| false).unwrap(), None);
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
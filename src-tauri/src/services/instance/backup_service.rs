@@ -30,6 +30,8 @@ pub fn backup_instance_data(
         ("options.txt", "options.txt"),
         ("servers.dat", "servers.dat"),
         ("optionsof.txt", "optionsof.txt"),
+        ("mod_manifest.json", "mod_manifest.json"),
+        ("piconfig/modpack_base.json", "piconfig/modpack_base.json"),
     ];
 
     for (rel_path, zip_prefix) in targets {
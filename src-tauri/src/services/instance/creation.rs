@@ -126,6 +126,7 @@ impl InstanceCreationService {
             modpack_uuid: None,
             modpack_version: None,
             modpack_source: None,
+            modpack_version_id: None,
         };
         fs::write(
             tmp_instance_root.join("instance.json"),
@@ -1,4 +1,5 @@
 pub mod action;
+pub mod anvil;
 pub mod binding;
 pub mod creation;
 pub mod environment;
@@ -7,9 +8,15 @@ pub mod manifest_builder;
 pub mod mod_manager;
 pub mod mod_manifest_service;
 pub mod mod_snapshot_manager;
+pub mod nbt;
+pub mod partial_restore;
 pub mod resource_manager;
+pub mod save_diff;
 pub mod save_manager;
+pub mod save_retention;
 pub mod tag;
 pub mod verify_service;
+pub mod world_info;
+pub mod world_optimizer;
 pub mod backup_service;
 
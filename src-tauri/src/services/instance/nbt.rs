@@ -0,0 +1,362 @@
+// src-tauri/src/services/instance/nbt.rs
+//
+// 只读的 NBT 解析器，用于在不启动 Java 的情况下查看 level.dat / playerdata。
+// 支持 gzip、zlib 与未压缩三种封装，按大端序读取。
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+const MAX_NBT_DEPTH: usize = 512;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    Compound(HashMap<String, NbtTag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    pub fn get(&self, key: &str) -> Option<&NbtTag> {
+        match self {
+            NbtTag::Compound(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// 依次按键名向下查找，例如 `["Data", "Version", "Name"]`。
+    pub fn path(&self, keys: &[&str]) -> Option<&NbtTag> {
+        keys.iter().try_fold(self, |tag, key| tag.get(key))
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            NbtTag::Byte(value) => Some(*value as i64),
+            NbtTag::Short(value) => Some(*value as i64),
+            NbtTag::Int(value) => Some(*value as i64),
+            NbtTag::Long(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            NbtTag::Float(value) => Some(*value as f64),
+            NbtTag::Double(value) => Some(*value),
+            other => other.as_i64().map(|value| value as f64),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NbtTag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[NbtTag]> {
+        match self {
+            NbtTag::List(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// 按魔数自动识别压缩方式后解析整个 NBT 文件，返回根标签。
+pub fn read_nbt_file(path: &Path) -> Result<NbtTag, String> {
+    let raw = std::fs::read(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    parse_compressed(&raw)
+}
+
+pub fn parse_compressed(raw: &[u8]) -> Result<NbtTag, String> {
+    let mut bytes = Vec::new();
+    match raw {
+        [0x1f, 0x8b, ..] => {
+            GzDecoder::new(raw)
+                .read_to_end(&mut bytes)
+                .map_err(|e| format!("NBT gzip 解压失败: {}", e))?;
+        }
+        [0x78, ..] => {
+            ZlibDecoder::new(raw)
+                .read_to_end(&mut bytes)
+                .map_err(|e| format!("NBT zlib 解压失败: {}", e))?;
+        }
+        _ => bytes.extend_from_slice(raw),
+    }
+    parse(&bytes)
+}
+
+/// 解析未压缩的 NBT 数据，根标签必须是 Compound。
+pub fn parse(bytes: &[u8]) -> Result<NbtTag, String> {
+    let mut reader = NbtReader { bytes, pos: 0 };
+    let tag_type = reader.u8()?;
+    if tag_type != 10 {
+        return Err(format!("NBT 根标签类型无效: {}", tag_type));
+    }
+    reader.string()?;
+    reader.payload(tag_type, 0)
+}
+
+struct NbtReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NbtReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "NBT 数据意外结束".to_string())?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Err(format!("NBT 长度为负数: {}", len));
+        }
+        Ok(len as usize)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        // Java 的 Modified UTF-8 与标准 UTF-8 仅在少数字符上不同，这里宽松处理
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    fn payload(&mut self, tag_type: u8, depth: usize) -> Result<NbtTag, String> {
+        if depth > MAX_NBT_DEPTH {
+            return Err("NBT 嵌套层级过深".to_string());
+        }
+        Ok(match tag_type {
+            1 => NbtTag::Byte(self.u8()? as i8),
+            2 => NbtTag::Short(self.i16()?),
+            3 => NbtTag::Int(self.i32()?),
+            4 => NbtTag::Long(self.i64()?),
+            5 => NbtTag::Float(f32::from_be_bytes(self.array()?)),
+            6 => NbtTag::Double(f64::from_be_bytes(self.array()?)),
+            7 => {
+                let len = self.len()?;
+                NbtTag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => NbtTag::String(self.string()?),
+            9 => {
+                let item_type = self.u8()?;
+                let len = self.len()?;
+                if item_type == 0 && len > 0 {
+                    return Err("NBT 列表元素类型无效".to_string());
+                }
+                // 长度来自文件，先按剩余字节数约束预分配，避免恶意数据撑爆内存
+                let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+                for _ in 0..len {
+                    items.push(self.payload(item_type, depth + 1)?);
+                }
+                NbtTag::List(items)
+            }
+            10 => {
+                let mut map = HashMap::new();
+                loop {
+                    let child_type = self.u8()?;
+                    if child_type == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    map.insert(name, self.payload(child_type, depth + 1)?);
+                }
+                NbtTag::Compound(map)
+            }
+            11 => {
+                let len = self.len()?;
+                let raw = self.take(len.checked_mul(4).ok_or("NBT 数组过长")?)?;
+                NbtTag::IntArray(
+                    raw.chunks_exact(4)
+                        .map(|c| i32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                        .collect(),
+                )
+            }
+            12 => {
+                let len = self.len()?;
+                let raw = self.take(len.checked_mul(8).ok_or("NBT 数组过长")?)?;
+                NbtTag::LongArray(
+                    raw.chunks_exact(8)
+                        .map(|c| {
+                            i64::from_be_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]])
+                        })
+                        .collect(),
+                )
+            }
+            other => return Err(format!("未知的 NBT 标签类型: {}", other)),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// 测试用的最小 NBT 写入器。
+    pub(crate) fn write_tag(out: &mut Vec<u8>, tag: &NbtTag) {
+        match tag {
+            NbtTag::Byte(v) => out.push(*v as u8),
+            NbtTag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NbtTag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NbtTag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NbtTag::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NbtTag::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+            NbtTag::String(v) => {
+                out.extend_from_slice(&(v.len() as u16).to_be_bytes());
+                out.extend_from_slice(v.as_bytes());
+            }
+            NbtTag::List(items) => {
+                out.push(items.first().map(type_id).unwrap_or(0));
+                out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+                for item in items {
+                    write_tag(out, item);
+                }
+            }
+            NbtTag::Compound(map) => {
+                for (name, child) in map {
+                    out.push(type_id(child));
+                    write_tag(out, &NbtTag::String(name.clone()));
+                    write_tag(out, child);
+                }
+                out.push(0);
+            }
+            NbtTag::ByteArray(values) => {
+                out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                out.extend(values.iter().map(|v| *v as u8));
+            }
+            NbtTag::IntArray(values) => {
+                out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for v in values {
+                    out.extend_from_slice(&v.to_be_bytes());
+                }
+            }
+            NbtTag::LongArray(values) => {
+                out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+                for v in values {
+                    out.extend_from_slice(&v.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    fn type_id(tag: &NbtTag) -> u8 {
+        match tag {
+            NbtTag::Byte(_) => 1,
+            NbtTag::Short(_) => 2,
+            NbtTag::Int(_) => 3,
+            NbtTag::Long(_) => 4,
+            NbtTag::Float(_) => 5,
+            NbtTag::Double(_) => 6,
+            NbtTag::ByteArray(_) => 7,
+            NbtTag::String(_) => 8,
+            NbtTag::List(_) => 9,
+            NbtTag::Compound(_) => 10,
+            NbtTag::IntArray(_) => 11,
+            NbtTag::LongArray(_) => 12,
+        }
+    }
+
+    pub(crate) fn compound(entries: Vec<(&str, NbtTag)>) -> NbtTag {
+        NbtTag::Compound(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    /// 生成带 gzip 封装的 NBT 文件内容，与原版 level.dat 一致。
+    pub(crate) fn gzip_nbt(root: &NbtTag) -> Vec<u8> {
+        let mut raw = vec![10];
+        write_tag(&mut raw, &NbtTag::String(String::new()));
+        write_tag(&mut raw, root);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn round_trips_nested_compound_through_gzip() {
+        let root = compound(vec![(
+            "Data",
+            compound(vec![
+                ("LevelName", NbtTag::String("My World".to_string())),
+                ("DayTime", NbtTag::Long(6000)),
+                (
+                    "Pos",
+                    NbtTag::List(vec![NbtTag::Double(1.5), NbtTag::Double(64.0)]),
+                ),
+            ]),
+        )]);
+        let parsed = parse_compressed(&gzip_nbt(&root)).unwrap();
+        assert_eq!(parsed, root);
+        assert_eq!(
+            parsed.path(&["Data", "LevelName"]).and_then(NbtTag::as_str),
+            Some("My World")
+        );
+        assert_eq!(
+            parsed.path(&["Data", "DayTime"]).and_then(NbtTag::as_i64),
+            Some(6000)
+        );
+    }
+
+    #[test]
+    fn round_trips_array_tags() {
+        let root = compound(vec![
+            ("Bytes", NbtTag::ByteArray(vec![-1, 0, 127])),
+            ("Ints", NbtTag::IntArray(vec![i32::MIN, 0, 65536])),
+            ("Longs", NbtTag::LongArray(vec![-2, i64::MAX])),
+            ("Empty", NbtTag::IntArray(Vec::new())),
+        ]);
+        assert_eq!(parse_compressed(&gzip_nbt(&root)).unwrap(), root);
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let mut raw = vec![10];
+        write_tag(&mut raw, &NbtTag::String(String::new()));
+        write_tag(&mut raw, &compound(vec![("Seed", NbtTag::Long(42))]));
+        raw.truncate(raw.len() - 4);
+        assert!(parse(&raw).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+// src-tauri/src/services/instance/partial_restore.rs
+//
+// 选择性恢复的纯逻辑：把“维度 / 路径前缀”选择展开为存档内的相对路径前缀，
+// 以及沿增量链解析恢复时需要依次解压的备份。本地恢复与 WebDAV 下载共用同一套链解析。
+use crate::services::instance::save_manager::SaveBackupMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveRestoreSelection {
+    /// 存档内的相对路径前缀，例如 `playerdata/<uuid>.dat`、`datapacks/foo`
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// 维度 ID，例如 `minecraft:the_nether`、`mymod:sky`
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    /// 配置归档内的路径前缀，例如 `config/create-common.toml`
+    #[serde(default)]
+    pub config_paths: Vec<String>,
+}
+
+/// 统一为 `/` 分隔、去掉首尾斜杠的相对路径，拒绝绝对路径与 `..`。
+pub fn normalize_prefix(prefix: &str) -> Result<String, String> {
+    let normalized = prefix.replace('\\', "/").trim_matches('/').to_string();
+    if normalized.is_empty() {
+        return Err("restore path must not be empty".to_string());
+    }
+    let path = Path::new(&normalized);
+    if path.is_absolute()
+        || path.components().any(|component| {
+            matches!(
+                component,
+                Component::ParentDir | Component::Prefix(_) | Component::RootDir
+            )
+        })
+    {
+        return Err(format!("unsafe restore path: {}", prefix));
+    }
+    Ok(normalized)
+}
+
+/// 维度在存档目录中的位置：主世界的数据直接位于根目录，
+/// 下界 / 末地沿用 DIM-1 / DIM1，其余维度位于 `dimensions/<ns>/<path>`。
+pub fn dimension_prefixes(dimension: &str) -> Result<Vec<String>, String> {
+    let prefixes = match dimension {
+        "minecraft:overworld" => vec![
+            "region".to_string(),
+            "entities".to_string(),
+            "poi".to_string(),
+        ],
+        "minecraft:the_nether" => vec!["DIM-1".to_string()],
+        "minecraft:the_end" => vec!["DIM1".to_string()],
+        other => {
+            let (namespace, path) = other
+                .split_once(':')
+                .ok_or_else(|| format!("invalid dimension id: {}", other))?;
+            vec![normalize_prefix(&format!(
+                "dimensions/{}/{}",
+                namespace, path
+            ))?]
+        }
+    };
+    Ok(prefixes)
+}
+
+impl SaveRestoreSelection {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.dimensions.is_empty() && self.config_paths.is_empty()
+    }
+
+    pub fn world_prefixes(&self) -> Result<Vec<String>, String> {
+        let mut prefixes = Vec::new();
+        for dimension in &self.dimensions {
+            prefixes.extend(dimension_prefixes(dimension)?);
+        }
+        for path in &self.paths {
+            prefixes.push(normalize_prefix(path)?);
+        }
+        prefixes.sort();
+        prefixes.dedup();
+        Ok(prefixes)
+    }
+
+    pub fn config_prefixes(&self) -> Result<Vec<String>, String> {
+        let mut prefixes = self
+            .config_paths
+            .iter()
+            .map(|path| normalize_prefix(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        prefixes.sort();
+        prefixes.dedup();
+        Ok(prefixes)
+    }
+}
+
+pub fn path_matches(path: &str, prefixes: &[String]) -> bool {
+    prefixes.iter().any(|prefix| {
+        path == prefix
+            || path
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// 按“基础备份在前”的顺序返回恢复 `backup_id` 所需的全部备份，并检测缺失与循环依赖。
+pub fn resolve_backup_chain<'a, F>(backup_id: &str, lookup: F) -> Result<Vec<String>, String>
+where
+    F: Fn(&str) -> Option<&'a SaveBackupMetadata>,
+{
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = backup_id.to_string();
+    loop {
+        if !visited.insert(current.clone()) {
+            return Err(format!("save backup dependency cycle: {}", current));
+        }
+        let meta = lookup(&current).ok_or_else(|| format!("save backup not found: {}", current))?;
+        chain.push(current.clone());
+        if meta.backup_mode != "differential" {
+            break;
+        }
+        current = meta
+            .base_backup_id
+            .clone()
+            .ok_or_else(|| format!("differential save backup is missing base id: {}", current))?;
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn expands_dimensions_and_paths() {
+        let selection = SaveRestoreSelection {
+            paths: vec!["playerdata\\abc.dat".to_string(), "/datapacks/".to_string()],
+            dimensions: vec![
+                "minecraft:the_nether".to_string(),
+                "mymod:sky/high".to_string(),
+            ],
+            config_paths: Vec::new(),
+        };
+        assert_eq!(
+            selection.world_prefixes().unwrap(),
+            vec![
+                "DIM-1".to_string(),
+                "datapacks".to_string(),
+                "dimensions/mymod/sky/high".to_string(),
+                "playerdata/abc.dat".to_string(),
+            ]
+        );
+        assert!(normalize_prefix("../level.dat").is_err());
+        assert!(dimension_prefixes("nether").is_err());
+    }
+
+    #[test]
+    fn prefix_matching_respects_path_boundaries() {
+        let prefixes = vec!["DIM-1".to_string(), "playerdata/abc.dat".to_string()];
+        assert!(path_matches("DIM-1/region/r.0.0.mca", &prefixes));
+        assert!(path_matches("playerdata/abc.dat", &prefixes));
+        assert!(!path_matches("DIM-10/region/r.0.0.mca", &prefixes));
+        assert!(!path_matches("playerdata/abc.dat_old", &prefixes));
+    }
+
+    #[test]
+    fn resolves_chain_base_first_and_detects_gaps() {
+        let mut records = HashMap::new();
+        records.insert(
+            "full".to_string(),
+            SaveBackupMetadata {
+                backup_id: "full".to_string(),
+                backup_mode: "full".to_string(),
+                ..Default::default()
+            },
+        );
+        records.insert(
+            "diff".to_string(),
+            SaveBackupMetadata {
+                backup_id: "diff".to_string(),
+                backup_mode: "differential".to_string(),
+                base_backup_id: Some("full".to_string()),
+                ..Default::default()
+            },
+        );
+        records.insert(
+            "orphan".to_string(),
+            SaveBackupMetadata {
+                backup_id: "orphan".to_string(),
+                backup_mode: "differential".to_string(),
+                base_backup_id: Some("gone".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            resolve_backup_chain("diff", |id| records.get(id)).unwrap(),
+            vec!["full".to_string(), "diff".to_string()]
+        );
+        assert!(resolve_backup_chain("orphan", |id| records.get(id)).is_err());
+    }
+}
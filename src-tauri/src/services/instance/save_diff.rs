@@ -0,0 +1,263 @@
+// src-tauri/src/services/instance/save_diff.rs
+//
+// 比较两份存档清单（SaveBackupManifest），按内容类别汇总新增 / 删除 / 修改的文件。
+// 每个备份的清单都记录了当时完整的文件列表，因此任意两份清单可以直接对比，
+// 不需要沿增量链回放。
+use crate::services::instance::save_manager::{SaveBackupManifest, SaveBackupManifestEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+pub const DIFF_CATEGORY_REGION: &str = "region";
+pub const DIFF_CATEGORY_PLAYERDATA: &str = "playerdata";
+pub const DIFF_CATEGORY_DATAPACKS: &str = "datapacks";
+pub const DIFF_CATEGORY_CONFIGS: &str = "configs";
+pub const DIFF_CATEGORY_OTHER: &str = "other";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SaveDiffChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveDiffEntry {
+    pub path: String,
+    pub change: SaveDiffChange,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub size_delta: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveDiffGroup {
+    pub category: String,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub size_delta: i64,
+    pub entries: Vec<SaveDiffEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveBackupDiff {
+    pub from_backup_id: String,
+    /// 为 None 时表示与当前存档对比
+    pub to_backup_id: Option<String>,
+    pub folder_name: String,
+    pub groups: Vec<SaveDiffGroup>,
+    pub total_size_delta: i64,
+}
+
+/// 按路径判断文件属于哪一类内容。维度目录（DIM-1、dimensions/...）下的
+/// region / entities / poi 都归为区块数据。
+pub fn world_path_category(path: &str) -> &'static str {
+    let segments = path.split('/').collect::<Vec<_>>();
+    let dirs = &segments[..segments.len().saturating_sub(1)];
+    if dirs
+        .iter()
+        .any(|dir| matches!(*dir, "region" | "entities" | "poi"))
+    {
+        return DIFF_CATEGORY_REGION;
+    }
+    match dirs.first().copied() {
+        Some("playerdata" | "advancements" | "stats") => DIFF_CATEGORY_PLAYERDATA,
+        Some("datapacks") => DIFF_CATEGORY_DATAPACKS,
+        _ => DIFF_CATEGORY_OTHER,
+    }
+}
+
+fn size_delta(old: u64, new: u64) -> i64 {
+    new as i64 - old as i64
+}
+
+fn diff_entries<'a, F>(
+    old: &'a [SaveBackupManifestEntry],
+    new: &'a [SaveBackupManifestEntry],
+    category: F,
+    groups: &mut BTreeMap<&'static str, SaveDiffGroup>,
+) where
+    F: Fn(&str) -> &'static str,
+{
+    let old_by_path = old
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect::<HashMap<_, _>>();
+    let new_by_path = new
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect::<HashMap<_, _>>();
+
+    let mut push = |path: &str, entry: SaveDiffEntry| {
+        let key = category(path);
+        let group = groups.entry(key).or_insert_with(|| SaveDiffGroup {
+            category: key.to_string(),
+            ..Default::default()
+        });
+        match entry.change {
+            SaveDiffChange::Added => group.added += 1,
+            SaveDiffChange::Removed => group.removed += 1,
+            SaveDiffChange::Modified => group.modified += 1,
+        }
+        group.size_delta += entry.size_delta;
+        group.entries.push(entry);
+    };
+
+    for entry in new {
+        match old_by_path.get(entry.path.as_str()) {
+            None => push(
+                &entry.path,
+                SaveDiffEntry {
+                    path: entry.path.clone(),
+                    change: SaveDiffChange::Added,
+                    old_size: None,
+                    new_size: Some(entry.size),
+                    size_delta: size_delta(0, entry.size),
+                },
+            ),
+            Some(previous)
+                if previous.size != entry.size || previous.fingerprint != entry.fingerprint =>
+            {
+                push(
+                    &entry.path,
+                    SaveDiffEntry {
+                        path: entry.path.clone(),
+                        change: SaveDiffChange::Modified,
+                        old_size: Some(previous.size),
+                        new_size: Some(entry.size),
+                        size_delta: size_delta(previous.size, entry.size),
+                    },
+                )
+            }
+            Some(_) => {}
+        }
+    }
+    for entry in old {
+        if !new_by_path.contains_key(entry.path.as_str()) {
+            push(
+                &entry.path,
+                SaveDiffEntry {
+                    path: entry.path.clone(),
+                    change: SaveDiffChange::Removed,
+                    old_size: Some(entry.size),
+                    new_size: None,
+                    size_delta: size_delta(entry.size, 0),
+                },
+            );
+        }
+    }
+}
+
+/// 计算从 `old` 到 `new` 的变化，分组按类别名排序，组内按路径排序。
+pub fn diff_manifests(old: &SaveBackupManifest, new: &SaveBackupManifest) -> Vec<SaveDiffGroup> {
+    let mut groups = BTreeMap::new();
+    diff_entries(&old.entries, &new.entries, world_path_category, &mut groups);
+    diff_entries(
+        &old.configs.entries,
+        &new.configs.entries,
+        |_| DIFF_CATEGORY_CONFIGS,
+        &mut groups,
+    );
+
+    groups
+        .into_values()
+        .map(|mut group| {
+            group.entries.sort_by(|a, b| a.path.cmp(&b.path));
+            group
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::instance::save_manager::SaveBackupManifestSection;
+
+    fn entry(path: &str, size: u64, mtime: i64) -> SaveBackupManifestEntry {
+        SaveBackupManifestEntry {
+            path: path.to_string(),
+            size,
+            mtime,
+            fingerprint: format!("{:x}:{:x}", size, mtime),
+        }
+    }
+
+    #[test]
+    fn categorises_world_paths() {
+        assert_eq!(
+            world_path_category("region/r.0.0.mca"),
+            DIFF_CATEGORY_REGION
+        );
+        assert_eq!(
+            world_path_category("DIM-1/region/r.0.0.mca"),
+            DIFF_CATEGORY_REGION
+        );
+        assert_eq!(
+            world_path_category("entities/r.1.1.mca"),
+            DIFF_CATEGORY_REGION
+        );
+        assert_eq!(
+            world_path_category("playerdata/abc.dat"),
+            DIFF_CATEGORY_PLAYERDATA
+        );
+        assert_eq!(
+            world_path_category("stats/abc.json"),
+            DIFF_CATEGORY_PLAYERDATA
+        );
+        assert_eq!(
+            world_path_category("datapacks/pack.zip"),
+            DIFF_CATEGORY_DATAPACKS
+        );
+        assert_eq!(world_path_category("level.dat"), DIFF_CATEGORY_OTHER);
+        assert_eq!(world_path_category("region"), DIFF_CATEGORY_OTHER);
+    }
+
+    #[test]
+    fn reports_added_removed_and_modified_with_deltas() {
+        let old = SaveBackupManifest {
+            entries: vec![
+                entry("level.dat", 100, 1),
+                entry("region/r.0.0.mca", 4096, 1),
+                entry("region/r.0.1.mca", 8192, 1),
+            ],
+            configs: SaveBackupManifestSection {
+                entries: vec![entry("config/mod.toml", 10, 1)],
+                deleted: Vec::new(),
+            },
+            ..Default::default()
+        };
+        let new = SaveBackupManifest {
+            entries: vec![
+                entry("level.dat", 100, 1),
+                entry("region/r.0.0.mca", 12288, 2),
+                entry("playerdata/p.dat", 50, 2),
+            ],
+            configs: SaveBackupManifestSection {
+                entries: vec![entry("config/mod.toml", 12, 2)],
+                deleted: Vec::new(),
+            },
+            ..Default::default()
+        };
+
+        let groups = diff_manifests(&old, &new);
+        let categories = groups
+            .iter()
+            .map(|group| group.category.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(categories, vec!["configs", "playerdata", "region"]);
+
+        let region = &groups[2];
+        assert_eq!((region.added, region.removed, region.modified), (0, 1, 1));
+        assert_eq!(region.size_delta, 0);
+        assert_eq!(region.entries[0].path, "region/r.0.0.mca");
+        assert_eq!(region.entries[0].change, SaveDiffChange::Modified);
+        assert_eq!(region.entries[1].change, SaveDiffChange::Removed);
+
+        assert_eq!(groups[0].size_delta, 2);
+        assert_eq!(groups[1].added, 1);
+    }
+}
@@ -1,6 +1,11 @@
 use crate::domain::instance::InstanceConfig;
 use crate::services::config_service::ConfigService;
-use chrono::Local;
+use crate::services::instance::partial_restore::{self, SaveRestoreSelection};
+use crate::services::instance::save_diff::{self, SaveBackupDiff};
+use crate::services::instance::save_retention::{self, SaveBackupRetention};
+use crate::services::instance::world_info::{self, WorldInfo};
+use crate::services::instance::world_optimizer::{self, WorldOptimizeOptions, WorldOptimizeReport};
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use std::collections::{HashMap, HashSet};
@@ -25,6 +30,11 @@ const BACKUP_MANIFEST_FILE: &str = "manifest.json";
 const SAVE_BACKUP_PROGRESS_EVENT: &str = "save-backup-progress";
 const DEFAULT_EXIT_BACKUP_COOLDOWN_SECONDS: u64 = 5;
 const DEFAULT_STABLE_WINDOW_SECONDS: u64 = 2;
+const PERIODIC_BACKUP_TRIGGER: &str = "auto_interval";
+const DEFAULT_PERIODIC_BACKUP_INTERVAL_MINUTES: u64 = 30;
+const PERIODIC_BACKUP_RETRY_SECONDS: u64 = 60;
+const PERIODIC_BACKUP_MAX_ATTEMPTS: u32 = 5;
+const PERIODIC_BACKUP_POLL_SECONDS: u64 = 5;
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -38,6 +48,8 @@ pub struct SaveItem {
     pub icon_path: Option<String>,
     #[serde(default)]
     pub webdav_backup_enabled: bool,
+    #[serde(default)]
+    pub world_info: Option<WorldInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -56,6 +68,11 @@ pub struct SaveMetadataCache {
     pub size_bytes: u64,
     pub last_played_time: i64,
     pub created_time: i64,
+    #[serde(default)]
+    pub world_info: Option<WorldInfo>,
+    /// level.dat 解析失败时记录其修改时间，文件未变化前不再重复解析
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_info_failed_at: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -113,6 +130,13 @@ pub struct SaveBackupState {
     pub safe_backup: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePeriodicBackupSettings {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+}
+
 #[derive(Clone)]
 struct SaveBackupPolicy {
     enabled: bool,
@@ -122,6 +146,19 @@ struct SaveBackupPolicy {
     wait_after_exit_seconds: u64,
     require_stable_files: bool,
     stable_window_seconds: u64,
+    periodic_enabled: bool,
+    periodic_interval_minutes: u64,
+    retention: SaveBackupRetention,
+    world_retention: HashMap<String, SaveBackupRetention>,
+}
+
+impl SaveBackupPolicy {
+    fn retention_for(&self, world: &SaveBackupWorld) -> &SaveBackupRetention {
+        self.world_retention
+            .get(&world.uuid)
+            .or_else(|| self.world_retention.get(&world.folder_name))
+            .unwrap_or(&self.retention)
+    }
 }
 
 impl Default for SaveBackupPolicy {
@@ -134,6 +171,10 @@ impl Default for SaveBackupPolicy {
             wait_after_exit_seconds: DEFAULT_EXIT_BACKUP_COOLDOWN_SECONDS,
             require_stable_files: true,
             stable_window_seconds: DEFAULT_STABLE_WINDOW_SECONDS,
+            periodic_enabled: false,
+            periodic_interval_minutes: DEFAULT_PERIODIC_BACKUP_INTERVAL_MINUTES,
+            retention: SaveBackupRetention::default(),
+            world_retention: HashMap::new(),
         }
     }
 }
@@ -200,6 +241,8 @@ pub struct SaveBackupMetadata {
     pub user: SaveBackupUser,
     #[serde(default)]
     pub has_configs: bool,
+    #[serde(default)]
+    pub world_info: Option<WorldInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -230,6 +273,34 @@ pub struct SaveRestoreResult {
     pub warnings: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePartialRestoreCheckResult {
+    #[serde(flatten)]
+    pub check: SaveRestoreCheckResult,
+    /// 恢复时依次解压的备份，基础备份在前
+    pub chain: Vec<String>,
+    pub world_prefixes: Vec<String>,
+    pub config_prefixes: Vec<String>,
+    pub matched_world_files: usize,
+    pub matched_config_files: usize,
+    /// 在备份中没有匹配到任何文件的前缀；恢复时这些位置的现有文件会被清空
+    pub unmatched_prefixes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePartialRestoreResult {
+    pub backup_id: String,
+    pub restored_folder_name: String,
+    pub restored_files: usize,
+    pub removed_files: usize,
+    pub restored_config_files: usize,
+    pub guard_backup_id: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveBackupProgress {
@@ -255,6 +326,17 @@ struct BackupRecord {
 
 pub struct SaveManagerService;
 
+/// 恢复过程中的临时目录与回滚目录，离开作用域时（包括提前返回）一并删除。
+struct WorkDirsGuard(Vec<PathBuf>);
+
+impl Drop for WorkDirsGuard {
+    fn drop(&mut self) {
+        for dir in &self.0 {
+            let _ = SaveManagerService::remove_dir_if_exists(dir);
+        }
+    }
+}
+
 impl SaveManagerService {
     fn get_base_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
         let base = ConfigService::get_base_path(app)
@@ -505,17 +587,33 @@ impl SaveManagerService {
     }
 
     fn extract_archive_to(archive_path: &Path, dst: &Path) -> Result<(), String> {
+        Self::extract_archive_filtered_to(archive_path, dst, |_| true).map(|_| ())
+    }
+
+    /// 只解压 `filter` 接受的条目（按 `/` 分隔的相对路径判断），返回解压出的文件数。
+    fn extract_archive_filtered_to<F>(
+        archive_path: &Path,
+        dst: &Path,
+        filter: F,
+    ) -> Result<usize, String>
+    where
+        F: Fn(&str) -> bool,
+    {
         let file = File::open(archive_path).map_err(|e| e.to_string())?;
         let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
 
         fs::create_dir_all(dst).map_err(|e| e.to_string())?;
 
+        let mut extracted = 0;
         for index in 0..archive.len() {
             let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
             let enclosed_name = entry
                 .enclosed_name()
                 .ok_or_else(|| "archive entry contains an invalid path".to_string())?
                 .to_path_buf();
+            if !filter(&Self::relative_path_string(&enclosed_name)) {
+                continue;
+            }
             let out_path = dst.join(enclosed_name);
 
             if entry.is_dir() {
@@ -529,9 +627,17 @@ impl SaveManagerService {
 
             let mut out_file = File::create(&out_path).map_err(|e| e.to_string())?;
             io::copy(&mut entry, &mut out_file).map_err(|e| e.to_string())?;
+            extracted += 1;
         }
 
-        Ok(())
+        Ok(extracted)
+    }
+
+    fn relative_path_string(path: &Path) -> String {
+        path.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
     }
 
     fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result<()> {
@@ -589,6 +695,20 @@ impl SaveManagerService {
         Ok(())
     }
 
+    fn move_file_with_fallback(src: &Path, dst: &Path) -> Result<(), String> {
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        if fs::rename(src, dst).is_ok() {
+            return Ok(());
+        }
+
+        fs::copy(src, dst).map_err(|e| e.to_string())?;
+        fs::remove_file(src).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     fn write_json_atomically<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|e| e.to_string())?;
@@ -675,6 +795,31 @@ impl SaveManagerService {
             }
         }
 
+        if let Some(periodic) = save_backup.get("periodic") {
+            if let Some(enabled) = periodic.get("enabled").and_then(|value| value.as_bool()) {
+                policy.periodic_enabled = enabled;
+            }
+            if let Some(minutes) = periodic
+                .get("intervalMinutes")
+                .and_then(|value| value.as_u64())
+            {
+                policy.periodic_interval_minutes = minutes;
+            }
+        }
+
+        if let Some(retention) = save_backup
+            .get("retention")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+        {
+            policy.retention = retention;
+        }
+        if let Some(world_retention) = save_backup
+            .get("worldRetention")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+        {
+            policy.world_retention = world_retention;
+        }
+
         policy
     }
 
@@ -982,6 +1127,13 @@ impl SaveManagerService {
         true
     }
 
+    /// 游戏运行中的定时备份只能在存档处于一致状态时进行：
+    /// session.lock 没有正在被改写，且整个存档目录在稳定窗口内没有写入。
+    fn is_live_save_consistent(save_dir: &Path, policy: &SaveBackupPolicy) -> bool {
+        !Self::is_session_locked(save_dir)
+            && Self::is_save_tree_stable(save_dir, policy.stable_window_seconds.max(1))
+    }
+
     fn quick_file_fingerprint(path: &Path) -> String {
         match fs::metadata(path) {
             Ok(meta) => {
@@ -1088,6 +1240,16 @@ impl SaveManagerService {
         Uuid::from_bytes(bytes).to_string()
     }
 
+    /// 只读取存档目录中记录的世界 UUID，不存在时不创建
+    fn read_world_uuid(save_dir: &Path) -> Option<String> {
+        let content = fs::read_to_string(save_dir.join("pilauncher_world_idx.json")).ok()?;
+        let idx = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+        idx["uuid"]
+            .as_str()
+            .map(str::to_string)
+            .filter(|uuid| !uuid.trim().is_empty())
+    }
+
     fn get_or_create_world_uuid(save_dir: &Path, cached_uuid: &str) -> String {
         let idx_path = save_dir.join("pilauncher_world_idx.json");
 
@@ -1096,14 +1258,8 @@ impl SaveManagerService {
             uuid: String,
         }
 
-        if idx_path.exists() {
-            if let Ok(content) = fs::read_to_string(&idx_path) {
-                if let Ok(idx) = serde_json::from_str::<WorldIdx>(&content) {
-                    if !idx.uuid.trim().is_empty() {
-                        return idx.uuid;
-                    }
-                }
-            }
+        if let Some(uuid) = Self::read_world_uuid(save_dir) {
+            return uuid;
         }
 
         // If not in pilauncher_world_idx.json, check if we have a cached UUID
@@ -1132,6 +1288,20 @@ impl SaveManagerService {
         format!("{:x}", hasher.finalize())
     }
 
+    fn inspect_world_info(save_dir: &Path) -> Option<WorldInfo> {
+        match world_info::inspect_world(save_dir) {
+            Ok(info) => Some(info),
+            Err(error) => {
+                eprintln!(
+                    "[SaveManager] failed to inspect world {}: {}",
+                    save_dir.display(),
+                    error
+                );
+                None
+            }
+        }
+    }
+
     fn inspect_save_folder(
         _instance_id: &str,
         folder_name: &str,
@@ -1163,9 +1333,15 @@ impl SaveManagerService {
         let world_uuid = Self::get_or_create_world_uuid(save_dir, &cache.world_uuid);
         let needs_refresh = cache.world_uuid.is_empty()
             || cache.world_name.is_empty()
-            || meta_modified < level_modified;
+            || meta_modified < level_modified
+            || (cache.world_info.is_none()
+                && level_dat.exists()
+                && cache.world_info_failed_at != Some(level_modified));
 
         if needs_refresh {
+            let world_info = Self::inspect_world_info(save_dir);
+            let world_info_failed_at =
+                (world_info.is_none() && level_dat.exists()).then_some(level_modified);
             cache = SaveMetadataCache {
                 world_uuid,
                 world_name: folder_name.to_string(),
@@ -1177,6 +1353,8 @@ impl SaveManagerService {
                     .or_else(|| folder_meta.modified().ok())
                     .map(Self::system_time_to_timestamp)
                     .unwrap_or_default(),
+                world_info,
+                world_info_failed_at,
             };
         } else {
             cache.world_uuid = world_uuid;
@@ -1281,7 +1459,14 @@ impl SaveManagerService {
 
         let instance_config = Self::get_instance_config(&instance_dir)?;
         let save_cache = Self::inspect_save_folder(instance_id, folder_name, &src_save_dir)?;
-        let safe_backup = Self::assess_backup_safety(&src_save_dir, policy);
+        let safe_backup = if trigger == PERIODIC_BACKUP_TRIGGER {
+            if !Self::is_live_save_consistent(&src_save_dir, policy) {
+                return Err("save is being written, periodic backup postponed".to_string());
+            }
+            true
+        } else {
+            Self::assess_backup_safety(&src_save_dir, policy)
+        };
 
         let mut base_backup_id = None;
         let mut base_time = None;
@@ -1452,6 +1637,7 @@ impl SaveManagerService {
                 state: SaveBackupState { safe_backup },
                 user: SaveBackupUser::default(),
                 has_configs: config_size > 0,
+                world_info: save_cache.world_info,
             };
 
             Self::write_json_atomically(&temp_backup_dir.join(BACKUP_META_FILE), &meta)?;
@@ -1571,6 +1757,25 @@ impl SaveManagerService {
             .ok_or_else(|| "backup snapshot not found".to_string())
     }
 
+    /// 解析恢复 `backup_id` 所需的备份链，基础备份在前。
+    fn collect_backup_chain<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        backup_id: &str,
+    ) -> Result<Vec<BackupRecord>, String> {
+        let mut records_by_id = Self::load_backup_records(app, instance_id)?
+            .into_iter()
+            .map(|record| (record.meta.backup_id.clone(), record))
+            .collect::<HashMap<_, _>>();
+        let ordered = partial_restore::resolve_backup_chain(backup_id, |id| {
+            records_by_id.get(id).map(|record| &record.meta)
+        })?;
+        Ok(ordered
+            .iter()
+            .filter_map(|id| records_by_id.remove(id))
+            .collect())
+    }
+
     fn diff_mods(
         current_mods: &[SaveBackupModEntry],
         backup_mods: &[SaveBackupModEntry],
@@ -1752,6 +1957,7 @@ impl SaveManagerService {
                         .exists()
                         .then(|| icon_path.to_string_lossy().to_string()),
                     webdav_backup_enabled: false,
+                    world_info: cache.world_info,
                 };
                 save.webdav_backup_enabled =
                     Self::save_webdav_enabled(&webdav_selection, instance_id, &save);
@@ -1789,6 +1995,7 @@ impl SaveManagerService {
                 .exists()
                 .then(|| icon_path.to_string_lossy().to_string()),
             webdav_backup_enabled: enabled,
+            world_info: cache.world_info,
         };
 
         let identity = if save.world_uuid.trim().is_empty() {
@@ -1832,7 +2039,9 @@ impl SaveManagerService {
         if !policy.enabled {
             return Err("save backup is disabled for this instance".to_string());
         }
-        Self::create_backup(app, instance_id, folder_name, trigger, mode, &policy)
+        let meta = Self::create_backup(app, instance_id, folder_name, trigger, mode, &policy)?;
+        Self::prune_world_backups_logged(app, instance_id, &meta.world, &policy);
+        Ok(meta)
     }
 
     pub fn backup_recent_save_on_game_exit<R: Runtime>(
@@ -1867,7 +2076,10 @@ impl SaveManagerService {
                 "differential",
                 &policy,
             ) {
-                Ok(meta) => backups.push(meta),
+                Ok(meta) => {
+                    Self::prune_world_backups_logged(app, instance_id, &meta.world, &policy);
+                    backups.push(meta);
+                }
                 Err(error) => {
                     eprintln!(
                         "[SaveBackup] auto_exit backup failed for {} / {}: {}",
@@ -1880,6 +2092,119 @@ impl SaveManagerService {
         Ok(backups)
     }
 
+    /// 定时备份的间隔（秒），至少一分钟；策略未开启定时备份时返回 None。
+    fn periodic_interval_seconds(policy: &SaveBackupPolicy) -> Option<u64> {
+        (policy.enabled && policy.periodic_enabled)
+            .then(|| policy.periodic_interval_minutes.max(1) * 60)
+    }
+
+    /// 在游戏进程存活期间按策略间隔对正在游玩的世界做增量备份。
+    /// `pid` 用于判断本次会话是否结束；进程退出后循环自然终止，交给退出备份收尾。
+    pub fn start_periodic_backups<R: Runtime>(app: AppHandle<R>, instance_id: String, pid: u32) {
+        let Ok(instance_dir) = Self::get_instance_dir(&app, &instance_id) else {
+            return;
+        };
+        if Self::periodic_interval_seconds(&Self::read_backup_policy(&instance_dir)).is_none() {
+            return;
+        }
+
+        thread::spawn(move || {
+            let session_alive = || {
+                crate::commands::launcher_cmd::CURRENT_GAME_PID
+                    .load(std::sync::atomic::Ordering::SeqCst)
+                    == pid
+            };
+            let wait = |seconds: u64| -> bool {
+                let mut waited = 0;
+                while waited < seconds {
+                    if !session_alive() {
+                        return false;
+                    }
+                    let step = PERIODIC_BACKUP_POLL_SECONDS.min(seconds - waited);
+                    thread::sleep(Duration::from_secs(step));
+                    waited += step;
+                }
+                session_alive()
+            };
+
+            let mut last_backup_at = Local::now().timestamp();
+            loop {
+                let policy = Self::read_backup_policy(&instance_dir);
+                let Some(interval_seconds) = Self::periodic_interval_seconds(&policy) else {
+                    return;
+                };
+                if !wait(interval_seconds) {
+                    return;
+                }
+
+                let tick_started_at = Local::now().timestamp();
+                let mut saves = match Self::get_saves(&app, &instance_id) {
+                    Ok(saves) => saves,
+                    Err(error) => {
+                        eprintln!(
+                            "[SaveBackup] auto_interval failed to list saves for {}: {}",
+                            instance_id, error
+                        );
+                        continue;
+                    }
+                };
+                // 只备份本周期内被游戏写入过的世界
+                saves.retain(|save| save.last_played_time >= last_backup_at);
+                if !policy.backup_all_worlds_on_exit {
+                    saves.truncate(1);
+                }
+
+                for save in saves {
+                    for attempt in 1..=PERIODIC_BACKUP_MAX_ATTEMPTS {
+                        match Self::create_backup(
+                            &app,
+                            &instance_id,
+                            &save.folder_name,
+                            PERIODIC_BACKUP_TRIGGER,
+                            "differential",
+                            &policy,
+                        ) {
+                            Ok(meta) => {
+                                Self::prune_world_backups_logged(
+                                    &app,
+                                    &instance_id,
+                                    &meta.world,
+                                    &policy,
+                                );
+                                let message = format!(
+                                    "[SaveBackup] auto_interval backup completed for {}",
+                                    save.folder_name
+                                );
+                                println!("{}", message);
+                                let _ = app.emit("game-log", message);
+                                break;
+                            }
+                            Err(error) => {
+                                eprintln!(
+                                    "[SaveBackup] auto_interval attempt {}/{} for {} / {}: {}",
+                                    attempt,
+                                    PERIODIC_BACKUP_MAX_ATTEMPTS,
+                                    instance_id,
+                                    save.folder_name,
+                                    error
+                                );
+                                if attempt == PERIODIC_BACKUP_MAX_ATTEMPTS
+                                    || !wait(PERIODIC_BACKUP_RETRY_SECONDS)
+                                {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    if !session_alive() {
+                        return;
+                    }
+                }
+                last_backup_at = tick_started_at;
+            }
+        });
+    }
+
     pub fn delete_save<R: Runtime>(
         app: &AppHandle<R>,
         instance_id: &str,
@@ -1943,6 +2268,213 @@ impl SaveManagerService {
         Ok(())
     }
 
+    fn prune_world_backups<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        world: &SaveBackupWorld,
+        policy: &SaveBackupPolicy,
+    ) -> Result<Vec<String>, String> {
+        if policy.retention_for(world).is_unbounded() {
+            return Ok(Vec::new());
+        }
+
+        let backups = Self::load_backup_records(app, instance_id)?
+            .into_iter()
+            .map(|record| record.meta)
+            .collect::<Vec<_>>();
+        let doomed = Self::plan_world_backup_pruning(&backups, world, policy, Local::now());
+
+        // 计划按从新到旧排列，增量备份总会先于其基础备份被删除
+        let mut pruned = Vec::new();
+        for backup_id in doomed {
+            Self::delete_backup(app, instance_id, &backup_id)?;
+            pruned.push(backup_id);
+        }
+        Ok(pruned)
+    }
+
+    /// 从实例的全部备份中挑出该世界的备份，按其生效的保留规则计算要删除的备份。
+    fn plan_world_backup_pruning(
+        backups: &[SaveBackupMetadata],
+        world: &SaveBackupWorld,
+        policy: &SaveBackupPolicy,
+        now: DateTime<Local>,
+    ) -> Vec<String> {
+        let world_backups = backups
+            .iter()
+            .filter(|meta| meta.world.uuid == world.uuid)
+            .cloned()
+            .collect::<Vec<_>>();
+        save_retention::plan_world_pruning(&world_backups, policy.retention_for(world), now)
+    }
+
+    fn prune_world_backups_logged<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        world: &SaveBackupWorld,
+        policy: &SaveBackupPolicy,
+    ) {
+        if let Err(error) = Self::prune_world_backups(app, instance_id, world, policy) {
+            eprintln!(
+                "[SaveBackup] retention pruning failed for {} / {}: {}",
+                instance_id, world.folder_name, error
+            );
+        }
+    }
+
+    /// 按实例当前的保留策略清理该实例下所有世界的备份，返回被删除的备份 ID。
+    pub fn prune_backups<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+    ) -> Result<Vec<String>, String> {
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let policy = Self::read_backup_policy(&instance_dir);
+
+        let mut worlds = HashMap::new();
+        for record in Self::load_backup_records(app, instance_id)? {
+            worlds
+                .entry(record.meta.world.uuid.clone())
+                .or_insert(record.meta.world);
+        }
+
+        let mut pruned = Vec::new();
+        for world in worlds.values() {
+            pruned.extend(Self::prune_world_backups(app, instance_id, world, &policy)?);
+        }
+        Ok(pruned)
+    }
+
+    /// 供远端备份复用：解析某个世界在该实例策略下生效的保留规则。
+    pub fn backup_retention_for_world<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        world: &SaveBackupWorld,
+    ) -> Result<SaveBackupRetention, String> {
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        Ok(Self::read_backup_policy(&instance_dir)
+            .retention_for(world)
+            .clone())
+    }
+
+    /// 瘦身存档：先做一次完整备份，再在存档副本上删除未使用的区块，
+    /// 成功后用副本原子替换原存档。`dry_run` 时直接只读统计，不备份也不替换。
+    pub fn optimize_world<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        folder_name: &str,
+        options: WorldOptimizeOptions,
+    ) -> Result<WorldOptimizeReport, String> {
+        let saves_dir = Self::get_game_dir(app, instance_id)?.join("saves");
+        let save_dir = saves_dir.join(folder_name);
+        if !save_dir.is_dir() {
+            return Err("save folder does not exist".to_string());
+        }
+
+        if options.dry_run {
+            let mut report = world_optimizer::optimize_world_dir(&save_dir, &options)?;
+            report.folder_name = folder_name.to_string();
+            return Ok(report);
+        }
+
+        if Self::is_game_process_running() || Self::is_session_locked(&save_dir) {
+            return Err("cannot optimize a world while the game is running".to_string());
+        }
+
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let backup = Self::create_backup(
+            app,
+            instance_id,
+            folder_name,
+            "pre_optimize",
+            "full",
+            &Self::read_backup_policy(&instance_dir),
+        )?;
+
+        let work_dir = saves_dir.join(format!(".optimize-{}", Uuid::new_v4()));
+        let rollback_dir = saves_dir.join(format!(".rollback-{}", Uuid::new_v4()));
+        let result = (|| -> Result<WorldOptimizeReport, String> {
+            Self::copy_dir_all(&save_dir, &work_dir).map_err(|e| e.to_string())?;
+            let report = world_optimizer::optimize_world_dir(&work_dir, &options)?;
+
+            Self::move_dir_with_fallback(&save_dir, &rollback_dir)?;
+            if let Err(error) = Self::move_dir_with_fallback(&work_dir, &save_dir) {
+                let _ = Self::move_dir_with_fallback(&rollback_dir, &save_dir);
+                return Err(error);
+            }
+            Ok(report)
+        })();
+        let _ = Self::remove_dir_if_exists(&work_dir);
+
+        let mut report = result?;
+        Self::remove_dir_if_exists(&rollback_dir)?;
+
+        // 区块数量与大小已变化，清掉缓存的世界信息，下次列出存档时重新统计
+        Self::clear_cached_world_info(&save_dir)?;
+
+        report.folder_name = folder_name.to_string();
+        report.backup_id = Some(backup.backup_id);
+        Ok(report)
+    }
+
+    fn clear_cached_world_info(save_dir: &Path) -> Result<(), String> {
+        let meta_path = save_dir.join(SAVE_METADATA_FILE);
+        if let Some(mut cache) = fs::read_to_string(&meta_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<SaveMetadataCache>(&content).ok())
+        {
+            cache.world_info = None;
+            Self::write_json_atomically(&meta_path, &cache)?;
+        }
+        Ok(())
+    }
+
+    /// 对比两个备份，或在 `to_backup_id` 为空时对比备份与当前存档。
+    /// 两个备份必须属于同一个世界。
+    pub fn diff_backups<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        from_backup_id: &str,
+        to_backup_id: Option<&str>,
+    ) -> Result<SaveBackupDiff, String> {
+        let from = Self::find_backup_record(app, instance_id, from_backup_id)?;
+        let from_manifest = Self::load_manifest(&from.backup_dir)
+            .ok_or_else(|| "backup snapshot has no file manifest".to_string())?;
+
+        let to_manifest = match to_backup_id {
+            Some(to_backup_id) => {
+                let to = Self::find_backup_record(app, instance_id, to_backup_id)?;
+                if to.meta.world.uuid != from.meta.world.uuid {
+                    return Err("cannot compare backups of different worlds".to_string());
+                }
+                Self::load_manifest(&to.backup_dir)
+                    .ok_or_else(|| "backup snapshot has no file manifest".to_string())?
+            }
+            None => {
+                let game_dir = Self::get_game_dir(app, instance_id)?;
+                let save_dir = game_dir.join("saves").join(&from.meta.world.folder_name);
+                if !save_dir.exists() {
+                    return Err("save folder does not exist".to_string());
+                }
+                // 只有备份本身包含配置时才扫描当前配置，避免把“未备份”误报成“已删除”
+                let config_sources = if from.meta.has_configs {
+                    Self::get_config_backup_sources(&game_dir)
+                } else {
+                    Vec::new()
+                };
+                Self::build_save_manifest(&save_dir, &config_sources, None)
+            }
+        };
+
+        let groups = save_diff::diff_manifests(&from_manifest, &to_manifest);
+        Ok(SaveBackupDiff {
+            from_backup_id: from_backup_id.to_string(),
+            to_backup_id: to_backup_id.map(str::to_string),
+            folder_name: from.meta.world.folder_name,
+            total_size_delta: groups.iter().map(|group| group.size_delta).sum(),
+            groups,
+        })
+    }
+
     pub fn verify_restore<R: Runtime>(
         app: &AppHandle<R>,
         instance_id: &str,
@@ -2206,6 +2738,399 @@ impl SaveManagerService {
         })
     }
 
+    fn archive_file_paths(archive_path: &Path) -> Result<Vec<String>, String> {
+        let file = File::open(archive_path).map_err(|e| e.to_string())?;
+        let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+        let mut paths = Vec::new();
+        for index in 0..archive.len() {
+            let entry = archive.by_index(index).map_err(|e| e.to_string())?;
+            if entry.is_dir() {
+                continue;
+            }
+            if let Some(name) = entry.enclosed_name() {
+                paths.push(Self::relative_path_string(&name));
+            }
+        }
+        Ok(paths)
+    }
+
+    /// 目标备份中位于所选前缀下的文件（存档部分, 配置部分）。
+    /// 清单记录的是备份时完整的文件列表；没有清单的旧版全量备份直接读取归档目录。
+    fn selected_backup_paths(
+        record: &BackupRecord,
+        world_prefixes: &[String],
+        config_prefixes: &[String],
+    ) -> Result<(HashSet<String>, HashSet<String>), String> {
+        let (world_paths, config_paths) = match Self::load_manifest(&record.backup_dir) {
+            Some(manifest) => (
+                manifest
+                    .entries
+                    .into_iter()
+                    .map(|entry| entry.path)
+                    .collect::<Vec<_>>(),
+                manifest
+                    .configs
+                    .entries
+                    .into_iter()
+                    .map(|entry| entry.path)
+                    .collect::<Vec<_>>(),
+            ),
+            None if record.meta.backup_mode == "differential" => {
+                return Err(
+                    "differential backup manifest is missing; restore is blocked".to_string(),
+                );
+            }
+            None => {
+                let world_paths = match &record.world_payload {
+                    BackupPayload::Archive(path) => Self::archive_file_paths(path)?,
+                    BackupPayload::Missing => Vec::new(),
+                };
+                let config_paths = match &record.configs_payload {
+                    BackupPayload::Archive(path) if !config_prefixes.is_empty() => {
+                        Self::archive_file_paths(path)?
+                    }
+                    _ => Vec::new(),
+                };
+                (world_paths, config_paths)
+            }
+        };
+
+        Ok((
+            world_paths
+                .into_iter()
+                .filter(|path| partial_restore::path_matches(path, world_prefixes))
+                .collect(),
+            config_paths
+                .into_iter()
+                .filter(|path| partial_restore::path_matches(path, config_prefixes))
+                .collect(),
+        ))
+    }
+
+    /// 沿备份链依次解压匹配的条目，再删掉目标备份清单中已不存在的文件，
+    /// 得到目标备份时刻这些前缀下的完整内容。
+    fn extract_selected_chain(
+        chain: &[BackupRecord],
+        configs: bool,
+        prefixes: &[String],
+        wanted: &HashSet<String>,
+        dst: &Path,
+    ) -> Result<(), String> {
+        fs::create_dir_all(dst).map_err(|e| e.to_string())?;
+        for record in chain {
+            let payload = if configs {
+                if !record.meta.has_configs {
+                    continue;
+                }
+                &record.configs_payload
+            } else {
+                &record.world_payload
+            };
+            match payload {
+                BackupPayload::Archive(path) => {
+                    Self::extract_archive_filtered_to(path, dst, |entry| {
+                        partial_restore::path_matches(entry, prefixes)
+                    })?;
+                }
+                BackupPayload::Missing => {
+                    return Err(format!(
+                        "backup payload is missing: {}",
+                        record.meta.backup_id
+                    ));
+                }
+            }
+        }
+
+        for entry in WalkDir::new(dst).into_iter().filter_map(|entry| entry.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(rel) = entry.path().strip_prefix(dst) else {
+                continue;
+            };
+            if !wanted.contains(&Self::relative_path_string(rel)) {
+                fs::remove_file(entry.path()).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 把 `live_root` 中所选前缀下的现有文件移入 `rollback_root`，再放入 `restored_root` 的内容；
+    /// 任一步失败都会逐个文件回滚。返回（恢复的文件数, 被移除且未被替换的文件数）。
+    fn swap_selected_files(
+        live_root: &Path,
+        restored_root: &Path,
+        rollback_root: &Path,
+        prefixes: &[String],
+    ) -> Result<(usize, usize), String> {
+        let mut moved_out: Vec<PathBuf> = Vec::new();
+        let mut moved_in: Vec<PathBuf> = Vec::new();
+
+        let result = (|| -> Result<(), String> {
+            for prefix in prefixes {
+                let live_path = live_root.join(prefix);
+                let files = if live_path.is_file() {
+                    vec![PathBuf::from(prefix)]
+                } else if live_path.is_dir() {
+                    WalkDir::new(&live_path)
+                        .into_iter()
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.file_type().is_file())
+                        .filter_map(|entry| {
+                            entry
+                                .path()
+                                .strip_prefix(live_root)
+                                .ok()
+                                .map(Path::to_path_buf)
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                for rel in files {
+                    Self::move_file_with_fallback(
+                        &live_root.join(&rel),
+                        &rollback_root.join(&rel),
+                    )?;
+                    moved_out.push(rel);
+                }
+            }
+
+            for entry in WalkDir::new(restored_root)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+            {
+                let rel = entry
+                    .path()
+                    .strip_prefix(restored_root)
+                    .map_err(|e| e.to_string())?
+                    .to_path_buf();
+                Self::move_file_with_fallback(entry.path(), &live_root.join(&rel))?;
+                moved_in.push(rel);
+            }
+            Ok(())
+        })();
+
+        if let Err(error) = result {
+            for rel in &moved_in {
+                let _ = fs::remove_file(live_root.join(rel));
+            }
+            for rel in &moved_out {
+                let _ =
+                    Self::move_file_with_fallback(&rollback_root.join(rel), &live_root.join(rel));
+            }
+            return Err(format!("failed to swap restored files: {}", error));
+        }
+
+        let replaced = moved_in.iter().collect::<HashSet<_>>();
+        let removed = moved_out
+            .iter()
+            .filter(|rel| !replaced.contains(rel))
+            .count();
+        Ok((moved_in.len(), removed))
+    }
+
+    /// 选择性恢复的预检查：校验整条备份链的归档，并列出所选前缀在备份中匹配到的文件数量。
+    pub fn verify_partial_restore<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        backup_id: &str,
+        selection: &SaveRestoreSelection,
+    ) -> Result<SavePartialRestoreCheckResult, String> {
+        if selection.is_empty() {
+            return Err("restore selection is empty".to_string());
+        }
+        let world_prefixes = selection.world_prefixes()?;
+        let config_prefixes = selection.config_prefixes()?;
+
+        let chain = Self::collect_backup_chain(app, instance_id, backup_id)?;
+        let target = chain
+            .last()
+            .ok_or_else(|| "backup snapshot not found".to_string())?;
+        if !config_prefixes.is_empty() && !target.meta.has_configs {
+            return Err("backup snapshot does not contain configs".to_string());
+        }
+        for record in &chain {
+            Self::verify_backup_record_payloads(record, !config_prefixes.is_empty())?;
+        }
+
+        let (world_paths, config_paths) =
+            Self::selected_backup_paths(target, &world_prefixes, &config_prefixes)?;
+        let mut check = Self::build_restore_check(app, instance_id, &target.meta)?;
+        check.can_restore_configs = target.meta.has_configs;
+
+        let unmatched_prefixes = world_prefixes
+            .iter()
+            .filter(|prefix| {
+                !world_paths
+                    .iter()
+                    .any(|path| partial_restore::path_matches(path, std::slice::from_ref(prefix)))
+            })
+            .chain(config_prefixes.iter().filter(|prefix| {
+                !config_paths
+                    .iter()
+                    .any(|path| partial_restore::path_matches(path, std::slice::from_ref(prefix)))
+            }))
+            .cloned()
+            .collect::<Vec<_>>();
+        for prefix in &unmatched_prefixes {
+            check
+                .warnings
+                .push(format!("backup has no files under {}", prefix));
+        }
+        if Self::is_game_process_running() {
+            check
+                .warnings
+                .push("the game is running; close it before restoring".to_string());
+        }
+
+        Ok(SavePartialRestoreCheckResult {
+            check,
+            chain: chain
+                .iter()
+                .map(|record| record.meta.backup_id.clone())
+                .collect(),
+            world_prefixes,
+            config_prefixes,
+            matched_world_files: world_paths.len(),
+            matched_config_files: config_paths.len(),
+            unmatched_prefixes,
+        })
+    }
+
+    /// 只恢复备份中的部分路径 / 维度，其余文件保持不变。
+    /// 所选前缀下的现有文件会被替换为备份时刻的内容，备份时不存在的文件会被移除。
+    pub fn restore_backup_partial<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        backup_id: &str,
+        selection: &SaveRestoreSelection,
+        auto_backup_current: bool,
+    ) -> Result<SavePartialRestoreResult, String> {
+        if selection.is_empty() {
+            return Err("restore selection is empty".to_string());
+        }
+        let world_prefixes = selection.world_prefixes()?;
+        let config_prefixes = selection.config_prefixes()?;
+
+        let chain = Self::collect_backup_chain(app, instance_id, backup_id)?;
+        let target = chain
+            .last()
+            .ok_or_else(|| "backup snapshot not found".to_string())?;
+        if !config_prefixes.is_empty() && !target.meta.has_configs {
+            return Err("backup snapshot does not contain configs".to_string());
+        }
+        for record in &chain {
+            Self::verify_backup_record_payloads(record, !config_prefixes.is_empty())?;
+        }
+
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let game_dir = Self::get_game_dir(app, instance_id)?;
+        let saves_dir = game_dir.join("saves");
+        let target_folder_name = target.meta.world.folder_name.clone();
+        let target_save_dir = saves_dir.join(&target_folder_name);
+        if !world_prefixes.is_empty() && !target_save_dir.exists() {
+            return Err("save folder does not exist; use a full restore instead".to_string());
+        }
+        if Self::is_game_process_running() || Self::is_session_locked(&target_save_dir) {
+            return Err("cannot restore while the game is running".to_string());
+        }
+
+        let (world_paths, config_paths) =
+            Self::selected_backup_paths(target, &world_prefixes, &config_prefixes)?;
+
+        let guard_backup_id = if auto_backup_current && target_save_dir.exists() {
+            Some(
+                Self::create_backup(
+                    app,
+                    instance_id,
+                    &target_folder_name,
+                    "restore_guard",
+                    "full",
+                    &Self::read_backup_policy(&instance_dir),
+                )?
+                .backup_id,
+            )
+        } else {
+            None
+        };
+
+        let mut result = SavePartialRestoreResult {
+            backup_id: target.meta.backup_id.clone(),
+            restored_folder_name: target_folder_name.clone(),
+            guard_backup_id,
+            ..Default::default()
+        };
+
+        if !world_prefixes.is_empty() {
+            let temp_restore_dir = saves_dir.join(format!(".restore-{}", Uuid::new_v4()));
+            let rollback_dir = saves_dir.join(format!(".rollback-{}", Uuid::new_v4()));
+            let _work_dirs = WorkDirsGuard(vec![temp_restore_dir.clone(), rollback_dir.clone()]);
+            let world_result = Self::extract_selected_chain(
+                &chain,
+                false,
+                &world_prefixes,
+                &world_paths,
+                &temp_restore_dir,
+            )
+            .and_then(|_| {
+                Self::swap_selected_files(
+                    &target_save_dir,
+                    &temp_restore_dir,
+                    &rollback_dir,
+                    &world_prefixes,
+                )
+            });
+            let (restored, removed) = world_result?;
+            result.restored_files = restored;
+            result.removed_files = removed;
+
+            Self::clear_cached_world_info(&target_save_dir)?;
+            let _ = Self::inspect_save_folder(instance_id, &target_folder_name, &target_save_dir);
+        }
+
+        if !config_prefixes.is_empty() {
+            let temp_configs_root =
+                instance_dir.join(format!(".restore-configs-{}", Uuid::new_v4()));
+            let rollback_configs_root =
+                instance_dir.join(format!(".rollback-configs-{}", Uuid::new_v4()));
+            let _work_dirs = WorkDirsGuard(vec![
+                temp_configs_root.clone(),
+                rollback_configs_root.clone(),
+            ]);
+            let config_result = Self::extract_selected_chain(
+                &chain,
+                true,
+                &config_prefixes,
+                &config_paths,
+                &temp_configs_root,
+            )
+            .and_then(|_| {
+                Self::swap_selected_files(
+                    &game_dir,
+                    &temp_configs_root,
+                    &rollback_configs_root,
+                    &config_prefixes,
+                )
+            });
+            match config_result {
+                Ok((restored, removed)) => {
+                    result.restored_config_files = restored;
+                    result.removed_files += removed;
+                }
+                Err(error) => {
+                    // 存档部分已经恢复完成，配置失败只作为警告返回
+                    result
+                        .warnings
+                        .push(format!("config restore failed: {}", error));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
     pub fn get_backups<R: Runtime>(
         app: &AppHandle<R>,
         instance_id: &str,
@@ -2333,4 +3258,370 @@ impl SaveManagerService {
         Self::write_json_atomically(&config_path, &value)?;
         Ok(())
     }
+
+    pub fn get_backup_retention<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        folder_name: Option<&str>,
+    ) -> Result<SaveBackupRetention, String> {
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let policy = Self::read_backup_policy(&instance_dir);
+        Ok(match folder_name {
+            // 与备份裁剪时一致：先按世界 UUID，再按文件夹名查找
+            Some(folder_name) => {
+                let save_dir = Self::get_game_dir(app, instance_id)?
+                    .join("saves")
+                    .join(folder_name);
+                let world = SaveBackupWorld {
+                    name: folder_name.to_string(),
+                    uuid: Self::read_world_uuid(&save_dir).unwrap_or_default(),
+                    folder_name: folder_name.to_string(),
+                };
+                policy.retention_for(&world).clone()
+            }
+            None => policy.retention,
+        })
+    }
+
+    /// 设置实例级（`folder_name` 为空）或单个世界的保留策略；
+    /// 传入空策略会移除该世界的单独配置，回落到实例级策略。
+    pub fn set_backup_retention<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        folder_name: Option<&str>,
+        retention: SaveBackupRetention,
+    ) -> Result<(), String> {
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let config_path = instance_dir.join("instance.json");
+
+        let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
+        let mut value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let root = value
+            .as_object_mut()
+            .ok_or_else(|| "invalid instance.json".to_string())?;
+        let save_backup = root
+            .entry("saveBackup")
+            .or_insert_with(|| {
+                serde_json::json!({
+                    "enabled": true,
+                    "autoOnExit": false,
+                    "includeConfigs": true,
+                    "backupAllWorldsOnExit": false,
+                    "safety": {
+                        "waitAfterExitSeconds": DEFAULT_EXIT_BACKUP_COOLDOWN_SECONDS,
+                        "requireStableFiles": true,
+                        "stableWindowSeconds": DEFAULT_STABLE_WINDOW_SECONDS,
+                    }
+                })
+            })
+            .as_object_mut()
+            .ok_or_else(|| "invalid saveBackup section in instance.json".to_string())?;
+
+        let retention_value = serde_json::to_value(&retention).map_err(|e| e.to_string())?;
+        match folder_name {
+            None => {
+                save_backup.insert("retention".to_string(), retention_value);
+            }
+            Some(folder_name) => {
+                let worlds = save_backup
+                    .entry("worldRetention")
+                    .or_insert_with(|| serde_json::json!({}))
+                    .as_object_mut()
+                    .ok_or_else(|| "invalid worldRetention section in instance.json".to_string())?;
+                if retention == SaveBackupRetention::default() {
+                    worlds.remove(folder_name);
+                } else {
+                    worlds.insert(folder_name.to_string(), retention_value);
+                }
+            }
+        }
+
+        Self::write_json_atomically(&config_path, &value)
+    }
+
+    pub fn get_periodic_backup_settings<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+    ) -> Result<SavePeriodicBackupSettings, String> {
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let policy = Self::read_backup_policy(&instance_dir);
+        Ok(SavePeriodicBackupSettings {
+            enabled: policy.periodic_enabled,
+            interval_minutes: policy.periodic_interval_minutes,
+        })
+    }
+
+    pub fn set_periodic_backup_settings<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        settings: SavePeriodicBackupSettings,
+    ) -> Result<(), String> {
+        if settings.enabled && settings.interval_minutes == 0 {
+            return Err("periodic backup interval must be at least one minute".to_string());
+        }
+
+        let instance_dir = Self::get_instance_dir(app, instance_id)?;
+        let config_path = instance_dir.join("instance.json");
+
+        let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
+        let mut value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let periodic = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+
+        if let Some(save_backup) = value.get_mut("saveBackup") {
+            if let Some(obj) = save_backup.as_object_mut() {
+                obj.insert("periodic".to_string(), periodic);
+            }
+        } else {
+            let save_backup_obj = serde_json::json!({
+                "enabled": true,
+                "autoOnExit": false,
+                "includeConfigs": true,
+                "backupAllWorldsOnExit": false,
+                "safety": {
+                    "waitAfterExitSeconds": DEFAULT_EXIT_BACKUP_COOLDOWN_SECONDS,
+                    "requireStableFiles": true,
+                    "stableWindowSeconds": DEFAULT_STABLE_WINDOW_SECONDS,
+                },
+                "periodic": periodic,
+            });
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("saveBackup".to_string(), save_backup_obj);
+            }
+        }
+
+        Self::write_json_atomically(&config_path, &value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pilauncher-save-manager-{}-{}",
+            name,
+            Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_file(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn read_file(root: &Path, rel: &str) -> String {
+        fs::read_to_string(root.join(rel)).unwrap()
+    }
+
+    fn zip_record(dir: &Path, backup_id: &str, files: &[(&str, &str)]) -> BackupRecord {
+        let archive_path = dir.join(format!("{}.zip", backup_id));
+        let mut zip = ZipWriter::new(File::create(&archive_path).unwrap());
+        for (name, content) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        BackupRecord {
+            meta: SaveBackupMetadata {
+                backup_id: backup_id.to_string(),
+                ..Default::default()
+            },
+            backup_dir: dir.to_path_buf(),
+            world_payload: BackupPayload::Archive(archive_path),
+            configs_payload: BackupPayload::Missing,
+        }
+    }
+
+    #[test]
+    fn extract_selected_chain_applies_diffs_and_drops_deleted_files() {
+        let dir = temp_dir("chain");
+        let chain = vec![
+            zip_record(
+                &dir,
+                "full",
+                &[
+                    ("level.dat", "level"),
+                    ("region/r.0.0.mca", "v1"),
+                    ("region/r.1.0.mca", "gone"),
+                ],
+            ),
+            zip_record(&dir, "diff", &[("region/r.0.0.mca", "v2")]),
+        ];
+        let prefixes = vec!["region".to_string()];
+        let wanted = HashSet::from(["region/r.0.0.mca".to_string()]);
+        let dst = dir.join("out");
+
+        SaveManagerService::extract_selected_chain(&chain, false, &prefixes, &wanted, &dst)
+            .unwrap();
+        assert_eq!(read_file(&dst, "region/r.0.0.mca"), "v2");
+        assert!(!dst.join("region/r.1.0.mca").exists());
+        assert!(!dst.join("level.dat").exists());
+
+        let mut broken = chain;
+        broken[1].world_payload = BackupPayload::Missing;
+        assert!(SaveManagerService::extract_selected_chain(
+            &broken,
+            false,
+            &prefixes,
+            &wanted,
+            &dir.join("broken")
+        )
+        .is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn swap_selected_files_replaces_prefixes_and_rolls_back_on_failure() {
+        let dir = temp_dir("swap");
+        let live = dir.join("live");
+        let restored = dir.join("restored");
+        write_file(&live, "level.dat", "live-level");
+        write_file(&live, "region/r.0.0.mca", "live");
+        write_file(&live, "region/r.1.0.mca", "live-only");
+        write_file(&restored, "region/r.0.0.mca", "restored");
+        let prefixes = vec!["region".to_string()];
+
+        let counts = SaveManagerService::swap_selected_files(
+            &live,
+            &restored,
+            &dir.join("rollback"),
+            &prefixes,
+        )
+        .unwrap();
+        assert_eq!(counts, (1, 1));
+        assert_eq!(read_file(&live, "region/r.0.0.mca"), "restored");
+        assert!(!live.join("region/r.1.0.mca").exists());
+        assert_eq!(
+            read_file(&dir.join("rollback"), "region/r.1.0.mca"),
+            "live-only"
+        );
+        assert_eq!(read_file(&live, "level.dat"), "live-level");
+
+        // level.dat 是文件，无法在其下放入恢复内容，整个交换应回滚
+        write_file(&restored, "region/r.0.0.mca", "second");
+        write_file(&restored, "level.dat/nested", "bad");
+        let result = SaveManagerService::swap_selected_files(
+            &live,
+            &restored,
+            &dir.join("rollback-2"),
+            &prefixes,
+        );
+        assert!(result.is_err());
+        assert_eq!(read_file(&live, "region/r.0.0.mca"), "restored");
+        assert_eq!(read_file(&live, "level.dat"), "live-level");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn periodic_interval_is_at_least_one_minute() {
+        let mut policy = SaveBackupPolicy {
+            periodic_enabled: true,
+            periodic_interval_minutes: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            SaveManagerService::periodic_interval_seconds(&policy),
+            Some(60)
+        );
+        policy.periodic_interval_minutes = 15;
+        assert_eq!(
+            SaveManagerService::periodic_interval_seconds(&policy),
+            Some(900)
+        );
+        policy.periodic_enabled = false;
+        assert_eq!(SaveManagerService::periodic_interval_seconds(&policy), None);
+        policy.periodic_enabled = true;
+        policy.enabled = false;
+        assert_eq!(SaveManagerService::periodic_interval_seconds(&policy), None);
+    }
+
+    #[test]
+    fn periodic_backup_is_postponed_while_the_save_is_written() {
+        let dir = temp_dir("live-save");
+        write_file(&dir, "level.dat", "level");
+        write_file(&dir, "session.lock", "lock");
+        let policy = SaveBackupPolicy {
+            stable_window_seconds: 0,
+            ..Default::default()
+        };
+
+        // 刚被游戏改写的 session.lock 表示世界正在保存
+        #[cfg(not(target_os = "windows"))]
+        assert!(!SaveManagerService::is_live_save_consistent(&dir, &policy));
+
+        File::options()
+            .write(true)
+            .open(dir.join("session.lock"))
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        // 稳定窗口内仍有区块写入
+        let region = dir.join("region");
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            fs::create_dir_all(&region).unwrap();
+            fs::write(region.join("r.0.0.mca"), "chunk").unwrap();
+        });
+        assert!(!SaveManagerService::is_live_save_consistent(&dir, &policy));
+        writer.join().unwrap();
+
+        assert!(SaveManagerService::is_live_save_consistent(&dir, &policy));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn periodic_backups_are_pruned_by_world_retention() {
+        let world = SaveBackupWorld {
+            uuid: "world-a".to_string(),
+            folder_name: "World A".to_string(),
+            ..Default::default()
+        };
+        let other = SaveBackupWorld {
+            uuid: "world-b".to_string(),
+            folder_name: "World B".to_string(),
+            ..Default::default()
+        };
+        let now = Local::now();
+        let backup = |id: &str, world: &SaveBackupWorld, minutes_ago: i64| SaveBackupMetadata {
+            backup_id: id.to_string(),
+            backup_mode: "full".to_string(),
+            world: world.clone(),
+            created_at: now.timestamp() - minutes_ago * 60,
+            trigger: PERIODIC_BACKUP_TRIGGER.to_string(),
+            ..Default::default()
+        };
+        let backups = vec![
+            backup("tick-3", &world, 0),
+            backup("tick-2", &world, 30),
+            backup("tick-1", &world, 60),
+            backup("other", &other, 90),
+        ];
+        let mut policy = SaveBackupPolicy::default();
+        policy.world_retention.insert(
+            "world-a".to_string(),
+            SaveBackupRetention {
+                keep_last: Some(2),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            SaveManagerService::plan_world_backup_pruning(&backups, &world, &policy, now),
+            vec!["tick-1".to_string()]
+        );
+        assert!(
+            SaveManagerService::plan_world_backup_pruning(&backups, &other, &policy, now)
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,315 @@
+// src-tauri/src/services/instance/save_retention.rs
+//
+// 存档备份保留策略（祖父-父-子轮换）。
+// 只负责“哪些备份可以删”的纯计算，真正的删除由本地 SaveManagerService
+// 与远端 WebDAV 同步服务各自执行，两边共用同一份策略与同一套规则。
+use crate::services::instance::save_manager::SaveBackupMetadata;
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveBackupRetention {
+    /// 无条件保留最近的 N 个备份
+    #[serde(default)]
+    pub keep_last: Option<usize>,
+    /// 最近 N 天内每天保留最新的一个
+    #[serde(default)]
+    pub keep_daily: Option<u32>,
+    /// 最近 N 周内每周保留最新的一个
+    #[serde(default)]
+    pub keep_weekly: Option<u32>,
+    /// 最近 N 个月内每月保留最新的一个
+    #[serde(default)]
+    pub keep_monthly: Option<u32>,
+    /// 该世界所有保留备份的总大小上限（字节）
+    #[serde(default)]
+    pub max_total_bytes: Option<u64>,
+    /// 是否允许自动清理手动创建的备份
+    #[serde(default)]
+    pub prune_manual: bool,
+}
+
+impl SaveBackupRetention {
+    /// 未配置任何规则时视为“全部保留”，与旧版本行为一致。
+    pub fn is_unbounded(&self) -> bool {
+        self.keep_last.is_none()
+            && self.keep_daily.is_none()
+            && self.keep_weekly.is_none()
+            && self.keep_monthly.is_none()
+            && self.max_total_bytes.is_none()
+    }
+}
+
+fn local_date(timestamp: i64) -> Option<NaiveDate> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|time| time.date_naive())
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn month_index(date: NaiveDate) -> i64 {
+    date.year() as i64 * 12 + date.month0() as i64
+}
+
+/// 按时间桶保留每个桶内最新的备份。`bucket` 返回 None 表示超出窗口。
+fn keep_newest_per_bucket<K, F>(
+    sorted: &[&SaveBackupMetadata],
+    keep: &mut HashSet<String>,
+    bucket: F,
+) where
+    K: Eq + std::hash::Hash,
+    F: Fn(NaiveDate) -> Option<K>,
+{
+    let mut seen = HashSet::new();
+    for meta in sorted {
+        let Some(key) = local_date(meta.created_at).and_then(&bucket) else {
+            continue;
+        };
+        if seen.insert(key) {
+            keep.insert(meta.backup_id.clone());
+        }
+    }
+}
+
+/// 把被保留备份在增量链上依赖的基础备份一并保留。
+fn keep_chain_ancestors(by_id: &HashMap<&str, &SaveBackupMetadata>, keep: &mut HashSet<String>) {
+    let mut pending = keep.iter().cloned().collect::<Vec<_>>();
+    while let Some(backup_id) = pending.pop() {
+        let base = by_id
+            .get(backup_id.as_str())
+            .and_then(|meta| meta.base_backup_id.as_deref());
+        if let Some(base) = base {
+            if by_id.contains_key(base) && keep.insert(base.to_string()) {
+                pending.push(base.to_string());
+            }
+        }
+    }
+}
+
+/// 计算单个世界下应被清理的备份，按创建时间从新到旧返回，
+/// 保证增量备份总是先于其基础备份被删除。
+pub fn plan_world_pruning(
+    backups: &[SaveBackupMetadata],
+    retention: &SaveBackupRetention,
+    now: DateTime<Local>,
+) -> Vec<String> {
+    if retention.is_unbounded() || backups.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = backups.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.backup_id.cmp(&b.backup_id))
+    });
+    let by_id = sorted
+        .iter()
+        .map(|meta| (meta.backup_id.as_str(), *meta))
+        .collect::<HashMap<_, _>>();
+
+    let has_time_rules = retention.keep_last.is_some()
+        || retention.keep_daily.is_some()
+        || retention.keep_weekly.is_some()
+        || retention.keep_monthly.is_some();
+    let mut keep = HashSet::new();
+    if has_time_rules {
+        if let Some(count) = retention.keep_last {
+            keep.extend(sorted.iter().take(count).map(|meta| meta.backup_id.clone()));
+        }
+
+        let today = now.date_naive();
+        if let Some(days) = retention.keep_daily.filter(|days| *days > 0) {
+            let oldest = today - chrono::Duration::days(days as i64 - 1);
+            keep_newest_per_bucket(&sorted, &mut keep, |date| {
+                (date >= oldest && date <= today).then_some(date)
+            });
+        }
+        if let Some(weeks) = retention.keep_weekly.filter(|weeks| *weeks > 0) {
+            let current = week_start(today);
+            let oldest = current - chrono::Duration::weeks(weeks as i64 - 1);
+            keep_newest_per_bucket(&sorted, &mut keep, |date| {
+                let start = week_start(date);
+                (start >= oldest && start <= current).then_some(start)
+            });
+        }
+        if let Some(months) = retention.keep_monthly.filter(|months| *months > 0) {
+            let current = month_index(today);
+            let oldest = current - (months as i64 - 1);
+            keep_newest_per_bucket(&sorted, &mut keep, |date| {
+                let index = month_index(date);
+                (index >= oldest && index <= current).then_some(index)
+            });
+        }
+    } else {
+        keep.extend(sorted.iter().map(|meta| meta.backup_id.clone()));
+    }
+
+    if !retention.prune_manual {
+        keep.extend(
+            sorted
+                .iter()
+                .filter(|meta| meta.trigger == "manual")
+                .map(|meta| meta.backup_id.clone()),
+        );
+    }
+    // 最新的备份永远保留，避免策略配置失误导致世界没有任何可用备份
+    keep.insert(sorted[0].backup_id.clone());
+    keep_chain_ancestors(&by_id, &mut keep);
+
+    if let Some(limit) = retention.max_total_bytes {
+        let mut total = sorted
+            .iter()
+            .filter(|meta| keep.contains(&meta.backup_id))
+            .map(|meta| meta.files.total_size)
+            .sum::<u64>();
+        // 从最旧的开始剔除，但不能剔除仍被其它保留备份引用的基础备份
+        for meta in sorted.iter().skip(1).rev() {
+            if total <= limit {
+                break;
+            }
+            if !keep.contains(&meta.backup_id) {
+                continue;
+            }
+            if meta.trigger == "manual" && !retention.prune_manual {
+                continue;
+            }
+            let still_needed = sorted.iter().any(|other| {
+                keep.contains(&other.backup_id)
+                    && other.base_backup_id.as_deref() == Some(meta.backup_id.as_str())
+            });
+            if still_needed {
+                continue;
+            }
+            keep.remove(&meta.backup_id);
+            total = total.saturating_sub(meta.files.total_size);
+        }
+    }
+
+    sorted
+        .iter()
+        .filter(|meta| !keep.contains(&meta.backup_id))
+        .map(|meta| meta.backup_id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::instance::save_manager::SaveBackupFiles;
+
+    fn backup(id: &str, days_ago: i64, base: Option<&str>, size: u64) -> SaveBackupMetadata {
+        SaveBackupMetadata {
+            backup_id: id.to_string(),
+            backup_mode: if base.is_some() {
+                "differential"
+            } else {
+                "full"
+            }
+            .to_string(),
+            base_backup_id: base.map(str::to_string),
+            trigger: "auto_exit".to_string(),
+            created_at: (now() - chrono::Duration::days(days_ago)).timestamp(),
+            files: SaveBackupFiles {
+                total_size: size,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn now() -> DateTime<Local> {
+        Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2024, 6, 14)
+                    .unwrap()
+                    .and_hms_opt(12, 0, 0)
+                    .unwrap(),
+            )
+            .single()
+            .unwrap()
+    }
+
+    #[test]
+    fn empty_policy_keeps_everything() {
+        let backups = vec![backup("a", 0, None, 1), backup("b", 40, None, 1)];
+        assert!(plan_world_pruning(&backups, &SaveBackupRetention::default(), now()).is_empty());
+    }
+
+    #[test]
+    fn keep_last_never_drops_needed_base() {
+        let backups = vec![
+            backup("full", 3, None, 10),
+            backup("diff1", 2, Some("full"), 1),
+            backup("diff2", 1, Some("full"), 1),
+            backup("old", 9, None, 10),
+        ];
+        let retention = SaveBackupRetention {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+        let pruned = plan_world_pruning(&backups, &retention, now());
+        assert_eq!(pruned, vec!["diff1".to_string(), "old".to_string()]);
+    }
+
+    #[test]
+    fn daily_and_weekly_buckets_keep_newest_each() {
+        let mut backups = Vec::new();
+        for day in 0..30 {
+            backups.push(backup(&format!("d{day}"), day, None, 1));
+        }
+        backups.push(backup("d0-early", 0, None, 1));
+        backups.last_mut().unwrap().created_at -= 3600;
+        let retention = SaveBackupRetention {
+            keep_daily: Some(7),
+            keep_weekly: Some(2),
+            ..Default::default()
+        };
+        let pruned = plan_world_pruning(&backups, &retention, now());
+        for day in 0..7 {
+            assert!(!pruned.contains(&format!("d{day}")));
+        }
+        assert!(pruned.contains(&"d0-early".to_string()));
+        // 2024-06-14 为周五，上一周（6/3 - 6/9）最新的是 5 天前的周日
+        assert!(!pruned.contains(&"d5".to_string()));
+        assert!(pruned.contains(&"d20".to_string()));
+    }
+
+    #[test]
+    fn size_cap_drops_oldest_but_respects_chain() {
+        let backups = vec![
+            backup("full", 3, None, 50),
+            backup("diff", 1, Some("full"), 5),
+            backup("old", 10, None, 50),
+        ];
+        let retention = SaveBackupRetention {
+            max_total_bytes: Some(60),
+            ..Default::default()
+        };
+        let pruned = plan_world_pruning(&backups, &retention, now());
+        assert_eq!(pruned, vec!["old".to_string()]);
+    }
+
+    #[test]
+    fn manual_backups_are_kept_unless_opted_in() {
+        let mut manual = backup("manual", 20, None, 1);
+        manual.trigger = "manual".to_string();
+        let backups = vec![backup("a", 0, None, 1), manual];
+        let mut retention = SaveBackupRetention {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+        assert!(plan_world_pruning(&backups, &retention, now()).is_empty());
+        retention.prune_manual = true;
+        assert_eq!(
+            plan_world_pruning(&backups, &retention, now()),
+            vec!["manual".to_string()]
+        );
+    }
+}
@@ -0,0 +1,399 @@
+// src-tauri/src/services/instance/world_info.rs
+//
+// 读取存档内部信息：level.dat 基础属性、playerdata 玩家概况与各维度区块统计。
+use crate::services::instance::anvil::RegionFile;
+use crate::services::instance::nbt::{self, NbtTag};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldDataPacks {
+    #[serde(default)]
+    pub enabled: Vec<String>,
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldPlayerSummary {
+    /// playerdata 文件名中的 UUID；单人存档 level.dat 内嵌的玩家为 None
+    pub uuid: Option<String>,
+    pub dimension: String,
+    pub position: Option<[f64; 3]>,
+    pub health: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldDimensionStats {
+    pub dimension: String,
+    pub region_files: usize,
+    pub chunk_count: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldInfo {
+    pub level_name: Option<String>,
+    /// 种子以字符串返回，避免前端 Number 精度丢失
+    pub seed: Option<String>,
+    pub game_mode: Option<String>,
+    #[serde(default)]
+    pub hardcore: bool,
+    pub difficulty: Option<String>,
+    pub version_name: Option<String>,
+    pub data_version: Option<i64>,
+    pub last_played: Option<i64>,
+    pub day_time: Option<i64>,
+    #[serde(default)]
+    pub data_packs: WorldDataPacks,
+    #[serde(default)]
+    pub players: Vec<WorldPlayerSummary>,
+    #[serde(default)]
+    pub dimensions: Vec<WorldDimensionStats>,
+}
+
+fn game_mode_name(value: i64) -> String {
+    match value {
+        0 => "survival".to_string(),
+        1 => "creative".to_string(),
+        2 => "adventure".to_string(),
+        3 => "spectator".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn difficulty_name(value: i64) -> String {
+    match value {
+        0 => "peaceful".to_string(),
+        1 => "easy".to_string(),
+        2 => "normal".to_string(),
+        3 => "hard".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 1.16 之前的维度是整数 ID，之后改为命名空间字符串。
+fn dimension_name(tag: Option<&NbtTag>) -> String {
+    match tag {
+        Some(NbtTag::String(value)) => value.clone(),
+        Some(other) => match other.as_i64() {
+            Some(-1) => "minecraft:the_nether".to_string(),
+            Some(1) => "minecraft:the_end".to_string(),
+            _ => "minecraft:overworld".to_string(),
+        },
+        None => "minecraft:overworld".to_string(),
+    }
+}
+
+fn string_list(tag: Option<&NbtTag>) -> Vec<String> {
+    tag.and_then(NbtTag::as_list)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn summarize_player(uuid: Option<String>, player: &NbtTag) -> WorldPlayerSummary {
+    let position = player
+        .get("Pos")
+        .and_then(NbtTag::as_list)
+        .and_then(|pos| match pos {
+            [x, y, z] => Some([x.as_f64()?, y.as_f64()?, z.as_f64()?]),
+            _ => None,
+        });
+    WorldPlayerSummary {
+        uuid,
+        dimension: dimension_name(player.get("Dimension")),
+        position,
+        health: player.get("Health").and_then(NbtTag::as_f64),
+    }
+}
+
+fn apply_level_data(info: &mut WorldInfo, data: &NbtTag) {
+    info.level_name = data
+        .get("LevelName")
+        .and_then(NbtTag::as_str)
+        .map(str::to_string);
+    // 1.16+ 种子位于 WorldGenSettings，旧版本在 RandomSeed
+    info.seed = data
+        .path(&["WorldGenSettings", "seed"])
+        .or_else(|| data.get("RandomSeed"))
+        .and_then(NbtTag::as_i64)
+        .map(|seed| seed.to_string());
+    info.game_mode = data
+        .get("GameType")
+        .and_then(NbtTag::as_i64)
+        .map(game_mode_name);
+    info.hardcore = data
+        .get("hardcore")
+        .and_then(NbtTag::as_i64)
+        .map(|value| value != 0)
+        .unwrap_or(false);
+    info.difficulty = data
+        .get("Difficulty")
+        .and_then(NbtTag::as_i64)
+        .map(difficulty_name);
+    info.version_name = data
+        .path(&["Version", "Name"])
+        .and_then(NbtTag::as_str)
+        .map(str::to_string);
+    info.data_version = data.get("DataVersion").and_then(NbtTag::as_i64);
+    info.last_played = data.get("LastPlayed").and_then(NbtTag::as_i64);
+    info.day_time = data
+        .get("DayTime")
+        .or_else(|| data.get("Time"))
+        .and_then(NbtTag::as_i64);
+    info.data_packs = WorldDataPacks {
+        enabled: string_list(data.path(&["DataPacks", "Enabled"])),
+        disabled: string_list(data.path(&["DataPacks", "Disabled"])),
+    };
+    if let Some(player) = data.get("Player") {
+        info.players.push(summarize_player(None, player));
+    }
+}
+
+/// 统计 .mca 文件头中已分配的区块数量，不解压任何区块数据。
+pub fn count_region_chunks(path: &Path) -> usize {
+    RegionFile::open(path)
+        .map(|region| region.chunk_indices().len())
+        .unwrap_or(0)
+}
+
+fn region_stats(dimension: String, region_dir: &Path) -> Option<WorldDimensionStats> {
+    let entries = fs::read_dir(region_dir).ok()?;
+    let mut stats = WorldDimensionStats {
+        dimension,
+        ..Default::default()
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("mca") {
+            continue;
+        }
+        stats.region_files += 1;
+        stats.chunk_count += count_region_chunks(&path);
+    }
+    Some(stats)
+}
+
+/// 找出存档内所有维度的 region 目录，包括 1.16+ 数据包定义的自定义维度。
+pub(crate) fn dimension_region_dirs(save_dir: &Path) -> Vec<(String, std::path::PathBuf)> {
+    let mut dirs = vec![
+        ("minecraft:overworld".to_string(), save_dir.join("region")),
+        (
+            "minecraft:the_nether".to_string(),
+            save_dir.join("DIM-1").join("region"),
+        ),
+        (
+            "minecraft:the_end".to_string(),
+            save_dir.join("DIM1").join("region"),
+        ),
+    ];
+
+    let custom_root = save_dir.join("dimensions");
+    for namespace in fs::read_dir(&custom_root)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+    {
+        let namespace_path = namespace.path();
+        let namespace_name = namespace.file_name().to_string_lossy().to_string();
+        for entry in walkdir::WalkDir::new(&namespace_path)
+            .min_depth(1)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_dir() && entry.file_name() == "region")
+        {
+            let Some(parent) = entry.path().parent() else {
+                continue;
+            };
+            let Ok(relative) = parent.strip_prefix(&namespace_path) else {
+                continue;
+            };
+            let id = relative.to_string_lossy().replace('\\', "/");
+            if id.is_empty() {
+                continue;
+            }
+            let dimension = format!("{}:{}", namespace_name, id);
+            if dirs.iter().any(|(existing, _)| existing == &dimension) {
+                continue;
+            }
+            dirs.push((dimension, entry.path().to_path_buf()));
+        }
+    }
+    dirs
+}
+
+/// 读取一个存档目录的内部信息。level.dat 缺失或损坏时返回错误，
+/// 单个玩家文件或区域文件读取失败则跳过。
+pub fn inspect_world(save_dir: &Path) -> Result<WorldInfo, String> {
+    let level = nbt::read_nbt_file(&save_dir.join("level.dat"))?;
+    let data = level
+        .get("Data")
+        .ok_or_else(|| "level.dat 缺少 Data 节点".to_string())?;
+
+    let mut info = WorldInfo::default();
+    apply_level_data(&mut info, data);
+
+    if let Ok(entries) = fs::read_dir(save_dir.join("playerdata")) {
+        let mut players = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("dat"))
+            .filter_map(|path| {
+                let uuid = path.file_stem()?.to_string_lossy().to_string();
+                let player = nbt::read_nbt_file(&path).ok()?;
+                Some(summarize_player(Some(uuid), &player))
+            })
+            .collect::<Vec<_>>();
+        players.sort_by(|a, b| a.uuid.cmp(&b.uuid));
+        info.players.extend(players);
+    }
+
+    info.dimensions = dimension_region_dirs(save_dir)
+        .into_iter()
+        .filter_map(|(dimension, dir)| region_stats(dimension, &dir))
+        .collect();
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::instance::anvil::tests::{build_region, chunk_root};
+    use crate::services::instance::nbt::tests::{compound, gzip_nbt};
+
+    fn temp_world(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pilauncher-world-info-{}-{}",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn region_with_chunks(count: usize) -> Vec<u8> {
+        let chunks: Vec<_> = (0..count).map(|index| (index, chunk_root(0))).collect();
+        build_region(&chunks)
+    }
+
+    #[test]
+    fn inspects_level_players_and_regions() {
+        let world = temp_world("full");
+        let level = compound(vec![(
+            "Data",
+            compound(vec![
+                ("LevelName", NbtTag::String("Survival".to_string())),
+                (
+                    "WorldGenSettings",
+                    compound(vec![("seed", NbtTag::Long(-4172144997902289642))]),
+                ),
+                ("GameType", NbtTag::Int(0)),
+                ("Difficulty", NbtTag::Byte(3)),
+                ("DataVersion", NbtTag::Int(3465)),
+                (
+                    "Version",
+                    compound(vec![("Name", NbtTag::String("1.20.1".to_string()))]),
+                ),
+                ("LastPlayed", NbtTag::Long(1_700_000_000_000)),
+                ("DayTime", NbtTag::Long(18000)),
+                (
+                    "DataPacks",
+                    compound(vec![(
+                        "Enabled",
+                        NbtTag::List(vec![
+                            NbtTag::String("vanilla".to_string()),
+                            NbtTag::String("file/terralith.zip".to_string()),
+                        ]),
+                    )]),
+                ),
+            ]),
+        )]);
+        fs::write(world.join("level.dat"), gzip_nbt(&level)).unwrap();
+
+        fs::create_dir_all(world.join("playerdata")).unwrap();
+        let player = compound(vec![
+            (
+                "Pos",
+                NbtTag::List(vec![
+                    NbtTag::Double(10.5),
+                    NbtTag::Double(70.0),
+                    NbtTag::Double(-3.25),
+                ]),
+            ),
+            (
+                "Dimension",
+                NbtTag::String("minecraft:the_nether".to_string()),
+            ),
+            ("Health", NbtTag::Float(18.0)),
+        ]);
+        fs::write(
+            world
+                .join("playerdata")
+                .join("0f0e0d0c-0000-0000-0000-000000000001.dat"),
+            gzip_nbt(&player),
+        )
+        .unwrap();
+
+        fs::create_dir_all(world.join("region")).unwrap();
+        fs::write(
+            world.join("region").join("r.0.0.mca"),
+            region_with_chunks(5),
+        )
+        .unwrap();
+        fs::write(
+            world.join("region").join("r.0.1.mca"),
+            region_with_chunks(2),
+        )
+        .unwrap();
+        fs::create_dir_all(world.join("dimensions/mymod/sky/region")).unwrap();
+        fs::write(
+            world.join("dimensions/mymod/sky/region/r.0.0.mca"),
+            region_with_chunks(1),
+        )
+        .unwrap();
+
+        let info = inspect_world(&world).unwrap();
+        assert_eq!(info.seed.as_deref(), Some("-4172144997902289642"));
+        assert_eq!(info.game_mode.as_deref(), Some("survival"));
+        assert_eq!(info.difficulty.as_deref(), Some("hard"));
+        assert_eq!(info.version_name.as_deref(), Some("1.20.1"));
+        assert_eq!(info.day_time, Some(18000));
+        assert_eq!(info.data_packs.enabled.len(), 2);
+        assert_eq!(info.players.len(), 1);
+        assert_eq!(info.players[0].dimension, "minecraft:the_nether");
+        assert_eq!(info.players[0].position, Some([10.5, 70.0, -3.25]));
+
+        let overworld = info
+            .dimensions
+            .iter()
+            .find(|stats| stats.dimension == "minecraft:overworld")
+            .unwrap();
+        assert_eq!((overworld.region_files, overworld.chunk_count), (2, 7));
+        assert!(info
+            .dimensions
+            .iter()
+            .any(|stats| stats.dimension == "mymod:sky" && stats.chunk_count == 1));
+
+        let _ = fs::remove_dir_all(world);
+    }
+
+    #[test]
+    fn legacy_integer_dimensions_are_named() {
+        assert_eq!(
+            dimension_name(Some(&NbtTag::Int(-1))),
+            "minecraft:the_nether"
+        );
+        assert_eq!(dimension_name(None), "minecraft:overworld");
+    }
+}
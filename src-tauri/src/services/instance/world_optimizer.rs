@@ -0,0 +1,389 @@
+// src-tauri/src/services/instance/world_optimizer.rs
+//
+// 存档瘦身：删除 InhabitedTime 低于阈值、且不在出生点与玩家附近保护半径内的区块，
+// 同步清理 entities / poi 中对应坐标的数据，并删除清空后的区域文件。
+// 这里只处理一个已脱机的存档目录，备份与原子替换由 SaveManagerService 负责。
+use crate::services::instance::anvil::{self, RegionFile};
+use crate::services::instance::nbt::{self, NbtTag};
+use crate::services::instance::world_info;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// 与区块坐标一一对应、需要随区块一起清理的子目录
+const COMPANION_REGION_DIRS: [&str; 2] = ["entities", "poi"];
+const DEFAULT_MIN_INHABITED_TICKS: i64 = 20 * 60 * 5;
+const DEFAULT_PROTECT_RADIUS_CHUNKS: i32 = 8;
+
+fn default_min_inhabited_ticks() -> i64 {
+    DEFAULT_MIN_INHABITED_TICKS
+}
+
+fn default_protect_radius_chunks() -> i32 {
+    DEFAULT_PROTECT_RADIUS_CHUNKS
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldOptimizeOptions {
+    /// 玩家在区块内停留的累计 tick 数低于该值时视为“未使用”
+    #[serde(default = "default_min_inhabited_ticks")]
+    pub min_inhabited_ticks: i64,
+    /// 出生点与每个玩家所在位置周围受保护的区块半径
+    #[serde(default = "default_protect_radius_chunks")]
+    pub protect_radius_chunks: i32,
+    /// 只处理这些维度，为空时处理全部维度
+    #[serde(default)]
+    pub dimensions: Vec<String>,
+    /// 仅统计不落盘
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl Default for WorldOptimizeOptions {
+    fn default() -> Self {
+        Self {
+            min_inhabited_ticks: DEFAULT_MIN_INHABITED_TICKS,
+            protect_radius_chunks: DEFAULT_PROTECT_RADIUS_CHUNKS,
+            dimensions: Vec::new(),
+            dry_run: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DimensionOptimizeStats {
+    pub dimension: String,
+    pub region_files_scanned: usize,
+    pub region_files_removed: usize,
+    pub chunks_scanned: usize,
+    pub chunks_removed: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldOptimizeReport {
+    pub folder_name: String,
+    pub backup_id: Option<String>,
+    pub dry_run: bool,
+    pub dimensions: Vec<DimensionOptimizeStats>,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_saved: u64,
+}
+
+/// 1.18 起区块数据直接位于根标签，之前包在 `Level` 里。
+fn chunk_inhabited_time(root: &NbtTag) -> Option<i64> {
+    root.get("InhabitedTime")
+        .or_else(|| root.path(&["Level", "InhabitedTime"]))
+        .and_then(NbtTag::as_i64)
+}
+
+fn block_to_chunk(value: f64) -> i32 {
+    (value.floor() as i64 >> 4) as i32
+}
+
+/// 按维度收集受保护的区块中心：主世界出生点与每个玩家的位置。
+fn protected_anchors(save_dir: &Path) -> Result<HashMap<String, Vec<(i32, i32)>>, String> {
+    let level = nbt::read_nbt_file(&save_dir.join("level.dat"))?;
+    let mut anchors: HashMap<String, Vec<(i32, i32)>> = HashMap::new();
+    if let Some(data) = level.get("Data") {
+        let spawn_x = data.get("SpawnX").and_then(NbtTag::as_i64);
+        let spawn_z = data.get("SpawnZ").and_then(NbtTag::as_i64);
+        if let (Some(x), Some(z)) = (spawn_x, spawn_z) {
+            anchors
+                .entry("minecraft:overworld".to_string())
+                .or_default()
+                .push(((x >> 4) as i32, (z >> 4) as i32));
+        }
+    }
+
+    let info = world_info::inspect_world(save_dir)?;
+    for player in info.players {
+        if let Some([x, _, z]) = player.position {
+            anchors
+                .entry(player.dimension)
+                .or_default()
+                .push((block_to_chunk(x), block_to_chunk(z)));
+        }
+    }
+    Ok(anchors)
+}
+
+fn is_protected(chunk: (i32, i32), anchors: &[(i32, i32)], radius: i32) -> bool {
+    anchors
+        .iter()
+        .any(|(x, z)| (chunk.0 - x).abs() <= radius && (chunk.1 - z).abs() <= radius)
+}
+
+fn region_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(anvil::parse_region_coords)
+                .is_some()
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// 对同名区域文件应用删除集合，返回 (处理前大小, 处理后大小, 是否已删除文件)。
+fn apply_removals(
+    path: &Path,
+    removed: &HashSet<usize>,
+    dry_run: bool,
+) -> Result<(u64, u64, bool), String> {
+    let region = RegionFile::open(path)?;
+    let before = region.file_size();
+    let remaining = region
+        .chunk_indices()
+        .into_iter()
+        .filter(|index| !removed.contains(index))
+        .count();
+    if dry_run {
+        // 预估值：删除后按剩余区块占比缩减，清空则整个文件可回收
+        let total = region.chunk_indices().len().max(1) as u64;
+        let after = if remaining == 0 {
+            0
+        } else {
+            before * remaining as u64 / total
+        };
+        return Ok((before, after, remaining == 0));
+    }
+    if removed.is_empty() {
+        return Ok((before, before, false));
+    }
+    let after = region.write_retaining(path, |index| !removed.contains(&index))?;
+    Ok((before, after.unwrap_or(0), after.is_none()))
+}
+
+fn optimize_dimension(
+    dimension: &str,
+    dimension_root: &Path,
+    anchors: &[(i32, i32)],
+    options: &WorldOptimizeOptions,
+) -> Result<DimensionOptimizeStats, String> {
+    let mut stats = DimensionOptimizeStats {
+        dimension: dimension.to_string(),
+        ..Default::default()
+    };
+
+    for region_path in region_files(&dimension_root.join("region")) {
+        let region = RegionFile::open(&region_path)?;
+        let mut removed = HashSet::new();
+        for index in region.chunk_indices() {
+            stats.chunks_scanned += 1;
+            if is_protected(
+                region.chunk_coords(index),
+                anchors,
+                options.protect_radius_chunks,
+            ) {
+                continue;
+            }
+            // 无法解析或缺少 InhabitedTime 的区块一律保留
+            let inhabited = match region.read_chunk(index) {
+                Ok(Some(root)) => chunk_inhabited_time(&root),
+                _ => None,
+            };
+            if inhabited.is_some_and(|ticks| ticks < options.min_inhabited_ticks) {
+                removed.insert(index);
+            }
+        }
+        drop(region);
+
+        stats.region_files_scanned += 1;
+        stats.chunks_removed += removed.len();
+        let (before, after, deleted) = apply_removals(&region_path, &removed, options.dry_run)?;
+        stats.bytes_before += before;
+        stats.bytes_after += after;
+        if deleted {
+            stats.region_files_removed += 1;
+        }
+
+        let Some(file_name) = region_path.file_name() else {
+            continue;
+        };
+        for companion in COMPANION_REGION_DIRS {
+            let companion_path = dimension_root.join(companion).join(file_name);
+            if !companion_path.exists() {
+                continue;
+            }
+            let (before, after, deleted) =
+                apply_removals(&companion_path, &removed, options.dry_run)?;
+            stats.bytes_before += before;
+            stats.bytes_after += after;
+            if deleted {
+                stats.region_files_removed += 1;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// 在给定存档目录上执行瘦身。调用方需保证游戏未运行且该目录是可丢弃的副本。
+pub fn optimize_world_dir(
+    save_dir: &Path,
+    options: &WorldOptimizeOptions,
+) -> Result<WorldOptimizeReport, String> {
+    let anchors = protected_anchors(save_dir)?;
+    let mut report = WorldOptimizeReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    for (dimension, region_dir) in world_info::dimension_region_dirs(save_dir) {
+        if !options.dimensions.is_empty() && !options.dimensions.contains(&dimension) {
+            continue;
+        }
+        let Some(dimension_root) = region_dir.parent() else {
+            continue;
+        };
+        if !region_dir.is_dir() {
+            continue;
+        }
+        let dimension_anchors = anchors.get(&dimension).cloned().unwrap_or_default();
+        let stats = optimize_dimension(&dimension, dimension_root, &dimension_anchors, options)?;
+        report.bytes_before += stats.bytes_before;
+        report.bytes_after += stats.bytes_after;
+        report.dimensions.push(stats);
+    }
+
+    report.bytes_saved = report.bytes_before.saturating_sub(report.bytes_after);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::instance::anvil::tests::{build_region, chunk_root};
+    use crate::services::instance::nbt::tests::{compound, gzip_nbt};
+
+    fn fixture_world() -> std::path::PathBuf {
+        let world = std::env::temp_dir().join(format!(
+            "pilauncher-world-optimizer-{}",
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(world.join("region")).unwrap();
+        fs::create_dir_all(world.join("entities")).unwrap();
+        fs::create_dir_all(world.join("playerdata")).unwrap();
+
+        let level = compound(vec![(
+            "Data",
+            compound(vec![("SpawnX", NbtTag::Int(8)), ("SpawnZ", NbtTag::Int(8))]),
+        )]);
+        fs::write(world.join("level.dat"), gzip_nbt(&level)).unwrap();
+
+        // 玩家站在区块 (20, 20) 上，位于 r.0.0 中索引 20 + 20 * 32 处
+        let player = compound(vec![(
+            "Pos",
+            NbtTag::List(vec![
+                NbtTag::Double(328.0),
+                NbtTag::Double(64.0),
+                NbtTag::Double(330.0),
+            ]),
+        )]);
+        fs::write(
+            world
+                .join("playerdata")
+                .join("00000000-0000-0000-0000-000000000001.dat"),
+            gzip_nbt(&player),
+        )
+        .unwrap();
+
+        let near_spawn = 1;
+        let unused = 10 + 10 * 32;
+        let lived_in = 11 + 10 * 32;
+        let near_player = 20 + 20 * 32;
+        fs::write(
+            world.join("region").join("r.0.0.mca"),
+            build_region(&[
+                (near_spawn, chunk_root(0)),
+                (unused, chunk_root(10)),
+                (lived_in, chunk_root(100_000)),
+                (near_player, chunk_root(0)),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            world.join("entities").join("r.0.0.mca"),
+            build_region(&[(unused, chunk_root(0)), (lived_in, chunk_root(0))]),
+        )
+        .unwrap();
+        fs::write(
+            world.join("region").join("r.3.3.mca"),
+            build_region(&[(0, chunk_root(0))]),
+        )
+        .unwrap();
+        fs::write(
+            world.join("entities").join("r.3.3.mca"),
+            build_region(&[(0, chunk_root(0))]),
+        )
+        .unwrap();
+        world
+    }
+
+    fn options() -> WorldOptimizeOptions {
+        WorldOptimizeOptions {
+            min_inhabited_ticks: 1200,
+            protect_radius_chunks: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prunes_unused_chunks_outside_protected_areas() {
+        let world = fixture_world();
+        let report = optimize_world_dir(&world, &options()).unwrap();
+
+        let overworld = &report.dimensions[0];
+        assert_eq!(overworld.dimension, "minecraft:overworld");
+        assert_eq!(overworld.chunks_scanned, 5);
+        assert_eq!(overworld.chunks_removed, 2);
+        assert_eq!(overworld.region_files_removed, 2);
+        assert!(report.bytes_saved > 0);
+        assert!(!world.join("region").join("r.3.3.mca").exists());
+        assert!(!world.join("entities").join("r.3.3.mca").exists());
+
+        let region = RegionFile::open(&world.join("region").join("r.0.0.mca")).unwrap();
+        assert_eq!(region.chunk_indices(), vec![1, 11 + 10 * 32, 20 + 20 * 32]);
+        let entities = RegionFile::open(&world.join("entities").join("r.0.0.mca")).unwrap();
+        assert_eq!(entities.chunk_indices(), vec![11 + 10 * 32]);
+
+        let _ = fs::remove_dir_all(world);
+    }
+
+    #[test]
+    fn dry_run_leaves_files_untouched() {
+        let world = fixture_world();
+        let before = fs::read(world.join("region").join("r.0.0.mca")).unwrap();
+        let report = optimize_world_dir(
+            &world,
+            &WorldOptimizeOptions {
+                dry_run: true,
+                ..options()
+            },
+        )
+        .unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.dimensions[0].chunks_removed, 2);
+        assert_eq!(
+            fs::read(world.join("region").join("r.0.0.mca")).unwrap(),
+            before
+        );
+        assert!(world.join("region").join("r.3.3.mca").exists());
+
+        let _ = fs::remove_dir_all(world);
+    }
+}
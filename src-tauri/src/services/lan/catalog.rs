@@ -0,0 +1,208 @@
+// src-tauri/src/services/lan/catalog.rs
+//
+// 局域网共享目录。
+// 汇总本机可分享的实例与存档（大小、版本、加载器、模组数量），供已配对设备浏览后按需拉取。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::domain::lan::{LanSharedInstance, LanSharedSave};
+
+pub fn is_safe_filename(name: &str) -> bool {
+    !name.is_empty() && !name.contains('/') && !name.contains('\\') && name != "." && name != ".."
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn count_mods(mods_dir: &Path) -> u32 {
+    fs::read_dir(mods_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
+                    name.ends_with(".jar") || name.ends_with(".jar.disabled")
+                })
+                .count() as u32
+        })
+        .unwrap_or(0)
+}
+
+fn modified_millis(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn list_saves(saves_dir: &Path) -> Vec<LanSharedSave> {
+    let mut saves: Vec<LanSharedSave> = fs::read_dir(saves_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| {
+                    let path = entry.path();
+                    let level_dat = path.join("level.dat");
+                    LanSharedSave {
+                        name: entry.file_name().to_string_lossy().to_string(),
+                        size: dir_size(&path),
+                        last_modified: modified_millis(if level_dat.exists() {
+                            &level_dat
+                        } else {
+                            &path
+                        }),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    saves.sort_by_key(|save| std::cmp::Reverse(save.last_modified));
+    saves
+}
+
+fn read_instance(instance_dir: &Path) -> Option<LanSharedInstance> {
+    let id = instance_dir.file_name()?.to_string_lossy().to_string();
+    let content = fs::read_to_string(instance_dir.join("instance.json")).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let text = |pointer: &str| {
+        config
+            .pointer(pointer)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let name = Some(text("/name"))
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| id.clone());
+
+    Some(LanSharedInstance {
+        name,
+        mc_version: text("/mcVersion"),
+        loader_type: text("/loader/type"),
+        loader_version: text("/loader/version"),
+        mod_count: count_mods(&instance_dir.join("mods")),
+        size: dir_size(instance_dir),
+        saves: list_saves(&instance_dir.join("saves")),
+        id,
+    })
+}
+
+/// 扫描实例目录生成共享目录，只包含带有 instance.json 的实例；会遍历文件计算大小，需在阻塞线程中调用
+pub fn build_catalog(instances_dir: &Path) -> Vec<LanSharedInstance> {
+    let mut instances: Vec<LanSharedInstance> = fs::read_dir(instances_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| read_instance(&entry.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    instances.sort_by_key(|instance| instance.name.to_lowercase());
+    instances
+}
+
+/// 解析传输内容的源目录与显示名称，拒绝路径穿越
+pub fn resolve_transfer_source(
+    instances_dir: &Path,
+    transfer_type: &str,
+    target_id: &str,
+    save_name: Option<&str>,
+) -> Result<(PathBuf, String), String> {
+    if !is_safe_filename(target_id) {
+        return Err("非法的实例 ID".to_string());
+    }
+    let (src_dir, item_name) = if transfer_type == "instance" {
+        (instances_dir.join(target_id), target_id.to_string())
+    } else {
+        let save = save_name.unwrap_or_default();
+        if !is_safe_filename(save) {
+            return Err("非法的存档名称".to_string());
+        }
+        (
+            instances_dir.join(target_id).join("saves").join(save),
+            save.to_string(),
+        )
+    };
+
+    if !src_dir.is_dir() {
+        return Err("目标内容不存在，无法发送".to_string());
+    }
+    Ok((src_dir, item_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_instances_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pilauncher-catalog-{}", uuid::Uuid::new_v4()));
+        let instance = dir.join("pack");
+        fs::create_dir_all(instance.join("mods")).unwrap();
+        fs::create_dir_all(instance.join("saves").join("World 1")).unwrap();
+        fs::create_dir_all(dir.join("not-an-instance")).unwrap();
+        fs::write(
+            instance.join("instance.json"),
+            r#"{"name":"My Pack","mcVersion":"1.20.1","loader":{"type":"fabric","version":"0.15.0"}}"#,
+        )
+        .unwrap();
+        fs::write(instance.join("mods").join("a.jar"), [0u8; 10]).unwrap();
+        fs::write(instance.join("mods").join("b.jar.disabled"), [0u8; 5]).unwrap();
+        fs::write(instance.join("mods").join("notes.txt"), [0u8; 3]).unwrap();
+        fs::write(
+            instance.join("saves").join("World 1").join("level.dat"),
+            [0u8; 7],
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn catalog_lists_instances_with_metadata_and_saves() {
+        let dir = temp_instances_dir();
+        let catalog = build_catalog(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(catalog.len(), 1);
+        let instance = &catalog[0];
+        assert_eq!(instance.id, "pack");
+        assert_eq!(instance.name, "My Pack");
+        assert_eq!(instance.mc_version, "1.20.1");
+        assert_eq!(instance.loader_type, "fabric");
+        assert_eq!(instance.mod_count, 2);
+        assert!(instance.size >= 25);
+        assert_eq!(instance.saves.len(), 1);
+        assert_eq!(instance.saves[0].name, "World 1");
+        assert_eq!(instance.saves[0].size, 7);
+    }
+
+    #[test]
+    fn transfer_source_rejects_traversal_and_missing_targets() {
+        let dir = temp_instances_dir();
+        let instance = resolve_transfer_source(&dir, "instance", "pack", None);
+        let save = resolve_transfer_source(&dir, "save", "pack", Some("World 1"));
+        let traversal = resolve_transfer_source(&dir, "save", "pack", Some(".."));
+        let missing = resolve_transfer_source(&dir, "instance", "other", None);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(instance.unwrap().1, "pack");
+        assert!(save.unwrap().0.ends_with("World 1"));
+        assert!(traversal.is_err());
+        assert!(resolve_transfer_source(&dir, "instance", "../x", None).is_err());
+        assert!(missing.is_err());
+    }
+}
@@ -0,0 +1,945 @@
+// src-tauri/src/services/lan/delta_transfer.rs
+//
+// 局域网增量传输。
+// 发送方先提交带 SHA-1 的文件清单（模组优先复用 mod_manifest.json 中的哈希），
+// 接收方用本地已有的同哈希文件补齐，只索取缺失部分；缺失文件按块上传，
+// 暂存区中的 .part 文件保证中断后可以从已接收的偏移继续；请求体均经 secure_channel 会话加密。
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use walkdir::WalkDir;
+
+use crate::domain::lan::{
+    DeltaCompleteRequest, DeltaFileEntry, DeltaNeededFile, DeltaOffer, DeltaOfferResponse,
+    DeviceIdentity,
+};
+use crate::domain::mod_manifest::{build_file_state, compute_sha1, mod_manifest_key};
+use crate::services::instance::mod_manifest_service::ModManifestService;
+use crate::services::lan::secure_channel::{self, SecureSession};
+use crate::services::lan::trust_store::TrustStore;
+
+/// 单个分块的大小，接收端的请求体上限需略大于它
+pub const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+pub const CHUNK_BODY_LIMIT: usize = CHUNK_SIZE + 64 * 1024;
+const CHUNK_RETRIES: usize = 3;
+/// 超过该时长未续传的暂存会话在启动时清理
+pub const STALE_SESSION_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub fn is_valid_transfer_id(transfer_id: &str) -> bool {
+    !transfer_id.is_empty()
+        && transfer_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// 接收方的暂存目录，完成后作为 tempPath 交给 apply_received_transfer
+pub fn staging_dir(temp_root: &Path, transfer_id: &str) -> PathBuf {
+    temp_root.join(format!("{}.delta", transfer_id))
+}
+
+/// 前端回传的 tempPath 必须位于传输缓存目录之内，返回规范化后的路径
+pub fn resolve_received_path(temp_root: &Path, temp_path: &str) -> Result<PathBuf, String> {
+    let root = temp_root
+        .canonicalize()
+        .map_err(|_| "传输缓存目录不存在".to_string())?;
+    let path = Path::new(temp_path)
+        .canonicalize()
+        .map_err(|_| "接收的临时文件不存在".to_string())?;
+    if path == root || !path.starts_with(&root) {
+        return Err("非法的临时文件路径".to_string());
+    }
+    Ok(path)
+}
+
+fn offer_path(temp_root: &Path, transfer_id: &str) -> PathBuf {
+    temp_root.join(format!("{}.delta.json", transfer_id))
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    let mut value = path.as_os_str().to_owned();
+    value.push(".part");
+    PathBuf::from(value)
+}
+
+/// 清单中的路径统一使用 `/` 分隔，只允许普通路径段
+pub fn safe_relative_path(path: &str) -> Result<PathBuf, String> {
+    let invalid = || format!("非法的传输路径: {}", path);
+    if path.is_empty() || path.contains('\\') || path.contains(':') {
+        return Err(invalid());
+    }
+    let relative = PathBuf::from(path);
+    let all_normal = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if !all_normal || path.split('/').any(|segment| segment.is_empty()) {
+        return Err(invalid());
+    }
+    Ok(relative)
+}
+
+fn relative_name(prefix: &Path, path: &Path) -> String {
+    path.strip_prefix(prefix)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn same_hash(left: &str, right: &str) -> bool {
+    left.eq_ignore_ascii_case(right)
+}
+
+/// 读取 mods 目录下仍与清单记录一致（大小与修改时间未变）的 SHA-1
+fn manifest_hashes(instance_dir: &Path) -> HashMap<PathBuf, String> {
+    let manifest_path = instance_dir.join("mod_manifest.json");
+    if !manifest_path.exists() {
+        return HashMap::new();
+    }
+    let manifest = ModManifestService::read_manifest_robust(&manifest_path);
+    let mods_dir = instance_dir.join("mods");
+    let Ok(entries) = fs::read_dir(&mods_dir) else {
+        return HashMap::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let manifest_entry = manifest.get(&mod_manifest_key(&file_name))?;
+            if !manifest_entry.hash.algorithm.eq_ignore_ascii_case("sha1") {
+                return None;
+            }
+            let current_state = build_file_state(&path).ok()?;
+            (manifest_entry.file_state.as_ref() == Some(&current_state))
+                .then(|| (path, manifest_entry.hash.value.clone()))
+        })
+        .collect()
+}
+
+/// 生成发送清单：路径相对于 src_dir 的父目录，与压缩包内的结构一致
+pub fn build_file_list<F>(
+    src_dir: &Path,
+    mut on_progress: F,
+) -> Result<(Vec<String>, Vec<DeltaFileEntry>), String>
+where
+    F: FnMut(u64, u64, String),
+{
+    let prefix = src_dir.parent().unwrap_or(src_dir);
+    let known_hashes = manifest_hashes(src_dir);
+    let entries: Vec<_> = WalkDir::new(src_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .collect();
+    let total = entries.len() as u64;
+
+    let mut directories = Vec::new();
+    let mut files = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let path = entry.path();
+        let name = relative_name(prefix, path);
+        if entry.file_type().is_dir() {
+            directories.push(name);
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let size = entry
+            .metadata()
+            .map_err(|e| format!("读取文件信息失败: {}", e))?
+            .len();
+        let sha1 = match known_hashes.get(path) {
+            Some(hash) => hash.clone(),
+            None => compute_sha1(path).map_err(|e| format!("计算文件校验值失败: {}", e))?,
+        };
+        on_progress(index as u64 + 1, total, format!("Hashing {}", name));
+        files.push(DeltaFileEntry {
+            path: name,
+            size,
+            sha1,
+        });
+    }
+    Ok((directories, files))
+}
+
+/// 接收方本地可复用的文件：所有实例中与清单记录一致的模组，按 SHA-1 索引
+pub fn local_hash_index(instances_dir: &Path) -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    let Ok(entries) = fs::read_dir(instances_dir) else {
+        return index;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if entry.path().is_dir() {
+            for (path, hash) in manifest_hashes(&entry.path()) {
+                index.entry(hash.to_ascii_lowercase()).or_insert(path);
+            }
+        }
+    }
+    index
+}
+
+fn copy_verified(source: &Path, dest: &Path, sha1: &str) -> bool {
+    let part = part_path(dest);
+    let copied = fs::copy(source, &part).is_ok()
+        && compute_sha1(&part)
+            .map(|hash| same_hash(&hash, sha1))
+            .unwrap_or(false);
+    if copied && fs::rename(&part, dest).is_ok() {
+        return true;
+    }
+    let _ = fs::remove_file(&part);
+    false
+}
+
+pub fn load_offer(temp_root: &Path, transfer_id: &str) -> Result<DeltaOffer, String> {
+    let content = fs::read_to_string(offer_path(temp_root, transfer_id))
+        .map_err(|_| "传输会话不存在或已结束".to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("传输会话已损坏: {}", e))
+}
+
+/// 登记发送清单并返回仍需接收的文件。
+/// 暂存区已完成且哈希一致的文件直接跳过，本地有同哈希文件的先复制；
+/// same_path_root 为实例目录时，同路径、同大小且哈希一致的文件也会复用
+pub fn prepare_offer(
+    temp_root: &Path,
+    offer: &DeltaOffer,
+    local_index: &HashMap<String, PathBuf>,
+    same_path_root: Option<&Path>,
+) -> Result<Vec<DeltaNeededFile>, String> {
+    if !is_valid_transfer_id(&offer.transfer_id) {
+        return Err("非法的传输 ID".to_string());
+    }
+    for path in offer
+        .directories
+        .iter()
+        .chain(offer.files.iter().map(|file| &file.path))
+    {
+        safe_relative_path(path)?;
+    }
+
+    let staging = staging_dir(temp_root, &offer.transfer_id);
+    fs::create_dir_all(&staging).map_err(|e| format!("创建传输暂存目录失败: {}", e))?;
+    let content = serde_json::to_string(offer).map_err(|e| e.to_string())?;
+    fs::write(offer_path(temp_root, &offer.transfer_id), content)
+        .map_err(|e| format!("保存传输会话失败: {}", e))?;
+    for directory in &offer.directories {
+        fs::create_dir_all(staging.join(directory))
+            .map_err(|e| format!("创建传输暂存目录失败: {}", e))?;
+    }
+
+    let mut needed = Vec::new();
+    for file in &offer.files {
+        let relative = safe_relative_path(&file.path)?;
+        let dest = staging.join(&relative);
+        let staged = fs::metadata(&dest)
+            .map(|meta| meta.is_file() && meta.len() == file.size)
+            .unwrap_or(false)
+            && compute_sha1(&dest)
+                .map(|hash| same_hash(&hash, &file.sha1))
+                .unwrap_or(false);
+        if staged {
+            continue;
+        }
+        // 大小相同但内容不一致的旧文件会让 write_chunk 误判为已完成
+        if dest.is_file() {
+            fs::remove_file(&dest).map_err(|e| format!("清理传输暂存文件失败: {}", e))?;
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建传输暂存目录失败: {}", e))?;
+        }
+        // 空文件没有分块可发，直接创建
+        if file.size == 0 {
+            fs::write(&dest, []).map_err(|e| format!("创建传输暂存文件失败: {}", e))?;
+            continue;
+        }
+
+        let same_path = same_path_root
+            .map(|root| root.join(&relative))
+            .filter(|path| {
+                fs::metadata(path)
+                    .map(|meta| meta.is_file() && meta.len() == file.size)
+                    .unwrap_or(false)
+            });
+        let reused = local_index
+            .get(&file.sha1.to_ascii_lowercase())
+            .into_iter()
+            .chain(same_path.as_ref())
+            .any(|source| copy_verified(source, &dest, &file.sha1));
+        if reused {
+            continue;
+        }
+
+        let part = part_path(&dest);
+        let offset = match fs::metadata(&part) {
+            Ok(meta) if meta.len() <= file.size => meta.len(),
+            Ok(_) => {
+                let _ = fs::remove_file(&part);
+                0
+            }
+            Err(_) => 0,
+        };
+        needed.push(DeltaNeededFile {
+            path: file.path.clone(),
+            offset,
+        });
+    }
+    Ok(needed)
+}
+
+pub enum ChunkError {
+    /// 偏移与已接收的长度不一致，附带接收方当前的偏移
+    OffsetMismatch(u64),
+    Invalid(String),
+}
+
+/// 追加一个分块，文件接收完整后校验 SHA-1 并转正；返回该文件是否已完成
+pub fn write_chunk(
+    temp_root: &Path,
+    transfer_id: &str,
+    path: &str,
+    offset: u64,
+    bytes: &[u8],
+) -> Result<bool, ChunkError> {
+    let offer = load_offer(temp_root, transfer_id).map_err(ChunkError::Invalid)?;
+    let entry = offer
+        .files
+        .iter()
+        .find(|file| file.path == path)
+        .ok_or_else(|| ChunkError::Invalid(format!("清单中不存在文件: {}", path)))?;
+    let relative = safe_relative_path(path).map_err(ChunkError::Invalid)?;
+    let dest = staging_dir(temp_root, transfer_id).join(relative);
+    if dest.exists() {
+        return Ok(true);
+    }
+
+    let part = part_path(&dest);
+    let current = fs::metadata(&part).map(|meta| meta.len()).unwrap_or(0);
+    if current != offset {
+        return Err(ChunkError::OffsetMismatch(current));
+    }
+    let end = offset + bytes.len() as u64;
+    if end > entry.size {
+        return Err(ChunkError::Invalid("分块超出文件大小".to_string()));
+    }
+
+    if let Some(parent) = part.parent() {
+        fs::create_dir_all(parent).map_err(|e| ChunkError::Invalid(e.to_string()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&part)
+        .map_err(|e| ChunkError::Invalid(format!("写入暂存文件失败: {}", e)))?;
+    file.write_all(bytes)
+        .and_then(|_| file.flush())
+        .map_err(|e| ChunkError::Invalid(format!("写入暂存文件失败: {}", e)))?;
+    drop(file);
+
+    if end < entry.size {
+        return Ok(false);
+    }
+    let verified = compute_sha1(&part)
+        .map(|hash| same_hash(&hash, &entry.sha1))
+        .unwrap_or(false);
+    if !verified {
+        let _ = fs::remove_file(&part);
+        return Err(ChunkError::Invalid(format!("文件校验失败: {}", path)));
+    }
+    fs::rename(&part, &dest).map_err(|e| ChunkError::Invalid(e.to_string()))?;
+    Ok(true)
+}
+
+/// 确认清单中的文件全部到齐，结束会话并返回暂存目录与总大小
+pub fn finalize(temp_root: &Path, transfer_id: &str) -> Result<(PathBuf, u64), String> {
+    let offer = load_offer(temp_root, transfer_id)?;
+    let staging = staging_dir(temp_root, transfer_id);
+    for file in &offer.files {
+        let dest = staging.join(safe_relative_path(&file.path)?);
+        let complete = fs::metadata(&dest)
+            .map(|meta| meta.len() == file.size)
+            .unwrap_or(false);
+        if !complete {
+            return Err(format!("仍有文件未接收完成: {}", file.path));
+        }
+    }
+    let _ = fs::remove_file(offer_path(temp_root, transfer_id));
+    Ok((staging, offer_size(&offer)))
+}
+
+pub fn offer_size(offer: &DeltaOffer) -> u64 {
+    offer.files.iter().map(|file| file.size).sum()
+}
+
+/// 仍需传输的字节数
+pub fn remaining_bytes(offer: &DeltaOffer, needed: &[DeltaNeededFile]) -> u64 {
+    needed
+        .iter()
+        .filter_map(|needed_file| {
+            offer
+                .files
+                .iter()
+                .find(|file| file.path == needed_file.path)
+                .map(|file| file.size.saturating_sub(needed_file.offset))
+        })
+        .sum()
+}
+
+/// 放弃会话时清理暂存内容
+pub fn discard(temp_root: &Path, transfer_id: &str) {
+    let _ = fs::remove_dir_all(staging_dir(temp_root, transfer_id));
+    let _ = fs::remove_file(offer_path(temp_root, transfer_id));
+}
+
+/// 只清理尚未完成的会话；已 finalize、等待用户确认部署的暂存目录保留。返回是否清理
+pub fn discard_pending(temp_root: &Path, transfer_id: &str) -> bool {
+    if !offer_path(temp_root, transfer_id).is_file() {
+        return false;
+    }
+    discard(temp_root, transfer_id);
+    true
+}
+
+/// 启动时清理超过 max_age 未更新的 .delta 暂存目录与 .delta.json 会话
+pub fn discard_stale(temp_root: &Path, max_age: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(temp_root) else {
+        return 0;
+    };
+    let mut stale_ids = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(transfer_id) = name
+            .strip_suffix(".delta.json")
+            .or_else(|| name.strip_suffix(".delta"))
+        else {
+            continue;
+        };
+        let stale = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= max_age);
+        if stale
+            && is_valid_transfer_id(transfer_id)
+            && !stale_ids.iter().any(|id| id == transfer_id)
+        {
+            stale_ids.push(transfer_id.to_string());
+        }
+    }
+    for transfer_id in &stale_ids {
+        discard(temp_root, transfer_id);
+    }
+    stale_ids.len()
+}
+
+/// 发送方的对端信息、签名身份与握手得到的加密会话
+pub struct DeltaPeer<'a> {
+    pub client: &'a reqwest::Client,
+    pub base_url: String,
+    pub identity: &'a DeviceIdentity,
+    pub device_id: &'a str,
+    pub device_name: &'a str,
+    pub username: &'a str,
+    pub session_id: &'a str,
+    pub session: &'a SecureSession,
+}
+
+pub enum DeltaSendOutcome {
+    /// 对端不支持增量协议
+    Unsupported,
+    Sent {
+        total: u64,
+        transferred: u64,
+    },
+}
+
+impl DeltaPeer<'_> {
+    fn post(&self, path: &str, offer: &DeltaOffer) -> Result<reqwest::RequestBuilder, String> {
+        let (timestamp, signature) =
+            TrustStore::sign_request(self.identity, self.device_id, "POST", path)?;
+        Ok(self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("X-Device-Id", self.device_id)
+            .header(
+                "X-Device-Name",
+                urlencoding::encode(self.device_name).into_owned(),
+            )
+            .header(
+                "X-Username",
+                urlencoding::encode(self.username).into_owned(),
+            )
+            .header("X-Transfer-Id", offer.transfer_id.clone())
+            .header("X-Transfer-Type", offer.transfer_type.clone())
+            .header(
+                "X-Transfer-Name",
+                urlencoding::encode(&offer.name).into_owned(),
+            )
+            .header("X-Session-Id", self.session_id)
+            .header("X-Signature", signature)
+            .header("X-Timestamp", timestamp))
+    }
+
+    async fn send_chunk(
+        &self,
+        offer: &DeltaOffer,
+        path: &str,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> Result<Result<(), u64>, String> {
+        let response = self
+            .post("/api/transfer/chunk", offer)?
+            .header("X-File-Path", urlencoding::encode(path).into_owned())
+            .header("X-Offset", offset.to_string())
+            .body(self.session.seal(
+                &bytes,
+                &format!("chunk:{}:{}:{}", offer.transfer_id, path, offset),
+            )?)
+            .send()
+            .await
+            .map_err(|e| format!("发送分块失败: {}", e))?;
+        match response.status() {
+            status if status.is_success() => Ok(Ok(())),
+            reqwest::StatusCode::CONFLICT => {
+                let text = response.text().await.unwrap_or_default();
+                text.trim()
+                    .parse::<u64>()
+                    .map(Err)
+                    .map_err(|_| "对方返回了无效的续传偏移".to_string())
+            }
+            status => {
+                let text = response.text().await.unwrap_or_default();
+                Err(format!("目标设备返回状态码 {}: {}", status, text))
+            }
+        }
+    }
+
+    /// 通知对方放弃会话并清理暂存；网络中断时多半送不到，对方保留已接收部分供续传
+    async fn abort(&self, offer: &DeltaOffer) {
+        let aad = format!("abort:{}", offer.transfer_id);
+        let (Ok(request), Ok(body)) = (
+            self.post("/api/transfer/abort", offer),
+            self.session.seal(&[], &aad),
+        ) else {
+            return;
+        };
+        let _ = request.body(body).send().await;
+    }
+
+    /// 提交清单、上传缺失文件并通知对方完成；出错时返回 (消息, 记录状态)
+    pub async fn send<F>(
+        &self,
+        offer: &DeltaOffer,
+        source_root: &Path,
+        on_progress: F,
+    ) -> Result<DeltaSendOutcome, (String, &'static str)>
+    where
+        F: FnMut(u64, u64, String),
+    {
+        let failed = |message: String| (message, "failed");
+        let sealed_offer = serde_json::to_vec(offer)
+            .map_err(|e| e.to_string())
+            .and_then(|plain| {
+                self.session
+                    .seal(&plain, &format!("offer:{}", offer.transfer_id))
+            })
+            .map_err(failed)?;
+        let response = self
+            .post("/api/transfer/offer", offer)
+            .map_err(failed)?
+            .body(sealed_offer)
+            .send()
+            .await
+            .map_err(|e| failed(format!("发送失败: {}", e)))?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED => {
+                return Ok(DeltaSendOutcome::Unsupported)
+            }
+            reqwest::StatusCode::FORBIDDEN => {
+                return Err(("目标设备返回状态码 403 Forbidden".to_string(), "rejected"))
+            }
+            status if !status.is_success() => {
+                return Err(failed(format!("目标设备返回状态码 {}", status)))
+            }
+            _ => {}
+        }
+        let needed = response
+            .json::<DeltaOfferResponse>()
+            .await
+            .map_err(|e| failed(format!("解析对方响应失败: {}", e)))?
+            .needed;
+
+        match self.upload(offer, &needed, source_root, on_progress).await {
+            Ok((total, transferred)) => Ok(DeltaSendOutcome::Sent { total, transferred }),
+            Err(error) => {
+                self.abort(offer).await;
+                Err(error)
+            }
+        }
+    }
+
+    /// 按对方索取的清单上传文件并提交完成请求，返回 (总字节, 实际传输字节)
+    async fn upload<F>(
+        &self,
+        offer: &DeltaOffer,
+        needed: &[DeltaNeededFile],
+        source_root: &Path,
+        mut on_progress: F,
+    ) -> Result<(u64, u64), (String, &'static str)>
+    where
+        F: FnMut(u64, u64, String),
+    {
+        let failed = |message: String| (message, "failed");
+        let sizes: HashMap<&str, u64> = offer
+            .files
+            .iter()
+            .map(|file| (file.path.as_str(), file.size))
+            .collect();
+        let total = offer_size(offer);
+        let mut current = total - remaining_bytes(offer, needed);
+        let mut transferred = 0_u64;
+        on_progress(current, total, "对方已有的文件将被跳过".to_string());
+
+        for needed_file in needed {
+            let size = sizes.get(needed_file.path.as_str()).copied().unwrap_or(0);
+            let source = source_root.join(safe_relative_path(&needed_file.path).map_err(failed)?);
+            let mut file = tokio::fs::File::open(&source)
+                .await
+                .map_err(|e| failed(format!("读取源文件失败: {}", e)))?;
+            let mut offset = needed_file.offset;
+            let mut attempts = 0;
+
+            while offset < size {
+                file.seek(std::io::SeekFrom::Start(offset))
+                    .await
+                    .map_err(|e| failed(format!("读取源文件失败: {}", e)))?;
+                let mut buffer = vec![0_u8; CHUNK_SIZE.min((size - offset) as usize)];
+                file.read_exact(&mut buffer)
+                    .await
+                    .map_err(|e| failed(format!("读取源文件失败: {}", e)))?;
+                let length = buffer.len() as u64;
+
+                match self
+                    .send_chunk(offer, &needed_file.path, offset, buffer)
+                    .await
+                {
+                    Ok(Ok(())) => {
+                        offset += length;
+                        current += length;
+                        transferred += length;
+                        attempts = 0;
+                        on_progress(current, total, format!("正在发送 {}", needed_file.path));
+                    }
+                    // 对方记录的偏移与本地不一致时以对方为准，重新对齐后继续
+                    Ok(Err(remote_offset)) if remote_offset <= size && attempts < CHUNK_RETRIES => {
+                        current = (current + remote_offset).saturating_sub(offset);
+                        offset = remote_offset;
+                        attempts += 1;
+                    }
+                    Ok(Err(_)) => return Err(failed("续传偏移无法对齐".to_string())),
+                    Err(error) if attempts < CHUNK_RETRIES => {
+                        println!("[LAN Delta] 分块发送失败，准备重试: {}", error);
+                        attempts += 1;
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    Err(error) => return Err(failed(error)),
+                }
+            }
+        }
+
+        let complete = DeltaCompleteRequest {
+            transfer_id: offer.transfer_id.clone(),
+            content_sha256: secure_channel::digest_file_list(&offer.files),
+        };
+        let sealed_complete = serde_json::to_vec(&complete)
+            .map_err(|e| e.to_string())
+            .and_then(|plain| {
+                self.session
+                    .seal(&plain, &format!("complete:{}", offer.transfer_id))
+            })
+            .map_err(failed)?;
+        let response = self
+            .post("/api/transfer/complete", offer)
+            .map_err(failed)?
+            .body(sealed_complete)
+            .send()
+            .await
+            .map_err(|e| failed(format!("发送失败: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(failed(format!(
+                "目标设备未能完成接收 ({}): {}",
+                status, text
+            )));
+        }
+        Ok((total, transferred))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pilauncher-delta-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn sha1_of(bytes: &[u8]) -> String {
+        use sha1::{Digest, Sha1};
+        hex::encode(Sha1::digest(bytes))
+    }
+
+    fn offer(files: Vec<DeltaFileEntry>) -> DeltaOffer {
+        DeltaOffer {
+            transfer_id: "t-1".to_string(),
+            transfer_type: "instance".to_string(),
+            name: "Pack".to_string(),
+            directories: vec!["Pack".to_string(), "Pack/mods".to_string()],
+            files,
+        }
+    }
+
+    #[test]
+    fn relative_paths_reject_traversal() {
+        assert!(safe_relative_path("Pack/mods/a.jar").is_ok());
+        assert!(safe_relative_path("../evil").is_err());
+        assert!(safe_relative_path("Pack/../../evil").is_err());
+        assert!(safe_relative_path("/etc/passwd").is_err());
+        assert!(safe_relative_path("C:/Windows").is_err());
+        assert!(safe_relative_path("Pack//a").is_err());
+    }
+
+    #[test]
+    fn received_paths_must_stay_inside_the_temp_root() {
+        let root = temp_root();
+        let temp = root.join("temp_transfers");
+        fs::create_dir_all(staging_dir(&temp, "t-1")).unwrap();
+        fs::write(temp.join("t-2.zip"), b"zip").unwrap();
+        fs::write(root.join("outside.zip"), b"zip").unwrap();
+
+        let staged = staging_dir(&temp, "t-1");
+        assert!(resolve_received_path(&temp, staged.to_str().unwrap()).is_ok());
+        assert!(resolve_received_path(&temp, temp.join("t-2.zip").to_str().unwrap()).is_ok());
+        let escaped = temp.join("..").join("outside.zip");
+        assert!(resolve_received_path(&temp, escaped.to_str().unwrap()).is_err());
+        assert!(resolve_received_path(&temp, temp.to_str().unwrap()).is_err());
+        assert!(resolve_received_path(&temp, temp.join("missing.zip").to_str().unwrap()).is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn offer_reuses_local_files_and_resumes_partial_ones() {
+        let root = temp_root();
+        let local = root.join("local.jar");
+        fs::write(&local, b"shared mod").unwrap();
+        let index = HashMap::from([(sha1_of(b"shared mod"), local)]);
+
+        let offer = offer(vec![
+            DeltaFileEntry {
+                path: "Pack/mods/shared.jar".to_string(),
+                size: 10,
+                sha1: sha1_of(b"shared mod"),
+            },
+            DeltaFileEntry {
+                path: "Pack/options.txt".to_string(),
+                size: 11,
+                sha1: sha1_of(b"hello world"),
+            },
+        ]);
+        let staging = staging_dir(&root, "t-1");
+        fs::create_dir_all(staging.join("Pack")).unwrap();
+        fs::write(staging.join("Pack/options.txt.part"), b"hello").unwrap();
+
+        let needed = prepare_offer(&root, &offer, &index, None).unwrap();
+        assert_eq!(
+            needed,
+            vec![DeltaNeededFile {
+                path: "Pack/options.txt".to_string(),
+                offset: 5,
+            }]
+        );
+        assert!(staging.join("Pack/mods/shared.jar").exists());
+
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/options.txt", 0, b"hello"),
+            Err(ChunkError::OffsetMismatch(5))
+        ));
+        assert!(finalize(&root, "t-1").is_err());
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/options.txt", 5, b" world"),
+            Ok(true)
+        ));
+        let (dir, total) = finalize(&root, "t-1").unwrap();
+        assert_eq!(total, 21);
+        assert_eq!(
+            fs::read(dir.join("Pack/options.txt")).unwrap(),
+            b"hello world"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn corrupted_chunk_is_discarded() {
+        let root = temp_root();
+        let offer = offer(vec![DeltaFileEntry {
+            path: "Pack/a.txt".to_string(),
+            size: 3,
+            sha1: sha1_of(b"abc"),
+        }]);
+        prepare_offer(&root, &offer, &HashMap::new(), None).unwrap();
+
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/a.txt", 0, b"abd"),
+            Err(ChunkError::Invalid(_))
+        ));
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/a.txt", 0, b"abc"),
+            Ok(true)
+        ));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn staged_file_with_same_size_but_other_content_is_requested_again() {
+        let root = temp_root();
+        let offer = offer(vec![DeltaFileEntry {
+            path: "Pack/a.txt".to_string(),
+            size: 3,
+            sha1: sha1_of(b"abc"),
+        }]);
+        let staging = staging_dir(&root, "t-1");
+        fs::create_dir_all(staging.join("Pack")).unwrap();
+        fs::write(staging.join("Pack/a.txt"), b"abd").unwrap();
+
+        let needed = prepare_offer(&root, &offer, &HashMap::new(), None).unwrap();
+        assert_eq!(
+            needed,
+            vec![DeltaNeededFile {
+                path: "Pack/a.txt".to_string(),
+                offset: 0,
+            }]
+        );
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/a.txt", 0, b"abc"),
+            Ok(true)
+        ));
+        assert!(prepare_offer(&root, &offer, &HashMap::new(), None)
+            .unwrap()
+            .is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn abandoned_sessions_are_discarded_but_finalized_ones_are_kept() {
+        let root = temp_root();
+        let offer = offer(vec![DeltaFileEntry {
+            path: "Pack/a.txt".to_string(),
+            size: 3,
+            sha1: sha1_of(b"abc"),
+        }]);
+        prepare_offer(&root, &offer, &HashMap::new(), None).unwrap();
+        assert!(discard_pending(&root, "t-1"));
+        assert!(!staging_dir(&root, "t-1").exists());
+        assert!(!offer_path(&root, "t-1").exists());
+
+        prepare_offer(&root, &offer, &HashMap::new(), None).unwrap();
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/a.txt", 0, b"abc"),
+            Ok(true)
+        ));
+        finalize(&root, "t-1").unwrap();
+        assert!(!discard_pending(&root, "t-1"));
+        assert!(staging_dir(&root, "t-1").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn only_stale_sessions_are_removed_at_startup() {
+        let root = temp_root();
+        fs::create_dir_all(staging_dir(&root, "t-1")).unwrap();
+        fs::write(offer_path(&root, "t-1"), b"{}").unwrap();
+        fs::write(root.join("t-2-pull.zip"), b"zip").unwrap();
+
+        assert_eq!(discard_stale(&root, STALE_SESSION_AGE), 0);
+        assert!(staging_dir(&root, "t-1").exists());
+
+        assert_eq!(discard_stale(&root, Duration::ZERO), 1);
+        assert!(!staging_dir(&root, "t-1").exists());
+        assert!(!offer_path(&root, "t-1").exists());
+        assert!(root.join("t-2-pull.zip").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn empty_files_are_staged_without_chunks() {
+        let root = temp_root();
+        let offer = offer(vec![
+            DeltaFileEntry {
+                path: "Pack/mods/empty.jar".to_string(),
+                size: 0,
+                sha1: sha1_of(b""),
+            },
+            DeltaFileEntry {
+                path: "Pack/a.txt".to_string(),
+                size: 3,
+                sha1: sha1_of(b"abc"),
+            },
+        ]);
+
+        let needed = prepare_offer(&root, &offer, &HashMap::new(), None).unwrap();
+        assert_eq!(
+            needed,
+            vec![DeltaNeededFile {
+                path: "Pack/a.txt".to_string(),
+                offset: 0,
+            }]
+        );
+        assert_eq!(remaining_bytes(&offer, &needed), 3);
+        // 续传时已存在的空文件不会再次出现在待接收列表中
+        assert_eq!(
+            prepare_offer(&root, &offer, &HashMap::new(), None).unwrap(),
+            needed
+        );
+
+        assert!(matches!(
+            write_chunk(&root, "t-1", "Pack/a.txt", 0, b"abc"),
+            Ok(true)
+        ));
+        let (dir, total) = finalize(&root, "t-1").unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(fs::read(dir.join("Pack/mods/empty.jar")).unwrap(), b"");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn file_list_uses_forward_slashes_relative_to_parent() {
+        let root = temp_root();
+        let instance = root.join("Pack");
+        fs::create_dir_all(instance.join("mods")).unwrap();
+        fs::write(instance.join("mods").join("a.jar"), b"abc").unwrap();
+
+        let (directories, files) = build_file_list(&instance, |_, _, _| {}).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(directories.contains(&"Pack/mods".to_string()));
+        assert_eq!(
+            files,
+            vec![DeltaFileEntry {
+                path: "Pack/mods/a.jar".to_string(),
+                size: 3,
+                sha1: sha1_of(b"abc"),
+            }]
+        );
+    }
+}
@@ -1,33 +1,47 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        Query, State, WebSocketUpgrade,
+        ConnectInfo, DefaultBodyLimit, OriginalUri, Query, State, WebSocketUpgrade,
     },
     http::{header, HeaderMap, Method, Request, StatusCode},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{get, post},
-    Json, Router,
+    Extension, Json, Router,
 };
-use futures::StreamExt;
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::io::AsyncWriteExt;
 use tokio::sync::{broadcast, oneshot};
 use tower_http::cors::{Any, CorsLayer};
 
-use crate::domain::lan::{DeviceInitInfo, TrustRequest};
+use crate::domain::lan::{
+    DeltaCompleteRequest, DeltaOffer, DeltaOfferResponse, DeviceInitInfo, KeyHandover,
+    LanPullRequest, TransferProgressEvent, TrustRequest,
+};
 use crate::services::config_service::ConfigService;
 use crate::services::db_service::AppDatabase;
+use crate::services::lan::catalog;
+use crate::services::lan::delta_transfer::{self, ChunkError};
+use crate::services::lan::key_rotation::{self, HandoverOutcome};
+use crate::services::lan::pairing;
+use crate::services::lan::secure_channel::{
+    self, EphemeralKey, FrameSealer, HandshakeRequest, HandshakeResponse, SecureSession,
+};
+use crate::services::lan::skin_server::{self, SkinServerState};
 use crate::services::lan::transfer_records::{
     emit_transfer_progress, upsert_transfer_record, TransferRecordUpsert,
 };
-use crate::services::lan::trust_store::TrustStore;
+use crate::services::lan::transfer_service;
+use crate::services::lan::trust_store::{self, TrustStore};
+
+/// 局域网 HTTP 服务的默认端口，mDNS 广播与皮肤服务地址都依赖它
+pub const DEFAULT_HTTP_PORT: u16 = 9999;
 
 const PNG_SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
 
@@ -54,6 +68,17 @@ pub struct SharedLanState {
     pub ws_sender: broadcast::Sender<String>,
     pub current_device_info: Mutex<DeviceInitInfo>,
     pub local_bg_path: Mutex<String>,
+    pub skin_server: SkinServerState,
+    /// 增量接收进度：transfer_id -> (已接收字节, 总字节)
+    pub delta_progress: Mutex<HashMap<String, (u64, u64)>>,
+    secure_sessions: Mutex<HashMap<String, PeerSession>>,
+}
+
+/// 握手建立的加密会话，只能由发起握手的设备使用
+struct PeerSession {
+    device_id: String,
+    session: Arc<SecureSession>,
+    created_at: Instant,
 }
 
 impl SharedLanState {
@@ -73,10 +98,47 @@ impl SharedLanState {
                 instance_name: None,
                 instance_id: None,
                 bg_url: "/device/bg".to_string(),
+                public_key: String::new(),
             }),
             local_bg_path: Mutex::new(String::new()),
+            skin_server: SkinServerState::default(),
+            delta_progress: Mutex::new(HashMap::new()),
+            secure_sessions: Mutex::new(HashMap::new()),
         }
     }
+
+    fn store_secure_session(&self, device_id: &str, session: SecureSession) -> String {
+        let ttl = Duration::from_secs(secure_channel::SESSION_TTL_SECS);
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let mut sessions = self.secure_sessions.lock().unwrap();
+        sessions.retain(|_, entry| entry.created_at.elapsed() < ttl);
+        sessions.insert(
+            session_id.clone(),
+            PeerSession {
+                device_id: device_id.to_string(),
+                session: Arc::new(session),
+                created_at: Instant::now(),
+            },
+        );
+        session_id
+    }
+
+    /// 查找属于该设备且未过期的会话
+    fn secure_session(&self, session_id: &str, device_id: &str) -> Option<Arc<SecureSession>> {
+        let ttl = Duration::from_secs(secure_channel::SESSION_TTL_SECS);
+        let sessions = self.secure_sessions.lock().unwrap();
+        sessions
+            .get(session_id)
+            .filter(|entry| entry.created_at.elapsed() < ttl)
+            .filter(|entry| entry.device_id == device_id)
+            .map(|entry| entry.session.clone())
+    }
+
+    /// 吊销或轮换密钥后立即作废该设备已建立的加密会话
+    pub fn drop_secure_sessions(&self, device_id: &str) {
+        let mut sessions = self.secure_sessions.lock().unwrap();
+        sessions.retain(|_, entry| entry.device_id != device_id);
+    }
 }
 
 pub struct AxumAppState {
@@ -84,12 +146,76 @@ pub struct AxumAppState {
     pub shared_state: Arc<SharedLanState>,
 }
 
+/// 通过签名校验的已配对设备，由中间件放入请求扩展
+#[derive(Clone)]
+pub struct AuthenticatedPeer {
+    pub device_id: String,
+    pub trust_level: String,
+    pub public_key_b64: String,
+}
+
 async fn auth_middleware(
     State(state): State<Arc<AxumAppState>>,
     headers: HeaderMap,
-    request: Request<axum::body::Body>,
+    mut request: Request<axum::body::Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    let (method, path) = signed_method_and_path(&request);
+    let peer = verify_peer_request(&state, &headers, &method, &path).await?;
+    // 必须是已信任设备，好友设备需要先升级为信任设备
+    if peer.trust_level != "trusted" {
+        println!(
+            "[API 鉴权] 失败：设备 {} 的信任等级为 {}, 拒绝传输",
+            peer.device_id, peer.trust_level
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+    record_peer_use(&state, &peer, &method, &path).await;
+    request.extensions_mut().insert(peer);
+    Ok(next.run(request).await)
+}
+
+async fn record_peer_use(state: &AxumAppState, peer: &AuthenticatedPeer, method: &str, path: &str) {
+    let db = state.tauri_app.state::<AppDatabase>();
+    let detail = format!("{} {}", method, path);
+    if let Err(e) = TrustStore::record_use(&db.pool, &peer.device_id, &detail).await {
+        println!("[API 鉴权] 写入审计日志失败: {}", e);
+    }
+}
+
+/// 信任设备与好友设备均可访问（加密握手）；共享目录与拉取只对信任设备开放，走 auth_middleware
+async fn peer_auth_middleware(
+    State(state): State<Arc<AxumAppState>>,
+    headers: HeaderMap,
+    mut request: Request<axum::body::Body>,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let (method, path) = signed_method_and_path(&request);
+    let peer = verify_peer_request(&state, &headers, &method, &path).await?;
+    if peer.trust_level != "trusted" && peer.trust_level != "friend" {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    record_peer_use(&state, &peer, &method, &path).await;
+    request.extensions_mut().insert(peer);
+    Ok(next.run(request).await)
+}
+
+/// 嵌套路由看到的是去掉 /api 前缀的路径，签名基于完整路径
+fn signed_method_and_path(request: &Request<axum::body::Body>) -> (String, String) {
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    (request.method().as_str().to_string(), path)
+}
+
+pub(crate) async fn verify_peer_request(
+    state: &AxumAppState,
+    headers: &HeaderMap,
+    method: &str,
+    path: &str,
+) -> Result<AuthenticatedPeer, StatusCode> {
     use sqlx::Row;
     use base64::{engine::general_purpose, Engine as _};
     use ed25519_dalek::{Signature, VerifyingKey, Verifier};
@@ -119,7 +245,7 @@ async fn auth_middleware(
     // 3. 从数据库查询公钥与信任状态
     let db = state.tauri_app.state::<AppDatabase>();
     let device_record = sqlx::query(
-        "SELECT public_key_b64, trust_level FROM trusted_devices WHERE device_uuid = $1 LIMIT 1"
+        "SELECT public_key_b64, trust_level, expires_at FROM trusted_devices WHERE device_uuid = $1 LIMIT 1"
     )
     .bind(sender_device_id)
     .fetch_optional(&db.pool)
@@ -129,11 +255,12 @@ async fn auth_middleware(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    let (public_key_b64, trust_level) = match device_record {
+    let (public_key_b64, trust_level, expires_at) = match device_record {
         Some(row) => {
             let pk: String = row.try_get("public_key_b64").map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
             let tl: String = row.try_get("trust_level").map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-            (pk, tl)
+            let expires_at: Option<i64> = row.try_get("expires_at").unwrap_or_default();
+            (pk, tl, expires_at)
         }
         None => {
             println!("[API 鉴权] 失败：设备 {} 未在信任数据库中", sender_device_id);
@@ -141,15 +268,7 @@ async fn auth_middleware(
         }
     };
 
-    // 必须是已信任设备，好友设备需要先升级为信任设备
-    if trust_level != "trusted" {
-        println!("[API 鉴权] 失败：设备 {} 的信任等级为 {}, 拒绝传输", sender_device_id, trust_level);
-        return Err(StatusCode::FORBIDDEN);
-    }
-
     // 4. 重构签名消息: "{method}:{path}:{timestamp}:{sender_device_id}"
-    let method = request.method().as_str().to_string();
-    let path = request.uri().path().to_string();
     let message = format!("{}:{}:{}:{}", method, path, timestamp_str, sender_device_id);
 
     // 5. 验证 Ed25519 签名
@@ -169,11 +288,30 @@ async fn auth_middleware(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
-    Ok(next.run(request).await)
+    // 6. 吊销与到期在每次请求时从数据库读取，修改后立即生效
+    let denied_reason = if trust_level == "revoked" {
+        Some(("revoked", "吊销"))
+    } else if trust_store::is_expired(expires_at) {
+        Some(("expired", "过期"))
+    } else {
+        None
+    };
+    if let Some((reason, label)) = denied_reason {
+        println!("[API 鉴权] 失败：设备 {} 的信任已{}", sender_device_id, label);
+        let _ = TrustStore::record_denied(&db.pool, sender_device_id, reason).await;
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(AuthenticatedPeer {
+        device_id: sender_device_id.to_string(),
+        trust_level,
+        public_key_b64,
+    })
 }
 
 async fn request_trust(
     State(state): State<Arc<AxumAppState>>,
+    ConnectInfo(remote): ConnectInfo<std::net::SocketAddr>,
     headers: HeaderMap,
     Json(payload): Json<TrustRequest>,
 ) -> Result<Json<TrustRequest>, StatusCode> {
@@ -221,7 +359,7 @@ async fn request_trust(
     // 但是，如果设备已经在我们的数据库中且公钥相同，则可以自动允许（这在 resolve 之后或再次连接时发生）
     let db = state.tauri_app.state::<AppDatabase>();
     let existing_trust = sqlx::query(
-        "SELECT trust_level, public_key_b64 FROM trusted_devices WHERE device_uuid = $1 LIMIT 1"
+        "SELECT trust_level, public_key_b64, expires_at FROM trusted_devices WHERE device_uuid = $1 LIMIT 1"
     )
     .bind(&payload.device_id)
     .fetch_optional(&db.pool)
@@ -238,35 +376,36 @@ async fn request_trust(
         use sqlx::Row;
         let db_pk: String = row.try_get("public_key_b64").unwrap_or_default();
         let db_tl: String = row.try_get("trust_level").unwrap_or_default();
-        if db_pk == payload.public_key && (db_tl == "trusted" || (db_tl == "friend" && request_kind != "trusted")) {
+        let db_expires_at: Option<i64> = row.try_get("expires_at").unwrap_or_default();
+        // 已吊销的设备不再弹出配对确认，需由本机主动重新配对
+        if db_tl == "revoked" {
+            let _ = TrustStore::record_denied(&db.pool, &payload.device_id, "revoked").await;
+            return Err(StatusCode::FORBIDDEN);
+        }
+        if db_pk == payload.public_key
+            && !trust_store::is_expired(db_expires_at)
+            && (db_tl == "trusted" || (db_tl == "friend" && request_kind != "trusted"))
+        {
             is_already_trusted = true;
         }
     }
 
+    // 对方的服务端口与本机一致，来源端口是临时端口
+    let peer_address = pairing::format_address(&remote.ip().to_string(), DEFAULT_HTTP_PORT);
+
     if is_already_trusted {
-        let target_username = payload.username.clone().unwrap_or_default();
-        let relationship_result = if request_kind == "trusted" {
-            TrustStore::add_trusted_device(
-                &db.pool,
-                payload.device_id.clone(),
-                payload.device_name.clone(),
-                payload.user_uuid.clone(),
-                target_username,
-                payload.public_key.clone(),
-            )
-            .await
-        } else {
-            TrustStore::add_friend_device(
-                &db.pool,
-                payload.device_id.clone(),
-                payload.device_name.clone(),
-                payload.user_uuid.clone(),
-                target_username,
-                payload.public_key.clone(),
-            )
-            .await
-        };
-        relationship_result.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        TrustStore::refresh_device_relationship(
+            &db.pool,
+            &payload.device_id,
+            &payload.device_name,
+            &payload.user_uuid,
+            payload.username.as_deref().unwrap_or_default(),
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let _ =
+            TrustStore::remember_address_if_missing(&db.pool, &payload.device_id, &peer_address)
+                .await;
         let _ = state.tauri_app.emit("trust_list_updated", json!({}));
 
         let base_path = ConfigService::get_base_path(&state.tauri_app)
@@ -319,20 +458,32 @@ async fn request_trust(
     );
 
     match rx.await {
-        Ok(Some(my_identity)) => Ok(Json(my_identity)),
+        Ok(Some(my_identity)) => {
+            let _ = TrustStore::remember_address_if_missing(
+                &db.pool,
+                &payload.device_id,
+                &peer_address,
+            )
+            .await;
+            Ok(Json(my_identity))
+        }
         _ => Err(StatusCode::FORBIDDEN),
     }
 }
 
 async fn get_device_init(State(state): State<Arc<AxumAppState>>) -> Json<DeviceInitInfo> {
-    Json(
-        state
-            .shared_state
-            .current_device_info
-            .lock()
-            .unwrap()
-            .clone(),
-    )
+    let mut info = state
+        .shared_state
+        .current_device_info
+        .lock()
+        .unwrap()
+        .clone();
+    if let Ok(Some(base_path)) = ConfigService::get_base_path(&state.tauri_app) {
+        info.public_key =
+            TrustStore::get_or_create_identity(&PathBuf::from(base_path).join("config"))
+                .public_key_b64;
+    }
+    Json(info)
 }
 
 async fn get_device_bg(State(state): State<Arc<AxumAppState>>) -> impl IntoResponse {
@@ -419,228 +570,844 @@ async fn get_device_avatar(
     (StatusCode::OK, headers, bytes)
 }
 
+#[derive(Deserialize)]
+struct WsQuery {
+    session: Option<String>,
+}
+
+/// 推送内容经会话密钥加密并按序号绑定 aad，连接前需先完成握手；
+/// 连接请求本身也要签名，会话只能由建立它的设备使用
 async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AxumAppState>>,
-) -> impl IntoResponse {
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    Query(query): Query<WsQuery>,
+) -> Response {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let peer = match verify_peer_request(&state, &headers, "GET", uri.path()).await {
+        Ok(peer) if peer.trust_level == "trusted" || peer.trust_level == "friend" => peer,
+        Ok(_) => return StatusCode::FORBIDDEN.into_response(),
+        Err(status) => return status.into_response(),
+    };
+    let Some(session) = query.session.as_deref().and_then(|session_id| {
+        state
+            .shared_state
+            .secure_session(session_id, &peer.device_id)
+    }) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    record_peer_use(&state, &peer, "GET", uri.path()).await;
+    let device_id = peer.device_id;
     let mut rx = state.shared_state.ws_sender.subscribe();
     ws.on_upgrade(move |mut socket: WebSocket| async move {
+        let db = state.tauri_app.state::<AppDatabase>();
+        let mut index = 0_u64;
         while let Ok(message) = rx.recv().await {
-            if socket.send(Message::Text(message.into())).await.is_err() {
+            // 设备被吊销或信任到期后停止推送
+            if !TrustStore::is_access_active(&db.pool, &device_id)
+                .await
+                .unwrap_or(false)
+            {
+                break;
+            }
+            let Ok(sealed) = session.seal(message.as_bytes(), &format!("ws:{}", index)) else {
+                break;
+            };
+            index += 1;
+            let text = general_purpose::STANDARD.encode(sealed);
+            if socket.send(Message::Text(text.into())).await.is_err() {
                 break;
             }
         }
     })
 }
-async fn receive_transfer(
+fn instances_dir(app: &AppHandle) -> Option<PathBuf> {
+    ConfigService::get_base_path(app)
+        .ok()
+        .flatten()
+        .map(|base_path| PathBuf::from(base_path).join("instances"))
+}
+
+async fn list_shared_instances(State(state): State<Arc<AxumAppState>>) -> Response {
+    let Some(instances_dir) = instances_dir(&state.tauri_app) else {
+        return Json(Vec::<serde_json::Value>::new()).into_response();
+    };
+    match tokio::task::spawn_blocking(move || catalog::build_catalog(&instances_dir)).await {
+        Ok(instances) => Json(instances).into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// 拉取流结束后写入的传输记录
+struct PullCompletion {
+    transfer_id: String,
+    sender_device_id: String,
+    sender_device: String,
+    remote_device_id: String,
+    remote_device_name: String,
+    remote_username: String,
+    transfer_type: String,
+    name: String,
+    size: i64,
+}
+
+impl PullCompletion {
+    async fn record(self, app: &AppHandle) {
+        let db = app.state::<AppDatabase>();
+        let _ = upsert_transfer_record(
+            app,
+            &db,
+            TransferRecordUpsert {
+                transfer_id: &self.transfer_id,
+                direction: "outgoing",
+                sender_device_id: &self.sender_device_id,
+                sender_device: &self.sender_device,
+                receiver_device_id: &self.remote_device_id,
+                receiver_device: &self.remote_device_name,
+                remote_device_id: &self.remote_device_id,
+                remote_device_name: &self.remote_device_name,
+                remote_username: &self.remote_username,
+                transfer_type: &self.transfer_type,
+                name: &self.name,
+                size: self.size,
+                status: "received".to_string(),
+                error_message: None,
+                mark_completed: true,
+            },
+        )
+        .await;
+    }
+}
+
+/// 拉取用的临时压缩包随响应流一同释放：读完、出错或对方中途断开都会删除
+struct TempZipGuard(PathBuf);
+
+impl Drop for TempZipGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// 信任设备主动拉取：打包并以响应体流式返回压缩包
+async fn pull_transfer(
     State(state): State<Arc<AxumAppState>>,
+    Extension(peer): Extension<AuthenticatedPeer>,
     headers: HeaderMap,
-    request: Request<axum::body::Body>,
-) -> impl IntoResponse {
-    let transfer_id = headers
-        .get("X-Transfer-Id")
-        .and_then(|value| value.to_str().ok())
-        .map(|value| value.to_string())
-        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    Json(pull): Json<LanPullRequest>,
+) -> Response {
+    // 传输 ID 由本机生成并随响应返回，避免对方指定 ID 覆盖已有的记录或临时文件
+    let transfer_id = uuid::Uuid::new_v4().to_string();
 
-    let is_valid_transfer_id = !transfer_id.is_empty()
-        && transfer_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
-    if !is_valid_transfer_id {
-        return (
-            StatusCode::BAD_REQUEST,
-            "Invalid X-Transfer-Id header".to_string(),
-        );
-    }
+    let Some(instances_dir) = instances_dir(&state.tauri_app) else {
+        return (StatusCode::NOT_FOUND, "Base path is not configured").into_response();
+    };
+    let (src_dir, item_name) = match catalog::resolve_transfer_source(
+        &instances_dir,
+        &pull.transfer_type,
+        &pull.target_id,
+        pull.save_name.as_deref(),
+    ) {
+        Ok(source) => source,
+        Err(error) => return (StatusCode::NOT_FOUND, error).into_response(),
+    };
+    let session = match peer_session(&state, &headers, &peer) {
+        Ok(session) => session,
+        Err(error) => return error.into_response(),
+    };
 
-    let transfer_type = headers
-        .get("X-Transfer-Type")
-        .and_then(|value| value.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string();
-    let transfer_name = decode_header_value(&headers, "X-Transfer-Name", "Unnamed");
-    let from_device_name = decode_header_value(&headers, "X-Device-Name", "LAN Device");
-    let from_device_id = headers
-        .get("X-Device-Id")
-        .and_then(|value| value.to_str().ok())
-        .unwrap_or_default()
-        .to_string();
-    let from_username = decode_header_value(&headers, "X-Username", "");
-    let total = headers
-        .get(header::CONTENT_LENGTH)
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .unwrap_or(0);
+    let remote_device_name = decode_header_value(&headers, "X-Device-Name", "LAN Device");
+    let remote_username = decode_header_value(&headers, "X-Username", "");
+    let current_info = state
+        .shared_state
+        .current_device_info
+        .lock()
+        .unwrap()
+        .clone();
+    let progress = {
+        let transfer_id = transfer_id.clone();
+        let remote_device_id = peer.device_id.clone();
+        let remote_device_name = remote_device_name.clone();
+        let remote_username = remote_username.clone();
+        let transfer_type = pull.transfer_type.clone();
+        let item_name = item_name.clone();
+        move |status: &str, stage: &str, current: u64, total: u64, message: String| {
+            TransferProgressEvent {
+                transfer_id: transfer_id.clone(),
+                direction: "outgoing".to_string(),
+                remote_device_id: remote_device_id.clone(),
+                remote_device_name: remote_device_name.clone(),
+                remote_username: remote_username.clone(),
+                transfer_type: transfer_type.clone(),
+                name: item_name.clone(),
+                status: status.to_string(),
+                stage: stage.to_string(),
+                current,
+                total,
+                message,
+            }
+        }
+    };
+    let record = |status: &str, size: i64, error_message: Option<String>, mark_completed: bool| {
+        TransferRecordUpsert {
+            transfer_id: &transfer_id,
+            direction: "outgoing",
+            sender_device_id: &current_info.device_id,
+            sender_device: &current_info.device_name,
+            receiver_device_id: &peer.device_id,
+            receiver_device: &remote_device_name,
+            remote_device_id: &peer.device_id,
+            remote_device_name: &remote_device_name,
+            remote_username: &remote_username,
+            transfer_type: &pull.transfer_type,
+            name: &item_name,
+            size,
+            status: status.to_string(),
+            error_message,
+            mark_completed,
+        }
+    };
+
+    let db = state.tauri_app.state::<AppDatabase>();
+    let _ = upsert_transfer_record(&state.tauri_app, &db, record("packing", 0, None, false)).await;
+    emit_transfer_progress(
+        &state.tauri_app,
+        &progress(
+            "packing",
+            "PACKING",
+            0,
+            0,
+            "正在为拉取请求打包内容".to_string(),
+        ),
+    );
 
-    let app_dir = state
+    let temp_dir = state
         .tauri_app
         .path()
         .app_data_dir()
-        .unwrap_or_else(|_| PathBuf::from("."));
-    let temp_dir = app_dir.join("temp_transfers");
-    if let Err(error) = fs::create_dir_all(&temp_dir) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Create temp dir failed: {}", error),
-        );
-    }
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("temp_transfers");
+    let temp_zip = temp_dir.join(format!("{}-pull.zip", transfer_id));
+    let packed = {
+        let temp_zip = temp_zip.clone();
+        tokio::task::spawn_blocking(move || {
+            fs::create_dir_all(&temp_dir).map_err(|e| format!("创建传输缓存目录失败: {}", e))?;
+            transfer_service::zip_dir(&src_dir, &temp_zip)?;
+            let size = fs::metadata(&temp_zip)
+                .map(|metadata| metadata.len())
+                .map_err(|e| format!("读取临时压缩包信息失败: {}", e))?;
+            Ok::<_, String>((size, secure_channel::content_digest(&temp_zip)?))
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()))
+    };
+    let (total_size, content_sha256) = match packed {
+        Ok(packed) => packed,
+        Err(error) => {
+            let message = format!("打包失败: {}", error);
+            let _ = fs::remove_file(&temp_zip);
+            let _ = upsert_transfer_record(
+                &state.tauri_app,
+                &db,
+                record("failed", 0, Some(message.clone()), true),
+            )
+            .await;
+            emit_transfer_progress(
+                &state.tauri_app,
+                &progress("failed", "FAILED", 0, 0, message.clone()),
+            );
+            return (StatusCode::INTERNAL_SERVER_ERROR, message).into_response();
+        }
+    };
 
-    let temp_path = temp_dir.join(format!("{}.zip", transfer_id));
-    let mut file = match tokio::fs::File::create(&temp_path).await {
+    let file = match tokio::fs::File::open(&temp_zip).await {
         Ok(file) => file,
         Err(error) => {
+            let _ = fs::remove_file(&temp_zip);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Create temp file failed: {}", error),
-            );
+                format!("Open temp file failed: {}", error),
+            )
+                .into_response();
         }
     };
-
-    emit_transfer_progress(
+    let _ = upsert_transfer_record(
         &state.tauri_app,
-        &crate::domain::lan::TransferProgressEvent {
-            transfer_id: transfer_id.clone(),
-            direction: "incoming".to_string(),
-            remote_device_id: from_device_id.clone(),
-            remote_device_name: from_device_name.clone(),
-            remote_username: from_username.clone(),
-            transfer_type: transfer_type.clone(),
-            name: transfer_name.clone(),
-            status: "receiving".to_string(),
-            stage: "RECEIVING".to_string(),
-            current: 0,
-            total,
-            message: "正在接收来自局域网设备的传输内容".to_string(),
-        },
-    );
+        &db,
+        record("sending", total_size as i64, None, false),
+    )
+    .await;
 
-    let mut received = 0_u64;
-    let mut body = request.into_body().into_data_stream();
-    while let Some(next_chunk) = body.next().await {
-        let chunk = match next_chunk {
-            Ok(chunk) => chunk,
-            Err(error) => {
-                let _ = tokio::fs::remove_file(&temp_path).await;
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Read body failed: {}", error),
+    // 读到文件末尾即视为发送完成，随后记录历史并清理临时压缩包
+    let app_for_stream = state.tauri_app.clone();
+    let finished_record = PullCompletion {
+        transfer_id: transfer_id.clone(),
+        sender_device_id: current_info.device_id.clone(),
+        sender_device: current_info.device_name.clone(),
+        remote_device_id: peer.device_id.clone(),
+        remote_device_name: remote_device_name.clone(),
+        remote_username: remote_username.clone(),
+        transfer_type: pull.transfer_type.clone(),
+        name: item_name.clone(),
+        size: total_size as i64,
+    };
+    // 内容按帧加密，摘要同时写入每帧的 aad，篡改摘要头会导致解密失败
+    let sealer = FrameSealer::new(session, &format!("{}:{}", transfer_id, content_sha256));
+    // 文件句柄排在 guard 之前，流被丢弃时先关闭文件再删除
+    let body_stream = futures::stream::try_unfold(
+        (
+            Some(file),
+            0_u64,
+            Some(finished_record),
+            sealer,
+            Some(TempZipGuard(temp_zip)),
+        ),
+        move |(file, sent, mut finished_record, mut sealer, temp_zip)| {
+            let app = app_for_stream.clone();
+            let progress = progress.clone();
+            async move {
+                use tokio::io::AsyncReadExt;
+                // 文件读完并发出结束帧后结束响应
+                let Some(mut file) = file else {
+                    return Ok(None);
+                };
+                let mut buffer = vec![0_u8; secure_channel::FRAME_SIZE];
+                let read = file.read(&mut buffer).await?;
+                if read == 0 {
+                    drop(file);
+                    drop(temp_zip);
+                    if let Some(finished) = finished_record.take() {
+                        emit_transfer_progress(
+                            &app,
+                            &progress(
+                                "received",
+                                "RECEIVED",
+                                sent,
+                                sent,
+                                "对方已拉取传输内容".to_string(),
+                            ),
+                        );
+                        finished.record(&app).await;
+                    }
+                    let last = sealer.seal(&[], true).map_err(std::io::Error::other)?;
+                    return Ok::<_, std::io::Error>(Some((last, (None, sent, None, sealer, None))));
+                }
+                buffer.truncate(read);
+                let frame = sealer.seal(&buffer, false).map_err(std::io::Error::other)?;
+                let current = sent + read as u64;
+                emit_transfer_progress(
+                    &app,
+                    &progress(
+                        "sending",
+                        "SENDING",
+                        current,
+                        total_size,
+                        "正在通过局域网发送拉取内容".to_string(),
+                    ),
                 );
+                Ok(Some((
+                    frame,
+                    (Some(file), current, finished_record, sealer, temp_zip),
+                )))
             }
+        },
+    );
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        "application/octet-stream".parse().unwrap(),
+    );
+    response_headers.insert("X-Content-Size", total_size.into());
+    if let Ok(value) = transfer_id.parse() {
+        response_headers.insert("X-Transfer-Id", value);
+    }
+    if let Ok(value) = content_sha256.parse() {
+        response_headers.insert("X-Content-Sha256", value);
+    }
+    if let Ok(value) = urlencoding::encode(&item_name).parse() {
+        response_headers.insert("X-Transfer-Name", value);
+    }
+    (
+        StatusCode::OK,
+        response_headers,
+        axum::body::Body::from_stream(body_stream),
+    )
+        .into_response()
+}
+
+/// 已配对设备轮换密钥：交接声明由新旧密钥共同签名，校验通过后更新记录的公钥
+async fn rotate_peer_key(
+    State(state): State<Arc<AxumAppState>>,
+    Json(handover): Json<KeyHandover>,
+) -> StatusCode {
+    if let Err(e) = key_rotation::verify_handover(&handover) {
+        println!(
+            "[密钥轮换] 拒绝设备 {} 的交接声明: {}",
+            handover.device_id, e
+        );
+        return StatusCode::UNAUTHORIZED;
+    }
+    let db = state.tauri_app.state::<AppDatabase>();
+    match key_rotation::apply_handover(&db.pool, &handover).await {
+        Ok(HandoverOutcome::Applied) => {
+            state.shared_state.drop_secure_sessions(&handover.device_id);
+            let _ = state.tauri_app.emit("trust_list_updated", json!({}));
+            StatusCode::OK
+        }
+        Ok(HandoverOutcome::AlreadyApplied) => StatusCode::OK,
+        Ok(HandoverOutcome::UnknownDevice) => StatusCode::NOT_FOUND,
+        Ok(HandoverOutcome::Revoked) => StatusCode::FORBIDDEN,
+        Ok(HandoverOutcome::KeyMismatch) => StatusCode::CONFLICT,
+        Err(e) => {
+            println!("[密钥轮换] 更新设备公钥失败: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// 建立加密会话：用对方的临时公钥与配对时记录的公钥派生会话密钥
+async fn secure_handshake(
+    State(state): State<Arc<AxumAppState>>,
+    Extension(peer): Extension<AuthenticatedPeer>,
+    Json(request): Json<HandshakeRequest>,
+) -> Response {
+    let Ok(Some(base_path)) = ConfigService::get_base_path(&state.tauri_app) else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Base path is not configured",
+        )
+            .into_response();
+    };
+    let identity = TrustStore::get_or_create_identity(&PathBuf::from(base_path).join("config"));
+    let ephemeral = EphemeralKey::generate();
+    let session = match SecureSession::respond(
+        &identity,
+        &peer.public_key_b64,
+        &ephemeral,
+        &request.ephemeral_public,
+    ) {
+        Ok(session) => session,
+        Err(error) => return (StatusCode::BAD_REQUEST, error).into_response(),
+    };
+    let session_id = state
+        .shared_state
+        .store_secure_session(&peer.device_id, session);
+    Json(HandshakeResponse {
+        session_id,
+        ephemeral_public: ephemeral.public_b64(),
+    })
+    .into_response()
+}
+
+fn peer_session(
+    state: &AxumAppState,
+    headers: &HeaderMap,
+    peer: &AuthenticatedPeer,
+) -> Result<Arc<SecureSession>, (StatusCode, String)> {
+    headers
+        .get("X-Session-Id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|session_id| {
+            state
+                .shared_state
+                .secure_session(session_id, &peer.device_id)
+        })
+        .ok_or((
+            StatusCode::UNAUTHORIZED,
+            "Secure session required".to_string(),
+        ))
+}
+
+/// 解密请求体，aad 需与发送方加密时一致
+fn open_sealed_body(
+    state: &AxumAppState,
+    headers: &HeaderMap,
+    peer: &AuthenticatedPeer,
+    body: &[u8],
+    aad: &str,
+) -> Result<Vec<u8>, (StatusCode, String)> {
+    peer_session(state, headers, peer)?
+        .open(body, aad)
+        .map_err(|error| (StatusCode::BAD_REQUEST, error))
+}
+
+fn transfer_temp_root(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("temp_transfers")
+}
+
+/// 传输请求头中携带的发送方与内容信息
+struct IncomingTransfer {
+    transfer_id: String,
+    transfer_type: String,
+    name: String,
+    device_id: String,
+    device_name: String,
+    username: String,
+}
+
+impl IncomingTransfer {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let text = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
         };
+        Self {
+            transfer_id: text("X-Transfer-Id"),
+            transfer_type: Some(text("X-Transfer-Type"))
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| "unknown".to_string()),
+            name: decode_header_value(headers, "X-Transfer-Name", "Unnamed"),
+            device_id: text("X-Device-Id"),
+            device_name: decode_header_value(headers, "X-Device-Name", "LAN Device"),
+            username: decode_header_value(headers, "X-Username", ""),
+        }
+    }
 
-        if let Err(error) = file.write_all(&chunk).await {
-            let _ = tokio::fs::remove_file(&temp_path).await;
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Write temp file failed: {}", error),
-            );
+    fn progress(
+        &self,
+        status: &str,
+        stage: &str,
+        current: u64,
+        total: u64,
+        message: &str,
+    ) -> TransferProgressEvent {
+        TransferProgressEvent {
+            transfer_id: self.transfer_id.clone(),
+            direction: "incoming".to_string(),
+            remote_device_id: self.device_id.clone(),
+            remote_device_name: self.device_name.clone(),
+            remote_username: self.username.clone(),
+            transfer_type: self.transfer_type.clone(),
+            name: self.name.clone(),
+            status: status.to_string(),
+            stage: stage.to_string(),
+            current,
+            total,
+            message: message.to_string(),
         }
+    }
 
-        received += chunk.len() as u64;
-        emit_transfer_progress(
+    async fn record(&self, state: &AxumAppState, status: &str, size: u64) {
+        let current_info = state
+            .shared_state
+            .current_device_info
+            .lock()
+            .unwrap()
+            .clone();
+        let db = state.tauri_app.state::<AppDatabase>();
+        let _ = upsert_transfer_record(
             &state.tauri_app,
-            &crate::domain::lan::TransferProgressEvent {
-                transfer_id: transfer_id.clone(),
-                direction: "incoming".to_string(),
-                remote_device_id: from_device_id.clone(),
-                remote_device_name: from_device_name.clone(),
-                remote_username: from_username.clone(),
-                transfer_type: transfer_type.clone(),
-                name: transfer_name.clone(),
-                status: "receiving".to_string(),
-                stage: "RECEIVING".to_string(),
-                current: received,
-                total,
-                message: "正在接收压缩包数据".to_string(),
+            &db,
+            TransferRecordUpsert {
+                transfer_id: &self.transfer_id,
+                direction: "incoming",
+                sender_device_id: &self.device_id,
+                sender_device: &self.device_name,
+                receiver_device_id: &current_info.device_id,
+                receiver_device: &current_info.device_name,
+                remote_device_id: &self.device_id,
+                remote_device_name: &self.device_name,
+                remote_username: &self.username,
+                transfer_type: &self.transfer_type,
+                name: &self.name,
+                size: size as i64,
+                status: status.to_string(),
+                error_message: None,
+                mark_completed: false,
             },
-        );
+        )
+        .await;
     }
+}
 
-    if let Err(error) = file.flush().await {
-        let _ = tokio::fs::remove_file(&temp_path).await;
+/// 增量传输第一步：登记清单，返回本机缺失的文件
+async fn delta_offer(
+    State(state): State<Arc<AxumAppState>>,
+    Extension(peer): Extension<AuthenticatedPeer>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let incoming = IncomingTransfer::from_headers(&headers);
+    let aad = format!("offer:{}", incoming.transfer_id);
+    let offer = match open_sealed_body(&state, &headers, &peer, &body, &aad).and_then(|plain| {
+        serde_json::from_slice::<DeltaOffer>(&plain)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+    }) {
+        Ok(offer) if offer.transfer_id == incoming.transfer_id => offer,
+        Ok(_) => return (StatusCode::BAD_REQUEST, "Transfer id mismatch").into_response(),
+        Err(error) => return error.into_response(),
+    };
+    let Some(instances_dir) = instances_dir(&state.tauri_app) else {
         return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Flush temp file failed: {}", error),
-        );
-    }
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Base path is not configured",
+        )
+            .into_response();
+    };
+    let temp_root = transfer_temp_root(&state.tauri_app);
+
+    let prepared = {
+        let offer = offer.clone();
+        tokio::task::spawn_blocking(move || {
+            let local_index = delta_transfer::local_hash_index(&instances_dir);
+            // 同名实例中路径与内容都相同的文件（如配置、资源包）也无需重传
+            let same_path_root = (offer.transfer_type == "instance").then_some(instances_dir);
+            delta_transfer::prepare_offer(
+                &temp_root,
+                &offer,
+                &local_index,
+                same_path_root.as_deref(),
+            )
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()))
+    };
+    let needed = match prepared {
+        Ok(needed) => needed,
+        Err(error) => {
+            let temp_root = transfer_temp_root(&state.tauri_app);
+            delta_transfer::discard(&temp_root, &offer.transfer_id);
+            return (StatusCode::BAD_REQUEST, error).into_response();
+        }
+    };
 
-    let current_info = state
+    let total = delta_transfer::offer_size(&offer);
+    let received = total - delta_transfer::remaining_bytes(&offer, &needed);
+    state
         .shared_state
-        .current_device_info
+        .delta_progress
         .lock()
         .unwrap()
-        .clone();
-    let db = state.tauri_app.state::<AppDatabase>();
-    let _ = upsert_transfer_record(
+        .insert(offer.transfer_id.clone(), (received, total));
+    incoming.record(&state, "receiving", total).await;
+    emit_transfer_progress(
         &state.tauri_app,
-        &db,
-        TransferRecordUpsert {
-            transfer_id: &transfer_id,
-            direction: "incoming",
-            sender_device_id: &from_device_id,
-            sender_device: &from_device_name,
-            receiver_device_id: &current_info.device_id,
-            receiver_device: &current_info.device_name,
-            remote_device_id: &from_device_id,
-            remote_device_name: &from_device_name,
-            remote_username: &from_username,
-            transfer_type: &transfer_type,
-            name: &transfer_name,
-            size: received as i64,
-            status: "received".to_string(),
-            error_message: None,
-            mark_completed: false,
-        },
-    )
+        &incoming.progress(
+            "receiving",
+            "RECEIVING",
+            received,
+            total,
+            "本地已有的文件已复用，正在接收缺失部分",
+        ),
+    );
+
+    Json(DeltaOfferResponse { needed }).into_response()
+}
+
+async fn delta_chunk(
+    State(state): State<Arc<AxumAppState>>,
+    Extension(peer): Extension<AuthenticatedPeer>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let incoming = IncomingTransfer::from_headers(&headers);
+    let path = decode_header_value(&headers, "X-File-Path", "");
+    let Some(offset) = headers
+        .get("X-Offset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+    else {
+        return (StatusCode::BAD_REQUEST, "Invalid X-Offset header").into_response();
+    };
+    if !delta_transfer::is_valid_transfer_id(&incoming.transfer_id) {
+        return (StatusCode::BAD_REQUEST, "Invalid X-Transfer-Id header").into_response();
+    }
+
+    // 路径与偏移写入 aad，密文无法被挪到别的文件或位置
+    let aad = format!("chunk:{}:{}:{}", incoming.transfer_id, path, offset);
+    let body = match open_sealed_body(&state, &headers, &peer, &body, &aad) {
+        Ok(body) => body,
+        Err(error) => return error.into_response(),
+    };
+
+    let temp_root = transfer_temp_root(&state.tauri_app);
+    let length = body.len() as u64;
+    let transfer_id = incoming.transfer_id.clone();
+    let written = tokio::task::spawn_blocking(move || {
+        delta_transfer::write_chunk(&temp_root, &transfer_id, &path, offset, &body)
+    })
     .await;
 
+    match written {
+        Ok(Ok(_)) => {
+            let progress = {
+                let mut progress = state.shared_state.delta_progress.lock().unwrap();
+                progress.get_mut(&incoming.transfer_id).map(|entry| {
+                    entry.0 = (entry.0 + length).min(entry.1);
+                    *entry
+                })
+            };
+            if let Some((received, total)) = progress {
+                emit_transfer_progress(
+                    &state.tauri_app,
+                    &incoming.progress(
+                        "receiving",
+                        "RECEIVING",
+                        received,
+                        total,
+                        "正在接收文件数据",
+                    ),
+                );
+            }
+            StatusCode::OK.into_response()
+        }
+        Ok(Err(ChunkError::OffsetMismatch(current))) => {
+            (StatusCode::CONFLICT, current.to_string()).into_response()
+        }
+        Ok(Err(ChunkError::Invalid(error))) => (StatusCode::BAD_REQUEST, error).into_response(),
+        Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+    }
+}
+
+/// 发送方出错放弃传输：清理未完成的暂存内容，并把接收记录标为失败
+async fn delta_abort(
+    State(state): State<Arc<AxumAppState>>,
+    Extension(peer): Extension<AuthenticatedPeer>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let incoming = IncomingTransfer::from_headers(&headers);
+    if !delta_transfer::is_valid_transfer_id(&incoming.transfer_id) {
+        return (StatusCode::BAD_REQUEST, "Invalid X-Transfer-Id header").into_response();
+    }
+    let aad = format!("abort:{}", incoming.transfer_id);
+    if let Err(error) = open_sealed_body(&state, &headers, &peer, &body, &aad) {
+        return error.into_response();
+    }
+
+    let temp_root = transfer_temp_root(&state.tauri_app);
+    let transfer_id = incoming.transfer_id.clone();
+    let discarded = tokio::task::spawn_blocking(move || {
+        delta_transfer::discard_pending(&temp_root, &transfer_id)
+    })
+    .await
+    .unwrap_or(false);
+    let progress = state
+        .shared_state
+        .delta_progress
+        .lock()
+        .unwrap()
+        .remove(&incoming.transfer_id);
+    if let (true, Some((received, total))) = (discarded, progress) {
+        incoming.record(&state, "failed", total).await;
+        emit_transfer_progress(
+            &state.tauri_app,
+            &incoming.progress("failed", "FAILED", received, total, "发送方已中止传输"),
+        );
+    }
+    StatusCode::OK.into_response()
+}
+
+/// 增量传输最后一步：确认文件齐全后，与拉取的压缩包一样等待用户确认部署
+async fn delta_complete(
+    State(state): State<Arc<AxumAppState>>,
+    Extension(peer): Extension<AuthenticatedPeer>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    let incoming = IncomingTransfer::from_headers(&headers);
+    let aad = format!("complete:{}", incoming.transfer_id);
+    let request = match open_sealed_body(&state, &headers, &peer, &body, &aad).and_then(|plain| {
+        serde_json::from_slice::<DeltaCompleteRequest>(&plain)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+    }) {
+        Ok(request) if request.transfer_id == incoming.transfer_id => request,
+        Ok(_) => return (StatusCode::BAD_REQUEST, "Transfer id mismatch").into_response(),
+        Err(error) => return error.into_response(),
+    };
+    let temp_root = transfer_temp_root(&state.tauri_app);
+    let (staging, total) = match delta_transfer::finalize(&temp_root, &request.transfer_id) {
+        Ok(result) => result,
+        Err(error) => return (StatusCode::CONFLICT, error).into_response(),
+    };
+    if let Err(error) = secure_channel::save_expected_digest(&staging, &request.content_sha256) {
+        return (StatusCode::BAD_REQUEST, error).into_response();
+    }
+    state
+        .shared_state
+        .delta_progress
+        .lock()
+        .unwrap()
+        .remove(&request.transfer_id);
+
+    incoming.record(&state, "received", total).await;
     emit_transfer_progress(
         &state.tauri_app,
-        &crate::domain::lan::TransferProgressEvent {
-            transfer_id: transfer_id.clone(),
-            direction: "incoming".to_string(),
-            remote_device_id: from_device_id.clone(),
-            remote_device_name: from_device_name.clone(),
-            remote_username: from_username.clone(),
-            transfer_type: transfer_type.clone(),
-            name: transfer_name.clone(),
-            status: "received".to_string(),
-            stage: "RECEIVED".to_string(),
-            current: received,
-            total: if total == 0 { received } else { total },
-            message: "文件已接收，等待用户确认".to_string(),
-        },
+        &incoming.progress(
+            "received",
+            "RECEIVED",
+            total,
+            total,
+            "文件已接收，等待用户确认",
+        ),
     );
-
     let _ = state.tauri_app.emit(
         "transfer_received",
         json!({
-            "id": transfer_id,
-            "type": transfer_type,
-            "name": transfer_name,
-            "from": from_device_name,
-            "fromDeviceId": from_device_id,
-            "fromUsername": from_username,
-            "tempPath": temp_path.to_string_lossy().to_string(),
+            "id": incoming.transfer_id,
+            "type": incoming.transfer_type,
+            "name": incoming.name,
+            "from": incoming.device_name,
+            "fromDeviceId": incoming.device_id,
+            "fromUsername": incoming.username,
+            "tempPath": staging.to_string_lossy().to_string(),
         }),
     );
 
-    (StatusCode::OK, "Received".to_string())
+    StatusCode::OK.into_response()
 }
 
 pub async fn start_http_server(app: AppHandle, shared_state: Arc<SharedLanState>, port: u16) {
+    // 上次运行遗留、长时间未续传的增量暂存
+    let temp_root = transfer_temp_root(&app);
+    if let Ok(removed) = tokio::task::spawn_blocking(move || {
+        delta_transfer::discard_stale(&temp_root, delta_transfer::STALE_SESSION_AGE)
+    })
+    .await
+    {
+        if removed > 0 {
+            println!(
+                "[PiLauncher] Removed {} stale LAN transfer sessions.",
+                removed
+            );
+        }
+    }
+
     let axum_state = Arc::new(AxumAppState {
         tauri_app: app.clone(),
         shared_state,
     });
 
     let secure_routes = Router::new()
-        .route("/instances/list", get(|| async { "[]" }))
-        .route("/transfer/receive", post(receive_transfer))
+        .route("/instances/list", get(list_shared_instances))
+        .route("/transfer/pull", post(pull_transfer))
+        .route("/transfer/offer", post(delta_offer))
+        .route(
+            "/transfer/chunk",
+            post(delta_chunk).layer(DefaultBodyLimit::max(delta_transfer::CHUNK_BODY_LIMIT)),
+        )
+        .route("/transfer/complete", post(delta_complete))
+        .route("/transfer/abort", post(delta_abort))
         .route_layer(middleware::from_fn_with_state(
             axum_state.clone(),
             auth_middleware,
         ));
 
+    let peer_routes = Router::new()
+        .route("/secure/handshake", post(secure_handshake))
+        .route_layer(middleware::from_fn_with_state(
+            axum_state.clone(),
+            peer_auth_middleware,
+        ));
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
@@ -648,16 +1415,26 @@ pub async fn start_http_server(app: AppHandle, shared_state: Arc<SharedLanState>
 
     let app_router = Router::new()
         .route("/trust/request", post(request_trust))
+        .route(key_rotation::ROTATE_PATH, post(rotate_peer_key))
         .route("/device/init", get(get_device_init))
         .route("/device/bg", get(get_device_bg))
         .route("/device/avatar", get(get_device_avatar))
         .route("/ws", get(ws_handler))
-        .nest("/api", secure_routes)
+        .nest("/api", secure_routes.merge(peer_routes))
+        .nest(
+            skin_server::API_PREFIX,
+            skin_server::router(axum_state.clone()),
+        )
         .layer(cors)
         .with_state(axum_state);
 
     let addr = format!("0.0.0.0:{}", port);
     println!("[PiLauncher] Axum Server listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app_router).await.unwrap();
+    axum::serve(
+        listener,
+        app_router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
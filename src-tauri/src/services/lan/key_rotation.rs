@@ -0,0 +1,409 @@
+// src-tauri/src/services/lan/key_rotation.rs
+//
+// 设备密钥轮换。
+// 新旧两把密钥共同签署交接声明：旧密钥为新公钥背书，新密钥证明持有。
+// 已配对设备验证后把记录的公钥替换为新公钥；暂时联系不上的设备记录在
+// lan_key_handover.json 中，之后重试交接，交接链按顺序发送以覆盖多次轮换。
+
+use crate::domain::lan::KeyHandover;
+use crate::services::lan::trust_store::{TrustStore, AUDIT_KEY_ROTATED};
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub const ROTATE_PATH: &str = "/trust/rotate";
+const PENDING_FILE: &str = "lan_key_handover.json";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HandoverOutcome {
+    Applied,
+    AlreadyApplied,
+    UnknownDevice,
+    Revoked,
+    KeyMismatch,
+}
+
+/// 向单个设备发送交接链的结果
+#[derive(Debug, PartialEq, Eq)]
+enum Delivery {
+    Applied,
+    /// 对方不认识本机、已吊销或记录的公钥对不上，重试也不会成功
+    Refused,
+    Unreachable,
+}
+
+impl Delivery {
+    fn from_status(status: reqwest::StatusCode) -> Self {
+        match status {
+            status if status.is_success() => Delivery::Applied,
+            reqwest::StatusCode::FORBIDDEN
+            | reqwest::StatusCode::NOT_FOUND
+            | reqwest::StatusCode::CONFLICT => Delivery::Refused,
+            _ => Delivery::Unreachable,
+        }
+    }
+}
+
+/// 尚未送达的交接链与待通知的设备
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PendingHandovers {
+    handovers: Vec<KeyHandover>,
+    pending_devices: Vec<String>,
+}
+
+fn handover_message(
+    device_id: &str,
+    old_public_key: &str,
+    new_public_key: &str,
+    timestamp: i64,
+) -> String {
+    format!(
+        "pilauncher-key-rotation:{}:{}:{}:{}",
+        device_id, old_public_key, new_public_key, timestamp
+    )
+}
+
+fn decode_key_bytes(value: &str) -> Result<[u8; 32], String> {
+    general_purpose::STANDARD
+        .decode(value)
+        .map_err(|e| format!("解码密钥失败: {}", e))?
+        .try_into()
+        .map_err(|_| "密钥长度错误".to_string())
+}
+
+fn verify_signature(public_key: &str, message: &str, signature: &str) -> Result<(), String> {
+    let verifying_key = VerifyingKey::from_bytes(&decode_key_bytes(public_key)?)
+        .map_err(|_| "公钥无效".to_string())?;
+    let signature_bytes = general_purpose::STANDARD
+        .decode(signature)
+        .map_err(|_| "签名格式无效".to_string())?;
+    let signature =
+        Signature::from_slice(&signature_bytes).map_err(|_| "签名格式无效".to_string())?;
+    verifying_key
+        .verify(message.as_bytes(), &signature)
+        .map_err(|_| "签名校验失败".to_string())
+}
+
+fn fingerprint(public_key: &str) -> String {
+    public_key.chars().take(8).collect()
+}
+
+pub fn create_handover(
+    device_id: &str,
+    old_private_key_b64: &str,
+    new_key: &SigningKey,
+) -> Result<KeyHandover, String> {
+    let old_key = SigningKey::from_bytes(&decode_key_bytes(old_private_key_b64)?);
+    let old_public_key = general_purpose::STANDARD.encode(old_key.verifying_key().to_bytes());
+    let new_public_key = general_purpose::STANDARD.encode(new_key.verifying_key().to_bytes());
+    let timestamp = chrono::Utc::now().timestamp();
+    let message = handover_message(device_id, &old_public_key, &new_public_key, timestamp);
+
+    Ok(KeyHandover {
+        device_id: device_id.to_string(),
+        old_signature: general_purpose::STANDARD
+            .encode(old_key.sign(message.as_bytes()).to_bytes()),
+        new_signature: general_purpose::STANDARD
+            .encode(new_key.sign(message.as_bytes()).to_bytes()),
+        old_public_key,
+        new_public_key,
+        timestamp,
+    })
+}
+
+pub fn verify_handover(handover: &KeyHandover) -> Result<(), String> {
+    if handover.old_public_key == handover.new_public_key {
+        return Err("新旧公钥相同".to_string());
+    }
+    let message = handover_message(
+        &handover.device_id,
+        &handover.old_public_key,
+        &handover.new_public_key,
+        handover.timestamp,
+    );
+    verify_signature(&handover.old_public_key, &message, &handover.old_signature)?;
+    verify_signature(&handover.new_public_key, &message, &handover.new_signature)
+}
+
+fn load_pending(config_dir: &Path) -> PendingHandovers {
+    fs::read_to_string(config_dir.join(PENDING_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_pending(config_dir: &Path, pending: &PendingHandovers) -> Result<(), String> {
+    let path = config_dir.join(PENDING_FILE);
+    if pending.pending_devices.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("清理密钥交接记录失败: {}", e))?;
+        }
+        return Ok(());
+    }
+    let data = serde_json::to_string_pretty(pending).map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| format!("保存密钥交接记录失败: {}", e))
+}
+
+fn queue_handover(
+    config_dir: &Path,
+    handover: KeyHandover,
+    device_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut pending = load_pending(config_dir);
+    pending.handovers.push(handover);
+    for device_id in device_ids {
+        if !pending.pending_devices.contains(&device_id) {
+            pending.pending_devices.push(device_id);
+        }
+    }
+    save_pending(config_dir, &pending)
+}
+
+/// 生成新密钥并替换本机身份；交接链先落盘再替换密钥，避免丢失交接声明
+pub async fn rotate_identity(
+    pool: &SqlitePool,
+    config_dir: &Path,
+    device_id: &str,
+) -> Result<KeyHandover, String> {
+    let identity = TrustStore::get_or_create_identity(config_dir);
+    let secret_bytes: [u8; 32] = rand::random();
+    let new_key = SigningKey::from_bytes(&secret_bytes);
+    let handover = create_handover(device_id, &identity.private_key_b64, &new_key)?;
+
+    let device_ids = sqlx::query_scalar::<_, String>(
+        "SELECT device_uuid FROM trusted_devices
+         WHERE trust_level IN ('trusted', 'friend')",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("查询已配对设备失败: {}", e))?;
+
+    queue_handover(config_dir, handover.clone(), device_ids)?;
+    TrustStore::replace_identity_keys(
+        config_dir,
+        &general_purpose::STANDARD.encode(new_key.to_bytes()),
+        &handover.new_public_key,
+    )?;
+    TrustStore::record_audit(
+        pool,
+        device_id,
+        AUDIT_KEY_ROTATED,
+        &format!(
+            "local {} -> {}",
+            fingerprint(&handover.old_public_key),
+            fingerprint(&handover.new_public_key)
+        ),
+    )
+    .await?;
+
+    Ok(handover)
+}
+
+/// 接收方：签名已通过 verify_handover 校验后，按记录的公钥决定是否替换
+pub async fn apply_handover(
+    pool: &SqlitePool,
+    handover: &KeyHandover,
+) -> Result<HandoverOutcome, String> {
+    let row = sqlx::query(
+        "SELECT public_key_b64, trust_level FROM trusted_devices WHERE device_uuid = $1 LIMIT 1",
+    )
+    .bind(&handover.device_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to query trusted devices: {}", e))?;
+
+    let Some(row) = row else {
+        return Ok(HandoverOutcome::UnknownDevice);
+    };
+    let stored_key: String = row.try_get("public_key_b64").unwrap_or_default();
+    let trust_level: String = row.try_get("trust_level").unwrap_or_default();
+    if trust_level == "revoked" {
+        return Ok(HandoverOutcome::Revoked);
+    }
+    if stored_key == handover.new_public_key {
+        return Ok(HandoverOutcome::AlreadyApplied);
+    }
+    if stored_key != handover.old_public_key {
+        return Ok(HandoverOutcome::KeyMismatch);
+    }
+
+    sqlx::query(
+        "UPDATE trusted_devices
+         SET public_key_b64 = $2
+         WHERE device_uuid = $1 AND public_key_b64 = $3",
+    )
+    .bind(&handover.device_id)
+    .bind(&handover.new_public_key)
+    .bind(&handover.old_public_key)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to update device public key: {}", e))?;
+    TrustStore::record_audit(
+        pool,
+        &handover.device_id,
+        AUDIT_KEY_ROTATED,
+        &format!(
+            "{} -> {}",
+            fingerprint(&handover.old_public_key),
+            fingerprint(&handover.new_public_key)
+        ),
+    )
+    .await?;
+
+    Ok(HandoverOutcome::Applied)
+}
+
+/// 按顺序发送整条交接链，以最后一条的结果判断对方是否已换用新公钥；
+/// 链中较早的声明可能因对方已换过更新的公钥而返回 409，不影响结果
+async fn deliver_to(
+    client: &reqwest::Client,
+    address: &str,
+    handovers: &[KeyHandover],
+) -> Delivery {
+    let url = format!("http://{}{}", address, ROTATE_PATH);
+    let mut delivery = Delivery::Unreachable;
+    for handover in handovers {
+        match client.post(&url).json(handover).send().await {
+            Ok(response) => delivery = Delivery::from_status(response.status()),
+            Err(_) => return Delivery::Unreachable,
+        }
+    }
+    delivery
+}
+
+/// 向待通知的设备发送交接链，返回 (已送达, 仍待送达)；
+/// 明确拒绝交接或已被删除的设备不再重试
+pub async fn deliver_pending(
+    pool: &SqlitePool,
+    config_dir: &Path,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let mut pending = load_pending(config_dir);
+    if pending.pending_devices.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let mut targets = Vec::with_capacity(pending.pending_devices.len());
+    for device_id in &pending.pending_devices {
+        let address = sqlx::query_scalar::<_, String>(
+            "SELECT address FROM trusted_devices WHERE device_uuid = $1 LIMIT 1",
+        )
+        .bind(device_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("查询设备地址失败: {}", e))?;
+        // 记录已删除的设备无需再通知
+        if let Some(address) = address {
+            targets.push((device_id.clone(), address));
+        }
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(6))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let handovers = &pending.handovers;
+    let results = futures::future::join_all(targets.iter().map(|(_, address)| {
+        let client = &client;
+        async move {
+            if address.is_empty() {
+                Delivery::Unreachable
+            } else {
+                deliver_to(client, address, handovers).await
+            }
+        }
+    }))
+    .await;
+
+    let mut delivered = Vec::new();
+    let mut remaining = Vec::new();
+    for ((device_id, _), delivery) in targets.into_iter().zip(results) {
+        match delivery {
+            Delivery::Applied => delivered.push(device_id),
+            Delivery::Unreachable => remaining.push(device_id),
+            Delivery::Refused => {
+                println!("[密钥轮换] 设备 {} 拒绝了交接声明，不再重试", device_id);
+            }
+        }
+    }
+
+    pending.pending_devices = remaining.clone();
+    if pending.pending_devices.is_empty() {
+        pending.handovers.clear();
+    }
+    save_pending(config_dir, &pending)?;
+
+    Ok((delivered, remaining))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn handover_requires_both_signatures() {
+        let old_key = signing_key(1);
+        let old_private = general_purpose::STANDARD.encode(old_key.to_bytes());
+        let handover = create_handover("dev-1", &old_private, &signing_key(2)).unwrap();
+        assert!(verify_handover(&handover).is_ok());
+
+        let mut forged = handover.clone();
+        forged.new_public_key =
+            general_purpose::STANDARD.encode(signing_key(3).verifying_key().to_bytes());
+        assert!(verify_handover(&forged).is_err());
+
+        let mut other_device = handover.clone();
+        other_device.device_id = "dev-2".to_string();
+        assert!(verify_handover(&other_device).is_err());
+
+        let mut swapped = handover;
+        std::mem::swap(&mut swapped.old_signature, &mut swapped.new_signature);
+        assert!(verify_handover(&swapped).is_err());
+    }
+
+    #[test]
+    fn refused_handovers_are_not_retried() {
+        assert_eq!(
+            Delivery::from_status(reqwest::StatusCode::OK),
+            Delivery::Applied
+        );
+        for status in [
+            reqwest::StatusCode::FORBIDDEN,
+            reqwest::StatusCode::NOT_FOUND,
+            reqwest::StatusCode::CONFLICT,
+        ] {
+            assert_eq!(Delivery::from_status(status), Delivery::Refused);
+        }
+        assert_eq!(
+            Delivery::from_status(reqwest::StatusCode::SERVICE_UNAVAILABLE),
+            Delivery::Unreachable
+        );
+    }
+
+    #[test]
+    fn pending_handovers_accumulate_and_clear() {
+        let dir =
+            std::env::temp_dir().join(format!("pilauncher-rotation-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let old_private = general_purpose::STANDARD.encode(signing_key(1).to_bytes());
+        let first = create_handover("dev-1", &old_private, &signing_key(2)).unwrap();
+
+        queue_handover(&dir, first.clone(), vec!["a".into(), "b".into()]).unwrap();
+        queue_handover(&dir, first, vec!["b".into(), "c".into()]).unwrap();
+        let pending = load_pending(&dir);
+        assert_eq!(pending.handovers.len(), 2);
+        assert_eq!(pending.pending_devices, vec!["a", "b", "c"]);
+
+        save_pending(&dir, &PendingHandovers::default()).unwrap();
+        assert!(!dir.join(PENDING_FILE).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
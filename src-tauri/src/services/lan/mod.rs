@@ -1,5 +1,12 @@
+pub mod catalog;
+pub mod delta_transfer;
 pub mod http_api;
+pub mod key_rotation;
 pub mod mdns_service;
+pub mod pairing;
+pub mod secure_channel;
+pub mod skin_server;
 pub mod transfer_records;
 pub mod transfer_service;
 pub mod trust_store;
+pub mod udp_discovery;
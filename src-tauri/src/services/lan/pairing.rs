@@ -0,0 +1,239 @@
+// src-tauri/src/services/lan/pairing.rs
+//
+// 手动添加设备。
+// mDNS 在校园网、企业网或跨 VLAN 时经常不可用，用户可以直接输入 IP:端口、
+// 对方展示的配对码（IPv4 + 端口的 Base32 编码），或扫描包含设备 ID 与公钥的配对二维码。
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::services::lan::http_api::DEFAULT_HTTP_PORT;
+
+const PAIRING_URI_PREFIX: &str = "pilauncher://pair?";
+// Crockford Base32：去掉了容易混淆的 I、L、O、U
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_LEN: usize = 10;
+
+/// 解析出的待添加设备；来自二维码时额外带有预期的设备 ID 与公钥
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerTarget {
+    pub host: String,
+    pub port: u16,
+    pub device_id: Option<String>,
+    pub public_key: Option<String>,
+}
+
+impl PeerTarget {
+    fn new(host: String, port: u16) -> Self {
+        Self {
+            host,
+            port,
+            device_id: None,
+            public_key: None,
+        }
+    }
+
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// 拼接 host:port，裸 IPv6 地址需要加方括号
+pub fn format_address(host: &str, port: u16) -> String {
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{}]:{}", ip, port),
+        _ => format!("{}:{}", host, port),
+    }
+}
+
+/// 6 字节（IPv4 + 端口）编码为 10 位配对码，形如 "3ZK1M-08H2T"
+pub fn encode_pairing_code(ip: Ipv4Addr, port: u16) -> String {
+    let mut value = 0_u64;
+    for byte in ip.octets().into_iter().chain(port.to_be_bytes()) {
+        value = (value << 8) | byte as u64;
+    }
+    value <<= CODE_LEN * 5 - 48;
+    let code: String = (0..CODE_LEN)
+        .rev()
+        .map(|index| CODE_ALPHABET[((value >> (index * 5)) & 0x1f) as usize] as char)
+        .collect();
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+pub fn decode_pairing_code(code: &str) -> Result<(Ipv4Addr, u16), String> {
+    let invalid = || "配对码无效".to_string();
+    let mut value = 0_u64;
+    let mut length = 0;
+    for c in code.chars().filter(|c| *c != '-' && !c.is_whitespace()) {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        };
+        let digit = CODE_ALPHABET
+            .iter()
+            .position(|item| *item as char == c)
+            .ok_or_else(invalid)?;
+        value = (value << 5) | digit as u64;
+        length += 1;
+    }
+    if length != CODE_LEN {
+        return Err(invalid());
+    }
+    value >>= CODE_LEN * 5 - 48;
+    let bytes = value.to_be_bytes();
+    let port = u16::from_be_bytes([bytes[6], bytes[7]]);
+    if port == 0 {
+        return Err(invalid());
+    }
+    Ok((Ipv4Addr::new(bytes[2], bytes[3], bytes[4], bytes[5]), port))
+}
+
+/// 配对二维码内容，公钥用于在配对时确认对方身份
+pub fn pairing_uri(ip: Ipv4Addr, port: u16, device_id: &str, public_key: &str) -> String {
+    format!(
+        "{}addr={}:{}&id={}&key={}",
+        PAIRING_URI_PREFIX,
+        ip,
+        port,
+        urlencoding::encode(device_id),
+        urlencoding::encode(public_key)
+    )
+}
+
+fn parse_host_port(input: &str) -> Result<PeerTarget, String> {
+    if let Ok(addr) = input.parse::<SocketAddr>() {
+        let host = match addr.ip() {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{}]", ip),
+        };
+        return Ok(PeerTarget::new(host, addr.port()));
+    }
+    if let Ok(ip) = input.parse::<IpAddr>() {
+        let host = match ip {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{}]", ip),
+        };
+        return Ok(PeerTarget::new(host, DEFAULT_HTTP_PORT));
+    }
+
+    // 主机名，可带端口
+    let (host, port) = match input.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse::<u16>()
+                .ok()
+                .filter(|port| *port != 0)
+                .ok_or_else(|| format!("端口无效: {}", port))?,
+        ),
+        None => (input, DEFAULT_HTTP_PORT),
+    };
+    let valid_host = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+    if !valid_host {
+        return Err(format!("无法识别的地址: {}", input));
+    }
+    Ok(PeerTarget::new(host.to_string(), port))
+}
+
+fn parse_pairing_uri(query: &str) -> Result<PeerTarget, String> {
+    let mut address = None;
+    let mut device_id = None;
+    let mut public_key = None;
+    for pair in query.split('&') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let value = urlencoding::decode(value)
+            .map_err(|_| "配对二维码内容无效".to_string())?
+            .into_owned();
+        match key {
+            "addr" => address = Some(value),
+            "id" => device_id = Some(value),
+            "key" => public_key = Some(value),
+            _ => {}
+        }
+    }
+    let mut target =
+        parse_host_port(&address.ok_or_else(|| "配对二维码缺少设备地址".to_string())?)?;
+    target.device_id = device_id.filter(|value| !value.is_empty());
+    target.public_key = public_key.filter(|value| !value.is_empty());
+    Ok(target)
+}
+
+/// 解析用户输入：配对二维码内容、IP[:端口]、主机名[:端口] 或配对码
+pub fn parse_peer_input(input: &str) -> Result<PeerTarget, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("请输入设备地址或配对码".to_string());
+    }
+    if let Some(query) = input.strip_prefix(PAIRING_URI_PREFIX) {
+        return parse_pairing_uri(query);
+    }
+    if input.contains('.') || input.contains(':') {
+        return parse_host_port(input);
+    }
+    let (ip, port) = decode_pairing_code(input)?;
+    Ok(PeerTarget::new(ip.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairing_code_round_trips_and_tolerates_typos() {
+        let ip = Ipv4Addr::new(10, 23, 4, 200);
+        let code = encode_pairing_code(ip, 9999);
+        assert_eq!(code.len(), CODE_LEN + 1);
+        assert_eq!(decode_pairing_code(&code).unwrap(), (ip, 9999));
+
+        let sloppy = code.to_lowercase().replace('-', " ").replace('0', "o");
+        assert_eq!(decode_pairing_code(&sloppy).unwrap(), (ip, 9999));
+        assert!(decode_pairing_code("ABCDE").is_err());
+        assert!(decode_pairing_code("ABCDE-FGHIU").is_err());
+    }
+
+    #[test]
+    fn peer_input_accepts_addresses_codes_and_qr_uris() {
+        assert_eq!(
+            parse_peer_input("192.168.1.20").unwrap().address(),
+            format!("192.168.1.20:{}", DEFAULT_HTTP_PORT)
+        );
+        assert_eq!(
+            parse_peer_input(" 192.168.1.20:8080 ").unwrap().address(),
+            "192.168.1.20:8080"
+        );
+        assert_eq!(
+            parse_peer_input("[fe80::1]:9999").unwrap().address(),
+            "[fe80::1]:9999"
+        );
+        assert_eq!(
+            parse_peer_input("desk-pc.local:9999").unwrap().address(),
+            "desk-pc.local:9999"
+        );
+        assert!(parse_peer_input("bad host:1").is_err());
+        assert!(parse_peer_input("host:0").is_err());
+
+        let code = encode_pairing_code(Ipv4Addr::new(172, 16, 0, 5), 9999);
+        assert_eq!(
+            parse_peer_input(&code).unwrap().address(),
+            "172.16.0.5:9999"
+        );
+
+        let uri = pairing_uri(Ipv4Addr::new(172, 16, 0, 5), 9999, "dev-1", "a+b/c=");
+        let target = parse_peer_input(&uri).unwrap();
+        assert_eq!(target.address(), "172.16.0.5:9999");
+        assert_eq!(target.device_id.as_deref(), Some("dev-1"));
+        assert_eq!(target.public_key.as_deref(), Some("a+b/c="));
+
+        assert_eq!(format_address("fe80::1", 9999), "[fe80::1]:9999");
+        assert_eq!(
+            parse_peer_input(&format_address("fe80::1", 9999))
+                .unwrap()
+                .port,
+            9999
+        );
+    }
+}
@@ -0,0 +1,558 @@
+// src-tauri/src/services/lan/secure_channel.rs
+//
+// 局域网安全通道。
+// 双方由设备身份（ed25519）转换出 X25519 静态密钥，再各自生成一次性临时密钥做三重 DH，
+// 经 HKDF-SHA256 派生会话密钥：只有配对时记录的两台设备能算出同一把密钥，且泄露长期私钥
+// 也无法解密过去的会话。传输内容用 XChaCha20-Poly1305 加密，流式内容按帧加密并标记末帧；
+// 另外对传输内容计算端到端摘要，接收方在写入实例目录前重新校验。
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use base64::{engine::general_purpose, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use hmac::{Hmac, KeyInit as _, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::domain::lan::{DeltaFileEntry, DeviceIdentity};
+use crate::domain::mod_manifest::compute_sha1;
+use crate::services::lan::trust_store::TrustStore;
+
+pub const HANDSHAKE_PATH: &str = "/api/secure/handshake";
+/// 会话有效期，超时后需要重新握手
+pub const SESSION_TTL_SECS: u64 = 30 * 60;
+/// 流式传输中单帧明文的最大长度
+pub const FRAME_SIZE: usize = 128 * 1024;
+
+const NONCE_LEN: usize = 24;
+const FRAME_HEADER_LEN: usize = 4;
+const LAST_FRAME_FLAG: u32 = 1 << 31;
+const SESSION_INFO: &[u8] = b"pilauncher-lan-session-v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeRequest {
+    pub ephemeral_public: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeResponse {
+    pub session_id: String,
+    pub ephemeral_public: String,
+}
+
+/// 本次握手使用的一次性 X25519 密钥
+pub struct EphemeralKey {
+    secret: [u8; 32],
+    public: MontgomeryPoint,
+}
+
+impl EphemeralKey {
+    pub fn generate() -> Self {
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        Self {
+            secret,
+            public: MontgomeryPoint::mul_base_clamped(secret),
+        }
+    }
+
+    pub fn public_b64(&self) -> String {
+        general_purpose::STANDARD.encode(self.public.as_bytes())
+    }
+}
+
+fn decode_key(value: &str, label: &str) -> Result<[u8; 32], String> {
+    general_purpose::STANDARD
+        .decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("{}格式错误", label))
+}
+
+fn static_secret(identity: &DeviceIdentity) -> Result<[u8; 32], String> {
+    let private = decode_key(&identity.private_key_b64, "本机私钥")?;
+    Ok(SigningKey::from_bytes(&private).to_scalar_bytes())
+}
+
+fn static_public(public_key_b64: &str) -> Result<MontgomeryPoint, String> {
+    let public = decode_key(public_key_b64, "对方公钥")?;
+    VerifyingKey::from_bytes(&public)
+        .map(|key| key.to_montgomery())
+        .map_err(|_| "对方公钥无效".to_string())
+}
+
+fn diffie_hellman(secret: [u8; 32], public: &MontgomeryPoint) -> Result<[u8; 32], String> {
+    let shared = public.mul_clamped(secret).to_bytes();
+    // 低阶点会得到全零结果，拒绝以免派生出可预测的密钥
+    if shared == [0u8; 32] {
+        return Err("密钥交换失败：对方提供的公钥无效".to_string());
+    }
+    Ok(shared)
+}
+
+fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8]) -> Result<[u8; 32], String> {
+    let failed = |_| "派生会话密钥失败".to_string();
+    let mut extract = Hmac::<Sha256>::new_from_slice(salt).map_err(failed)?;
+    extract.update(ikm);
+    let prk = extract.finalize().into_bytes();
+    let mut expand = Hmac::<Sha256>::new_from_slice(&prk).map_err(failed)?;
+    expand.update(info);
+    expand.update(&[1]);
+    Ok(expand.finalize().into_bytes().into())
+}
+
+pub struct SecureSession {
+    aead: XChaCha20Poly1305,
+}
+
+impl SecureSession {
+    /// 三重 DH：临时-临时、发起方临时-响应方静态、发起方静态-响应方临时
+    fn derive(
+        initiator_ephemeral: &MontgomeryPoint,
+        responder_ephemeral: &MontgomeryPoint,
+        shared: [[u8; 32]; 3],
+    ) -> Result<Self, String> {
+        let mut salt = Vec::with_capacity(64);
+        salt.extend_from_slice(initiator_ephemeral.as_bytes());
+        salt.extend_from_slice(responder_ephemeral.as_bytes());
+        let key = hkdf_sha256(&salt, &shared.concat(), SESSION_INFO)?;
+        Ok(Self {
+            aead: XChaCha20Poly1305::new(&key.into()),
+        })
+    }
+
+    /// 发起方（发送请求的一端）在收到对方临时公钥后派生会话
+    pub fn initiate(
+        identity: &DeviceIdentity,
+        peer_public_key_b64: &str,
+        ephemeral: &EphemeralKey,
+        peer_ephemeral_b64: &str,
+    ) -> Result<Self, String> {
+        let peer_static = static_public(peer_public_key_b64)?;
+        let peer_ephemeral = MontgomeryPoint(decode_key(peer_ephemeral_b64, "对方临时公钥")?);
+        let shared = [
+            diffie_hellman(ephemeral.secret, &peer_ephemeral)?,
+            diffie_hellman(ephemeral.secret, &peer_static)?,
+            diffie_hellman(static_secret(identity)?, &peer_ephemeral)?,
+        ];
+        Self::derive(&ephemeral.public, &peer_ephemeral, shared)
+    }
+
+    /// 响应方用发起方的临时公钥与配对时记录的公钥派生同一会话
+    pub fn respond(
+        identity: &DeviceIdentity,
+        peer_public_key_b64: &str,
+        ephemeral: &EphemeralKey,
+        peer_ephemeral_b64: &str,
+    ) -> Result<Self, String> {
+        let peer_static = static_public(peer_public_key_b64)?;
+        let peer_ephemeral = MontgomeryPoint(decode_key(peer_ephemeral_b64, "对方临时公钥")?);
+        let shared = [
+            diffie_hellman(ephemeral.secret, &peer_ephemeral)?,
+            diffie_hellman(static_secret(identity)?, &peer_ephemeral)?,
+            diffie_hellman(ephemeral.secret, &peer_static)?,
+        ];
+        Self::derive(&peer_ephemeral, &ephemeral.public, shared)
+    }
+
+    /// 输出格式为 nonce || 密文；aad 用于绑定密文所属的传输与位置
+    pub fn seal(&self, plaintext: &[u8], aad: &str) -> Result<Vec<u8>, String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| "加密传输内容失败".to_string())?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn open(&self, sealed: &[u8], aad: &str) -> Result<Vec<u8>, String> {
+        if sealed.len() < NONCE_LEN {
+            return Err("加密数据不完整".to_string());
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.aead
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|_| "解密失败：数据被篡改或会话不匹配".to_string())
+    }
+}
+
+/// 向对端发起握手，返回 (会话 ID, 会话)；对端不支持加密通道时报错，不降级为明文传输
+pub async fn handshake(
+    client: &reqwest::Client,
+    base_url: &str,
+    identity: &DeviceIdentity,
+    device_id: &str,
+    peer_public_key_b64: &str,
+) -> Result<(String, Arc<SecureSession>), (String, &'static str)> {
+    let failed = |message: String| (message, "failed");
+    let ephemeral = EphemeralKey::generate();
+    let (timestamp, signature) =
+        TrustStore::sign_request(identity, device_id, "POST", HANDSHAKE_PATH).map_err(failed)?;
+    let response = client
+        .post(format!("{}{}", base_url, HANDSHAKE_PATH))
+        .header("X-Device-Id", device_id)
+        .header("X-Signature", signature)
+        .header("X-Timestamp", timestamp)
+        .json(&HandshakeRequest {
+            ephemeral_public: ephemeral.public_b64(),
+        })
+        .send()
+        .await
+        .map_err(|e| failed(format!("建立加密通道失败: {}", e)))?;
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED => {
+            return Err(failed(
+                "目标设备版本过旧，不支持加密传输，请升级后再试".to_string(),
+            ))
+        }
+        reqwest::StatusCode::FORBIDDEN => {
+            return Err(("目标设备返回状态码 403 Forbidden".to_string(), "rejected"))
+        }
+        status if !status.is_success() => {
+            return Err(failed(format!("建立加密通道失败: 状态码 {}", status)))
+        }
+        _ => {}
+    }
+    let reply = response
+        .json::<HandshakeResponse>()
+        .await
+        .map_err(|e| failed(format!("解析握手响应失败: {}", e)))?;
+    let session = SecureSession::initiate(
+        identity,
+        peer_public_key_b64,
+        &ephemeral,
+        &reply.ephemeral_public,
+    )
+    .map_err(failed)?;
+    Ok((reply.session_id, Arc::new(session)))
+}
+
+fn frame_aad(stream_id: &str, index: u64, last: bool) -> String {
+    format!("frame:{}:{}:{}", stream_id, index, last)
+}
+
+/// 流式加密：每帧为 4 字节头（最高位为末帧标记，其余为长度）+ 密文，
+/// 帧序号与末帧标记同时写入 aad，防止重排、截断与篡改标记
+pub struct FrameSealer {
+    session: Arc<SecureSession>,
+    stream_id: String,
+    index: u64,
+}
+
+impl FrameSealer {
+    pub fn new(session: Arc<SecureSession>, stream_id: &str) -> Self {
+        Self {
+            session,
+            stream_id: stream_id.to_string(),
+            index: 0,
+        }
+    }
+
+    pub fn seal(&mut self, plaintext: &[u8], last: bool) -> Result<Vec<u8>, String> {
+        let sealed = self
+            .session
+            .seal(plaintext, &frame_aad(&self.stream_id, self.index, last))?;
+        self.index += 1;
+        let header = sealed.len() as u32 | if last { LAST_FRAME_FLAG } else { 0 };
+        let mut frame = header.to_be_bytes().to_vec();
+        frame.extend_from_slice(&sealed);
+        Ok(frame)
+    }
+}
+
+pub struct FrameOpener {
+    session: Arc<SecureSession>,
+    stream_id: String,
+    index: u64,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl FrameOpener {
+    pub fn new(session: Arc<SecureSession>, stream_id: &str) -> Self {
+        Self {
+            session,
+            stream_id: stream_id.to_string(),
+            index: 0,
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// 追加收到的字节，返回其中已完整的明文帧
+    pub fn push(&mut self, bytes: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+        self.buffer.extend_from_slice(bytes);
+        let mut plaintexts = Vec::new();
+        while self.buffer.len() >= FRAME_HEADER_LEN {
+            if self.finished {
+                return Err("末帧之后出现多余数据".to_string());
+            }
+            let mut header = [0u8; FRAME_HEADER_LEN];
+            header.copy_from_slice(&self.buffer[..FRAME_HEADER_LEN]);
+            let header = u32::from_be_bytes(header);
+            let last = header & LAST_FRAME_FLAG != 0;
+            let length = (header & !LAST_FRAME_FLAG) as usize;
+            if length > FRAME_SIZE + 64 {
+                return Err("加密帧长度异常".to_string());
+            }
+            if self.buffer.len() < FRAME_HEADER_LEN + length {
+                break;
+            }
+            let sealed: Vec<u8> = self
+                .buffer
+                .drain(..FRAME_HEADER_LEN + length)
+                .skip(FRAME_HEADER_LEN)
+                .collect();
+            let plaintext = self
+                .session
+                .open(&sealed, &frame_aad(&self.stream_id, self.index, last))?;
+            self.finished = last;
+            self.index += 1;
+            plaintexts.push(plaintext);
+        }
+        Ok(plaintexts)
+    }
+
+    /// 流结束时确认已收到末帧，否则说明内容被截断
+    pub fn finish(&self) -> Result<(), String> {
+        if self.finished && self.buffer.is_empty() {
+            Ok(())
+        } else {
+            Err("传输内容不完整：未收到结束帧".to_string())
+        }
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 目录摘要：按路径排序后对每个文件的 "路径\n大小\nSHA-1\n" 计算 SHA-256，与增量清单一致
+pub fn digest_file_list(files: &[DeltaFileEntry]) -> String {
+    let mut entries: Vec<&DeltaFileEntry> = files.iter().collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut hasher = Sha256::new();
+    for entry in entries {
+        hasher.update(format!("{}\n{}\n{}\n", entry.path, entry.size, entry.sha1).as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// 重新读取落盘内容计算端到端摘要：压缩包取整体 SHA-256，增量暂存目录取文件清单摘要
+pub fn content_digest(path: &Path) -> Result<String, String> {
+    if !path.is_dir() {
+        return sha256_file(path);
+    }
+    let mut files = Vec::new();
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(path)
+            .map_err(|e| e.to_string())?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push(DeltaFileEntry {
+            path: relative,
+            size: entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+            sha1: compute_sha1(entry.path())?,
+        });
+    }
+    Ok(digest_file_list(&files))
+}
+
+/// 接收完成时记录发送方声明的摘要，与临时文件放在一起
+pub fn digest_path(temp_path: &Path) -> PathBuf {
+    let mut value = temp_path.as_os_str().to_owned();
+    value.push(".sha256");
+    PathBuf::from(value)
+}
+
+pub fn save_expected_digest(temp_path: &Path, digest: &str) -> Result<(), String> {
+    let digest = digest.trim().to_ascii_lowercase();
+    if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("内容摘要格式错误".to_string());
+    }
+    fs::write(digest_path(temp_path), digest).map_err(|e| format!("保存内容摘要失败: {}", e))
+}
+
+/// 部署前校验：缺少摘要或摘要不一致时都拒绝部署
+pub fn verify_content(temp_path: &Path) -> Result<(), String> {
+    let expected = fs::read_to_string(digest_path(temp_path))
+        .map_err(|_| "内容校验失败：缺少发送方的内容摘要，已拒绝部署".to_string())?;
+    let actual = content_digest(temp_path)?;
+    if actual != expected.trim() {
+        return Err("内容校验失败：接收到的文件与发送方不一致，已拒绝部署".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha1_hex(bytes: &[u8]) -> String {
+        use sha1::{Digest, Sha1};
+        hex::encode(Sha1::digest(bytes))
+    }
+
+    fn identity() -> DeviceIdentity {
+        let secret: [u8; 32] = rand::random();
+        let signing_key = SigningKey::from_bytes(&secret);
+        DeviceIdentity {
+            device_id: uuid::Uuid::new_v4().to_string(),
+            device_name: "test".to_string(),
+            user_uuid: String::new(),
+            private_key_b64: general_purpose::STANDARD.encode(signing_key.to_bytes()),
+            public_key_b64: general_purpose::STANDARD
+                .encode(signing_key.verifying_key().to_bytes()),
+        }
+    }
+
+    fn handshake(
+        initiator: &DeviceIdentity,
+        responder: &DeviceIdentity,
+        responder_view_of_initiator: &str,
+    ) -> (SecureSession, SecureSession) {
+        let initiator_ephemeral = EphemeralKey::generate();
+        let responder_ephemeral = EphemeralKey::generate();
+        let responder_session = SecureSession::respond(
+            responder,
+            responder_view_of_initiator,
+            &responder_ephemeral,
+            &initiator_ephemeral.public_b64(),
+        )
+        .unwrap();
+        let initiator_session = SecureSession::initiate(
+            initiator,
+            &responder.public_key_b64,
+            &initiator_ephemeral,
+            &responder_ephemeral.public_b64(),
+        )
+        .unwrap();
+        (initiator_session, responder_session)
+    }
+
+    #[test]
+    fn handshake_derives_shared_key_only_for_paired_identities() {
+        let alice = identity();
+        let bob = identity();
+        let (alice_session, bob_session) = handshake(&alice, &bob, &alice.public_key_b64);
+        let sealed = alice_session.seal(b"hello", "chunk:1").unwrap();
+        assert_eq!(bob_session.open(&sealed, "chunk:1").unwrap(), b"hello");
+        assert!(bob_session.open(&sealed, "chunk:2").is_err());
+
+        // 冒充者没有 alice 的私钥，即使拿到 alice 的公钥也无法得到同一会话
+        let mallory = identity();
+        let (mallory_session, bob_session) = handshake(&mallory, &bob, &alice.public_key_b64);
+        let sealed = mallory_session.seal(b"hello", "chunk:1").unwrap();
+        assert!(bob_session.open(&sealed, "chunk:1").is_err());
+    }
+
+    #[test]
+    fn frames_detect_truncation_and_reordering() {
+        let alice = identity();
+        let bob = identity();
+        let (alice_session, bob_session) = handshake(&alice, &bob, &alice.public_key_b64);
+        let (alice_session, bob_session) = (Arc::new(alice_session), Arc::new(bob_session));
+
+        let mut sealer = FrameSealer::new(alice_session, "t-1");
+        let first = sealer.seal(b"abc", false).unwrap();
+        let last = sealer.seal(b"def", true).unwrap();
+
+        let mut opener = FrameOpener::new(bob_session.clone(), "t-1");
+        let mut joined = first.clone();
+        joined.extend_from_slice(&last);
+        let mut plain = Vec::new();
+        // 任意切分网络数据块都应能还原
+        for piece in joined.chunks(5) {
+            for frame in opener.push(piece).unwrap() {
+                plain.extend(frame);
+            }
+        }
+        opener.finish().unwrap();
+        assert_eq!(plain, b"abcdef");
+
+        let mut truncated = FrameOpener::new(bob_session.clone(), "t-1");
+        truncated.push(&first).unwrap();
+        assert!(truncated.finish().is_err());
+
+        let mut reordered = FrameOpener::new(bob_session, "t-1");
+        assert!(reordered.push(&last).is_err());
+    }
+
+    #[test]
+    fn directory_digest_matches_sender_file_list() {
+        let dir = std::env::temp_dir().join(format!("pilauncher-secure-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("Pack").join("mods")).unwrap();
+        fs::write(dir.join("Pack").join("mods").join("a.jar"), b"mod").unwrap();
+        fs::write(dir.join("Pack").join("options.txt"), b"opts").unwrap();
+        let files = vec![
+            DeltaFileEntry {
+                path: "Pack/options.txt".to_string(),
+                size: 4,
+                sha1: sha1_hex(b"opts"),
+            },
+            DeltaFileEntry {
+                path: "Pack/mods/a.jar".to_string(),
+                size: 3,
+                sha1: sha1_hex(b"mod"),
+            },
+        ];
+
+        let unsigned = verify_content(&dir);
+        save_expected_digest(&dir, &digest_file_list(&files)).unwrap();
+        let verified = verify_content(&dir);
+        fs::write(dir.join("Pack").join("options.txt"), b"changed").unwrap();
+        let tampered = verify_content(&dir);
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_file(digest_path(&dir));
+
+        assert!(unsigned.is_err());
+        assert!(verified.is_ok());
+        assert!(tampered.is_err());
+    }
+}
@@ -0,0 +1,738 @@
+// src-tauri/src/services/lan/skin_server.rs
+//
+// 局域网离线皮肤服务。
+// 在局域网 HTTP 服务的 `/yggdrasil` 下提供最小化的 Yggdrasil 兼容接口，
+// 离线账号启动时通过 authlib-injector 指向房主的服务，加入同一局域网世界的玩家即可互相看到皮肤。
+
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, OriginalUri, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use base64::{engine::general_purpose, Engine as _};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::OnceCell;
+
+use crate::domain::lan::LanSkinServerConfig;
+use crate::domain::launcher::Account;
+use crate::services::auth::{authlib, paths, skin_png, wardrobe};
+use crate::services::config_service::ConfigService;
+use crate::services::lan::http_api::{self, AxumAppState, SharedLanState, DEFAULT_HTTP_PORT};
+use crate::services::lan::trust_store::TrustStore;
+
+pub const API_PREFIX: &str = "/yggdrasil";
+const CONFIG_FILE: &str = "lan_skin_server.json";
+const KEY_FILE: &str = "lan_yggdrasil_key.pem";
+/// join 与 hasJoined 之间允许的最大间隔
+const JOIN_TTL: Duration = Duration::from_secs(30);
+const SERVER_NAME: &str = "PiLauncher LAN";
+const REGISTER_PATH: &str = "/pilauncher/profiles";
+/// 单个皮肤材质的大小上限
+const MAX_TEXTURE_BYTES: usize = 256 * 1024;
+/// 内存中保留的材质数量上限，只保留仍被角色引用的材质
+const MAX_TEXTURES: usize = 64;
+
+#[derive(Clone, Debug)]
+struct SkinProfile {
+    id: String,
+    name: String,
+    model: String,
+    skin_hash: Option<String>,
+}
+
+/// 注册到皮肤服务的离线角色，皮肤为 PNG 的 base64
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterProfileRequest {
+    pub id: String,
+    pub name: String,
+    pub model: Option<String>,
+    pub skin: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JoinRequest {
+    selected_profile: String,
+    server_id: String,
+}
+
+#[derive(Deserialize)]
+struct HasJoinedQuery {
+    username: String,
+    #[serde(rename = "serverId")]
+    server_id: String,
+}
+
+#[derive(Deserialize)]
+struct ProfileQuery {
+    unsigned: Option<bool>,
+}
+
+/// 皮肤服务的内存状态，角色与材质在本次运行期间有效
+#[derive(Default)]
+pub struct SkinServerState {
+    profiles: Mutex<HashMap<String, SkinProfile>>,
+    textures: Mutex<HashMap<String, Vec<u8>>>,
+    joins: Mutex<HashMap<String, (String, Instant)>>,
+    signing_key: OnceCell<RsaPrivateKey>,
+}
+
+/// 统一为 32 位小写无横线 UUID，不合法时返回 None
+pub fn normalize_profile_id(id: &str) -> Option<String> {
+    let value = id.trim().replace('-', "").to_ascii_lowercase();
+    if value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+fn is_valid_player_name(name: &str) -> bool {
+    (1..=16).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl SkinServerState {
+    pub fn register(&self, request: RegisterProfileRequest) -> Result<(), String> {
+        let id = normalize_profile_id(&request.id).ok_or_else(|| "角色 UUID 不合法".to_string())?;
+        let name = request.name.trim().to_string();
+        if !is_valid_player_name(&name) {
+            return Err("角色名不合法".to_string());
+        }
+        let model =
+            wardrobe::normalize_skin_variant(request.model.as_deref().unwrap_or("classic"))?
+                .to_string();
+
+        let skin = match request.skin.as_deref().filter(|skin| !skin.is_empty()) {
+            Some(encoded) => {
+                if encoded.len() > MAX_TEXTURE_BYTES.div_ceil(3) * 4 {
+                    return Err("皮肤文件过大".to_string());
+                }
+                let bytes = general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|_| "皮肤数据不是有效的 base64".to_string())?;
+                if bytes.len() > MAX_TEXTURE_BYTES {
+                    return Err("皮肤文件过大".to_string());
+                }
+                skin_png::validate_skin_png_bytes(&bytes)?;
+                Some((hex::encode(Sha256::digest(&bytes)), bytes))
+            }
+            None => None,
+        };
+
+        let mut profiles = self
+            .profiles
+            .lock()
+            .map_err(|_| "皮肤服务状态已损坏".to_string())?;
+        let mut textures = self
+            .textures
+            .lock()
+            .map_err(|_| "皮肤服务状态已损坏".to_string())?;
+        // 同 UUID 或同名的旧角色（例如换了 UUID 的离线账号）会被替换，不再被引用的材质随之释放
+        let mut next = profiles.clone();
+        next.retain(|key, profile| key != &id && !profile.name.eq_ignore_ascii_case(&name));
+        next.insert(
+            id.clone(),
+            SkinProfile {
+                id,
+                name,
+                model,
+                skin_hash: skin.as_ref().map(|(hash, _)| hash.clone()),
+            },
+        );
+        let in_use: HashSet<&str> = next
+            .values()
+            .filter_map(|profile| profile.skin_hash.as_deref())
+            .collect();
+        if in_use.len() > MAX_TEXTURES {
+            return Err("皮肤服务保存的材质数量已达上限".to_string());
+        }
+        textures.retain(|hash, _| in_use.contains(hash.as_str()));
+        if let Some((hash, bytes)) = skin {
+            textures.entry(hash).or_insert(bytes);
+        }
+        *profiles = next;
+        Ok(())
+    }
+
+    fn profile(&self, id: &str) -> Option<SkinProfile> {
+        let id = normalize_profile_id(id)?;
+        self.profiles.lock().ok()?.get(&id).cloned()
+    }
+
+    fn profile_by_name(&self, name: &str) -> Option<SkinProfile> {
+        self.profiles
+            .lock()
+            .ok()?
+            .values()
+            .find(|profile| profile.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// 记录客户端的 join 请求，只接受已注册的角色
+    fn record_join(&self, profile_id: &str, server_id: &str) -> bool {
+        let Some(profile) = self.profile(profile_id) else {
+            return false;
+        };
+        let Ok(mut joins) = self.joins.lock() else {
+            return false;
+        };
+        joins.retain(|_, (_, joined_at)| joined_at.elapsed() < JOIN_TTL);
+        joins.insert(server_id.to_string(), (profile.id, Instant::now()));
+        true
+    }
+
+    /// 服务端校验 hasJoined，成功后消费该 join 记录
+    fn take_join(&self, username: &str, server_id: &str) -> Option<SkinProfile> {
+        let (profile_id, joined_at) = self.joins.lock().ok()?.remove(server_id)?;
+        if joined_at.elapsed() >= JOIN_TTL {
+            return None;
+        }
+        self.profile(&profile_id)
+            .filter(|profile| profile.name.eq_ignore_ascii_case(username))
+    }
+
+    fn texture(&self, hash: &str) -> Option<Vec<u8>> {
+        self.textures.lock().ok()?.get(hash).cloned()
+    }
+
+    async fn signing_key<R: Runtime>(&self, app: &AppHandle<R>) -> Result<&RsaPrivateKey, String> {
+        let key_path = app
+            .path()
+            .app_config_dir()
+            .map(|dir| dir.join(KEY_FILE))
+            .map_err(|e| format!("无法获取系统配置目录: {}", e))?;
+        self.signing_key
+            .get_or_try_init(|| async move {
+                tokio::task::spawn_blocking(move || load_or_create_signing_key(&key_path))
+                    .await
+                    .map_err(|e| e.to_string())?
+            })
+            .await
+    }
+}
+
+/// 读取持久化的签名密钥，不存在时生成 RSA-2048 并保存，保证重启后公钥不变
+fn load_or_create_signing_key(path: &std::path::Path) -> Result<RsaPrivateKey, String> {
+    if let Ok(pem) = fs::read_to_string(path) {
+        if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(&pem) {
+            return Ok(key);
+        }
+    }
+    let key = RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 2048)
+        .map_err(|e| format!("生成皮肤服务签名密钥失败: {}", e))?;
+    let pem = key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| format!("编码皮肤服务签名密钥失败: {}", e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    fs::write(path, pem.as_bytes()).map_err(|e| format!("保存皮肤服务签名密钥失败: {}", e))?;
+    Ok(key)
+}
+
+/// 按 Yggdrasil 规范对属性值做 SHA1withRSA 签名
+fn sign_property(key: &RsaPrivateKey, value: &str) -> String {
+    let signer = SigningKey::<Sha1>::new(key.clone());
+    general_purpose::STANDARD.encode(signer.sign(value.as_bytes()).to_bytes())
+}
+
+/// 构造 textures 属性值（base64 编码的 JSON）
+fn textures_value(profile: &SkinProfile, api_base: &str, timestamp: i64) -> String {
+    let mut textures = serde_json::Map::new();
+    if let Some(hash) = &profile.skin_hash {
+        let mut skin = json!({ "url": format!("{}/textures/{}", api_base, hash) });
+        if profile.model == "slim" {
+            skin["metadata"] = json!({ "model": "slim" });
+        }
+        textures.insert("SKIN".to_string(), skin);
+    }
+    let payload = json!({
+        "timestamp": timestamp,
+        "profileId": profile.id,
+        "profileName": profile.name,
+        "textures": textures,
+    });
+    general_purpose::STANDARD.encode(payload.to_string())
+}
+
+fn profile_json(profile: &SkinProfile, api_base: &str, key: Option<&RsaPrivateKey>) -> Value {
+    let value = textures_value(profile, api_base, chrono::Utc::now().timestamp_millis());
+    let mut property = json!({ "name": "textures", "value": value });
+    if let Some(key) = key {
+        property["signature"] = json!(sign_property(key, &value));
+    }
+    json!({
+        "id": profile.id,
+        "name": profile.name,
+        "properties": [property],
+    })
+}
+
+/// 本机皮肤服务对局域网公开的地址，材质 URL 也基于它生成
+pub fn local_api_root() -> Result<String, String> {
+    let ip = local_ip_address::local_ip().map_err(|e| format!("无法获取本机局域网地址: {}", e))?;
+    Ok(format!("http://{}:{}{}", ip, DEFAULT_HTTP_PORT, API_PREFIX))
+}
+
+/// 将用户填写的房主地址补全为皮肤服务 API 地址
+fn remote_api_root(host: &str) -> Option<String> {
+    let host = host
+        .trim()
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .trim_end_matches(API_PREFIX);
+    if host.is_empty() || host.contains('/') {
+        return None;
+    }
+    let authority = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:{}", host, DEFAULT_HTTP_PORT)
+    };
+    Some(format!("http://{}{}", authority, API_PREFIX))
+}
+
+fn api_base_or_error() -> Result<String, (StatusCode, String)> {
+    local_api_root().map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e))
+}
+
+async fn get_metadata(State(state): State<Arc<AxumAppState>>) -> Response {
+    let api_base = match api_base_or_error() {
+        Ok(value) => value,
+        Err(error) => return error.into_response(),
+    };
+    let public_key = match state
+        .shared_state
+        .skin_server
+        .signing_key(&state.tauri_app)
+        .await
+        .and_then(|key| {
+            key.to_public_key()
+                .to_public_key_pem(LineEnding::LF)
+                .map_err(|e| e.to_string())
+        }) {
+        Ok(pem) => pem,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    };
+    let skin_domain = reqwest::Url::parse(&api_base)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+
+    Json(json!({
+        "meta": {
+            "serverName": SERVER_NAME,
+            "implementationName": "pilauncher-lan",
+            "implementationVersion": env!("CARGO_PKG_VERSION"),
+            "feature.non_email_login": true,
+        },
+        "skinDomains": [skin_domain],
+        "signaturePublickey": public_key,
+    }))
+    .into_response()
+}
+
+async fn join(
+    State(state): State<Arc<AxumAppState>>,
+    Json(request): Json<JoinRequest>,
+) -> StatusCode {
+    // 离线账号没有可校验的令牌，只要求角色已在本服务注册
+    if state
+        .shared_state
+        .skin_server
+        .record_join(&request.selected_profile, &request.server_id)
+    {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::FORBIDDEN
+    }
+}
+
+async fn has_joined(
+    State(state): State<Arc<AxumAppState>>,
+    Query(query): Query<HasJoinedQuery>,
+) -> Response {
+    let skin_server = &state.shared_state.skin_server;
+    let Some(profile) = skin_server.take_join(&query.username, &query.server_id) else {
+        return StatusCode::NO_CONTENT.into_response();
+    };
+    let api_base = match api_base_or_error() {
+        Ok(value) => value,
+        Err(error) => return error.into_response(),
+    };
+    match skin_server.signing_key(&state.tauri_app).await {
+        Ok(key) => Json(profile_json(&profile, &api_base, Some(key))).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+async fn get_profile(
+    State(state): State<Arc<AxumAppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<ProfileQuery>,
+) -> Response {
+    let skin_server = &state.shared_state.skin_server;
+    let Some(profile) = skin_server.profile(&id) else {
+        return StatusCode::NO_CONTENT.into_response();
+    };
+    let api_base = match api_base_or_error() {
+        Ok(value) => value,
+        Err(error) => return error.into_response(),
+    };
+    if query.unsigned.unwrap_or(true) {
+        return Json(profile_json(&profile, &api_base, None)).into_response();
+    }
+    match skin_server.signing_key(&state.tauri_app).await {
+        Ok(key) => Json(profile_json(&profile, &api_base, Some(key))).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+async fn lookup_profiles(
+    State(state): State<Arc<AxumAppState>>,
+    Json(names): Json<Vec<String>>,
+) -> Json<Value> {
+    let profiles: Vec<Value> = names
+        .iter()
+        .take(10)
+        .filter_map(|name| state.shared_state.skin_server.profile_by_name(name))
+        .map(|profile| json!({ "id": profile.id, "name": profile.name }))
+        .collect();
+    Json(Value::Array(profiles))
+}
+
+async fn get_texture(State(state): State<Arc<AxumAppState>>, Path(hash): Path<String>) -> Response {
+    match state.shared_state.skin_server.texture(&hash) {
+        Some(bytes) => ([(header::CONTENT_TYPE, "image/png")], Bytes::from(bytes)).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// 只接受本机或已配对设备注册角色，避免局域网内任意主机占用材质存储或冒用角色名
+async fn register_profile(
+    State(state): State<Arc<AxumAppState>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    Json(request): Json<RegisterProfileRequest>,
+) -> Response {
+    if !remote.ip().is_loopback() {
+        match http_api::verify_peer_request(&state, &headers, "POST", uri.path()).await {
+            Ok(peer) if peer.trust_level == "trusted" || peer.trust_level == "friend" => {}
+            Ok(_) => return StatusCode::FORBIDDEN.into_response(),
+            Err(status) => return status.into_response(),
+        }
+    }
+    match state.shared_state.skin_server.register(request) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+/// 皮肤服务未开启时整个接口表现为不存在
+async fn require_enabled(
+    State(state): State<Arc<AxumAppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if load_config(&state.tauri_app).enabled {
+        next.run(request).await
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// 皮肤服务路由，由 http_api 挂载到 API_PREFIX 下；
+/// 查询接口不经过设备鉴权，角色注册由处理函数自行校验来源
+pub fn router(state: Arc<AxumAppState>) -> Router<Arc<AxumAppState>> {
+    Router::new()
+        .route("/", get(get_metadata))
+        .route("/sessionserver/session/minecraft/join", post(join))
+        .route(
+            "/sessionserver/session/minecraft/hasJoined",
+            get(has_joined),
+        )
+        .route(
+            "/sessionserver/session/minecraft/profile/{id}",
+            get(get_profile),
+        )
+        .route("/api/profiles/minecraft", post(lookup_profiles))
+        .route("/textures/{hash}", get(get_texture))
+        .route(REGISTER_PATH, post(register_profile))
+        .route_layer(middleware::from_fn_with_state(state, require_enabled))
+}
+
+fn config_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .map_err(|e| format!("无法获取系统配置目录: {}", e))
+}
+
+pub fn load_config<R: Runtime>(app: &AppHandle<R>) -> LanSkinServerConfig {
+    config_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &LanSkinServerConfig,
+) -> Result<(), String> {
+    if let Some(host) = config.host_address.as_deref() {
+        if !host.trim().is_empty() && remote_api_root(host).is_none() {
+            return Err("房主地址格式不正确，请填写 IP 或 IP:端口".to_string());
+        }
+    }
+    let path = config_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("保存局域网皮肤服务设置失败: {}", e))
+}
+
+/// 读取离线账号当前的皮肤与模型，组装注册请求
+fn build_register_request<R: Runtime>(
+    app: &AppHandle<R>,
+    account: &Account,
+) -> RegisterProfileRequest {
+    let skin = paths::active_account_skin_path(app, &account.uuid)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .filter(|bytes| skin_png::is_valid_skin_png_bytes(bytes))
+        .map(|bytes| general_purpose::STANDARD.encode(bytes));
+    let model = wardrobe::get_wardrobe_skin_library(app, &account.uuid)
+        .ok()
+        .and_then(|library| {
+            library
+                .assets
+                .into_iter()
+                .find(|asset| asset.is_active)
+                .and_then(|asset| asset.variant)
+        });
+
+    RegisterProfileRequest {
+        id: account.uuid.clone(),
+        name: account.username.clone(),
+        model,
+        skin,
+    }
+}
+
+/// 房主只接受已配对设备的注册，用本机设备身份为请求签名，返回 (设备 ID, 时间戳, 签名)
+fn sign_register_request<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<(String, String, String), String> {
+    let base_path = ConfigService::get_base_path(app)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "尚未配置启动器数据目录".to_string())?;
+    let identity = TrustStore::get_or_create_identity(&PathBuf::from(base_path).join("config"));
+    let current_id = app
+        .state::<Arc<SharedLanState>>()
+        .current_device_info
+        .lock()
+        .map_err(|_| "设备信息状态已损坏".to_string())?
+        .device_id
+        .clone();
+    let device_id = if current_id.trim().is_empty() {
+        identity.device_id.clone()
+    } else {
+        current_id
+    };
+    let path = format!("{}{}", API_PREFIX, REGISTER_PATH);
+    let (timestamp, signature) = TrustStore::sign_request(&identity, &device_id, "POST", &path)?;
+    Ok((device_id, timestamp, signature))
+}
+
+/// 离线账号启动前调用：未开启时返回 None；开启后把角色注册到本机或房主的皮肤服务，
+/// 返回 (API 地址, 预取元数据) 供 authlib-injector 使用
+pub async fn prepare_offline_session<R: Runtime>(
+    app: &AppHandle<R>,
+    account: &Account,
+) -> Result<Option<(String, String)>, String> {
+    let config = load_config(app);
+    if !config.enabled {
+        return Ok(None);
+    }
+    let request = build_register_request(app, account);
+
+    let api_root = match config
+        .host_address
+        .as_deref()
+        .filter(|host| !host.trim().is_empty())
+    {
+        Some(host) => {
+            let api_root = remote_api_root(host).ok_or_else(|| "房主地址格式不正确".to_string())?;
+            let (device_id, timestamp, signature) = sign_register_request(app)?;
+            let response = reqwest::Client::builder()
+                .timeout(Duration::from_secs(6))
+                .build()
+                .map_err(|e| e.to_string())?
+                .post(format!("{}{}", api_root, REGISTER_PATH))
+                .header("X-Device-Id", device_id)
+                .header("X-Signature", signature)
+                .header("X-Timestamp", timestamp)
+                .json(&json!({
+                    "id": request.id,
+                    "name": request.name,
+                    "model": request.model,
+                    "skin": request.skin,
+                }))
+                .send()
+                .await
+                .map_err(|e| format!("无法连接房主的皮肤服务: {}", e))?;
+            if !response.status().is_success() {
+                let message = response.text().await.unwrap_or_default();
+                return Err(format!("房主的皮肤服务拒绝了角色注册: {}", message));
+            }
+            api_root
+        }
+        None => {
+            app.state::<Arc<SharedLanState>>()
+                .skin_server
+                .register(request)?;
+            local_api_root()?
+        }
+    };
+
+    let (api_root, meta) = authlib::discover_api_root(&api_root).await?;
+    Ok(Some((api_root, meta.prefetched)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(model: &str, skin_hash: Option<&str>) -> SkinProfile {
+        SkinProfile {
+            id: "0123456789abcdef0123456789abcdef".to_string(),
+            name: "Steve".to_string(),
+            model: model.to_string(),
+            skin_hash: skin_hash.map(str::to_string),
+        }
+    }
+
+    fn decode(value: &str) -> Value {
+        serde_json::from_slice(&general_purpose::STANDARD.decode(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn ids_and_host_addresses_are_normalized() {
+        assert_eq!(
+            normalize_profile_id("01234567-89AB-CDEF-0123-456789ABCDEF").as_deref(),
+            Some("0123456789abcdef0123456789abcdef")
+        );
+        assert_eq!(normalize_profile_id("not-a-uuid"), None);
+        assert_eq!(
+            remote_api_root("192.168.1.5").as_deref(),
+            Some("http://192.168.1.5:9999/yggdrasil")
+        );
+        assert_eq!(
+            remote_api_root("http://192.168.1.5:8080/yggdrasil/").as_deref(),
+            Some("http://192.168.1.5:8080/yggdrasil")
+        );
+        assert_eq!(remote_api_root("host/path"), None);
+    }
+
+    #[test]
+    fn textures_carry_skin_url_and_slim_model() {
+        let base = "http://10.0.0.2:9999/yggdrasil";
+        let slim = decode(&textures_value(&profile("slim", Some("abc")), base, 1));
+        assert_eq!(
+            slim["textures"]["SKIN"]["url"],
+            "http://10.0.0.2:9999/yggdrasil/textures/abc"
+        );
+        assert_eq!(slim["textures"]["SKIN"]["metadata"]["model"], "slim");
+        assert_eq!(slim["profileName"], "Steve");
+
+        let classic = decode(&textures_value(&profile("classic", Some("abc")), base, 1));
+        assert!(classic["textures"]["SKIN"].get("metadata").is_none());
+
+        let bare = decode(&textures_value(&profile("classic", None), base, 1));
+        assert!(bare["textures"].as_object().unwrap().is_empty());
+    }
+
+    #[test]
+    fn join_is_consumed_by_matching_has_joined() {
+        let state = SkinServerState::default();
+        state
+            .register(RegisterProfileRequest {
+                id: "0123456789abcdef0123456789abcdef".to_string(),
+                name: "Steve".to_string(),
+                model: None,
+                skin: None,
+            })
+            .unwrap();
+
+        assert!(!state.record_join("ffffffffffffffffffffffffffffffff", "srv"));
+        assert!(state.record_join("01234567-89ab-cdef-0123-456789abcdef", "srv"));
+        assert!(state.take_join("Alex", "srv").is_none());
+        assert!(state.record_join("0123456789abcdef0123456789abcdef", "srv"));
+        assert_eq!(state.take_join("steve", "srv").unwrap().name, "Steve");
+        assert!(state.take_join("steve", "srv").is_none());
+    }
+
+    fn skin(tag: u8, len: usize) -> String {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&64u32.to_be_bytes());
+        bytes.extend_from_slice(&64u32.to_be_bytes());
+        bytes.resize(len.max(bytes.len() + 1), tag);
+        general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn request(index: usize, skin: Option<String>) -> RegisterProfileRequest {
+        RegisterProfileRequest {
+            id: format!("{:032x}", index + 1),
+            name: format!("Player{}", index),
+            model: None,
+            skin,
+        }
+    }
+
+    #[test]
+    fn textures_are_bounded_and_released_with_their_profile() {
+        let state = SkinServerState::default();
+        state.register(request(0, Some(skin(1, 64)))).unwrap();
+        state.register(request(0, Some(skin(2, 64)))).unwrap();
+        assert_eq!(state.textures.lock().unwrap().len(), 1);
+        state.register(request(0, None)).unwrap();
+        assert!(state.textures.lock().unwrap().is_empty());
+
+        assert!(state
+            .register(request(1, Some(skin(1, MAX_TEXTURE_BYTES + 1))))
+            .is_err());
+
+        for index in 0..MAX_TEXTURES {
+            state
+                .register(request(index, Some(skin(index as u8, 64 + index))))
+                .unwrap();
+        }
+        let overflow = request(MAX_TEXTURES, Some(skin(0, 32)));
+        assert!(state.register(overflow).is_err());
+        let overflow_id = format!("{:032x}", MAX_TEXTURES + 1);
+        assert!(state.profile(&overflow_id).is_none());
+        assert_eq!(state.textures.lock().unwrap().len(), MAX_TEXTURES);
+    }
+}
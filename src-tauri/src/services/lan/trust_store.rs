@@ -1,11 +1,63 @@
-use crate::domain::lan::DeviceIdentity;
-use sqlx::SqlitePool;
+use crate::domain::lan::{DeviceIdentity, TrustAuditEntry};
+use sqlx::{Row, SqlitePool};
 use std::fs;
 use std::path::Path;
 
+// 信任审计日志的事件类型
+pub const AUDIT_GRANTED: &str = "granted";
+pub const AUDIT_DOWNGRADED: &str = "downgraded";
+pub const AUDIT_REVOKED: &str = "revoked";
+pub const AUDIT_EXPIRY_SET: &str = "expiry_set";
+pub const AUDIT_KEY_ROTATED: &str = "key_rotated";
+pub const AUDIT_USED: &str = "used";
+pub const AUDIT_DENIED: &str = "denied";
+
+/// 同一设备的同一访问或拒绝记录的最小间隔（秒），避免分块传输或重复请求刷屏
+const AUDIT_THROTTLE_SECS: i64 = 60;
+/// 审计日志保留时长（秒）
+const AUDIT_RETENTION_SECS: i64 = 90 * 24 * 60 * 60;
+/// 审计日志最多保留的条数，超出时删除最旧的记录
+const AUDIT_MAX_ENTRIES: i64 = 10_000;
+
 pub struct TrustStore;
 
 impl TrustStore {
+    /// 先写临时文件再替换，轮换密钥时不会留下半截的身份文件
+    fn write_identity_keys(
+        identity_file: &Path,
+        private_key_b64: &str,
+        public_key_b64: &str,
+    ) -> std::io::Result<()> {
+        let identity_json = serde_json::json!({
+            "private_key_b64": private_key_b64,
+            "public_key_b64": public_key_b64
+        });
+
+        if let Some(parent) = identity_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_file = identity_file.with_extension("json.tmp");
+        fs::write(
+            &temp_file,
+            serde_json::to_string_pretty(&identity_json).unwrap_or_default(),
+        )?;
+        fs::rename(&temp_file, identity_file)
+    }
+
+    /// 用新密钥替换本机身份，设备 ID 与名称不变
+    pub fn replace_identity_keys(
+        config_dir: &Path,
+        private_key_b64: &str,
+        public_key_b64: &str,
+    ) -> Result<(), String> {
+        Self::write_identity_keys(
+            &config_dir.join("lan_identity.json"),
+            private_key_b64,
+            public_key_b64,
+        )
+        .map_err(|e| format!("保存新的设备密钥失败: {}", e))
+    }
+
     pub fn get_or_create_identity(config_dir: &Path) -> DeviceIdentity {
         let mut settings_file = config_dir.join("settings.json");
         if !settings_file.exists() {
@@ -64,18 +116,7 @@ impl TrustStore {
             private_key_b64 = general_purpose::STANDARD.encode(private_bytes);
             public_key_b64 = general_purpose::STANDARD.encode(public_bytes);
 
-            let identity_json = serde_json::json!({
-                "private_key_b64": private_key_b64,
-                "public_key_b64": public_key_b64
-            });
-
-            if let Some(parent) = identity_file.parent() {
-                let _ = fs::create_dir_all(parent);
-            }
-            let _ = fs::write(
-                &identity_file,
-                serde_json::to_string_pretty(&identity_json).unwrap_or_default(),
-            );
+            let _ = Self::write_identity_keys(&identity_file, &private_key_b64, &public_key_b64);
         }
 
         if device_id.trim().is_empty() {
@@ -94,6 +135,32 @@ impl TrustStore {
         }
     }
 
+    /// 为发往已配对设备的请求签名，返回 (时间戳, 签名)；
+    /// 签名消息格式为 "{method}:{path}:{timestamp}:{device_id}"，与 auth_middleware 的校验一致
+    pub fn sign_request(
+        identity: &DeviceIdentity,
+        sender_device_id: &str,
+        method: &str,
+        path: &str,
+    ) -> Result<(String, String), String> {
+        use base64::{engine::general_purpose, Engine as _};
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let message = format!("{}:{}:{}:{}", method, path, timestamp, sender_device_id);
+        let private_bytes = general_purpose::STANDARD
+            .decode(&identity.private_key_b64)
+            .map_err(|e| format!("解码私钥失败: {}", e))?;
+        let private_array: [u8; 32] = private_bytes
+            .try_into()
+            .map_err(|_| "私钥长度错误".to_string())?;
+        let signature = SigningKey::from_bytes(&private_array).sign(message.as_bytes());
+        Ok((
+            timestamp,
+            general_purpose::STANDARD.encode(signature.to_bytes()),
+        ))
+    }
+
     pub async fn upsert_device_relationship(
         pool: &SqlitePool,
         device_id: String,
@@ -120,9 +187,11 @@ impl TrustStore {
                 username = excluded.username,
                 public_key_b64 = excluded.public_key_b64,
                 trust_level = excluded.trust_level,
-                trusted_at = CURRENT_TIMESTAMP",
+                trusted_at = CURRENT_TIMESTAMP,
+                expires_at = NULL,
+                revoked_at = NULL",
         )
-        .bind(device_id)
+        .bind(&device_id)
         .bind(device_name)
         .bind(user_uuid)
         .bind(username)
@@ -132,7 +201,7 @@ impl TrustStore {
         .await
         .map_err(|e| format!("Failed to persist device relationship: {}", e))?;
 
-        Ok(())
+        Self::record_audit(pool, &device_id, AUDIT_GRANTED, trust_level).await
     }
 
     pub async fn add_trusted_device(
@@ -174,4 +243,473 @@ impl TrustStore {
         )
         .await
     }
+
+    /// 已建立且未过期的关系再次请求配对时只刷新资料：保留信任等级与到期时间，不写授权审计
+    pub async fn refresh_device_relationship(
+        pool: &SqlitePool,
+        device_id: &str,
+        device_name: &str,
+        user_uuid: &str,
+        username: &str,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE trusted_devices
+             SET device_name = $2, user_uuid = $3, username = $4
+             WHERE device_uuid = $1",
+        )
+        .bind(device_id)
+        .bind(device_name)
+        .bind(user_uuid)
+        .bind(username)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to refresh device relationship: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 读取配对时记录的对方公钥，用于建立加密会话
+    pub async fn get_public_key(
+        pool: &SqlitePool,
+        device_id: &str,
+    ) -> Result<Option<String>, String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT public_key_b64 FROM trusted_devices WHERE device_uuid = $1 LIMIT 1",
+        )
+        .bind(device_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to query device public key: {}", e))
+    }
+
+    /// 手动添加的设备：记住地址；尚未建立关系时以 'manual' 等级保存，
+    /// 二维码提供的公钥作为预期公钥，配对时用于核对对方身份
+    pub async fn remember_peer_address(
+        pool: &SqlitePool,
+        device_id: &str,
+        device_name: &str,
+        username: &str,
+        public_key_b64: &str,
+        address: &str,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO trusted_devices (
+                device_uuid,
+                device_name,
+                user_uuid,
+                username,
+                public_key_b64,
+                trust_level,
+                address,
+                last_seen
+             )
+             VALUES ($1, $2, '', $3, $4, 'manual', $5, CURRENT_TIMESTAMP)
+             ON CONFLICT(device_uuid) DO UPDATE SET
+                address = excluded.address,
+                last_seen = CURRENT_TIMESTAMP,
+                device_name = CASE WHEN excluded.device_name = '' THEN device_name
+                                   ELSE excluded.device_name END,
+                public_key_b64 = CASE WHEN trust_level = 'manual' AND excluded.public_key_b64 != ''
+                                      THEN excluded.public_key_b64
+                                      ELSE public_key_b64 END",
+        )
+        .bind(device_id)
+        .bind(device_name)
+        .bind(username)
+        .bind(public_key_b64)
+        .bind(address)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to remember peer address: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 设备在线时刷新地址与最后在线时间
+    pub async fn touch_peer_address(
+        pool: &SqlitePool,
+        device_id: &str,
+        address: &str,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE trusted_devices
+             SET address = $2, last_seen = CURRENT_TIMESTAMP
+             WHERE device_uuid = $1",
+        )
+        .bind(device_id)
+        .bind(address)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to update peer address: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 对方发起配对时补记它的地址，供密钥交接等主动连接使用；
+    /// 已有地址（可能是手动添加时的自定义端口）不覆盖
+    pub async fn remember_address_if_missing(
+        pool: &SqlitePool,
+        device_id: &str,
+        address: &str,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE trusted_devices
+             SET address = $2, last_seen = CURRENT_TIMESTAMP
+             WHERE device_uuid = $1 AND (address IS NULL OR address = '')",
+        )
+        .bind(device_id)
+        .bind(address)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to update peer address: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 手动添加时记录的预期公钥；已建立关系的设备不返回
+    pub async fn pinned_public_key(
+        pool: &SqlitePool,
+        device_id: &str,
+    ) -> Result<Option<String>, String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT public_key_b64 FROM trusted_devices
+             WHERE device_uuid = $1 AND trust_level = 'manual' AND public_key_b64 != ''
+             LIMIT 1",
+        )
+        .bind(device_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to query pinned public key: {}", e))
+    }
+
+    /// 忘记设备地址；仅手动添加、尚未配对的设备整行删除
+    pub async fn forget_peer_address(pool: &SqlitePool, device_id: &str) -> Result<(), String> {
+        sqlx::query(
+            "DELETE FROM trusted_devices WHERE device_uuid = $1 AND trust_level = 'manual'",
+        )
+        .bind(device_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to forget peer: {}", e))?;
+        sqlx::query("UPDATE trusted_devices SET address = '' WHERE device_uuid = $1")
+            .bind(device_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to forget peer: {}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn record_audit(
+        pool: &SqlitePool,
+        device_id: &str,
+        event: &str,
+        detail: &str,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO trust_audit_log (device_uuid, event, detail, created_at)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(device_id)
+        .bind(event)
+        .bind(detail)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to write trust audit log: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 记录一次已鉴权的访问并刷新 last_used；同一路径在间隔内只记一条
+    pub async fn record_use(
+        pool: &SqlitePool,
+        device_id: &str,
+        detail: &str,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE trusted_devices SET last_used = CURRENT_TIMESTAMP WHERE device_uuid = $1",
+        )
+        .bind(device_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to update device usage: {}", e))?;
+        Self::record_throttled(pool, device_id, AUDIT_USED, detail).await
+    }
+
+    /// 记录一次被拒绝的访问；已吊销或过期的设备反复请求时同样按间隔只记一条
+    pub async fn record_denied(
+        pool: &SqlitePool,
+        device_id: &str,
+        reason: &str,
+    ) -> Result<(), String> {
+        Self::record_throttled(pool, device_id, AUDIT_DENIED, reason).await
+    }
+
+    async fn record_throttled(
+        pool: &SqlitePool,
+        device_id: &str,
+        event: &str,
+        detail: &str,
+    ) -> Result<(), String> {
+        let now = chrono::Utc::now().timestamp();
+        sqlx::query(
+            "INSERT INTO trust_audit_log (device_uuid, event, detail, created_at)
+             SELECT $1, $2, $3, $4
+             WHERE NOT EXISTS (
+                SELECT 1 FROM trust_audit_log
+                WHERE device_uuid = $1 AND event = $2 AND detail = $3 AND created_at > $5
+             )",
+        )
+        .bind(device_id)
+        .bind(event)
+        .bind(detail)
+        .bind(now)
+        .bind(now - AUDIT_THROTTLE_SECS)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to write trust audit log: {}", e))?;
+
+        Ok(())
+    }
+
+    /// 清理超过保留期限的审计日志，并只保留最新的 AUDIT_MAX_ENTRIES 条
+    pub async fn prune_audit_log(pool: &SqlitePool) -> Result<u64, String> {
+        let cutoff = chrono::Utc::now().timestamp() - AUDIT_RETENTION_SECS;
+        let expired = sqlx::query("DELETE FROM trust_audit_log WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to prune trust audit log: {}", e))?
+            .rows_affected();
+        let overflow = sqlx::query(
+            "DELETE FROM trust_audit_log WHERE id NOT IN (
+                SELECT id FROM trust_audit_log ORDER BY created_at DESC, id DESC LIMIT $1
+             )",
+        )
+        .bind(AUDIT_MAX_ENTRIES)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to prune trust audit log: {}", e))?
+        .rows_affected();
+
+        Ok(expired + overflow)
+    }
+
+    pub async fn list_audit_log(
+        pool: &SqlitePool,
+        device_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<TrustAuditEntry>, String> {
+        let rows = sqlx::query(
+            "SELECT id, device_uuid, event, detail, created_at
+             FROM trust_audit_log
+             WHERE $1 IS NULL OR device_uuid = $1
+             ORDER BY created_at DESC, id DESC
+             LIMIT $2",
+        )
+        .bind(device_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to query trust audit log: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TrustAuditEntry {
+                id: row.try_get("id").unwrap_or_default(),
+                device_id: row.try_get("device_uuid").unwrap_or_default(),
+                event: row.try_get("event").unwrap_or_default(),
+                detail: row.try_get("detail").unwrap_or_default(),
+                created_at: row.try_get("created_at").unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// 吊销设备：立即失去所有访问权限，对方再次发起的配对请求也会被拒绝
+    pub async fn revoke_device(pool: &SqlitePool, device_id: &str) -> Result<bool, String> {
+        let result = sqlx::query(
+            "UPDATE trusted_devices
+             SET trust_level = 'revoked', revoked_at = $2
+             WHERE device_uuid = $1 AND trust_level != 'revoked'",
+        )
+        .bind(device_id)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to revoke device: {}", e))?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        Self::record_audit(pool, device_id, AUDIT_REVOKED, "").await?;
+        Ok(true)
+    }
+
+    /// 设置信任到期时间（Unix 秒），None 表示永不过期
+    pub async fn set_trust_expiry(
+        pool: &SqlitePool,
+        device_id: &str,
+        expires_at: Option<i64>,
+    ) -> Result<(), String> {
+        let result = sqlx::query(
+            "UPDATE trusted_devices
+             SET expires_at = $2
+             WHERE device_uuid = $1 AND trust_level IN ('trusted', 'friend')",
+        )
+        .bind(device_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to update trust expiry: {}", e))?;
+
+        if result.rows_affected() == 0 {
+            return Err("该设备未被信任，无法设置到期时间".to_string());
+        }
+        let detail = expires_at
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        Self::record_audit(pool, device_id, AUDIT_EXPIRY_SET, &detail).await
+    }
+
+    /// 设备当前是否仍可访问：信任或好友等级且未过期
+    pub async fn is_access_active(pool: &SqlitePool, device_id: &str) -> Result<bool, String> {
+        let row = sqlx::query(
+            "SELECT trust_level, expires_at FROM trusted_devices WHERE device_uuid = $1 LIMIT 1",
+        )
+        .bind(device_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to query device trust: {}", e))?;
+
+        Ok(row.is_some_and(|row| {
+            let trust_level: String = row.try_get("trust_level").unwrap_or_default();
+            let expires_at: Option<i64> = row.try_get("expires_at").unwrap_or_default();
+            (trust_level == "trusted" || trust_level == "friend") && !is_expired(expires_at)
+        }))
+    }
+}
+
+/// 到期时间已过（含恰好到期）
+pub fn is_expired(expires_at: Option<i64>) -> bool {
+    expires_at.is_some_and(|value| value <= chrono::Utc::now().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE trusted_devices (
+                device_uuid TEXT UNIQUE NOT NULL,
+                device_name TEXT NOT NULL,
+                user_uuid TEXT,
+                username TEXT DEFAULT '',
+                public_key_b64 TEXT NOT NULL,
+                trust_level TEXT DEFAULT 'trusted',
+                trusted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                last_used TIMESTAMP,
+                address TEXT DEFAULT '',
+                last_seen TIMESTAMP,
+                expires_at INTEGER,
+                revoked_at INTEGER
+            );
+            CREATE TABLE trust_audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                device_uuid TEXT NOT NULL,
+                event TEXT NOT NULL,
+                detail TEXT DEFAULT '',
+                created_at INTEGER NOT NULL
+            );",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    async fn audit_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM trust_audit_log")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn stored_address(pool: &SqlitePool, device_id: &str) -> String {
+        sqlx::query_scalar("SELECT address FROM trusted_devices WHERE device_uuid = $1")
+            .bind(device_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn repeated_pairing_request_keeps_the_trust_expiry() {
+        let pool = memory_pool().await;
+        TrustStore::add_friend_device(
+            &pool,
+            "dev-1".to_string(),
+            "Laptop".to_string(),
+            "user-1".to_string(),
+            "Steve".to_string(),
+            "key".to_string(),
+        )
+        .await
+        .unwrap();
+        let expires_at = chrono::Utc::now().timestamp() + 3600;
+        TrustStore::set_trust_expiry(&pool, "dev-1", Some(expires_at))
+            .await
+            .unwrap();
+        let before = audit_count(&pool).await;
+
+        TrustStore::refresh_device_relationship(&pool, "dev-1", "Desktop", "user-1", "Steve")
+            .await
+            .unwrap();
+
+        let row = sqlx::query(
+            "SELECT device_name, trust_level, expires_at FROM trusted_devices
+             WHERE device_uuid = 'dev-1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row.get::<String, _>("device_name"), "Desktop");
+        assert_eq!(row.get::<String, _>("trust_level"), "friend");
+        assert_eq!(row.get::<Option<i64>, _>("expires_at"), Some(expires_at));
+        assert_eq!(audit_count(&pool).await, before);
+    }
+
+    #[tokio::test]
+    async fn pairing_address_does_not_replace_a_known_one() {
+        let pool = memory_pool().await;
+        TrustStore::remember_peer_address(&pool, "dev-1", "Laptop", "", "", "10.0.0.2:25565")
+            .await
+            .unwrap();
+        TrustStore::remember_address_if_missing(&pool, "dev-1", "10.0.0.9:9999")
+            .await
+            .unwrap();
+        TrustStore::add_trusted_device(
+            &pool,
+            "dev-2".to_string(),
+            "Desktop".to_string(),
+            String::new(),
+            String::new(),
+            "key".to_string(),
+        )
+        .await
+        .unwrap();
+        TrustStore::remember_address_if_missing(&pool, "dev-2", "10.0.0.3:9999")
+            .await
+            .unwrap();
+
+        assert_eq!(stored_address(&pool, "dev-1").await, "10.0.0.2:25565");
+        assert_eq!(stored_address(&pool, "dev-2").await, "10.0.0.3:9999");
+    }
 }
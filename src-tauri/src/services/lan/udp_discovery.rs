@@ -0,0 +1,176 @@
+// src-tauri/src/services/lan/udp_discovery.rs
+//
+// UDP 广播发现：mDNS 组播被交换机或防火墙拦截时的后备方案。
+// 扫描方向 255.255.255.255 与本网段定向广播地址发送探测包，
+// 响应方回复自身设备信息，HTTP 地址取自回包的来源 IP。
+
+use crate::domain::lan::DiscoveredDevice;
+use crate::services::lan::http_api::SharedLanState;
+use crate::services::lan::trust_store::TrustStore;
+use local_ip_address::local_ip;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+pub const DISCOVERY_PORT: u16 = 9998;
+const DISCOVERY_MAGIC: &[u8] = b"PILAUNCHER_DISCOVER_V1";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiscoveryReply {
+    device_id: String,
+    device_name: String,
+    port: u16,
+    public_key: String,
+}
+
+/// 启动 UDP 发现响应方，端口被占用时只打印日志
+pub async fn start_responder(state: Arc<SharedLanState>, config_dir: PathBuf, http_port: u16) {
+    let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            println!("[UDP 发现] 无法监听端口 {}: {}", DISCOVERY_PORT, e);
+            return;
+        }
+    };
+    println!("[UDP 发现] 正在监听端口 {}", DISCOVERY_PORT);
+
+    let mut buf = [0_u8; 256];
+    loop {
+        let (len, from) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            Err(e) => {
+                println!("[UDP 发现] 接收失败: {}", e);
+                continue;
+            }
+        };
+        if buf[..len] != *DISCOVERY_MAGIC {
+            continue;
+        }
+
+        // 每次重新读取身份，密钥轮换后立即回复新公钥
+        let identity = TrustStore::get_or_create_identity(&config_dir);
+        let current_info = state.current_device_info.lock().unwrap().clone();
+        let reply = DiscoveryReply {
+            device_id: if !current_info.device_id.trim().is_empty() {
+                current_info.device_id
+            } else {
+                identity.device_id
+            },
+            device_name: if !current_info.device_name.trim().is_empty() {
+                current_info.device_name
+            } else {
+                identity.device_name
+            },
+            port: http_port,
+            public_key: identity.public_key_b64,
+        };
+        let Ok(bytes) = serde_json::to_vec(&reply) else {
+            continue;
+        };
+        if let Err(e) = socket.send_to(&bytes, from).await {
+            println!("[UDP 发现] 回复 {} 失败: {}", from, e);
+        }
+    }
+}
+
+/// 本网段 /24 的定向广播地址，部分路由器会丢弃 255.255.255.255
+fn directed_broadcast(ip: Ipv4Addr) -> Ipv4Addr {
+    let [a, b, c, _] = ip.octets();
+    Ipv4Addr::new(a, b, c, 255)
+}
+
+fn parse_reply(bytes: &[u8], from: SocketAddr) -> Option<DiscoveredDevice> {
+    let reply = serde_json::from_slice::<DiscoveryReply>(bytes).ok()?;
+    if reply.device_id.trim().is_empty() || reply.port == 0 {
+        return None;
+    }
+    Some(DiscoveredDevice {
+        device_id: reply.device_id,
+        device_name: reply.device_name,
+        ip: from.ip().to_string(),
+        port: reply.port,
+        public_key: reply.public_key,
+    })
+}
+
+/// 广播探测并收集 seconds 秒内的回复，按设备 ID 去重
+pub async fn scan_for_seconds(seconds: u64) -> Result<Vec<DiscoveredDevice>, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .await
+        .map_err(|e| format!("创建 UDP 套接字失败: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("启用 UDP 广播失败: {}", e))?;
+
+    let mut targets = vec![Ipv4Addr::BROADCAST];
+    if let Ok(IpAddr::V4(ip)) = local_ip() {
+        targets.push(directed_broadcast(ip));
+    }
+    let mut sent = false;
+    for target in targets {
+        sent |= socket
+            .send_to(DISCOVERY_MAGIC, (target, DISCOVERY_PORT))
+            .await
+            .is_ok();
+    }
+    if !sent {
+        return Err("发送 UDP 广播失败".to_string());
+    }
+
+    let own_ip = local_ip().ok();
+    let mut devices: Vec<DiscoveredDevice> = Vec::new();
+    let mut buf = [0_u8; 2048];
+    let deadline = tokio::time::sleep(Duration::from_secs(seconds));
+    tokio::pin!(deadline);
+
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            received = socket.recv_from(&mut buf) => {
+                let Ok((len, from)) = received else {
+                    continue;
+                };
+                if Some(from.ip()) == own_ip {
+                    continue;
+                }
+                if let Some(device) = parse_reply(&buf[..len], from) {
+                    if !devices.iter().any(|item| item.device_id == device.device_id) {
+                        devices.push(device);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(devices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reply_uses_sender_address_and_rejects_garbage() {
+        let from: SocketAddr = "192.168.3.7:9998".parse().unwrap();
+        let bytes = br#"{"deviceId":"dev-1","deviceName":"Desk","port":9999,"publicKey":"pk"}"#;
+        let device = parse_reply(bytes, from).unwrap();
+        assert_eq!(device.ip, "192.168.3.7");
+        assert_eq!(device.port, 9999);
+        assert_eq!(device.device_id, "dev-1");
+
+        assert!(parse_reply(b"not json", from).is_none());
+        assert!(parse_reply(
+            br#"{"deviceId":"","deviceName":"","port":9999,"publicKey":""}"#,
+            from
+        )
+        .is_none());
+        assert_eq!(
+            directed_broadcast(Ipv4Addr::new(10, 0, 12, 34)),
+            Ipv4Addr::new(10, 0, 12, 255)
+        );
+    }
+}
@@ -31,6 +31,7 @@ impl AuthService {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             AccountType::Authlib => AuthSession {
                 player_name: account.username,
@@ -39,6 +40,7 @@ impl AuthService {
                 user_type: "mojang".to_string(),
                 authlib_api_root: account.authlib_api_root,
                 authlib_injector_jar: None,
+                authlib_prefetched: account.authlib_prefetched,
             },
             AccountType::Offline => {
                 AuthSession {
@@ -49,6 +51,7 @@ impl AuthService {
                     user_type: "legacy".to_string(), // 必须纯小写
                     authlib_api_root: None,
                     authlib_injector_jar: None,
+                    authlib_prefetched: None,
                 }
             }
         }
@@ -764,6 +764,13 @@ impl LaunchCommandBuilder {
             self.auth.authlib_api_root.as_ref(),
         ) {
             final_args.push(format!("-javaagent:{}={}", jar_path, api_root));
+            // 预取的皮肤站元数据，免去 authlib-injector 启动时再请求一次 API
+            if let Some(prefetched) = self.auth.authlib_prefetched.as_ref() {
+                final_args.push(format!(
+                    "-Dauthlibinjector.yggdrasil.prefetched={}",
+                    prefetched
+                ));
+            }
         }
 
         final_args.extend(self.config.custom_jvm_args.clone());
@@ -994,6 +1001,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.21.1",
             "neoforge-21.1.224",
@@ -1211,6 +1219,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.21.1",
             "neoforge-21.1.224",
@@ -1264,6 +1273,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.12.2",
             "1.12.2",
@@ -1318,6 +1328,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.12.2",
             "1.12.2",
@@ -1410,6 +1421,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.21.1",
             "neoforge-21.1.224",
@@ -1480,6 +1492,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.21.1",
             "1.21.1",
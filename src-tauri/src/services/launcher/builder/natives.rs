@@ -187,6 +187,7 @@ mod tests {
                 user_type: "msa".to_string(),
                 authlib_api_root: None,
                 authlib_injector_jar: None,
+                authlib_prefetched: None,
             },
             "1.12.2",
             "1.12.2",
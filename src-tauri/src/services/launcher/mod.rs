@@ -230,6 +230,19 @@ fn collect_version_dirs_from_root(root: &Path) -> Vec<PathBuf> {
     dirs
 }
 
+/// 第三方路径只有能找到版本 json（版本目录或带 versions 的 .minecraft）时才作为启动元数据根；
+/// MultiMC/Prism 导入的只是游戏目录，版本、库与 natives 都由本机 runtime 提供
+fn resolve_third_party_root(third_party_path: &Path) -> Option<PathBuf> {
+    if collect_version_dirs_from_root(third_party_path)
+        .iter()
+        .any(|dir| read_version_json_from_dir(dir).is_some())
+    {
+        Some(third_party_path.to_path_buf())
+    } else {
+        None
+    }
+}
+
 fn loader_metadata_matches(
     requested_mc: &str,
     requested_loader_type: &str,
@@ -342,7 +355,31 @@ impl LauncherService {
         // 🌟 核心修复：同步修改游戏目录下的 options.txt，防止游戏内历史设置覆盖启动器设置
         patch_options_txt(&game_dir, resolved_config.fullscreen);
         let is_authlib_account = account.account_type == AccountType::Authlib;
+        // 离线账号开启局域网皮肤服务时，改走 authlib-injector 指向本机或房主的皮肤服务
+        let lan_skin_session = if account.account_type == AccountType::Offline {
+            match crate::services::lan::skin_server::prepare_offline_session(app, &account).await {
+                Ok(session) => session,
+                Err(error) => {
+                    let message =
+                        format!("[WARN] 局域网皮肤服务不可用，按普通离线模式启动: {}", error);
+                    println!("[Launcher] {}", message);
+                    let _ = app.emit("game-log", message);
+                    None
+                }
+            }
+        } else {
+            None
+        };
         let mut auth_session = AuthService::build_session(account, &runtime_dir);
+        if let Some((api_root, prefetched)) = lan_skin_session {
+            let injector_jar = ensure_authlib_injector(&runtime_dir)
+                .await
+                .map_err(AppError::Generic)?;
+            auth_session.user_type = "mojang".to_string();
+            auth_session.authlib_api_root = Some(api_root);
+            auth_session.authlib_prefetched = Some(prefetched);
+            auth_session.authlib_injector_jar = Some(injector_jar.to_string_lossy().to_string());
+        }
         if is_authlib_account {
             if auth_session
                 .authlib_api_root
@@ -369,13 +406,10 @@ impl LauncherService {
             _ => LoaderType::Vanilla,
         };
 
-        let mut third_party_root = None;
-        if let Some(tp_path) = &instance_cfg.third_party_path {
-            let tp_pathbuf = PathBuf::from(tp_path);
-            if tp_pathbuf.exists() {
-                third_party_root = Some(tp_pathbuf);
-            }
-        }
+        let third_party_root = instance_cfg
+            .third_party_path
+            .as_deref()
+            .and_then(|tp_path| resolve_third_party_root(Path::new(tp_path)));
 
         let target_version_id = discover_launch_version_from_metadata(
             &runtime_dir,
@@ -581,6 +615,12 @@ Module Path Entries: {}\n\
         if let Some(pid) = child.id() {
             crate::commands::launcher_cmd::CURRENT_GAME_PID
                 .store(pid, std::sync::atomic::Ordering::SeqCst);
+            // 运行期间的定时存档备份，进程退出后自动停止
+            crate::services::instance::save_manager::SaveManagerService::start_periodic_backups(
+                app.clone(),
+                instance_id.to_string(),
+                pid,
+            );
         }
 
         // 🌟 记录游戏时长：启动会话
@@ -710,6 +750,7 @@ mod tests {
             modpack_uuid: None,
             modpack_version: None,
             modpack_source: None,
+            modpack_version_id: None,
         }
     }
 
@@ -755,6 +796,28 @@ mod tests {
         let _ = std::fs::remove_dir_all(root);
     }
 
+    #[test]
+    fn third_party_root_requires_version_metadata() {
+        let root = unique_test_root("third-party-root");
+        let mmc_game_dir = root.join("instances").join("Pack").join(".minecraft");
+        std::fs::create_dir_all(mmc_game_dir.join("mods")).unwrap();
+        assert_eq!(resolve_third_party_root(&mmc_game_dir), None);
+
+        let version_dir = root.join(".minecraft").join("versions").join("1.20.1-Fabric");
+        std::fs::create_dir_all(&version_dir).unwrap();
+        std::fs::write(version_dir.join("1.20.1-Fabric.json"), "{}").unwrap();
+        assert_eq!(
+            resolve_third_party_root(&version_dir),
+            Some(version_dir.clone())
+        );
+        assert_eq!(
+            resolve_third_party_root(&root.join(".minecraft")),
+            Some(root.join(".minecraft"))
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
     #[test]
     fn discover_launch_version_falls_back_to_known_loader_folder() {
         let root = unique_test_root("metadata-fallback");
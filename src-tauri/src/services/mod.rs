@@ -19,6 +19,7 @@ pub mod minecraft_service; // ✅ 新增 minecraft 模块
 pub mod modpack_service; // ✅ 新增 modpack 模块
 pub mod playtime;
 pub mod qrcode_service;
+pub mod remote_store; // 同步用的远端存储抽象（WebDAV / S3 / 本地文件夹）
 pub mod resource_service; // ✅ 新增 resource 模块
 pub mod runtime_service; // ✅ 新增 launcher 模块
 pub mod social_service;
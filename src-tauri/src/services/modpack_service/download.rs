@@ -14,7 +14,7 @@ use super::logic::sanitize_instance_id;
 use super::ops::resolve_base_dir;
 use super::orchestrator::{execute_import, execute_import_with_logger};
 
-fn build_modpack_download_client(dl_settings: &DownloadSettings) -> Result<Client, String> {
+pub(super) fn build_modpack_download_client(dl_settings: &DownloadSettings) -> Result<Client, String> {
     let mut builder = Client::builder()
         .user_agent("PiLauncher/1.0 (Modpack)")
         .connect_timeout(Duration::from_secs(dl_settings.timeout.max(1)));
@@ -308,6 +308,29 @@ pub fn download_and_import_modpack<R: Runtime>(
         };
         deployment_cancel::unregister(&instance_id);
 
+        if result.is_ok() {
+            match super::remote::record_remote_source(&app, &instance_id, &normalized_url).await {
+                Ok(true) => {
+                    if let Some(logger) = &logger {
+                        logger
+                            .info("DOWNLOAD_MODPACK", "Linked instance to remote modpack source")
+                            .await;
+                    }
+                }
+                Ok(false) => {}
+                Err(error) => {
+                    if let Some(logger) = &logger {
+                        logger
+                            .warn(
+                                "DOWNLOAD_MODPACK",
+                                format!("Failed to record remote modpack source: {}", error),
+                            )
+                            .await;
+                    }
+                }
+            }
+        }
+
         if let Err(error) = result {
             eprintln!("Modpack deployment failed: {}", error);
             let _ = app.emit(
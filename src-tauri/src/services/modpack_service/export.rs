@@ -2,10 +2,21 @@ use crate::domain::instance::InstanceConfig;
 use crate::domain::mod_manifest::{mod_manifest_key, ModManifestEntry};
 use crate::domain::modpack::{
     PiPackManifest, PiPackMinecraftInfo, PiPackModEntry, PiPackPackageInfo, PIPACK_FORMAT_VERSION,
-    PIPACK_MANIFEST_FILE, PIPACK_OVERRIDES_DIR,
+    PIPACK_MANIFEST_FILE, PIPACK_OVERRIDES_DIR, PIPACK_SIGNATURE_FILE,
 };
 use crate::services::config_service::ConfigService;
+use crate::services::import_service::multimc_service::{
+    self, MmcInstanceSettings, MMC_GAME_DIR, MMC_INSTANCE_CFG, MMC_PACK_FILE,
+};
 use crate::services::instance::mod_manifest_service::ModManifestService;
+use crate::domain::lan::DeviceIdentity;
+use crate::services::lan::trust_store::TrustStore;
+use crate::services::modpack_service::pipack_signing;
+use crate::services::modpack_service::packwiz::{
+    self, PackwizCurseForgeUpdate, PackwizDownload, PackwizIndexRef, PackwizModFile,
+    PackwizModrinthUpdate, PackwizPack, PackwizUpdate, PACKWIZ_CURSEFORGE_MODE,
+    PACKWIZ_INDEX_FILE, PACKWIZ_PACK_FILE,
+};
 use chrono::Utc;
 use regex::Regex;
 use reqwest::Client;
@@ -36,7 +47,7 @@ pub struct ExportConfig {
     pub version: String,
     pub author: String,
     pub description: String,
-    pub format: String, // "zip", "curseforge", "mrpack", "pipack"
+    pub format: String, // "zip", "curseforge", "mrpack", "pipack", "packwiz", "prism"
     pub manifest_mode: bool,
     pub include_mods: bool,
     pub include_configs: bool,
@@ -45,6 +56,9 @@ pub struct ExportConfig {
     pub include_saves: bool,
     pub additional_paths: Vec<String>,
     pub output_path: String,
+    /// 仅 pipack：用本机局域网身份签名清单
+    #[serde(default)]
+    pub sign: bool,
 }
 
 #[derive(Default, Clone)]
@@ -64,6 +78,7 @@ struct PlannedArchiveFile {
 #[derive(Default)]
 struct ExportArtifacts {
     pipack_manifest: Option<PiPackManifest>,
+    pipack_signer: Option<DeviceIdentity>,
     skipped_files: HashSet<String>,
     curseforge_files: Vec<CurseForgeManifestFileReference>,
     mrpack_files: Vec<MrpackManifestFile>,
@@ -143,7 +158,16 @@ pub async fn execute_export<R: Runtime>(
         },
     );
 
-    let artifacts = prepare_export_artifacts(app, &instance_dir, &instance_meta, &config).await?;
+    if config.format == "packwiz" {
+        return export_packwiz(app, &instance_dir, &instance_meta, &config).await;
+    }
+
+    let mut artifacts =
+        prepare_export_artifacts(app, &instance_dir, &instance_meta, &config).await?;
+    if config.format == "pipack" && config.sign {
+        let config_dir = PathBuf::from(&base_path_str).join("config");
+        artifacts.pipack_signer = Some(TrustStore::get_or_create_identity(&config_dir));
+    }
     let files_to_pack = collect_files_to_pack(
         &instance_dir,
         &config,
@@ -158,6 +182,7 @@ pub async fn execute_export<R: Runtime>(
         .unix_permissions(0o755);
 
     let total_files = std::cmp::max(files_to_pack.len() as u64, 1);
+    let mut integrity = Vec::new();
     for (index, planned) in files_to_pack.iter().enumerate() {
         zip.start_file(planned.archive_path.as_str(), options)
             .map_err(|e| e.to_string())?;
@@ -166,6 +191,9 @@ pub async fn execute_export<R: Runtime>(
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
         zip.write_all(&buffer).map_err(|e| e.to_string())?;
+        if artifacts.pipack_manifest.is_some() {
+            integrity.push(pipack_signing::file_hash(&planned.archive_path, &buffer));
+        }
 
         let _ = app.emit(
             "export-progress",
@@ -178,6 +206,9 @@ pub async fn execute_export<R: Runtime>(
         );
     }
 
+    if let Some(manifest) = artifacts.pipack_manifest.as_mut() {
+        manifest.integrity = integrity;
+    }
     write_export_manifest(&mut zip, options, &config, &instance_meta, artifacts)?;
 
     zip.finish().map_err(|e| e.to_string())?;
@@ -195,6 +226,273 @@ pub async fn execute_export<R: Runtime>(
     Ok(())
 }
 
+/// packwiz is a directory format, so `output_path` is treated as the repository root.
+async fn export_packwiz<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_dir: &Path,
+    instance_meta: &InstanceConfig,
+    config: &ExportConfig,
+) -> Result<(), String> {
+    let output_dir = PathBuf::from(&config.output_path);
+    prepare_packwiz_output_dir(&output_dir)?;
+
+    let mut metafiles = Vec::new();
+    let mut metafile_names = HashSet::new();
+    let mut skipped_files = HashSet::new();
+    if config.include_mods && config.manifest_mode {
+        let client = build_export_http_client(app)?;
+        let exportable_mods = load_exportable_mod_files(instance_dir)?;
+        let total = std::cmp::max(exportable_mods.len() as u64, 1);
+        for (index, item) in exportable_mods.iter().enumerate() {
+            let _ = app.emit(
+                "export-progress",
+                ExportProgress {
+                    current: (index + 1) as u64,
+                    total,
+                    message: format!("Resolving {}", item.file_name),
+                    stage: "RESOLVING".to_string(),
+                },
+            );
+            if item.file_name.ends_with(".disabled") {
+                continue;
+            }
+            let Some(mod_file) = build_packwiz_metafile(&client, item).await else {
+                continue;
+            };
+            metafiles.push((
+                PathBuf::from("mods").join(packwiz::unique_packwiz_metafile_name(
+                    &item.file_name,
+                    &mut metafile_names,
+                )),
+                mod_file,
+            ));
+            skipped_files.insert(item.relative_path_str.clone());
+        }
+    }
+
+    let files_to_pack = collect_files_to_pack(instance_dir, config, Some(""), &skipped_files)?;
+    let total_files = std::cmp::max(files_to_pack.len() as u64, 1);
+    let mut plain_files = Vec::with_capacity(files_to_pack.len());
+    for (index, planned) in files_to_pack.iter().enumerate() {
+        plain_files.push(PathBuf::from(&planned.archive_path));
+        let target = output_dir.join(&planned.archive_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(&planned.source_path, &target).map_err(|e| e.to_string())?;
+
+        let _ = app.emit(
+            "export-progress",
+            ExportProgress {
+                current: (index + 1) as u64,
+                total: total_files,
+                message: format!("Copying {:?}", planned.relative_path),
+                stage: "PACKING".to_string(),
+            },
+        );
+    }
+
+    let mut versions = std::collections::BTreeMap::new();
+    versions.insert("minecraft".to_string(), instance_meta.mc_version.clone());
+    let loader = instance_meta.loader.r#type.trim().to_ascii_lowercase();
+    if !loader.is_empty() && loader != "vanilla" {
+        versions.insert(loader, instance_meta.loader.version.clone());
+    }
+    let pack = PackwizPack {
+        name: config.name.clone(),
+        author: Some(config.author.clone()).filter(|value| !value.trim().is_empty()),
+        version: Some(config.version.clone()).filter(|value| !value.trim().is_empty()),
+        description: Some(config.description.clone()).filter(|value| !value.trim().is_empty()),
+        pack_format: None,
+        index: PackwizIndexRef {
+            file: PACKWIZ_INDEX_FILE.to_string(),
+            hash_format: String::new(),
+            hash: String::new(),
+        },
+        versions,
+    };
+    packwiz::write_packwiz_pack(&output_dir, pack, &metafiles, &plain_files)?;
+
+    let _ = app.emit(
+        "export-progress",
+        ExportProgress {
+            current: 100,
+            total: 100,
+            message: "Export completed successfully.".to_string(),
+            stage: "DONE".to_string(),
+        },
+    );
+
+    Ok(())
+}
+
+/// Refuses to write into an unrelated non-empty directory; a previous packwiz export is
+/// cleared by removing the files its own index lists.
+fn prepare_packwiz_output_dir(output_dir: &Path) -> Result<(), String> {
+    if !output_dir.exists() {
+        return fs::create_dir_all(output_dir).map_err(|e| e.to_string());
+    }
+
+    let is_empty = fs::read_dir(output_dir)
+        .map_err(|e| e.to_string())?
+        .next()
+        .is_none();
+    if is_empty {
+        return Ok(());
+    }
+    if !output_dir.join(PACKWIZ_PACK_FILE).is_file() {
+        return Err(format!(
+            "Output directory is not empty and is not a packwiz pack: {}",
+            output_dir.display()
+        ));
+    }
+
+    let bundle = packwiz::load_local_pack(output_dir)?;
+    let index_dir = bundle.index_dir();
+    for (relative_path, _) in &bundle.mods {
+        let _ = fs::remove_file(index_dir.join(relative_path));
+    }
+    for relative_path in &bundle.overrides {
+        let _ = fs::remove_file(index_dir.join(relative_path));
+    }
+    let _ = fs::remove_file(output_dir.join(&bundle.pack.index.file));
+    Ok(())
+}
+
+async fn build_packwiz_metafile(
+    client: &Client,
+    item: &ExportableModFile,
+) -> Option<PackwizModFile> {
+    let display_name = parse_jar_metadata(&item.path).name.unwrap_or_else(|| {
+        item.file_name
+            .trim_end_matches(".disabled")
+            .trim_end_matches(".jar")
+            .to_string()
+    });
+
+    for (platform, project_id, file_id) in resolve_platform_references(&item.manifest_entry) {
+        match platform.as_str() {
+            "modrinth" => {
+                // A failed lookup only costs this mod its metafile; it is exported as a plain file.
+                let Ok(version) = fetch_modrinth_export_version(client, &file_id).await else {
+                    continue;
+                };
+                if version.project_id != project_id {
+                    continue;
+                }
+                let Some(remote_file) = select_modrinth_export_file(&version, item) else {
+                    continue;
+                };
+                if !remote_file.hashes.get("sha1").is_some_and(|value| {
+                    value.eq_ignore_ascii_case(&item.manifest_entry.hash.value)
+                }) {
+                    continue;
+                }
+                let (hash_format, hash) = match remote_file.hashes.get("sha512") {
+                    Some(sha512) => ("sha512", sha512.clone()),
+                    None => ("sha1", item.manifest_entry.hash.value.clone()),
+                };
+
+                return Some(PackwizModFile {
+                    name: display_name,
+                    filename: remote_file.filename.clone(),
+                    side: None,
+                    download: PackwizDownload {
+                        url: Some(remote_file.url.clone()),
+                        hash_format: hash_format.to_string(),
+                        hash,
+                        mode: None,
+                    },
+                    update: Some(PackwizUpdate {
+                        modrinth: Some(PackwizModrinthUpdate {
+                            mod_id: project_id,
+                            version: file_id,
+                        }),
+                        curseforge: None,
+                    }),
+                });
+            }
+            "curseforge" => {
+                let (Ok(project_id), Ok(file_id)) =
+                    (project_id.parse::<u64>(), file_id.parse::<u64>())
+                else {
+                    continue;
+                };
+
+                return Some(PackwizModFile {
+                    name: display_name,
+                    filename: item.file_name.clone(),
+                    side: None,
+                    download: PackwizDownload {
+                        url: None,
+                        hash_format: "sha1".to_string(),
+                        hash: item.manifest_entry.hash.value.clone(),
+                        mode: Some(PACKWIZ_CURSEFORGE_MODE.to_string()),
+                    },
+                    update: Some(PackwizUpdate {
+                        modrinth: None,
+                        curseforge: Some(PackwizCurseForgeUpdate {
+                            file_id,
+                            project_id,
+                        }),
+                    }),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The explicit manifest source comes first, followed by any scanned platform matches.
+fn resolve_platform_references(entry: &ModManifestEntry) -> Vec<(String, String, String)> {
+    let mut references = Vec::new();
+    if has_platform_reference(entry) {
+        references.push((
+            entry
+                .source
+                .platform
+                .as_deref()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase(),
+            entry.source.project_id.clone().unwrap_or_default(),
+            entry.source.file_id.clone().unwrap_or_default(),
+        ));
+    }
+
+    for platform in ["modrinth", "curseforge"] {
+        let Some(platform_match) = entry.matched_platforms.get(platform) else {
+            continue;
+        };
+        let (Some(project_id), Some(file_id)) = (
+            platform_match
+                .project_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty()),
+            platform_match
+                .file_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty()),
+        ) else {
+            continue;
+        };
+        let reference = (
+            platform.to_string(),
+            project_id.to_string(),
+            file_id.to_string(),
+        );
+        if !references.contains(&reference) {
+            references.push(reference);
+        }
+    }
+
+    references
+}
+
 fn load_instance_meta(
     instance_dir: &Path,
     config: &ExportConfig,
@@ -242,6 +540,7 @@ fn load_instance_meta(
             modpack_uuid: None,
             modpack_version: None,
             modpack_source: None,
+            modpack_version_id: None,
         }),
     )
 }
@@ -249,6 +548,8 @@ fn load_instance_meta(
 fn resolve_overrides_prefix(format: &str) -> Option<String> {
     match format {
         "zip" => None,
+        "packwiz" => Some(String::new()),
+        "prism" => Some(format!("{}/", MMC_GAME_DIR)),
         "curseforge" | "mrpack" | "pipack" => Some(format!("{}/", PIPACK_OVERRIDES_DIR)),
         _ => Some(format!("{}/", PIPACK_OVERRIDES_DIR)),
     }
@@ -381,6 +682,8 @@ fn prepare_pipack_manifest(
         overrides: PIPACK_OVERRIDES_DIR.to_string(),
         mods,
         server: instance_meta.server_binding.clone(),
+        // 打包时逐个文件计算后填入
+        integrity: Vec::new(),
     };
 
     Ok((Some(manifest), skip_mods))
@@ -782,6 +1085,34 @@ fn write_export_manifest(
             zip.write_all(index_str.as_bytes())
                 .map_err(|e| e.to_string())?;
         }
+        "prism" => {
+            let settings = MmcInstanceSettings {
+                min_memory: Some(instance_meta.memory.min),
+                max_memory: Some(instance_meta.memory.max),
+                jvm_args: instance_meta
+                    .jvm_args
+                    .clone()
+                    .filter(|value| !value.trim().is_empty()),
+                java_path: None,
+                window_width: instance_meta.window_width,
+                window_height: instance_meta.window_height,
+            };
+            zip.start_file(MMC_INSTANCE_CFG, options)
+                .map_err(|e| e.to_string())?;
+            zip.write_all(multimc_service::render_instance_cfg(&config.name, &settings).as_bytes())
+                .map_err(|e| e.to_string())?;
+
+            let pack = multimc_service::build_mmc_pack(
+                &instance_meta.mc_version,
+                &instance_meta.loader.r#type,
+                &instance_meta.loader.version,
+            );
+            let pack_str = serde_json::to_string_pretty(&pack).map_err(|e| e.to_string())?;
+            zip.start_file(MMC_PACK_FILE, options)
+                .map_err(|e| e.to_string())?;
+            zip.write_all(pack_str.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
         "pipack" => {
             let manifest = artifacts
                 .pipack_manifest
@@ -792,6 +1123,16 @@ fn write_export_manifest(
                 .map_err(|e| e.to_string())?;
             zip.write_all(manifest_str.as_bytes())
                 .map_err(|e| e.to_string())?;
+
+            if let Some(identity) = &artifacts.pipack_signer {
+                let signature = pipack_signing::sign_manifest(manifest_str.as_bytes(), identity)?;
+                let signature_str =
+                    serde_json::to_string_pretty(&signature).map_err(|e| e.to_string())?;
+                zip.start_file(PIPACK_SIGNATURE_FILE, options)
+                    .map_err(|e| e.to_string())?;
+                zip.write_all(signature_str.as_bytes())
+                    .map_err(|e| e.to_string())?;
+            }
         }
         _ => {}
     }
@@ -12,6 +12,7 @@ pub enum ModpackSourceHint {
     Modrinth,
     CurseForge,
     PiPack,
+    Packwiz,
 }
 
 pub const CURSEFORGE_CLASS_MOD: u64 = 6;
@@ -108,6 +109,7 @@ pub fn build_instance_config(
         modpack_uuid: metadata.pack_uuid.as_ref().map(|id| id.to_string()),
         modpack_version: metadata.pack_version.clone(),
         modpack_source: Some(metadata.source.clone()),
+        modpack_version_id: None,
     }
 }
 
@@ -203,7 +205,7 @@ pub fn parse_pipack_metadata(contents: &str) -> Result<ModpackMetadata, String>
     let manifest: PiPackManifest = serde_json::from_str(contents)
         .map_err(|e| format!("Failed to parse PiPack manifest: {}", e))?;
 
-    if manifest.format_version != PIPACK_FORMAT_VERSION {
+    if manifest.format_version == 0 || manifest.format_version > PIPACK_FORMAT_VERSION {
         return Err(format!(
             "Unsupported PiPack format version: {}",
             manifest.format_version
@@ -0,0 +1,992 @@
+// src-tauri/src/services/modpack_service/merge.rs
+//! 整合包升级的三方合并。
+//!
+//! 三方分别为：基线（导入/上次升级时整合包自带的内容）、当前实例（用户修改后的状态）、
+//! 新版整合包。只有一方相对基线发生变化时自动采用该方的结果；双方都改且结果不同则记为冲突，
+//! 默认保留用户版本，并把新版文件暂存到 `piconfig/modpack_merge/` 供用户之后选择。
+use crate::domain::mod_manifest::{compute_sha1, mod_manifest_key, ModManifest, ModManifestEntry};
+use crate::domain::modpack::{
+    ModpackMergeAction, ModpackMergeChange, ModpackMergeConflict, ModpackMergeReport,
+    ModpackMergeTarget,
+};
+use crate::services::instance::mod_manifest_service::ModManifestService;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const PACK_BASELINE_FILE: &str = "piconfig/modpack_base.json";
+pub const MERGE_REPORT_FILE: &str = "piconfig/modpack_merge.json";
+pub const MERGE_STASH_DIR: &str = "piconfig/modpack_merge";
+const CONFIG_DIR: &str = "config";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedMod {
+    pub file_key: String,
+    pub hash: String,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// 整合包基线：记录整合包自身提供的模组与 config 文件哈希，不保存文件内容。
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackBaseline {
+    #[serde(default)]
+    pub pack_version: Option<String>,
+    #[serde(default)]
+    pub mods: BTreeMap<String, TrackedMod>,
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Unchanged,
+    Ours,
+    Theirs,
+    Conflict,
+}
+
+pub fn merge_entry<V: PartialEq>(
+    base: Option<&V>,
+    ours: Option<&V>,
+    theirs: Option<&V>,
+) -> MergeOutcome {
+    if ours == theirs {
+        return if ours == base {
+            MergeOutcome::Unchanged
+        } else {
+            MergeOutcome::Ours
+        };
+    }
+    if ours == base {
+        return MergeOutcome::Theirs;
+    }
+    if theirs == base {
+        return MergeOutcome::Ours;
+    }
+    MergeOutcome::Conflict
+}
+
+pub fn three_way_merge<V: PartialEq>(
+    base: &BTreeMap<String, V>,
+    ours: &BTreeMap<String, V>,
+    theirs: &BTreeMap<String, V>,
+) -> Vec<(String, MergeOutcome)> {
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect();
+    keys.into_iter()
+        .map(|key| {
+            let outcome = merge_entry(base.get(key), ours.get(key), theirs.get(key));
+            (key.clone(), outcome)
+        })
+        .collect()
+}
+
+/// 模组在三方之间的身份：优先使用平台项目 ID，这样换版本（文件名变化）仍视为同一模组。
+pub fn mod_identity(file_key: &str, entry: &ModManifestEntry) -> String {
+    match (&entry.source.platform, &entry.source.project_id) {
+        (Some(platform), Some(project_id))
+            if !platform.trim().is_empty() && !project_id.trim().is_empty() =>
+        {
+            format!("{}:{}", platform.trim().to_lowercase(), project_id.trim())
+        }
+        _ => file_key.to_string(),
+    }
+}
+
+pub fn tracked_mods(
+    manifest: &ModManifest,
+    mods_dir: &Path,
+    filter: impl Fn(&str, &ModManifestEntry) -> bool,
+) -> BTreeMap<String, TrackedMod> {
+    let mut mods = BTreeMap::new();
+    let mut keys: Vec<&String> = manifest.keys().collect();
+    keys.sort();
+    for file_key in keys {
+        let entry = &manifest[file_key];
+        if !filter(file_key, entry) {
+            continue;
+        }
+        let Some(file_name) = find_mod_file(mods_dir, file_key) else {
+            continue;
+        };
+        mods.insert(
+            mod_identity(file_key, entry),
+            TrackedMod {
+                file_key: file_key.clone(),
+                hash: entry.hash.value.clone(),
+                disabled: file_name.ends_with(".disabled"),
+            },
+        );
+    }
+    mods
+}
+
+/// 返回 mods 目录中与清单键对应的实际文件名（可能带 `.disabled` 后缀）。
+pub fn find_mod_file(mods_dir: &Path, file_key: &str) -> Option<String> {
+    let disabled = format!("{}.disabled", file_key);
+    [file_key.to_string(), disabled]
+        .into_iter()
+        .find(|name| mods_dir.join(name).is_file())
+}
+
+pub fn hash_config_tree(root: &Path) -> Result<BTreeMap<String, String>, String> {
+    let mut files = BTreeMap::new();
+    let config_dir = root.join(CONFIG_DIR);
+    if !config_dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in WalkDir::new(&config_dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let key = rel.to_string_lossy().replace('\\', "/");
+        files.insert(key, compute_sha1(entry.path())?);
+    }
+    Ok(files)
+}
+
+pub fn load_baseline(instance_root: &Path) -> Option<PackBaseline> {
+    let content = fs::read_to_string(instance_root.join(PACK_BASELINE_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_baseline(instance_root: &Path, baseline: &PackBaseline) -> Result<(), String> {
+    write_json(&instance_root.join(PACK_BASELINE_FILE), baseline)
+}
+
+/// 首次导入后调用：此时实例中的模组与 config 都来自整合包本身。
+pub fn record_pack_baseline(
+    instance_root: &Path,
+    pack_version: Option<String>,
+) -> Result<(), String> {
+    let mods_dir = instance_root.join("mods");
+    let manifest = ModManifestService::sync_from_mods_dir(
+        &mods_dir,
+        &instance_root.join("mod_manifest.json"),
+    )?;
+    let baseline = PackBaseline {
+        pack_version,
+        mods: tracked_mods(&manifest, &mods_dir, |_, entry| {
+            entry.source.kind == crate::domain::mod_manifest::ModSourceKind::ModpackDeployment
+        }),
+        files: hash_config_tree(instance_root)?,
+    };
+    save_baseline(instance_root, &baseline)
+}
+
+/// 旧版本导入的实例没有基线：视整合包部署的模组为未改动，config 则不做假设。
+pub fn legacy_baseline(manifest: &ModManifest, mods_dir: &Path) -> PackBaseline {
+    PackBaseline {
+        pack_version: None,
+        mods: tracked_mods(manifest, mods_dir, |_, entry| {
+            entry.source.kind == crate::domain::mod_manifest::ModSourceKind::ModpackDeployment
+        }),
+        files: BTreeMap::new(),
+    }
+}
+
+pub struct MergeSides<'a> {
+    pub instance_root: &'a Path,
+    /// 升级前 config 的副本所在根目录（包含 `config/`）
+    pub ours_root: &'a Path,
+    /// 新版整合包 overrides 解压根目录（包含 `config/`）
+    pub theirs_root: &'a Path,
+    /// 被替换掉的用户模组移动到这里以便回滚或手动找回
+    pub displaced_mods_dir: &'a Path,
+}
+
+pub fn apply_config_merge(
+    sides: &MergeSides<'_>,
+    base: &BTreeMap<String, String>,
+    ours: &BTreeMap<String, String>,
+    theirs: &BTreeMap<String, String>,
+    report: &mut ModpackMergeReport,
+) -> Result<(), String> {
+    let stash_root = sides.instance_root.join(MERGE_STASH_DIR).join("theirs");
+    for (path, outcome) in three_way_merge(base, ours, theirs) {
+        let ours_source = ours.get(&path).map(|_| sides.ours_root.join(&path));
+        let theirs_source = theirs.get(&path).map(|_| sides.theirs_root.join(&path));
+        let target = sides.instance_root.join(&path);
+
+        match outcome {
+            MergeOutcome::Unchanged => {
+                place_file(&target, ours_source.as_deref())?;
+            }
+            MergeOutcome::Ours => {
+                place_file(&target, ours_source.as_deref())?;
+                if base.get(&path) != ours.get(&path) {
+                    report.applied.push(change(
+                        ModpackMergeTarget::Config,
+                        &path,
+                        ModpackMergeAction::KeptUserChange,
+                    ));
+                }
+            }
+            MergeOutcome::Theirs => {
+                place_file(&target, theirs_source.as_deref())?;
+                let action = classify(ours.contains_key(&path), theirs.contains_key(&path));
+                report
+                    .applied
+                    .push(change(ModpackMergeTarget::Config, &path, action));
+            }
+            MergeOutcome::Conflict => {
+                place_file(&target, ours_source.as_deref())?;
+                place_file(&stash_root.join(&path), theirs_source.as_deref())?;
+                report.conflicts.push(ModpackMergeConflict {
+                    target: ModpackMergeTarget::Config,
+                    path: path.clone(),
+                    ours: ours.get(&path).map(|_| path.clone()),
+                    theirs: theirs.get(&path).map(|_| path.clone()),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 在导入新版整合包之后调用：此时 mods 目录同时包含用户原有文件和新版下载的文件，
+/// 按合并结果移除多余的一方。
+pub fn apply_mod_merge(
+    sides: &MergeSides<'_>,
+    base: &BTreeMap<String, TrackedMod>,
+    ours: &BTreeMap<String, TrackedMod>,
+    theirs: &BTreeMap<String, TrackedMod>,
+    report: &mut ModpackMergeReport,
+) -> Result<(), String> {
+    let mods_dir = sides.instance_root.join("mods");
+    let stash_dir = sides
+        .instance_root
+        .join(MERGE_STASH_DIR)
+        .join("theirs")
+        .join("mods");
+    let base_hashes = hashes(base);
+    let ours_hashes = hashes(ours);
+    let theirs_hashes = hashes(theirs);
+
+    for (identity, outcome) in three_way_merge(&base_hashes, &ours_hashes, &theirs_hashes) {
+        let ours_mod = ours.get(&identity);
+        let theirs_mod = theirs.get(&identity);
+
+        let keep = match outcome {
+            MergeOutcome::Theirs => theirs_mod,
+            _ => ours_mod,
+        };
+        let keep_key = keep.map(|m| m.file_key.as_str());
+
+        if let (Some(ours_mod), Some(theirs_mod)) = (ours_mod, theirs_mod) {
+            if ours_mod.file_key == theirs_mod.file_key && ours_mod.hash != theirs_mod.hash {
+                restore_overwritten_mod(sides, &ours_mod.file_key, outcome, &stash_dir)?;
+            }
+        }
+        if let Some(theirs_mod) = theirs_mod {
+            if Some(theirs_mod.file_key.as_str()) != keep_key {
+                let target = if outcome == MergeOutcome::Conflict {
+                    Some(stash_dir.as_path())
+                } else {
+                    None
+                };
+                displace_mod(&mods_dir, &theirs_mod.file_key, target)?;
+            }
+        }
+        if let Some(ours_mod) = ours_mod {
+            if Some(ours_mod.file_key.as_str()) != keep_key {
+                displace_mod(
+                    &mods_dir,
+                    &ours_mod.file_key,
+                    Some(sides.displaced_mods_dir),
+                )?;
+            }
+        }
+        // 用户禁用的模组在更新后保持禁用，同时清理导入时重新下载的启用副本
+        if let (Some(kept), Some(ours_mod)) = (keep, ours_mod) {
+            if ours_mod.disabled {
+                set_mod_disabled(&mods_dir, &kept.file_key)?;
+            }
+        }
+
+        match outcome {
+            MergeOutcome::Unchanged => {}
+            MergeOutcome::Ours => {
+                if base_hashes.get(&identity) != ours_hashes.get(&identity) {
+                    report.applied.push(change(
+                        ModpackMergeTarget::Mod,
+                        &identity,
+                        ModpackMergeAction::KeptUserChange,
+                    ));
+                }
+            }
+            MergeOutcome::Theirs => {
+                let action = classify(ours_mod.is_some(), theirs_mod.is_some());
+                report
+                    .applied
+                    .push(change(ModpackMergeTarget::Mod, &identity, action));
+            }
+            MergeOutcome::Conflict => {
+                report.conflicts.push(ModpackMergeConflict {
+                    target: ModpackMergeTarget::Mod,
+                    path: identity.clone(),
+                    ours: ours_mod.map(|m| m.file_key.clone()),
+                    theirs: theirs_mod.map(|m| m.file_key.clone()),
+                });
+            }
+        }
+    }
+
+    ModManifestService::sync_from_mods_dir(
+        &mods_dir,
+        &sides.instance_root.join("mod_manifest.json"),
+    )?;
+    Ok(())
+}
+
+/// 导入前把用户模组复制到 `ours_root/mods`：新版整合包中同名但内容不同的文件会在导入时
+/// 直接覆盖用户文件，合并时需要从这里取回用户版本。
+pub fn snapshot_mods(
+    mods_dir: &Path,
+    ours: &BTreeMap<String, TrackedMod>,
+    ours_root: &Path,
+) -> Result<(), String> {
+    for tracked in ours.values() {
+        if let Some(file_name) = find_mod_file(mods_dir, &tracked.file_key) {
+            place_file(
+                &ours_root.join("mods").join(&file_name),
+                Some(&mods_dir.join(&file_name)),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// 导入失败时把 config 与 mods 恢复到导入前的快照：删除导入新增的文件，
+/// 再从 `ours_root` 取回被覆盖的用户文件。
+pub fn restore_ours_snapshot(
+    instance_root: &Path,
+    ours_root: &Path,
+    ours_files: &BTreeMap<String, String>,
+    mods_before: &HashSet<String>,
+) -> Result<(), String> {
+    for key in hash_config_tree(instance_root)?.into_keys() {
+        if !ours_files.contains_key(&key) {
+            place_file(&instance_root.join(&key), None)?;
+        }
+    }
+    for key in ours_files.keys() {
+        place_file(&instance_root.join(key), Some(&ours_root.join(key)))?;
+    }
+
+    let mods_dir = instance_root.join("mods");
+    for file_name in list_mod_files(&mods_dir) {
+        if !mods_before.contains(&file_name) {
+            place_file(&mods_dir.join(&file_name), None)?;
+        }
+    }
+    for file_name in list_mod_files(&ours_root.join("mods")) {
+        place_file(
+            &mods_dir.join(&file_name),
+            Some(&ours_root.join("mods").join(&file_name)),
+        )?;
+    }
+    ModManifestService::sync_from_mods_dir(&mods_dir, &instance_root.join("mod_manifest.json"))?;
+    Ok(())
+}
+
+/// 列出 mods 目录顶层的文件名。
+pub fn list_mod_files(mods_dir: &Path) -> HashSet<String> {
+    fs::read_dir(mods_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_file())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 同名模组被导入覆盖后，按合并结果安置两个版本：
+/// 采用新版时用户文件移到 displaced 目录；保留用户版本时取回快照，冲突时新版另存到暂存目录。
+fn restore_overwritten_mod(
+    sides: &MergeSides<'_>,
+    file_key: &str,
+    outcome: MergeOutcome,
+    stash_dir: &Path,
+) -> Result<(), String> {
+    let mods_dir = sides.instance_root.join("mods");
+    let snapshot_dir = sides.ours_root.join("mods");
+    let Some(snapshot_name) = find_mod_file(&snapshot_dir, file_key) else {
+        return Ok(());
+    };
+    // 用户禁用的旧文件不会被覆盖，快照里已有同样的内容
+    if snapshot_name != file_key {
+        place_file(&mods_dir.join(&snapshot_name), None)?;
+    }
+    let snapshot = snapshot_dir.join(&snapshot_name);
+    match outcome {
+        MergeOutcome::Theirs => {
+            move_file(&snapshot, &sides.displaced_mods_dir.join(&snapshot_name))?;
+        }
+        MergeOutcome::Ours | MergeOutcome::Conflict => {
+            let target = (outcome == MergeOutcome::Conflict).then_some(stash_dir);
+            displace_mod(&mods_dir, file_key, target)?;
+            move_file(&snapshot, &mods_dir.join(&snapshot_name))?;
+        }
+        MergeOutcome::Unchanged => {}
+    }
+    Ok(())
+}
+
+pub fn load_merge_report(instance_root: &Path) -> Option<ModpackMergeReport> {
+    let content = fs::read_to_string(instance_root.join(MERGE_REPORT_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn save_merge_report(instance_root: &Path, report: &ModpackMergeReport) -> Result<(), String> {
+    write_json(&instance_root.join(MERGE_REPORT_FILE), report)
+}
+
+/// 清除上一次合并遗留的冲突记录与暂存文件
+pub fn clear_merge_state(instance_root: &Path) {
+    let _ = fs::remove_file(instance_root.join(MERGE_REPORT_FILE));
+    let _ = fs::remove_dir_all(instance_root.join(MERGE_STASH_DIR));
+}
+
+/// 解决单个冲突。`use_theirs` 为 true 时采用新版整合包的文件，否则保留当前文件。
+pub fn resolve_merge_conflict(
+    instance_root: &Path,
+    target: ModpackMergeTarget,
+    path: &str,
+    use_theirs: bool,
+    displaced_mods_dir: Option<&Path>,
+) -> Result<ModpackMergeReport, String> {
+    let mut report = load_merge_report(instance_root)
+        .ok_or_else(|| "No pending modpack merge for this instance".to_string())?;
+    let index = report
+        .conflicts
+        .iter()
+        .position(|c| c.target == target && c.path == path)
+        .ok_or_else(|| format!("Merge conflict not found: {}", path))?;
+    let conflict = report.conflicts.remove(index);
+    let stash_root = instance_root.join(MERGE_STASH_DIR).join("theirs");
+
+    match conflict.target {
+        ModpackMergeTarget::Config => {
+            let stashed = stash_root.join(&conflict.path);
+            if use_theirs {
+                let source = conflict.theirs.as_ref().map(|_| stashed.clone());
+                place_file(&instance_root.join(&conflict.path), source.as_deref())?;
+            }
+            let _ = fs::remove_file(&stashed);
+        }
+        ModpackMergeTarget::Mod => {
+            let mods_dir = instance_root.join("mods");
+            let stash_dir = stash_root.join("mods");
+            if use_theirs {
+                if let Some(ours_key) = &conflict.ours {
+                    displace_mod(&mods_dir, ours_key, displaced_mods_dir)?;
+                }
+                if let Some(theirs_key) = &conflict.theirs {
+                    if let Some(file_name) = find_mod_file(&stash_dir, theirs_key) {
+                        move_file(&stash_dir.join(&file_name), &mods_dir.join(&file_name))?;
+                    }
+                }
+                ModManifestService::sync_from_mods_dir(
+                    &mods_dir,
+                    &instance_root.join("mod_manifest.json"),
+                )?;
+            } else if let Some(theirs_key) = &conflict.theirs {
+                displace_mod(&stash_dir, theirs_key, None)?;
+            }
+        }
+    }
+
+    report.applied.push(change(
+        conflict.target,
+        &conflict.path,
+        if use_theirs {
+            classify(conflict.ours.is_some(), conflict.theirs.is_some())
+        } else {
+            ModpackMergeAction::KeptUserChange
+        },
+    ));
+
+    if report.conflicts.is_empty() {
+        clear_merge_state(instance_root);
+    } else {
+        save_merge_report(instance_root, &report)?;
+    }
+    Ok(report)
+}
+
+fn hashes(mods: &BTreeMap<String, TrackedMod>) -> BTreeMap<String, String> {
+    mods.iter()
+        .map(|(identity, m)| (identity.clone(), m.hash.clone()))
+        .collect()
+}
+
+fn change(
+    target: ModpackMergeTarget,
+    path: &str,
+    action: ModpackMergeAction,
+) -> ModpackMergeChange {
+    ModpackMergeChange {
+        target,
+        path: path.to_string(),
+        action,
+    }
+}
+
+fn classify(in_ours: bool, in_theirs: bool) -> ModpackMergeAction {
+    match (in_ours, in_theirs) {
+        (false, _) => ModpackMergeAction::Added,
+        (true, false) => ModpackMergeAction::Removed,
+        (true, true) => ModpackMergeAction::Updated,
+    }
+}
+
+/// 让目标路径的内容与来源一致；来源为 None 表示该文件最终不应存在。
+fn place_file(target: &Path, source: Option<&Path>) -> Result<(), String> {
+    match source {
+        Some(source) => {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(source, target)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+        None => {
+            if target.is_file() {
+                fs::remove_file(target)
+                    .map_err(|e| format!("Failed to remove {}: {}", target.display(), e))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 将模组文件（含 `.disabled` 变体）移出 mods 目录；没有目标目录时直接删除。
+fn displace_mod(mods_dir: &Path, file_key: &str, target_dir: Option<&Path>) -> Result<(), String> {
+    while let Some(file_name) = find_mod_file(mods_dir, file_key) {
+        let path = mods_dir.join(&file_name);
+        match target_dir {
+            Some(dir) => move_file(&path, &dir.join(&file_name))?,
+            None => fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?,
+        }
+    }
+    Ok(())
+}
+
+fn set_mod_disabled(mods_dir: &Path, file_key: &str) -> Result<(), String> {
+    let enabled = mods_dir.join(file_key);
+    let disabled = mods_dir.join(format!("{}.disabled", file_key));
+    if !enabled.is_file() {
+        return Ok(());
+    }
+    if disabled.is_file() {
+        fs::remove_file(&enabled).map_err(|e| e.to_string())
+    } else {
+        fs::rename(&enabled, &disabled).map_err(|e| e.to_string())
+    }
+}
+
+fn move_file(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if fs::rename(source, target).is_err() {
+        fs::copy(source, target)
+            .map_err(|e| format!("Failed to move {}: {}", source.display(), e))?;
+        fs::remove_file(source).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+pub fn copy_config_tree(source_root: &Path, target_root: &Path) -> Result<(), String> {
+    let source = source_root.join(CONFIG_DIR);
+    if !source.is_dir() {
+        return Ok(());
+    }
+    for entry in WalkDir::new(&source).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(rel) = entry.path().strip_prefix(source_root) else {
+            continue;
+        };
+        let target: PathBuf = target_root.join(rel);
+        place_file(&target, Some(entry.path()))?;
+    }
+    Ok(())
+}
+
+/// 判断清单条目是否由新版整合包声明（按文件名或 CurseForge 项目 ID）。
+pub fn is_declared_mod(
+    declared: &HashSet<String>,
+    file_key: &str,
+    entry: &ModManifestEntry,
+) -> bool {
+    declared.iter().any(|key| mod_manifest_key(key) == file_key)
+        || entry
+            .source
+            .project_id
+            .as_ref()
+            .map(|id| declared.contains(id))
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn temp_root(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", prefix, Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn map(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn tracked(file_key: &str, hash: &str) -> TrackedMod {
+        TrackedMod {
+            file_key: file_key.to_string(),
+            hash: hash.to_string(),
+            disabled: false,
+        }
+    }
+
+    #[test]
+    fn merge_entry_prefers_the_side_that_changed() {
+        let a = "a".to_string();
+        let b = "b".to_string();
+        let c = "c".to_string();
+        assert_eq!(
+            merge_entry(Some(&a), Some(&a), Some(&a)),
+            MergeOutcome::Unchanged
+        );
+        assert_eq!(
+            merge_entry(Some(&a), Some(&a), Some(&b)),
+            MergeOutcome::Theirs
+        );
+        assert_eq!(
+            merge_entry(Some(&a), Some(&b), Some(&a)),
+            MergeOutcome::Ours
+        );
+        assert_eq!(
+            merge_entry(Some(&a), Some(&b), Some(&b)),
+            MergeOutcome::Ours
+        );
+        assert_eq!(
+            merge_entry(Some(&a), Some(&b), Some(&c)),
+            MergeOutcome::Conflict
+        );
+        // 用户删除、整合包未改动：保持删除
+        assert_eq!(merge_entry(Some(&a), None, Some(&a)), MergeOutcome::Ours);
+        // 用户删除、整合包更新：冲突
+        assert_eq!(
+            merge_entry(Some(&a), None, Some(&b)),
+            MergeOutcome::Conflict
+        );
+        // 整合包新增
+        assert_eq!(merge_entry(None, None, Some(&a)), MergeOutcome::Theirs);
+        // 没有基线时双方不同即冲突
+        assert_eq!(
+            merge_entry(None, Some(&a), Some(&b)),
+            MergeOutcome::Conflict
+        );
+    }
+
+    #[test]
+    fn config_merge_applies_non_conflicting_changes_and_stashes_conflicts() {
+        let root = temp_root("pilauncher_merge_cfg");
+        let instance = root.join("instance");
+        let ours_root = root.join("ours");
+        let theirs_root = root.join("theirs");
+        for (dir, files) in [
+            (
+                &ours_root,
+                vec![
+                    ("config/user.toml", "user-edit"),
+                    ("config/both.toml", "ours"),
+                    ("config/keep.toml", "same"),
+                ],
+            ),
+            (
+                &theirs_root,
+                vec![
+                    ("config/user.toml", "base"),
+                    ("config/both.toml", "theirs"),
+                    ("config/keep.toml", "same"),
+                    ("config/new.toml", "new"),
+                ],
+            ),
+        ] {
+            for (rel, content) in files {
+                fs::create_dir_all(dir.join(rel).parent().unwrap()).unwrap();
+                fs::write(dir.join(rel), content).unwrap();
+            }
+        }
+        // 模拟导入后 config 被新版覆盖
+        copy_config_tree(&theirs_root, &instance).unwrap();
+
+        let base = map(&[
+            ("config/user.toml", "h-base"),
+            ("config/both.toml", "h-base"),
+            ("config/keep.toml", "h-same"),
+        ]);
+        let ours = map(&[
+            ("config/user.toml", "h-user"),
+            ("config/both.toml", "h-ours"),
+            ("config/keep.toml", "h-same"),
+        ]);
+        let theirs = map(&[
+            ("config/user.toml", "h-base"),
+            ("config/both.toml", "h-theirs"),
+            ("config/keep.toml", "h-same"),
+            ("config/new.toml", "h-new"),
+        ]);
+
+        let sides = MergeSides {
+            instance_root: &instance,
+            ours_root: &ours_root,
+            theirs_root: &theirs_root,
+            displaced_mods_dir: &root.join("displaced"),
+        };
+        let mut report = ModpackMergeReport::default();
+        apply_config_merge(&sides, &base, &ours, &theirs, &mut report).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(instance.join("config/user.toml")).unwrap(),
+            "user-edit"
+        );
+        assert_eq!(
+            fs::read_to_string(instance.join("config/new.toml")).unwrap(),
+            "new"
+        );
+        assert_eq!(
+            fs::read_to_string(instance.join("config/both.toml")).unwrap(),
+            "ours"
+        );
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].path, "config/both.toml");
+        save_merge_report(&instance, &report).unwrap();
+
+        let report = resolve_merge_conflict(
+            &instance,
+            ModpackMergeTarget::Config,
+            "config/both.toml",
+            true,
+            None,
+        )
+        .unwrap();
+        assert!(report.conflicts.is_empty());
+        assert_eq!(
+            fs::read_to_string(instance.join("config/both.toml")).unwrap(),
+            "theirs"
+        );
+        assert!(!instance.join(MERGE_REPORT_FILE).exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn mod_merge_keeps_user_additions_and_removals() {
+        let root = temp_root("pilauncher_merge_mods");
+        let instance = root.join("instance");
+        let mods = instance.join("mods");
+        let displaced = root.join("displaced");
+        fs::create_dir_all(&mods).unwrap();
+        // 导入后的 mods 目录：用户文件 + 新版下载的文件
+        for name in [
+            "lib-1.0.jar",
+            "lib-2.0.jar",
+            "extra.jar",
+            "dropped.jar",
+            "removed-by-user.jar",
+            "tweak.jar.disabled",
+            "tweak.jar",
+        ] {
+            fs::write(mods.join(name), name).unwrap();
+        }
+
+        let base: BTreeMap<String, TrackedMod> = [
+            ("modrinth:lib".to_string(), tracked("lib-1.0.jar", "l1")),
+            ("dropped.jar".to_string(), tracked("dropped.jar", "d")),
+            (
+                "removed-by-user.jar".to_string(),
+                tracked("removed-by-user.jar", "r"),
+            ),
+            ("tweak.jar".to_string(), tracked("tweak.jar", "t")),
+        ]
+        .into_iter()
+        .collect();
+        let ours: BTreeMap<String, TrackedMod> = [
+            ("modrinth:lib".to_string(), tracked("lib-1.0.jar", "l1")),
+            ("dropped.jar".to_string(), tracked("dropped.jar", "d")),
+            ("extra.jar".to_string(), tracked("extra.jar", "e")),
+            (
+                "tweak.jar".to_string(),
+                TrackedMod {
+                    disabled: true,
+                    ..tracked("tweak.jar", "t")
+                },
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let theirs: BTreeMap<String, TrackedMod> = [
+            ("modrinth:lib".to_string(), tracked("lib-2.0.jar", "l2")),
+            (
+                "removed-by-user.jar".to_string(),
+                tracked("removed-by-user.jar", "r"),
+            ),
+            ("tweak.jar".to_string(), tracked("tweak.jar", "t")),
+        ]
+        .into_iter()
+        .collect();
+
+        let sides = MergeSides {
+            instance_root: &instance,
+            ours_root: &root,
+            theirs_root: &root,
+            displaced_mods_dir: &displaced,
+        };
+        let mut report = ModpackMergeReport::default();
+        apply_mod_merge(&sides, &base, &ours, &theirs, &mut report).unwrap();
+
+        assert!(mods.join("lib-2.0.jar").exists());
+        assert!(!mods.join("lib-1.0.jar").exists());
+        assert!(displaced.join("lib-1.0.jar").exists());
+        assert!(mods.join("extra.jar").exists());
+        assert!(!mods.join("dropped.jar").exists());
+        assert!(!mods.join("removed-by-user.jar").exists());
+        assert!(mods.join("tweak.jar.disabled").exists());
+        assert!(!mods.join("tweak.jar").exists());
+        assert!(report.conflicts.is_empty());
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn mod_merge_recovers_user_files_overwritten_by_same_name_downloads() {
+        let root = temp_root("pilauncher_merge_same_name");
+        let instance = root.join("instance");
+        let mods = instance.join("mods");
+        let ours_root = root.join("ours");
+        let displaced = root.join("displaced");
+        fs::create_dir_all(&mods).unwrap();
+        for name in ["patched.jar", "clash.jar", "plain.jar"] {
+            fs::write(mods.join(name), format!("user {}", name)).unwrap();
+        }
+        let base: BTreeMap<String, TrackedMod> = [
+            ("patched.jar".to_string(), tracked("patched.jar", "p0")),
+            ("clash.jar".to_string(), tracked("clash.jar", "c0")),
+            ("plain.jar".to_string(), tracked("plain.jar", "n0")),
+        ]
+        .into_iter()
+        .collect();
+        let ours: BTreeMap<String, TrackedMod> = [
+            ("patched.jar".to_string(), tracked("patched.jar", "p-user")),
+            ("clash.jar".to_string(), tracked("clash.jar", "c-user")),
+            ("plain.jar".to_string(), tracked("plain.jar", "n0")),
+        ]
+        .into_iter()
+        .collect();
+        let theirs: BTreeMap<String, TrackedMod> = [
+            ("patched.jar".to_string(), tracked("patched.jar", "p0")),
+            ("clash.jar".to_string(), tracked("clash.jar", "c-new")),
+            ("plain.jar".to_string(), tracked("plain.jar", "n-new")),
+        ]
+        .into_iter()
+        .collect();
+
+        snapshot_mods(&mods, &ours, &ours_root).unwrap();
+        // 模拟导入：同名文件被新版覆盖
+        for name in ["patched.jar", "clash.jar", "plain.jar"] {
+            fs::write(mods.join(name), format!("pack {}", name)).unwrap();
+        }
+
+        let sides = MergeSides {
+            instance_root: &instance,
+            ours_root: &ours_root,
+            theirs_root: &root,
+            displaced_mods_dir: &displaced,
+        };
+        let mut report = ModpackMergeReport::default();
+        apply_mod_merge(&sides, &base, &ours, &theirs, &mut report).unwrap();
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(mods.join("patched.jar")), "user patched.jar");
+        assert_eq!(read(mods.join("clash.jar")), "user clash.jar");
+        let stashed = instance.join(MERGE_STASH_DIR).join("theirs/mods/clash.jar");
+        assert_eq!(read(stashed), "pack clash.jar");
+        assert_eq!(read(mods.join("plain.jar")), "pack plain.jar");
+        assert_eq!(read(displaced.join("plain.jar")), "user plain.jar");
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].path, "clash.jar");
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn restore_ours_snapshot_undoes_a_failed_import() {
+        let root = temp_root("pilauncher_merge_restore");
+        let instance = root.join("instance");
+        let mods = instance.join("mods");
+        let ours_root = root.join("ours");
+        fs::create_dir_all(&mods).unwrap();
+        fs::create_dir_all(instance.join("config")).unwrap();
+        fs::write(instance.join("config/user.toml"), "user").unwrap();
+        fs::write(mods.join("user.jar"), "user jar").unwrap();
+
+        let manifest =
+            ModManifestService::sync_from_mods_dir(&mods, &instance.join("mod_manifest.json"))
+                .unwrap();
+        let ours = tracked_mods(&manifest, &mods, |_, _| true);
+        let ours_files = hash_config_tree(&instance).unwrap();
+        let mods_before = list_mod_files(&mods);
+        copy_config_tree(&instance, &ours_root).unwrap();
+        snapshot_mods(&mods, &ours, &ours_root).unwrap();
+
+        // 模拟导入中途失败：覆盖了用户文件并留下新文件
+        fs::write(instance.join("config/user.toml"), "pack").unwrap();
+        fs::write(instance.join("config/new.toml"), "pack").unwrap();
+        fs::write(mods.join("user.jar"), "pack jar").unwrap();
+        fs::write(mods.join("new.jar"), "pack jar").unwrap();
+
+        restore_ours_snapshot(&instance, &ours_root, &ours_files, &mods_before).unwrap();
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(instance.join("config/user.toml")), "user");
+        assert!(!instance.join("config/new.toml").exists());
+        assert_eq!(read(mods.join("user.jar")), "user jar");
+        assert!(!mods.join("new.jar").exists());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
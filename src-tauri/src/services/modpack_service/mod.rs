@@ -2,14 +2,22 @@ mod download;
 pub mod export;
 mod logging;
 mod logic;
+pub mod merge;
 mod ops;
 mod orchestrator;
+pub mod packwiz;
+pub mod pipack_signing;
+pub mod remote;
 pub mod upgrade;
 pub mod rollback;
 
 pub use download::{download_and_import_modpack, start_import};
-pub use ops::parse_modpack;
+pub use ops::{parse_modpack, parse_modpack_location};
 pub use orchestrator::execute_import;
-pub use upgrade::{check_modpack_update, execute_modpack_upgrade};
+pub use upgrade::{
+    check_modpack_update, execute_modpack_upgrade, get_modpack_merge_conflicts,
+    resolve_modpack_merge_conflict,
+};
+pub use remote::{check_remote_updates, prepare_remote_update};
 pub use rollback::rollback_modpack_upgrade;
 
@@ -11,6 +11,7 @@ use super::logic::{
     normalize_override_dir, parse_curseforge_metadata, parse_modrinth_metadata,
     parse_pipack_metadata, ModpackSourceHint,
 };
+use super::packwiz::{self, PackwizLocation, PACKWIZ_PACK_FILE};
 
 pub fn resolve_base_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     let base_path_str = ConfigService::get_base_path(app)
@@ -55,7 +56,34 @@ pub fn detect_modpack_source<R: Read + Seek>(
     )
 }
 
+/// Detects the pack format of a path that may be an archive or a local packwiz repository.
+pub fn detect_modpack_location(path: &str) -> Result<ModpackSourceHint, String> {
+    if packwiz::is_packwiz_location(path) {
+        return Ok(ModpackSourceHint::Packwiz);
+    }
+    let mut archive = open_modpack_archive(path)?;
+    detect_modpack_source(&mut archive)
+}
+
+/// Resolves a local packwiz path (repository directory or its `pack.toml`) to the repository root.
+pub fn local_packwiz_root(path: &str) -> Result<PathBuf, String> {
+    match packwiz::resolve_packwiz_location(path) {
+        Some(PackwizLocation::Local(root)) => Ok(root),
+        Some(PackwizLocation::Remote(_)) => {
+            Err("Remote packwiz packs must be staged before use".to_string())
+        }
+        None => Err(format!("Not a packwiz pack: {}", path)),
+    }
+}
+
 pub fn parse_modpack(path: &str) -> Result<ModpackMetadata, String> {
+    if packwiz::is_packwiz_location(path) {
+        let root = local_packwiz_root(path)?;
+        let contents = fs::read_to_string(root.join(PACKWIZ_PACK_FILE))
+            .map_err(|e| format!("Failed to read packwiz pack.toml: {}", e))?;
+        return packwiz::parse_packwiz_metadata(&contents);
+    }
+
     let mut archive = open_modpack_archive(path)?;
     match detect_modpack_source(&mut archive)? {
         ModpackSourceHint::PiPack => {
@@ -70,7 +98,21 @@ pub fn parse_modpack(path: &str) -> Result<ModpackMetadata, String> {
             let contents = read_zip_entry_to_string(&mut archive, "manifest.json")?;
             parse_curseforge_metadata(&contents)
         }
+        ModpackSourceHint::Packwiz => Err("Unexpected packwiz archive".to_string()),
+    }
+}
+
+/// Like `parse_modpack`, but also accepts an HTTP(S) packwiz `pack.toml` URL.
+pub async fn parse_modpack_location(path: &str) -> Result<ModpackMetadata, String> {
+    if let Some(PackwizLocation::Remote(url)) = packwiz::resolve_packwiz_location(path) {
+        let client = reqwest::Client::builder()
+            .user_agent("PiLauncher/1.0 (Packwiz)")
+            .build()
+            .map_err(|e| e.to_string())?;
+        let contents = packwiz::fetch_remote_pack_toml(&client, &url).await?;
+        return packwiz::parse_packwiz_metadata(&contents);
     }
+    parse_modpack(path)
 }
 
 pub fn read_pipack_manifest(path: &str) -> Result<PiPackManifest, String> {
@@ -105,6 +147,11 @@ pub fn write_instance_config(instance_root: &Path, config: &InstanceConfig) -> R
 }
 
 pub fn extract_overrides(zip_path: &str, target_dir: &Path) -> Result<(), String> {
+    if packwiz::is_packwiz_location(zip_path) {
+        let bundle = packwiz::load_local_pack(&local_packwiz_root(zip_path)?)?;
+        return packwiz::extract_packwiz_overrides(&bundle, target_dir);
+    }
+
     let file = File::open(zip_path).map_err(|e| e.to_string())?;
     let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
 
@@ -29,13 +29,15 @@ use super::logic::{
     sanitize_instance_id, CurseForgeInstallTarget, ModpackSourceHint,
 };
 use super::ops::{
-    create_instance_layout, detect_modpack_source, extract_overrides, open_modpack_archive,
-    parse_modpack, read_pipack_manifest, read_zip_entry_to_string, resolve_base_dir,
+    create_instance_layout, detect_modpack_location, extract_overrides, local_packwiz_root,
+    open_modpack_archive, parse_modpack, read_pipack_manifest, read_zip_entry_to_string,
+    resolve_base_dir,
 };
+use super::packwiz::{self, PackwizLocation};
 
 #[derive(Deserialize)]
-struct CurseForgeEnvelope<T> {
-    data: T,
+pub(super) struct CurseForgeEnvelope<T> {
+    pub(super) data: T,
 }
 
 #[derive(Deserialize)]
@@ -97,7 +99,7 @@ fn should_track_mod_manifest(path: &Path) -> bool {
         && path.starts_with(Path::new("mods"))
 }
 
-fn extract_modrinth_source_ids(url: &str) -> Option<(String, String)> {
+pub(super) fn extract_modrinth_source_ids(url: &str) -> Option<(String, String)> {
     let sanitized = url
         .split_once('#')
         .map(|(value, _)| value)
@@ -162,7 +164,7 @@ fn finalize_imported_mod_manifest(
     Ok(())
 }
 
-fn resolve_curseforge_api_key() -> Option<String> {
+pub(super) fn resolve_curseforge_api_key() -> Option<String> {
     let from_vite = env::var("VITE_CURSEFORGE_API_KEY").ok();
     let from_plain = env::var("CURSEFORGE_API_KEY").ok();
     let from_baked = option_env!("CURSEFORGE_API_KEY")
@@ -254,7 +256,7 @@ fn percent_encode(input: &str) -> String {
     out
 }
 
-fn curseforge_edge_url(file_id: u64, file_name: &str) -> String {
+pub(super) fn curseforge_edge_url(file_id: u64, file_name: &str) -> String {
     let prefix = file_id / 1000;
     let suffix = file_id % 1000;
     let encoded = percent_encode(file_name);
@@ -707,6 +709,18 @@ async fn execute_import_inner<R: Runtime>(
         return Err("Cancelled".to_string());
     }
 
+    let staging_dir = packwiz_temp_root(base_dir, instance_id).join("pack");
+    let staged_packwiz_path = stage_remote_packwiz(app, zip_path, &staging_dir).await?;
+    if let Some(staged) = &staged_packwiz_path {
+        logger
+            .info(
+                "PACKWIZ",
+                format!("Remote packwiz pack {} staged at {}", zip_path, staged),
+            )
+            .await;
+    }
+    let zip_path = staged_packwiz_path.as_deref().unwrap_or(zip_path);
+
     logger
         .info("PARSE", format!("Parsing archive {}", zip_path))
         .await;
@@ -742,6 +756,34 @@ async fn execute_import_inner<R: Runtime>(
                 ),
             )
             .await;
+
+        let mut verification = super::pipack_signing::verify_pipack_archive(zip_path)?;
+        let db = app.state::<crate::services::db_service::AppDatabase>();
+        if let Err(e) = super::pipack_signing::resolve_signer_trust(&db.pool, &mut verification).await {
+            logger.warn("VERIFY", e).await;
+        }
+        logger
+            .info(
+                "VERIFY",
+                format!(
+                    "PiPack verification: signed={} signature_valid={} signer={:?} trusted={} verified_files={}",
+                    verification.signed,
+                    verification.signature_valid,
+                    verification.signer_device_name,
+                    verification.trusted,
+                    verification.verified_files
+                ),
+            )
+            .await;
+        if verification.is_tampered() {
+            return Err(format!(
+                "PiPack integrity check failed (signature_valid={}, modified={:?}, missing={:?}, unlisted={:?})",
+                verification.signature_valid,
+                verification.mismatched_files,
+                verification.missing_files,
+                verification.unlisted_files
+            ));
+        }
     }
 
     let effective_server_binding = server_binding.or_else(|| {
@@ -949,6 +991,13 @@ async fn execute_import_inner<R: Runtime>(
         return Err("Cancelled".to_string());
     }
 
+    // 记录整合包基线，供之后升级时做三方合并
+    if let Err(e) = super::merge::record_pack_baseline(&instance_root, metadata.pack_version.clone()) {
+        logger
+            .warn("BASELINE", format!("Failed to record modpack baseline: {}", e))
+            .await;
+    }
+
     let _ = app.emit(
         "instance-deployment-progress",
         DownloadProgressEvent {
@@ -977,6 +1026,7 @@ fn cleanup_modpack_artifacts(
         temp_root.join("curseforge").join(instance_id),
         temp_root.join("modrinth").join(instance_id),
         temp_root.join("pipack").join(instance_id),
+        packwiz_temp_root(base_dir, instance_id),
     ]
     .into_iter()
     .map(|path| {
@@ -991,6 +1041,35 @@ fn cleanup_modpack_artifacts(
     .collect()
 }
 
+/// Mirrors a remote packwiz `pack.toml` URL into `staging_dir` and returns the local path;
+/// local paths and archives return `None` and are used as-is.
+pub(crate) async fn stage_remote_packwiz<R: Runtime>(
+    app: &AppHandle<R>,
+    pack_path: &str,
+    staging_dir: &Path,
+) -> Result<Option<String>, String> {
+    let Some(location @ PackwizLocation::Remote(_)) = packwiz::resolve_packwiz_location(pack_path)
+    else {
+        return Ok(None);
+    };
+    let dl_settings = ConfigService::get_download_settings(app);
+    let client = Client::builder()
+        .user_agent("PiLauncher/1.0 (Packwiz)")
+        .connect_timeout(Duration::from_secs(dl_settings.timeout.max(1)))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let staged = packwiz::stage_packwiz_pack(&client, &location, staging_dir).await?;
+    Ok(Some(staged.to_string_lossy().to_string()))
+}
+
+fn packwiz_temp_root(base_dir: &Path, instance_id: &str) -> PathBuf {
+    base_dir
+        .join("temp")
+        .join("modpack")
+        .join("packwiz")
+        .join(instance_id)
+}
+
 async fn fetch_modpack_mods<R: Runtime>(
     app: &AppHandle<R>,
     zip_path: &str,
@@ -1000,8 +1079,7 @@ async fn fetch_modpack_mods<R: Runtime>(
     cancel: &Arc<AtomicBool>,
     logger: &ModpackImportLogger,
 ) -> Result<(), String> {
-    let mut archive = open_modpack_archive(zip_path)?;
-    let source = detect_modpack_source(&mut archive)?;
+    let source = detect_modpack_location(zip_path)?;
     logger
         .info(
             "MODS",
@@ -1045,6 +1123,18 @@ async fn fetch_modpack_mods<R: Runtime>(
             )
             .await
         }
+        ModpackSourceHint::Packwiz => {
+            download_packwiz_mods(
+                app,
+                zip_path,
+                instance_root,
+                instance_id,
+                base_dir,
+                cancel,
+                logger,
+            )
+            .await
+        }
     }
 }
 
@@ -1793,3 +1883,243 @@ async fn download_curseforge_mods<R: Runtime>(
         .await;
     finalize_imported_mod_manifest(instance_root, manifest_entries)
 }
+
+async fn download_packwiz_mods<R: Runtime>(
+    app: &AppHandle<R>,
+    pack_path: &str,
+    instance_root: &Path,
+    instance_id: &str,
+    base_dir: &Path,
+    cancel: &Arc<AtomicBool>,
+    logger: &ModpackImportLogger,
+) -> Result<(), String> {
+    let bundle = packwiz::load_local_pack(&local_packwiz_root(pack_path)?)?;
+    logger
+        .info(
+            "PACKWIZ_MODS",
+            format!(
+                "packwiz index loaded: metafiles={} files={}",
+                bundle.mods.len(),
+                bundle.overrides.len()
+            ),
+        )
+        .await;
+    if bundle.mods.is_empty() {
+        return finalize_imported_mod_manifest(instance_root, Vec::new());
+    }
+
+    let dl_settings = ConfigService::get_download_settings(app);
+    let concurrency = if dl_settings.concurrency > 0 {
+        dl_settings.concurrency
+    } else {
+        8
+    };
+    let retry_count = dl_settings.retry_count;
+    let verify_hash = dl_settings.verify_after_download;
+    let speed_limit_bytes_per_sec = ConfigService::download_speed_limit_bytes_per_sec(&dl_settings);
+
+    let client = Client::builder()
+        .user_agent("PiLauncher/1.0 (Packwiz)")
+        .connect_timeout(Duration::from_secs(dl_settings.timeout.max(1)))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let temp_root = packwiz_temp_root(base_dir, instance_id).join("downloads");
+    tokio::fs::create_dir_all(&temp_root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let curseforge_api_key = if bundle.mods.iter().any(|(_, mod_file)| {
+        mod_file.is_curseforge_metadata() && !mod_file.is_server_only()
+    }) {
+        Some(resolve_curseforge_api_key().ok_or_else(|| {
+            "CurseForge API key is missing. Set VITE_CURSEFORGE_API_KEY or CURSEFORGE_API_KEY."
+                .to_string()
+        })?)
+    } else {
+        None
+    };
+
+    let mut tasks: Vec<DownloadTask> = Vec::new();
+    let mut tracked_manifest_sources: Vec<(
+        String,
+        crate::domain::mod_manifest::ModManifestSource,
+        PathBuf,
+    )> = Vec::new();
+    let mut post_download_checks = Vec::new();
+    let mut skipped_count = 0usize;
+    let mut reused_count = 0usize;
+
+    for (metafile_path, mod_file) in &bundle.mods {
+        if is_cancelled(cancel) {
+            return Err("Cancelled".to_string());
+        }
+        if mod_file.is_server_only() {
+            skipped_count += 1;
+            continue;
+        }
+
+        let relative_path = packwiz::metafile_target_path(metafile_path, mod_file)
+            .ok_or_else(|| format!("Invalid packwiz file name: {}", mod_file.filename))?;
+        let target_path = instance_root.join(&relative_path);
+        let file_name = relative_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| mod_file.filename.clone());
+
+        if should_track_mod_manifest(&relative_path) {
+            if let Some((platform, project_id, file_id)) = mod_file.platform_reference() {
+                tracked_manifest_sources.push((
+                    file_name.clone(),
+                    build_manifest_source(
+                        ModSourceKind::ModpackDeployment,
+                        Some(platform.to_string()),
+                        Some(project_id),
+                        Some(file_id),
+                    ),
+                    target_path.clone(),
+                ));
+            }
+        }
+
+        let expected_sha1 = mod_file
+            .download
+            .hash_format
+            .eq_ignore_ascii_case("sha1")
+            .then(|| mod_file.download.hash.to_ascii_lowercase());
+
+        if target_path.exists()
+            && packwiz::verify_packwiz_download(&target_path, &mod_file.download).is_ok()
+        {
+            reused_count += 1;
+            continue;
+        }
+
+        let (url, fallback_urls, expected_size) = if mod_file.is_curseforge_metadata() {
+            let reference = mod_file
+                .update
+                .as_ref()
+                .and_then(|update| update.curseforge.as_ref())
+                .ok_or_else(|| {
+                    format!(
+                        "packwiz metafile {} uses CurseForge mode without update.curseforge",
+                        metafile_path.display()
+                    )
+                })?;
+            let api_key = curseforge_api_key.as_deref().unwrap_or_default();
+            let info = fetch_curseforge_file_info(
+                &client,
+                api_key,
+                reference.project_id,
+                reference.file_id,
+            )
+            .await?;
+            let (url, fallback_urls) = curseforge_download_candidates(
+                info.download_url,
+                info.id,
+                &file_name,
+                dl_settings.auto_check_latency,
+            );
+            (url, fallback_urls, Some(info.file_length))
+        } else {
+            let Some(url) = mod_file
+                .download
+                .url
+                .as_deref()
+                .map(|url| url.trim().replace(' ', "%20"))
+                .filter(|url| !url.is_empty())
+            else {
+                skipped_count += 1;
+                logger
+                    .warn(
+                        "PACKWIZ_MODS",
+                        format!("Skipped metafile without url: {}", metafile_path.display()),
+                    )
+                    .await;
+                continue;
+            };
+            (url, Vec::new(), None)
+        };
+
+        let temp_path = temp_root
+            .join(&relative_path)
+            .with_file_name(format!("{}.tmp", file_name));
+        // sha1 由下载器校验，其余格式下载完成后按元文件声明的格式校验
+        if verify_hash && expected_sha1.is_none() {
+            post_download_checks.push((target_path.clone(), &mod_file.download));
+        }
+        tasks.push(DownloadTask {
+            url,
+            fallback_urls,
+            path: target_path,
+            temp_path,
+            name: file_name,
+            expected_sha1: if verify_hash { expected_sha1 } else { None },
+            expected_size,
+        });
+    }
+
+    if !tasks.is_empty() {
+        logger
+            .info(
+                "PACKWIZ_MODS",
+                format!(
+                    "Running {} packwiz downloads (reused={} skipped={})",
+                    tasks.len(),
+                    reused_count,
+                    skipped_count
+                ),
+            )
+            .await;
+        run_downloads::<R>(
+            app,
+            instance_id,
+            &client,
+            tasks,
+            DownloadStage::Mods,
+            concurrency,
+            speed_limit_bytes_per_sec,
+            retry_count,
+            verify_hash,
+            Duration::from_secs(dl_settings.timeout.max(1).saturating_mul(2).max(30)),
+            cancel,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for (path, download) in post_download_checks {
+            if let Err(error) = packwiz::verify_packwiz_download(&path, download) {
+                let _ = fs::remove_file(&path);
+                return Err(error);
+            }
+        }
+    } else {
+        logger
+            .info(
+                "PACKWIZ_MODS",
+                format!(
+                    "No packwiz downloads needed (reused={} skipped={})",
+                    reused_count, skipped_count
+                ),
+            )
+            .await;
+    }
+
+    let mut manifest_entries = Vec::new();
+    for (file_name, source, target_path) in tracked_manifest_sources {
+        if let (Ok(file_state), Ok(hash)) = (
+            build_file_state(&target_path),
+            compute_file_hash(&target_path),
+        ) {
+            manifest_entries.push((file_name, build_manifest_entry(source, hash, file_state)));
+        }
+    }
+
+    logger
+        .info(
+            "PACKWIZ_MODS",
+            format!("Finalizing mod manifest entries={}", manifest_entries.len()),
+        )
+        .await;
+    finalize_imported_mod_manifest(instance_root, manifest_entries)
+}
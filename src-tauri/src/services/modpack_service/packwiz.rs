@@ -0,0 +1,881 @@
+use crate::domain::modpack::ModpackMetadata;
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::logic::safe_relative_path;
+
+pub const PACKWIZ_PACK_FILE: &str = "pack.toml";
+pub const PACKWIZ_INDEX_FILE: &str = "index.toml";
+pub const PACKWIZ_METAFILE_SUFFIX: &str = ".pw.toml";
+pub const PACKWIZ_PACK_FORMAT: &str = "packwiz:1.1.0";
+pub const PACKWIZ_DEFAULT_HASH_FORMAT: &str = "sha256";
+pub const PACKWIZ_CURSEFORGE_MODE: &str = "metadata:curseforge";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizPack {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<String>,
+    pub index: PackwizIndexRef,
+    #[serde(default)]
+    pub versions: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizIndexRef {
+    pub file: String,
+    pub hash_format: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizIndex {
+    pub hash_format: String,
+    #[serde(default)]
+    pub files: Vec<PackwizIndexFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizIndexFile {
+    pub file: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizModFile {
+    pub name: String,
+    pub filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<String>,
+    pub download: PackwizDownload,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<PackwizUpdate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizDownload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub hash_format: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PackwizUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<PackwizModrinthUpdate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<PackwizCurseForgeUpdate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizModrinthUpdate {
+    pub mod_id: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizCurseForgeUpdate {
+    pub file_id: u64,
+    pub project_id: u64,
+}
+
+impl PackwizModFile {
+    pub fn is_server_only(&self) -> bool {
+        self.side
+            .as_deref()
+            .is_some_and(|side| side.eq_ignore_ascii_case("server"))
+    }
+
+    pub fn is_curseforge_metadata(&self) -> bool {
+        self.download
+            .mode
+            .as_deref()
+            .is_some_and(|mode| mode.eq_ignore_ascii_case(PACKWIZ_CURSEFORGE_MODE))
+    }
+
+    /// 返回 (platform, project_id, file_id)，用于写入 mod_manifest 的来源信息。
+    pub fn platform_reference(&self) -> Option<(&'static str, String, String)> {
+        let update = self.update.as_ref()?;
+        if let Some(modrinth) = &update.modrinth {
+            return Some((
+                "modrinth",
+                modrinth.mod_id.clone(),
+                modrinth.version.clone(),
+            ));
+        }
+        update.curseforge.as_ref().map(|curseforge| {
+            (
+                "curseforge",
+                curseforge.project_id.to_string(),
+                curseforge.file_id.to_string(),
+            )
+        })
+    }
+}
+
+/// Where a packwiz pack lives: a local repository checkout or an HTTP(S) `pack.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackwizLocation {
+    Local(PathBuf),
+    Remote(Url),
+}
+
+pub fn resolve_packwiz_location(path: &str) -> Option<PackwizLocation> {
+    let trimmed = path.trim();
+    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        let url = Url::parse(trimmed).ok()?;
+        return url
+            .path()
+            .ends_with(".toml")
+            .then_some(PackwizLocation::Remote(url));
+    }
+
+    let candidate = Path::new(trimmed);
+    if candidate.is_dir() {
+        return candidate
+            .join(PACKWIZ_PACK_FILE)
+            .is_file()
+            .then(|| PackwizLocation::Local(candidate.to_path_buf()));
+    }
+
+    if candidate.is_file()
+        && candidate.file_name().is_some_and(|name| {
+            name.to_string_lossy()
+                .eq_ignore_ascii_case(PACKWIZ_PACK_FILE)
+        })
+    {
+        return candidate
+            .parent()
+            .map(|parent| PackwizLocation::Local(parent.to_path_buf()));
+    }
+
+    None
+}
+
+pub fn is_packwiz_location(path: &str) -> bool {
+    resolve_packwiz_location(path).is_some()
+}
+
+pub fn parse_packwiz_pack(contents: &str) -> Result<PackwizPack, String> {
+    toml::from_str(contents).map_err(|e| format!("Failed to parse packwiz pack.toml: {}", e))
+}
+
+pub fn parse_packwiz_metadata(contents: &str) -> Result<ModpackMetadata, String> {
+    let pack = parse_packwiz_pack(contents)?;
+    let version = pack
+        .versions
+        .get("minecraft")
+        .cloned()
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let mut loader = String::from("Vanilla");
+    let mut loader_version = String::new();
+    for (key, label) in [
+        ("fabric", "Fabric"),
+        ("forge", "Forge"),
+        ("neoforge", "NeoForge"),
+        ("quilt", "Quilt"),
+    ] {
+        if let Some(value) = pack.versions.get(key) {
+            loader = label.to_string();
+            loader_version = value.clone();
+            break;
+        }
+    }
+
+    Ok(ModpackMetadata {
+        name: pack.name,
+        version,
+        loader,
+        loader_version,
+        author: pack.author.unwrap_or_else(|| "Unknown Author".to_string()),
+        source: "Packwiz".to_string(),
+        pack_version: pack.version,
+        packaged_at: None,
+        pack_uuid: None,
+    })
+}
+
+/// A packwiz repository that has been resolved on the local disk.
+pub struct PackwizBundle {
+    pub root: PathBuf,
+    pub pack: PackwizPack,
+    pub index: PackwizIndex,
+    /// (metafile path relative to the index directory, parsed metafile)
+    pub mods: Vec<(PathBuf, PackwizModFile)>,
+    /// Plain files that are copied into the instance as-is.
+    pub overrides: Vec<PathBuf>,
+}
+
+impl PackwizBundle {
+    pub fn index_dir(&self) -> PathBuf {
+        let index_path = self.root.join(&self.pack.index.file);
+        index_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.root.clone())
+    }
+}
+
+/// The instance-relative target of a metafile: `mods/sodium.pw.toml` → `mods/sodium.jar`.
+pub fn metafile_target_path(metafile_path: &Path, mod_file: &PackwizModFile) -> Option<PathBuf> {
+    let file_name = Path::new(&mod_file.filename).file_name()?;
+    let target = match metafile_path.parent() {
+        Some(parent) => parent.join(file_name),
+        None => PathBuf::from(file_name),
+    };
+    safe_relative_path(&target.to_string_lossy().replace('\\', "/"))
+}
+
+fn index_entry_path(entry: &PackwizIndexFile) -> Result<PathBuf, String> {
+    safe_relative_path(entry.file.trim_start_matches("./"))
+        .ok_or_else(|| format!("Invalid file path in packwiz index: {}", entry.file))
+}
+
+pub fn load_local_pack(root: &Path) -> Result<PackwizBundle, String> {
+    let pack_contents = fs::read_to_string(root.join(PACKWIZ_PACK_FILE))
+        .map_err(|e| format!("Failed to read packwiz pack.toml: {}", e))?;
+    let pack = parse_packwiz_pack(&pack_contents)?;
+
+    let index_relative = safe_relative_path(pack.index.file.trim_start_matches("./"))
+        .ok_or_else(|| format!("Invalid packwiz index path: {}", pack.index.file))?;
+    let index_bytes = fs::read(root.join(&index_relative))
+        .map_err(|e| format!("Failed to read packwiz index: {}", e))?;
+    verify_packwiz_hash(
+        &pack.index.hash_format,
+        &pack.index.hash,
+        &index_bytes,
+        &pack.index.file,
+    )?;
+    let index: PackwizIndex = toml::from_str(&String::from_utf8_lossy(&index_bytes))
+        .map_err(|e| format!("Failed to parse packwiz index: {}", e))?;
+
+    let index_dir = index_relative
+        .parent()
+        .map(|parent| root.join(parent))
+        .unwrap_or_else(|| root.to_path_buf());
+
+    let mut mods = Vec::new();
+    let mut overrides = Vec::new();
+    for entry in &index.files {
+        let relative_path = index_entry_path(entry)?;
+        let bytes = fs::read(index_dir.join(&relative_path))
+            .map_err(|e| format!("Failed to read packwiz file {}: {}", entry.file, e))?;
+        let hash_format = entry
+            .hash_format
+            .as_deref()
+            .unwrap_or(index.hash_format.as_str());
+        verify_packwiz_hash(hash_format, &entry.hash, &bytes, &entry.file)?;
+
+        if entry.metafile || entry.file.ends_with(PACKWIZ_METAFILE_SUFFIX) {
+            let mod_file: PackwizModFile = toml::from_str(&String::from_utf8_lossy(&bytes))
+                .map_err(|e| format!("Failed to parse packwiz metafile {}: {}", entry.file, e))?;
+            if compute_packwiz_hash(&mod_file.download.hash_format, &[]).is_none() {
+                return Err(format!(
+                    "Unsupported packwiz hash format in {}: {}",
+                    entry.file, mod_file.download.hash_format
+                ));
+            }
+            mods.push((relative_path, mod_file));
+        } else {
+            overrides.push(relative_path);
+        }
+    }
+
+    Ok(PackwizBundle {
+        root: root.to_path_buf(),
+        pack,
+        index,
+        mods,
+        overrides,
+    })
+}
+
+pub async fn fetch_remote_pack_toml(client: &Client, url: &Url) -> Result<String, String> {
+    let bytes = fetch_remote_bytes(client, url).await?;
+    String::from_utf8(bytes).map_err(|e| format!("packwiz pack.toml is not UTF-8: {}", e))
+}
+
+/// Mirrors a remote packwiz repository (pack.toml, index and every indexed file) into
+/// `staging_dir`, verifying each file against the index hashes. Local packs are returned as-is.
+pub async fn stage_packwiz_pack(
+    client: &Client,
+    location: &PackwizLocation,
+    staging_dir: &Path,
+) -> Result<PathBuf, String> {
+    let pack_url = match location {
+        PackwizLocation::Local(root) => return Ok(root.clone()),
+        PackwizLocation::Remote(url) => url,
+    };
+
+    if staging_dir.exists() {
+        fs::remove_dir_all(staging_dir).map_err(|e| e.to_string())?;
+    }
+    fs::create_dir_all(staging_dir).map_err(|e| e.to_string())?;
+
+    let pack_contents = fetch_remote_pack_toml(client, pack_url).await?;
+    let pack = parse_packwiz_pack(&pack_contents)?;
+    fs::write(staging_dir.join(PACKWIZ_PACK_FILE), &pack_contents).map_err(|e| e.to_string())?;
+
+    let index_relative = safe_relative_path(pack.index.file.trim_start_matches("./"))
+        .ok_or_else(|| format!("Invalid packwiz index path: {}", pack.index.file))?;
+    let index_url = pack_url
+        .join(&pack.index.file)
+        .map_err(|e| format!("Invalid packwiz index url: {}", e))?;
+    let index_bytes = fetch_remote_bytes(client, &index_url).await?;
+    verify_packwiz_hash(
+        &pack.index.hash_format,
+        &pack.index.hash,
+        &index_bytes,
+        &pack.index.file,
+    )?;
+    write_staged_file(staging_dir, &index_relative, &index_bytes)?;
+
+    let index: PackwizIndex = toml::from_str(&String::from_utf8_lossy(&index_bytes))
+        .map_err(|e| format!("Failed to parse packwiz index: {}", e))?;
+    let index_dir = index_relative
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    for entry in &index.files {
+        let relative_path = index_entry_path(entry)?;
+        let file_url = index_url
+            .join(&entry.file)
+            .map_err(|e| format!("Invalid packwiz file url {}: {}", entry.file, e))?;
+        let bytes = fetch_remote_bytes(client, &file_url).await?;
+        let hash_format = entry
+            .hash_format
+            .as_deref()
+            .unwrap_or(index.hash_format.as_str());
+        verify_packwiz_hash(hash_format, &entry.hash, &bytes, &entry.file)?;
+        write_staged_file(staging_dir, &index_dir.join(&relative_path), &bytes)?;
+    }
+
+    Ok(staging_dir.to_path_buf())
+}
+
+async fn fetch_remote_bytes(client: &Client, url: &Url) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| format!("packwiz request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "packwiz request failed: {} ({})",
+            response.status(),
+            url
+        ));
+    }
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("packwiz response read failed: {}", e))
+}
+
+fn write_staged_file(root: &Path, relative_path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let target = root.join(relative_path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(target, bytes).map_err(|e| e.to_string())
+}
+
+/// Copies the non-metafile entries of the index into the instance directory.
+pub fn extract_packwiz_overrides(bundle: &PackwizBundle, target_dir: &Path) -> Result<(), String> {
+    let index_dir = bundle.index_dir();
+    for relative_path in &bundle.overrides {
+        let source = index_dir.join(relative_path);
+        if !source.is_file() {
+            return Err(format!(
+                "packwiz file listed in index is missing: {}",
+                relative_path.display()
+            ));
+        }
+        let target = target_dir.join(relative_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(&source, &target).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Returns `None` for hash formats we cannot compute locally (e.g. CurseForge murmur2).
+pub fn compute_packwiz_hash(hash_format: &str, bytes: &[u8]) -> Option<String> {
+    match hash_format.trim().to_ascii_lowercase().as_str() {
+        "sha1" => {
+            use sha1::Digest;
+            Some(hex::encode(Sha1::digest(bytes)))
+        }
+        "sha256" => {
+            use sha2::Digest;
+            Some(hex::encode(Sha256::digest(bytes)))
+        }
+        "sha512" => {
+            use sha2::Digest;
+            Some(hex::encode(Sha512::digest(bytes)))
+        }
+        "md5" => Some(format!("{:x}", md5::compute(bytes))),
+        _ => None,
+    }
+}
+
+fn verify_packwiz_hash(
+    hash_format: &str,
+    expected: &str,
+    bytes: &[u8],
+    label: &str,
+) -> Result<(), String> {
+    match compute_packwiz_hash(hash_format, bytes) {
+        Some(actual) if !actual.eq_ignore_ascii_case(expected.trim()) => Err(format!(
+            "packwiz hash mismatch for {}: expected {} {}, got {}",
+            label, hash_format, expected, actual
+        )),
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "Unsupported packwiz hash format for {}: {}",
+            label, hash_format
+        )),
+    }
+}
+
+/// Verifies a downloaded mod against the `hash-format` declared by its metafile.
+pub fn verify_packwiz_download(path: &Path, download: &PackwizDownload) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    verify_packwiz_hash(
+        &download.hash_format,
+        &download.hash,
+        &bytes,
+        &path.display().to_string(),
+    )
+}
+
+/// Writes `pack.toml`, `index.toml` and the given metafiles into `output_dir`.
+/// `plain_files` are paths (relative to `output_dir`) the caller already wrote there; only
+/// those and the metafiles are indexed, so unrelated files such as `.git/` stay out of the pack.
+pub fn write_packwiz_pack(
+    output_dir: &Path,
+    mut pack: PackwizPack,
+    metafiles: &[(PathBuf, PackwizModFile)],
+    plain_files: &[PathBuf],
+) -> Result<(), String> {
+    fs::create_dir_all(output_dir).map_err(|e| e.to_string())?;
+
+    for (relative_path, mod_file) in metafiles {
+        let contents = toml::to_string_pretty(mod_file).map_err(|e| e.to_string())?;
+        write_staged_file(output_dir, relative_path, contents.as_bytes())?;
+    }
+
+    let mut files = Vec::new();
+    let indexed = metafiles
+        .iter()
+        .map(|(relative_path, _)| relative_path)
+        .chain(plain_files);
+    for relative_path in indexed {
+        let bytes = fs::read(output_dir.join(relative_path)).map_err(|e| e.to_string())?;
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        if relative_path == PACKWIZ_PACK_FILE || relative_path == PACKWIZ_INDEX_FILE {
+            continue;
+        }
+        files.push(PackwizIndexFile {
+            hash: compute_packwiz_hash(PACKWIZ_DEFAULT_HASH_FORMAT, &bytes).unwrap_or_default(),
+            metafile: relative_path.ends_with(PACKWIZ_METAFILE_SUFFIX),
+            file: relative_path,
+            hash_format: None,
+            alias: None,
+            preserve: false,
+        });
+    }
+    files.sort_by(|left, right| left.file.cmp(&right.file));
+    files.dedup_by(|left, right| left.file == right.file);
+
+    let index = PackwizIndex {
+        hash_format: PACKWIZ_DEFAULT_HASH_FORMAT.to_string(),
+        files,
+    };
+    let index_contents = toml::to_string_pretty(&index).map_err(|e| e.to_string())?;
+    fs::write(output_dir.join(PACKWIZ_INDEX_FILE), &index_contents).map_err(|e| e.to_string())?;
+
+    pack.index = PackwizIndexRef {
+        file: PACKWIZ_INDEX_FILE.to_string(),
+        hash_format: PACKWIZ_DEFAULT_HASH_FORMAT.to_string(),
+        hash: compute_packwiz_hash(PACKWIZ_DEFAULT_HASH_FORMAT, index_contents.as_bytes())
+            .unwrap_or_default(),
+    };
+    pack.pack_format
+        .get_or_insert_with(|| PACKWIZ_PACK_FORMAT.to_string());
+    let pack_contents = toml::to_string_pretty(&pack).map_err(|e| e.to_string())?;
+    fs::write(output_dir.join(PACKWIZ_PACK_FILE), pack_contents).map_err(|e| e.to_string())
+}
+
+/// Like `packwiz_metafile_name`, but appends `-2`, `-3`, ... when another mod in the same
+/// export already produced the same slug (e.g. `Foo+.jar` and `foo-.jar`).
+pub fn unique_packwiz_metafile_name(file_name: &str, used: &mut HashSet<String>) -> String {
+    let name = packwiz_metafile_name(file_name);
+    if used.insert(name.clone()) {
+        return name;
+    }
+    let stem = name.trim_end_matches(PACKWIZ_METAFILE_SUFFIX);
+    (2..)
+        .map(|suffix| format!("{}-{}{}", stem, suffix, PACKWIZ_METAFILE_SUFFIX))
+        .find(|candidate| used.insert(candidate.clone()))
+        .unwrap_or(name)
+}
+
+pub fn packwiz_metafile_name(file_name: &str) -> String {
+    let stem = file_name
+        .trim_end_matches(".disabled")
+        .trim_end_matches(".jar")
+        .to_ascii_lowercase();
+    let slug: String = stem
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+    format!(
+        "{}{}",
+        if slug.is_empty() { "mod" } else { slug },
+        PACKWIZ_METAFILE_SUFFIX
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compute_packwiz_hash, extract_packwiz_overrides, load_local_pack, metafile_target_path,
+        packwiz_metafile_name, parse_packwiz_metadata, resolve_packwiz_location,
+        stage_packwiz_pack, unique_packwiz_metafile_name, write_packwiz_pack, PackwizDownload,
+        PackwizIndexRef, PackwizLocation, PackwizModFile, PackwizModrinthUpdate, PackwizPack,
+        PackwizUpdate,
+    };
+    use axum::extract::{Path as AxumPath, State};
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[test]
+    fn parses_packwiz_metadata() {
+        let metadata = parse_packwiz_metadata(
+            r#"
+name = "Demo Pack"
+author = "Pi"
+version = "1.2.0"
+pack-format = "packwiz:1.1.0"
+
+[index]
+file = "index.toml"
+hash-format = "sha256"
+hash = "abc"
+
+[versions]
+minecraft = "1.20.1"
+fabric = "0.15.11"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(metadata.name, "Demo Pack");
+        assert_eq!(metadata.version, "1.20.1");
+        assert_eq!(metadata.loader, "Fabric");
+        assert_eq!(metadata.loader_version, "0.15.11");
+        assert_eq!(metadata.source, "Packwiz");
+        assert_eq!(metadata.pack_version.as_deref(), Some("1.2.0"));
+    }
+
+    #[test]
+    fn resolves_local_and_remote_locations() {
+        let root = create_temp_dir("packwiz_location");
+        fs::write(root.join("pack.toml"), "").unwrap();
+
+        assert_eq!(
+            resolve_packwiz_location(root.to_str().unwrap()),
+            Some(PackwizLocation::Local(root.clone()))
+        );
+        assert_eq!(
+            resolve_packwiz_location(root.join("pack.toml").to_str().unwrap()),
+            Some(PackwizLocation::Local(root.clone()))
+        );
+        assert!(matches!(
+            resolve_packwiz_location("https://example.com/pack/pack.toml"),
+            Some(PackwizLocation::Remote(_))
+        ));
+        assert_eq!(
+            resolve_packwiz_location("https://example.com/pack.zip"),
+            None
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn maps_metafile_to_target_path() {
+        let mod_file = sample_mod_file("sodium.jar");
+        assert_eq!(
+            metafile_target_path(Path::new("mods/sodium.pw.toml"), &mod_file),
+            Some(PathBuf::from("mods/sodium.jar"))
+        );
+        assert_eq!(
+            packwiz_metafile_name("Sodium 0.5.jar"),
+            "sodium-0-5.pw.toml"
+        );
+
+        let mut used = HashSet::new();
+        let mut unique = |name| unique_packwiz_metafile_name(name, &mut used);
+        assert_eq!(unique("Foo+.jar"), "foo.pw.toml");
+        assert_eq!(unique("foo-.jar"), "foo-2.pw.toml");
+        assert_eq!(unique("FOO.jar"), "foo-3.pw.toml");
+    }
+
+    #[test]
+    fn rejects_tampered_files_and_unsupported_hash_formats() {
+        let root = create_temp_dir("packwiz_tampered_file");
+        fs::create_dir_all(root.join("config")).unwrap();
+        fs::write(root.join("config/demo.json"), "{}").unwrap();
+        write_packwiz_pack(&root, sample_pack(), &[], &demo_config()).unwrap();
+        fs::write(root.join("config/demo.json"), "{\"changed\":true}").unwrap();
+        assert!(load_local_pack(&root)
+            .err()
+            .unwrap()
+            .contains("hash mismatch"));
+        let _ = fs::remove_dir_all(root);
+
+        let root = create_temp_dir("packwiz_murmur");
+        let mut mod_file = sample_mod_file("sodium.jar");
+        mod_file.download.hash_format = "murmur2".to_string();
+        let metafiles = [(PathBuf::from("mods/sodium.pw.toml"), mod_file)];
+        write_packwiz_pack(&root, sample_pack(), &metafiles, &[]).unwrap();
+        assert!(load_local_pack(&root)
+            .err()
+            .unwrap()
+            .contains("Unsupported packwiz hash format"));
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn writes_and_reloads_packwiz_pack() {
+        let root = create_temp_dir("packwiz_roundtrip");
+        let instance_dir = create_temp_dir("packwiz_roundtrip_instance");
+        fs::create_dir_all(root.join("config")).unwrap();
+        fs::write(root.join("config/demo.json"), "{}").unwrap();
+
+        write_packwiz_pack(
+            &root,
+            sample_pack(),
+            &[(
+                PathBuf::from("mods/sodium.pw.toml"),
+                sample_mod_file("sodium.jar"),
+            )],
+            &demo_config(),
+        )
+        .unwrap();
+
+        let bundle = load_local_pack(&root).unwrap();
+        assert_eq!(bundle.pack.name, "Demo Pack");
+        assert_eq!(bundle.mods.len(), 1);
+        assert_eq!(bundle.mods[0].1.filename, "sodium.jar");
+        assert_eq!(bundle.overrides, vec![PathBuf::from("config/demo.json")]);
+
+        extract_packwiz_overrides(&bundle, &instance_dir).unwrap();
+        assert_eq!(
+            fs::read_to_string(instance_dir.join("config/demo.json")).unwrap(),
+            "{}"
+        );
+
+        let _ = fs::remove_dir_all(root);
+        let _ = fs::remove_dir_all(instance_dir);
+    }
+
+    #[test]
+    fn indexes_only_the_files_written_by_the_export() {
+        let root = create_temp_dir("packwiz_unrelated_files");
+        fs::create_dir_all(root.join("config")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("config/demo.json"), "{}").unwrap();
+        fs::write(root.join(".git/HEAD"), "ref: refs/heads/main").unwrap();
+        fs::write(root.join("notes.txt"), "leftover").unwrap();
+
+        write_packwiz_pack(&root, sample_pack(), &[], &demo_config()).unwrap();
+
+        let bundle = load_local_pack(&root).unwrap();
+        assert_eq!(bundle.overrides, vec![PathBuf::from("config/demo.json")]);
+        let index = fs::read_to_string(root.join("index.toml")).unwrap();
+        assert!(!index.contains(".git"));
+        assert!(!index.contains("notes.txt"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn rejects_tampered_index() {
+        let root = create_temp_dir("packwiz_tampered");
+        write_packwiz_pack(&root, sample_pack(), &[], &[]).unwrap();
+        fs::write(root.join("index.toml"), "hash-format = \"sha256\"\n").unwrap();
+
+        assert!(load_local_pack(&root)
+            .err()
+            .unwrap()
+            .contains("hash mismatch"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn stages_remote_pack_from_http_server() {
+        let source = create_temp_dir("packwiz_remote_source");
+        let staging = create_temp_dir("packwiz_remote_staging");
+        fs::create_dir_all(source.join("config")).unwrap();
+        fs::write(source.join("config/demo.json"), "{\"remote\":true}").unwrap();
+        write_packwiz_pack(
+            &source,
+            sample_pack(),
+            &[(
+                PathBuf::from("mods/sodium.pw.toml"),
+                sample_mod_file("sodium.jar"),
+            )],
+            &demo_config(),
+        )
+        .unwrap();
+
+        let mut files = HashMap::new();
+        for entry in walkdir::WalkDir::new(&source) {
+            let entry = entry.unwrap();
+            if entry.file_type().is_file() {
+                let relative = entry
+                    .path()
+                    .strip_prefix(&source)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                files.insert(relative, fs::read(entry.path()).unwrap());
+            }
+        }
+
+        let router = Router::new()
+            .route(
+                "/pack/{*path}",
+                get(
+                    |State(files): State<Arc<HashMap<String, Vec<u8>>>>,
+                     AxumPath(path): AxumPath<String>| async move {
+                        files.get(&path).cloned().ok_or(StatusCode::NOT_FOUND)
+                    },
+                ),
+            )
+            .with_state(Arc::new(files));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        let location =
+            resolve_packwiz_location(&format!("http://{}/pack/pack.toml", addr)).unwrap();
+        let client = reqwest::Client::new();
+        let staged = stage_packwiz_pack(&client, &location, &staging)
+            .await
+            .unwrap();
+
+        let bundle = load_local_pack(&staged).unwrap();
+        assert_eq!(bundle.mods.len(), 1);
+        assert_eq!(
+            fs::read_to_string(staged.join("config/demo.json")).unwrap(),
+            "{\"remote\":true}"
+        );
+        assert_eq!(
+            compute_packwiz_hash("sha256", &fs::read(staged.join("index.toml")).unwrap()),
+            Some(bundle.pack.index.hash.clone())
+        );
+
+        let _ = fs::remove_dir_all(source);
+        let _ = fs::remove_dir_all(staging);
+    }
+
+    fn demo_config() -> [PathBuf; 1] {
+        [PathBuf::from("config/demo.json")]
+    }
+
+    fn sample_pack() -> PackwizPack {
+        let mut versions = BTreeMap::new();
+        versions.insert("minecraft".to_string(), "1.20.1".to_string());
+        versions.insert("fabric".to_string(), "0.15.11".to_string());
+        PackwizPack {
+            name: "Demo Pack".to_string(),
+            author: Some("Pi".to_string()),
+            version: Some("1.0.0".to_string()),
+            description: None,
+            pack_format: None,
+            index: PackwizIndexRef {
+                file: String::new(),
+                hash_format: String::new(),
+                hash: String::new(),
+            },
+            versions,
+        }
+    }
+
+    fn sample_mod_file(file_name: &str) -> PackwizModFile {
+        PackwizModFile {
+            name: "Sodium".to_string(),
+            filename: file_name.to_string(),
+            side: Some("client".to_string()),
+            download: PackwizDownload {
+                url: Some(
+                    "https://cdn.modrinth.com/data/AANobbMI/versions/abc/sodium.jar".to_string(),
+                ),
+                hash_format: "sha1".to_string(),
+                hash: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                mode: None,
+            },
+            update: Some(PackwizUpdate {
+                modrinth: Some(PackwizModrinthUpdate {
+                    mod_id: "AANobbMI".to_string(),
+                    version: "abc".to_string(),
+                }),
+                curseforge: None,
+            }),
+        }
+    }
+
+    fn create_temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", prefix, Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
@@ -0,0 +1,326 @@
+// src-tauri/src/services/modpack_service/pipack_signing.rs
+//! PiPack 完整性清单与签名：导出时用本机局域网身份（ed25519）签名 `pi_manifest.json`，
+//! 导入时校验签名与每个 overrides 文件的 sha256，并查询签名设备是否已受信任。
+use crate::domain::lan::DeviceIdentity;
+use crate::domain::modpack::{
+    PiPackFileHash, PiPackManifest, PiPackSignature, PiPackVerification, PIPACK_MANIFEST_FILE,
+    PIPACK_OVERRIDES_DIR, PIPACK_SIGNATURE_FILE,
+};
+use crate::services::lan::trust_store;
+
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use zip::ZipArchive;
+
+pub const PIPACK_SIGNATURE_ALGORITHM: &str = "ed25519";
+/// 签名消息前缀，避免与局域网 RPC 等其他用途的签名混用
+const SIGNATURE_CONTEXT: &[u8] = b"pilauncher:pipack:v2\n";
+
+pub fn file_hash(path: &str, bytes: &[u8]) -> PiPackFileHash {
+    PiPackFileHash {
+        path: path.to_string(),
+        sha256: hex::encode(Sha256::digest(bytes)),
+        size: bytes.len() as u64,
+    }
+}
+
+pub fn sign_manifest(
+    manifest_bytes: &[u8],
+    identity: &DeviceIdentity,
+) -> Result<PiPackSignature, String> {
+    let private_bytes = general_purpose::STANDARD
+        .decode(&identity.private_key_b64)
+        .map_err(|e| format!("Failed to decode device private key: {}", e))?;
+    let private_array: [u8; 32] = private_bytes
+        .try_into()
+        .map_err(|_| "Invalid device private key length".to_string())?;
+    let signing_key = SigningKey::from_bytes(&private_array);
+    let signature = signing_key.sign(&signed_message(manifest_bytes));
+
+    Ok(PiPackSignature {
+        algorithm: PIPACK_SIGNATURE_ALGORITHM.to_string(),
+        device_id: identity.device_id.clone(),
+        device_name: identity.device_name.clone(),
+        public_key_b64: general_purpose::STANDARD.encode(signing_key.verifying_key().to_bytes()),
+        signed_at: chrono::Utc::now().to_rfc3339(),
+        signature_b64: general_purpose::STANDARD.encode(signature.to_bytes()),
+    })
+}
+
+pub fn verify_manifest_signature(manifest_bytes: &[u8], signature: &PiPackSignature) -> bool {
+    if signature.algorithm != PIPACK_SIGNATURE_ALGORITHM {
+        return false;
+    }
+    let Ok(public_bytes) = general_purpose::STANDARD.decode(&signature.public_key_b64) else {
+        return false;
+    };
+    let Ok(sig_bytes) = general_purpose::STANDARD.decode(&signature.signature_b64) else {
+        return false;
+    };
+    let Ok(public_array) = <[u8; 32]>::try_from(public_bytes) else {
+        return false;
+    };
+    let Ok(verifying_key) = VerifyingKey::from_bytes(&public_array) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_slice(&sig_bytes) else {
+        return false;
+    };
+    verifying_key
+        .verify(&signed_message(manifest_bytes), &signature)
+        .is_ok()
+}
+
+fn signed_message(manifest_bytes: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(SIGNATURE_CONTEXT.len() + manifest_bytes.len());
+    message.extend_from_slice(SIGNATURE_CONTEXT);
+    message.extend_from_slice(manifest_bytes);
+    message
+}
+
+/// 校验 PiPack 的签名与完整性清单（不含信任查询）。
+pub fn verify_pipack_archive(path: &str) -> Result<PiPackVerification, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {}", e))?;
+
+    let manifest_bytes = read_entry_bytes(&mut archive, PIPACK_MANIFEST_FILE)?
+        .ok_or_else(|| format!("{} not found", PIPACK_MANIFEST_FILE))?;
+    let manifest: PiPackManifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| format!("Failed to parse PiPack manifest: {}", e))?;
+
+    let mut verification = PiPackVerification::default();
+    if let Some(signature_bytes) = read_entry_bytes(&mut archive, PIPACK_SIGNATURE_FILE)? {
+        let signature: PiPackSignature = serde_json::from_slice(&signature_bytes)
+            .map_err(|e| format!("Failed to parse PiPack signature: {}", e))?;
+        verification.signed = true;
+        verification.signature_valid = verify_manifest_signature(&manifest_bytes, &signature);
+        verification.signer_device_id = Some(signature.device_id);
+        verification.signer_device_name = Some(signature.device_name);
+        verification.signer_public_key_b64 = Some(signature.public_key_b64);
+    }
+
+    if manifest.integrity.is_empty() {
+        return Ok(verification);
+    }
+    verification.integrity_checked = true;
+
+    let listed: HashSet<&str> = manifest.integrity.iter().map(|f| f.path.as_str()).collect();
+    for expected in &manifest.integrity {
+        match read_entry_bytes(&mut archive, &expected.path)? {
+            Some(bytes) => {
+                if file_hash(&expected.path, &bytes) == *expected {
+                    verification.verified_files += 1;
+                } else {
+                    verification.mismatched_files.push(expected.path.clone());
+                }
+            }
+            None => verification.missing_files.push(expected.path.clone()),
+        }
+    }
+
+    let overrides_prefix = format!("{}/", PIPACK_OVERRIDES_DIR);
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        let name = entry.name().to_string();
+        if entry.is_file() && name.starts_with(&overrides_prefix) && !listed.contains(name.as_str())
+        {
+            verification.unlisted_files.push(name);
+        }
+    }
+
+    Ok(verification)
+}
+
+/// 签名有效时查询签名设备的信任状态：设备 ID 与公钥都须与 trusted_devices 中的记录一致，且信任未过期。
+pub async fn resolve_signer_trust(
+    pool: &SqlitePool,
+    verification: &mut PiPackVerification,
+) -> Result<(), String> {
+    if !verification.signature_valid {
+        return Ok(());
+    }
+    let (Some(device_id), Some(public_key)) = (
+        verification.signer_device_id.clone(),
+        verification.signer_public_key_b64.clone(),
+    ) else {
+        return Ok(());
+    };
+
+    let row = sqlx::query(
+        "SELECT public_key_b64, trust_level, expires_at FROM trusted_devices WHERE device_uuid = $1 LIMIT 1",
+    )
+    .bind(device_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to query trusted devices: {}", e))?;
+
+    if let Some(row) = row {
+        let stored_key: String = row.try_get("public_key_b64").unwrap_or_default();
+        let trust_level: String = row.try_get("trust_level").unwrap_or_default();
+        let expires_at: Option<i64> = row.try_get("expires_at").unwrap_or_default();
+        if stored_key == public_key {
+            verification.trusted = trust_level == "trusted" && !trust_store::is_expired(expires_at);
+            verification.trust_level = Some(trust_level);
+        }
+    }
+    Ok(())
+}
+
+fn read_entry_bytes<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", name, e)),
+    };
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    Ok(Some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::modpack::{PiPackMinecraftInfo, PiPackPackageInfo, PIPACK_FORMAT_VERSION};
+    use std::io::Write;
+    use std::path::Path;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn identity() -> DeviceIdentity {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        DeviceIdentity {
+            device_id: "device-a".to_string(),
+            device_name: "Alice PC".to_string(),
+            user_uuid: String::new(),
+            private_key_b64: general_purpose::STANDARD.encode(signing_key.to_bytes()),
+            public_key_b64: general_purpose::STANDARD
+                .encode(signing_key.verifying_key().to_bytes()),
+        }
+    }
+
+    fn manifest(integrity: Vec<PiPackFileHash>) -> PiPackManifest {
+        PiPackManifest {
+            format_version: PIPACK_FORMAT_VERSION,
+            package: PiPackPackageInfo {
+                name: "Pack".to_string(),
+                version: "1.0.0".to_string(),
+                author: "Alice".to_string(),
+                description: String::new(),
+                uuid: "uuid".to_string(),
+                packaged_at: "2024-01-01T00:00:00Z".to_string(),
+            },
+            minecraft: PiPackMinecraftInfo {
+                version: "1.20.1".to_string(),
+                loader: "fabric".to_string(),
+                loader_version: "0.15.0".to_string(),
+                instance_id: "pack".to_string(),
+                instance_name: "Pack".to_string(),
+            },
+            overrides: PIPACK_OVERRIDES_DIR.to_string(),
+            mods: Vec::new(),
+            server: None,
+            integrity,
+        }
+    }
+
+    fn write_pack(path: &Path, files: &[(&str, &[u8])], signer: Option<&DeviceIdentity>) {
+        let integrity = files.iter().map(|(p, b)| file_hash(p, b)).collect();
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest(integrity)).unwrap();
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        let options = SimpleFileOptions::default();
+        for (name, bytes) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.start_file(PIPACK_MANIFEST_FILE, options).unwrap();
+        zip.write_all(&manifest_bytes).unwrap();
+        if let Some(identity) = signer {
+            let signature = sign_manifest(&manifest_bytes, identity).unwrap();
+            zip.start_file(PIPACK_SIGNATURE_FILE, options).unwrap();
+            zip.write_all(&serde_json::to_vec(&signature).unwrap())
+                .unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn signature_round_trip_and_tamper_detection() {
+        let identity = identity();
+        let signature = sign_manifest(b"{\"a\":1}", &identity).unwrap();
+        assert!(verify_manifest_signature(b"{\"a\":1}", &signature));
+        assert!(!verify_manifest_signature(b"{\"a\":2}", &signature));
+        assert_eq!(signature.public_key_b64, identity.public_key_b64);
+    }
+
+    #[test]
+    fn verifies_signed_pack_and_reports_modified_files() {
+        let dir =
+            std::env::temp_dir().join(format!("pilauncher_pipack_sig_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let identity = identity();
+
+        let good = dir.join("good.zip");
+        write_pack(
+            &good,
+            &[("overrides/config/a.toml", b"a=1")],
+            Some(&identity),
+        );
+        let verification = verify_pipack_archive(good.to_str().unwrap()).unwrap();
+        assert!(verification.signed && verification.signature_valid);
+        assert_eq!(verification.signer_device_name.as_deref(), Some("Alice PC"));
+        assert_eq!(verification.verified_files, 1);
+        assert!(!verification.is_tampered());
+
+        // 替换文件内容但保留原清单
+        let tampered = dir.join("tampered.zip");
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest(vec![file_hash(
+            "overrides/config/a.toml",
+            b"a=1",
+        )]))
+        .unwrap();
+        let signature = sign_manifest(&manifest_bytes, &identity).unwrap();
+        let mut zip = ZipWriter::new(File::create(&tampered).unwrap());
+        let options = SimpleFileOptions::default();
+        zip.start_file("overrides/config/a.toml", options).unwrap();
+        zip.write_all(b"a=2").unwrap();
+        zip.start_file("overrides/mods/extra.jar", options).unwrap();
+        zip.write_all(b"jar").unwrap();
+        zip.start_file(PIPACK_MANIFEST_FILE, options).unwrap();
+        zip.write_all(&manifest_bytes).unwrap();
+        zip.start_file(PIPACK_SIGNATURE_FILE, options).unwrap();
+        zip.write_all(&serde_json::to_vec(&signature).unwrap())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let verification = verify_pipack_archive(tampered.to_str().unwrap()).unwrap();
+        assert!(verification.signature_valid);
+        assert_eq!(
+            verification.mismatched_files,
+            vec!["overrides/config/a.toml".to_string()]
+        );
+        assert_eq!(
+            verification.unlisted_files,
+            vec!["overrides/mods/extra.jar".to_string()]
+        );
+        assert!(verification.is_tampered());
+
+        let unsigned = dir.join("unsigned.zip");
+        write_pack(&unsigned, &[("overrides/options.txt", b"x")], None);
+        let verification = verify_pipack_archive(unsigned.to_str().unwrap()).unwrap();
+        assert!(!verification.signed);
+        assert!(verification.integrity_checked);
+        assert!(!verification.is_tampered());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
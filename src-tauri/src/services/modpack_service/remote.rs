@@ -0,0 +1,893 @@
+// src-tauri/src/services/modpack_service/remote.rs
+//! 远程整合包更新追踪：对从 Modrinth / CurseForge 下载的整合包查询新版本、
+//! 预下载并给出模组变化，最后交给 `execute_modpack_upgrade` 执行。
+use crate::domain::instance::InstanceConfig;
+use crate::domain::mod_manifest::ModSourceKind;
+use crate::domain::modpack::{
+    ModpackModDiff, ModpackModDiffEntry, ModpackRemoteRef, ModpackUpdateAvailableEvent,
+    RemoteModpackUpdatePreview, RemoteModpackUpdates, RemoteModpackVersion,
+};
+use crate::services::config_service::ConfigService;
+use crate::services::instance::mod_manifest_service::ModManifestService;
+
+use super::download::build_modpack_download_client;
+use super::merge::mod_identity;
+use super::ops::{open_modpack_archive, read_zip_entry_to_string, resolve_base_dir};
+use super::orchestrator::{
+    curseforge_edge_url, extract_modrinth_source_ids, resolve_curseforge_api_key,
+    CurseForgeEnvelope,
+};
+
+use chrono::DateTime;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+pub const MODPACK_UPDATE_EVENT: &str = "modpack-update-available";
+
+const MODRINTH_API: &str = "https://api.modrinth.com/v2";
+const CURSEFORGE_API: &str = "https://api.curseforge.com/v1";
+const MAX_CHANGELOG_FETCHES: usize = 10;
+const CURSEFORGE_LOADERS: [&str; 4] = ["forge", "fabric", "quilt", "neoforge"];
+
+#[derive(Deserialize)]
+struct ModrinthRawVersion {
+    id: String,
+    name: String,
+    version_number: String,
+    date_published: String,
+    #[serde(default)]
+    changelog: Option<String>,
+    #[serde(default)]
+    loaders: Vec<String>,
+    #[serde(default)]
+    game_versions: Vec<String>,
+    files: Vec<ModrinthRawFile>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthRawFile {
+    url: String,
+    filename: String,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeRawFile {
+    id: u64,
+    mod_id: u64,
+    display_name: String,
+    file_name: String,
+    file_date: String,
+    download_url: Option<String>,
+    #[serde(default)]
+    game_versions: Vec<String>,
+}
+
+/// 实例对应的远程来源；只有下载导入时记录了版本 ID 的实例才会被追踪。
+pub fn remote_ref_for_config(config: &InstanceConfig) -> Option<ModpackRemoteRef> {
+    let platform = config.modpack_source.as_deref()?.trim().to_lowercase();
+    if platform != "modrinth" && platform != "curseforge" {
+        return None;
+    }
+    let project_id = config
+        .modpack_id
+        .clone()
+        .filter(|id| !id.trim().is_empty())?;
+    let version_id = config
+        .modpack_version_id
+        .clone()
+        .filter(|id| !id.trim().is_empty())?;
+    Some(ModpackRemoteRef {
+        platform,
+        project_id,
+        version_id: Some(version_id),
+    })
+}
+
+/// 从 forgecdn 下载地址推出 CurseForge 文件 ID（路径形如 files/4567/890/name.zip）。
+pub fn curseforge_file_id_from_url(url: &str) -> Option<u64> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    if parsed.host_str()?.ends_with("forgecdn.net") {
+        let index = segments.iter().position(|s| *s == "files")?;
+        let high: u64 = segments.get(index + 1)?.parse().ok()?;
+        let low: u64 = segments.get(index + 2)?.parse().ok()?;
+        return Some(high * 1000 + low);
+    }
+    // https://www.curseforge.com/api/v1/mods/{modId}/files/{fileId}/download
+    let index = segments.iter().position(|s| *s == "files")?;
+    segments.get(index + 1)?.parse().ok()
+}
+
+async fn resolve_remote_ref(
+    client: &Client,
+    url: &str,
+) -> Result<Option<ModpackRemoteRef>, String> {
+    if let Some((project_id, version_id)) = extract_modrinth_source_ids(url) {
+        return Ok(Some(ModpackRemoteRef {
+            platform: "modrinth".to_string(),
+            project_id,
+            version_id: Some(version_id),
+        }));
+    }
+
+    let Some(file_id) = curseforge_file_id_from_url(url) else {
+        return Ok(None);
+    };
+    let api_key =
+        resolve_curseforge_api_key().ok_or_else(|| "Missing CurseForge API key".to_string())?;
+    let res = client
+        .post(format!("{}/mods/files", CURSEFORGE_API))
+        .header("x-api-key", api_key)
+        .json(&serde_json::json!({ "fileIds": [file_id] }))
+        .send()
+        .await
+        .map_err(|e| format!("CurseForge request failed: {}", e))?;
+    if !res.status().is_success() {
+        return Err(format!("CurseForge request failed: {}", res.status()));
+    }
+    let payload: CurseForgeEnvelope<Vec<CurseForgeRawFile>> = res
+        .json()
+        .await
+        .map_err(|e| format!("CurseForge response parse failed: {}", e))?;
+    Ok(payload
+        .data
+        .into_iter()
+        .next()
+        .map(|file| ModpackRemoteRef {
+            platform: "curseforge".to_string(),
+            project_id: file.mod_id.to_string(),
+            version_id: Some(file.id.to_string()),
+        }))
+}
+
+/// 下载导入完成后记录整合包的远程来源，供之后检查更新使用。
+pub async fn record_remote_source<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+    download_url: &str,
+) -> Result<bool, String> {
+    let client = build_modpack_download_client(&ConfigService::get_download_settings(app))?;
+    let Some(remote) = resolve_remote_ref(&client, download_url).await? else {
+        return Ok(false);
+    };
+
+    let instance_json_path = resolve_base_dir(app)?
+        .join("instances")
+        .join(instance_id)
+        .join("instance.json");
+    let mut config = read_instance_config(&instance_json_path)?;
+    config.modpack_source = Some(platform_display_name(&remote.platform).to_string());
+    config.modpack_id = Some(remote.project_id);
+    config.modpack_version_id = remote.version_id;
+    fs::write(
+        &instance_json_path,
+        serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| format!("Failed to write instance.json: {}", e))?;
+    Ok(true)
+}
+
+pub async fn check_remote_updates<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+) -> Result<RemoteModpackUpdates, String> {
+    let config = read_instance_config(&instance_json_path(app, instance_id)?)?;
+    let remote = remote_ref_for_config(&config).ok_or_else(|| {
+        "This instance is not linked to a Modrinth or CurseForge modpack".to_string()
+    })?;
+    let client = build_modpack_download_client(&ConfigService::get_download_settings(app))?;
+
+    let versions = list_compatible_versions(&client, &remote, &config).await?;
+    let current_id = remote.version_id.clone().unwrap_or_default();
+    let current_published = match versions.iter().find(|v| v.version_id == current_id) {
+        Some(version) => Some(version.published_at.clone()),
+        None => fetch_version_published_at(&client, &remote).await.ok(),
+    };
+    let mut newer = select_newer_versions(versions, &current_id, current_published.as_deref());
+    fill_missing_changelogs(&client, &remote, &mut newer).await;
+
+    Ok(RemoteModpackUpdates {
+        instance_id: instance_id.to_string(),
+        platform: remote.platform,
+        project_id: remote.project_id,
+        current_version: config.modpack_version,
+        current_version_id: remote.version_id,
+        versions: newer,
+    })
+}
+
+/// 用本地文件升级已关联远程来源的实例时，在远程版本列表中按文件名或版本号找回对应的版本 ID。
+/// `config` 应为升级后的配置（游戏版本与加载器可能已变化）。
+pub async fn match_remote_version_id<R: Runtime>(
+    app: &AppHandle<R>,
+    remote: &ModpackRemoteRef,
+    config: &InstanceConfig,
+    pack_version: Option<&str>,
+    file_name: Option<&str>,
+) -> Option<String> {
+    let client = build_modpack_download_client(&ConfigService::get_download_settings(app)).ok()?;
+    let versions = list_compatible_versions(&client, remote, config)
+        .await
+        .ok()?;
+    find_pack_version(&versions, pack_version, file_name).map(|v| v.version_id.clone())
+}
+
+fn find_pack_version<'a>(
+    versions: &'a [RemoteModpackVersion],
+    pack_version: Option<&str>,
+    file_name: Option<&str>,
+) -> Option<&'a RemoteModpackVersion> {
+    let matches = |value: &str, expected: Option<&str>| {
+        expected.is_some_and(|expected| !expected.trim().is_empty() && value == expected.trim())
+    };
+    versions
+        .iter()
+        .find(|v| matches(&v.file_name, file_name))
+        .or_else(|| {
+            versions.iter().find(|v| {
+                matches(&v.version_number, pack_version) || matches(&v.name, pack_version)
+            })
+        })
+}
+
+/// 下载选定版本的整合包并与当前实例的模组比较，返回的 `pack_path` 直接用于升级。
+pub async fn prepare_remote_update<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+    version_id: &str,
+) -> Result<RemoteModpackUpdatePreview, String> {
+    let base_dir = resolve_base_dir(app)?;
+    let instance_dir = base_dir.join("instances").join(instance_id);
+    let config = read_instance_config(&instance_dir.join("instance.json"))?;
+    let remote = remote_ref_for_config(&config).ok_or_else(|| {
+        "This instance is not linked to a Modrinth or CurseForge modpack".to_string()
+    })?;
+    let client = build_modpack_download_client(&ConfigService::get_download_settings(app))?;
+
+    let mut version = list_compatible_versions(&client, &remote, &config)
+        .await?
+        .into_iter()
+        .find(|v| v.version_id == version_id)
+        .ok_or_else(|| format!("Modpack version {} not found", version_id))?;
+    if version.changelog.is_none() {
+        version.changelog = fetch_changelog(&client, &remote, &version.version_id)
+            .await
+            .ok();
+    }
+
+    let update_dir = base_dir
+        .join("temp")
+        .join("modpack")
+        .join("updates")
+        .join(instance_id);
+    let _ = fs::remove_dir_all(&update_dir);
+    fs::create_dir_all(&update_dir).map_err(|e| e.to_string())?;
+    let file_name = Path::new(&version.file_name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "modpack.zip".to_string());
+    let pack_path = update_dir.join(file_name);
+
+    let bytes = client
+        .get(&version.download_url)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| format!("Failed to download modpack: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download modpack: {}", e))?;
+    fs::write(&pack_path, &bytes).map_err(|e| format!("Failed to save modpack: {}", e))?;
+
+    let pack_path_str = pack_path.to_string_lossy().to_string();
+    let incoming = read_pack_mod_sides(&pack_path_str)?;
+    let manifest = ModManifestService::sync_from_mods_dir(
+        &instance_dir.join("mods"),
+        &instance_dir.join("mod_manifest.json"),
+    )?;
+    let mut current = BTreeMap::new();
+    let mut pack_owned = HashSet::new();
+    for (file_key, entry) in &manifest {
+        let identity = mod_identity(file_key, entry);
+        let marker = if identity.starts_with("curseforge:") {
+            entry
+                .source
+                .file_id
+                .clone()
+                .unwrap_or_else(|| entry.hash.value.clone())
+        } else {
+            entry.hash.value.clone()
+        };
+        if entry.source.kind == ModSourceKind::ModpackDeployment {
+            pack_owned.insert(identity.clone());
+        }
+        current.insert(
+            identity,
+            DiffSide {
+                label: file_key.clone(),
+                marker,
+            },
+        );
+    }
+
+    Ok(RemoteModpackUpdatePreview {
+        instance_id: instance_id.to_string(),
+        version,
+        pack_path: pack_path_str,
+        diff: diff_pack_mods(&current, &incoming, &pack_owned),
+    })
+}
+
+/// 后台定期检查所有已关联远程来源的实例，发现新版本时发出 `modpack-update-available` 事件。
+pub fn start_background_update_check<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        // 避开启动高峰
+        tokio::time::sleep(Duration::from_secs(90)).await;
+        let mut notified = HashMap::new();
+        loop {
+            let settings = ConfigService::get_modpack_update_settings(&app);
+            if settings.auto_check {
+                check_all_instances(&app, &mut notified).await;
+            }
+            let hours = settings.interval_hours.max(1);
+            tokio::time::sleep(Duration::from_secs(hours * 3600)).await;
+        }
+    });
+}
+
+/// 同一实例的同一新版本只提醒一次，出现更新的版本时再次提醒
+fn should_notify_update(
+    notified: &mut HashMap<String, String>,
+    instance_id: &str,
+    version_id: &str,
+) -> bool {
+    if notified.get(instance_id).map(String::as_str) == Some(version_id) {
+        return false;
+    }
+    notified.insert(instance_id.to_string(), version_id.to_string());
+    true
+}
+
+async fn check_all_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    notified: &mut HashMap<String, String>,
+) {
+    let Ok(base_dir) = resolve_base_dir(app) else {
+        return;
+    };
+    let Ok(read_dir) = fs::read_dir(base_dir.join("instances")) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let instance_id = entry.file_name().to_string_lossy().to_string();
+        let Ok(config) = read_instance_config(&entry.path().join("instance.json")) else {
+            continue;
+        };
+        if remote_ref_for_config(&config).is_none() {
+            continue;
+        }
+        match check_remote_updates(app, &instance_id).await {
+            Ok(updates) => {
+                let latest = updates.versions.first().filter(|latest| {
+                    should_notify_update(notified, &instance_id, &latest.version_id)
+                });
+                if let Some(latest) = latest {
+                    let _ = app.emit(
+                        MODPACK_UPDATE_EVENT,
+                        ModpackUpdateAvailableEvent {
+                            instance_id: instance_id.clone(),
+                            instance_name: config.name.clone(),
+                            current_version: updates.current_version.clone(),
+                            latest_version: latest.version_number.clone(),
+                            latest_version_id: latest.version_id.clone(),
+                        },
+                    );
+                }
+            }
+            Err(e) => eprintln!("Modpack update check failed for {}: {}", instance_id, e),
+        }
+    }
+}
+
+async fn list_compatible_versions(
+    client: &Client,
+    remote: &ModpackRemoteRef,
+    config: &InstanceConfig,
+) -> Result<Vec<RemoteModpackVersion>, String> {
+    let loader = normalized_loader(&config.loader.r#type);
+    match remote.platform.as_str() {
+        "modrinth" => {
+            let mut query = vec![("game_versions", format!("[\"{}\"]", config.mc_version))];
+            if let Some(loader) = &loader {
+                query.push(("loaders", format!("[\"{}\"]", loader)));
+            }
+            let raw: Vec<ModrinthRawVersion> = client
+                .get(format!(
+                    "{}/project/{}/version",
+                    MODRINTH_API, remote.project_id
+                ))
+                .query(&query)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())
+                .map_err(|e| format!("Modrinth request failed: {}", e))?
+                .json()
+                .await
+                .map_err(|e| format!("Modrinth response parse failed: {}", e))?;
+            Ok(raw.into_iter().filter_map(modrinth_version).collect())
+        }
+        "curseforge" => {
+            let api_key = resolve_curseforge_api_key()
+                .ok_or_else(|| "Missing CurseForge API key".to_string())?;
+            let payload: CurseForgeEnvelope<Vec<CurseForgeRawFile>> = client
+                .get(format!(
+                    "{}/mods/{}/files",
+                    CURSEFORGE_API, remote.project_id
+                ))
+                .header("x-api-key", api_key)
+                .query(&[
+                    ("gameVersion", config.mc_version.as_str()),
+                    ("pageSize", "50"),
+                ])
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())
+                .map_err(|e| format!("CurseForge request failed: {}", e))?
+                .json()
+                .await
+                .map_err(|e| format!("CurseForge response parse failed: {}", e))?;
+            Ok(payload
+                .data
+                .into_iter()
+                .map(curseforge_version)
+                .filter(|v| curseforge_loader_matches(&v.loaders, loader.as_deref()))
+                .collect())
+        }
+        other => Err(format!("Unsupported modpack platform: {}", other)),
+    }
+}
+
+fn modrinth_version(raw: ModrinthRawVersion) -> Option<RemoteModpackVersion> {
+    let file = raw
+        .files
+        .iter()
+        .find(|f| f.primary)
+        .or_else(|| raw.files.first())?;
+    Some(RemoteModpackVersion {
+        version_id: raw.id,
+        name: raw.name,
+        version_number: raw.version_number,
+        changelog: raw.changelog.filter(|c| !c.trim().is_empty()),
+        published_at: raw.date_published,
+        file_name: file.filename.clone(),
+        download_url: file.url.clone(),
+        game_versions: raw.game_versions,
+        loaders: raw.loaders,
+    })
+}
+
+fn curseforge_version(raw: CurseForgeRawFile) -> RemoteModpackVersion {
+    let (loaders, game_versions): (Vec<String>, Vec<String>) = raw
+        .game_versions
+        .into_iter()
+        .partition(|v| CURSEFORGE_LOADERS.contains(&v.to_lowercase().as_str()));
+    let download_url = raw
+        .download_url
+        .map(|url| url.trim().replace(' ', "%20"))
+        .unwrap_or_else(|| curseforge_edge_url(raw.id, &raw.file_name));
+    RemoteModpackVersion {
+        version_id: raw.id.to_string(),
+        name: raw.display_name.clone(),
+        version_number: raw.display_name,
+        changelog: None,
+        published_at: raw.file_date,
+        file_name: raw.file_name,
+        download_url,
+        game_versions,
+        loaders: loaders.into_iter().map(|l| l.to_lowercase()).collect(),
+    }
+}
+
+/// CurseForge 整合包文件不一定标注加载器；未标注时视为兼容。
+fn curseforge_loader_matches(loaders: &[String], loader: Option<&str>) -> bool {
+    match loader {
+        Some(loader) if !loaders.is_empty() => loaders.iter().any(|l| l == loader),
+        _ => true,
+    }
+}
+
+fn normalized_loader(loader_type: &str) -> Option<String> {
+    let loader = loader_type.trim().to_lowercase();
+    if loader.is_empty() || loader == "vanilla" {
+        None
+    } else {
+        Some(loader)
+    }
+}
+
+async fn fetch_version_published_at(
+    client: &Client,
+    remote: &ModpackRemoteRef,
+) -> Result<String, String> {
+    let version_id = remote.version_id.as_deref().unwrap_or_default();
+    match remote.platform.as_str() {
+        "modrinth" => {
+            let raw: ModrinthRawVersion = client
+                .get(format!("{}/version/{}", MODRINTH_API, version_id))
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())
+                .map_err(|e| e.to_string())?
+                .json()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(raw.date_published)
+        }
+        _ => {
+            let api_key = resolve_curseforge_api_key()
+                .ok_or_else(|| "Missing CurseForge API key".to_string())?;
+            let payload: CurseForgeEnvelope<CurseForgeRawFile> = client
+                .get(format!(
+                    "{}/mods/{}/files/{}",
+                    CURSEFORGE_API, remote.project_id, version_id
+                ))
+                .header("x-api-key", api_key)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())
+                .map_err(|e| e.to_string())?
+                .json()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(payload.data.file_date)
+        }
+    }
+}
+
+async fn fetch_changelog(
+    client: &Client,
+    remote: &ModpackRemoteRef,
+    version_id: &str,
+) -> Result<String, String> {
+    if remote.platform != "curseforge" {
+        return Err("Changelog is included in the version listing".to_string());
+    }
+    let api_key =
+        resolve_curseforge_api_key().ok_or_else(|| "Missing CurseForge API key".to_string())?;
+    let payload: CurseForgeEnvelope<String> = client
+        .get(format!(
+            "{}/mods/{}/files/{}/changelog",
+            CURSEFORGE_API, remote.project_id, version_id
+        ))
+        .header("x-api-key", api_key)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(payload.data)
+}
+
+async fn fill_missing_changelogs(
+    client: &Client,
+    remote: &ModpackRemoteRef,
+    versions: &mut [RemoteModpackVersion],
+) {
+    for version in versions.iter_mut().take(MAX_CHANGELOG_FETCHES) {
+        if version.changelog.is_none() {
+            version.changelog = fetch_changelog(client, remote, &version.version_id)
+                .await
+                .ok();
+        }
+    }
+}
+
+/// 返回比当前版本更新的版本（最新在前）。当前版本在列表中时以其位置为界，
+/// 否则按发布时间比较；两者都不可用时返回全部。
+pub fn select_newer_versions(
+    mut versions: Vec<RemoteModpackVersion>,
+    current_id: &str,
+    current_published: Option<&str>,
+) -> Vec<RemoteModpackVersion> {
+    versions.sort_by_key(|v| std::cmp::Reverse(published_key(&v.published_at)));
+    if let Some(position) = versions.iter().position(|v| v.version_id == current_id) {
+        versions.truncate(position);
+        return versions;
+    }
+    match current_published {
+        Some(published) => {
+            let current = published_key(published);
+            versions
+                .into_iter()
+                .filter(|v| published_key(&v.published_at) > current)
+                .collect()
+        }
+        None => versions,
+    }
+}
+
+fn published_key(value: &str) -> i64 {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffSide {
+    /// 展示给用户的名称（文件名或 CurseForge 文件 ID）
+    pub label: String,
+    /// 用于判断是否变化的值（sha1 或 CurseForge 文件 ID）
+    pub marker: String,
+}
+
+pub fn read_pack_mod_sides(pack_path: &str) -> Result<BTreeMap<String, DiffSide>, String> {
+    let mut archive = open_modpack_archive(pack_path)?;
+    let mut sides = BTreeMap::new();
+
+    if let Ok(contents) = read_zip_entry_to_string(&mut archive, "modrinth.index.json") {
+        let index: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse Modrinth index: {}", e))?;
+        for file in index["files"].as_array().cloned().unwrap_or_default() {
+            let Some(path) = file["path"].as_str() else {
+                continue;
+            };
+            if !path.starts_with("mods/") {
+                continue;
+            }
+            let file_name = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string());
+            let identity = file["downloads"][0]
+                .as_str()
+                .and_then(extract_modrinth_source_ids)
+                .map(|(project_id, _)| format!("modrinth:{}", project_id))
+                .unwrap_or_else(|| file_name.clone());
+            let marker = file["hashes"]["sha1"]
+                .as_str()
+                .map(|s| s.to_lowercase())
+                .unwrap_or_default();
+            sides.insert(
+                identity,
+                DiffSide {
+                    label: file_name,
+                    marker,
+                },
+            );
+        }
+        return Ok(sides);
+    }
+
+    let contents = read_zip_entry_to_string(&mut archive, "manifest.json")?;
+    let manifest: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse CurseForge manifest: {}", e))?;
+    for file in manifest["files"].as_array().cloned().unwrap_or_default() {
+        let (Some(project_id), Some(file_id)) =
+            (file["projectID"].as_u64(), file["fileID"].as_u64())
+        else {
+            continue;
+        };
+        sides.insert(
+            format!("curseforge:{}", project_id),
+            DiffSide {
+                label: file_id.to_string(),
+                marker: file_id.to_string(),
+            },
+        );
+    }
+    Ok(sides)
+}
+
+/// 比较当前实例与新版整合包的模组；只有整合包部署的模组才会被列为移除。
+pub fn diff_pack_mods(
+    current: &BTreeMap<String, DiffSide>,
+    incoming: &BTreeMap<String, DiffSide>,
+    pack_owned: &HashSet<String>,
+) -> ModpackModDiff {
+    let mut diff = ModpackModDiff::default();
+    for (identity, side) in incoming {
+        match current.get(identity) {
+            None => diff.added.push(ModpackModDiffEntry {
+                identity: identity.clone(),
+                from: None,
+                to: Some(side.label.clone()),
+            }),
+            Some(existing) if existing.marker != side.marker => {
+                diff.updated.push(ModpackModDiffEntry {
+                    identity: identity.clone(),
+                    from: Some(existing.label.clone()),
+                    to: Some(side.label.clone()),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for (identity, side) in current {
+        if pack_owned.contains(identity) && !incoming.contains_key(identity) {
+            diff.removed.push(ModpackModDiffEntry {
+                identity: identity.clone(),
+                from: Some(side.label.clone()),
+                to: None,
+            });
+        }
+    }
+    diff
+}
+
+fn platform_display_name(platform: &str) -> &'static str {
+    if platform == "curseforge" {
+        "CurseForge"
+    } else {
+        "Modrinth"
+    }
+}
+
+fn instance_json_path<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+) -> Result<std::path::PathBuf, String> {
+    Ok(resolve_base_dir(app)?
+        .join("instances")
+        .join(instance_id)
+        .join("instance.json"))
+}
+
+fn read_instance_config(path: &Path) -> Result<InstanceConfig, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read instance.json: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse instance.json: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, published_at: &str) -> RemoteModpackVersion {
+        RemoteModpackVersion {
+            version_id: id.to_string(),
+            name: id.to_string(),
+            version_number: id.to_string(),
+            changelog: None,
+            published_at: published_at.to_string(),
+            file_name: format!("{}.mrpack", id),
+            download_url: String::new(),
+            game_versions: vec!["1.20.1".to_string()],
+            loaders: vec!["fabric".to_string()],
+        }
+    }
+
+    fn side(label: &str, marker: &str) -> DiffSide {
+        DiffSide {
+            label: label.to_string(),
+            marker: marker.to_string(),
+        }
+    }
+
+    #[test]
+    fn update_event_is_sent_once_per_version() {
+        let mut notified = HashMap::new();
+        assert!(should_notify_update(&mut notified, "pack", "v2"));
+        assert!(!should_notify_update(&mut notified, "pack", "v2"));
+        assert!(should_notify_update(&mut notified, "other", "v2"));
+        assert!(should_notify_update(&mut notified, "pack", "v3"));
+    }
+
+    #[test]
+    fn local_pack_is_matched_by_file_name_then_version() {
+        let versions = vec![
+            version("1.1.0", "2024-02-01T00:00:00Z"),
+            version("1.0.0", "2024-01-01T00:00:00Z"),
+        ];
+        let by_file = find_pack_version(&versions, Some("9.9"), Some("1.0.0.mrpack"));
+        assert_eq!(by_file.map(|v| v.version_id.as_str()), Some("1.0.0"));
+        let by_version = find_pack_version(&versions, Some("1.1.0"), Some("renamed.mrpack"));
+        assert_eq!(by_version.map(|v| v.version_id.as_str()), Some("1.1.0"));
+        assert!(find_pack_version(&versions, Some(""), None).is_none());
+    }
+
+    #[test]
+    fn curseforge_file_id_is_parsed_from_cdn_and_api_urls() {
+        assert_eq!(
+            curseforge_file_id_from_url("https://edge.forgecdn.net/files/4567/890/Pack-1.2.zip"),
+            Some(4_567_890)
+        );
+        assert_eq!(
+            curseforge_file_id_from_url(
+                "https://www.curseforge.com/api/v1/mods/123/files/4567890/download"
+            ),
+            Some(4_567_890)
+        );
+        assert_eq!(
+            curseforge_file_id_from_url("https://example.com/pack.zip"),
+            None
+        );
+    }
+
+    #[test]
+    fn newer_versions_are_bounded_by_current_version() {
+        let versions = vec![
+            version("v1", "2024-01-01T00:00:00Z"),
+            version("v3", "2024-03-01T00:00:00Z"),
+            version("v2", "2024-02-01T00:00:00Z"),
+        ];
+        let newer = select_newer_versions(versions.clone(), "v2", None);
+        assert_eq!(
+            newer
+                .iter()
+                .map(|v| v.version_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["v3"]
+        );
+
+        // 当前版本不在兼容列表中时按发布时间比较
+        let newer = select_newer_versions(versions, "old", Some("2024-01-15T00:00:00+00:00"));
+        assert_eq!(
+            newer
+                .iter()
+                .map(|v| v.version_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["v3", "v2"]
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_updated_and_pack_removed_mods() {
+        let current: BTreeMap<String, DiffSide> = [
+            ("modrinth:aaa".to_string(), side("a-1.jar", "h1")),
+            ("modrinth:bbb".to_string(), side("b.jar", "hb")),
+            ("dropped.jar".to_string(), side("dropped.jar", "hd")),
+            ("user.jar".to_string(), side("user.jar", "hu")),
+        ]
+        .into_iter()
+        .collect();
+        let incoming: BTreeMap<String, DiffSide> = [
+            ("modrinth:aaa".to_string(), side("a-2.jar", "h2")),
+            ("modrinth:bbb".to_string(), side("b.jar", "hb")),
+            ("modrinth:ccc".to_string(), side("c.jar", "hc")),
+        ]
+        .into_iter()
+        .collect();
+        let pack_owned: HashSet<String> = ["modrinth:aaa", "modrinth:bbb", "dropped.jar"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let diff = diff_pack_mods(&current, &incoming, &pack_owned);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].identity, "modrinth:ccc");
+        assert_eq!(diff.updated.len(), 1);
+        assert_eq!(diff.updated[0].from.as_deref(), Some("a-1.jar"));
+        assert_eq!(diff.updated[0].to.as_deref(), Some("a-2.jar"));
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].identity, "dropped.jar");
+    }
+
+    #[test]
+    fn curseforge_versions_split_loaders_from_game_versions() {
+        let raw = CurseForgeRawFile {
+            id: 5_001_002,
+            mod_id: 42,
+            display_name: "Pack 2.0".to_string(),
+            file_name: "Pack 2.0.zip".to_string(),
+            file_date: "2024-03-01T00:00:00Z".to_string(),
+            download_url: None,
+            game_versions: vec!["1.20.1".to_string(), "Forge".to_string()],
+        };
+        let version = curseforge_version(raw);
+        assert_eq!(version.loaders, vec!["forge".to_string()]);
+        assert_eq!(version.game_versions, vec!["1.20.1".to_string()]);
+        assert!(version.download_url.contains("/files/5001/002/"));
+        assert!(curseforge_loader_matches(&version.loaders, Some("forge")));
+        assert!(!curseforge_loader_matches(&version.loaders, Some("fabric")));
+    }
+}
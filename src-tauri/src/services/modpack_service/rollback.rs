@@ -2,6 +2,7 @@
 use crate::domain::instance::InstanceConfig;
 use crate::services::instance::backup_service::restore_backup_data;
 use crate::services::instance::mod_manifest_service::ModManifestService;
+use crate::services::modpack_service::merge;
 use crate::services::modpack_service::ops::resolve_base_dir;
 
 use std::fs;
@@ -42,16 +43,34 @@ pub async fn rollback_modpack_upgrade<R: Runtime>(
         return Err(format!("Backup zip file not found: {}", backup_zip_path.display()));
     }
 
-    // Step 1: Clean current configurations to prevent merge issues
+    // Step 1: Clean current configurations and merge state to prevent merge issues
     let current_config_dir = instance_dir.join("config");
     if current_config_dir.exists() {
         let _ = fs::remove_dir_all(&current_config_dir);
     }
+    merge::clear_merge_state(&instance_dir);
+    // 旧基线（如有）随备份一起恢复
+    let _ = fs::remove_file(instance_dir.join(merge::PACK_BASELINE_FILE));
 
     // Step 2: Restore options.txt, servers.dat, saves, config, and mod_manifest.json from backup ZIP
     restore_backup_data(&instance_dir, &backup_zip_path)?;
 
-    // Step 3: Delete mods that are not in the restored manifest
+    // Step 3: Move back mods displaced by the upgrade merge, then delete mods that are not in the restored manifest
+    let displaced_mods_dir = index_json["displacedModsPath"].as_str().map(|p| instance_dir.join(p));
+    if let Some(displaced_dir) = &displaced_mods_dir {
+        if let Ok(read_dir) = fs::read_dir(displaced_dir) {
+            let mods_dir = instance_dir.join("mods");
+            fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?;
+            for entry in read_dir.flatten() {
+                let target = mods_dir.join(entry.file_name());
+                if fs::rename(entry.path(), &target).is_err() {
+                    fs::copy(entry.path(), &target)
+                        .map_err(|e| format!("Failed to restore displaced mod: {}", e))?;
+                }
+            }
+        }
+    }
+
     let manifest_path = instance_dir.join("mod_manifest.json");
     if manifest_path.exists() {
         let restored_manifest = ModManifestService::read_manifest_robust(&manifest_path);
@@ -100,6 +119,9 @@ pub async fn rollback_modpack_upgrade<R: Runtime>(
     // Step 5: Clean up backup index and backup zip
     let _ = fs::remove_file(&backup_index_path);
     let _ = fs::remove_file(&backup_zip_path);
+    if let Some(displaced_dir) = displaced_mods_dir {
+        let _ = fs::remove_dir_all(displaced_dir);
+    }
 
     Ok(())
 }
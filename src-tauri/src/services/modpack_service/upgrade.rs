@@ -1,14 +1,17 @@
 // src-tauri/src/services/modpack_service/upgrade.rs
 use crate::domain::instance::InstanceConfig;
-use crate::domain::modpack::ModpackUpgradeInfo;
-use crate::domain::mod_manifest::{ModSourceKind};
+use crate::domain::modpack::{ModpackMergeReport, ModpackMergeTarget, ModpackUpgradeInfo};
 use crate::services::instance::backup_service::backup_instance_data;
 use crate::services::instance::mod_manifest_service::ModManifestService;
 use crate::services::modpack_service::ops::{
-    open_modpack_archive, read_zip_entry_to_string, detect_modpack_source, resolve_base_dir,
+    detect_modpack_location, local_packwiz_root, open_modpack_archive, read_zip_entry_to_string,
+    detect_modpack_source, extract_overrides, resolve_base_dir,
 };
+use crate::services::modpack_service::merge;
+use crate::services::modpack_service::remote;
+use crate::services::modpack_service::packwiz;
 use crate::services::modpack_service::logic::ModpackSourceHint;
-use crate::services::modpack_service::orchestrator::execute_import;
+use crate::services::modpack_service::orchestrator::{execute_import, stage_remote_packwiz};
 
 use chrono::Local;
 use std::collections::HashSet;
@@ -51,7 +54,8 @@ pub async fn check_modpack_update<R: Runtime>(
 
     // If a new local pack path is provided, read its metadata and compare versions
     if let Some(zip_path) = new_pack_path {
-        let metadata = crate::services::modpack_service::ops::parse_modpack(&zip_path)?;
+        let metadata =
+            crate::services::modpack_service::ops::parse_modpack_location(&zip_path).await?;
         let has_update = match (&config.modpack_version, &metadata.pack_version) {
             (Some(cur), Some(new)) => cur != new,
             _ => true,
@@ -76,6 +80,29 @@ pub async fn check_modpack_update<R: Runtime>(
         });
     }
 
+    // Instances downloaded from Modrinth / CurseForge are checked against the platform
+    if remote::remote_ref_for_config(&config).is_some() {
+        let updates = remote::check_remote_updates(app, instance_id).await?;
+        let latest = updates.versions.first();
+        return Ok(ModpackUpgradeInfo {
+            has_update: latest.is_some(),
+            current_version: config.modpack_version.clone(),
+            latest_version: latest
+                .map(|v| v.version_number.clone())
+                .or_else(|| config.modpack_version.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+            changelog: latest.and_then(|v| v.changelog.clone()),
+            new_mc_version: config.mc_version.clone(),
+            new_loader_type: config.loader.r#type.clone(),
+            new_loader_version: config.loader.version.clone(),
+            current_mc_version: config.mc_version.clone(),
+            backup_original_version: b_version,
+            backup_original_mc_version: b_mc_version,
+            backup_original_loader_type: b_loader_type,
+            backup_original_loader_version: b_loader_version,
+        });
+    }
+
     // Otherwise, simulate a mock update for testing
     let current_ver = config.modpack_version.clone().unwrap_or_else(|| "1.0.0".to_string());
     let has_update = current_ver != "1.1.0";
@@ -119,7 +146,8 @@ pub async fn execute_modpack_upgrade<R: Runtime>(
     instance_id: &str,
     new_pack_path: &str,
     skip_backup: Option<bool>,
-) -> Result<(), String> {
+    remote_version_id: Option<String>,
+) -> Result<ModpackMergeReport, String> {
     let base_dir = resolve_base_dir(app)?;
     let instance_dir = base_dir.join("instances").join(instance_id);
     let instance_json_path = instance_dir.join("instance.json");
@@ -132,10 +160,18 @@ pub async fn execute_modpack_upgrade<R: Runtime>(
     let old_config: InstanceConfig = serde_json::from_str(&config_content)
         .map_err(|e| format!("Failed to parse instance.json: {}", e))?;
 
+    let timestamp = Local::now().format("%Y%m%d%H%M%S").to_string();
+    let backup_dir = instance_dir.join(".backups");
+    let work_dir = backup_dir.join(format!("upgrade-{}-merge", timestamp));
+
+    // 远程 packwiz 包与安装时一样先镜像到本地，后续提取与导入都使用本地副本
+    let staged_pack_path = stage_remote_packwiz(app, new_pack_path, &work_dir.join("pack")).await?;
+    let new_pack_path = staged_pack_path.as_deref().unwrap_or(new_pack_path);
+    // 合并过程中被移出的用户模组放在这里，回滚时移回 mods/
+    let displaced_mods_name = format!("upgrade-{}-mods", timestamp);
+
     // Step 1: Backup sensitive directories and files if not skipped
     let backup_zip_path = if !skip_backup.unwrap_or(false) {
-        let timestamp = Local::now().format("%Y%m%d%H%M%S").to_string();
-        let backup_dir = instance_dir.join(".backups");
         let backup_zip_name = format!("upgrade-{}.zip", timestamp);
         let backup_zip_path = backup_dir.join(&backup_zip_name);
 
@@ -145,6 +181,7 @@ pub async fn execute_modpack_upgrade<R: Runtime>(
         let backup_index_path = instance_dir.join("backup_index.json");
         let index_json = serde_json::json!({
             "backupPath": format!(".backups/{}", backup_zip_name),
+            "displacedModsPath": format!(".backups/{}", displaced_mods_name),
             "originalVersion": old_config.modpack_version.clone().unwrap_or_else(|| "1.0.0".to_string()),
             "originalMcVersion": old_config.mc_version.clone(),
             "originalLoaderType": old_config.loader.r#type.clone(),
@@ -175,86 +212,185 @@ pub async fn execute_modpack_upgrade<R: Runtime>(
         )
         .map_err(|e| format!("Failed to write updated instance config: {}", e))?;
 
-        return Ok(());
+        return Ok(ModpackMergeReport {
+            from_version: old_config.modpack_version,
+            to_version: updated_config.modpack_version,
+            ..ModpackMergeReport::default()
+        });
     }
 
-    // Step 2: Diff Mod List and delete old ModpackDeployment mods
+    // Step 2: Snapshot the three sides of the merge.
+    // base = what the previous pack version shipped, ours = the instance as the user left it,
+    // theirs = the new pack's overrides (mods are known only after the import below).
+    merge::clear_merge_state(&instance_dir);
+    let mods_dir = instance_dir.join("mods");
     let manifest_path = instance_dir.join("mod_manifest.json");
-    let current_manifest = if manifest_path.exists() {
-        ModManifestService::read_manifest_robust(&manifest_path)
-    } else {
-        std::collections::HashMap::new()
-    };
-
-    let new_mod_keys = get_new_modpack_mod_keys(new_pack_path)?;
-    let mut files_to_delete = Vec::new();
+    let current_manifest = ModManifestService::sync_from_mods_dir(&mods_dir, &manifest_path)?;
+
+    let stored_baseline = merge::load_baseline(&instance_dir);
+    let has_baseline = stored_baseline.is_some();
+    let baseline = stored_baseline
+        .unwrap_or_else(|| merge::legacy_baseline(&current_manifest, &mods_dir));
+    let ours_mods = merge::tracked_mods(&current_manifest, &mods_dir, |_, _| true);
+    let ours_files = merge::hash_config_tree(&instance_dir)?;
+
+    let ours_root = work_dir.join("ours");
+    let theirs_root = work_dir.join("theirs");
+    let mods_before = merge::list_mod_files(&mods_dir);
+    merge::copy_config_tree(&instance_dir, &ours_root)?;
+    merge::snapshot_mods(&mods_dir, &ours_mods, &ours_root)?;
+    fs::create_dir_all(&theirs_root).map_err(|e| e.to_string())?;
+    extract_overrides(new_pack_path, &theirs_root)?;
+    let theirs_files = merge::hash_config_tree(&theirs_root)?;
 
-    for (file_key, entry) in &current_manifest {
-        if entry.source.kind == ModSourceKind::ModpackDeployment {
-            let mut is_kept = false;
-
-            // Check by project id (For CurseForge)
-            if let Some(proj_id) = &entry.source.project_id {
-                if new_mod_keys.contains(proj_id) {
-                    is_kept = true;
-                }
-            }
+    // Step 3: Run the import orchestrator to extract new overrides and download new mods.
+    // By passing the same instance name, the orchestrator will target the exact same directory.
+    let cancel = Arc::new(AtomicBool::new(false));
+    let import_result = execute_import(app, new_pack_path, &old_config.name, &cancel, None).await;
+    // 跳过备份时没有回滚索引引用被替换的用户模组，升级成功后一并清理
+    let displaced_mods_dir = backup_dir.join(&displaced_mods_name);
+
+    let merged = import_result.and_then(|_| {
+        // Step 4: Selective restore of user data (saves, screenshots, keybinds, servers)
+        // Because the overrides extraction in execute_import might have overwritten option files.
+        if let Some(zip_path) = &backup_zip_path {
+            restore_user_sensitive_data(&instance_dir, zip_path)?;
+        }
 
-            // Check by filename/normalized key (For Modrinth / PiPack)
-            for new_key in &new_mod_keys {
-                if crate::domain::mod_manifest::mod_manifest_key(new_key) == *file_key {
-                    is_kept = true;
-                    break;
-                }
+        // Step 5: Three-way merge of the mod set and config/ files
+        let new_mod_keys = get_new_modpack_mod_keys(new_pack_path)?;
+        let imported_manifest = ModManifestService::sync_from_mods_dir(&mods_dir, &manifest_path)?;
+        let ours_keys: HashSet<&str> = ours_mods.values().map(|m| m.file_key.as_str()).collect();
+        let mut theirs_mods = merge::tracked_mods(&imported_manifest, &mods_dir, |key, entry| {
+            merge::is_declared_mod(&new_mod_keys, key, entry)
+        });
+        // 同一模组新旧文件并存时（仅按项目 ID 声明的 CurseForge 包），以新下载的文件为新版
+        for (key, entry) in &imported_manifest {
+            if ours_keys.contains(key.as_str())
+                || !merge::is_declared_mod(&new_mod_keys, key, entry)
+            {
+                continue;
             }
-
-            if !is_kept {
-                let mods_dir = instance_dir.join("mods");
-                if mods_dir.exists() {
-                    if let Ok(read_dir) = fs::read_dir(&mods_dir) {
-                        for entry_file in read_dir.flatten() {
-                            let fname = entry_file.file_name().to_string_lossy().to_string();
-                            if crate::domain::mod_manifest::mod_manifest_key(&fname) == *file_key {
-                                files_to_delete.push(mods_dir.join(fname));
-                            }
-                        }
-                    }
-                }
+            if let Some(file_name) = merge::find_mod_file(&mods_dir, key) {
+                theirs_mods.insert(
+                    merge::mod_identity(key, entry),
+                    merge::TrackedMod {
+                        file_key: key.clone(),
+                        hash: entry.hash.value.clone(),
+                        disabled: file_name.ends_with(".disabled"),
+                    },
+                );
             }
         }
-    }
 
-    for path in files_to_delete {
-        if path.exists() {
-            let _ = fs::remove_file(path);
+        let metadata = crate::services::modpack_service::ops::parse_modpack(new_pack_path)?;
+        let mut report = ModpackMergeReport {
+            from_version: old_config.modpack_version.clone(),
+            to_version: metadata.pack_version.clone(),
+            has_baseline,
+            ..ModpackMergeReport::default()
+        };
+        let sides = merge::MergeSides {
+            instance_root: &instance_dir,
+            ours_root: &ours_root,
+            theirs_root: &theirs_root,
+            displaced_mods_dir: &displaced_mods_dir,
+        };
+        merge::apply_config_merge(
+            &sides,
+            &baseline.files,
+            &ours_files,
+            &theirs_files,
+            &mut report,
+        )?;
+        merge::apply_mod_merge(
+            &sides,
+            &baseline.mods,
+            &ours_mods,
+            &theirs_mods,
+            &mut report,
+        )?;
+
+        // The new pack version becomes the base for the next upgrade
+        merge::save_baseline(
+            &instance_dir,
+            &merge::PackBaseline {
+                pack_version: metadata.pack_version.clone(),
+                mods: theirs_mods,
+                files: theirs_files,
+            },
+        )?;
+        if !report.conflicts.is_empty() {
+            merge::save_merge_report(&instance_dir, &report)?;
         }
+        Ok((metadata, report))
+    });
+
+    let (metadata, report) = match merged {
+        Ok(merged) => merged,
+        Err(e) => {
+            // 导入或合并失败时先从快照恢复导入前的 config 与 mods；恢复失败则保留快照供手动找回
+            if let Err(restore_err) =
+                merge::restore_ours_snapshot(&instance_dir, &ours_root, &ours_files, &mods_before)
+            {
+                return Err(format!(
+                    "{} (failed to restore the instance, snapshot kept at {}: {})",
+                    e,
+                    work_dir.display(),
+                    restore_err
+                ));
+            }
+            let _ = fs::remove_dir_all(&work_dir);
+            return Err(e);
+        }
+    };
+    let _ = fs::remove_dir_all(&work_dir);
+    if backup_zip_path.is_none() {
+        let _ = fs::remove_dir_all(&displaced_mods_dir);
     }
 
-    // Step 3: Run the import orchestrator to extract new overrides and download new mods.
-    // By passing the same instance name, the orchestrator will target the exact same directory.
-    let cancel = Arc::new(AtomicBool::new(false));
-    execute_import(app, new_pack_path, &old_config.name, &cancel, None).await?;
-
-    // Step 4: Selective restore of user data (saves, screenshots, keybinds, servers)
-    // Because the overrides extraction in execute_import might have overwritten option files.
-    if let Some(zip_path) = backup_zip_path {
-        restore_user_sensitive_data(&instance_dir, &zip_path)?;
-    }
-
-    // Step 5: Update Modpack version in instance.json
-    let metadata = crate::services::modpack_service::ops::parse_modpack(new_pack_path)?;
+    // Step 6: Update Modpack version in instance.json
     let updated_config_content = fs::read_to_string(&instance_json_path)
         .map_err(|e| format!("Failed to read updated instance.json: {}", e))?;
     let mut updated_config: InstanceConfig = serde_json::from_str(&updated_config_content)
         .map_err(|e| format!("Failed to parse updated instance.json: {}", e))?;
 
-    updated_config.modpack_version = metadata.pack_version.or(Some("1.1.0".to_string()));
-    updated_config.modpack_uuid = metadata.pack_uuid;
-    updated_config.modpack_source = Some(metadata.source);
-    updated_config.modpack_id = Some(instance_id.to_string());
     updated_config.mc_version = metadata.version;
     updated_config.loader.r#type = metadata.loader;
     updated_config.loader.version = metadata.loader_version;
+    // 关联远程来源的实例保留平台项目 ID，并记录新安装的版本；
+    // 用本地文件升级时按文件名或版本号找回远程版本 ID，找不到则沿用原来的
+    match remote::remote_ref_for_config(&old_config) {
+        Some(remote) => {
+            let version_id = match remote_version_id {
+                Some(version_id) => Some(version_id),
+                None => {
+                    let file_name = Path::new(new_pack_path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string());
+                    remote::match_remote_version_id(
+                        app,
+                        &remote,
+                        &updated_config,
+                        metadata.pack_version.as_deref(),
+                        file_name.as_deref(),
+                    )
+                    .await
+                    .or(remote.version_id)
+                }
+            };
+            updated_config.modpack_source = old_config.modpack_source.clone();
+            updated_config.modpack_id = Some(remote.project_id);
+            updated_config.modpack_version_id = version_id;
+        }
+        None => {
+            updated_config.modpack_source = Some(metadata.source);
+            updated_config.modpack_id = Some(instance_id.to_string());
+            updated_config.modpack_version_id = None;
+        }
+    }
+    updated_config.modpack_version = metadata.pack_version.or(Some("1.1.0".to_string()));
+    updated_config.modpack_uuid = metadata.pack_uuid;
 
     fs::write(
         &instance_json_path,
@@ -262,13 +398,51 @@ pub async fn execute_modpack_upgrade<R: Runtime>(
     )
     .map_err(|e| format!("Failed to write updated instance config: {}", e))?;
 
-    Ok(())
+    Ok(report)
+}
+
+/// 读取上次升级遗留的合并冲突（没有则返回 None）。
+pub fn get_modpack_merge_conflicts<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+) -> Result<Option<ModpackMergeReport>, String> {
+    let instance_dir = resolve_base_dir(app)?.join("instances").join(instance_id);
+    Ok(merge::load_merge_report(&instance_dir))
+}
+
+pub fn resolve_modpack_merge_conflict<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+    target: ModpackMergeTarget,
+    path: &str,
+    use_theirs: bool,
+) -> Result<ModpackMergeReport, String> {
+    let instance_dir = resolve_base_dir(app)?.join("instances").join(instance_id);
+    let displaced_mods_dir = fs::read_to_string(instance_dir.join("backup_index.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|index| index["displacedModsPath"].as_str().map(|p| instance_dir.join(p)));
+    merge::resolve_merge_conflict(
+        &instance_dir,
+        target,
+        path,
+        use_theirs,
+        displaced_mods_dir.as_deref(),
+    )
 }
 
 fn get_new_modpack_mod_keys(zip_path: &str) -> Result<HashSet<String>, String> {
+    let mut keys = HashSet::new();
+    if let ModpackSourceHint::Packwiz = detect_modpack_location(zip_path)? {
+        let bundle = packwiz::load_local_pack(&local_packwiz_root(zip_path)?)?;
+        for (_, mod_file) in bundle.mods {
+            keys.insert(mod_file.filename);
+        }
+        return Ok(keys);
+    }
+
     let mut archive = open_modpack_archive(zip_path)?;
     let source = detect_modpack_source(&mut archive)?;
-    let mut keys = HashSet::new();
 
     match source {
         ModpackSourceHint::PiPack => {
@@ -306,6 +480,7 @@ fn get_new_modpack_mod_keys(zip_path: &str) -> Result<HashSet<String>, String> {
                 }
             }
         }
+        ModpackSourceHint::Packwiz => {}
     }
     Ok(keys)
 }
@@ -1,7 +1,9 @@
+use crate::domain::library::RemoteStoreKind;
 use crate::error::{AppError, AppResult};
 use crate::services::config_service::{ConfigService, PlaytimeSyncSettings};
 use crate::services::file_write_lock::lock_for_path;
 use crate::services::instance::binding::InstanceBindingService;
+use crate::services::remote_store::RemoteStore;
 use chrono::{DateTime, NaiveDateTime, Utc};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -725,16 +727,24 @@ impl PlaytimeService {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(20))
             .build()?;
+        let store = RemoteStore::from_settings(
+            &settings.store,
+            &settings.webdav_url,
+            &settings.username,
+            &settings.password,
+            client,
+        )
+        .map_err(AppError::Generic)?;
 
         let remote_root = Self::normalized_remote_root(settings);
         let stats_root = format!("{}/stats", remote_root);
 
-        Self::ensure_remote_collection(&client, settings, &remote_root).await?;
-        Self::ensure_remote_collection(&client, settings, &stats_root).await?;
+        Self::ensure_remote_collection(&store, &remote_root).await?;
+        Self::ensure_remote_collection(&store, &stats_root).await?;
+        Self::encrypt_plaintext_remote(&store, &remote_root, &stats_root).await?;
 
         let mut remote_manifest = Self::fetch_remote_json::<DevicesManifest>(
-            &client,
-            settings,
+            &store,
             &format!("{}/devices.json", remote_root),
         )
         .await?
@@ -755,8 +765,7 @@ impl PlaytimeService {
             }
 
             if let Some(snapshot) = Self::fetch_remote_json::<DeviceSnapshot>(
-                &client,
-                settings,
+                &store,
                 &format!("{}/{}.json", stats_root, device_id),
             )
             .await?
@@ -789,8 +798,7 @@ impl PlaytimeService {
         let local_snapshot_content = fs::read_to_string(&local_snapshot_path)?;
 
         Self::put_remote_json(
-            &client,
-            settings,
+            &store,
             &format!("{}/{}.json", stats_root, identity.device_id),
             &local_snapshot_content,
         )
@@ -809,8 +817,7 @@ impl PlaytimeService {
             .sort_by(|left, right| left.name.cmp(&right.name));
 
         Self::put_remote_json(
-            &client,
-            settings,
+            &store,
             &format!("{}/devices.json", remote_root),
             &serde_json::to_string_pretty(&remote_manifest)?,
         )
@@ -823,92 +830,60 @@ impl PlaytimeService {
         Ok(())
     }
 
-    async fn fetch_remote_json<T: for<'de> Deserialize<'de>>(
-        client: &reqwest::Client,
-        settings: &PlaytimeSyncSettings,
-        remote_path: &str,
-    ) -> AppResult<Option<T>> {
-        let response =
-            Self::authorized_request(client, settings, reqwest::Method::GET, remote_path)
-                .send()
-                .await?;
-
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
-            return Ok(None);
-        }
-
-        if !response.status().is_success() {
-            return Err(AppError::Generic(format!(
-                "Playtime sync request failed: {}",
-                response.status()
-            )));
-        }
-
-        Ok(Some(response.json::<T>().await?))
-    }
-
-    async fn put_remote_json(
-        client: &reqwest::Client,
-        settings: &PlaytimeSyncSettings,
-        remote_path: &str,
-        body: &str,
+    /// 开启端到端加密后，把加密前上传的设备清单与各设备快照改写为密文。
+    async fn encrypt_plaintext_remote(
+        store: &RemoteStore,
+        remote_root: &str,
+        stats_root: &str,
     ) -> AppResult<()> {
-        let response =
-            Self::authorized_request(client, settings, reqwest::Method::PUT, remote_path)
-                .header(reqwest::header::CONTENT_TYPE, "application/json")
-                .body(body.to_string())
-                .send()
-                .await?;
-
-        if !response.status().is_success() {
-            return Err(AppError::Generic(format!(
-                "Failed to upload playtime snapshot: {}",
-                response.status()
-            )));
-        }
+        let manifest_path = format!("{}/devices.json", remote_root);
+        let Some(manifest) = store
+            .get_plaintext_json::<DevicesManifest>(&manifest_path)
+            .await
+            .map_err(AppError::Generic)?
+        else {
+            return Ok(());
+        };
 
+        for device in &manifest.devices {
+            store
+                .encrypt_plaintext_file(&format!("{}/{}.json", stats_root, device.id))
+                .await
+                .map_err(AppError::Generic)?;
+        }
+        store
+            .encrypt_plaintext_file(&manifest_path)
+            .await
+            .map_err(AppError::Generic)?;
         Ok(())
     }
 
-    async fn ensure_remote_collection(
-        client: &reqwest::Client,
-        settings: &PlaytimeSyncSettings,
+    async fn fetch_remote_json<T: for<'de> Deserialize<'de>>(
+        store: &RemoteStore,
         remote_path: &str,
-    ) -> AppResult<()> {
-        let method = reqwest::Method::from_bytes(b"MKCOL")
-            .map_err(|error| AppError::Generic(error.to_string()))?;
-        let response = Self::authorized_request(client, settings, method, remote_path)
-            .send()
-            .await?;
-
-        let status = response.status();
-        if status.is_success()
-            || status == reqwest::StatusCode::METHOD_NOT_ALLOWED
-            || status == reqwest::StatusCode::CONFLICT
-        {
-            return Ok(());
-        }
-
-        Err(AppError::Generic(format!(
-            "Failed to create playtime sync directory: {}",
-            status
-        )))
+    ) -> AppResult<Option<T>> {
+        store
+            .get_json::<T>(remote_path)
+            .await
+            .map_err(|error| AppError::Generic(format!("Playtime sync request failed: {}", error)))
     }
 
-    fn authorized_request(
-        client: &reqwest::Client,
-        settings: &PlaytimeSyncSettings,
-        method: reqwest::Method,
-        remote_path: &str,
-    ) -> reqwest::RequestBuilder {
-        let url = Self::join_remote_url(&settings.webdav_url, remote_path);
-        let builder = client.request(method, url);
+    async fn put_remote_json(store: &RemoteStore, remote_path: &str, body: &str) -> AppResult<()> {
+        store
+            .put(remote_path, body.as_bytes().to_vec(), "application/json")
+            .await
+            .map_err(|error| {
+                AppError::Generic(format!("Failed to upload playtime snapshot: {}", error))
+            })
+    }
 
-        if settings.username.trim().is_empty() {
-            builder
-        } else {
-            builder.basic_auth(settings.username.clone(), Some(settings.password.clone()))
-        }
+    async fn ensure_remote_collection(store: &RemoteStore, remote_path: &str) -> AppResult<()> {
+        store.ensure_dir(remote_path).await.map(|_| ()).map_err(|error| {
+            AppError::Generic(format!(
+                "Failed to create playtime sync directory: {}",
+                error
+            ))
+        })
     }
 
     async fn write_checkpoint<R: Runtime>(
@@ -1122,7 +1097,14 @@ impl PlaytimeService {
     }
 
     fn is_sync_configured(settings: &PlaytimeSyncSettings) -> bool {
-        !settings.webdav_url.trim().is_empty()
+        match settings.store.kind {
+            RemoteStoreKind::Webdav => !settings.webdav_url.trim().is_empty(),
+            RemoteStoreKind::S3 => {
+                !settings.store.s3.endpoint.trim().is_empty()
+                    && !settings.store.s3.bucket.trim().is_empty()
+            }
+            RemoteStoreKind::Local => !settings.store.local_path.trim().is_empty(),
+        }
     }
 
     fn normalized_remote_root(settings: &PlaytimeSyncSettings) -> String {
@@ -1136,11 +1118,4 @@ impl PlaytimeService {
         normalized.trim_matches('/').to_string()
     }
 
-    fn join_remote_url(base_url: &str, remote_path: &str) -> String {
-        format!(
-            "{}/{}",
-            base_url.trim_end_matches('/'),
-            remote_path.trim_start_matches('/')
-        )
-    }
 }
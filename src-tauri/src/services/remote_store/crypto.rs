@@ -0,0 +1,343 @@
+// src-tauri/src/services/remote_store/crypto.rs
+//
+// 客户端端到端加密：口令经 Argon2id 派生密钥，文件内容用 XChaCha20-Poly1305 加密，
+// 路径的每一段用确定性的合成随机数加密后再做 base64url 编码，远端只能看到密文名。
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, KeyInit as _, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use super::normalize_path;
+
+/// 存放 KDF 参数与口令校验值的明文文件，位于存储根目录
+pub const KEY_HEADER_FILE: &str = ".pilauncher-crypto.json";
+
+const CONTENT_MAGIC: &[u8; 4] = b"PLE1";
+pub(crate) const KEY_LEN: usize = 32;
+pub(crate) const NONCE_LEN: usize = 24;
+pub(crate) const SALT_LEN: usize = 16;
+const VERIFIER_PLAINTEXT: &[u8] = b"pilauncher-e2e-verifier";
+const VERIFIER_AAD: &[u8] = b"verifier";
+const NAME_AAD: &[u8] = b"name";
+
+// OWASP 推荐的 Argon2id 最低参数：19 MiB 内存、2 轮、单线程
+pub(crate) const DEFAULT_MEMORY_KIB: u32 = 19 * 1024;
+pub(crate) const DEFAULT_ITERATIONS: u32 = 2;
+pub(crate) const DEFAULT_PARALLELISM: u32 = 1;
+
+// 密钥头可被篡改，派生前限制 KDF 参数范围，避免被诱导消耗过多内存或时间
+const MEMORY_KIB_RANGE: std::ops::RangeInclusive<u32> = 8 * 1024..=1024 * 1024;
+const ITERATIONS_RANGE: std::ops::RangeInclusive<u32> = 1..=10;
+const PARALLELISM_RANGE: std::ops::RangeInclusive<u32> = 1..=16;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyHeader {
+    pub version: u32,
+    pub kdf: String,
+    pub salt: String,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub verifier: String,
+}
+
+pub struct StoreCipher {
+    aead: XChaCha20Poly1305,
+    name_key: [u8; 32],
+}
+
+/// 用 Argon2id 从口令派生密钥并填满 `output`，超出范围的参数直接拒绝。
+pub(crate) fn derive_argon2id(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    output: &mut [u8],
+) -> Result<(), String> {
+    if !MEMORY_KIB_RANGE.contains(&memory_kib)
+        || !ITERATIONS_RANGE.contains(&iterations)
+        || !PARALLELISM_RANGE.contains(&parallelism)
+    {
+        return Err(format!(
+            "encryption key parameters are out of range: memory {memory_kib} KiB, {iterations} iterations, parallelism {parallelism}"
+        ));
+    }
+    let params = Params::new(memory_kib, iterations, parallelism, Some(output.len()))
+        .map_err(|error| format!("invalid encryption key parameters: {error}"))?;
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, output)
+        .map_err(|error| format!("failed to derive encryption key: {error}"))
+}
+
+/// XChaCha20-Poly1305 加密，返回随机数与密文，供需要自行保存两者的本地密文复用。
+pub(crate) fn seal_detached(
+    key: &[u8; KEY_LEN],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<([u8; NONCE_LEN], Vec<u8>), String> {
+    seal_with(&XChaCha20Poly1305::new(key.into()), plaintext, aad)
+}
+
+pub(crate) fn open_detached(
+    key: &[u8; KEY_LEN],
+    nonce: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, String> {
+    open_with(&XChaCha20Poly1305::new(key.into()), nonce, ciphertext, aad)
+}
+
+fn seal_with(
+    aead: &XChaCha20Poly1305,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<([u8; NONCE_LEN], Vec<u8>), String> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = aead
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| "failed to encrypt data".to_string())?;
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    nonce_bytes.copy_from_slice(&nonce);
+    Ok((nonce_bytes, ciphertext))
+}
+
+fn open_with(
+    aead: &XChaCha20Poly1305,
+    nonce: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, String> {
+    if nonce.len() != NONCE_LEN {
+        return Err("encrypted data has an invalid nonce".to_string());
+    }
+    aead.decrypt(
+        XNonce::from_slice(nonce),
+        Payload {
+            msg: ciphertext,
+            aad,
+        },
+    )
+    .map_err(|_| "encrypted data failed authentication".to_string())
+}
+
+fn derive_keys(passphrase: &str, salt: &[u8], header: &KeyHeader) -> Result<[u8; 64], String> {
+    let mut output = [0u8; 64];
+    derive_argon2id(
+        passphrase,
+        salt,
+        header.memory_kib,
+        header.iterations,
+        header.parallelism,
+        &mut output,
+    )?;
+    Ok(output)
+}
+
+impl StoreCipher {
+    fn from_key_material(material: &[u8; 64]) -> Self {
+        let mut name_key = [0u8; 32];
+        name_key.copy_from_slice(&material[32..]);
+        Self {
+            aead: XChaCha20Poly1305::new(material[..32].into()),
+            name_key,
+        }
+    }
+
+    /// 首次开启加密时生成新的盐与校验值，返回需要写到远端的密钥头。
+    pub fn create(passphrase: &str) -> Result<(Self, KeyHeader), String> {
+        if passphrase.is_empty() {
+            return Err("encryption passphrase is empty".to_string());
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut header = KeyHeader {
+            version: 1,
+            kdf: "argon2id".to_string(),
+            salt: URL_SAFE_NO_PAD.encode(salt),
+            memory_kib: DEFAULT_MEMORY_KIB,
+            iterations: DEFAULT_ITERATIONS,
+            parallelism: DEFAULT_PARALLELISM,
+            verifier: String::new(),
+        };
+        let cipher = Self::from_key_material(&derive_keys(passphrase, &salt, &header)?);
+        header.verifier =
+            URL_SAFE_NO_PAD.encode(cipher.seal_with_aad(VERIFIER_PLAINTEXT, VERIFIER_AAD)?);
+        Ok((cipher, header))
+    }
+
+    /// 用远端密钥头还原密钥，口令不对时返回错误。
+    pub fn unlock(passphrase: &str, header: &KeyHeader) -> Result<Self, String> {
+        if header.version != 1 || header.kdf != "argon2id" {
+            return Err(format!(
+                "unsupported remote encryption format: v{} {}",
+                header.version, header.kdf
+            ));
+        }
+        let salt = URL_SAFE_NO_PAD
+            .decode(&header.salt)
+            .map_err(|_| "remote encryption header is corrupted".to_string())?;
+        let verifier = URL_SAFE_NO_PAD
+            .decode(&header.verifier)
+            .map_err(|_| "remote encryption header is corrupted".to_string())?;
+        let cipher = Self::from_key_material(&derive_keys(passphrase, &salt, header)?);
+        match cipher.open_with_aad(&verifier, VERIFIER_AAD) {
+            Ok(plain) if plain == VERIFIER_PLAINTEXT => Ok(cipher),
+            _ => Err("encryption passphrase does not match the remote data".to_string()),
+        }
+    }
+
+    fn seal_with_aad(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+        let (nonce, ciphertext) = seal_with(&self.aead, plaintext, aad)
+            .map_err(|_| "failed to encrypt remote file".to_string())?;
+        let mut output = Vec::with_capacity(CONTENT_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        output.extend_from_slice(CONTENT_MAGIC);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    fn open_with_aad(&self, data: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+        if !is_sealed(data) {
+            return Err("remote file is not encrypted".to_string());
+        }
+        let body = &data[CONTENT_MAGIC.len()..];
+        if body.len() < NONCE_LEN {
+            return Err("remote encrypted file is truncated".to_string());
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        open_with(&self.aead, nonce, ciphertext, aad)
+            .map_err(|_| "remote encrypted file failed authentication".to_string())
+    }
+
+    /// 加密文件内容；明文路径作为附加数据参与认证，密文不能被挪到别的文件名下。
+    pub fn seal(&self, path: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        self.seal_with_aad(plaintext, normalize_path(path)?.as_bytes())
+    }
+
+    pub fn open(&self, path: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+        self.open_with_aad(data, normalize_path(path)?.as_bytes())
+            .map_err(|error| format!("{error}: {path}"))
+    }
+
+    fn name_nonce(&self, segment: &str) -> Result<[u8; NONCE_LEN], String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.name_key)
+            .map_err(|error| format!("failed to derive remote name nonce: {error}"))?;
+        mac.update(segment.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&digest[..NONCE_LEN]);
+        Ok(nonce)
+    }
+
+    /// 同一个名字总是加密成同一个密文名，按路径读写与列目录都不需要额外索引。
+    pub fn encrypt_name(&self, segment: &str) -> Result<String, String> {
+        let nonce = self.name_nonce(segment)?;
+        let ciphertext = self
+            .aead
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: segment.as_bytes(),
+                    aad: NAME_AAD,
+                },
+            )
+            .map_err(|_| "failed to encrypt remote name".to_string())?;
+        let mut raw = nonce.to_vec();
+        raw.extend_from_slice(&ciphertext);
+        Ok(URL_SAFE_NO_PAD.encode(raw))
+    }
+
+    /// 不是本密钥加密的名字（其他文件、明文遗留）返回 `None`。
+    pub fn decrypt_name(&self, encoded: &str) -> Option<String> {
+        let raw = URL_SAFE_NO_PAD.decode(encoded).ok()?;
+        if raw.len() <= NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = raw.split_at(NONCE_LEN);
+        let plain = self
+            .aead
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: NAME_AAD,
+                },
+            )
+            .ok()?;
+        let name = String::from_utf8(plain).ok()?;
+        (self.name_nonce(&name).ok()?.as_slice() == nonce).then_some(name)
+    }
+
+    pub fn encrypt_path(&self, path: &str) -> Result<String, String> {
+        let normalized = normalize_path(path)?;
+        if normalized.is_empty() {
+            return Ok(normalized);
+        }
+        normalized
+            .split('/')
+            .map(|segment| self.encrypt_name(segment))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|segments| segments.join("/"))
+    }
+}
+
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(CONTENT_MAGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_contents_and_names() {
+        let (cipher, header) = StoreCipher::create("correct horse").unwrap();
+        let unlocked = StoreCipher::unlock("correct horse", &header).unwrap();
+
+        let sealed = cipher.seal("a/b.json", b"{\"x\":1}").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(unlocked.open("a/b.json", &sealed).unwrap(), b"{\"x\":1}");
+        assert!(unlocked.open("a/c.json", &sealed).is_err());
+
+        let name = cipher.encrypt_name("op-1-abc.json").unwrap();
+        assert_eq!(name, unlocked.encrypt_name("op-1-abc.json").unwrap());
+        assert!(!name.contains("op-"));
+        assert_eq!(
+            unlocked.decrypt_name(&name).as_deref(),
+            Some("op-1-abc.json")
+        );
+        assert_eq!(unlocked.decrypt_name("op-1-abc.json"), None);
+        assert_eq!(cipher.encrypt_path("/x/y/").unwrap().split('/').count(), 2);
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase_and_tampering() {
+        let (cipher, header) = StoreCipher::create("one").unwrap();
+        assert!(StoreCipher::unlock("two", &header).is_err());
+
+        let mut hostile = header.clone();
+        hostile.memory_kib = u32::MAX;
+        assert!(matches!(
+            StoreCipher::unlock("one", &hostile),
+            Err(error) if error.contains("out of range")
+        ));
+
+        let mut sealed = cipher.seal("file", b"payload").unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(cipher.open("file", &sealed).is_err());
+        assert!(cipher.open("file", b"plain text").is_err());
+    }
+}
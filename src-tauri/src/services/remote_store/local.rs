@@ -0,0 +1,108 @@
+// src-tauri/src/services/remote_store/local.rs
+//
+// 本地文件夹后端：直接读写某个目录，适用于 NAS / SMB 挂载或交给 Syncthing 等工具同步的文件夹。
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use super::normalize_path;
+
+const TEMP_SUFFIX: &str = ".pilauncher-tmp";
+
+pub struct LocalFolderStore {
+    root: PathBuf,
+}
+
+impl LocalFolderStore {
+    pub fn new(root: &str) -> Result<Self, String> {
+        let trimmed = root.trim();
+        if trimmed.is_empty() {
+            return Err("local sync folder is empty".to_string());
+        }
+        let root = PathBuf::from(trimmed);
+        if !root.is_absolute() {
+            return Err("local sync folder must be an absolute path".to_string());
+        }
+        Ok(Self { root })
+    }
+
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let normalized = normalize_path(path)?;
+        Ok(if normalized.is_empty() {
+            self.root.clone()
+        } else {
+            self.root
+                .join(normalized.replace('/', std::path::MAIN_SEPARATOR_STR))
+        })
+    }
+
+    pub fn describe(&self, path: &str) -> String {
+        self.resolve(path)
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| self.root.display().to_string())
+    }
+
+    pub fn ensure_dir(&self, path: &str) -> Result<bool, String> {
+        let dir = self.resolve(path)?;
+        if dir.is_dir() {
+            return Ok(false);
+        }
+        fs::create_dir_all(&dir)
+            .map_err(|error| format!("failed to create {}: {error}", dir.display()))?;
+        Ok(true)
+    }
+
+    pub fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let dir = self.resolve(dir)?;
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(format!("failed to list {}: {error}", dir.display())),
+        };
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| !name.ends_with(TEMP_SUFFIX))
+            .collect())
+    }
+
+    pub fn get(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let file = self.resolve(path)?;
+        match fs::read(&file) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(format!("failed to read {}: {error}", file.display())),
+        }
+    }
+
+    /// 先写临时文件再改名，其他设备的同步工具不会看到写了一半的文件。
+    pub fn put(&self, path: &str, body: &[u8]) -> Result<(), String> {
+        let file = self.resolve(path)?;
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        let mut temp_name = file.as_os_str().to_os_string();
+        temp_name.push(TEMP_SUFFIX);
+        let temp = PathBuf::from(temp_name);
+        fs::write(&temp, body)
+            .map_err(|error| format!("failed to write {}: {error}", file.display()))?;
+        fs::rename(&temp, &file).map_err(|error| {
+            let _ = fs::remove_file(&temp);
+            format!("failed to write {}: {error}", file.display())
+        })
+    }
+
+    pub fn delete(&self, path: &str) -> Result<(), String> {
+        let target = self.resolve(path)?;
+        let result = if target.is_dir() {
+            fs::remove_dir_all(&target)
+        } else {
+            fs::remove_file(&target)
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(format!("failed to delete {}: {error}", target.display())),
+        }
+    }
+}
@@ -0,0 +1,665 @@
+// src-tauri/src/services/remote_store/mod.rs
+//
+// 同步与远端备份使用的存储抽象。上层只按 `/` 分隔的相对路径读写文件、列目录和加锁，
+// 数据实际落在 WebDAV、S3 兼容对象存储还是本地文件夹（NAS 挂载、Syncthing 目录）由配置决定。
+// 开启端到端加密后，所有读写在这里透明地加解密，上层逻辑不需要感知。
+pub(crate) mod crypto;
+mod local;
+mod s3;
+mod webdav;
+
+pub use local::LocalFolderStore;
+pub use s3::S3Store;
+pub use webdav::WebDavStore;
+
+use crate::domain::library::{RemoteStoreKind, RemoteStoreSettings, WebDavSyncConfig};
+use crypto::{KeyHeader, StoreCipher, KEY_HEADER_FILE};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+use tokio::sync::OnceCell;
+
+/// 没有原生锁的后端（S3、本地文件夹）在被锁目录下写入的锁文件
+const ADVISORY_LOCK_FILE: &str = ".pilauncher-lock.json";
+const LOCK_TIMEOUT_SECONDS: u64 = 300;
+const ENCRYPTED_CONTENT_TYPE: &str = "application/octet-stream";
+
+enum Backend {
+    WebDav(WebDavStore),
+    S3(S3Store),
+    Local(LocalFolderStore),
+}
+
+pub struct RemoteStore {
+    backend: Backend,
+    lock_token: Option<String>,
+    passphrase: Option<String>,
+    /// 同步预演（dry-run）时置位，任何写操作都会被拒绝
+    read_only: bool,
+    /// 首次访问时读取（或创建）远端密钥头后缓存；未开启加密时为 `None`
+    cipher: OnceCell<Option<StoreCipher>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdvisoryLock {
+    token: String,
+    expires_at: i64,
+}
+
+/// 统一为不带首尾斜杠的相对路径，拒绝 `..` 等越界写法。
+pub(crate) fn normalize_path(path: &str) -> Result<String, String> {
+    let normalized = path.replace('\\', "/").trim_matches('/').to_string();
+    if Path::new(&normalized).components().any(|component| {
+        matches!(
+            component,
+            Component::ParentDir | Component::Prefix(_) | Component::RootDir
+        )
+    }) {
+        return Err(format!("unsafe remote path: {path}"));
+    }
+    Ok(normalized)
+}
+
+impl RemoteStore {
+    pub fn from_settings(
+        settings: &RemoteStoreSettings,
+        webdav_url: &str,
+        username: &str,
+        password: &str,
+        client: Client,
+    ) -> Result<Self, String> {
+        let backend = match settings.kind {
+            RemoteStoreKind::Webdav => {
+                Backend::WebDav(WebDavStore::new(client, webdav_url, username, password)?)
+            }
+            RemoteStoreKind::S3 => Backend::S3(S3Store::new(client, &settings.s3)?),
+            RemoteStoreKind::Local => Backend::Local(LocalFolderStore::new(&settings.local_path)?),
+        };
+        let passphrase = if settings.encryption.enabled {
+            if settings.encryption.passphrase.is_empty() {
+                return Err(
+                    "end-to-end encryption is enabled, but no passphrase is set".to_string()
+                );
+            }
+            Some(settings.encryption.passphrase.clone())
+        } else {
+            None
+        };
+        Ok(Self {
+            backend,
+            lock_token: None,
+            passphrase,
+            read_only: false,
+            cipher: OnceCell::new(),
+        })
+    }
+
+    pub fn for_sync_config(config: &WebDavSyncConfig) -> Result<Self, String> {
+        let client = Client::builder()
+            .build()
+            .map_err(|error| format!("failed to build sync client: {error}"))?;
+        Self::from_settings(
+            &config.store,
+            &config.base_url,
+            &config.username,
+            &config.password,
+            client,
+        )
+    }
+
+    /// 转成只读存储，供同步预演使用：远端不会被创建目录、写入、删除或加锁。
+    pub fn into_read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub fn kind(&self) -> RemoteStoreKind {
+        match self.backend {
+            Backend::WebDav(_) => RemoteStoreKind::Webdav,
+            Backend::S3(_) => RemoteStoreKind::S3,
+            Backend::Local(_) => RemoteStoreKind::Local,
+        }
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    /// 给用户看的位置描述（URL、`s3://` 地址或本地路径）。
+    pub fn describe(&self, path: &str) -> String {
+        match &self.backend {
+            Backend::WebDav(store) => store.url(path),
+            Backend::S3(store) => store.describe(path),
+            Backend::Local(store) => store.describe(path),
+        }
+    }
+
+    /// 确保目录存在，返回是否新建。对象存储没有目录概念，总是返回 false。
+    pub async fn ensure_dir(&self, path: &str) -> Result<bool, String> {
+        let remote_path = self.remote_path(path).await?;
+        self.raw_ensure_dir(&remote_path).await
+    }
+
+    /// 列出目录下的直接子项名称（文件与子目录），目录不存在时返回空列表。
+    pub async fn list(&self, dir: &str) -> Result<Vec<String>, String> {
+        let cipher = self.cipher().await?;
+        let remote_dir = match cipher {
+            Some(cipher) => cipher.encrypt_path(dir)?,
+            None => dir.to_string(),
+        };
+        let mut names = self.raw_list(&remote_dir).await?;
+        if let Some(cipher) = cipher {
+            names = names
+                .iter()
+                .filter_map(|name| cipher.decrypt_name(name))
+                .collect();
+        }
+        names.retain(|name| name != ADVISORY_LOCK_FILE && name != KEY_HEADER_FILE);
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// 读取文件，不存在时返回 `Ok(None)`。
+    pub async fn get(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        let Some(cipher) = self.cipher().await? else {
+            return self.raw_get(path).await;
+        };
+        match self.raw_get(&cipher.encrypt_path(path)?).await? {
+            Some(data) => cipher.open(path, &data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>, String> {
+        match self.get(path).await? {
+            Some(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|error| format!("invalid JSON at {path}: {error}")),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn put(&self, path: &str, body: Vec<u8>, content_type: &str) -> Result<(), String> {
+        let Some(cipher) = self.cipher().await? else {
+            return self.raw_put(path, body, content_type).await;
+        };
+        let sealed = cipher.seal(path, &body)?;
+        self.raw_put(&cipher.encrypt_path(path)?, sealed, ENCRYPTED_CONTENT_TYPE)
+            .await
+    }
+
+    /// 删除文件或整个目录，目标不存在时视为成功。
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        let remote_path = self.remote_path(path).await?;
+        self.raw_delete(&remote_path).await
+    }
+
+    /// 独占锁定目录，之后通过本实例发出的写操作都会携带锁令牌。
+    pub async fn lock(&mut self, path: &str) -> Result<(), String> {
+        if self.lock_token.is_some() {
+            return Err("remote store is already locked by this session".to_string());
+        }
+        self.ensure_writable()?;
+        let token = match &self.backend {
+            Backend::WebDav(store) => {
+                let remote_path = self.remote_path(path).await?;
+                store.lock(&remote_path, LOCK_TIMEOUT_SECONDS).await?
+            }
+            Backend::S3(_) | Backend::Local(_) => self.acquire_advisory_lock(path).await?,
+        };
+        self.lock_token = Some(token);
+        Ok(())
+    }
+
+    pub async fn unlock(&mut self, path: &str) -> Result<(), String> {
+        let Some(token) = self.lock_token.take() else {
+            return Ok(());
+        };
+        match &self.backend {
+            Backend::WebDav(store) => {
+                let remote_path = self.remote_path(path).await?;
+                store.unlock(&remote_path, &token).await
+            }
+            Backend::S3(_) | Backend::Local(_) => {
+                let lock_path = Self::advisory_lock_path(path);
+                let held = self
+                    .get_json::<AdvisoryLock>(&lock_path)
+                    .await
+                    .ok()
+                    .flatten()
+                    .is_some_and(|lock| lock.token == token);
+                if held {
+                    self.delete(&lock_path).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 列出开启加密之前上传的明文子项，供迁移使用；未开启加密时返回空列表。
+    pub async fn list_plaintext(&self, dir: &str) -> Result<Vec<String>, String> {
+        if self.cipher().await?.is_none() {
+            return Ok(Vec::new());
+        }
+        let mut names = self.raw_list(dir).await?;
+        names.retain(|name| name != ADVISORY_LOCK_FILE && name != KEY_HEADER_FILE);
+        names.sort();
+        Ok(names)
+    }
+
+    /// 读取加密前遗留的明文 JSON；未开启加密时返回 `None`。
+    pub async fn get_plaintext_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<Option<T>, String> {
+        if self.cipher().await?.is_none() {
+            return Ok(None);
+        }
+        match self.raw_get(path).await? {
+            Some(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|error| format!("invalid JSON at {path}: {error}")),
+            None => Ok(None),
+        }
+    }
+
+    /// 读取明文遗留的 `path`，加密写到对应的密文位置后删除明文，返回是否做了迁移。
+    /// 已迁移或不存在的文件直接跳过，所以可以在每次同步前重复调用。
+    pub async fn encrypt_plaintext_file(&self, path: &str) -> Result<bool, String> {
+        let Some(cipher) = self.cipher().await? else {
+            return Ok(false);
+        };
+        let Some(body) = self.raw_get(path).await? else {
+            return Ok(false);
+        };
+        let remote_path = cipher.encrypt_path(path)?;
+        let segments = remote_path.split('/').collect::<Vec<_>>();
+        for depth in 1..segments.len() {
+            self.raw_ensure_dir(&segments[..depth].join("/")).await?;
+        }
+        self.raw_put(
+            &remote_path,
+            cipher.seal(path, &body)?,
+            ENCRYPTED_CONTENT_TYPE,
+        )
+        .await?;
+        self.raw_delete(path).await?;
+        Ok(true)
+    }
+
+    async fn cipher(&self) -> Result<Option<&StoreCipher>, String> {
+        self.cipher
+            .get_or_try_init(|| self.load_cipher())
+            .await
+            .map(Option::as_ref)
+    }
+
+    async fn load_cipher(&self) -> Result<Option<StoreCipher>, String> {
+        let header = self.read_key_header().await?;
+        let Some(passphrase) = self.passphrase.as_deref() else {
+            return match header {
+                Some(_) => Err(
+                    "remote data is end-to-end encrypted; set the sync passphrase to continue"
+                        .to_string(),
+                ),
+                None => Ok(None),
+            };
+        };
+        if let Some(header) = header {
+            return StoreCipher::unlock(passphrase, &header).map(Some);
+        }
+
+        let (cipher, header) = StoreCipher::create(passphrase)?;
+        // 只读时远端还没有任何加密数据，临时密钥足够让后续读取都返回“不存在”
+        if self.read_only {
+            return Ok(Some(cipher));
+        }
+        self.raw_put(
+            KEY_HEADER_FILE,
+            serde_json::to_vec_pretty(&header).map_err(|error| error.to_string())?,
+            "application/json",
+        )
+        .await?;
+        // 两台设备同时首次开启加密时，以最终留在远端的密钥头为准
+        match self.read_key_header().await? {
+            Some(current) if current.verifier != header.verifier => {
+                StoreCipher::unlock(passphrase, &current).map(Some)
+            }
+            _ => Ok(Some(cipher)),
+        }
+    }
+
+    async fn read_key_header(&self) -> Result<Option<KeyHeader>, String> {
+        match self.raw_get(KEY_HEADER_FILE).await? {
+            Some(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(|error| format!("invalid remote encryption header: {error}")),
+            None => Ok(None),
+        }
+    }
+
+    async fn remote_path(&self, path: &str) -> Result<String, String> {
+        match self.cipher().await? {
+            Some(cipher) => cipher.encrypt_path(path),
+            None => Ok(path.to_string()),
+        }
+    }
+
+    fn ensure_writable(&self) -> Result<(), String> {
+        if self.read_only {
+            return Err("remote store is opened read-only for a sync preview".to_string());
+        }
+        Ok(())
+    }
+
+    async fn raw_ensure_dir(&self, path: &str) -> Result<bool, String> {
+        self.ensure_writable()?;
+        match &self.backend {
+            Backend::WebDav(store) => {
+                store
+                    .ensure_collection(path, self.lock_token.as_deref())
+                    .await
+            }
+            Backend::S3(_) => Ok(false),
+            Backend::Local(store) => store.ensure_dir(path),
+        }
+    }
+
+    async fn raw_list(&self, dir: &str) -> Result<Vec<String>, String> {
+        match &self.backend {
+            Backend::WebDav(store) => store.list(dir).await,
+            Backend::S3(store) => store.list(dir).await,
+            Backend::Local(store) => store.list(dir),
+        }
+    }
+
+    async fn raw_get(&self, path: &str) -> Result<Option<Vec<u8>>, String> {
+        match &self.backend {
+            Backend::WebDav(store) => store.get(path).await,
+            Backend::S3(store) => store.get(path).await,
+            Backend::Local(store) => store.get(path),
+        }
+    }
+
+    async fn raw_put(&self, path: &str, body: Vec<u8>, content_type: &str) -> Result<(), String> {
+        self.ensure_writable()?;
+        match &self.backend {
+            Backend::WebDav(store) => {
+                store
+                    .put(path, body, content_type, self.lock_token.as_deref())
+                    .await
+            }
+            Backend::S3(store) => store.put(path, body, content_type).await,
+            Backend::Local(store) => store.put(path, &body),
+        }
+    }
+
+    async fn raw_delete(&self, path: &str) -> Result<(), String> {
+        self.ensure_writable()?;
+        match &self.backend {
+            Backend::WebDav(store) => store.delete(path, self.lock_token.as_deref()).await,
+            Backend::S3(store) => store.delete(path).await,
+            Backend::Local(store) => store.delete(path),
+        }
+    }
+
+    fn advisory_lock_path(path: &str) -> String {
+        let dir = path.trim_matches('/');
+        if dir.is_empty() {
+            ADVISORY_LOCK_FILE.to_string()
+        } else {
+            format!("{dir}/{ADVISORY_LOCK_FILE}")
+        }
+    }
+
+    /// 对象存储与文件夹没有原子的加锁原语，这里写入带过期时间的锁文件后再读回确认，
+    /// 足以挡住两台设备同时同步的常见情况。
+    async fn acquire_advisory_lock(&self, path: &str) -> Result<String, String> {
+        let lock_path = Self::advisory_lock_path(path);
+        let now = chrono::Utc::now().timestamp_millis();
+        if let Ok(Some(existing)) = self.get_json::<AdvisoryLock>(&lock_path).await {
+            if existing.expires_at > now {
+                return Err(
+                    "remote sync directory is locked by another sync session; please retry later"
+                        .to_string(),
+                );
+            }
+        }
+
+        let lock = AdvisoryLock {
+            token: uuid::Uuid::new_v4().to_string(),
+            expires_at: now + (LOCK_TIMEOUT_SECONDS * 1000) as i64,
+        };
+        self.put(
+            &lock_path,
+            serde_json::to_vec(&lock).map_err(|error| error.to_string())?,
+            "application/json",
+        )
+        .await?;
+
+        match self.get_json::<AdvisoryLock>(&lock_path).await? {
+            Some(current) if current.token == lock.token => Ok(lock.token),
+            _ => Err(
+                "remote sync directory is locked by another sync session; please retry later"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::library::RemoteEncryptionSettings;
+
+    fn local_store(root: &Path) -> RemoteStore {
+        encrypted_store(root, None)
+    }
+
+    fn encrypted_store(root: &Path, passphrase: Option<&str>) -> RemoteStore {
+        RemoteStore::from_settings(
+            &RemoteStoreSettings {
+                kind: RemoteStoreKind::Local,
+                local_path: root.to_string_lossy().to_string(),
+                encryption: RemoteEncryptionSettings {
+                    enabled: passphrase.is_some(),
+                    passphrase: passphrase.unwrap_or_default().to_string(),
+                },
+                ..Default::default()
+            },
+            "",
+            "",
+            "",
+            Client::new(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn local_store_round_trips_files_and_directories() {
+        let root = std::env::temp_dir().join(format!("pilauncher-store-{}", uuid::Uuid::new_v4()));
+        let store = local_store(&root);
+
+        assert!(store.ensure_dir("PiLauncherSync/favorites").await.unwrap());
+        assert!(!store.ensure_dir("PiLauncherSync/favorites").await.unwrap());
+        store
+            .put(
+                "PiLauncherSync/favorites/op-1.json",
+                b"{}".to_vec(),
+                "application/json",
+            )
+            .await
+            .unwrap();
+        store
+            .put(
+                "PiLauncherSync/favorites/nested/a.bin",
+                vec![1, 2],
+                "application/octet-stream",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.list("PiLauncherSync/favorites").await.unwrap(),
+            vec!["nested".to_string(), "op-1.json".to_string()]
+        );
+        assert_eq!(
+            store
+                .get("PiLauncherSync/favorites/nested/a.bin")
+                .await
+                .unwrap(),
+            Some(vec![1, 2])
+        );
+        assert_eq!(
+            store.get("PiLauncherSync/missing.json").await.unwrap(),
+            None
+        );
+        assert!(store
+            .list("PiLauncherSync/missing")
+            .await
+            .unwrap()
+            .is_empty());
+
+        store
+            .delete("PiLauncherSync/favorites/nested")
+            .await
+            .unwrap();
+        store
+            .delete("PiLauncherSync/favorites/nested")
+            .await
+            .unwrap();
+        assert_eq!(
+            store.list("PiLauncherSync/favorites").await.unwrap(),
+            vec!["op-1.json".to_string()]
+        );
+        assert!(store.get("../outside").await.is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn advisory_lock_excludes_other_sessions() {
+        let root = std::env::temp_dir().join(format!("pilauncher-store-{}", uuid::Uuid::new_v4()));
+        let mut first = local_store(&root);
+        let mut second = local_store(&root);
+
+        first.lock("PiLauncherSync/save-backups").await.unwrap();
+        assert!(second.lock("PiLauncherSync/save-backups").await.is_err());
+        assert!(first
+            .list("PiLauncherSync/save-backups")
+            .await
+            .unwrap()
+            .is_empty());
+
+        first.unlock("PiLauncherSync/save-backups").await.unwrap();
+        second.lock("PiLauncherSync/save-backups").await.unwrap();
+        second.unlock("PiLauncherSync/save-backups").await.unwrap();
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn read_only_store_never_writes() {
+        let root = std::env::temp_dir().join(format!("pilauncher-store-{}", uuid::Uuid::new_v4()));
+        local_store(&root)
+            .put("PiLauncherSync/a.json", b"{}".to_vec(), "application/json")
+            .await
+            .unwrap();
+
+        let mut store = local_store(&root).into_read_only();
+        assert_eq!(
+            store.get("PiLauncherSync/a.json").await.unwrap(),
+            Some(b"{}".to_vec())
+        );
+        assert!(store.ensure_dir("PiLauncherSync/new").await.is_err());
+        assert!(store
+            .put("PiLauncherSync/b.json", Vec::new(), "application/json")
+            .await
+            .is_err());
+        assert!(store.delete("PiLauncherSync/a.json").await.is_err());
+        assert!(store.lock("PiLauncherSync").await.is_err());
+
+        let encrypted = encrypted_store(&root, Some("hunter2")).into_read_only();
+        assert!(encrypted.list("PiLauncherSync").await.unwrap().is_empty());
+        assert!(!root.join(KEY_HEADER_FILE).exists());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn encrypted_store_hides_names_and_migrates_plaintext() {
+        let root = std::env::temp_dir().join(format!("pilauncher-store-{}", uuid::Uuid::new_v4()));
+        let plain = local_store(&root);
+        plain
+            .put(
+                "PiLauncherSync/favorites/op-1.json",
+                b"{}".to_vec(),
+                "application/json",
+            )
+            .await
+            .unwrap();
+
+        let store = encrypted_store(&root, Some("hunter2"));
+        assert_eq!(
+            store
+                .list_plaintext("PiLauncherSync/favorites")
+                .await
+                .unwrap(),
+            vec!["op-1.json".to_string()]
+        );
+        assert!(store
+            .encrypt_plaintext_file("PiLauncherSync/favorites/op-1.json")
+            .await
+            .unwrap());
+        assert!(!store
+            .encrypt_plaintext_file("PiLauncherSync/favorites/op-1.json")
+            .await
+            .unwrap());
+        store
+            .put(
+                "PiLauncherSync/favorites/op-2.json",
+                b"[1]".to_vec(),
+                "application/json",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.list("PiLauncherSync/favorites").await.unwrap(),
+            vec!["op-1.json".to_string(), "op-2.json".to_string()]
+        );
+        assert_eq!(
+            store
+                .get("PiLauncherSync/favorites/op-1.json")
+                .await
+                .unwrap(),
+            Some(b"{}".to_vec())
+        );
+        for entry in walkdir::WalkDir::new(&root)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+        {
+            let name = entry.file_name().to_string_lossy().to_string();
+            assert!(!name.starts_with("op-"), "leaked name {name}");
+        }
+
+        let reopened = encrypted_store(&root, Some("hunter2"));
+        assert_eq!(
+            reopened
+                .get("PiLauncherSync/favorites/op-2.json")
+                .await
+                .unwrap(),
+            Some(b"[1]".to_vec())
+        );
+        assert!(encrypted_store(&root, Some("wrong"))
+            .list("PiLauncherSync/favorites")
+            .await
+            .is_err());
+        assert!(local_store(&root)
+            .get("PiLauncherSync/favorites/op-2.json")
+            .await
+            .is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,305 @@
+// src-tauri/src/services/remote_store/webdav.rs
+use regex::Regex;
+use reqwest::{header, Client, Method, StatusCode, Url};
+
+pub struct WebDavStore {
+    client: Client,
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+pub(crate) fn validate_base_url(base_url: &str) -> Result<(), String> {
+    let trimmed = base_url.trim();
+    if trimmed.is_empty() {
+        return Err("WebDAV address is empty".to_string());
+    }
+
+    let parsed = Url::parse(trimmed).map_err(|error| format!("invalid WebDAV URL: {error}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("WebDAV address must start with http:// or https://".to_string());
+    }
+
+    Ok(())
+}
+
+fn normalize_lock_token(value: &str) -> Option<String> {
+    let token = value
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(format!("<{token}>"))
+    }
+}
+
+fn lock_if_header(lock_token: &str) -> String {
+    format!("({lock_token})")
+}
+
+fn extract_lock_token_from_body(body: &str) -> Option<String> {
+    for marker in ["opaquelocktoken:", "urn:uuid:"] {
+        if let Some(start) = body.find(marker) {
+            let token = body[start..]
+                .split(|character: char| {
+                    character == '<'
+                        || character == '>'
+                        || character == '"'
+                        || character == '\''
+                        || character.is_whitespace()
+                })
+                .next()
+                .unwrap_or_default()
+                .trim();
+            if !token.is_empty() {
+                return Some(token.to_string());
+            }
+        }
+    }
+    None
+}
+
+impl WebDavStore {
+    pub fn new(
+        client: Client,
+        base_url: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, String> {
+        validate_base_url(base_url)?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim().to_string(),
+            username: username.trim().to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    pub fn url(&self, remote_path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            remote_path.trim_start_matches('/')
+        )
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        remote_path: &str,
+        lock_token: Option<&str>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self.client.request(method, self.url(remote_path));
+        if !(self.username.is_empty() && self.password.is_empty()) {
+            request = request.basic_auth(&self.username, Some(self.password.clone()));
+        }
+        if let Some(lock_token) = lock_token {
+            request = request.header("If", lock_if_header(lock_token));
+        }
+        request
+    }
+
+    pub async fn ensure_collection(
+        &self,
+        remote_path: &str,
+        lock_token: Option<&str>,
+    ) -> Result<bool, String> {
+        let method = Method::from_bytes(b"MKCOL")
+            .map_err(|error| format!("invalid MKCOL method: {error}"))?;
+        let response = self
+            .request(method, remote_path, lock_token)
+            .send()
+            .await
+            .map_err(|error| format!("failed to create WebDAV directory: {error}"))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(true);
+        }
+        if status == StatusCode::METHOD_NOT_ALLOWED || status == StatusCode::CONFLICT {
+            return Ok(false);
+        }
+
+        Err(format!("failed to create WebDAV directory: HTTP {status}"))
+    }
+
+    pub async fn list(&self, remote_dir: &str) -> Result<Vec<String>, String> {
+        let method = Method::from_bytes(b"PROPFIND")
+            .map_err(|error| format!("invalid PROPFIND method: {error}"))?;
+        let response = self
+            .request(method, remote_dir, None)
+            .header("Depth", "1")
+            .send()
+            .await
+            .map_err(|error| format!("failed to list WebDAV files in {remote_dir}: {error}"))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        if !response.status().is_success() {
+            return Err(format!(
+                "failed to list WebDAV files in {remote_dir}: HTTP {}",
+                response.status()
+            ));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|error| format!("failed to read WebDAV files listing: {error}"))?;
+        let href_pattern = Regex::new(r"(?i)<(?:[a-z0-9]+:)?href>([^<]+)</(?:[a-z0-9]+:)?href>")
+            .map_err(|error| error.to_string())?;
+
+        let own_path = remote_dir.trim_matches('/');
+        let mut file_names = Vec::new();
+        for captures in href_pattern.captures_iter(&body) {
+            let Some(value) = captures.get(1) else {
+                continue;
+            };
+            let Ok(decoded) = urlencoding::decode(value.as_str()) else {
+                continue;
+            };
+            let trimmed = decoded.trim_end_matches('/');
+            if trimmed.ends_with(own_path) {
+                continue;
+            }
+            if let Some(segment) = trimmed.rsplit('/').next() {
+                if !segment.is_empty() {
+                    file_names.push(segment.to_string());
+                }
+            }
+        }
+        Ok(file_names)
+    }
+
+    pub async fn get(&self, remote_path: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self
+            .request(Method::GET, remote_path, None)
+            .send()
+            .await
+            .map_err(|error| format!("failed to download WebDAV file {remote_path}: {error}"))?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!(
+                "failed to download WebDAV file {remote_path}: HTTP {}",
+                response.status()
+            ));
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|bytes| Some(bytes.to_vec()))
+            .map_err(|error| format!("failed to read WebDAV file {remote_path}: {error}"))
+    }
+
+    pub async fn put(
+        &self,
+        remote_path: &str,
+        body: Vec<u8>,
+        content_type: &str,
+        lock_token: Option<&str>,
+    ) -> Result<(), String> {
+        let response = self
+            .request(Method::PUT, remote_path, lock_token)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| format!("failed to upload WebDAV file {remote_path}: {error}"))?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "failed to upload WebDAV file {remote_path}: HTTP {}",
+                response.status()
+            ))
+        }
+    }
+
+    pub async fn delete(&self, remote_path: &str, lock_token: Option<&str>) -> Result<(), String> {
+        let response = self
+            .request(Method::DELETE, remote_path, lock_token)
+            .send()
+            .await
+            .map_err(|error| format!("failed to delete WebDAV path {remote_path}: {error}"))?;
+
+        if response.status().is_success() || response.status() == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(format!(
+                "failed to delete WebDAV path {remote_path}: HTTP {}",
+                response.status()
+            ))
+        }
+    }
+
+    pub async fn lock(&self, remote_path: &str, timeout_seconds: u64) -> Result<String, String> {
+        let method =
+            Method::from_bytes(b"LOCK").map_err(|error| format!("invalid LOCK method: {error}"))?;
+        let body = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:lockinfo xmlns:D="DAV:">
+  <D:lockscope><D:exclusive/></D:lockscope>
+  <D:locktype><D:write/></D:locktype>
+  <D:owner><D:href>PiLauncher</D:href></D:owner>
+</D:lockinfo>"#;
+
+        let response = self
+            .request(method, remote_path, None)
+            .header("Depth", "0")
+            .header("Timeout", format!("Second-{timeout_seconds}"))
+            .header(header::CONTENT_TYPE, "application/xml")
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| format!("failed to lock WebDAV directory: {error}"))?;
+
+        let status = response.status();
+        if status.as_u16() == 423 {
+            return Err(
+                "WebDAV directory is locked by another sync session; please retry later"
+                    .to_string(),
+            );
+        }
+        if !status.is_success() {
+            return Err(format!("failed to lock WebDAV directory: HTTP {status}"));
+        }
+
+        let header_token = response
+            .headers()
+            .get("Lock-Token")
+            .and_then(|value| value.to_str().ok())
+            .and_then(normalize_lock_token);
+        let body = response.text().await.unwrap_or_default();
+        header_token
+            .or_else(|| {
+                extract_lock_token_from_body(&body).and_then(|token| normalize_lock_token(&token))
+            })
+            .ok_or_else(|| "WebDAV lock succeeded, but no lock token was returned".to_string())
+    }
+
+    pub async fn unlock(&self, remote_path: &str, lock_token: &str) -> Result<(), String> {
+        let method = Method::from_bytes(b"UNLOCK")
+            .map_err(|error| format!("invalid UNLOCK method: {error}"))?;
+        let response = self
+            .request(method, remote_path, None)
+            .header("Lock-Token", lock_token)
+            .send()
+            .await
+            .map_err(|error| format!("failed to unlock WebDAV directory: {error}"))?;
+
+        let status = response.status();
+        if status.is_success() || status == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(format!("failed to unlock WebDAV directory: HTTP {status}"))
+        }
+    }
+}
@@ -1,5 +1,4 @@
-use crate::domain::library::WebDavSyncConfig;
-use reqwest::Client;
+use crate::services::remote_store::RemoteStore;
 use std::fs;
 use tauri::{AppHandle, Runtime};
 
@@ -10,8 +9,7 @@ use super::util;
 
 pub(crate) async fn compact_operations<R: Runtime>(
     app: &AppHandle<R>,
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     snapshot_timestamp: i64,
 ) -> Result<usize, String> {
     let mut compacted = 0usize;
@@ -31,7 +29,7 @@ pub(crate) async fn compact_operations<R: Runtime>(
         }
     }
 
-    let remote_operation_files = remote::list_operation_files(client, config).await?;
+    let remote_operation_files = remote::list_operation_files(store).await?;
     for file_name in remote_operation_files {
         let Some(timestamp) = util::operation_timestamp_from_file_name(&file_name) else {
             continue;
@@ -40,7 +38,7 @@ pub(crate) async fn compact_operations<R: Runtime>(
             continue;
         }
 
-        remote::delete_operation_file(client, config, &file_name).await?;
+        remote::delete_operation_file(store, &file_name).await?;
     }
 
     Ok(compacted)
@@ -0,0 +1,1441 @@
+use crate::domain::library::WebDavSyncConfig;
+use crate::domain::mod_manifest::{ModManifestEntry, ModSourceKind};
+use crate::services::config_service::ConfigService;
+use crate::services::instance::mod_manifest_service::ModManifestService;
+use crate::services::remote_store::RemoteStore;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use walkdir::WalkDir;
+
+use super::constants::{INSTANCES_DIR, REMOTE_ROOT};
+use super::plan::{self, PlannedConflict, SyncConflictResolution, WebDavSyncPlan, WebDavSyncScope};
+use super::{local_store, migration, paths, util};
+
+const INSTANCE_CONFIG_FILE: &str = "instance.json";
+const OPTIONS_FILE: &str = "options.txt";
+const CONFIG_DIR: &str = "config";
+const MAX_SYNCED_FILE_SIZE: u64 = 8 * 1024 * 1024;
+const KEEP_REMOTE_OPERATIONS: usize = 20;
+
+// 这些字段跟着设备走（Java 路径、内存、游玩统计、外部目录、本地图片），不参与同步
+const LOCAL_ONLY_INSTANCE_KEYS: &[&str] = &[
+    "java",
+    "memory",
+    "playTime",
+    "lastPlayed",
+    "third_party_path",
+    "cover_image",
+    "hero_logo",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSyncFile {
+    pub path: String,
+    pub sha1: String,
+    pub size: u64,
+}
+
+/// 模组只同步平台引用，jar 由各设备自行从 Modrinth / CurseForge 下载。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceModReference {
+    pub file_name: String,
+    pub platform: String,
+    pub project_id: String,
+    pub file_id: String,
+    pub sha1: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSyncRevision {
+    pub op_id: String,
+    pub instance_id: String,
+    pub timestamp: i64,
+    pub device_id: String,
+    #[serde(default)]
+    pub parent_revision: Option<String>,
+    pub revision: String,
+    #[serde(default)]
+    pub files: Vec<InstanceSyncFile>,
+    #[serde(default)]
+    pub mods: Vec<InstanceModReference>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSyncState {
+    pub instance_id: String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub base: Option<InstanceSyncRevision>,
+    #[serde(default)]
+    pub last_sync_at: i64,
+    #[serde(default)]
+    pub pending_mods: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSyncConflict {
+    pub instance_id: String,
+    pub local_revision: String,
+    pub remote_revision: String,
+    pub remote_device_id: String,
+    pub remote_timestamp: i64,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstanceSyncAction {
+    Uploaded,
+    Downloaded,
+    Merged,
+    Unchanged,
+    Conflict,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceSyncOutcome {
+    pub instance_id: String,
+    pub action: InstanceSyncAction,
+    pub revision: Option<String>,
+    pub transferred_files: usize,
+    pub installed_mods: usize,
+    pub removed_mods: usize,
+    pub pending_mods: Vec<String>,
+    pub conflict: Option<InstanceSyncConflict>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavInstanceSyncResult {
+    pub remote_root: String,
+    pub instances: Vec<InstanceSyncOutcome>,
+    pub remote_only_instances: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceConflictChoice {
+    KeepLocal,
+    KeepRemote,
+}
+
+impl InstanceConflictChoice {
+    pub fn from_input(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "local" => Ok(Self::KeepLocal),
+            "remote" => Ok(Self::KeepRemote),
+            other => Err(format!("unknown conflict resolution: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SyncDecision {
+    Upload,
+    Download,
+    /// 两边改动互不重叠，合并后的版本需要应用到本地再上传
+    Merge(InstanceSyncRevision),
+    Unchanged,
+    Conflict(Vec<String>),
+}
+
+struct InstanceDirs {
+    instance_dir: PathBuf,
+    game_dir: PathBuf,
+}
+
+impl InstanceDirs {
+    fn resolve<R: Runtime>(app: &AppHandle<R>, instance_id: &str) -> Result<Self, String> {
+        if instance_id.is_empty()
+            || instance_id.contains(['/', '\\'])
+            || instance_id.starts_with('.')
+        {
+            return Err(format!("invalid instance id: {instance_id}"));
+        }
+        let base_path = ConfigService::get_base_path(app)
+            .map_err(|error| error.to_string())?
+            .ok_or_else(|| "base path is not configured".to_string())?;
+        let instance_dir = PathBuf::from(base_path).join("instances").join(instance_id);
+        if !instance_dir.join(INSTANCE_CONFIG_FILE).is_file() {
+            return Err(format!("instance does not exist locally: {instance_id}"));
+        }
+        let game_dir = fs::read_to_string(instance_dir.join(INSTANCE_CONFIG_FILE))
+            .ok()
+            .and_then(|content| {
+                serde_json::from_str::<crate::domain::instance::InstanceConfig>(&content).ok()
+            })
+            .and_then(|config| config.third_party_path)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| instance_dir.clone());
+        Ok(Self {
+            instance_dir,
+            game_dir,
+        })
+    }
+
+    fn mods_dir(&self) -> PathBuf {
+        self.game_dir.join("mods")
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.instance_dir.join("mod_manifest.json")
+    }
+
+    fn local_path(&self, path: &str) -> PathBuf {
+        if path == INSTANCE_CONFIG_FILE {
+            self.instance_dir.join(path)
+        } else {
+            self.game_dir.join(path)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 本地状态：每个实例一份 <sync>/instances/<id>.json，记录开关与上次同步的基线版本
+// ---------------------------------------------------------------------------
+
+fn state_path<R: Runtime>(app: &AppHandle<R>, instance_id: &str) -> Result<PathBuf, String> {
+    Ok(paths::instances_state_dir(app)?.join(format!("{instance_id}.json")))
+}
+
+fn read_state<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+) -> Result<InstanceSyncState, String> {
+    let path = state_path(app, instance_id)?;
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(InstanceSyncState {
+            instance_id: instance_id.to_string(),
+            ..InstanceSyncState::default()
+        });
+    };
+    serde_json::from_str(&content)
+        .map_err(|error| format!("invalid instance sync state {}: {error}", path.display()))
+}
+
+fn write_state<R: Runtime>(app: &AppHandle<R>, state: &InstanceSyncState) -> Result<(), String> {
+    let path = state_path(app, &state.instance_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    let content = serde_json::to_vec_pretty(state).map_err(|error| error.to_string())?;
+    fs::write(&path, content).map_err(|error| error.to_string())
+}
+
+pub(crate) fn list_instance_sync_states<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<Vec<InstanceSyncState>, String> {
+    let dir = paths::instances_state_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut states = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .map_err(|error| error.to_string())?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(instance_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        states.push(read_state(app, instance_id)?);
+    }
+    states.sort_by(|left, right| left.instance_id.cmp(&right.instance_id));
+    Ok(states)
+}
+
+pub(crate) fn set_instance_sync_enabled<R: Runtime>(
+    app: &AppHandle<R>,
+    instance_id: &str,
+    enabled: bool,
+) -> Result<InstanceSyncState, String> {
+    InstanceDirs::resolve(app, instance_id)?;
+    let mut state = read_state(app, instance_id)?;
+    state.enabled = enabled;
+    write_state(app, &state)?;
+    Ok(state)
+}
+
+// ---------------------------------------------------------------------------
+// 本地版本：instance.json（去掉本机字段）、options.txt、config/** 与模组引用
+// ---------------------------------------------------------------------------
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha1::digest(bytes))
+}
+
+/// 只允许同步白名单内的相对路径，远端传来的路径不能写到实例目录之外。
+fn is_tracked_path(path: &str) -> bool {
+    if path == INSTANCE_CONFIG_FILE || path == OPTIONS_FILE {
+        return true;
+    }
+    if path.contains('\\') || !path.starts_with("config/") {
+        return false;
+    }
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+}
+
+fn is_safe_mod_file_name(file_name: &str) -> bool {
+    !file_name.is_empty()
+        && !file_name.starts_with('.')
+        && !file_name.contains(['/', '\\'])
+        && file_name.ends_with(".jar")
+}
+
+fn sanitize_instance_config(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut value = serde_json::from_slice::<serde_json::Value>(bytes)
+        .map_err(|error| format!("invalid instance.json: {error}"))?;
+    if let Some(object) = value.as_object_mut() {
+        for key in LOCAL_ONLY_INSTANCE_KEYS {
+            object.remove(*key);
+        }
+    }
+    serde_json::to_vec_pretty(&value).map_err(|error| error.to_string())
+}
+
+/// 远端 instance.json 覆盖同步字段，本机字段保留本地原值。
+fn merge_instance_config(local: &[u8], remote: &[u8]) -> Result<Vec<u8>, String> {
+    let local = serde_json::from_slice::<serde_json::Value>(local)
+        .map_err(|error| format!("invalid local instance.json: {error}"))?;
+    let mut merged = serde_json::from_slice::<serde_json::Value>(remote)
+        .map_err(|error| format!("invalid remote instance.json: {error}"))?;
+    let Some(merged_object) = merged.as_object_mut() else {
+        return Err("remote instance.json is not an object".to_string());
+    };
+    for key in LOCAL_ONLY_INSTANCE_KEYS {
+        merged_object.remove(*key);
+        if let Some(value) = local.get(*key) {
+            merged_object.insert((*key).to_string(), value.clone());
+        }
+    }
+    serde_json::to_vec_pretty(&merged).map_err(|error| error.to_string())
+}
+
+fn read_local_file(dirs: &InstanceDirs, path: &str) -> Result<Vec<u8>, String> {
+    let bytes = fs::read(dirs.local_path(path))
+        .map_err(|error| format!("failed to read {path}: {error}"))?;
+    if path == INSTANCE_CONFIG_FILE {
+        sanitize_instance_config(&bytes)
+    } else {
+        Ok(bytes)
+    }
+}
+
+fn collect_local_files(dirs: &InstanceDirs) -> Result<Vec<InstanceSyncFile>, String> {
+    let mut candidates = vec![INSTANCE_CONFIG_FILE.to_string()];
+    if dirs.game_dir.join(OPTIONS_FILE).is_file() {
+        candidates.push(OPTIONS_FILE.to_string());
+    }
+    let config_dir = dirs.game_dir.join(CONFIG_DIR);
+    if config_dir.is_dir() {
+        for entry in WalkDir::new(&config_dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+        {
+            if entry.metadata().map(|meta| meta.len()).unwrap_or(u64::MAX) > MAX_SYNCED_FILE_SIZE {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(&dirs.game_dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if is_tracked_path(&relative) {
+                candidates.push(relative);
+            }
+        }
+    }
+
+    let mut files = Vec::with_capacity(candidates.len());
+    for path in candidates {
+        let bytes = read_local_file(dirs, &path)?;
+        files.push(InstanceSyncFile {
+            sha1: sha1_hex(&bytes),
+            size: bytes.len() as u64,
+            path,
+        });
+    }
+    files.sort_by(|left, right| left.path.cmp(&right.path));
+    Ok(files)
+}
+
+fn mod_reference(file_name: &str, entry: &ModManifestEntry) -> Option<InstanceModReference> {
+    let from_source = match (
+        entry.source.platform.as_deref(),
+        entry.source.project_id.as_deref(),
+        entry.source.file_id.as_deref(),
+    ) {
+        (Some(platform), Some(project_id), Some(file_id))
+            if !platform.is_empty() && !project_id.is_empty() && !file_id.is_empty() =>
+        {
+            Some((
+                platform.to_string(),
+                project_id.to_string(),
+                file_id.to_string(),
+            ))
+        }
+        _ => None,
+    };
+    let (platform, project_id, file_id) = from_source.or_else(|| {
+        ["modrinth", "curseforge"].into_iter().find_map(|platform| {
+            let matched = entry.matched_platforms.get(platform)?;
+            Some((
+                platform.to_string(),
+                matched.project_id.clone()?,
+                matched.file_id.clone()?,
+            ))
+        })
+    })?;
+    let platform = platform.to_ascii_lowercase();
+    if platform != "modrinth" && platform != "curseforge" {
+        return None;
+    }
+    Some(InstanceModReference {
+        file_name: file_name.to_string(),
+        platform,
+        project_id,
+        file_id,
+        // 其他算法的摘要不能当作 sha1 校验，留空时安装端只按平台引用下载
+        sha1: if entry.hash.algorithm.eq_ignore_ascii_case("sha1") {
+            entry.hash.value.to_ascii_lowercase()
+        } else {
+            String::new()
+        },
+    })
+}
+
+/// 只有能在平台上找到来源的模组会进入同步；本地手动导入的 jar 不会出现在引用里，
+/// 因此也永远不会被其他设备的同步删掉。
+fn collect_local_mods(dirs: &InstanceDirs) -> Result<Vec<InstanceModReference>, String> {
+    let manifest = ModManifestService::load_from_mods_dir(&dirs.mods_dir(), &dirs.manifest_path())?;
+    let mut mods = manifest
+        .iter()
+        .filter_map(|(file_name, entry)| mod_reference(file_name, entry))
+        .collect::<Vec<_>>();
+    mods.sort_by(|left, right| left.file_name.cmp(&right.file_name));
+    Ok(mods)
+}
+
+fn revision_hash(files: &[InstanceSyncFile], mods: &[InstanceModReference]) -> String {
+    let mut hasher = Sha1::new();
+    for file in files {
+        hasher.update(format!("f\0{}\0{}\n", file.path, file.sha1));
+    }
+    for reference in mods {
+        hasher.update(format!(
+            "m\0{}\0{}\0{}\0{}\n",
+            reference.file_name, reference.platform, reference.project_id, reference.file_id
+        ));
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn build_local_revision(
+    dirs: &InstanceDirs,
+    state: &InstanceSyncState,
+    instance_id: &str,
+    device_id: &str,
+) -> Result<InstanceSyncRevision, String> {
+    let files = collect_local_files(dirs)?;
+    let mut mods = collect_local_mods(dirs)?;
+    // 上次没装上的模组仍算作本地已有，避免下载失败被误当成“本地删除”再同步回远端
+    if let Some(base) = state.base.as_ref() {
+        let present = mods
+            .iter()
+            .map(|reference| reference.file_name.clone())
+            .collect::<HashSet<_>>();
+        mods.extend(
+            base.mods
+                .iter()
+                .filter(|reference| {
+                    state.pending_mods.contains(&reference.file_name)
+                        && !present.contains(&reference.file_name)
+                })
+                .cloned(),
+        );
+        mods.sort_by(|left, right| left.file_name.cmp(&right.file_name));
+    }
+    Ok(InstanceSyncRevision {
+        op_id: uuid::Uuid::new_v4().to_string(),
+        instance_id: instance_id.to_string(),
+        timestamp: util::now_millis(),
+        device_id: device_id.to_string(),
+        parent_revision: state.base.as_ref().map(|base| base.revision.clone()),
+        revision: revision_hash(&files, &mods),
+        files,
+        mods,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// 冲突判定
+// ---------------------------------------------------------------------------
+
+fn revision_entries(revision: &InstanceSyncRevision) -> BTreeMap<String, String> {
+    let mut entries = revision
+        .files
+        .iter()
+        .map(|file| (file.path.clone(), file.sha1.clone()))
+        .collect::<BTreeMap<_, _>>();
+    for reference in &revision.mods {
+        entries.insert(
+            format!("mods/{}", reference.file_name),
+            format!(
+                "{}:{}:{}",
+                reference.platform, reference.project_id, reference.file_id
+            ),
+        );
+    }
+    entries
+}
+
+fn changed_paths(from: &InstanceSyncRevision, to: &InstanceSyncRevision) -> BTreeSet<String> {
+    let from = revision_entries(from);
+    let to = revision_entries(to);
+    from.keys()
+        .chain(to.keys())
+        .filter(|path| from.get(*path) != to.get(*path))
+        .cloned()
+        .collect()
+}
+
+fn decide(
+    local: &InstanceSyncRevision,
+    base: Option<&InstanceSyncRevision>,
+    head: Option<&InstanceSyncRevision>,
+) -> SyncDecision {
+    let Some(head) = head else {
+        return SyncDecision::Upload;
+    };
+    if head.revision == local.revision {
+        return SyncDecision::Unchanged;
+    }
+    if let Some(base) = base {
+        if base.revision == head.revision {
+            return SyncDecision::Upload;
+        }
+        if base.revision == local.revision {
+            return SyncDecision::Download;
+        }
+        let overlapping = changed_paths(base, local)
+            .intersection(&changed_paths(base, head))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !overlapping.is_empty() {
+            return SyncDecision::Conflict(overlapping);
+        }
+        return SyncDecision::Merge(merge_revisions(base, local, head));
+    }
+    SyncDecision::Conflict(changed_paths(local, head).into_iter().collect())
+}
+
+/// 远端改过的条目取远端，其余保留本地，得到两边改动都生效的版本。
+fn merge_revisions(
+    base: &InstanceSyncRevision,
+    local: &InstanceSyncRevision,
+    head: &InstanceSyncRevision,
+) -> InstanceSyncRevision {
+    let remote_changed = changed_paths(base, head);
+    let from_head = |path: &str| remote_changed.contains(path);
+
+    let mut files = local
+        .files
+        .iter()
+        .filter(|file| !from_head(&file.path))
+        .chain(head.files.iter().filter(|file| from_head(&file.path)))
+        .cloned()
+        .collect::<Vec<_>>();
+    files.sort_by(|left, right| left.path.cmp(&right.path));
+
+    let mod_key = |reference: &InstanceModReference| format!("mods/{}", reference.file_name);
+    let mut mods = local
+        .mods
+        .iter()
+        .filter(|reference| !from_head(&mod_key(reference)))
+        .chain(
+            head.mods
+                .iter()
+                .filter(|reference| from_head(&mod_key(reference))),
+        )
+        .cloned()
+        .collect::<Vec<_>>();
+    mods.sort_by(|left, right| left.file_name.cmp(&right.file_name));
+
+    InstanceSyncRevision {
+        parent_revision: Some(head.revision.clone()),
+        revision: revision_hash(&files, &mods),
+        files,
+        mods,
+        ..local.clone()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 远端布局：instances/<id>/operations/op-*.json、snapshot.json（当前版本）与 blobs/<sha1>
+// ---------------------------------------------------------------------------
+
+fn remote_instance_dir(instance_id: &str) -> String {
+    format!("{INSTANCES_DIR}/{instance_id}")
+}
+
+fn remote_snapshot_path(instance_id: &str) -> String {
+    format!("{}/snapshot.json", remote_instance_dir(instance_id))
+}
+
+fn remote_operations_dir(instance_id: &str) -> String {
+    format!("{}/operations", remote_instance_dir(instance_id))
+}
+
+fn remote_blobs_dir(instance_id: &str) -> String {
+    format!("{}/blobs", remote_instance_dir(instance_id))
+}
+
+async fn ensure_instance_layout(store: &RemoteStore) -> Result<(), String> {
+    store.ensure_dir(REMOTE_ROOT).await?;
+    store.ensure_dir(INSTANCES_DIR).await?;
+    let encrypted = migration::encrypt_plaintext_instances(store).await?;
+    if encrypted > 0 {
+        log::info!("Instance sync: encrypted {encrypted} plaintext remote files");
+    }
+    Ok(())
+}
+
+async fn upload_revision(
+    store: &RemoteStore,
+    dirs: &InstanceDirs,
+    revision: &InstanceSyncRevision,
+) -> Result<usize, String> {
+    let instance_id = &revision.instance_id;
+    store.ensure_dir(&remote_instance_dir(instance_id)).await?;
+    store
+        .ensure_dir(&remote_operations_dir(instance_id))
+        .await?;
+    store.ensure_dir(&remote_blobs_dir(instance_id)).await?;
+
+    let existing_blobs = store
+        .list(&remote_blobs_dir(instance_id))
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let mut uploaded = 0usize;
+    for file in &revision.files {
+        if existing_blobs.contains(&file.sha1) {
+            continue;
+        }
+        let bytes = read_local_file(dirs, &file.path)?;
+        if sha1_hex(&bytes) != file.sha1 {
+            return Err(format!("{} changed while syncing, try again", file.path));
+        }
+        store
+            .put(
+                &format!("{}/{}", remote_blobs_dir(instance_id), file.sha1),
+                bytes,
+                "application/octet-stream",
+            )
+            .await
+            .map_err(|error| format!("failed to upload {}: {error}", file.path))?;
+        uploaded += 1;
+    }
+
+    let body = serde_json::to_vec_pretty(revision).map_err(|error| error.to_string())?;
+    store
+        .put(
+            &format!(
+                "{}/op-{}-{}.json",
+                remote_operations_dir(instance_id),
+                revision.timestamp,
+                revision.op_id
+            ),
+            body.clone(),
+            "application/json",
+        )
+        .await?;
+    store
+        .put(&remote_snapshot_path(instance_id), body, "application/json")
+        .await?;
+
+    compact_remote_instance(store, revision, existing_blobs).await?;
+    Ok(uploaded)
+}
+
+/// 只保留最近的操作记录；快照之外没有引用的 blob 不会再被读取，直接删除。
+async fn compact_remote_instance(
+    store: &RemoteStore,
+    head: &InstanceSyncRevision,
+    existing_blobs: HashSet<String>,
+) -> Result<(), String> {
+    let operations_dir = remote_operations_dir(&head.instance_id);
+    let mut operations = store
+        .list(&operations_dir)
+        .await?
+        .into_iter()
+        .filter_map(|name| Some((util::operation_timestamp_from_file_name(&name)?, name)))
+        .collect::<Vec<_>>();
+    operations.sort();
+    let excess = operations.len().saturating_sub(KEEP_REMOTE_OPERATIONS);
+    for (_, name) in operations.into_iter().take(excess) {
+        store.delete(&format!("{operations_dir}/{name}")).await?;
+    }
+
+    let referenced = head
+        .files
+        .iter()
+        .map(|file| file.sha1.as_str())
+        .collect::<HashSet<_>>();
+    let blobs_dir = remote_blobs_dir(&head.instance_id);
+    for blob in existing_blobs {
+        if !referenced.contains(blob.as_str()) {
+            store.delete(&format!("{blobs_dir}/{blob}")).await?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// 应用远端版本
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct ApplyReport {
+    written_files: usize,
+    installed_mods: usize,
+    removed_mods: usize,
+    pending_mods: Vec<String>,
+}
+
+async fn apply_remote_revision(
+    store: &RemoteStore,
+    dirs: &InstanceDirs,
+    local: &InstanceSyncRevision,
+    head: &InstanceSyncRevision,
+) -> Result<ApplyReport, String> {
+    let mut report = ApplyReport::default();
+    let local_files = local
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file.sha1.as_str()))
+        .collect::<BTreeMap<_, _>>();
+
+    for file in &head.files {
+        if !is_tracked_path(&file.path) {
+            return Err(format!(
+                "remote revision contains an invalid path: {}",
+                file.path
+            ));
+        }
+        if local_files.get(file.path.as_str()) == Some(&file.sha1.as_str()) {
+            continue;
+        }
+        let blob_path = format!("{}/{}", remote_blobs_dir(&head.instance_id), file.sha1);
+        let bytes = store
+            .get(&blob_path)
+            .await?
+            .ok_or_else(|| format!("remote file is missing: {}", file.path))?;
+        if sha1_hex(&bytes) != file.sha1 {
+            return Err(format!("remote file failed verification: {}", file.path));
+        }
+        let target = dirs.local_path(&file.path);
+        let contents = if file.path == INSTANCE_CONFIG_FILE {
+            let current = fs::read(&target).map_err(|error| error.to_string())?;
+            merge_instance_config(&current, &bytes)?
+        } else {
+            bytes
+        };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(&target, contents)
+            .map_err(|error| format!("failed to write {}: {error}", file.path))?;
+        report.written_files += 1;
+    }
+
+    let remote_paths = head
+        .files
+        .iter()
+        .map(|file| file.path.as_str())
+        .collect::<HashSet<_>>();
+    for path in local_files.keys() {
+        if *path == INSTANCE_CONFIG_FILE || remote_paths.contains(path) {
+            continue;
+        }
+        let target = dirs.local_path(path);
+        if target.is_file() {
+            fs::remove_file(&target)
+                .map_err(|error| format!("failed to remove {path}: {error}"))?;
+        }
+    }
+
+    apply_remote_mods(dirs, local, head, &mut report).await?;
+    Ok(report)
+}
+
+async fn apply_remote_mods(
+    dirs: &InstanceDirs,
+    local: &InstanceSyncRevision,
+    head: &InstanceSyncRevision,
+    report: &mut ApplyReport,
+) -> Result<(), String> {
+    let mods_dir = dirs.mods_dir();
+    fs::create_dir_all(&mods_dir).map_err(|error| error.to_string())?;
+
+    let remote_names = head
+        .mods
+        .iter()
+        .map(|reference| reference.file_name.as_str())
+        .collect::<HashSet<_>>();
+    for reference in &local.mods {
+        if remote_names.contains(reference.file_name.as_str()) {
+            continue;
+        }
+        for name in [
+            reference.file_name.clone(),
+            format!("{}.disabled", reference.file_name),
+        ] {
+            let path = mods_dir.join(&name);
+            if path.is_file() {
+                fs::remove_file(&path).map_err(|error| error.to_string())?;
+                report.removed_mods += 1;
+            }
+        }
+    }
+
+    let missing = head
+        .mods
+        .iter()
+        .filter(|reference| {
+            !local.mods.contains(reference)
+                && !mods_dir.join(&reference.file_name).is_file()
+                && !mods_dir
+                    .join(format!("{}.disabled", reference.file_name))
+                    .is_file()
+        })
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        let client = reqwest::Client::builder()
+            .user_agent("PiLauncher/1.0 (InstanceSync)")
+            .timeout(Duration::from_secs(120))
+            .build()
+            .map_err(|error| error.to_string())?;
+        for reference in missing {
+            match install_mod(&client, dirs, reference).await {
+                Ok(()) => report.installed_mods += 1,
+                Err(error) => {
+                    log::warn!(
+                        "Instance sync: failed to install {}: {error}",
+                        reference.file_name
+                    );
+                    report.pending_mods.push(reference.file_name.clone());
+                }
+            }
+        }
+    }
+
+    ModManifestService::sync_from_mods_dir(&mods_dir, &dirs.manifest_path())?;
+    Ok(())
+}
+
+async fn resolve_mod_download_url(
+    client: &reqwest::Client,
+    reference: &InstanceModReference,
+) -> Result<String, String> {
+    match reference.platform.as_str() {
+        "modrinth" => {
+            let url = format!("https://api.modrinth.com/v2/version/{}", reference.file_id);
+            let version = client
+                .get(&url)
+                .send()
+                .await
+                .map_err(|error| format!("Modrinth request failed: {error}"))?
+                .error_for_status()
+                .map_err(|error| format!("Modrinth request failed: {error}"))?
+                .json::<serde_json::Value>()
+                .await
+                .map_err(|error| format!("Modrinth response parse failed: {error}"))?;
+            let files = version
+                .get("files")
+                .and_then(|files| files.as_array())
+                .cloned()
+                .unwrap_or_default();
+            files
+                .iter()
+                .find(|file| {
+                    file.get("filename").and_then(|name| name.as_str())
+                        == Some(reference.file_name.as_str())
+                })
+                .or_else(|| {
+                    files.iter().find(|file| {
+                        file.get("primary").and_then(|primary| primary.as_bool()) == Some(true)
+                    })
+                })
+                .or_else(|| files.first())
+                .and_then(|file| file.get("url").and_then(|url| url.as_str()))
+                .map(str::to_string)
+                .ok_or_else(|| format!("Modrinth version {} has no files", reference.file_id))
+        }
+        "curseforge" => {
+            let file_id = reference
+                .file_id
+                .parse::<u64>()
+                .map_err(|_| format!("invalid CurseForge file id: {}", reference.file_id))?;
+            Ok(format!(
+                "https://edge.forgecdn.net/files/{}/{:03}/{}",
+                file_id / 1000,
+                file_id % 1000,
+                urlencoding::encode(&reference.file_name)
+            ))
+        }
+        other => Err(format!("unsupported mod platform: {other}")),
+    }
+}
+
+async fn install_mod(
+    client: &reqwest::Client,
+    dirs: &InstanceDirs,
+    reference: &InstanceModReference,
+) -> Result<(), String> {
+    if !is_safe_mod_file_name(&reference.file_name) {
+        return Err(format!("invalid mod file name: {}", reference.file_name));
+    }
+    let url = resolve_mod_download_url(client, reference).await?;
+    let bytes = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|error| error.to_string())?
+        .error_for_status()
+        .map_err(|error| error.to_string())?
+        .bytes()
+        .await
+        .map_err(|error| error.to_string())?;
+    if !reference.sha1.is_empty() && sha1_hex(&bytes) != reference.sha1 {
+        return Err("downloaded file does not match the synced hash".to_string());
+    }
+
+    let target = dirs.mods_dir().join(&reference.file_name);
+    let temp = dirs
+        .mods_dir()
+        .join(format!(".{}.part", reference.file_name));
+    fs::write(&temp, &bytes).map_err(|error| error.to_string())?;
+    fs::rename(&temp, &target).map_err(|error| error.to_string())?;
+    ModManifestService::upsert_downloaded_mod(
+        &dirs.manifest_path(),
+        &target,
+        ModSourceKind::LauncherDownload,
+        Some(reference.platform.clone()),
+        Some(reference.project_id.clone()),
+        Some(reference.file_id.clone()),
+        None,
+        None,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// 同步入口
+// ---------------------------------------------------------------------------
+
+/// 开头补装仍失败的模组如果目标版本还引用着，继续留在待安装列表里。
+fn carry_pending_mods(
+    state: &InstanceSyncState,
+    dirs: &InstanceDirs,
+    target: &InstanceSyncRevision,
+    failed: Vec<String>,
+) -> Vec<String> {
+    let mut pending = state
+        .pending_mods
+        .iter()
+        .filter(|name| {
+            target
+                .mods
+                .iter()
+                .any(|reference| &reference.file_name == *name)
+                && !dirs.mods_dir().join(name.as_str()).is_file()
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    pending.extend(failed);
+    pending.sort();
+    pending.dedup();
+    pending
+}
+
+fn outcome(instance_id: &str, action: InstanceSyncAction) -> InstanceSyncOutcome {
+    InstanceSyncOutcome {
+        instance_id: instance_id.to_string(),
+        action,
+        revision: None,
+        transferred_files: 0,
+        installed_mods: 0,
+        removed_mods: 0,
+        pending_mods: Vec::new(),
+        conflict: None,
+        error: None,
+    }
+}
+
+async fn sync_instance<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+    device_id: &str,
+    mut state: InstanceSyncState,
+    choice: Option<InstanceConflictChoice>,
+) -> Result<InstanceSyncOutcome, String> {
+    let instance_id = state.instance_id.clone();
+    let dirs = InstanceDirs::resolve(app, &instance_id)?;
+
+    // 先补装上次失败的模组，装上后它们就是本地真实存在的文件了
+    if let Some(base) = state.base.clone() {
+        if !state.pending_mods.is_empty() {
+            let mut report = ApplyReport::default();
+            let empty = InstanceSyncRevision {
+                mods: Vec::new(),
+                ..base.clone()
+            };
+            let retry = InstanceSyncRevision {
+                mods: base
+                    .mods
+                    .iter()
+                    .filter(|reference| state.pending_mods.contains(&reference.file_name))
+                    .cloned()
+                    .collect(),
+                ..base
+            };
+            apply_remote_mods(&dirs, &empty, &retry, &mut report).await?;
+            state.pending_mods = report.pending_mods;
+        }
+    }
+
+    let mut local = build_local_revision(&dirs, &state, &instance_id, device_id)?;
+    let head = store
+        .get_json::<InstanceSyncRevision>(&remote_snapshot_path(&instance_id))
+        .await?;
+    let decision = match (choice, head.as_ref()) {
+        (Some(InstanceConflictChoice::KeepLocal), _) => SyncDecision::Upload,
+        (Some(InstanceConflictChoice::KeepRemote), Some(_)) => SyncDecision::Download,
+        (Some(InstanceConflictChoice::KeepRemote), None) => {
+            return Err(format!("no remote revision for instance {instance_id}"));
+        }
+        (None, head) => decide(&local, state.base.as_ref(), head),
+    };
+
+    let mut result = outcome(&instance_id, InstanceSyncAction::Unchanged);
+    match decision {
+        SyncDecision::Unchanged => {
+            state.base = head;
+        }
+        SyncDecision::Upload => {
+            local.parent_revision = head.as_ref().map(|head| head.revision.clone());
+            result.transferred_files = upload_revision(store, &dirs, &local).await?;
+            result.action = InstanceSyncAction::Uploaded;
+            state.base = Some(local.clone());
+        }
+        SyncDecision::Download => {
+            let head =
+                head.ok_or_else(|| format!("no remote revision for instance {instance_id}"))?;
+            let report = apply_remote_revision(store, &dirs, &local, &head).await?;
+            result.action = InstanceSyncAction::Downloaded;
+            result.transferred_files = report.written_files;
+            result.installed_mods = report.installed_mods;
+            result.removed_mods = report.removed_mods;
+            state.pending_mods = carry_pending_mods(&state, &dirs, &head, report.pending_mods);
+            state.base = Some(head);
+        }
+        SyncDecision::Merge(merged) => {
+            let report = apply_remote_revision(store, &dirs, &local, &merged).await?;
+            state.pending_mods = carry_pending_mods(&state, &dirs, &merged, report.pending_mods);
+            // 以合并结果为基线重建本地版本，没装上的模组仍按合并结果上传
+            state.base = Some(merged.clone());
+            let mut merged_local = build_local_revision(&dirs, &state, &instance_id, device_id)?;
+            merged_local.parent_revision = merged.parent_revision.clone();
+            result.transferred_files =
+                report.written_files + upload_revision(store, &dirs, &merged_local).await?;
+            result.installed_mods = report.installed_mods;
+            result.removed_mods = report.removed_mods;
+            result.action = InstanceSyncAction::Merged;
+            state.base = Some(merged_local);
+        }
+        SyncDecision::Conflict(paths) => {
+            let head =
+                head.ok_or_else(|| format!("no remote revision for instance {instance_id}"))?;
+            result.action = InstanceSyncAction::Conflict;
+            result.conflict = Some(InstanceSyncConflict {
+                instance_id: instance_id.clone(),
+                local_revision: local.revision.clone(),
+                remote_revision: head.revision.clone(),
+                remote_device_id: head.device_id.clone(),
+                remote_timestamp: head.timestamp,
+                paths,
+            });
+            // 冲突时不动本地与远端，也不推进基线，等待用户选择保留哪一边
+            write_state(app, &state)?;
+            result.pending_mods = state.pending_mods;
+            return Ok(result);
+        }
+    }
+
+    result.revision = state.base.as_ref().map(|base| base.revision.clone());
+    result.pending_mods = state.pending_mods.clone();
+    state.last_sync_at = util::now_millis();
+    write_state(app, &state)?;
+    Ok(result)
+}
+
+/// 预演实例同步：对每个开启同步的实例做与正式同步相同的判定，只读不写。
+pub(crate) async fn plan_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+    device_id: &str,
+) -> Result<WebDavSyncPlan, String> {
+    let mut plan = WebDavSyncPlan::new(WebDavSyncScope::Instances, store.describe(INSTANCES_DIR));
+    for state in list_instance_sync_states(app)?
+        .into_iter()
+        .filter(|state| state.enabled)
+    {
+        let instance_id = state.instance_id.clone();
+        let dirs = InstanceDirs::resolve(app, &instance_id)?;
+        let local = build_local_revision(&dirs, &state, &instance_id, device_id)?;
+        let head = store
+            .get_json::<InstanceSyncRevision>(&remote_snapshot_path(&instance_id))
+            .await?;
+        let local_entries = revision_entries(&local);
+        let head_entries = head.as_ref().map(revision_entries).unwrap_or_default();
+        let remote_path = |path: &str| format!("{}/{path}", remote_instance_dir(&instance_id));
+        let local_path = |path: &str| format!("instances/{instance_id}/{path}");
+
+        match decide(&local, state.base.as_ref(), head.as_ref()) {
+            SyncDecision::Unchanged => {}
+            SyncDecision::Upload => {
+                for (path, hash) in &local_entries {
+                    if head_entries.get(path) != Some(hash) {
+                        plan.uploads.push(remote_path(path));
+                    }
+                }
+                for path in head_entries.keys() {
+                    if !local_entries.contains_key(path) {
+                        plan.remote_deletions.push(remote_path(path));
+                    }
+                }
+                plan.uploads.push(remote_snapshot_path(&instance_id));
+            }
+            SyncDecision::Download => {
+                for (path, hash) in &head_entries {
+                    if local_entries.get(path) != Some(hash) {
+                        plan.downloads.push(remote_path(path));
+                    }
+                }
+                for path in local_entries.keys() {
+                    if !head_entries.contains_key(path) {
+                        plan.local_deletions.push(local_path(path));
+                    }
+                }
+            }
+            SyncDecision::Merge(merged) => {
+                let merged_entries = revision_entries(&merged);
+                for path in changed_paths(&local, &merged) {
+                    if merged_entries.contains_key(&path) {
+                        plan.downloads.push(remote_path(&path));
+                    } else {
+                        plan.local_deletions.push(local_path(&path));
+                    }
+                }
+                let pushed = head
+                    .as_ref()
+                    .map(|head| changed_paths(head, &merged))
+                    .unwrap_or_default();
+                for path in pushed {
+                    if merged_entries.contains_key(&path) {
+                        plan.uploads.push(remote_path(&path));
+                    } else {
+                        plan.remote_deletions.push(remote_path(&path));
+                    }
+                }
+                plan.uploads.push(remote_snapshot_path(&instance_id));
+            }
+            SyncDecision::Conflict(paths) => {
+                let Some(head) = head.as_ref() else {
+                    return Err(format!("no remote revision for instance {instance_id}"));
+                };
+                for path in paths {
+                    plan.conflicts.push(PlannedConflict {
+                        path: local_path(&path),
+                        local: format!("revision {}", local.revision),
+                        remote: format!(
+                            "revision {} by {} at {}",
+                            head.revision, head.device_id, head.timestamp
+                        ),
+                        resolution: SyncConflictResolution::Manual,
+                    });
+                }
+            }
+        }
+    }
+    Ok(plan.finish())
+}
+
+fn finish_locked<T>(
+    sync_result: Result<T, String>,
+    unlock_result: Result<(), String>,
+) -> Result<T, String> {
+    match (sync_result, unlock_result) {
+        (Ok(result), Ok(())) => Ok(result),
+        (Err(error), Ok(())) => Err(error),
+        (Ok(_), Err(unlock_error)) => Err(format!(
+            "instance sync completed, but failed to release remote lock: {}",
+            unlock_error
+        )),
+        (Err(error), Err(unlock_error)) => Err(format!(
+            "{}; additionally failed to release remote lock: {}",
+            error, unlock_error
+        )),
+    }
+}
+
+pub(crate) async fn sync_instances<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &WebDavSyncConfig,
+    confirmed_plan: Option<&str>,
+) -> Result<WebDavInstanceSyncResult, String> {
+    let device_id = local_store::ensure_device_id(app, &config.device_id)?;
+    let mut store = RemoteStore::for_sync_config(config)?;
+    ensure_instance_layout(&store).await?;
+    store.lock(INSTANCES_DIR).await?;
+    let sync_result = sync_instances_locked(app, &store, &device_id, confirmed_plan).await;
+    let unlock_result = store.unlock(INSTANCES_DIR).await;
+    finish_locked(sync_result, unlock_result)
+}
+
+async fn sync_instances_locked<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+    device_id: &str,
+    confirmed_plan: Option<&str>,
+) -> Result<WebDavInstanceSyncResult, String> {
+    if confirmed_plan.is_some() {
+        let plan = plan_instances(app, store, device_id).await?;
+        plan::ensure_plan_confirmed(&plan, confirmed_plan)?;
+    }
+    let mut instances = Vec::new();
+    let mut enabled_ids = HashSet::new();
+    for state in list_instance_sync_states(app)?
+        .into_iter()
+        .filter(|state| state.enabled)
+    {
+        let instance_id = state.instance_id.clone();
+        enabled_ids.insert(instance_id.clone());
+        match sync_instance(app, store, device_id, state, None).await {
+            Ok(result) => instances.push(result),
+            Err(error) => {
+                log::warn!("Instance sync: {instance_id} failed: {error}");
+                let mut failed = outcome(&instance_id, InstanceSyncAction::Failed);
+                failed.error = Some(error);
+                instances.push(failed);
+            }
+        }
+    }
+    let remote_only_instances = store
+        .list(INSTANCES_DIR)
+        .await?
+        .into_iter()
+        .filter(|instance_id| !enabled_ids.contains(instance_id))
+        .collect();
+    Ok(WebDavInstanceSyncResult {
+        remote_root: store.describe(INSTANCES_DIR),
+        instances,
+        remote_only_instances,
+    })
+}
+
+pub(crate) async fn resolve_instance_conflict<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &WebDavSyncConfig,
+    instance_id: &str,
+    choice: InstanceConflictChoice,
+) -> Result<InstanceSyncOutcome, String> {
+    let device_id = local_store::ensure_device_id(app, &config.device_id)?;
+    let state = read_state(app, instance_id)?;
+    let mut store = RemoteStore::for_sync_config(config)?;
+    ensure_instance_layout(&store).await?;
+    store.lock(INSTANCES_DIR).await?;
+    let sync_result = sync_instance(app, &store, &device_id, state, Some(choice)).await;
+    let unlock_result = store.unlock(INSTANCES_DIR).await;
+    finish_locked(sync_result, unlock_result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(files: &[(&str, &str)], mods: &[(&str, &str)]) -> InstanceSyncRevision {
+        let files = files
+            .iter()
+            .map(|(path, sha1)| InstanceSyncFile {
+                path: path.to_string(),
+                sha1: sha1.to_string(),
+                size: 1,
+            })
+            .collect::<Vec<_>>();
+        let mods = mods
+            .iter()
+            .map(|(file_name, file_id)| InstanceModReference {
+                file_name: file_name.to_string(),
+                platform: "modrinth".to_string(),
+                project_id: "p".to_string(),
+                file_id: file_id.to_string(),
+                sha1: String::new(),
+            })
+            .collect::<Vec<_>>();
+        InstanceSyncRevision {
+            op_id: "op".to_string(),
+            instance_id: "demo".to_string(),
+            timestamp: 1,
+            device_id: "device".to_string(),
+            parent_revision: None,
+            revision: revision_hash(&files, &mods),
+            files,
+            mods,
+        }
+    }
+
+    #[test]
+    fn decides_direction_from_base_revision() {
+        let base = revision(&[("options.txt", "a")], &[("sodium.jar", "1")]);
+        let local_edit = revision(&[("options.txt", "b")], &[("sodium.jar", "1")]);
+        let remote_edit = revision(&[("options.txt", "a")], &[("sodium.jar", "2")]);
+
+        assert_eq!(decide(&base, None, None), SyncDecision::Upload);
+        assert_eq!(
+            decide(&base, Some(&base), Some(&base)),
+            SyncDecision::Unchanged
+        );
+        assert_eq!(
+            decide(&local_edit, Some(&base), Some(&base)),
+            SyncDecision::Upload
+        );
+        assert_eq!(
+            decide(&base, Some(&base), Some(&remote_edit)),
+            SyncDecision::Download
+        );
+
+        let both_edit = revision(&[("options.txt", "c")], &[("sodium.jar", "1")]);
+        assert_eq!(
+            decide(&local_edit, Some(&base), Some(&both_edit)),
+            SyncDecision::Conflict(vec!["options.txt".to_string()])
+        );
+        assert_eq!(
+            decide(&local_edit, None, Some(&remote_edit)),
+            SyncDecision::Conflict(vec![
+                "mods/sodium.jar".to_string(),
+                "options.txt".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn merges_disjoint_changes_from_both_sides() {
+        let base = revision(&[("options.txt", "a")], &[("sodium.jar", "1")]);
+        let local_edit = revision(
+            &[("options.txt", "b"), ("config/local.toml", "l")],
+            &[("sodium.jar", "1")],
+        );
+        let remote_edit = revision(
+            &[("options.txt", "a")],
+            &[("sodium.jar", "2"), ("lithium.jar", "3")],
+        );
+
+        let SyncDecision::Merge(merged) = decide(&local_edit, Some(&base), Some(&remote_edit))
+        else {
+            panic!("disjoint changes should merge");
+        };
+        let expected = revision(
+            &[("config/local.toml", "l"), ("options.txt", "b")],
+            &[("lithium.jar", "3"), ("sodium.jar", "2")],
+        );
+        assert_eq!(merged.files, expected.files);
+        assert_eq!(merged.mods, expected.mods);
+        assert_eq!(merged.revision, expected.revision);
+        assert_eq!(
+            merged.parent_revision.as_deref(),
+            Some(remote_edit.revision.as_str())
+        );
+
+        // 远端删除、本地未改动的文件在合并结果中同样被删除
+        let remote_delete = revision(&[], &[("sodium.jar", "1")]);
+        let local_mod = revision(&[("options.txt", "a")], &[("sodium.jar", "2")]);
+        let SyncDecision::Merge(merged) = decide(&local_mod, Some(&base), Some(&remote_delete))
+        else {
+            panic!("disjoint changes should merge");
+        };
+        assert!(merged.files.is_empty());
+        assert_eq!(merged.mods, local_mod.mods);
+    }
+
+    #[test]
+    fn mod_reference_only_carries_sha1_digests() {
+        let entry = |algorithm: &str| {
+            serde_json::from_value::<ModManifestEntry>(serde_json::json!({
+                "source": {
+                    "kind": "launcherDownload",
+                    "platform": "modrinth",
+                    "projectId": "AANobbMI",
+                    "fileId": "4xBqJEgS"
+                },
+                "hash": { "algorithm": algorithm, "value": "ABCDEF" }
+            }))
+            .unwrap()
+        };
+
+        let reference = mod_reference("sodium.jar", &entry("sha1")).unwrap();
+        assert_eq!(reference.sha1, "abcdef");
+        let reference = mod_reference("sodium.jar", &entry("sha512")).unwrap();
+        assert_eq!(reference.platform, "modrinth");
+        assert_eq!(reference.file_id, "4xBqJEgS");
+        assert!(reference.sha1.is_empty());
+    }
+
+    #[test]
+    fn keeps_device_specific_instance_fields() {
+        let local = br#"{"name":"Old","java":"/opt/java","memory":4096,"playTime":12}"#;
+        let remote = br#"{"name":"New","java":"C:\\java","loader":"fabric"}"#;
+
+        let sanitized = sanitize_instance_config(remote).unwrap();
+        let sanitized = serde_json::from_slice::<serde_json::Value>(&sanitized).unwrap();
+        assert!(sanitized.get("java").is_none());
+
+        let merged = merge_instance_config(local, remote).unwrap();
+        let merged = serde_json::from_slice::<serde_json::Value>(&merged).unwrap();
+        assert_eq!(merged["name"], "New");
+        assert_eq!(merged["loader"], "fabric");
+        assert_eq!(merged["java"], "/opt/java");
+        assert_eq!(merged["memory"], 4096);
+        assert_eq!(merged["playTime"], 12);
+    }
+
+    #[test]
+    fn only_tracks_whitelisted_relative_paths() {
+        assert!(is_tracked_path("instance.json"));
+        assert!(is_tracked_path("options.txt"));
+        assert!(is_tracked_path("config/sodium-options.json"));
+        assert!(!is_tracked_path("config/../saves/level.dat"));
+        assert!(!is_tracked_path("mods/sodium.jar"));
+        assert!(!is_tracked_path("config\\x.toml"));
+        assert!(!is_safe_mod_file_name("../evil.jar"));
+        assert!(is_safe_mod_file_name("sodium-0.5.jar"));
+    }
+}
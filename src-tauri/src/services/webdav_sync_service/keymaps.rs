@@ -1,31 +1,24 @@
 use crate::domain::library::WebDavSyncConfig;
 use crate::services::config_service::ConfigService;
-use reqwest::{Client, Method};
+use crate::services::remote_store::RemoteStore;
 use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 use tauri::{AppHandle, Runtime};
 
 use super::constants::{KEYBOARD_DIR, KEYBOARD_USER_DIR};
-use super::remote;
+use super::plan::WebDavSyncPlan;
 
 pub(crate) async fn sync_keyboard_profiles<R: Runtime>(
     app: &AppHandle<R>,
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
 ) -> Result<(), String> {
     // 1. Ensure remote collections exist
-    remote::ensure_collection(client, config, KEYBOARD_DIR).await?;
-    remote::ensure_collection(client, config, KEYBOARD_USER_DIR).await?;
+    store.ensure_dir(KEYBOARD_DIR).await?;
+    store.ensure_dir(KEYBOARD_USER_DIR).await?;
 
     // 2. Resolve local user keymap profiles directory
-    let base_path = ConfigService::get_base_path(app)
-        .map_err(|error| error.to_string())?
-        .ok_or_else(|| "base path is not configured".to_string())?;
-    let local_dir = PathBuf::from(&base_path)
-        .join("config")
-        .join("keyboard")
-        .join("user");
+    let local_dir = local_keymaps_dir(app)?;
 
     // 3. Ensure local directory exists
     if !local_dir.exists() {
@@ -45,7 +38,7 @@ pub(crate) async fn sync_keyboard_profiles<R: Runtime>(
     }
 
     // 5. Scan remote profiles
-    let remote_files = match super::library::list_files_in_dir(client, config, KEYBOARD_USER_DIR).await {
+    let remote_files = match store.list(KEYBOARD_USER_DIR).await {
         Ok(files) => files,
         Err(err) => {
             log::warn!("Failed to list remote keyboard profiles: {}", err);
@@ -75,17 +68,74 @@ pub(crate) async fn sync_keyboard_profiles<R: Runtime>(
         let has_remote = remote_files.contains(&file_name);
 
         if has_local && !has_remote {
-            upload_keymap_file(app, client, config, &local_path, &remote_path).await?;
+            upload_keymap_file(app, store, &local_path, &remote_path).await?;
         } else if !has_local && has_remote {
-            download_keymap_file(client, config, &local_path, &remote_path).await?;
+            download_keymap_file(store, &local_path, &remote_path).await?;
         } else if has_local && has_remote {
             let local_updated_at = read_local_profile_updated_at(&local_path)?;
-            let remote_updated_at = read_remote_profile_updated_at(client, config, &remote_path).await?;
+            let remote_updated_at = read_remote_profile_updated_at(store, &remote_path).await?;
 
             if local_updated_at > remote_updated_at {
-                upload_keymap_file(app, client, config, &local_path, &remote_path).await?;
+                upload_keymap_file(app, store, &local_path, &remote_path).await?;
             } else if remote_updated_at > local_updated_at {
-                download_keymap_file(client, config, &local_path, &remote_path).await?;
+                download_keymap_file(store, &local_path, &remote_path).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn local_keymaps_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let base_path = ConfigService::get_base_path(app)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| "base path is not configured".to_string())?;
+    Ok(PathBuf::from(&base_path)
+        .join("config")
+        .join("keyboard")
+        .join("user"))
+}
+
+/// 预演键位同步：比较方式与 `sync_keyboard_profiles` 相同，只读取不写入。
+pub(crate) async fn plan_keyboard_profiles<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+    plan: &mut WebDavSyncPlan,
+) -> Result<(), String> {
+    let local_dir = local_keymaps_dir(app)?;
+    let local_files = fs::read_dir(&local_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json")
+                })
+                .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+                .collect::<HashSet<_>>()
+        })
+        .unwrap_or_default();
+    let remote_files = store
+        .list(KEYBOARD_USER_DIR)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| name.ends_with(".json"))
+        .collect::<HashSet<_>>();
+
+    for file_name in local_files.union(&remote_files) {
+        let remote_path = format!("{}/{}", KEYBOARD_USER_DIR, file_name);
+        match (local_files.contains(file_name), remote_files.contains(file_name)) {
+            (true, false) => plan.uploads.push(remote_path),
+            (false, true) => plan.downloads.push(remote_path),
+            _ => {
+                let local_updated_at = read_local_profile_updated_at(&local_dir.join(file_name))?;
+                let remote_updated_at = read_remote_profile_updated_at(store, &remote_path).await?;
+                if local_updated_at > remote_updated_at {
+                    plan.uploads.push(remote_path);
+                } else if remote_updated_at > local_updated_at {
+                    plan.downloads.push(remote_path);
+                }
             }
         }
     }
@@ -104,24 +154,15 @@ fn read_local_profile_updated_at(path: &PathBuf) -> Result<String, String> {
 }
 
 async fn read_remote_profile_updated_at(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     remote_path: &str,
 ) -> Result<String, String> {
-    let response = remote::authorized_request(client, config, Method::GET, remote_path)
-        .send()
+    let bytes = store
+        .get(remote_path)
         .await
-        .map_err(|e| format!("failed to fetch remote keymap file header: {e}"))?;
+        .map_err(|e| format!("failed to fetch remote keymap file header: {e}"))?
+        .ok_or_else(|| format!("remote keymap file is missing: {remote_path}"))?;
 
-    if !response.status().is_success() {
-        return Err(format!("failed to fetch remote keymap file: HTTP {}", response.status()));
-    }
-
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("failed to read remote keymap file bytes: {e}"))?;
-    
     let header: serde_json::Value = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
     let updated_at = header.get("updatedAt")
         .and_then(|v| v.as_str())
@@ -133,48 +174,31 @@ async fn read_remote_profile_updated_at(
 
 async fn upload_keymap_file<R: Runtime>(
     app: &AppHandle<R>,
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     local_path: &PathBuf,
     remote_path: &str,
 ) -> Result<(), String> {
-    log::info!("WebDAV keymap sync: Uploading keymap {:?} to {}", local_path, remote_path);
+    let _ = app;
+    log::info!("Keymap sync: Uploading keymap {:?} to {}", local_path, remote_path);
     let bytes_to_upload = fs::read(local_path).map_err(|e| e.to_string())?;
     
-    let response = remote::authorized_request(client, config, Method::PUT, remote_path)
-        .header(reqwest::header::CONTENT_TYPE, "application/json")
-        .body(bytes_to_upload)
-        .send()
+    store
+        .put(remote_path, bytes_to_upload, "application/json")
         .await
-        .map_err(|e| format!("failed to upload keymap file {remote_path}: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!("failed to upload keymap file {remote_path}: HTTP {}", response.status()));
-    }
-    
-    Ok(())
+        .map_err(|e| format!("failed to upload keymap file {remote_path}: {e}"))
 }
 
 async fn download_keymap_file(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     local_path: &PathBuf,
     remote_path: &str,
 ) -> Result<(), String> {
-    log::info!("WebDAV keymap sync: Downloading keymap from {} to {:?}", remote_path, local_path);
-    let response = remote::authorized_request(client, config, Method::GET, remote_path)
-        .send()
+    log::info!("Keymap sync: Downloading keymap from {} to {:?}", remote_path, local_path);
+    let bytes = store
+        .get(remote_path)
         .await
-        .map_err(|e| format!("failed to download keymap file {remote_path}: {e}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!("failed to download keymap file {remote_path}: HTTP {}", response.status()));
-    }
-
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("failed to read downloaded keymap file bytes: {e}"))?;
+        .map_err(|e| format!("failed to download keymap file {remote_path}: {e}"))?
+        .ok_or_else(|| format!("remote keymap file is missing: {remote_path}"))?;
 
     if let Some(parent) = local_path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
@@ -188,9 +212,7 @@ pub(crate) async fn delete_webdav_keymap(
     config: &WebDavSyncConfig,
     filename: &str,
 ) -> Result<(), String> {
-    let client = Client::builder()
-        .build()
-        .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
+    let store = RemoteStore::for_sync_config(config)?;
 
     let safe_filename = filename
         .chars()
@@ -198,18 +220,8 @@ pub(crate) async fn delete_webdav_keymap(
         .collect::<String>();
     let remote_path = format!("{}/{}.json", KEYBOARD_USER_DIR, safe_filename);
 
-    let response = remote::authorized_request(&client, config, Method::DELETE, &remote_path)
-        .send()
+    store
+        .delete(&remote_path)
         .await
-        .map_err(|e| format!("failed to delete remote keymap file {remote_path}: {e}"))?;
-
-    let status = response.status();
-    if status.is_success() || status == reqwest::StatusCode::NOT_FOUND {
-        Ok(())
-    } else {
-        Err(format!(
-            "failed to delete remote keymap file {remote_path}: HTTP {status}"
-        ))
-    }
+        .map_err(|e| format!("failed to delete remote keymap file {remote_path}: {e}"))
 }
-
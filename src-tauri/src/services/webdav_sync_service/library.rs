@@ -1,7 +1,7 @@
-use crate::domain::library::WebDavSyncConfig;
+use crate::domain::library::StarredItem;
 use crate::services::config_service::ConfigService;
 use crate::services::library_service::LibraryService;
-use reqwest::{Client, Method, StatusCode};
+use crate::services::remote_store::RemoteStore;
 use sqlx::SqlitePool;
 use std::collections::HashSet;
 use std::fs::{self, File};
@@ -11,61 +11,10 @@ use tauri::{AppHandle, Runtime};
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
 use zip::{ZipArchive, ZipWriter};
-use regex::Regex;
 
 use super::constants::{LIBRARY_DIR, LIBRARY_RESOURCEPACKS_DIR, LIBRARY_SHADERS_DIR};
-use super::{paths, remote};
-
-pub(crate) async fn list_files_in_dir(
-    client: &Client,
-    config: &WebDavSyncConfig,
-    remote_dir: &str,
-) -> Result<Vec<String>, String> {
-    let method = Method::from_bytes(b"PROPFIND")
-        .map_err(|error| format!("invalid PROPFIND method: {error}"))?;
-    let response = remote::authorized_request(client, config, method, remote_dir)
-        .header("Depth", "1")
-        .send()
-        .await
-        .map_err(|error| format!("failed to list WebDAV files in {remote_dir}: {error}"))?;
-
-    if response.status() == StatusCode::NOT_FOUND {
-        return Ok(Vec::new());
-    }
-    if !response.status().is_success() {
-        return Err(format!(
-            "failed to list WebDAV files in {remote_dir}: HTTP {}",
-            response.status()
-        ));
-    }
-
-    let body = response
-        .text()
-        .await
-        .map_err(|error| format!("failed to read WebDAV files listing: {error}"))?;
-    let href_pattern = Regex::new(r"(?i)<(?:[a-z0-9]+:)?href>([^<]+)</(?:[a-z0-9]+:)?href>")
-        .map_err(|error| error.to_string())?;
-
-    let mut file_names = Vec::new();
-    for captures in href_pattern.captures_iter(&body) {
-        if let Some(val) = captures.get(1) {
-            if let Ok(decoded) = urlencoding::decode(val.as_str()) {
-                let trimmed = decoded.trim_end_matches('/');
-                if trimmed.ends_with(remote_dir) {
-                    continue;
-                }
-                if let Some(segment) = trimmed.rsplit('/').next() {
-                    if !segment.is_empty() {
-                        file_names.push(segment.to_string());
-                    }
-                }
-            }
-        }
-    }
-    file_names.sort();
-    file_names.dedup();
-    Ok(file_names)
-}
+use super::paths;
+use super::plan::WebDavSyncPlan;
 
 fn zip_dir(src_dir: &Path, dst_zip: &Path) -> Result<(), String> {
     let file = File::create(dst_zip).map_err(|error| error.to_string())?;
@@ -155,26 +104,12 @@ fn scan_local_dir_names(dir: &Path) -> Result<Vec<String>, String> {
     Ok(names)
 }
 
-pub(crate) async fn sync_library_files<R: Runtime>(
-    app: &AppHandle<R>,
-    pool: &SqlitePool,
-    client: &Client,
-    config: &WebDavSyncConfig,
-) -> Result<(), String> {
-    // 1. Ensure remote layouts
-    remote::ensure_collection(client, config, LIBRARY_DIR).await?;
-    remote::ensure_collection(client, config, LIBRARY_SHADERS_DIR).await?;
-    remote::ensure_collection(client, config, LIBRARY_RESOURCEPACKS_DIR).await?;
-
-    // 2. Fetch active starred items from DB
-    let starred_items = LibraryService::get_starred_items(pool)
-        .await
-        .map_err(|e| format!("failed to query starred items: {e}"))?;
-
+/// 收藏里的自定义光影 / 资源包对应的文件名，分别返回光影与资源包两组。
+fn active_library_files(starred_items: &[StarredItem]) -> (HashSet<String>, HashSet<String>) {
     let mut active_shaders = HashSet::new();
     let mut active_resourcepacks = HashSet::new();
 
-    for item in &starred_items {
+    for item in starred_items {
         if item.source == "custom" {
             if let Ok(snapshot_val) = serde_json::from_str::<serde_json::Value>(&item.snapshot) {
                 if let Some(file_name) = snapshot_val.get("fileName").and_then(|v| v.as_str()) {
@@ -188,20 +123,89 @@ pub(crate) async fn sync_library_files<R: Runtime>(
         }
     }
 
-    // 3. Resolve local directories
+    (active_shaders, active_resourcepacks)
+}
+
+fn local_library_dirs<R: Runtime>(app: &AppHandle<R>) -> Result<(PathBuf, PathBuf), String> {
     let base_path = ConfigService::get_base_path(app)
         .map_err(|error| error.to_string())?
         .ok_or_else(|| "base path is not configured".to_string())?;
     let library_base = PathBuf::from(&base_path).join("shared_mods").join("library");
-    let local_shaders_dir = library_base.join("shaders");
-    let local_resourcepacks_dir = library_base.join("resourcepacks");
+    Ok((library_base.join("shaders"), library_base.join("resourcepacks")))
+}
+
+/// 预演资源库同步：只读取本地目录与远端列表，按与 `sync_folder_files` 相同的规则记入计划。
+pub(crate) async fn plan_library_files<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+    merged_items: &[StarredItem],
+    plan: &mut WebDavSyncPlan,
+) -> Result<(), String> {
+    let (active_shaders, active_resourcepacks) = active_library_files(merged_items);
+    let (local_shaders_dir, local_resourcepacks_dir) = local_library_dirs(app)?;
+
+    for (local_dir, local_label, remote_dir, active_set) in [
+        (
+            &local_shaders_dir,
+            "shared_mods/library/shaders",
+            LIBRARY_SHADERS_DIR,
+            &active_shaders,
+        ),
+        (
+            &local_resourcepacks_dir,
+            "shared_mods/library/resourcepacks",
+            LIBRARY_RESOURCEPACKS_DIR,
+            &active_resourcepacks,
+        ),
+    ] {
+        let local_set: HashSet<String> = scan_local_dir_names(local_dir)?.into_iter().collect();
+        let remote_set: HashSet<String> = store.list(remote_dir).await?.into_iter().collect();
+
+        for file_name in active_set {
+            let has_local = local_set.contains(file_name);
+            let has_remote = remote_set.contains(file_name);
+            if has_local && !has_remote {
+                plan.uploads.push(format!("{remote_dir}/{file_name}"));
+            } else if !has_local && has_remote {
+                plan.downloads.push(format!("{remote_dir}/{file_name}"));
+            }
+        }
+        for file_name in local_set.difference(active_set) {
+            plan.local_deletions.push(format!("{local_label}/{file_name}"));
+        }
+        for file_name in remote_set.difference(active_set) {
+            plan.remote_deletions.push(format!("{remote_dir}/{file_name}"));
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn sync_library_files<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    store: &RemoteStore,
+) -> Result<(), String> {
+    // 1. Ensure remote layouts
+    store.ensure_dir(LIBRARY_DIR).await?;
+    store.ensure_dir(LIBRARY_SHADERS_DIR).await?;
+    store.ensure_dir(LIBRARY_RESOURCEPACKS_DIR).await?;
+
+    // 2. Fetch active starred items from DB
+    let starred_items = LibraryService::get_starred_items(pool)
+        .await
+        .map_err(|e| format!("failed to query starred items: {e}"))?;
+    let (active_shaders, active_resourcepacks) = active_library_files(&starred_items);
+
+    // 3. Resolve local directories
+    let (local_shaders_dir, local_resourcepacks_dir) = local_library_dirs(app)?;
 
     fs::create_dir_all(&local_shaders_dir).map_err(|e| e.to_string())?;
     fs::create_dir_all(&local_resourcepacks_dir).map_err(|e| e.to_string())?;
 
-    // 4. Fetch WebDAV remote files
-    let remote_shaders = list_files_in_dir(client, config, LIBRARY_SHADERS_DIR).await?;
-    let remote_resourcepacks = list_files_in_dir(client, config, LIBRARY_RESOURCEPACKS_DIR).await?;
+    // 4. Fetch remote files
+    let remote_shaders = store.list(LIBRARY_SHADERS_DIR).await?;
+    let remote_resourcepacks = store.list(LIBRARY_RESOURCEPACKS_DIR).await?;
 
     let remote_shaders_set: HashSet<String> = remote_shaders.into_iter().collect();
     let remote_resourcepacks_set: HashSet<String> = remote_resourcepacks.into_iter().collect();
@@ -216,8 +220,7 @@ pub(crate) async fn sync_library_files<R: Runtime>(
     // 6. Perform dual-sync for Shaders
     sync_folder_files(
         app,
-        client,
-        config,
+        store,
         &local_shaders_dir,
         LIBRARY_SHADERS_DIR,
         &active_shaders,
@@ -228,8 +231,7 @@ pub(crate) async fn sync_library_files<R: Runtime>(
     // 7. Perform dual-sync for Resource Packs
     sync_folder_files(
         app,
-        client,
-        config,
+        store,
         &local_resourcepacks_dir,
         LIBRARY_RESOURCEPACKS_DIR,
         &active_resourcepacks,
@@ -242,8 +244,7 @@ pub(crate) async fn sync_library_files<R: Runtime>(
 
 async fn sync_folder_files<R: Runtime>(
     app: &AppHandle<R>,
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     local_dir: &Path,
     remote_dir: &str,
     active_set: &HashSet<String>,
@@ -260,7 +261,7 @@ async fn sync_folder_files<R: Runtime>(
 
         if has_local && !has_remote {
             // Upload to remote
-            log::info!("WebDAV library sync: Uploading {} to {}", file_name, remote_path);
+            log::info!("Library sync: Uploading {} to {}", file_name, remote_path);
             let bytes_to_upload = if local_path.is_dir() {
                 // Zip it first into a temp file
                 let temp_zip = paths::sync_root(app)?.join(format!("lib-upload-{}.zip", uuid::Uuid::new_v4()));
@@ -272,33 +273,18 @@ async fn sync_folder_files<R: Runtime>(
                 fs::read(&local_path).map_err(|e| e.to_string())?
             };
 
-            let response = remote::authorized_request(client, config, Method::PUT, &remote_path)
-                .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
-                .body(bytes_to_upload)
-                .send()
+            store
+                .put(&remote_path, bytes_to_upload, "application/octet-stream")
                 .await
                 .map_err(|e| format!("failed to upload {file_name}: {e}"))?;
-
-            if !response.status().is_success() {
-                return Err(format!("failed to upload {file_name}: HTTP {}", response.status()));
-            }
         } else if !has_local && has_remote {
             // Download from remote
-            log::info!("WebDAV library sync: Downloading {} from {}", file_name, remote_path);
-            let response = remote::authorized_request(client, config, Method::GET, &remote_path)
-                .send()
+            log::info!("Library sync: Downloading {} from {}", file_name, remote_path);
+            let bytes = store
+                .get(&remote_path)
                 .await
-                .map_err(|e| format!("failed to download {file_name}: {e}"))?;
-
-            if !response.status().is_success() {
-                return Err(format!("failed to download {file_name}: HTTP {}", response.status()));
-            }
-
-            let bytes = response
-                .bytes()
-                .await
-                .map_err(|e| format!("failed to read downloaded bytes for {file_name}: {e}"))?
-                .to_vec();
+                .map_err(|e| format!("failed to download {file_name}: {e}"))?
+                .ok_or_else(|| format!("failed to download {file_name}: remote file is missing"))?;
 
             // Determine if directory or file based on filename ending with .zip
             let is_zip_file = file_name.to_lowercase().ends_with(".zip");
@@ -319,7 +305,7 @@ async fn sync_folder_files<R: Runtime>(
     for file_name in local_set {
         if !active_set.contains(file_name) {
             let local_path = local_dir.join(file_name);
-            log::info!("WebDAV library sync: Cleaning up local path {:?}", local_path);
+            log::info!("Library sync: Cleaning up local path {:?}", local_path);
             if local_path.is_dir() {
                 let _ = fs::remove_dir_all(&local_path);
             } else {
@@ -328,19 +314,15 @@ async fn sync_folder_files<R: Runtime>(
         }
     }
 
-    // C. Clean up remote files on WebDAV that are no longer starred anywhere
+    // C. Clean up remote files that are no longer starred anywhere
     for file_name in remote_set {
         if !active_set.contains(file_name) {
             let remote_path = format!("{remote_dir}/{file_name}");
-            log::info!("WebDAV library sync: Cleaning up remote WebDAV path {}", remote_path);
-            let response = remote::authorized_request(client, config, Method::DELETE, &remote_path)
-                .send()
+            log::info!("Library sync: Cleaning up remote path {}", remote_path);
+            store
+                .delete(&remote_path)
                 .await
                 .map_err(|e| format!("failed to delete remote file {remote_path}: {e}"))?;
-
-            if response.status() != StatusCode::NOT_FOUND && !response.status().is_success() {
-                return Err(format!("failed to delete remote file {file_name}: HTTP {}", response.status()));
-            }
         }
     }
 
@@ -52,6 +52,19 @@ pub(crate) fn ensure_device_id<R: Runtime>(
     Ok(device_id)
 }
 
+/// 只读版本的 `ensure_device_id`，供同步预演使用，设备文件不存在时也不会创建。
+pub(crate) fn peek_device_id<R: Runtime>(
+    app: &AppHandle<R>,
+    preferred_device_id: &str,
+) -> Result<String, String> {
+    let device = fs::read_to_string(paths::device_path(app)?)
+        .ok()
+        .and_then(|content| serde_json::from_str::<DeviceFile>(&content).ok())
+        .map(|device| device.device_id)
+        .filter(|device_id| !device_id.trim().is_empty());
+    Ok(device.unwrap_or_else(|| preferred_device_id.trim().to_string()))
+}
+
 pub(crate) fn write_operation<R: Runtime>(
     app: &AppHandle<R>,
     operation: &FavoriteOperation,
@@ -1,38 +1,28 @@
-use crate::domain::library::{FavoriteOperation, FavoriteOperationAction, WebDavSyncConfig};
-use reqwest::{Client, Method, StatusCode};
+use crate::domain::library::{FavoriteOperation, FavoriteOperationAction};
+use crate::services::remote_store::RemoteStore;
 use std::collections::HashSet;
 use tauri::{AppHandle, Runtime};
 
-use super::constants::LEGACY_FAVORITES_FILE;
+use super::constants::{
+    INSTANCES_DIR, KEYBOARD_USER_DIR, LEGACY_FAVORITES_FILE, LIBRARY_RESOURCEPACKS_DIR,
+    LIBRARY_SHADERS_DIR, OPERATIONS_DIR, SAVE_BACKUPS_DATA_DIR, SAVE_BACKUPS_MANIFEST_PATH,
+    SKINS_ARCHIVE_PATH, SKINS_MANIFEST_PATH, SNAPSHOT_PATH,
+};
 use super::local_store;
 use super::models::LegacyFavoriteSyncDocument;
-use super::remote;
 use super::util;
 
 pub(crate) async fn migrate_legacy_remote_document_if_present<R: Runtime>(
     app: &AppHandle<R>,
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
 ) -> Result<(), String> {
-    let response = remote::authorized_request(client, config, Method::GET, LEGACY_FAVORITES_FILE)
-        .send()
+    let Some(document) = store
+        .get_json::<LegacyFavoriteSyncDocument>(LEGACY_FAVORITES_FILE)
         .await
-        .map_err(|error| format!("failed to read legacy WebDAV favorites: {error}"))?;
-
-    if response.status() == StatusCode::NOT_FOUND {
+        .map_err(|error| format!("failed to read legacy remote favorites: {error}"))?
+    else {
         return Ok(());
-    }
-    if !response.status().is_success() {
-        return Err(format!(
-            "failed to read legacy WebDAV favorites: HTTP {}",
-            response.status()
-        ));
-    }
-
-    let document = response
-        .json::<LegacyFavoriteSyncDocument>()
-        .await
-        .map_err(|error| format!("invalid legacy WebDAV favorites document: {error}"))?;
+    };
     let existing_op_ids = local_store::load_operations(app)?
         .into_iter()
         .map(|operation| operation.op_id)
@@ -80,3 +70,94 @@ pub(crate) async fn migrate_legacy_remote_document_if_present<R: Runtime>(
 
     Ok(())
 }
+
+async fn encrypt_plaintext_dir(store: &RemoteStore, dir: &str) -> Result<usize, String> {
+    let mut migrated = 0usize;
+    for name in store.list_plaintext(dir).await? {
+        if store
+            .encrypt_plaintext_file(&format!("{dir}/{name}"))
+            .await?
+        {
+            migrated += 1;
+        }
+    }
+    Ok(migrated)
+}
+
+/// 开启端到端加密后，把收藏、资源库与键位同步在加密前上传的明文文件改写为密文。
+/// 每个文件加密写入后立即删除明文，中途失败时下次同步会从剩下的文件继续。
+pub(crate) async fn encrypt_plaintext_favorites(store: &RemoteStore) -> Result<usize, String> {
+    if !store.is_encrypted() {
+        return Ok(0);
+    }
+    let mut migrated = 0usize;
+    for dir in [
+        OPERATIONS_DIR,
+        LIBRARY_SHADERS_DIR,
+        LIBRARY_RESOURCEPACKS_DIR,
+        KEYBOARD_USER_DIR,
+    ] {
+        migrated += encrypt_plaintext_dir(store, dir).await?;
+    }
+    for path in [SNAPSHOT_PATH, LEGACY_FAVORITES_FILE] {
+        migrated += usize::from(store.encrypt_plaintext_file(path).await?);
+    }
+    Ok(migrated)
+}
+
+pub(crate) async fn encrypt_plaintext_skins(store: &RemoteStore) -> Result<usize, String> {
+    if !store.is_encrypted() {
+        return Ok(0);
+    }
+    let mut migrated = 0usize;
+    for path in [SKINS_ARCHIVE_PATH, SKINS_MANIFEST_PATH] {
+        migrated += usize::from(store.encrypt_plaintext_file(path).await?);
+    }
+    Ok(migrated)
+}
+
+/// 存档备份按明文清单逐个迁移数据文件，清单最后迁移：清单还是明文时，
+/// 加密后的同步逻辑看不到远端备份，不会读到只迁移了一半的数据。
+pub(crate) async fn encrypt_plaintext_save_backups(store: &RemoteStore) -> Result<usize, String> {
+    if !store.is_encrypted() {
+        return Ok(0);
+    }
+    let Some(manifest) = store
+        .get_plaintext_json::<serde_json::Value>(SAVE_BACKUPS_MANIFEST_PATH)
+        .await?
+    else {
+        return Ok(0);
+    };
+    let mut migrated = 0usize;
+    for relative_path in manifest
+        .get("files")
+        .and_then(|files| files.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|file| file.get("relativePath").and_then(|value| value.as_str()))
+    {
+        let remote_path = format!("{SAVE_BACKUPS_DATA_DIR}/{relative_path}");
+        migrated += usize::from(store.encrypt_plaintext_file(&remote_path).await?);
+    }
+    migrated += usize::from(store.encrypt_plaintext_file(SAVE_BACKUPS_MANIFEST_PATH).await?);
+    Ok(migrated)
+}
+
+/// 实例同步目录下每个实例先迁移 blob 与操作记录，快照最后迁移，理由同存档备份清单。
+pub(crate) async fn encrypt_plaintext_instances(store: &RemoteStore) -> Result<usize, String> {
+    if !store.is_encrypted() {
+        return Ok(0);
+    }
+    let mut migrated = 0usize;
+    for instance_id in store.list_plaintext(INSTANCES_DIR).await? {
+        let instance_dir = format!("{INSTANCES_DIR}/{instance_id}");
+        migrated += encrypt_plaintext_dir(store, &format!("{instance_dir}/blobs")).await?;
+        migrated += encrypt_plaintext_dir(store, &format!("{instance_dir}/operations")).await?;
+        migrated += usize::from(
+            store
+                .encrypt_plaintext_file(&format!("{instance_dir}/snapshot.json"))
+                .await?,
+        );
+    }
+    Ok(migrated)
+}
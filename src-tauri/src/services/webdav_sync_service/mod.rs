@@ -1,10 +1,12 @@
 mod compaction;
 mod constants;
+mod instances;
 mod library;
 mod local_store;
 mod migration;
 mod models;
 mod paths;
+mod plan;
 mod remote;
 mod save_backups;
 mod skins;
@@ -18,15 +20,22 @@ use crate::domain::library::{
     FavoriteOperation, FavoriteOperationAction, StarredItem, WebDavFavoriteSyncResult,
     WebDavSaveBackupSyncResult, WebDavSkinSyncResult, WebDavSyncConfig,
 };
-use reqwest::Client;
+use crate::services::remote_store::RemoteStore;
+pub use instances::{
+    InstanceConflictChoice, InstanceSyncOutcome, InstanceSyncState, WebDavInstanceSyncResult,
+};
+pub use plan::{
+    PlannedConflict, PlannedTombstone, SyncConflictResolution, WebDavSyncPlan, WebDavSyncScope,
+};
 pub use save_backups::{
     WebDavRemoteSaveBackup, WebDavSaveBackupDeleteResult, WebDavSaveBackupDownloadResult,
+    WebDavSaveBackupPruneResult,
 };
 use sqlx::SqlitePool;
 use std::collections::HashSet;
 use tauri::{AppHandle, Runtime};
 
-use constants::{REMOTE_ROOT, SNAPSHOT_MAX_AGE_MILLIS, SNAPSHOT_OPERATION_THRESHOLD};
+use constants::REMOTE_ROOT;
 
 pub struct WebDavSyncService;
 
@@ -73,19 +82,25 @@ impl WebDavSyncService {
         app: &AppHandle<R>,
         pool: &SqlitePool,
         config: &WebDavSyncConfig,
+        confirmed_plan: Option<&str>,
     ) -> Result<WebDavFavoriteSyncResult, String> {
-        util::validate_base_url(&config.base_url)?;
+        let store = RemoteStore::for_sync_config(config)?;
         local_store::ensure_local_layout(app)?;
 
         let device_id = local_store::ensure_device_id(app, &config.device_id)?;
+        let planned_at = util::now_millis();
+        if confirmed_plan.is_some() {
+            let plan = plan::plan_favorites(app, pool, &store, &device_id, planned_at).await?;
+            plan::ensure_plan_confirmed(&plan, confirmed_plan)?;
+        }
         state::ensure_local_state_covered_by_operations(app, pool, &device_id).await?;
 
-        let client = Client::builder()
-            .build()
-            .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
-
-        let remote_created = remote::ensure_layout(&client, config).await?;
-        let remote_snapshot = remote::download_snapshot(&client, config).await?;
+        let remote_created = remote::ensure_layout(&store).await?;
+        let encrypted = migration::encrypt_plaintext_favorites(&store).await?;
+        if encrypted > 0 {
+            log::info!("WebDAV sync: encrypted {encrypted} plaintext remote files");
+        }
+        let remote_snapshot = remote::download_snapshot(&store).await?;
         let local_snapshot = local_store::read_snapshot(app)?;
         let mut sync_meta = local_store::read_sync_meta(app)?;
 
@@ -102,14 +117,14 @@ impl WebDavSyncService {
 
             if sync_meta.favorites.last_snapshot_timestamp < snapshot.last_timestamp {
                 sync_meta.favorites.last_snapshot_timestamp = snapshot.last_timestamp;
-                sync_meta.favorites.last_snapshot_at = util::now_millis();
+                sync_meta.favorites.last_snapshot_at = planned_at;
             }
         }
 
-        let mut remote_operation_files = remote::list_operation_files(&client, config).await?;
+        let mut remote_operation_files = remote::list_operation_files(&store).await?;
         if remote_operation_files.is_empty() {
-            migration::migrate_legacy_remote_document_if_present(app, &client, config).await?;
-            remote_operation_files = remote::list_operation_files(&client, config).await?;
+            migration::migrate_legacy_remote_document_if_present(app, &store).await?;
+            remote_operation_files = remote::list_operation_files(&store).await?;
         }
         let local_operation_files = local_store::list_operation_files(app)?;
 
@@ -127,7 +142,7 @@ impl WebDavSyncService {
             if remote_file_set.contains(&file_name) {
                 continue;
             }
-            remote::upload_operation_file(&client, config, app, &file_name).await?;
+            remote::upload_operation_file(&store, app, &file_name).await?;
             uploaded_operations += 1;
         }
 
@@ -136,35 +151,27 @@ impl WebDavSyncService {
             if local_file_set.contains(&file_name) {
                 continue;
             }
-            remote::download_operation_file(&client, config, app, &file_name).await?;
+            remote::download_operation_file(&store, app, &file_name).await?;
             downloaded_operations += 1;
         }
 
         let operations = local_store::load_operations(app)?;
         let winners = snapshot::resolve_latest_operations(selected_snapshot.as_ref(), &operations);
         let merged_favorites = state::apply_operation_state(pool, &winners).await?;
-        let newest_snapshot_timestamp = selected_snapshot
-            .as_ref()
-            .map(|snapshot| snapshot.last_timestamp)
-            .unwrap_or(0);
-        let operations_since_snapshot = operations
-            .iter()
-            .filter(|operation| operation.timestamp > newest_snapshot_timestamp)
-            .count();
-        let snapshot_age = util::now_millis().saturating_sub(sync_meta.favorites.last_snapshot_at);
-        let should_compact = selected_snapshot.is_none()
-            || operations_since_snapshot >= SNAPSHOT_OPERATION_THRESHOLD
-            || sync_meta.favorites.last_snapshot_at <= 0
-            || snapshot_age >= SNAPSHOT_MAX_AGE_MILLIS;
+        let should_compact = plan::should_compact_favorites(
+            selected_snapshot.as_ref(),
+            &operations,
+            sync_meta.favorites.last_snapshot_at,
+            planned_at,
+        );
 
         let mut snapshot_updated = false;
         let mut compacted_operations = 0usize;
         if should_compact {
             let snapshot = snapshot::write_local_snapshot(app, &winners)?;
-            remote::upload_snapshot(&client, config, &snapshot).await?;
+            remote::upload_snapshot(&store, &snapshot).await?;
             compacted_operations =
-                compaction::compact_operations(app, &client, config, snapshot.last_timestamp)
-                    .await?;
+                compaction::compact_operations(app, &store, snapshot.last_timestamp).await?;
             sync_meta.favorites.last_snapshot_at = util::now_millis();
             sync_meta.favorites.last_snapshot_timestamp = snapshot.last_timestamp;
             snapshot_updated = true;
@@ -174,18 +181,18 @@ impl WebDavSyncService {
                 .map(|remote| snapshot.last_timestamp > remote.last_timestamp)
                 .unwrap_or(true);
             if remote_is_older {
-                remote::upload_snapshot(&client, config, snapshot).await?;
+                remote::upload_snapshot(&store, snapshot).await?;
             }
         }
         
-        library::sync_library_files(app, pool, &client, config).await?;
-        keymaps::sync_keyboard_profiles(app, &client, config).await?;
+        library::sync_library_files(app, pool, &store).await?;
+        keymaps::sync_keyboard_profiles(app, &store).await?;
 
         sync_meta.favorites.last_sync_at = util::now_millis();
         local_store::write_sync_meta(app, &sync_meta)?;
 
         Ok(WebDavFavoriteSyncResult {
-            remote_root: util::join_remote_url(&config.base_url, REMOTE_ROOT),
+            remote_root: store.describe(REMOTE_ROOT),
             remote_created,
             uploaded_operations,
             downloaded_operations,
@@ -196,18 +203,43 @@ impl WebDavSyncService {
         })
     }
 
+    /// 同步预演：用只读存储算出某一类同步将要做的事，本地与远端都不会被修改。
+    pub async fn plan_sync<R: Runtime>(
+        app: &AppHandle<R>,
+        pool: &SqlitePool,
+        config: &WebDavSyncConfig,
+        scope: WebDavSyncScope,
+    ) -> Result<WebDavSyncPlan, String> {
+        let store = RemoteStore::for_sync_config(config)?.into_read_only();
+        let device_id = local_store::peek_device_id(app, &config.device_id)?;
+        match scope {
+            WebDavSyncScope::Favorites => {
+                plan::plan_favorites(app, pool, &store, &device_id, util::now_millis()).await
+            }
+            WebDavSyncScope::Skins => skins::plan_skin_assets(app, &store).await,
+            WebDavSyncScope::SaveBackups => {
+                save_backups::plan_save_backups(app, config, &store).await
+            }
+            WebDavSyncScope::Instances => {
+                instances::plan_instances(app, &store, &device_id).await
+            }
+        }
+    }
+
     pub async fn sync_skin_assets<R: Runtime>(
         app: &AppHandle<R>,
         config: &WebDavSyncConfig,
+        confirmed_plan: Option<&str>,
     ) -> Result<WebDavSkinSyncResult, String> {
-        skins::sync_skin_assets(app, config).await
+        skins::sync_skin_assets(app, config, confirmed_plan).await
     }
 
     pub async fn sync_save_backups<R: Runtime>(
         app: &AppHandle<R>,
         config: &WebDavSyncConfig,
+        confirmed_plan: Option<&str>,
     ) -> Result<WebDavSaveBackupSyncResult, String> {
-        save_backups::sync_save_backups(app, config).await
+        save_backups::sync_save_backups(app, config, confirmed_plan).await
     }
 
     pub async fn list_remote_save_backups(
@@ -244,11 +276,49 @@ impl WebDavSyncService {
         save_backups::delete_remote_save_backup(config, backup_id).await
     }
 
+    pub async fn prune_remote_save_backups<R: Runtime>(
+        app: &AppHandle<R>,
+        config: &WebDavSyncConfig,
+    ) -> Result<WebDavSaveBackupPruneResult, String> {
+        save_backups::prune_remote_save_backups(app, config).await
+    }
+
     pub async fn delete_webdav_keymap(
         config: &WebDavSyncConfig,
         filename: &str,
     ) -> Result<(), String> {
         keymaps::delete_webdav_keymap(config, filename).await
     }
+
+    pub async fn sync_instances<R: Runtime>(
+        app: &AppHandle<R>,
+        config: &WebDavSyncConfig,
+        confirmed_plan: Option<&str>,
+    ) -> Result<WebDavInstanceSyncResult, String> {
+        instances::sync_instances(app, config, confirmed_plan).await
+    }
+
+    pub async fn resolve_instance_conflict<R: Runtime>(
+        app: &AppHandle<R>,
+        config: &WebDavSyncConfig,
+        instance_id: &str,
+        choice: InstanceConflictChoice,
+    ) -> Result<InstanceSyncOutcome, String> {
+        instances::resolve_instance_conflict(app, config, instance_id, choice).await
+    }
+
+    pub fn set_instance_sync_enabled<R: Runtime>(
+        app: &AppHandle<R>,
+        instance_id: &str,
+        enabled: bool,
+    ) -> Result<InstanceSyncState, String> {
+        instances::set_instance_sync_enabled(app, instance_id, enabled)
+    }
+
+    pub fn list_instance_sync_states<R: Runtime>(
+        app: &AppHandle<R>,
+    ) -> Result<Vec<InstanceSyncState>, String> {
+        instances::list_instance_sync_states(app)
+    }
 }
 
@@ -28,3 +28,7 @@ pub(crate) fn operations_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf,
 pub(crate) fn snapshot_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
     Ok(favorites_root(app)?.join("snapshot.json"))
 }
+
+pub(crate) fn instances_state_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    Ok(sync_root(app)?.join("instances"))
+}
@@ -0,0 +1,445 @@
+use crate::domain::library::{
+    FavoriteOperation, FavoriteOperationAction, FavoriteSnapshot, StarredItem,
+};
+use crate::services::library_service::LibraryService;
+use crate::services::remote_store::RemoteStore;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{
+    OPERATIONS_DIR, SNAPSHOT_MAX_AGE_MILLIS, SNAPSHOT_OPERATION_THRESHOLD, SNAPSHOT_PATH,
+};
+use super::{keymaps, library, local_store, remote, snapshot, state, util};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebDavSyncScope {
+    Favorites,
+    Skins,
+    SaveBackups,
+    Instances,
+}
+
+/// 真正同步时冲突会按哪一边处理；`Manual` 表示同步会停下来等用户选择。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncConflictResolution {
+    KeepLocal,
+    KeepRemote,
+    Manual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTombstone {
+    pub item_id: String,
+    pub deleted_at: i64,
+    pub device_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedConflict {
+    pub path: String,
+    pub local: String,
+    pub remote: String,
+    pub resolution: SyncConflictResolution,
+}
+
+/// 同步预演结果。远端路径相对存储根目录，本地路径相对启动器数据目录。
+/// `fingerprint` 由计划内容算出，执行同步时带上它，计划过期就会拒绝执行。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavSyncPlan {
+    pub scope: WebDavSyncScope,
+    pub remote_root: String,
+    pub fingerprint: String,
+    pub uploads: Vec<String>,
+    pub downloads: Vec<String>,
+    pub local_deletions: Vec<String>,
+    pub remote_deletions: Vec<String>,
+    pub tombstones: Vec<PlannedTombstone>,
+    pub conflicts: Vec<PlannedConflict>,
+}
+
+impl WebDavSyncPlan {
+    pub(crate) fn new(scope: WebDavSyncScope, remote_root: String) -> Self {
+        Self {
+            scope,
+            remote_root,
+            fingerprint: String::new(),
+            uploads: Vec::new(),
+            downloads: Vec::new(),
+            local_deletions: Vec::new(),
+            remote_deletions: Vec::new(),
+            tombstones: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.uploads.is_empty()
+            && self.downloads.is_empty()
+            && self.local_deletions.is_empty()
+            && self.remote_deletions.is_empty()
+            && self.tombstones.is_empty()
+            && self.conflicts.is_empty()
+    }
+
+    /// 排序去重后计算指纹，同样的本地与远端状态总是得到同样的指纹。
+    pub(crate) fn finish(mut self) -> Self {
+        for list in [
+            &mut self.uploads,
+            &mut self.downloads,
+            &mut self.local_deletions,
+            &mut self.remote_deletions,
+        ] {
+            list.sort();
+            list.dedup();
+        }
+        self.tombstones
+            .sort_by(|left, right| left.item_id.cmp(&right.item_id));
+        self.conflicts
+            .sort_by(|left, right| left.path.cmp(&right.path));
+        self.fingerprint.clear();
+        let material = serde_json::to_vec(&self).unwrap_or_default();
+        self.fingerprint = format!("{:x}", Sha1::digest(material));
+        self
+    }
+}
+
+/// 带着确认过的计划执行同步时，先重新规划一次；结果不同说明期间本地或远端变了。
+pub(crate) fn ensure_plan_confirmed(
+    plan: &WebDavSyncPlan,
+    confirmed_plan: Option<&str>,
+) -> Result<(), String> {
+    match confirmed_plan {
+        Some(fingerprint) if fingerprint != plan.fingerprint => Err(
+            "sync plan is out of date: local or remote data changed since it was reviewed"
+                .to_string(),
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn describe_operation(operation: &FavoriteOperation) -> String {
+    match operation.action {
+        FavoriteOperationAction::Add => format!("add at {}", operation.timestamp),
+        FavoriteOperationAction::Remove => format!("remove at {}", operation.timestamp),
+    }
+}
+
+/// 同一收藏在本地待上传与远端新下载的操作里都出现且内容不同，就是并发修改。
+fn favorite_conflicts(
+    local_pending: &[FavoriteOperation],
+    remote_new: &[FavoriteOperation],
+    winners: &HashMap<String, FavoriteOperation>,
+) -> Vec<PlannedConflict> {
+    let mut latest_local = HashMap::<&str, &FavoriteOperation>::new();
+    for operation in local_pending {
+        let entry = latest_local
+            .entry(operation.target_id.as_str())
+            .or_insert(operation);
+        if util::operation_is_newer(operation, entry) {
+            *entry = operation;
+        }
+    }
+    let mut latest_remote = HashMap::<&str, &FavoriteOperation>::new();
+    for operation in remote_new {
+        let entry = latest_remote
+            .entry(operation.target_id.as_str())
+            .or_insert(operation);
+        if util::operation_is_newer(operation, entry) {
+            *entry = operation;
+        }
+    }
+
+    latest_local
+        .iter()
+        .filter_map(|(target_id, local)| {
+            let remote = latest_remote.get(target_id)?;
+            let same_item =
+                serde_json::to_value(&local.item).ok() == serde_json::to_value(&remote.item).ok();
+            if local.action == remote.action && same_item {
+                return None;
+            }
+            let resolution = match winners.get(*target_id) {
+                Some(winner) if winner.op_id == remote.op_id => SyncConflictResolution::KeepRemote,
+                _ => SyncConflictResolution::KeepLocal,
+            };
+            Some(PlannedConflict {
+                path: format!("favorites/{target_id}"),
+                local: describe_operation(local),
+                remote: format!("{} by {}", describe_operation(remote), remote.device_id),
+                resolution,
+            })
+        })
+        .collect()
+}
+
+/// 是否要把当前状态压缩成新快照。`planned_at` 固定为规划时刻，执行时沿用同一时间，
+/// 不会因为预演与执行之间跨过快照过期时间而多删远端操作。
+pub(crate) fn should_compact_favorites(
+    selected_snapshot: Option<&FavoriteSnapshot>,
+    operations: &[FavoriteOperation],
+    last_snapshot_at: i64,
+    planned_at: i64,
+) -> bool {
+    let newest_snapshot_timestamp = selected_snapshot
+        .map(|snapshot| snapshot.last_timestamp)
+        .unwrap_or(0);
+    let operations_since_snapshot = operations
+        .iter()
+        .filter(|operation| operation.timestamp > newest_snapshot_timestamp)
+        .count();
+    selected_snapshot.is_none()
+        || operations_since_snapshot >= SNAPSHOT_OPERATION_THRESHOLD
+        || last_snapshot_at <= 0
+        || planned_at.saturating_sub(last_snapshot_at) >= SNAPSHOT_MAX_AGE_MILLIS
+}
+
+pub(crate) async fn plan_favorites<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    store: &RemoteStore,
+    device_id: &str,
+    planned_at: i64,
+) -> Result<WebDavSyncPlan, String> {
+    let mut plan = WebDavSyncPlan::new(
+        WebDavSyncScope::Favorites,
+        store.describe(super::constants::REMOTE_ROOT),
+    );
+
+    let uncovered = state::uncovered_local_operations(app, pool, device_id).await?;
+    let mut local_operations = local_store::load_operations(app)?;
+    local_operations.extend(uncovered.iter().cloned());
+    let mut local_file_set = local_store::list_operation_files(app)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    local_file_set.extend(uncovered.iter().map(util::operation_file_name));
+
+    let remote_snapshot = remote::download_snapshot(store).await?;
+    let local_snapshot = local_store::read_snapshot(app)?;
+    let selected_snapshot = snapshot::pick_newer_snapshot(local_snapshot, remote_snapshot.clone());
+    let remote_file_set = remote::list_operation_files(store)
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    let mut local_pending = Vec::new();
+    for operation in &local_operations {
+        let file_name = util::operation_file_name(operation);
+        if !remote_file_set.contains(&file_name) {
+            plan.uploads.push(format!("{OPERATIONS_DIR}/{file_name}"));
+            local_pending.push(operation.clone());
+        }
+    }
+
+    let mut remote_new = Vec::new();
+    for file_name in &remote_file_set {
+        if local_file_set.contains(file_name) {
+            continue;
+        }
+        plan.downloads.push(format!("{OPERATIONS_DIR}/{file_name}"));
+        let operation = store
+            .get_json::<FavoriteOperation>(&format!("{OPERATIONS_DIR}/{file_name}"))
+            .await
+            .map_err(|error| format!("failed to read remote operation: {error}"))?
+            .ok_or_else(|| format!("remote operation disappeared: {file_name}"))?;
+        remote_new.push(operation);
+    }
+
+    let mut operations = local_operations;
+    operations.extend(remote_new.iter().cloned());
+    let winners = snapshot::resolve_latest_operations(selected_snapshot.as_ref(), &operations);
+    plan.conflicts = favorite_conflicts(&local_pending, &remote_new, &winners);
+
+    let starred_items = LibraryService::get_starred_items(pool)
+        .await
+        .map_err(|error| error.to_string())?;
+    let starred_ids = starred_items
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect::<HashSet<_>>();
+    plan.tombstones = winners
+        .values()
+        .filter(|operation| {
+            operation.action == FavoriteOperationAction::Remove
+                && starred_ids.contains(operation.target_id.as_str())
+        })
+        .map(|operation| PlannedTombstone {
+            item_id: operation.target_id.clone(),
+            deleted_at: operation.timestamp,
+            device_id: operation.device_id.clone(),
+        })
+        .collect();
+
+    // 与 sync_favorites 相同的压缩条件：要压缩时上传新快照并删掉已被快照覆盖的远端操作
+    let sync_meta = local_store::read_sync_meta(app)?;
+    // 执行时采用了更新的快照会把快照时间记为当前，这里按同样的规则推算
+    let last_snapshot_at = match selected_snapshot.as_ref() {
+        Some(snapshot) if sync_meta.favorites.last_snapshot_timestamp < snapshot.last_timestamp => {
+            planned_at
+        }
+        _ => sync_meta.favorites.last_snapshot_at,
+    };
+    if should_compact_favorites(
+        selected_snapshot.as_ref(),
+        &operations,
+        last_snapshot_at,
+        planned_at,
+    ) {
+        plan.uploads.push(SNAPSHOT_PATH.to_string());
+        let compacted_timestamp = winners
+            .values()
+            .map(|operation| operation.timestamp)
+            .max()
+            .unwrap_or(0);
+        plan.remote_deletions.extend(
+            remote_file_set
+                .iter()
+                .filter(|file_name| {
+                    util::operation_timestamp_from_file_name(file_name)
+                        .map(|timestamp| timestamp <= compacted_timestamp)
+                        .unwrap_or(false)
+                })
+                .map(|file_name| format!("{OPERATIONS_DIR}/{file_name}")),
+        );
+    } else if let Some(snapshot) = selected_snapshot.as_ref() {
+        let remote_is_older = remote_snapshot
+            .as_ref()
+            .map(|remote| snapshot.last_timestamp > remote.last_timestamp)
+            .unwrap_or(true);
+        if remote_is_older {
+            plan.uploads.push(SNAPSHOT_PATH.to_string());
+        }
+    }
+
+    // 资源库文件按合并后的收藏决定去留，键位配置独立比较
+    let merged_items = merged_starred_items(starred_items, &winners);
+    library::plan_library_files(app, store, &merged_items, &mut plan).await?;
+    keymaps::plan_keyboard_profiles(app, store, &mut plan).await?;
+
+    Ok(plan.finish())
+}
+
+fn merged_starred_items(
+    starred_items: Vec<StarredItem>,
+    winners: &HashMap<String, FavoriteOperation>,
+) -> Vec<StarredItem> {
+    let mut merged = starred_items
+        .into_iter()
+        .map(|item| (item.id.clone(), item))
+        .collect::<HashMap<_, _>>();
+    for operation in winners.values() {
+        match operation.action {
+            FavoriteOperationAction::Add => {
+                if let Some(item) = operation.item.clone() {
+                    merged.insert(operation.target_id.clone(), item);
+                }
+            }
+            FavoriteOperationAction::Remove => {
+                merged.remove(&operation.target_id);
+            }
+        }
+    }
+    merged.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation(
+        op_id: &str,
+        target_id: &str,
+        action: FavoriteOperationAction,
+        timestamp: i64,
+    ) -> FavoriteOperation {
+        FavoriteOperation {
+            op_id: op_id.to_string(),
+            target_id: target_id.to_string(),
+            action,
+            timestamp,
+            device_id: format!("device-{op_id}"),
+            item: None,
+        }
+    }
+
+    #[test]
+    fn compaction_uses_the_planned_time() {
+        let snapshot = FavoriteSnapshot {
+            version: 1,
+            favorites: Vec::new(),
+            states: Vec::new(),
+            last_timestamp: 100,
+        };
+        let operations = vec![operation("a", "x", FavoriteOperationAction::Add, 50)];
+        let last_snapshot_at = 1_000;
+        let before_expiry = last_snapshot_at + SNAPSHOT_MAX_AGE_MILLIS - 1;
+
+        assert!(!should_compact_favorites(
+            Some(&snapshot),
+            &operations,
+            last_snapshot_at,
+            before_expiry
+        ));
+        assert!(should_compact_favorites(
+            Some(&snapshot),
+            &operations,
+            last_snapshot_at,
+            before_expiry + 1
+        ));
+        assert!(should_compact_favorites(
+            None,
+            &operations,
+            last_snapshot_at,
+            before_expiry
+        ));
+    }
+
+    #[test]
+    fn fingerprint_ignores_ordering_but_not_content() {
+        let mut first = WebDavSyncPlan::new(WebDavSyncScope::Skins, "root".to_string());
+        first.uploads = vec!["b".to_string(), "a".to_string()];
+        let mut second = WebDavSyncPlan::new(WebDavSyncScope::Skins, "root".to_string());
+        second.uploads = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+        let (first, second) = (first.finish(), second.finish());
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert!(ensure_plan_confirmed(&second, Some(&first.fingerprint)).is_ok());
+        assert!(ensure_plan_confirmed(&second, None).is_ok());
+
+        let mut third = WebDavSyncPlan::new(WebDavSyncScope::Skins, "root".to_string());
+        third.remote_deletions = vec!["a".to_string()];
+        let third = third.finish();
+        assert_ne!(first.fingerprint, third.fingerprint);
+        assert!(ensure_plan_confirmed(&third, Some(&first.fingerprint)).is_err());
+    }
+
+    #[test]
+    fn reports_concurrent_favorite_changes_with_the_winning_side() {
+        let local = vec![
+            operation("l1", "shader-a", FavoriteOperationAction::Remove, 20),
+            operation("l2", "shader-b", FavoriteOperationAction::Add, 5),
+        ];
+        let remote = vec![
+            operation("r1", "shader-a", FavoriteOperationAction::Add, 10),
+            operation("r2", "shader-b", FavoriteOperationAction::Remove, 30),
+            operation("r3", "shader-c", FavoriteOperationAction::Add, 1),
+        ];
+        let mut all = local.clone();
+        all.extend(remote.iter().cloned());
+        let winners = snapshot::resolve_latest_operations(None, &all);
+
+        let mut conflicts = favorite_conflicts(&local, &remote, &winners);
+        conflicts.sort_by(|left, right| left.path.cmp(&right.path));
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].path, "favorites/shader-a");
+        assert_eq!(conflicts[0].resolution, SyncConflictResolution::KeepLocal);
+        assert_eq!(conflicts[1].path, "favorites/shader-b");
+        assert_eq!(conflicts[1].resolution, SyncConflictResolution::KeepRemote);
+    }
+}
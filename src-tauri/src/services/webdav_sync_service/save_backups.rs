@@ -3,7 +3,8 @@ use crate::services::config_service::ConfigService;
 use crate::services::instance::save_manager::{
     SaveBackupMetadata, SaveManagerService, SaveRestoreResult,
 };
-use reqwest::{header, Client, Method, StatusCode};
+use crate::services::instance::{partial_restore, save_retention};
+use crate::services::remote_store::RemoteStore;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
@@ -18,10 +19,12 @@ use super::constants::{
     REMOTE_ROOT, SAVE_BACKUPS_ARCHIVE_PATH, SAVE_BACKUPS_ARCHIVE_TEMP_PATH,
     SAVE_BACKUPS_BACKUPS_DIR, SAVE_BACKUPS_DATA_DIR, SAVE_BACKUPS_DIR, SAVE_BACKUPS_MANIFEST_PATH,
 };
-use super::{remote, util};
+use super::plan::{
+    self, PlannedConflict, SyncConflictResolution, WebDavSyncPlan, WebDavSyncScope,
+};
+use super::{migration, util};
 
 const SAVE_BACKUP_SYNC_SCHEMA_VERSION: i32 = 2;
-const SAVE_BACKUP_LOCK_TIMEOUT_SECONDS: u64 = 300;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
@@ -88,6 +91,14 @@ pub struct WebDavSaveBackupDeleteResult {
     pub remaining_backups: usize,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavSaveBackupPruneResult {
+    pub pruned_backup_ids: Vec<String>,
+    pub deleted_files: usize,
+    pub remaining_backups: usize,
+}
+
 #[derive(Clone)]
 struct RemoteBackupRecord {
     meta: SaveBackupMetadata,
@@ -100,27 +111,24 @@ struct RemoteBackupRecord {
 pub(crate) async fn sync_save_backups<R: Runtime>(
     app: &AppHandle<R>,
     config: &WebDavSyncConfig,
+    confirmed_plan: Option<&str>,
 ) -> Result<WebDavSaveBackupSyncResult, String> {
-    util::validate_base_url(&config.base_url)?;
-
-    let client = Client::builder()
-        .build()
-        .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
-    let remote_created = ensure_save_backup_layout(&client, config).await?;
-    let lock_token = lock_save_backup_directory(&client, config).await?;
+    let mut store = RemoteStore::for_sync_config(config)?;
+    let remote_created = ensure_save_backup_layout(&store).await?;
+    store.lock(SAVE_BACKUPS_DIR).await?;
     let sync_result =
-        sync_save_backups_locked(app, config, &client, remote_created, &lock_token).await;
-    let unlock_result = unlock_save_backup_directory(&client, config, &lock_token).await;
+        sync_save_backups_locked(app, config, &store, remote_created, confirmed_plan).await;
+    let unlock_result = store.unlock(SAVE_BACKUPS_DIR).await;
 
     match (sync_result, unlock_result) {
         (Ok(result), Ok(())) => Ok(result),
         (Err(error), Ok(())) => Err(error),
         (Ok(_), Err(unlock_error)) => Err(format!(
-            "save backup sync completed, but failed to release WebDAV lock: {}",
+            "save backup sync completed, but failed to release remote lock: {}",
             unlock_error
         )),
         (Err(error), Err(unlock_error)) => Err(format!(
-            "{}; additionally failed to release WebDAV lock: {}",
+            "{}; additionally failed to release remote lock: {}",
             error, unlock_error
         )),
     }
@@ -129,18 +137,14 @@ pub(crate) async fn sync_save_backups<R: Runtime>(
 pub(crate) async fn list_remote_save_backups(
     config: &WebDavSyncConfig,
 ) -> Result<Vec<WebDavRemoteSaveBackup>, String> {
-    util::validate_base_url(&config.base_url)?;
-
-    let client = Client::builder()
-        .build()
-        .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
-    ensure_save_backup_layout(&client, config).await?;
+    let store = RemoteStore::for_sync_config(config)?;
+    ensure_save_backup_layout(&store).await?;
 
-    let Some(manifest) = download_manifest(&client, config).await? else {
+    let Some(manifest) = download_manifest(&store).await? else {
         return Ok(Vec::new());
     };
 
-    let mut records = load_remote_backup_records(&client, config, &manifest).await?;
+    let mut records = load_remote_backup_records(&store, &manifest).await?;
     records.sort_by(|left, right| {
         right
             .meta
@@ -172,17 +176,13 @@ pub(crate) async fn download_remote_save_backup<R: Runtime>(
     restore_configs: bool,
     auto_backup_current: bool,
 ) -> Result<WebDavSaveBackupDownloadResult, String> {
-    util::validate_base_url(&config.base_url)?;
+    let store = RemoteStore::for_sync_config(config)?;
+    ensure_save_backup_layout(&store).await?;
 
-    let client = Client::builder()
-        .build()
-        .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
-    ensure_save_backup_layout(&client, config).await?;
-
-    let manifest = download_manifest(&client, config)
+    let manifest = download_manifest(&store)
         .await?
         .ok_or_else(|| "WebDAV save backup manifest does not exist".to_string())?;
-    let records = load_remote_backup_records(&client, config, &manifest).await?;
+    let records = load_remote_backup_records(&store, &manifest).await?;
     let records_by_id = records
         .into_iter()
         .map(|record| (record.meta.backup_id.clone(), record))
@@ -195,8 +195,7 @@ pub(crate) async fn download_remote_save_backup<R: Runtime>(
     let ordered_backup_ids = collect_required_backup_chain(backup_id, &records_by_id)?;
     let downloaded_files = download_backup_chain_to_local(
         app,
-        config,
-        &client,
+        &store,
         target_instance_id,
         &ordered_backup_ids,
         &records_by_id,
@@ -229,45 +228,112 @@ pub(crate) async fn delete_remote_save_backup(
     config: &WebDavSyncConfig,
     backup_id: &str,
 ) -> Result<WebDavSaveBackupDeleteResult, String> {
-    util::validate_base_url(&config.base_url)?;
-
-    let client = Client::builder()
-        .build()
-        .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
-    ensure_save_backup_layout(&client, config).await?;
+    let mut store = RemoteStore::for_sync_config(config)?;
+    ensure_save_backup_layout(&store).await?;
 
-    let lock_token = lock_save_backup_directory(&client, config).await?;
-    let delete_result =
-        delete_remote_save_backup_locked(config, &client, backup_id, &lock_token).await;
-    let unlock_result = unlock_save_backup_directory(&client, config, &lock_token).await;
+    store.lock(SAVE_BACKUPS_DIR).await?;
+    let delete_result = delete_remote_save_backup_locked(&store, backup_id).await;
+    let unlock_result = store.unlock(SAVE_BACKUPS_DIR).await;
 
     match (delete_result, unlock_result) {
         (Ok(result), Ok(())) => Ok(result),
         (Err(error), Ok(())) => Err(error),
         (Ok(_), Err(unlock_error)) => Err(format!(
-            "save backup delete completed, but failed to release WebDAV lock: {}",
+            "save backup delete completed, but failed to release remote lock: {}",
+            unlock_error
+        )),
+        (Err(error), Err(unlock_error)) => Err(format!(
+            "{}; additionally failed to release remote lock: {}",
+            error, unlock_error
+        )),
+    }
+}
+
+/// 按各实例本地配置的保留策略清理远端备份，逐个走与手动删除相同的路径，
+/// 因此仍被增量备份依赖的基础备份不会被删除。
+pub(crate) async fn prune_remote_save_backups<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &WebDavSyncConfig,
+) -> Result<WebDavSaveBackupPruneResult, String> {
+    let mut store = RemoteStore::for_sync_config(config)?;
+    ensure_save_backup_layout(&store).await?;
+
+    store.lock(SAVE_BACKUPS_DIR).await?;
+    let prune_result = prune_remote_save_backups_locked(app, &store).await;
+    let unlock_result = store.unlock(SAVE_BACKUPS_DIR).await;
+
+    match (prune_result, unlock_result) {
+        (Ok(result), Ok(())) => Ok(result),
+        (Err(error), Ok(())) => Err(error),
+        (Ok(_), Err(unlock_error)) => Err(format!(
+            "save backup pruning completed, but failed to release remote lock: {}",
             unlock_error
         )),
         (Err(error), Err(unlock_error)) => Err(format!(
-            "{}; additionally failed to release WebDAV lock: {}",
+            "{}; additionally failed to release remote lock: {}",
             error, unlock_error
         )),
     }
 }
 
+async fn prune_remote_save_backups_locked<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+) -> Result<WebDavSaveBackupPruneResult, String> {
+    let mut result = WebDavSaveBackupPruneResult {
+        pruned_backup_ids: Vec::new(),
+        deleted_files: 0,
+        remaining_backups: 0,
+    };
+    let Some(manifest) = download_manifest(store).await? else {
+        return Ok(result);
+    };
+    let records = load_remote_backup_records(store, &manifest).await?;
+    result.remaining_backups = records.len();
+
+    let mut worlds: HashMap<(String, String), Vec<SaveBackupMetadata>> = HashMap::new();
+    for record in records {
+        worlds
+            .entry((record.instance_id, record.world_key))
+            .or_default()
+            .push(record.meta);
+    }
+
+    let now = chrono::Local::now();
+    let mut doomed = Vec::new();
+    for ((instance_id, _), backups) in &worlds {
+        let retention =
+            SaveManagerService::backup_retention_for_world(app, instance_id, &backups[0].world)?;
+        doomed.extend(save_retention::plan_world_pruning(backups, &retention, now));
+    }
+
+    for backup_id in doomed {
+        let deleted = delete_remote_save_backup_locked(store, &backup_id).await?;
+        result.deleted_files += deleted.deleted_files;
+        result.remaining_backups = deleted.remaining_backups;
+        result.pruned_backup_ids.push(backup_id);
+    }
+
+    Ok(result)
+}
+
 async fn sync_save_backups_locked<R: Runtime>(
     app: &AppHandle<R>,
     config: &WebDavSyncConfig,
-    client: &Client,
+    store: &RemoteStore,
     remote_created: bool,
-    lock_token: &str,
+    confirmed_plan: Option<&str>,
 ) -> Result<WebDavSaveBackupSyncResult, String> {
+    if confirmed_plan.is_some() {
+        let plan = plan_save_backups(app, config, store).await?;
+        plan::ensure_plan_confirmed(&plan, confirmed_plan)?;
+    }
     let backups_root = local_save_backups_root(app)?;
     let selected_worlds = SaveManagerService::load_webdav_backup_selection(app)?.selected_worlds;
     let save_backup_mode = normalize_save_backup_mode(&config.save_backup_mode);
     let backup_only = save_backup_mode == "backup";
     let local_snapshot = scan_local_save_backups_snapshot(&backups_root, &selected_worlds)?;
-    let remote_manifest = download_manifest(client, config).await?;
+    let remote_manifest = download_manifest(store).await?;
 
     let mut uploaded_files = 0usize;
     let mut downloaded_files = 0usize;
@@ -284,9 +350,9 @@ async fn sync_save_backups_locked<R: Runtime>(
         .unwrap_or(0);
 
     if selected_worlds.is_empty() || local_snapshot.file_count == 0 {
-        delete_remote_save_backup_tree(client, config, lock_token).await?;
+        delete_remote_save_backup_tree(store).await?;
         return Ok(WebDavSaveBackupSyncResult {
-            remote_root: util::join_remote_url(&config.base_url, SAVE_BACKUPS_DATA_DIR),
+            remote_root: store.describe(SAVE_BACKUPS_DATA_DIR),
             remote_created,
             mode: save_backup_mode,
             uploaded_files,
@@ -303,18 +369,8 @@ async fn sync_save_backups_locked<R: Runtime>(
 
     if !backup_only {
         if let Some(manifest) = remote_manifest.as_ref() {
-            let remote_differs = manifest.content_hash != local_snapshot.content_hash;
-            let remote_is_newer = manifest.updated_at > local_snapshot.updated_at;
-            let remote_selection: HashSet<String> =
-                manifest.selected_worlds.iter().cloned().collect();
-            if remote_differs
-                && !manifest.content_hash.is_empty()
-                && !manifest.files.is_empty()
-                && remote_selection == selected_worlds
-                && remote_is_newer
-            {
-                restore_remote_tree(client, config, &backups_root, manifest, &selected_worlds)
-                    .await?;
+            if should_restore_remote(manifest, &local_snapshot, &selected_worlds) {
+                restore_remote_tree(store, &backups_root, manifest, &selected_worlds).await?;
                 downloaded_files = manifest.file_count;
                 restored = true;
 
@@ -328,7 +384,7 @@ async fn sync_save_backups_locked<R: Runtime>(
                 }
 
                 return Ok(WebDavSaveBackupSyncResult {
-                    remote_root: util::join_remote_url(&config.base_url, SAVE_BACKUPS_DATA_DIR),
+                    remote_root: store.describe(SAVE_BACKUPS_DATA_DIR),
                     remote_created,
                     mode: save_backup_mode,
                     uploaded_files,
@@ -362,15 +418,7 @@ async fn sync_save_backups_locked<R: Runtime>(
             files: local_snapshot.files.clone(),
         };
 
-        publish_remote_tree(
-            client,
-            config,
-            &backups_root,
-            &local_snapshot,
-            &manifest,
-            lock_token,
-        )
-        .await?;
+        publish_remote_tree(store, &backups_root, &local_snapshot, &manifest).await?;
         uploaded_files = local_snapshot.file_count;
         remote_files = local_snapshot.file_count;
         remote_backups = local_snapshot.backup_count;
@@ -378,7 +426,7 @@ async fn sync_save_backups_locked<R: Runtime>(
     }
 
     Ok(WebDavSaveBackupSyncResult {
-        remote_root: util::join_remote_url(&config.base_url, SAVE_BACKUPS_DATA_DIR),
+        remote_root: store.describe(SAVE_BACKUPS_DATA_DIR),
         remote_created,
         mode: save_backup_mode,
         uploaded_files,
@@ -393,10 +441,149 @@ async fn sync_save_backups_locked<R: Runtime>(
     })
 }
 
-async fn ensure_save_backup_layout(
-    client: &Client,
+fn should_restore_remote(
+    manifest: &SaveBackupsManifest,
+    local_snapshot: &LocalSaveBackupsSnapshot,
+    selected_worlds: &HashSet<String>,
+) -> bool {
+    let remote_differs = manifest.content_hash != local_snapshot.content_hash;
+    let remote_is_newer = manifest.updated_at > local_snapshot.updated_at;
+    let remote_selection: HashSet<String> = manifest.selected_worlds.iter().cloned().collect();
+    remote_differs
+        && !manifest.content_hash.is_empty()
+        && !manifest.files.is_empty()
+        && &remote_selection == selected_worlds
+        && remote_is_newer
+}
+
+/// 预演存档备份同步，判断顺序与 `sync_save_backups_locked` 一致：
+/// 没有选中的世界时清空远端，远端较新时整体恢复，否则用本地整体覆盖远端。
+pub(crate) async fn plan_save_backups<R: Runtime>(
+    app: &AppHandle<R>,
     config: &WebDavSyncConfig,
-) -> Result<bool, String> {
+    store: &RemoteStore,
+) -> Result<WebDavSyncPlan, String> {
+    let mut plan = WebDavSyncPlan::new(
+        WebDavSyncScope::SaveBackups,
+        store.describe(SAVE_BACKUPS_DATA_DIR),
+    );
+    let backups_root = local_save_backups_root(app)?;
+    let selected_worlds = SaveManagerService::load_webdav_backup_selection(app)?.selected_worlds;
+    let backup_only = normalize_save_backup_mode(&config.save_backup_mode) == "backup";
+    let local_snapshot = scan_local_save_backups_snapshot(&backups_root, &selected_worlds)?;
+    let remote_manifest = download_manifest(store).await?;
+    let remote_files = remote_manifest
+        .as_ref()
+        .map(list_manifest_files)
+        .unwrap_or_default();
+    let describe_remote = |manifest: &SaveBackupsManifest| {
+        format!(
+            "{} backups, {} files, updated at {}",
+            manifest.backup_count, manifest.file_count, manifest.updated_at
+        )
+    };
+    let describe_local = format!(
+        "{} backups, {} files, updated at {}",
+        local_snapshot.backup_count, local_snapshot.file_count, local_snapshot.updated_at
+    );
+
+    if selected_worlds.is_empty() || local_snapshot.file_count == 0 {
+        plan.remote_deletions.extend(
+            remote_files
+                .iter()
+                .map(|file| format!("{SAVE_BACKUPS_DATA_DIR}/{}", file.relative_path)),
+        );
+        if let Some(manifest) = remote_manifest.as_ref() {
+            plan.remote_deletions.push(SAVE_BACKUPS_MANIFEST_PATH.to_string());
+            if !remote_files.is_empty() {
+                plan.conflicts.push(PlannedConflict {
+                    path: SAVE_BACKUPS_DIR.to_string(),
+                    local: describe_local,
+                    remote: describe_remote(manifest),
+                    resolution: SyncConflictResolution::KeepLocal,
+                });
+            }
+        }
+        return Ok(plan.finish());
+    }
+
+    let local_paths = local_snapshot
+        .files
+        .iter()
+        .map(|file| file.relative_path.as_str())
+        .collect::<HashSet<_>>();
+    if let Some(manifest) = remote_manifest.as_ref().filter(|manifest| {
+        !backup_only && should_restore_remote(manifest, &local_snapshot, &selected_worlds)
+    }) {
+        let remote_paths = remote_files
+            .iter()
+            .filter(|file| selected_backup_path(&file.relative_path, &selected_worlds))
+            .map(|file| file.relative_path.as_str())
+            .collect::<HashSet<_>>();
+        for file in &remote_files {
+            let local_hash = local_snapshot
+                .files
+                .iter()
+                .find(|local| local.relative_path == file.relative_path)
+                .map(|local| local.content_hash.as_str());
+            if remote_paths.contains(file.relative_path.as_str())
+                && local_hash != Some(file.content_hash.as_str())
+            {
+                plan.downloads.push(format!("{SAVE_BACKUPS_DATA_DIR}/{}", file.relative_path));
+            }
+        }
+        plan.local_deletions.extend(
+            local_paths
+                .difference(&remote_paths)
+                .map(|path| format!("backups/saves/{path}")),
+        );
+        if local_snapshot.file_count > 0 {
+            plan.conflicts.push(PlannedConflict {
+                path: SAVE_BACKUPS_DIR.to_string(),
+                local: describe_local,
+                remote: describe_remote(manifest),
+                resolution: SyncConflictResolution::KeepRemote,
+            });
+        }
+        return Ok(plan.finish());
+    }
+
+    let remote_matches_local = remote_manifest
+        .as_ref()
+        .map(|manifest| manifest.content_hash == local_snapshot.content_hash)
+        .unwrap_or(false);
+    if !remote_matches_local {
+        // 上传会先清空远端目录再整体写入
+        plan.uploads.extend(
+            local_snapshot
+                .files
+                .iter()
+                .map(|file| format!("{SAVE_BACKUPS_DATA_DIR}/{}", file.relative_path)),
+        );
+        plan.uploads.push(SAVE_BACKUPS_MANIFEST_PATH.to_string());
+        plan.remote_deletions.extend(
+            remote_files
+                .iter()
+                .filter(|file| !local_paths.contains(file.relative_path.as_str()))
+                .map(|file| format!("{SAVE_BACKUPS_DATA_DIR}/{}", file.relative_path)),
+        );
+        if let Some(manifest) = remote_manifest
+            .as_ref()
+            .filter(|manifest| manifest.updated_at > local_snapshot.updated_at)
+        {
+            plan.conflicts.push(PlannedConflict {
+                path: SAVE_BACKUPS_DIR.to_string(),
+                local: describe_local,
+                remote: describe_remote(manifest),
+                resolution: SyncConflictResolution::KeepLocal,
+            });
+        }
+    }
+
+    Ok(plan.finish())
+}
+
+async fn ensure_save_backup_layout(store: &RemoteStore) -> Result<bool, String> {
     let mut remote_created = false;
     for remote_path in [
         REMOTE_ROOT,
@@ -404,8 +591,9 @@ async fn ensure_save_backup_layout(
         SAVE_BACKUPS_BACKUPS_DIR,
         SAVE_BACKUPS_DATA_DIR,
     ] {
-        remote_created |= remote::ensure_collection(client, config, remote_path).await?;
+        remote_created |= store.ensure_dir(remote_path).await?;
     }
+    migration::encrypt_plaintext_save_backups(store).await?;
     Ok(remote_created)
 }
 
@@ -558,16 +746,14 @@ fn scan_local_save_backups_snapshot(
 }
 
 async fn publish_remote_tree(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     backups_root: &Path,
     snapshot: &LocalSaveBackupsSnapshot,
     manifest: &SaveBackupsManifest,
-    lock_token: &str,
 ) -> Result<(), String> {
-    delete_remote_save_backup_tree(client, config, lock_token).await?;
-    ensure_collection_locked(client, config, SAVE_BACKUPS_BACKUPS_DIR, lock_token).await?;
-    ensure_collection_locked(client, config, SAVE_BACKUPS_DATA_DIR, lock_token).await?;
+    delete_remote_save_backup_tree(store).await?;
+    store.ensure_dir(SAVE_BACKUPS_BACKUPS_DIR).await?;
+    store.ensure_dir(SAVE_BACKUPS_DATA_DIR).await?;
 
     for file in &snapshot.files {
         let local_path = backups_root.join(
@@ -575,34 +761,17 @@ async fn publish_remote_tree(
                 .replace('/', std::path::MAIN_SEPARATOR_STR),
         );
         let remote_path = format!("{SAVE_BACKUPS_DATA_DIR}/{}", file.relative_path);
-        ensure_remote_parent_collections(client, config, &remote_path, lock_token).await?;
+        ensure_remote_parent_collections(store, &remote_path).await?;
         let body = fs::read(&local_path).map_err(|error| error.to_string())?;
-        upload_bytes(
-            client,
-            config,
-            &remote_path,
-            "application/octet-stream",
-            body,
-            Some(lock_token),
-        )
-        .await?;
+        upload_bytes(store, &remote_path, "application/octet-stream", body).await?;
     }
 
     let manifest_bytes = serde_json::to_vec_pretty(manifest).map_err(|error| error.to_string())?;
-    upload_bytes(
-        client,
-        config,
-        SAVE_BACKUPS_MANIFEST_PATH,
-        "application/json",
-        manifest_bytes,
-        Some(lock_token),
-    )
-    .await
+    upload_bytes(store, SAVE_BACKUPS_MANIFEST_PATH, "application/json", manifest_bytes).await
 }
 
 async fn restore_remote_tree(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     backups_root: &Path,
     manifest: &SaveBackupsManifest,
     selected_worlds: &HashSet<String>,
@@ -626,7 +795,7 @@ async fn restore_remote_tree(
             if !selected_backup_path(&file.relative_path, selected_worlds) {
                 continue;
             }
-            let body = download_remote_file(client, config, &file.relative_path).await?;
+            let body = download_remote_file(store, &file.relative_path).await?;
             let target = temp_dir.join(
                 file.relative_path
                     .replace('/', std::path::MAIN_SEPARATOR_STR),
@@ -678,8 +847,7 @@ async fn restore_remote_tree(
 }
 
 async fn load_remote_backup_records(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     manifest: &SaveBackupsManifest,
 ) -> Result<Vec<RemoteBackupRecord>, String> {
     let mut prefixes = BTreeSet::new();
@@ -694,7 +862,7 @@ async fn load_remote_backup_records(
     let mut records = Vec::new();
     for prefix in prefixes {
         let meta_path = format!("{prefix}/meta.json");
-        let meta_bytes = download_remote_file(client, config, &meta_path).await?;
+        let meta_bytes = download_remote_file(store, &meta_path).await?;
         if md5_bytes(&meta_bytes)
             != manifest_hash_for_path(manifest, &meta_path).unwrap_or_default()
         {
@@ -764,53 +932,15 @@ fn collect_required_backup_chain(
     backup_id: &str,
     records_by_id: &HashMap<String, RemoteBackupRecord>,
 ) -> Result<Vec<String>, String> {
-    fn visit(
-        backup_id: &str,
-        records_by_id: &HashMap<String, RemoteBackupRecord>,
-        visiting: &mut HashSet<String>,
-        visited: &mut HashSet<String>,
-        ordered: &mut Vec<String>,
-    ) -> Result<(), String> {
-        if visited.contains(backup_id) {
-            return Ok(());
-        }
-        if !visiting.insert(backup_id.to_string()) {
-            return Err(format!("WebDAV save backup dependency cycle: {backup_id}"));
-        }
-
-        let record = records_by_id
-            .get(backup_id)
-            .ok_or_else(|| format!("WebDAV save backup not found: {backup_id}"))?;
-        if record.meta.backup_mode == "differential" {
-            let base_id = record.meta.base_backup_id.as_deref().ok_or_else(|| {
-                format!("differential WebDAV save backup is missing base id: {backup_id}")
-            })?;
-            visit(base_id, records_by_id, visiting, visited, ordered)?;
-        }
-
-        visiting.remove(backup_id);
-        visited.insert(backup_id.to_string());
-        ordered.push(backup_id.to_string());
-        Ok(())
-    }
-
-    let mut visiting = HashSet::new();
-    let mut visited = HashSet::new();
-    let mut ordered = Vec::new();
-    visit(
-        backup_id,
-        records_by_id,
-        &mut visiting,
-        &mut visited,
-        &mut ordered,
-    )?;
-    Ok(ordered)
+    partial_restore::resolve_backup_chain(backup_id, |id| {
+        records_by_id.get(id).map(|record| &record.meta)
+    })
+    .map_err(|e| format!("WebDAV {}", e))
 }
 
 async fn download_backup_chain_to_local<R: Runtime>(
     app: &AppHandle<R>,
-    config: &WebDavSyncConfig,
-    client: &Client,
+    store: &RemoteStore,
     target_instance_id: &str,
     ordered_backup_ids: &[String],
     records_by_id: &HashMap<String, RemoteBackupRecord>,
@@ -850,7 +980,7 @@ async fn download_backup_chain_to_local<R: Runtime>(
                 );
                 validate_manifest_relative_path(&target_relative_path)?;
 
-                let bytes = download_remote_file(client, config, &file.relative_path).await?;
+                let bytes = download_remote_file(store, &file.relative_path).await?;
                 if bytes.len() as u64 != file.size || md5_bytes(&bytes) != file.content_hash {
                     return Err(format!(
                         "WebDAV save backup file hash mismatch: {}",
@@ -918,15 +1048,13 @@ async fn download_backup_chain_to_local<R: Runtime>(
 }
 
 async fn delete_remote_save_backup_locked(
-    config: &WebDavSyncConfig,
-    client: &Client,
+    store: &RemoteStore,
     backup_id: &str,
-    lock_token: &str,
 ) -> Result<WebDavSaveBackupDeleteResult, String> {
-    let manifest = download_manifest(client, config)
+    let manifest = download_manifest(store)
         .await?
         .ok_or_else(|| "WebDAV save backup manifest does not exist".to_string())?;
-    let records = load_remote_backup_records(client, config, &manifest).await?;
+    let records = load_remote_backup_records(store, &manifest).await?;
     let records_by_id = records
         .into_iter()
         .map(|record| (record.meta.backup_id.clone(), record))
@@ -952,13 +1080,10 @@ async fn delete_remote_save_backup_locked(
 
     let deleted_files = record.files.len();
     let deleted_prefix = record.prefix.clone();
-    delete_remote_path(
-        client,
-        config,
-        &format!("{SAVE_BACKUPS_DATA_DIR}/{deleted_prefix}"),
-        Some(lock_token),
-    )
-    .await?;
+    store
+        .delete(&format!("{SAVE_BACKUPS_DATA_DIR}/{deleted_prefix}"))
+        .await
+        .map_err(|error| format!("failed to delete remote save backup: {error}"))?;
 
     let remaining_files = manifest
         .files
@@ -967,8 +1092,7 @@ async fn delete_remote_save_backup_locked(
         .cloned()
         .collect::<Vec<_>>();
     if remaining_files.is_empty() {
-        let _ =
-            delete_remote_path(client, config, SAVE_BACKUPS_MANIFEST_PATH, Some(lock_token)).await;
+        let _ = store.delete(SAVE_BACKUPS_MANIFEST_PATH).await;
         return Ok(WebDavSaveBackupDeleteResult {
             backup_id: backup_id.to_string(),
             deleted_files,
@@ -979,12 +1103,10 @@ async fn delete_remote_save_backup_locked(
     let next_manifest = rebuild_manifest_with_files(&manifest, remaining_files);
     let remaining_backups = next_manifest.backup_count;
     upload_bytes(
-        client,
-        config,
+        store,
         SAVE_BACKUPS_MANIFEST_PATH,
         "application/json",
         serde_json::to_vec_pretty(&next_manifest).map_err(|error| error.to_string())?,
-        Some(lock_token),
     )
     .await?;
 
@@ -1115,63 +1237,25 @@ fn move_dir_with_fallback(src: &Path, dst: &Path) -> Result<(), String> {
     Ok(())
 }
 
-async fn download_manifest(
-    client: &Client,
-    config: &WebDavSyncConfig,
-) -> Result<Option<SaveBackupsManifest>, String> {
-    let response =
-        remote::authorized_request(client, config, Method::GET, SAVE_BACKUPS_MANIFEST_PATH)
-            .send()
-            .await
-            .map_err(|error| format!("failed to download WebDAV save backup manifest: {error}"))?;
-
-    if response.status() == StatusCode::NOT_FOUND {
-        return Ok(None);
-    }
-    if !response.status().is_success() {
-        return Err(format!(
-            "failed to download WebDAV save backup manifest: HTTP {}",
-            response.status()
-        ));
-    }
-
-    response
-        .json::<SaveBackupsManifest>()
+async fn download_manifest(store: &RemoteStore) -> Result<Option<SaveBackupsManifest>, String> {
+    store
+        .get_json::<SaveBackupsManifest>(SAVE_BACKUPS_MANIFEST_PATH)
         .await
-        .map(Some)
-        .map_err(|error| format!("invalid WebDAV save backup manifest: {error}"))
+        .map_err(|error| format!("failed to download remote save backup manifest: {error}"))
 }
 
-async fn download_remote_file(
-    client: &Client,
-    config: &WebDavSyncConfig,
-    relative_path: &str,
-) -> Result<Vec<u8>, String> {
+async fn download_remote_file(store: &RemoteStore, relative_path: &str) -> Result<Vec<u8>, String> {
     let remote_path = format!("{SAVE_BACKUPS_DATA_DIR}/{relative_path}");
-    let response = remote::authorized_request(client, config, Method::GET, &remote_path)
-        .send()
-        .await
-        .map_err(|error| format!("failed to download WebDAV save backup file: {error}"))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "failed to download WebDAV save backup file: HTTP {}",
-            response.status()
-        ));
-    }
-
-    response
-        .bytes()
+    store
+        .get(&remote_path)
         .await
-        .map(|bytes| bytes.to_vec())
-        .map_err(|error| format!("failed to read WebDAV save backup file: {error}"))
+        .map_err(|error| format!("failed to download remote save backup file: {error}"))?
+        .ok_or_else(|| format!("remote save backup file is missing: {relative_path}"))
 }
 
 async fn ensure_remote_parent_collections(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     remote_path: &str,
-    lock_token: &str,
 ) -> Result<(), String> {
     let mut current = String::new();
     let parts = remote_path.split('/').collect::<Vec<_>>();
@@ -1180,217 +1264,27 @@ async fn ensure_remote_parent_collections(
             current.push('/');
         }
         current.push_str(part);
-        ensure_collection_locked(client, config, &current, lock_token).await?;
+        store.ensure_dir(&current).await?;
     }
     Ok(())
 }
 
-async fn ensure_collection_locked(
-    client: &Client,
-    config: &WebDavSyncConfig,
-    remote_path: &str,
-    lock_token: &str,
-) -> Result<bool, String> {
-    let method =
-        Method::from_bytes(b"MKCOL").map_err(|error| format!("invalid MKCOL method: {error}"))?;
-    let response = remote::authorized_request(client, config, method, remote_path)
-        .header("If", lock_if_header(lock_token))
-        .send()
-        .await
-        .map_err(|error| format!("failed to create WebDAV directory: {error}"))?;
-
-    let status = response.status();
-    if status.is_success() {
-        return Ok(true);
-    }
-    if status == StatusCode::METHOD_NOT_ALLOWED || status == StatusCode::CONFLICT {
-        return Ok(false);
-    }
-
-    Err(format!("failed to create WebDAV directory: HTTP {status}"))
-}
-
-async fn delete_remote_save_backup_tree(
-    client: &Client,
-    config: &WebDavSyncConfig,
-    lock_token: &str,
-) -> Result<(), String> {
-    let _ = delete_remote_path(client, config, SAVE_BACKUPS_DATA_DIR, Some(lock_token)).await;
-    let _ = delete_remote_path(client, config, SAVE_BACKUPS_MANIFEST_PATH, Some(lock_token)).await;
-    let _ = delete_remote_path(client, config, SAVE_BACKUPS_ARCHIVE_PATH, Some(lock_token)).await;
-    let _ = delete_remote_path(
-        client,
-        config,
-        SAVE_BACKUPS_ARCHIVE_TEMP_PATH,
-        Some(lock_token),
-    )
-    .await;
+async fn delete_remote_save_backup_tree(store: &RemoteStore) -> Result<(), String> {
+    let _ = store.delete(SAVE_BACKUPS_DATA_DIR).await;
+    let _ = store.delete(SAVE_BACKUPS_MANIFEST_PATH).await;
+    let _ = store.delete(SAVE_BACKUPS_ARCHIVE_PATH).await;
+    let _ = store.delete(SAVE_BACKUPS_ARCHIVE_TEMP_PATH).await;
     Ok(())
 }
 
-async fn lock_save_backup_directory(
-    client: &Client,
-    config: &WebDavSyncConfig,
-) -> Result<String, String> {
-    let method =
-        Method::from_bytes(b"LOCK").map_err(|error| format!("invalid LOCK method: {error}"))?;
-    let body = r#"<?xml version="1.0" encoding="utf-8" ?>
-<D:lockinfo xmlns:D="DAV:">
-  <D:lockscope><D:exclusive/></D:lockscope>
-  <D:locktype><D:write/></D:locktype>
-  <D:owner><D:href>PiLauncher</D:href></D:owner>
-</D:lockinfo>"#;
-
-    let response = remote::authorized_request(client, config, method, SAVE_BACKUPS_DIR)
-        .header("Depth", "0")
-        .header(
-            "Timeout",
-            format!("Second-{SAVE_BACKUP_LOCK_TIMEOUT_SECONDS}"),
-        )
-        .header(header::CONTENT_TYPE, "application/xml")
-        .body(body)
-        .send()
-        .await
-        .map_err(|error| format!("failed to lock WebDAV save backup directory: {error}"))?;
-
-    let status = response.status();
-    if status.as_u16() == 423 {
-        return Err(
-            "WebDAV save backup directory is locked by another sync session; please retry later"
-                .to_string(),
-        );
-    }
-    if !status.is_success() {
-        return Err(format!(
-            "failed to lock WebDAV save backup directory: HTTP {status}"
-        ));
-    }
-
-    let header_token = response
-        .headers()
-        .get("Lock-Token")
-        .and_then(|value| value.to_str().ok())
-        .and_then(normalize_lock_token);
-    let body = response.text().await.unwrap_or_default();
-    header_token
-        .or_else(|| {
-            extract_lock_token_from_body(&body).and_then(|token| normalize_lock_token(&token))
-        })
-        .ok_or_else(|| "WebDAV lock succeeded, but no lock token was returned".to_string())
-}
-
-async fn unlock_save_backup_directory(
-    client: &Client,
-    config: &WebDavSyncConfig,
-    lock_token: &str,
-) -> Result<(), String> {
-    let method =
-        Method::from_bytes(b"UNLOCK").map_err(|error| format!("invalid UNLOCK method: {error}"))?;
-    let response = remote::authorized_request(client, config, method, SAVE_BACKUPS_DIR)
-        .header("Lock-Token", lock_token)
-        .send()
-        .await
-        .map_err(|error| format!("failed to unlock WebDAV save backup directory: {error}"))?;
-
-    let status = response.status();
-    if status.is_success() || status == StatusCode::NOT_FOUND {
-        Ok(())
-    } else {
-        Err(format!(
-            "failed to unlock WebDAV save backup directory: HTTP {status}"
-        ))
-    }
-}
-
-fn normalize_lock_token(value: &str) -> Option<String> {
-    let token = value
-        .trim()
-        .trim_start_matches('<')
-        .trim_end_matches('>')
-        .trim();
-    if token.is_empty() {
-        None
-    } else {
-        Some(format!("<{token}>"))
-    }
-}
-
-fn lock_if_header(lock_token: &str) -> String {
-    format!("({lock_token})")
-}
-
-fn extract_lock_token_from_body(body: &str) -> Option<String> {
-    for marker in ["opaquelocktoken:", "urn:uuid:"] {
-        if let Some(start) = body.find(marker) {
-            let token = body[start..]
-                .split(|character: char| {
-                    character == '<'
-                        || character == '>'
-                        || character == '"'
-                        || character == '\''
-                        || character.is_whitespace()
-                })
-                .next()
-                .unwrap_or_default()
-                .trim();
-            if !token.is_empty() {
-                return Some(token.to_string());
-            }
-        }
-    }
-    None
-}
-
-async fn delete_remote_path(
-    client: &Client,
-    config: &WebDavSyncConfig,
-    remote_path: &str,
-    lock_token: Option<&str>,
-) -> Result<(), String> {
-    let mut request = remote::authorized_request(client, config, Method::DELETE, remote_path);
-    if let Some(lock_token) = lock_token {
-        request = request.header("If", lock_if_header(lock_token));
-    }
-    let response = request
-        .send()
-        .await
-        .map_err(|error| format!("failed to delete WebDAV path: {error}"))?;
-
-    if response.status().is_success() || response.status() == StatusCode::NOT_FOUND {
-        Ok(())
-    } else {
-        Err(format!(
-            "failed to delete WebDAV path: HTTP {}",
-            response.status()
-        ))
-    }
-}
-
 async fn upload_bytes(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     remote_path: &str,
     content_type: &'static str,
     body: Vec<u8>,
-    lock_token: Option<&str>,
 ) -> Result<(), String> {
-    let mut request = remote::authorized_request(client, config, Method::PUT, remote_path)
-        .header(header::CONTENT_TYPE, content_type);
-    if let Some(lock_token) = lock_token {
-        request = request.header("If", lock_if_header(lock_token));
-    }
-    let response = request
-        .body(body)
-        .send()
+    store
+        .put(remote_path, body, content_type)
         .await
-        .map_err(|error| format!("failed to upload WebDAV save backup: {error}"))?;
-
-    if response.status().is_success() {
-        Ok(())
-    } else {
-        Err(format!(
-            "failed to upload WebDAV save backup: HTTP {}",
-            response.status()
-        ))
-    }
+        .map_err(|error| format!("failed to upload remote save backup file: {error}"))
 }
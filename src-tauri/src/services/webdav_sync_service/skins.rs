@@ -1,6 +1,6 @@
 use crate::domain::library::{WebDavSkinSyncResult, WebDavSyncConfig};
 use crate::services::config_service::ConfigService;
-use reqwest::{header, Client, Method, StatusCode};
+use crate::services::remote_store::RemoteStore;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
 use std::io::{self, Cursor};
@@ -15,7 +15,10 @@ use zip::{ZipArchive, ZipWriter};
 use super::constants::{
     REMOTE_ROOT, SKINS_ARCHIVE_PATH, SKINS_DIR, SKINS_MANIFEST_PATH, WARDROBE_DIR,
 };
-use super::{paths, remote, util};
+use super::plan::{
+    self, PlannedConflict, SyncConflictResolution, WebDavSyncPlan, WebDavSyncScope,
+};
+use super::{migration, paths, util};
 
 const SKIN_BACKUP_SCHEMA_VERSION: i32 = 1;
 
@@ -48,17 +51,18 @@ struct FileFingerprint {
 pub(crate) async fn sync_skin_assets<R: Runtime>(
     app: &AppHandle<R>,
     config: &WebDavSyncConfig,
+    confirmed_plan: Option<&str>,
 ) -> Result<WebDavSkinSyncResult, String> {
-    util::validate_base_url(&config.base_url)?;
-
-    let client = Client::builder()
-        .build()
-        .map_err(|error| format!("failed to build WebDAV client: {error}"))?;
-    let remote_created = ensure_skin_layout(&client, config).await?;
+    let store = RemoteStore::for_sync_config(config)?;
+    let remote_created = ensure_skin_layout(&store).await?;
+    migration::encrypt_plaintext_skins(&store).await?;
+    if confirmed_plan.is_some() {
+        plan::ensure_plan_confirmed(&plan_skin_assets(app, &store).await?, confirmed_plan)?;
+    }
 
     let skins_root = local_skins_root(app)?;
     let local_snapshot = scan_local_skin_snapshot(&skins_root)?;
-    let remote_manifest = download_manifest(&client, config).await?;
+    let remote_manifest = download_manifest(&store).await?;
     let mut remote_files = remote_manifest
         .as_ref()
         .map(|manifest| manifest.file_count)
@@ -70,20 +74,15 @@ pub(crate) async fn sync_skin_assets<R: Runtime>(
     let mut restored = false;
 
     if let Some(manifest) = remote_manifest.as_ref() {
-        let remote_differs = manifest.content_hash != local_snapshot.content_hash;
-        let remote_is_newer = manifest.updated_at > local_snapshot.updated_at;
-        if remote_differs
-            && !manifest.content_hash.is_empty()
-            && (local_snapshot.file_count == 0 || remote_is_newer)
-        {
-            match download_archive(&client, config).await? {
+        if should_restore(manifest, &local_snapshot) {
+            match download_archive(&store).await? {
                 Some(bytes) => {
                     restore_archive(&skins_root, bytes)?;
                     downloaded_files = manifest.file_count;
                     restored = true;
                     let restored_snapshot = scan_local_skin_snapshot(&skins_root)?;
                     return Ok(WebDavSkinSyncResult {
-                        remote_root: util::join_remote_url(&config.base_url, SKINS_DIR),
+                        remote_root: store.describe(SKINS_DIR),
                         remote_created,
                         uploaded_files,
                         downloaded_files,
@@ -111,14 +110,7 @@ pub(crate) async fn sync_skin_assets<R: Runtime>(
     if local_snapshot.file_count > 0 && !remote_matches_local {
         let archive_path = create_local_archive(app, &skins_root)?;
         let archive_bytes = fs::read(&archive_path).map_err(|error| error.to_string())?;
-        upload_bytes(
-            &client,
-            config,
-            SKINS_ARCHIVE_PATH,
-            "application/zip",
-            archive_bytes,
-        )
-        .await?;
+        upload_bytes(&store, SKINS_ARCHIVE_PATH, "application/zip", archive_bytes).await?;
 
         let manifest = SkinBackupManifest {
             schema_version: SKIN_BACKUP_SCHEMA_VERSION,
@@ -129,14 +121,7 @@ pub(crate) async fn sync_skin_assets<R: Runtime>(
         };
         let manifest_bytes =
             serde_json::to_vec_pretty(&manifest).map_err(|error| error.to_string())?;
-        upload_bytes(
-            &client,
-            config,
-            SKINS_MANIFEST_PATH,
-            "application/json",
-            manifest_bytes,
-        )
-        .await?;
+        upload_bytes(&store, SKINS_MANIFEST_PATH, "application/json", manifest_bytes).await?;
 
         let _ = fs::remove_file(archive_path);
         uploaded_files = local_snapshot.file_count;
@@ -145,7 +130,7 @@ pub(crate) async fn sync_skin_assets<R: Runtime>(
     }
 
     Ok(WebDavSkinSyncResult {
-        remote_root: util::join_remote_url(&config.base_url, SKINS_DIR),
+        remote_root: store.describe(SKINS_DIR),
         remote_created,
         uploaded_files,
         downloaded_files,
@@ -156,10 +141,78 @@ pub(crate) async fn sync_skin_assets<R: Runtime>(
     })
 }
 
-async fn ensure_skin_layout(client: &Client, config: &WebDavSyncConfig) -> Result<bool, String> {
+fn should_restore(manifest: &SkinBackupManifest, local_snapshot: &LocalSkinSnapshot) -> bool {
+    let remote_differs = manifest.content_hash != local_snapshot.content_hash;
+    let remote_is_newer = manifest.updated_at > local_snapshot.updated_at;
+    remote_differs
+        && !manifest.content_hash.is_empty()
+        && (local_snapshot.file_count == 0 || remote_is_newer)
+}
+
+/// 预演皮肤同步：整包恢复会替换本地皮肤目录，整包上传会覆盖远端压缩包，
+/// 两边都有不同内容时记为冲突，并注明实际同步会保留哪一边。
+pub(crate) async fn plan_skin_assets<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+) -> Result<WebDavSyncPlan, String> {
+    let mut plan = WebDavSyncPlan::new(WebDavSyncScope::Skins, store.describe(SKINS_DIR));
+    let local_snapshot = scan_local_skin_snapshot(&local_skins_root(app)?)?;
+    let remote_manifest = download_manifest(store).await?;
+    let describe_remote = |manifest: &SkinBackupManifest| {
+        format!(
+            "{} files, updated at {}",
+            manifest.file_count, manifest.updated_at
+        )
+    };
+    let describe_local = format!(
+        "{} files, updated at {}",
+        local_snapshot.file_count, local_snapshot.updated_at
+    );
+
+    if let Some(manifest) = remote_manifest
+        .as_ref()
+        .filter(|manifest| should_restore(manifest, &local_snapshot))
+    {
+        plan.downloads.push(SKINS_ARCHIVE_PATH.to_string());
+        if local_snapshot.file_count > 0 {
+            plan.local_deletions.push("config/skins".to_string());
+            plan.conflicts.push(PlannedConflict {
+                path: "config/skins".to_string(),
+                local: describe_local,
+                remote: describe_remote(manifest),
+                resolution: SyncConflictResolution::KeepRemote,
+            });
+        }
+        return Ok(plan.finish());
+    }
+
+    let remote_matches_local = remote_manifest
+        .as_ref()
+        .map(|manifest| manifest.content_hash == local_snapshot.content_hash)
+        .unwrap_or(false);
+    if local_snapshot.file_count > 0 && !remote_matches_local {
+        plan.uploads.push(SKINS_ARCHIVE_PATH.to_string());
+        plan.uploads.push(SKINS_MANIFEST_PATH.to_string());
+        if let Some(manifest) = remote_manifest
+            .as_ref()
+            .filter(|manifest| !manifest.content_hash.is_empty())
+        {
+            plan.conflicts.push(PlannedConflict {
+                path: "config/skins".to_string(),
+                local: describe_local,
+                remote: describe_remote(manifest),
+                resolution: SyncConflictResolution::KeepLocal,
+            });
+        }
+    }
+
+    Ok(plan.finish())
+}
+
+async fn ensure_skin_layout(store: &RemoteStore) -> Result<bool, String> {
     let mut remote_created = false;
     for remote_path in [REMOTE_ROOT, WARDROBE_DIR, SKINS_DIR] {
-        remote_created |= remote::ensure_collection(client, config, remote_path).await?;
+        remote_created |= store.ensure_dir(remote_path).await?;
     }
     Ok(remote_created)
 }
@@ -334,78 +387,28 @@ fn restore_archive(skins_root: &Path, archive_bytes: Vec<u8>) -> Result<(), Stri
     fs::rename(&temp_dir, skins_root).map_err(|error| error.to_string())
 }
 
-async fn download_manifest(
-    client: &Client,
-    config: &WebDavSyncConfig,
-) -> Result<Option<SkinBackupManifest>, String> {
-    let response = remote::authorized_request(client, config, Method::GET, SKINS_MANIFEST_PATH)
-        .send()
-        .await
-        .map_err(|error| format!("failed to read WebDAV skin backup manifest: {error}"))?;
-
-    if response.status() == StatusCode::NOT_FOUND {
-        return Ok(None);
-    }
-    if !response.status().is_success() {
-        return Err(format!(
-            "failed to read WebDAV skin backup manifest: HTTP {}",
-            response.status()
-        ));
-    }
-
-    response
-        .json::<SkinBackupManifest>()
+async fn download_manifest(store: &RemoteStore) -> Result<Option<SkinBackupManifest>, String> {
+    store
+        .get_json::<SkinBackupManifest>(SKINS_MANIFEST_PATH)
         .await
-        .map(Some)
-        .map_err(|error| format!("invalid WebDAV skin backup manifest: {error}"))
+        .map_err(|error| format!("failed to read remote skin backup manifest: {error}"))
 }
 
-async fn download_archive(
-    client: &Client,
-    config: &WebDavSyncConfig,
-) -> Result<Option<Vec<u8>>, String> {
-    let response = remote::authorized_request(client, config, Method::GET, SKINS_ARCHIVE_PATH)
-        .send()
-        .await
-        .map_err(|error| format!("failed to download WebDAV skin backup: {error}"))?;
-
-    if response.status() == StatusCode::NOT_FOUND {
-        return Ok(None);
-    }
-    if !response.status().is_success() {
-        return Err(format!(
-            "failed to download WebDAV skin backup: HTTP {}",
-            response.status()
-        ));
-    }
-
-    response
-        .bytes()
+async fn download_archive(store: &RemoteStore) -> Result<Option<Vec<u8>>, String> {
+    store
+        .get(SKINS_ARCHIVE_PATH)
         .await
-        .map(|bytes| Some(bytes.to_vec()))
-        .map_err(|error| format!("failed to read WebDAV skin backup: {error}"))
+        .map_err(|error| format!("failed to download remote skin backup: {error}"))
 }
 
 async fn upload_bytes(
-    client: &Client,
-    config: &WebDavSyncConfig,
+    store: &RemoteStore,
     remote_path: &str,
     content_type: &'static str,
     body: Vec<u8>,
 ) -> Result<(), String> {
-    let response = remote::authorized_request(client, config, Method::PUT, remote_path)
-        .header(header::CONTENT_TYPE, content_type)
-        .body(body)
-        .send()
+    store
+        .put(remote_path, body, content_type)
         .await
-        .map_err(|error| format!("failed to upload WebDAV skin backup: {error}"))?;
-
-    if response.status().is_success() {
-        Ok(())
-    } else {
-        Err(format!(
-            "failed to upload WebDAV skin backup: HTTP {}",
-            response.status()
-        ))
-    }
+        .map_err(|error| format!("failed to upload remote skin backup: {error}"))
 }
@@ -13,6 +13,19 @@ pub(crate) async fn ensure_local_state_covered_by_operations<R: Runtime>(
     pool: &SqlitePool,
     device_id: &str,
 ) -> Result<(), String> {
+    for operation in uncovered_local_operations(app, pool, device_id).await? {
+        local_store::write_operation(app, &operation)?;
+    }
+    Ok(())
+}
+
+/// 数据库里还没有对应操作记录的收藏与墓碑。操作 ID 由内容推导，
+/// 同步预演算出的文件名与真正写入时一致。
+pub(crate) async fn uncovered_local_operations<R: Runtime>(
+    app: &AppHandle<R>,
+    pool: &SqlitePool,
+    device_id: &str,
+) -> Result<Vec<FavoriteOperation>, String> {
     let existing_operations = local_store::load_operations(app)?;
     let mut covered_targets = existing_operations
         .iter()
@@ -33,22 +46,21 @@ pub(crate) async fn ensure_local_state_covered_by_operations<R: Runtime>(
         .await
         .map_err(|error| error.to_string())?;
 
+    let mut operations = Vec::new();
     for item in local_items {
         if covered_targets.contains(&item.id) {
             continue;
         }
 
-        local_store::write_operation(
-            app,
-            &FavoriteOperation {
-                op_id: uuid::Uuid::new_v4().to_string(),
-                target_id: item.id.clone(),
-                action: FavoriteOperationAction::Add,
-                timestamp: util::normalize_legacy_timestamp(item.updated_at.max(item.created_at)),
-                device_id: device_id.to_string(),
-                item: Some(item),
-            },
-        )?;
+        let timestamp = util::normalize_legacy_timestamp(item.updated_at.max(item.created_at));
+        operations.push(FavoriteOperation {
+            op_id: util::legacy_operation_id("local-add", &item.id, timestamp),
+            target_id: item.id.clone(),
+            action: FavoriteOperationAction::Add,
+            timestamp,
+            device_id: device_id.to_string(),
+            item: Some(item),
+        });
     }
 
     for tombstone in local_tombstones {
@@ -56,20 +68,18 @@ pub(crate) async fn ensure_local_state_covered_by_operations<R: Runtime>(
             continue;
         }
 
-        local_store::write_operation(
-            app,
-            &FavoriteOperation {
-                op_id: uuid::Uuid::new_v4().to_string(),
-                target_id: tombstone.item_id,
-                action: FavoriteOperationAction::Remove,
-                timestamp: util::normalize_legacy_timestamp(tombstone.deleted_at),
-                device_id: device_id.to_string(),
-                item: None,
-            },
-        )?;
+        let timestamp = util::normalize_legacy_timestamp(tombstone.deleted_at);
+        operations.push(FavoriteOperation {
+            op_id: util::legacy_operation_id("local-remove", &tombstone.item_id, timestamp),
+            target_id: tombstone.item_id,
+            action: FavoriteOperationAction::Remove,
+            timestamp,
+            device_id: device_id.to_string(),
+            item: None,
+        });
     }
 
-    Ok(())
+    Ok(operations)
 }
 
 pub(crate) async fn apply_operation_state(
@@ -1,21 +1,6 @@
 use crate::domain::library::{FavoriteOperation, FavoriteOperationAction};
-use reqwest::Url;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub(crate) fn validate_base_url(base_url: &str) -> Result<(), String> {
-    let trimmed = base_url.trim();
-    if trimmed.is_empty() {
-        return Err("WebDAV address is empty".to_string());
-    }
-
-    let parsed = Url::parse(trimmed).map_err(|error| format!("invalid WebDAV URL: {error}"))?;
-    if !matches!(parsed.scheme(), "http" | "https") {
-        return Err("WebDAV address must start with http:// or https://".to_string());
-    }
-
-    Ok(())
-}
-
 pub(crate) fn normalize_legacy_timestamp(value: i64) -> i64 {
     if value.abs() < 10_000_000_000 {
         value.saturating_mul(1000)
@@ -31,14 +16,6 @@ pub(crate) fn now_millis() -> i64 {
         .unwrap_or(0)
 }
 
-pub(crate) fn join_remote_url(base_url: &str, remote_path: &str) -> String {
-    format!(
-        "{}/{}",
-        base_url.trim_end_matches('/'),
-        remote_path.trim_start_matches('/')
-    )
-}
-
 pub(crate) fn operation_is_newer(
     candidate: &FavoriteOperation,
     current: &FavoriteOperation,
@@ -1,12 +1,12 @@
 import React, { useEffect, useRef } from 'react';
 import { useTranslation } from 'react-i18next';
 import { doesFocusableExist, getCurrentFocusKey, setFocus } from '@noriginmedia/norigin-spatial-navigation';
-import { AlertTriangle, Loader2, LogIn, Server } from 'lucide-react';
+import { AlertTriangle, Loader2, LogIn, Server, User } from 'lucide-react';
 
 import { OreButton } from '../../../../ui/primitives/OreButton';
 import { OreInput } from '../../../../ui/primitives/OreInput';
 import { OreModal } from '../../../../ui/primitives/OreModal';
-import type { AuthlibFormState } from '../../hooks/useAuthlibAuth';
+import type { AuthlibFormState, AuthlibProfileOption } from '../../hooks/useAuthlibAuth';
 
 interface AuthlibAuthModalProps {
   isOpen: boolean;
@@ -17,6 +17,8 @@ interface AuthlibAuthModalProps {
   setAuthlibError: React.Dispatch<React.SetStateAction<string>>;
   isLoading: boolean;
   handleLogin: () => void;
+  profiles: AuthlibProfileOption[] | null;
+  onSelectProfile: (profileId: string) => void;
 }
 
 const API_INPUT_FOCUS_KEY = 'authlib-api-root';
@@ -24,6 +26,7 @@ const USER_INPUT_FOCUS_KEY = 'authlib-username';
 const PASSWORD_INPUT_FOCUS_KEY = 'authlib-password';
 const CANCEL_BUTTON_FOCUS_KEY = 'authlib-cancel';
 const CONFIRM_BUTTON_FOCUS_KEY = 'authlib-confirm';
+const profileFocusKey = (profileId: string) => `authlib-profile-${profileId}`;
 
 export const AuthlibAuthModal: React.FC<AuthlibAuthModalProps> = ({
   isOpen,
@@ -34,6 +37,8 @@ export const AuthlibAuthModal: React.FC<AuthlibAuthModalProps> = ({
   setAuthlibError,
   isLoading,
   handleLogin,
+  profiles,
+  onSelectProfile,
 }) => {
   const { t } = useTranslation();
   const lastFocusBeforeModalRef = useRef<string | null>(null);
@@ -79,6 +84,19 @@ export const AuthlibAuthModal: React.FC<AuthlibAuthModalProps> = ({
     return () => clearTimeout(timer);
   }, [isOpen]);
 
+  useEffect(() => {
+    if (!profiles?.length) return;
+
+    const timer = setTimeout(() => {
+      const firstKey = profileFocusKey(profiles[0].id);
+      if (doesFocusableExist(firstKey)) {
+        setFocus(firstKey);
+      }
+    }, 50);
+
+    return () => clearTimeout(timer);
+  }, [profiles]);
+
   const updateForm = (patch: Partial<AuthlibFormState>) => {
     setAuthlibError('');
     setAuthlibForm((prev) => ({ ...prev, ...patch }));
@@ -231,6 +249,25 @@ export const AuthlibAuthModal: React.FC<AuthlibAuthModalProps> = ({
           </div>
         )}
 
+        {profiles && profiles.length > 0 && (
+          <div className="mb-4 flex flex-col gap-2">
+            <p className="text-xs font-minecraft text-ore-text-muted">
+              {t('settings.account.authlib.selectProfile')}
+            </p>
+            {profiles.map((profile) => (
+              <OreButton
+                key={profile.id}
+                focusKey={profileFocusKey(profile.id)}
+                variant="secondary"
+                size="full"
+                disabled={isLoading}
+                onClick={() => onSelectProfile(profile.id)}
+              >
+                <User size={16} className="mr-2" /> {profile.name}
+              </OreButton>
+            ))}
+          </div>
+        )}
       </div>
     </OreModal>
   );
@@ -51,7 +51,9 @@ export const AccountSettings: React.FC = () => {
     isAuthlibLoading,
     openAuthlibLogin,
     closeAuthlibLogin,
-    handleAuthlibLogin
+    handleAuthlibLogin,
+    authlibProfiles,
+    selectAuthlibProfile
   } = useAuthlibAuth();
 
   const confirmDelete = () => {
@@ -187,6 +189,8 @@ export const AccountSettings: React.FC = () => {
           setAuthlibError={setAuthlibError}
           isLoading={isAuthlibLoading}
           handleLogin={handleAuthlibLogin}
+          profiles={authlibProfiles}
+          onSelectProfile={(profileId) => void selectAuthlibProfile(profileId)}
         />
       )}
 
@@ -2,7 +2,7 @@ import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 
 import i18n from '../../../ui/i18';
-import { useAccountStore, type MinecraftAccount } from '../../../store/useAccountStore';
+import { fromAccountSummary, useAccountStore, type AccountSummary } from '../../../store/useAccountStore';
 
 export interface AuthlibFormState {
   apiRoot: string;
@@ -10,6 +10,19 @@ export interface AuthlibFormState {
   password: string;
 }
 
+export interface AuthlibProfileOption {
+  id: string;
+  name: string;
+}
+
+// 后端认证成功后返回的待选角色，令牌留在后端，凭 ticket 完成登录
+interface AuthlibLoginChallenge {
+  ticket: string;
+  apiRoot: string;
+  profiles: AuthlibProfileOption[];
+  selectedProfileId?: string | null;
+}
+
 const initialForm: AuthlibFormState = {
   apiRoot: '',
   username: '',
@@ -22,15 +35,27 @@ export const useAuthlibAuth = () => {
   const [authlibForm, setAuthlibForm] = useState<AuthlibFormState>(initialForm);
   const [authlibError, setAuthlibError] = useState('');
   const [isAuthlibLoading, setIsAuthlibLoading] = useState(false);
+  const [authlibChallenge, setAuthlibChallenge] = useState<AuthlibLoginChallenge | null>(null);
 
   const openAuthlibLogin = () => {
     setAuthlibError('');
+    setAuthlibChallenge(null);
     setAuthlibForm((prev) => ({ ...prev, password: '' }));
     setIsAuthlibModalOpen(true);
   };
 
   const closeAuthlibLogin = () => {
     if (isAuthlibLoading) return;
+    setAuthlibChallenge(null);
+    setIsAuthlibModalOpen(false);
+  };
+
+  const finishAuthlibLogin = async (ticket: string, profileId: string) => {
+    const summary = await invoke<AccountSummary>('finish_authlib_login', { ticket, profileId });
+
+    addAccount(fromAccountSummary(summary));
+    setAuthlibChallenge(null);
+    setAuthlibForm((prev) => ({ ...prev, password: '' }));
     setIsAuthlibModalOpen(false);
   };
 
@@ -53,29 +78,41 @@ export const useAuthlibAuth = () => {
 
     setIsAuthlibLoading(true);
     try {
-      const rawAccount = await invoke<any>('login_authlib', {
+      // 登录在后端完成并写入账号库，这里只拿到角色列表和账号摘要
+      const challenge = await invoke<AuthlibLoginChallenge>('start_authlib_login', {
         apiRoot,
         username,
         password,
       });
 
-      const accountData: MinecraftAccount = {
-        uuid: rawAccount.uuid || rawAccount.id || rawAccount.profileId || '',
-        name: rawAccount.username || rawAccount.name || rawAccount.displayName || username,
-        type: 'authlib',
-        accessToken: rawAccount.access_token || rawAccount.accessToken || '',
-        refreshToken: rawAccount.refresh_token || rawAccount.refreshToken || null,
-        expiresAt: rawAccount.expires_at || rawAccount.expiresAt || null,
-        skinUrl: rawAccount.skin_url || rawAccount.skinUrl || null,
-        authlibApiRoot:
-          rawAccount.authlib_api_root || rawAccount.authlibApiRoot || apiRoot,
-      };
-
-      addAccount(accountData);
-      setAuthlibForm((prev) => ({ ...prev, password: '' }));
-      setIsAuthlibModalOpen(false);
-    } catch (error) {
-      setAuthlibError(String(error));
+      const profileId = challenge.selectedProfileId
+        ?? (challenge.profiles.length === 1 ? challenge.profiles[0].id : null);
+      if (profileId) {
+        await finishAuthlibLogin(challenge.ticket, profileId);
+      } else if (challenge.profiles.length === 0) {
+        setAuthlibError(i18n.t('settings.account.authlib.errors.noProfiles'));
+      } else {
+        // 多个角色且皮肤站未预选时交给用户选择
+        setAuthlibChallenge(challenge);
+      }
+    } catch (error: any) {
+      setAuthlibError(error?.message ?? String(error));
+    } finally {
+      setIsAuthlibLoading(false);
+    }
+  };
+
+  const selectAuthlibProfile = async (profileId: string) => {
+    if (!authlibChallenge || isAuthlibLoading) return;
+
+    setAuthlibError('');
+    setIsAuthlibLoading(true);
+    try {
+      await finishAuthlibLogin(authlibChallenge.ticket, profileId);
+    } catch (error: any) {
+      // 票据过期后需要重新输入密码
+      setAuthlibChallenge(null);
+      setAuthlibError(error?.message ?? String(error));
     } finally {
       setIsAuthlibLoading(false);
     }
@@ -92,5 +129,7 @@ export const useAuthlibAuth = () => {
     openAuthlibLogin,
     closeAuthlibLogin,
     handleAuthlibLogin,
+    authlibProfiles: authlibChallenge?.profiles ?? null,
+    selectAuthlibProfile,
   };
 };
@@ -3,7 +3,7 @@ import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { open as openShell } from '@tauri-apps/plugin-shell';
 import i18n from '../../../ui/i18';
-import { useAccountStore, type MinecraftAccount } from '../../../store/useAccountStore';
+import { fromAccountSummary, useAccountStore, type AccountSummary } from '../../../store/useAccountStore';
 
 export interface DeviceCodeInfo {
   user_code: string;
@@ -31,30 +31,18 @@ export const useMicrosoftAuth = () => {
       setIsLoading(false);
 
       setLoginStatusMsg(i18n.t('settings.account.microsoft.polling'));
-      const rawAccount = await invoke<any>('poll_and_exchange_microsoft_token', {
+      // 令牌由后端写入账号库，这里只拿到账号摘要
+      const summary = await invoke<AccountSummary>('poll_and_exchange_microsoft_token', {
         deviceCode: info.device_code,
         interval: info.interval
       });
 
-      // ✅ 核心排错：打印 Rust 后端传来的原始 JSON
-      console.log("[微软登录] Rust 返回的原始数据:", rawAccount);
-
-      // 极其贪婪的提取逻辑
-      const accountData: MinecraftAccount = {
-        uuid: rawAccount.uuid || rawAccount.id || rawAccount.profileId || '',
-        name: rawAccount.username || rawAccount.name || rawAccount.displayName || i18n.t('settings.account.card.unknownPlayer'),
-        type: 'microsoft', // 既然从这里登录，就强行锁定为 microsoft 类型
-        accessToken: rawAccount.access_token || rawAccount.accessToken || '',
-        refreshToken: rawAccount.refresh_token || rawAccount.refreshToken || null,
-        expiresAt: rawAccount.expires_at || rawAccount.expiresAt || null,
-        skinUrl: rawAccount.skin_url || rawAccount.skinUrl || null,
-        capeUrl: rawAccount.cape_url || rawAccount.capeUrl || null,
-      };
-
-      addAccount(accountData);
+      addAccount(fromAccountSummary(summary));
       setIsLoginModalOpen(false);
     } catch (err: any) {
-      setLoginStatusMsg(i18n.t('settings.account.microsoft.loginFailed', { error: String(err) }));
+      // 登录链在 Minecraft 阶段返回 { kind, message } 形式的类型化错误
+      const error = err?.message ?? String(err);
+      setLoginStatusMsg(i18n.t('settings.account.microsoft.loginFailed', { error }));
     }
   };
 
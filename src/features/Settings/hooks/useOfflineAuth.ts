@@ -4,10 +4,15 @@ import { invoke } from '@tauri-apps/api/core';
 import { open as openDialog } from '@tauri-apps/plugin-dialog';
 
 import i18n from '../../../ui/i18';
-import { useAccountStore, type MinecraftAccount } from '../../../store/useAccountStore';
+import {
+  fromAccountSummary,
+  useAccountStore,
+  type AccountSummary,
+  type MinecraftAccount,
+} from '../../../store/useAccountStore';
 
 export const useOfflineAuth = () => {
-  const { accounts, addAccount, updateAccount } = useAccountStore();
+  const { accounts, addAccount, updateAccount, removeAccount } = useAccountStore();
   const [isOfflineModalOpen, setIsOfflineModalOpen] = useState(false);
   const [offlineForm, setOfflineForm] = useState({ name: '', isEdit: false, oldUuid: '' });
   const [offlineError, setOfflineError] = useState('');
@@ -40,12 +45,15 @@ export const useOfflineAuth = () => {
     }
 
     try {
-      const generatedUuid = await invoke<string>('generate_offline_uuid', { name });
-      if (offlineForm.isEdit) {
-        updateAccount(offlineForm.oldUuid, { name, uuid: generatedUuid });
-      } else {
-        addAccount({ uuid: generatedUuid, name, type: 'offline', accessToken: 'offline_local_token' });
+      // 离线账号同样写入后端账号库，启动时按账号 id 取用
+      const summary = await invoke<AccountSummary>('add_account', {
+        request: { type: 'offline', username: name },
+      });
+      const generatedUuid = summary.id;
+      if (offlineForm.isEdit && offlineForm.oldUuid !== generatedUuid) {
+        removeAccount(offlineForm.oldUuid);
       }
+      addAccount(fromAccountSummary(summary, accounts.find((account) => account.uuid === generatedUuid)));
 
       setIsOfflineModalOpen(false);
 
@@ -58,8 +66,8 @@ export const useOfflineAuth = () => {
       } catch (error) {
         console.log('Offline skin fallback to default avatar.', error);
       }
-    } catch (error) {
-      setOfflineError(String(error));
+    } catch (error: any) {
+      setOfflineError(error?.message ?? String(error));
     }
   };
 
@@ -1,4 +1,4 @@
-import React, { useCallback, useEffect, useMemo, useRef, useState } from 'react';
+import React, { useCallback, useEffect, useMemo, useState } from 'react';
 import { invoke, convertFileSrc } from '@tauri-apps/api/core';
 import { AnimatePresence, motion } from 'motion/react';
 import {
@@ -33,11 +33,6 @@ interface JavaFriendStatus {
 
 interface JavaFriendsStatusPayload {
   friends: JavaFriendStatus[];
-  authUpdate: {
-    accessToken: string;
-    refreshToken: string;
-    expiresAt: number;
-  };
 }
 
 export interface DeviceInitInfo {
@@ -413,8 +408,6 @@ export const JavaFriendsAndLanPanel: React.FC<JavaFriendsAndLanPanelProps> = ({
   onRequestTrust,
   onTrustDevice,
 }) => {
-  const updateAccount = useAccountStore((state) => state.updateAccount);
-  const refreshTokenRef = useRef(account.refreshToken);
   const [javaFriends, setJavaFriends] = useState<JavaFriendStatus[]>([]);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -431,14 +424,9 @@ export const JavaFriendsAndLanPanel: React.FC<JavaFriendsAndLanPanelProps> = ({
     [trusted],
   );
 
-  useEffect(() => {
-    refreshTokenRef.current = account.refreshToken;
-  }, [account.refreshToken]);
-
   // Load Java Friends status
   const loadFriends = useCallback(async () => {
-    const refreshToken = refreshTokenRef.current;
-    if (!isPremium || !refreshToken) {
+    if (!isPremium || account.hasRefreshToken === false) {
       setJavaFriends([]);
       setError(null);
       return;
@@ -446,23 +434,19 @@ export const JavaFriendsAndLanPanel: React.FC<JavaFriendsAndLanPanelProps> = ({
 
     setIsLoading(true);
     try {
+      // 刷新令牌由后端账号库按账号 id 取用并续期
       const payload = await invoke<JavaFriendsStatusPayload>('get_java_friends_status', {
-        refreshToken,
+        accountId: account.uuid,
       });
 
       setJavaFriends(payload.friends);
       setError(null);
-      updateAccount(account.uuid, {
-        accessToken: payload.authUpdate.accessToken,
-        refreshToken: payload.authUpdate.refreshToken,
-        expiresAt: payload.authUpdate.expiresAt,
-      });
     } catch (err) {
       setError(String(err));
     } finally {
       setIsLoading(false);
     }
-  }, [account.uuid, isPremium, updateAccount]);
+  }, [account.hasRefreshToken, account.uuid, isPremium]);
 
   useEffect(() => {
     void loadFriends();
@@ -7,7 +7,12 @@
 
 import { useEffect, useRef, useCallback, useState } from 'react';
 import { invoke, convertFileSrc } from '@tauri-apps/api/core';
-import { useAccountStore } from '../../../store/useAccountStore';
+import {
+  fromAccountSummary,
+  useAccountStore,
+  type AccountSummary,
+  type MinecraftAccount,
+} from '../../../store/useAccountStore';
 import { useLauncherStore } from '../../../store/useLauncherStore';
 import { SkinEngine, type AnimationPreset } from '../engine/SkinEngine';
 
@@ -17,8 +22,7 @@ interface SkinViewerAccount {
   uuid?: string;
   name?: string;
   type?: string;
-  accessToken?: string;
-  refreshToken?: string | null;
+  hasRefreshToken?: boolean;
   expiresAt?: number | null;
   skinUrl?: string | null;
   capeUrl?: string | null;
@@ -145,18 +149,6 @@ const applyTimestamp = (url?: string | null) => {
   return cleanUrl ? `${cleanUrl}?t=${Date.now()}` : null;
 };
 
-const toAccountData = (rawAccount: Record<string, any>, fallback: SkinViewerAccount): SkinViewerAccount => ({
-  uuid: rawAccount.uuid || rawAccount.id || rawAccount.profileId || fallback.uuid,
-  name: rawAccount.username || rawAccount.name || rawAccount.displayName || fallback.name,
-  type: 'microsoft',
-  accessToken: rawAccount.access_token || rawAccount.accessToken || fallback.accessToken,
-  refreshToken: rawAccount.refresh_token || rawAccount.refreshToken || fallback.refreshToken || null,
-  expiresAt: rawAccount.expires_at || rawAccount.expiresAt || fallback.expiresAt || null,
-  skinUrl: rawAccount.skin_url || rawAccount.skinUrl || fallback.skinUrl || null,
-  capeUrl: rawAccount.cape_url || rawAccount.capeUrl || fallback.capeUrl || null,
-  metadata: rawAccount.metadata || fallback.metadata || null,
-});
-
 const isSessionExpiredError = (error: unknown): boolean => {
   const message = String(error);
   return message.includes('HTTP 401') || message.includes('会话已过期') || message.includes('浼氳瘽宸茶繃鏈');
@@ -166,37 +158,31 @@ const syncMicrosoftAppearance = async (
   account: SkinViewerAccount,
   updateAccount: (oldUuid: string, updates: Partial<SkinViewerAccount>) => void,
 ) => {
-  if (!account.uuid || !account.accessToken) return;
+  if (!account.uuid) return;
 
   const originalUuid = account.uuid;
   let accountForProfile = account;
   let profileUuid = account.uuid;
-  let profileAccessToken = account.accessToken;
 
-  const loadProfile = async (accessToken: string, accountUuid: string) =>
-    invoke<WardrobeProfile>('get_wardrobe_profile', {
-      accessToken,
-      accountUuid,
-    });
+  // 令牌由后端账号库按账号 id 取用
+  const loadProfile = async (accountId: string) =>
+    invoke<WardrobeProfile>('get_wardrobe_profile', { accountId });
 
   let profile: WardrobeProfile;
   try {
-    profile = await loadProfile(profileAccessToken, profileUuid);
+    profile = await loadProfile(profileUuid);
   } catch (error) {
-    if (!account.refreshToken || !isSessionExpiredError(error)) {
+    if (account.hasRefreshToken === false || !isSessionExpiredError(error)) {
       throw error;
     }
 
-    const rawAccount = await invoke<Record<string, any>>('refresh_microsoft_token', {
-      refreshToken: account.refreshToken,
+    const summary = await invoke<AccountSummary>('refresh_microsoft_token', {
+      id: originalUuid,
     });
-    accountForProfile = toAccountData(rawAccount, account);
-    if (!accountForProfile.uuid || !accountForProfile.accessToken) return;
-
-    profileUuid = accountForProfile.uuid;
-    profileAccessToken = accountForProfile.accessToken;
+    accountForProfile = fromAccountSummary(summary, account as MinecraftAccount);
+    profileUuid = summary.id;
     updateAccount(originalUuid, accountForProfile);
-    profile = await loadProfile(profileAccessToken, profileUuid);
+    profile = await loadProfile(profileUuid);
   }
 
   const activeSkin = findActiveSkin(profile);
@@ -316,8 +302,7 @@ export function useSkinViewer(visibleTab = 'home', options: UseSkinViewerOptions
     ? [
       currentAccount.uuid,
       currentAccount.type,
-      currentAccount.accessToken,
-      currentAccount.refreshToken,
+      currentAccount.expiresAt,
     ].join(':')
     : '';
 
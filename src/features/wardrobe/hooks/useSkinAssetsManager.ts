@@ -129,8 +129,7 @@ export function useSkinAssetsManager(options: UseSkinAssetsManagerOptions) {
           if (isMicrosoft) {
             const nextProfile = await runWithSessionRefresh(currentAccount, (accountForAction) =>
               invoke<WardrobeProfile>('update_active_wardrobe_skin_variant', {
-                accessToken: accountForAction.accessToken,
-                accountUuid: accountForAction.uuid,
+                accountId: accountForAction.uuid,
                 variant: nextModel,
               })
             );
@@ -167,8 +166,7 @@ export function useSkinAssetsManager(options: UseSkinAssetsManagerOptions) {
       if (isMicrosoft) {
         const nextProfile = await runWithSessionRefresh(currentAccount, (accountForAction) =>
           invoke<WardrobeProfile>('apply_wardrobe_skin', {
-            accessToken: accountForAction.accessToken,
-            accountUuid: accountForAction.uuid,
+            accountId: accountForAction.uuid,
             sourcePath: skinMenuAsset.filePath!,
             variant: skinMenuModel,
           })
@@ -261,14 +259,12 @@ export function useSkinAssetsManager(options: UseSkinAssetsManagerOptions) {
         const nextProfile = shouldClear
           ? await runWithSessionRefresh(currentAccount, (accountForAction) =>
             invoke<WardrobeProfile>('clear_active_cape', {
-              accessToken: accountForAction.accessToken,
-              accountUuid: accountForAction.uuid,
+              accountId: accountForAction.uuid,
             })
           )
           : await runWithSessionRefresh(currentAccount, (accountForAction) =>
             invoke<WardrobeProfile>('set_active_cape', {
-              accessToken: accountForAction.accessToken,
-              accountUuid: accountForAction.uuid,
+              accountId: accountForAction.uuid,
               capeId: capeMenuAsset.id,
             })
           );
@@ -1,13 +1,17 @@
 import { useCallback, useState, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 
-import { useAccountStore, type MinecraftAccount } from '../../../store/useAccountStore';
+import {
+  fromAccountSummary,
+  useAccountStore,
+  type AccountSummary,
+  type MinecraftAccount,
+} from '../../../store/useAccountStore';
 import { useWardrobeStore } from '../../../store/useWardrobeStore';
 import type { WardrobeProfile, WardrobeSkinLibrary } from '../types';
 import {
   isMicrosoftAccount,
   isSessionExpiredError,
-  toAccountData,
   resolveSkinModel,
   findActiveSkin,
   findActiveCape,
@@ -55,15 +59,15 @@ export function useWardrobeSession() {
 
   const refreshAccountSession = useCallback(
     async (account: MinecraftAccount) => {
-      if (!account.refreshToken) {
+      if (account.hasRefreshToken === false) {
         throw new Error('会话已过期，请重新登录微软账号');
       }
 
       setNotice('会话已过期，正在刷新登录状态');
-      const rawAccount = await invoke<Record<string, any>>('refresh_microsoft_token', {
-        refreshToken: account.refreshToken,
+      const summary = await invoke<AccountSummary>('refresh_microsoft_token', {
+        id: account.uuid,
       });
-      const refreshed = toAccountData(rawAccount, account);
+      const refreshed = fromAccountSummary(summary, account);
       updateAccount(account.uuid, refreshed);
       setNotice('账号会话已刷新');
       return refreshed;
@@ -94,8 +98,7 @@ export function useWardrobeSession() {
     async (account: MinecraftAccount) => {
       return runWithSessionRefresh(account, (accountForAction) =>
         invoke<WardrobeProfile>('get_wardrobe_profile', {
-          accessToken: accountForAction.accessToken,
-          accountUuid: accountForAction.uuid,
+          accountId: accountForAction.uuid,
         })
       );
     },
@@ -26,18 +26,6 @@ export const isSessionExpiredError = (error: unknown): boolean => {
 export const modelLabel = (model: WardrobeSkinModel): string =>
   model === 'slim' ? 'Slim 模型' : 'Classic 模型';
 
-export const toAccountData = (rawAccount: Record<string, any>, fallback: MinecraftAccount): MinecraftAccount => ({
-  uuid: rawAccount.uuid || rawAccount.id || rawAccount.profileId || fallback.uuid,
-  name: rawAccount.username || rawAccount.name || rawAccount.displayName || fallback.name,
-  type: 'microsoft',
-  accessToken: rawAccount.access_token || rawAccount.accessToken || fallback.accessToken,
-  refreshToken: rawAccount.refresh_token || rawAccount.refreshToken || fallback.refreshToken || null,
-  expiresAt: rawAccount.expires_at || rawAccount.expiresAt || fallback.expiresAt || null,
-  skinUrl: rawAccount.skin_url || rawAccount.skinUrl || fallback.skinUrl || null,
-  capeUrl: rawAccount.cape_url || rawAccount.capeUrl || fallback.capeUrl || null,
-  metadata: rawAccount.metadata || fallback.metadata || null,
-});
-
 export const validateSkinImage = (previewUrl: string): Promise<{ width: number; height: number }> =>
   new Promise((resolve, reject) => {
     const image = new Image();
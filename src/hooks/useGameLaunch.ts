@@ -26,7 +26,7 @@ export const useGameLaunch = () => {
 
       if (isLaunching) return;
 
-      const { accounts, activeAccountId, updateAccount } = useAccountStore.getState();
+      const { accounts, activeAccountId } = useAccountStore.getState();
       const currentAccount = accounts.find((a) => a.uuid === activeAccountId);
 
       if (!currentAccount) {
@@ -310,77 +310,11 @@ export const useGameLaunch = () => {
           }
         }
 
-        let mappedAccountType = 'offline';
-        if (currentAccount.type?.toLowerCase() === 'microsoft') {
-          mappedAccountType = 'microsoft';
-        } else if (currentAccount.type?.toLowerCase() === 'authlib') {
-          mappedAccountType = 'authlib';
-        }
-
-        let activeAccessToken = currentAccount.accessToken || '0';
-
-        if (mappedAccountType === 'microsoft' && currentAccount.refreshToken) {
-          const nowInSeconds = Math.floor(Date.now() / 1000);
-
-          if (!currentAccount.expiresAt || currentAccount.expiresAt < nowInSeconds + 3600) {
-            console.log('[Launch] 微软 Token 即将过期或已过期，正在后台执行静默刷新...');
-            logStore.addLogs([
-              '[INFO] 正在与微软验证服务器通信，静默续期登录状态...',
-            ]);
-
-            try {
-              const refreshedAccount = await invoke<any>('refresh_microsoft_token', {
-                refreshToken: currentAccount.refreshToken,
-              });
-
-              const newAccessToken =
-                refreshedAccount.access_token || refreshedAccount.accessToken;
-              const newRefreshToken =
-                refreshedAccount.refresh_token ||
-                refreshedAccount.refreshToken ||
-                currentAccount.refreshToken;
-              const newExpiresAt = refreshedAccount.expires_at || refreshedAccount.expiresAt;
-
-              updateAccount(currentAccount.uuid, {
-                accessToken: newAccessToken,
-                refreshToken: newRefreshToken,
-                expiresAt: newExpiresAt,
-              });
-
-              activeAccessToken = newAccessToken;
-              console.log('[Launch] 微软 Token 静默续期成功');
-              logStore.addLogs([
-                '[INFO] 微软登录状态续期成功，准备构建游戏参数...',
-              ]);
-            } catch (refreshErr) {
-              console.error('Token 刷新失败:', refreshErr);
-              logStore.addLogs([
-                '[ERROR] 微软账号授权已完全失效且无法自动续期。',
-              ]);
-              logStore.addLogs([
-                `[ERROR] 请前往【设置】删除该账号并重新扫码登录。详细错误: ${refreshErr}`,
-              ]);
-              logStore.setGameState('crashed');
-              setIsLaunching(false);
-              return;
-            }
-          }
-        }
-
+        // 令牌只在后端账号库中，微软令牌临近过期时由后端在启动前静默续期
         await invoke('launch_game', {
           instanceId,
           preLaunchCheckEnabled: false,
-          account: {
-            id: currentAccount.uuid,
-            accountType: mappedAccountType,
-            username: currentAccount.name,
-            uuid: currentAccount.uuid,
-            accessToken: activeAccessToken,
-            refreshToken: currentAccount.refreshToken || null,
-            expiresAt: currentAccount.expiresAt || null,
-            skinUrl: currentAccount.skinUrl || null,
-            authlibApiRoot: currentAccount.authlibApiRoot || null,
-          },
+          accountId: currentAccount.uuid,
         });
       } catch (error) {
         console.error('游戏启动失败:', error);
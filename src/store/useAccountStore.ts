@@ -1,18 +1,18 @@
 // src/store/useAccountStore.ts
+import { invoke } from '@tauri-apps/api/core';
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
 
+// 令牌只保存在后端加密账号库中，这里的 uuid 同时也是账号库里的账号 id
 export interface MinecraftAccount {
   uuid: string;
   name: string;
   // ✅ 将 type 改为支持任意字符串，为以后的 LittleSkin / 自建外置登录铺路
   // 顺手加上 'authlib' 的语法提示，对齐后端的枚举
-  type: 'microsoft' | 'offline' | 'authlib' | string; 
-  accessToken: string;
-  
-  // ✅ 核心修复：补齐以下三个可选字段，并允许其为 null，彻底消除 TS 报错！
-  refreshToken?: string | null;
+  type: 'microsoft' | 'offline' | 'authlib' | string;
+
   expiresAt?: number | null;
+  hasRefreshToken?: boolean;
   skinUrl?: string | null;
   capeUrl?: string | null;
   metadata?: {
@@ -21,6 +21,57 @@ export interface MinecraftAccount {
   authlibApiRoot?: string | null;
 }
 
+// 后端账号库返回的账号摘要，不含令牌
+export interface AccountSummary {
+  id: string;
+  accountType: 'microsoft' | 'offline' | 'authlib';
+  username: string;
+  uuid: string;
+  expiresAt?: number | null;
+  skinUrl?: string | null;
+  capeUrl?: string | null;
+  authlibApiRoot?: string | null;
+  hasRefreshToken: boolean;
+  selected: boolean;
+}
+
+// 旧版本把令牌持久化在前端，只在一次性迁移到后端账号库时读取
+type LegacyMinecraftAccount = MinecraftAccount & {
+  accessToken?: string;
+  refreshToken?: string | null;
+};
+
+const VAULT_ACCOUNT_TYPES = ['microsoft', 'offline', 'authlib'];
+
+// 皮肤、披风与模型是前端缓存的展示数据（离线皮肤是本地文件），同步时保留本地值
+export const fromAccountSummary = (
+  summary: AccountSummary,
+  local?: MinecraftAccount,
+): MinecraftAccount => ({
+  uuid: summary.id,
+  name: summary.username,
+  type: summary.accountType,
+  expiresAt: summary.expiresAt ?? null,
+  hasRefreshToken: summary.hasRefreshToken,
+  skinUrl: local?.skinUrl || summary.skinUrl || null,
+  capeUrl: local?.capeUrl || summary.capeUrl || null,
+  metadata: local?.metadata ?? null,
+  authlibApiRoot: summary.authlibApiRoot ?? local?.authlibApiRoot ?? null,
+});
+
+const toVaultAccount = (account: LegacyMinecraftAccount) => ({
+  id: account.uuid,
+  accountType: account.type.toLowerCase(),
+  username: account.name,
+  uuid: account.uuid,
+  accessToken: account.accessToken,
+  refreshToken: account.refreshToken || null,
+  expiresAt: account.expiresAt || null,
+  skinUrl: account.skinUrl || null,
+  capeUrl: account.capeUrl || null,
+  authlibApiRoot: account.authlibApiRoot || null,
+});
+
 interface AccountStore {
   accounts: MinecraftAccount[];
   activeAccountId: string | null;
@@ -32,8 +83,24 @@ interface AccountStore {
   updateAccount: (oldUuid: string, updates: Partial<MinecraftAccount>) => void; // ✅ 新增修改方法
   removeAccount: (uuid: string) => void;
   setActiveAccount: (uuid: string) => void;
+  syncAccounts: (summaries: AccountSummary[]) => void;
 }
 
+// 启动时与后端账号库对齐；本地还留有旧版令牌时先一次性导入，之后本地只保存摘要
+const syncAccountsWithVault = async (state: AccountStore) => {
+  const legacyAccounts = (state.accounts as LegacyMinecraftAccount[]).filter(
+    (account) =>
+      account.accessToken && VAULT_ACCOUNT_TYPES.includes(account.type?.toLowerCase()),
+  );
+  const summaries = legacyAccounts.length > 0
+    ? await invoke<AccountSummary[]>('import_frontend_accounts', {
+      accounts: legacyAccounts.map(toVaultAccount),
+      selectedId: state.activeAccountId,
+    })
+    : await invoke<AccountSummary[]>('list_accounts');
+  state.syncAccounts(summaries);
+};
+
 export const useAccountStore = create<AccountStore>()(
   persist(
     (set) => ({
@@ -43,24 +110,29 @@ export const useAccountStore = create<AccountStore>()(
       isHydrated: false,
       setHydrated: (state) => set({ isHydrated: state }),
       unlockThirdPartyAuth: () => set({ hasUnlockedThirdPartyAuth: true }),
-      
-      addAccount: (account) => set((state) => {
-        const exists = state.accounts.some(a => a.uuid === account.uuid);
-        const newAccounts = exists 
-          ? state.accounts.map(a => a.uuid === account.uuid ? account : a)
-          : [...state.accounts, account];
-        const isMicrosoft = account.type?.toLowerCase() === 'microsoft';
-          
-        return { 
-          accounts: newAccounts,
-          activeAccountId: account.uuid,
-          hasUnlockedThirdPartyAuth: state.hasUnlockedThirdPartyAuth || isMicrosoft
-        };
-      }),
+
+      addAccount: (account) => {
+        void invoke('select_account', { id: account.uuid }).catch((error) => {
+          console.error('切换账号库选中账号失败:', error);
+        });
+        set((state) => {
+          const exists = state.accounts.some(a => a.uuid === account.uuid);
+          const newAccounts = exists
+            ? state.accounts.map(a => a.uuid === account.uuid ? account : a)
+            : [...state.accounts, account];
+          const isMicrosoft = account.type?.toLowerCase() === 'microsoft';
+
+          return {
+            accounts: newAccounts,
+            activeAccountId: account.uuid,
+            hasUnlockedThirdPartyAuth: state.hasUnlockedThirdPartyAuth || isMicrosoft
+          };
+        });
+      },
 
       // ✅ 核心逻辑：修改账号时，如果 UUID 发了生改变，自动迁移活动状态
       updateAccount: (oldUuid, updates) => set((state) => {
-        const newAccounts = state.accounts.map(a => 
+        const newAccounts = state.accounts.map(a =>
           a.uuid === oldUuid ? { ...a, ...updates } : a
         );
         let newActiveId = state.activeAccountId;
@@ -70,25 +142,57 @@ export const useAccountStore = create<AccountStore>()(
         return { accounts: newAccounts, activeAccountId: newActiveId };
       }),
 
-      removeAccount: (uuid) => set((state) => {
-        const newAccounts = state.accounts.filter(a => a.uuid !== uuid);
+      removeAccount: (uuid) => {
+        void invoke('remove_account', { id: uuid }).catch((error) => {
+          console.error('从账号库移除账号失败:', error);
+        });
+        set((state) => {
+          const newAccounts = state.accounts.filter(a => a.uuid !== uuid);
+          return {
+            accounts: newAccounts,
+            activeAccountId: state.activeAccountId === uuid
+              ? (newAccounts[0]?.uuid || null)
+              : state.activeAccountId
+          };
+        });
+      },
+
+      setActiveAccount: (uuid) => {
+        void invoke('select_account', { id: uuid }).catch((error) => {
+          console.error('切换账号库选中账号失败:', error);
+        });
+        set({ activeAccountId: uuid });
+      },
+
+      syncAccounts: (summaries) => set((state) => {
+        const accounts = summaries.map((summary) =>
+          fromAccountSummary(summary, state.accounts.find(a => a.uuid === summary.id))
+        );
+        const selected = summaries.find((summary) => summary.selected)?.id;
+        const activeAccountId = selected
+          ?? (accounts.some(a => a.uuid === state.activeAccountId) ? state.activeAccountId : null)
+          ?? accounts[0]?.uuid
+          ?? null;
+        const hasMicrosoft = accounts.some(a => a.type?.toLowerCase() === 'microsoft');
         return {
-          accounts: newAccounts,
-          activeAccountId: state.activeAccountId === uuid 
-            ? (newAccounts[0]?.uuid || null) 
-            : state.activeAccountId
+          accounts,
+          activeAccountId,
+          hasUnlockedThirdPartyAuth: state.hasUnlockedThirdPartyAuth || hasMicrosoft
         };
       }),
-
-      setActiveAccount: (uuid) => set({ activeAccountId: uuid }),
     }),
-    { 
+    {
       name: 'pilauncher-accounts',
       onRehydrateStorage: () => (state) => {
         state?.setHydrated(true);
         if (state?.accounts.some((account) => account.type?.toLowerCase() === 'microsoft')) {
           state.unlockThirdPartyAuth();
         }
+        if (state) {
+          void syncAccountsWithVault(state).catch((error) => {
+            console.error('同步后端账号库失败:', error);
+          });
+        }
       }
     }
   )